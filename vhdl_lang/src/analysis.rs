@@ -9,15 +9,24 @@ mod analyze;
 mod assignment;
 mod association;
 mod concurrent;
+mod configuration;
+mod constant_folding;
 mod declarative;
 mod design_unit;
+mod design_units;
+mod drivers;
 mod expression;
+mod external_names;
+mod hierarchy;
+mod hover;
+mod instances;
 mod literals;
 mod lock;
 mod names;
 mod overloaded;
 mod package_instance;
 mod range;
+mod references;
 mod root;
 mod scope;
 mod semantic;
@@ -31,6 +40,14 @@ mod types;
 #[cfg(test)]
 pub(crate) mod tests;
 
+pub(crate) use constant_folding::{StaticRange, StaticValue};
 pub(crate) use root::{Library, LockedUnit};
+pub(crate) use sequential::{may_reach_end, may_wait};
+pub(crate) use static_expression::bit_string_to_string;
 
+pub use self::design_units::{DesignUnitSummary, LibraryUnits};
+pub use self::drivers::{AccessLevel, SignalAccess};
+pub use self::hierarchy::{HierarchyNode, HierarchyNodeKind, InstanceInfo};
+pub use self::instances::InstancePath;
+pub use self::references::ReferenceKind;
 pub use self::root::{DesignRoot, EntHierarchy};
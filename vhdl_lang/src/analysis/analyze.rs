@@ -11,8 +11,8 @@ use crate::data::error_codes::ErrorCode;
 use crate::data::*;
 use crate::named_entity::*;
 use crate::syntax::TokenAccess;
-use crate::TokenSpan;
-use fnv::FnvHashSet;
+use crate::{TokenId, TokenSpan, VHDLStandard};
+use fnv::{FnvHashMap, FnvHashSet};
 use std::cell::RefCell;
 use std::ops::Deref;
 
@@ -140,6 +140,8 @@ pub(super) struct AnalyzeContext<'a, 't> {
     std_sym: Symbol,
     standard_sym: Symbol,
     pub(super) is_std_logic_1164: bool,
+    /// The VHDL standard of the analyzed unit
+    pub(super) standard: VHDLStandard,
 
     // Record dependencies and sensitives when
     // analyzing design units
@@ -160,6 +162,12 @@ pub(super) struct AnalyzeContext<'a, 't> {
     uses: RefCell<FnvHashSet<UnitId>>,
     missing_unit: RefCell<FnvHashSet<(Symbol, Symbol, Option<Symbol>)>>,
     uses_library_all: RefCell<FnvHashSet<Symbol>>,
+    // The candidates of operators that could not be disambiguated
+    ambiguous_operators: RefCell<FnvHashMap<TokenId, Vec<EntityId>>>,
+    // The types of the expressions that could be resolved to a single type
+    expression_types: RefCell<FnvHashMap<TokenSpan, EntityId>>,
+    // The names of objects that were reported as illegally read
+    pub(super) illegal_reads: RefCell<FnvHashSet<TokenSpan>>,
     pub ctx: &'t dyn TokenAccess,
 }
 
@@ -170,6 +178,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         source: Source,
         arena: &'a Arena,
         ctx: &'t dyn TokenAccess,
+        standard: VHDLStandard,
     ) -> AnalyzeContext<'a, 't> {
         AnalyzeContext {
             work_sym: root.symbol_utf8("work"),
@@ -180,6 +189,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     &root.symbol_utf8("ieee"),
                     &root.symbol_utf8("std_logic_1164"),
                 ),
+            standard,
             root,
             current_unit: current_unit.clone(),
             source,
@@ -187,10 +197,48 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             uses: RefCell::new(FnvHashSet::default()),
             missing_unit: RefCell::new(FnvHashSet::default()),
             uses_library_all: RefCell::new(FnvHashSet::default()),
+            ambiguous_operators: RefCell::new(FnvHashMap::default()),
+            expression_types: RefCell::new(FnvHashMap::default()),
+            illegal_reads: RefCell::new(FnvHashSet::default()),
             ctx,
         }
     }
 
+    /// Record the candidates of an operator that could not be disambiguated
+    /// or forget them if the operator was disambiguated later on.
+    pub(super) fn set_ambiguous_operator(
+        &self,
+        token: TokenId,
+        candidates: Option<&[OverloadedEnt<'a>]>,
+    ) {
+        let mut ambiguous_operators = self.ambiguous_operators.borrow_mut();
+        if let Some(candidates) = candidates {
+            ambiguous_operators.insert(token, candidates.iter().map(|ent| ent.id()).collect());
+        } else {
+            ambiguous_operators.remove(&token);
+        }
+    }
+
+    pub(super) fn take_ambiguous_operators(&self) -> FnvHashMap<TokenId, Vec<EntityId>> {
+        self.ambiguous_operators.take()
+    }
+
+    /// Record the type of the expression at the span.
+    /// Expressions are analyzed again once the target type is known,
+    /// so a later call replaces the type that was inferred without it.
+    pub(super) fn set_expression_type(&self, span: TokenSpan, typ: TypeEnt<'a>) {
+        self.expression_types.borrow_mut().insert(span, typ.id());
+    }
+
+    pub(super) fn expression_type(&self, span: TokenSpan) -> Option<TypeEnt<'a>> {
+        let id = *self.expression_types.borrow().get(&span)?;
+        TypeEnt::from_any(self.arena.get(id))
+    }
+
+    pub(super) fn take_expression_types(&self) -> FnvHashMap<TokenSpan, EntityId> {
+        self.expression_types.take()
+    }
+
     pub fn work_library_name(&self) -> &Symbol {
         self.current_unit.library_name()
     }
@@ -317,7 +365,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         self.get_package_body().is_some()
     }
 
-    fn get_analysis(
+    pub(super) fn get_analysis(
         &self,
         use_pos: Option<&SrcPos>,
         unit: &'a LockedUnit,
@@ -337,7 +385,11 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         }
     }
 
-    fn get_primary_unit(&self, library_name: &Symbol, name: &Symbol) -> Option<&'a LockedUnit> {
+    pub(super) fn get_primary_unit(
+        &self,
+        library_name: &Symbol,
+        name: &Symbol,
+    ) -> Option<&'a LockedUnit> {
         let units = self.root.get_library_units(library_name)?;
         if let Some(unit) = units.get(&UnitKey::Primary(name.clone())) {
             return Some(unit);
@@ -346,7 +398,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         None
     }
 
-    fn get_secondary_unit(
+    pub(super) fn get_secondary_unit(
         &self,
         library_name: &Symbol,
         primary: &Symbol,
@@ -11,6 +11,7 @@ use crate::ast::token_range::WithTokenSpan;
 use crate::ast::*;
 use crate::data::*;
 use crate::named_entity::*;
+use fnv::FnvHashSet;
 
 impl<'a, 't> AnalyzeContext<'a, 't> {
     // @TODO maybe make generic function for expression/waveform.
@@ -47,7 +48,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     expression,
                     alternatives,
                 } = selection;
-                let ctyp = as_fatal(self.expr_unambiguous_type(scope, expression, diagnostics))?;
+                let ctyp = as_fatal(self.selector_type(scope, expression, diagnostics))?;
                 for Alternative {
                     choices,
                     item,
@@ -57,6 +58,11 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     self.analyze_expression_for_target(scope, ttyp, item, diagnostics)?;
                     self.choice_with_ttyp(scope, ctyp, choices, diagnostics)?;
                 }
+                self.check_others_choice(alternatives, diagnostics);
+                self.check_locally_static_choices(alternatives, diagnostics);
+                if let Some(ctyp) = ctyp {
+                    self.check_choice_coverage(ctyp, expression, alternatives, diagnostics);
+                }
             }
         }
         Ok(())
@@ -74,9 +80,22 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             AssignmentType::Signal,
             diagnostics,
         ))?;
+        let reject = if let Some(WithTokenSpan {
+            item:
+                DelayMechanism::Inertial {
+                    reject: Some(ref mut expr),
+                },
+            ..
+        }) = assignment.delay_mechanism
+        {
+            self.expr_with_ttyp(scope, self.time(), expr, diagnostics)?;
+            self.check_static_delay(expr, "Pulse rejection limit", diagnostics)
+        } else {
+            None
+        };
         match &mut assignment.rhs {
             AssignmentRightHand::Simple(wavf) => {
-                self.analyze_waveform(scope, ttyp, wavf, diagnostics)?;
+                self.analyze_waveform(scope, ttyp, wavf, reject, diagnostics)?;
             }
             AssignmentRightHand::Conditional(conditionals) => {
                 let Conditionals {
@@ -85,11 +104,11 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 } = conditionals;
                 for conditional in conditionals {
                     let Conditional { condition, item } = conditional;
-                    self.analyze_waveform(scope, ttyp, item, diagnostics)?;
+                    self.analyze_waveform(scope, ttyp, item, reject, diagnostics)?;
                     self.boolean_expr(scope, condition, diagnostics)?;
                 }
                 if let Some((wavf, _)) = else_item {
-                    self.analyze_waveform(scope, ttyp, wavf, diagnostics)?;
+                    self.analyze_waveform(scope, ttyp, wavf, reject, diagnostics)?;
                 }
             }
             AssignmentRightHand::Selected(selection) => {
@@ -97,36 +116,70 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     expression,
                     alternatives,
                 } = selection;
-                let ctyp = as_fatal(self.expr_unambiguous_type(scope, expression, diagnostics))?;
+                let ctyp = as_fatal(self.selector_type(scope, expression, diagnostics))?;
                 for Alternative {
                     choices,
                     item,
                     span: _,
                 } in alternatives.iter_mut()
                 {
-                    self.analyze_waveform(scope, ttyp, item, diagnostics)?;
+                    self.analyze_waveform(scope, ttyp, item, reject, diagnostics)?;
                     self.choice_with_ttyp(scope, ctyp, choices, diagnostics)?;
                 }
+                self.check_others_choice(alternatives, diagnostics);
+                self.check_locally_static_choices(alternatives, diagnostics);
+                if let Some(ctyp) = ctyp {
+                    self.check_choice_coverage(ctyp, expression, alternatives, diagnostics);
+                }
             }
         }
         Ok(())
     }
 
+    /// Analyze a waveform of a signal assignment.
+    /// `reject` is the statically known pulse rejection limit of the assignment, if any.
     fn analyze_waveform(
         &self,
         scope: &Scope<'a>,
         ttyp: Option<TypeEnt<'a>>,
         wavf: &mut Waveform,
+        reject: Option<StaticDelay>,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
         match wavf {
             Waveform::Elements(ref mut elems) => {
-                for elem in elems.iter_mut() {
+                // LRM 10.5.2.1: The time expressions of the waveform elements
+                // must be in ascending order
+                let mut previous: Option<StaticDelay> = None;
+                for (idx, elem) in elems.iter_mut().enumerate() {
                     let WaveformElement { value, after } = elem;
                     self.analyze_expression_for_target(scope, ttyp, value, diagnostics)?;
-                    if let Some(expr) = after {
-                        self.expr_with_ttyp(scope, self.time(), expr, diagnostics)?;
+                    let Some(expr) = after else {
+                        // An omitted after clause is equivalent to `after 0 ns`
+                        previous = (idx == 0).then_some(0.0);
+                        continue;
+                    };
+                    self.expr_with_ttyp(scope, self.time(), expr, diagnostics)?;
+                    let delay = self.check_static_delay(expr, "Delay", diagnostics);
+                    if let (Some(delay), Some(previous)) = (delay, previous) {
+                        if delay <= previous {
+                            diagnostics.add(
+                                expr.pos(self.ctx),
+                                "Delays of waveform elements must be in ascending order",
+                                ErrorCode::IllegalDelay,
+                            );
+                        }
                     }
+                    if let (0, Some(delay), Some(reject)) = (idx, delay, reject) {
+                        if reject > delay {
+                            diagnostics.add(
+                                expr.pos(self.ctx),
+                                "Pulse rejection limit must not be greater than the delay of the first waveform element",
+                                ErrorCode::IllegalDelay,
+                            );
+                        }
+                    }
+                    previous = delay;
                 }
             }
             Waveform::Unaffected(_) => {}
@@ -148,4 +201,357 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         }
         Ok(())
     }
+
+    /// LRM 10.9 Case statement
+    /// The selector must be of a discrete type
+    /// or of a one-dimensional array type whose element type is a character type.
+    fn selector_type(
+        &self,
+        scope: &Scope<'a>,
+        selector: &mut WithTokenSpan<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> EvalResult<TypeEnt<'a>> {
+        let ctyp = self.expr_unambiguous_type(scope, selector, diagnostics)?;
+        let is_legal = match ctyp.base().kind() {
+            Type::Array {
+                indexes, elem_type, ..
+            } => indexes.len() == 1 && is_character_type(*elem_type),
+            Type::Real | Type::Universal(UniversalType::Real) => false,
+            Type::Record(..) | Type::Access(..) | Type::Protected(..) | Type::File => false,
+            _ => true,
+        };
+        if is_legal {
+            Ok(ctyp)
+        } else {
+            diagnostics.add(
+                selector.pos(self.ctx),
+                format!(
+                    "Selector must be of a discrete type or a one-dimensional character array type, got {}",
+                    ctyp.describe()
+                ),
+                ErrorCode::TypeMismatch,
+            );
+            Err(EvalError::Unknown)
+        }
+    }
+
+    /// LRM 10.9 Case statement
+    /// Each value of the subtype of the selector must be represented once
+    /// and only once in the set of choices.
+    ///
+    /// Only enumeration selectors are checked, since the choices of other types
+    /// require the evaluation of static expressions.
+    /// Choices that are not plain enumeration literals, such as ranges or constants,
+    /// disable the check for missing choices.
+    pub(crate) fn check_choice_coverage<T>(
+        &self,
+        ctyp: TypeEnt<'a>,
+        selector: &WithTokenSpan<Expression>,
+        alternatives: &[Alternative<T>],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let Type::Enum(literals) = ctyp.base().kind() else {
+            return;
+        };
+        // Only the values of a constrained subtype must be covered
+        // but its range is not known without static evaluation
+        if ctyp.is_constrained_subtype() || self.has_constrained_subtype(selector) {
+            return;
+        }
+
+        let mut covered = FnvHashSet::default();
+        let mut has_others = false;
+        let mut is_complete = true;
+        for choice in alternatives.iter().flat_map(|alt| alt.choices.iter()) {
+            match &choice.item {
+                Choice::Expression(expr) => match self.enum_literal_choice(expr) {
+                    Some(literal) => {
+                        if !covered.insert(literal.clone()) {
+                            diagnostics.add(
+                                choice.pos(self.ctx),
+                                format!("Choice {literal} is already covered"),
+                                ErrorCode::DuplicateChoice,
+                            );
+                        }
+                    }
+                    None => is_complete = false,
+                },
+                Choice::DiscreteRange(_) => is_complete = false,
+                Choice::Others => has_others = true,
+            }
+        }
+
+        if has_others || !is_complete {
+            return;
+        }
+        let mut missing: Vec<String> = literals
+            .iter()
+            .filter(|literal| !covered.contains(*literal))
+            .map(|literal| literal.to_string())
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        missing.sort();
+        let plural = if missing.len() == 1 { "" } else { "s" };
+        let listed = if missing.len() > MAX_LISTED_CHOICES {
+            format!(
+                "{} and {} more",
+                missing[..MAX_LISTED_CHOICES].join(", "),
+                missing.len() - MAX_LISTED_CHOICES
+            )
+        } else {
+            missing.join(", ")
+        };
+        diagnostics.add(
+            selector.pos(self.ctx),
+            format!("Missing choice{plural} for {listed}"),
+            ErrorCode::MissingChoice,
+        );
+    }
+
+    /// Returns the value of a delay that is a, possibly negated, physical literal of type time.
+    /// Reports an error if the delay is negative.
+    fn check_static_delay(
+        &self,
+        expr: &WithTokenSpan<Expression>,
+        what: &str,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<StaticDelay> {
+        let delay = self.static_delay(&expr.item)?;
+        if delay < 0.0 {
+            diagnostics.add(
+                expr.pos(self.ctx),
+                format!("{what} must not be negative"),
+                ErrorCode::IllegalDelay,
+            );
+        }
+        Some(delay)
+    }
+
+    fn static_delay(&self, expr: &Expression) -> Option<StaticDelay> {
+        match expr {
+            Expression::Unary(op, operand) => match op.item.item {
+                Operator::Minus => self.static_delay(&operand.item).map(|delay| -delay),
+                Operator::Plus => self.static_delay(&operand.item),
+                _ => None,
+            },
+            Expression::Literal(Literal::Physical(PhysicalLiteral { value, unit })) => {
+                let unit = self.arena.get(unit.reference.get()?).as_actual();
+                match unit.kind() {
+                    AnyEntKind::PhysicalLiteral(typ) if typ.base_type() == self.time() => {}
+                    _ => return None,
+                }
+                let Designator::Identifier(unit_name) = unit.designator() else {
+                    return None;
+                };
+                let (_, scale) = TIME_UNITS
+                    .iter()
+                    .find(|(name, _)| unit_name.name_utf8().eq_ignore_ascii_case(name))?;
+                let value = match value {
+                    AbstractLiteral::Integer(value) => *value as StaticDelay,
+                    AbstractLiteral::Real(value) => *value,
+                };
+                Some(value * scale)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether the selector denotes an object that was declared
+    /// with a constrained subtype indication.
+    fn has_constrained_subtype(&self, selector: &WithTokenSpan<Expression>) -> bool {
+        let Expression::Name(name) = &selector.item else {
+            return false;
+        };
+        let reference = match name.as_ref() {
+            Name::Designator(designator) => &designator.reference,
+            Name::Selected(_, suffix) => &suffix.item.reference,
+            _ => return false,
+        };
+        let Some(id) = reference.get() else {
+            return false;
+        };
+        match self.arena.get(id).kind() {
+            AnyEntKind::Object(object) => object.subtype.is_constrained(),
+            AnyEntKind::ObjectAlias {
+                base_object,
+                type_mark,
+            } => {
+                base_object.object().subtype.is_constrained() || type_mark.is_constrained_subtype()
+            }
+            AnyEntKind::ElementDeclaration(subtype) => subtype.is_constrained(),
+            _ => false,
+        }
+    }
+
+    /// LRM 10.9 Case statement
+    /// The choice `others` is only allowed as the only choice of the last alternative.
+    pub(crate) fn check_others_choice<T>(
+        &self,
+        alternatives: &[Alternative<T>],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        for (idx, alternative) in alternatives.iter().enumerate() {
+            let is_last = idx + 1 == alternatives.len() && alternative.choices.len() == 1;
+            for choice in alternative.choices.iter() {
+                if matches!(choice.item, Choice::Others) && !is_last {
+                    diagnostics.add(
+                        choice.pos(self.ctx),
+                        "'others' must be the only choice of the last alternative",
+                        ErrorCode::MisplacedOthers,
+                    );
+                }
+            }
+        }
+    }
+
+    /// LRM 10.9 Case statement
+    /// The choices of a case statement must be locally static.
+    ///
+    /// Only names of objects that are never locally static are reported, such as signals,
+    /// variables, generics and deferred constants.
+    /// Constants are not reported since their value may be locally static.
+    pub(crate) fn check_locally_static_choices<T>(
+        &self,
+        alternatives: &[Alternative<T>],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        for choice in alternatives.iter().flat_map(|alt| alt.choices.iter()) {
+            let object = match &choice.item {
+                Choice::Expression(expr) => self.non_static_object(expr),
+                Choice::DiscreteRange(DiscreteRange::Range(crate::ast::Range::Range(
+                    constraint,
+                ))) => self
+                    .non_static_object(&constraint.left_expr.item)
+                    .or_else(|| self.non_static_object(&constraint.right_expr.item)),
+                Choice::DiscreteRange(_) | Choice::Others => None,
+            };
+            if let Some(object) = object {
+                diagnostics.add(
+                    choice.pos(self.ctx),
+                    format!("Choice must be locally static, got {}", object.describe()),
+                    ErrorCode::NonStaticChoice,
+                );
+            }
+        }
+    }
+
+    /// Returns the first object within `expr` that is never locally static.
+    /// Prefixes of attribute names are not considered,
+    /// since attributes such as `'length` may be locally static.
+    fn non_static_object(&self, expr: &Expression) -> Option<EntRef<'a>> {
+        match expr {
+            Expression::Binary(_, left, right) => self
+                .non_static_object(&left.item)
+                .or_else(|| self.non_static_object(&right.item)),
+            Expression::Unary(_, operand) | Expression::Parenthesized(operand) => {
+                self.non_static_object(&operand.item)
+            }
+            Expression::Qualified(qexpr) => self.non_static_object(&qexpr.expr.item),
+            Expression::Aggregate(assocs) => assocs.iter().find_map(|assoc| match &assoc.item {
+                ElementAssociation::Positional(expr) | ElementAssociation::Named(_, expr) => {
+                    self.non_static_object(&expr.item)
+                }
+            }),
+            Expression::Name(name) => self.non_static_object_of_name(name),
+            Expression::Literal(_) | Expression::New(_) => None,
+        }
+    }
+
+    fn non_static_object_of_name(&self, name: &Name) -> Option<EntRef<'a>> {
+        let reference =
+            match name {
+                Name::Designator(designator) => &designator.reference,
+                Name::Selected(prefix, suffix) => {
+                    if let Some(object) = self.non_static_object_of_name(&prefix.item) {
+                        return Some(object);
+                    }
+                    &suffix.item.reference
+                }
+                Name::Slice(prefix, _) | Name::SelectedAll(prefix) => {
+                    return self.non_static_object_of_name(&prefix.item)
+                }
+                Name::CallOrIndexed(fcall) => {
+                    return self
+                        .non_static_object_of_name(&fcall.name.item)
+                        .or_else(|| {
+                            fcall.parameters.items.iter().find_map(|param| {
+                                match &param.actual.item {
+                                    ActualPart::Expression(expr) => self.non_static_object(expr),
+                                    ActualPart::Open => None,
+                                }
+                            })
+                        })
+                }
+                Name::Attribute(_) | Name::External(_) => return None,
+            };
+        let ent = self.arena.get(reference.get()?);
+        let is_static = match ent.kind() {
+            AnyEntKind::Object(object) => {
+                object.class == ObjectClass::Constant && object.iface.is_none()
+            }
+            AnyEntKind::ObjectAlias { base_object, .. } => {
+                let object = base_object.object();
+                object.class == ObjectClass::Constant && object.iface.is_none()
+            }
+            AnyEntKind::DeferredConstant(_)
+            | AnyEntKind::File(_)
+            | AnyEntKind::InterfaceFile(_) => false,
+            _ => true,
+        };
+        if is_static {
+            None
+        } else {
+            Some(ent)
+        }
+    }
+
+    /// Returns the designator of the enumeration literal that a choice denotes.
+    fn enum_literal_choice(&self, expr: &Expression) -> Option<Designator> {
+        let reference = match expr {
+            Expression::Literal(Literal::Character(chr)) => {
+                return Some(Designator::Character(*chr))
+            }
+            Expression::Name(name) => match name.as_ref() {
+                Name::Designator(designator) => &designator.reference,
+                Name::Selected(_, suffix) => &suffix.item.reference,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let ent = self.arena.get(reference.get()?);
+        match ent.kind() {
+            AnyEntKind::Overloaded(Overloaded::EnumLiteral(_)) => Some(ent.designator().clone()),
+            _ => None,
+        }
+    }
+}
+
+/// A statically known delay in femtoseconds
+type StaticDelay = f64;
+
+/// The units of `std.standard.time` in femtoseconds
+const TIME_UNITS: [(&str, StaticDelay); 8] = [
+    ("fs", 1.0),
+    ("ps", 1e3),
+    ("ns", 1e6),
+    ("us", 1e9),
+    ("ms", 1e12),
+    ("sec", 1e15),
+    ("min", 60e15),
+    ("hr", 3600e15),
+];
+
+/// The maximum number of missing choices that are listed in a diagnostic
+const MAX_LISTED_CHOICES: usize = 4;
+
+/// A character type is an enumeration type with at least one character literal.
+fn is_character_type(typ: TypeEnt<'_>) -> bool {
+    match typ.base().kind() {
+        Type::Enum(literals) => literals
+            .iter()
+            .any(|literal| matches!(literal, Designator::Character(_))),
+        _ => false,
+    }
 }
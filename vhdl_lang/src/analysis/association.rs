@@ -4,10 +4,11 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use crate::analysis::names::ObjectName;
+use crate::analysis::names::{ObjectBase, ObjectName};
 use fnv::FnvHashMap;
 use itertools::Itertools;
 use vhdl_lang::TokenSpan;
+use vhdl_lang::VHDLStandard::VHDL2008;
 
 use super::analyze::*;
 use super::names::ResolvedName;
@@ -84,6 +85,16 @@ impl<'a> ResolvedFormal<'a> {
     }
 }
 
+/// LRM 6.5.6.3: Formals with a default value and ports of a mode other than in
+/// may be left unassociated or be associated with open,
+/// unless the port is of an unconstrained composite type
+fn may_be_unassociated(formal_region: &FormalRegion<'_>, formal: &InterfaceEnt<'_>) -> bool {
+    formal.has_default()
+        || (formal_region.typ == InterfaceType::Port
+            && formal.is_output_signal()
+            && !formal.is_unconstrained_composite())
+}
+
 impl<'a, 't> AnalyzeContext<'a, 't> {
     fn resolve_formal(
         &self,
@@ -422,12 +433,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         }
 
         for (idx, formal) in formal_region.iter().enumerate() {
-            if !(associated.contains_key(&idx)
-                // Default may be unconnected
-                || formal.has_default()
-                // Output ports are allowed to be unconnected
-                || (formal_region.typ == InterfaceType::Port && formal.is_out_or_inout_signal()))
-            {
+            if !(associated.contains_key(&idx) || may_be_unassociated(formal_region, &formal)) {
                 let diagnostic = Diagnostic::new(
                     error_pos,
                     format!("No association of {}", formal.describe()),
@@ -499,19 +505,63 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                                     actual.span,
                                     diagnostics,
                                 )?;
+                            } else if formal_region.typ == InterfaceType::Port {
+                                self.check_port_actual(
+                                    resolved_formal,
+                                    expr,
+                                    scope,
+                                    actual.span,
+                                    diagnostics,
+                                )?;
+                                if self.standard < VHDL2008 {
+                                    self.check_buffer_actual(
+                                        resolved_formal,
+                                        expr,
+                                        scope,
+                                        actual.span,
+                                        diagnostics,
+                                    )?;
+                                }
                             }
+                            let mut actual_diagnostics = Vec::new();
                             self.expr_pos_with_ttyp(
                                 scope,
                                 resolved_formal.type_mark,
                                 actual.span,
                                 expr,
-                                diagnostics,
+                                &mut actual_diagnostics,
                             )?;
+                            // The actual of a formal that is not read is not read either
+                            let actual_pos = actual.pos(self.ctx);
+                            actual_diagnostics.retain(|diag| {
+                                diag.code != ErrorCode::IllegalPortRead
+                                    || diag.pos != actual_pos
+                                    || reads_actual(&resolved_formal.iface)
+                            });
+                            diagnostics.append(actual_diagnostics);
                         } else {
                             self.expr_pos_unknown_ttyp(scope, actual.span, expr, diagnostics)?;
                         }
                     }
-                    ActualPart::Open => {}
+                    ActualPart::Open => {
+                        if let Some(resolved_formal) = resolved_formal {
+                            if !resolved_formal.is_partial
+                                && !may_be_unassociated(formal_region, &resolved_formal.iface)
+                            {
+                                diagnostics.push(
+                                    Diagnostic::new(
+                                        actual.pos(self.ctx),
+                                        format!(
+                                            "{} without a default value cannot be left open",
+                                            resolved_formal.iface.describe()
+                                        ),
+                                        ErrorCode::Unassociated,
+                                    )
+                                    .opt_related(resolved_formal.iface.decl_pos(), "Defined here"),
+                                );
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -540,15 +590,22 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     );
                     return Ok(());
                 };
-                if !matches!(name, ResolvedName::ObjectName(
-                                                        ObjectName { base, .. },
-                                                    ) if base.class() == ObjectClass::Signal)
-                {
-                    diagnostics.add(
+                match name {
+                    ResolvedName::ObjectName(ObjectName { base, .. })
+                        if base.class() == ObjectClass::Signal =>
+                    {
+                        self.check_actual_can_be_driven(
+                            resolved_formal,
+                            &base,
+                            actual_pos,
+                            diagnostics,
+                        );
+                    }
+                    _ => diagnostics.add(
                         actual_pos.pos(self.ctx),
                         "Name must denote a signal name",
                         ErrorCode::InterfaceModeMismatch,
-                    );
+                    ),
                 }
             }
             InterfaceClass::Variable => {
@@ -562,15 +619,23 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     );
                     return Ok(());
                 };
-                if !matches!(name, ResolvedName::ObjectName(
-                                                        ObjectName { base, .. },
-                                                    ) if base.class() == ObjectClass::Variable || base.class() == ObjectClass::SharedVariable)
-                {
-                    diagnostics.add(
+                match name {
+                    ResolvedName::ObjectName(ObjectName { base, .. })
+                        if base.class() == ObjectClass::Variable
+                            || base.class() == ObjectClass::SharedVariable =>
+                    {
+                        self.check_actual_can_be_driven(
+                            resolved_formal,
+                            &base,
+                            actual_pos,
+                            diagnostics,
+                        );
+                    }
+                    _ => diagnostics.add(
                         actual_pos.pos(self.ctx),
                         "Name must denote a variable name",
                         ErrorCode::InterfaceModeMismatch,
-                    );
+                    ),
                 }
             }
             InterfaceClass::File => {
@@ -600,6 +665,154 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         Ok(())
     }
 
+    // LRM 6.5.6.3: The actual of a port of mode out, inout or buffer must be a signal name,
+    // possibly converted by a function or type conversion, since it is driven by the port.
+    // Unresolved names are reported when the actual is type checked.
+    fn check_port_actual(
+        &self,
+        resolved_formal: &ResolvedFormal<'a>,
+        expr: &mut Expression,
+        scope: &Scope<'a>,
+        actual_pos: TokenSpan,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        if !resolved_formal.iface.is_output_signal() {
+            return Ok(());
+        }
+        let Expression::Name(name) = expr else {
+            diagnostics.add(
+                actual_pos.pos(self.ctx),
+                "Expression must be a name denoting a signal",
+                ErrorCode::InterfaceModeMismatch,
+            );
+            return Ok(());
+        };
+        let Some(resolved) =
+            as_fatal(self.name_resolve(scope, actual_pos, name, &mut NullDiagnostics))?
+        else {
+            return Ok(());
+        };
+        let (object_pos, resolved) = match resolved {
+            ResolvedName::ObjectName(_) => (actual_pos, resolved),
+            _ => {
+                let Name::CallOrIndexed(fcall) = name.as_mut() else {
+                    return self.not_a_signal_name(actual_pos, diagnostics);
+                };
+                let Some((arg_pos, arg)) =
+                    to_formal_conversion_argument(&mut fcall.parameters.items)
+                else {
+                    return self.not_a_signal_name(actual_pos, diagnostics);
+                };
+                let Some(resolved) =
+                    as_fatal(self.name_resolve(scope, arg_pos, arg, &mut NullDiagnostics))?
+                else {
+                    return Ok(());
+                };
+                (arg_pos, resolved)
+            }
+        };
+        match resolved {
+            ResolvedName::ObjectName(ObjectName { base, .. })
+                if base.class() == ObjectClass::Signal =>
+            {
+                self.check_actual_can_be_driven(resolved_formal, &base, object_pos, diagnostics);
+                Ok(())
+            }
+            _ => self.not_a_signal_name(object_pos, diagnostics),
+        }
+    }
+
+    // VHDL-1993 1.1.1.2: A port of mode buffer may only be associated with a formal port of mode
+    // in or buffer and a formal port of mode buffer only with an actual that is not a port
+    // of another mode. VHDL-2002 lifted these restrictions.
+    fn check_buffer_actual(
+        &self,
+        resolved_formal: &ResolvedFormal<'a>,
+        expr: &mut Expression,
+        scope: &Scope<'a>,
+        actual_pos: TokenSpan,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let AnyEntKind::Object(formal) = resolved_formal.iface.kind() else {
+            return Ok(());
+        };
+        let Some(InterfaceMode::Simple(formal_mode)) = formal.mode() else {
+            return Ok(());
+        };
+        let Expression::Name(name) = expr else {
+            return Ok(());
+        };
+        let Some(ResolvedName::ObjectName(ObjectName { base, .. })) =
+            as_fatal(self.name_resolve(scope, actual_pos, name, &mut NullDiagnostics))?
+        else {
+            return Ok(());
+        };
+        if !base.is_port() {
+            return Ok(());
+        }
+        let Some(InterfaceMode::Simple(actual_mode)) = base.mode() else {
+            return Ok(());
+        };
+        let is_legal = match (formal_mode, actual_mode) {
+            (Mode::Linkage, _) => true,
+            (Mode::Buffer, actual_mode) => *actual_mode == Mode::Buffer,
+            (formal_mode, Mode::Buffer) => *formal_mode == Mode::In,
+            _ => true,
+        };
+        if !is_legal {
+            diagnostics.add(
+                actual_pos.pos(self.ctx),
+                format!(
+                    "{} may not be associated with a formal of mode {formal_mode} before VHDL-2008",
+                    base.describe_class()
+                ),
+                ErrorCode::InterfaceModeMismatch,
+            );
+        }
+        Ok(())
+    }
+
+    fn not_a_signal_name(
+        &self,
+        pos: TokenSpan,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        diagnostics.add(
+            pos.pos(self.ctx),
+            "Name must denote a signal name",
+            ErrorCode::InterfaceModeMismatch,
+        );
+        Ok(())
+    }
+
+    // LRM 6.5.6.3: The actual of a formal of mode out, inout or buffer is updated
+    // and must therefore be an object that can be assigned to.
+    fn check_actual_can_be_driven(
+        &self,
+        resolved_formal: &ResolvedFormal<'a>,
+        actual: &ObjectBase<'_>,
+        actual_pos: TokenSpan,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let AnyEntKind::Object(formal) = resolved_formal.iface.kind() else {
+            return;
+        };
+        if let Some(mode @ InterfaceMode::Simple(Mode::Out | Mode::InOut | Mode::Buffer)) =
+            formal.mode()
+        {
+            if !actual.can_be_assigned_to() {
+                diagnostics.add(
+                    actual_pos.pos(self.ctx),
+                    format!(
+                        "{} may not be associated with a formal of mode {mode}",
+                        actual.describe_class()
+                    ),
+                    ErrorCode::InterfaceModeMismatch,
+                );
+            }
+        }
+    }
+
     fn expression_as_name(
         &self,
         expr: &mut Expression,
@@ -617,6 +830,17 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
     }
 }
 
+/// False for formals whose actual is updated but not read by the instance or subprogram
+fn reads_actual(formal: &InterfaceEnt<'_>) -> bool {
+    !matches!(
+        formal.kind(),
+        AnyEntKind::Object(object) if matches!(
+            object.mode(),
+            Some(InterfaceMode::Simple(Mode::Out | Mode::Buffer | Mode::Linkage))
+        )
+    )
+}
+
 fn to_formal_conversion_argument(
     parameters: &mut [AssociationElement],
 ) -> Option<(TokenSpan, &mut Box<Name>)> {
@@ -83,20 +83,53 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         let src_span = statement.span();
         match statement.statement.item {
             ConcurrentStatement::Block(ref mut block) => {
+                let nested = scope.nested();
                 if let Some(ref mut guard_condition) = block.guard_condition {
                     self.boolean_expr(scope, guard_condition, diagnostics)?;
+                    // LRM 11.2: A guarded block implicitly declares the signal GUARD
+                    nested.add(
+                        self.define_guard_signal(parent, guard_condition, src_span),
+                        diagnostics,
+                    );
                 }
-                let nested = scope.nested();
+                let error_pos = match statement.label.tree {
+                    Some(ref label) => label.pos(self.ctx).clone(),
+                    None => self.ctx.get_pos(src_span.start_token).clone(),
+                };
                 if let Some(ref mut list) = block.header.generic_clause {
-                    self.analyze_interface_list(&nested, parent, list, diagnostics)?;
-                }
-                if let Some(ref mut list) = block.header.generic_map {
+                    let generic_region =
+                        self.analyze_interface_list(&nested, parent, list, diagnostics)?;
+                    self.check_association(
+                        &error_pos,
+                        &generic_region,
+                        scope,
+                        block
+                            .header
+                            .generic_map
+                            .as_mut()
+                            .map(|it| it.list.items.as_mut_slice())
+                            .unwrap_or(&mut []),
+                        diagnostics,
+                    )?;
+                } else if let Some(ref mut list) = block.header.generic_map {
                     self.analyze_assoc_elems(scope, &mut list.list.items[..], diagnostics)?;
                 }
                 if let Some(ref mut list) = block.header.port_clause {
-                    self.analyze_interface_list(&nested, parent, list, diagnostics)?;
-                }
-                if let Some(ref mut list) = block.header.port_map {
+                    let port_region =
+                        self.analyze_interface_list(&nested, parent, list, diagnostics)?;
+                    self.check_association(
+                        &error_pos,
+                        &port_region,
+                        scope,
+                        block
+                            .header
+                            .port_map
+                            .as_mut()
+                            .map(|it| it.list.items.as_mut_slice())
+                            .unwrap_or(&mut []),
+                        diagnostics,
+                    )?;
+                } else if let Some(ref mut list) = block.header.port_map {
                     self.analyze_assoc_elems(scope, &mut list.list.items[..], diagnostics)?;
                 }
 
@@ -192,6 +225,10 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     let nested = scope.nested();
                     self.analyze_generate_body(&nested, parent, item, src_span, diagnostics)?;
                 }
+                self.check_others_choice(alternatives, diagnostics);
+                if let Some(ctyp) = ctyp {
+                    self.check_choice_coverage(ctyp, expression, alternatives, diagnostics);
+                }
             }
             ConcurrentStatement::Instance(ref mut instance) => {
                 self.analyze_instance(scope, instance, diagnostics)?;
@@ -267,6 +304,29 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         Ok(())
     }
 
+    /// Defines the implicit signal `GUARD` of a guarded block.
+    fn define_guard_signal(
+        &self,
+        parent: EntRef<'a>,
+        guard_condition: &WithTokenSpan<Expression>,
+        src_span: TokenSpan,
+    ) -> EntRef<'a> {
+        self.arena.alloc(
+            Designator::Identifier(self.root.symbol_utf8("guard")),
+            Some(parent),
+            Related::ImplicitOf(parent),
+            AnyEntKind::Object(Object {
+                class: ObjectClass::Signal,
+                iface: None,
+                subtype: Subtype::new(self.boolean()),
+                has_default: true,
+            }),
+            Some(guard_condition.pos(self.ctx)),
+            src_span,
+            Some(self.source()),
+        )
+    }
+
     fn analyze_instance(
         &self,
         scope: &Scope<'a>,
@@ -481,3 +541,38 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         Ok(())
     }
 }
+
+/// The bodies of a for, if or case generate statement, including all alternatives,
+/// or `None` if the statement is not a generate statement.
+pub(super) fn generate_bodies(statement: &ConcurrentStatement) -> Option<Vec<&GenerateBody>> {
+    match statement {
+        ConcurrentStatement::ForGenerate(generate) => Some(vec![&generate.body]),
+        ConcurrentStatement::IfGenerate(generate) => {
+            let Conditionals {
+                conditionals,
+                else_item,
+            } = &generate.conds;
+            Some(
+                conditionals
+                    .iter()
+                    .map(|conditional| &conditional.item)
+                    .chain(else_item.as_ref().map(|(body, _)| body))
+                    .collect(),
+            )
+        }
+        ConcurrentStatement::CaseGenerate(generate) => Some(
+            generate
+                .sels
+                .alternatives
+                .iter()
+                .map(|alternative| &alternative.item)
+                .collect(),
+        ),
+        ConcurrentStatement::ProcedureCall(_)
+        | ConcurrentStatement::Block(_)
+        | ConcurrentStatement::Process(_)
+        | ConcurrentStatement::Assert(_)
+        | ConcurrentStatement::Instance(_)
+        | ConcurrentStatement::Assignment(_) => None,
+    }
+}
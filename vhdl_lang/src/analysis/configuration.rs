@@ -0,0 +1,419 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::concurrent::generate_bodies;
+use super::*;
+use crate::analysis::names::ResolvedName;
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::named_entity::*;
+use analyze::*;
+use std::ops::Deref;
+
+impl<'a> AnalyzeContext<'a, '_> {
+    /// Analyze the block configuration of an architecture of `entity`,
+    /// such as the outermost block configuration of a configuration declaration.
+    pub(crate) fn analyze_block_configuration(
+        &self,
+        scope: &Scope<'a>,
+        entity: DesignEnt<'a>,
+        block: &mut BlockConfiguration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let (Some(library_name), Designator::Identifier(entity_name)) =
+            (entity.library_name(), entity.designator())
+        else {
+            return Ok(());
+        };
+        let pos = block.block_spec.pos(self.ctx);
+        let Name::Designator(ref mut designator) = block.block_spec.item else {
+            diagnostics.add(
+                pos,
+                format!(
+                    "Expected the name of an architecture of {}",
+                    entity.describe()
+                ),
+                ErrorCode::MismatchedKinds,
+            );
+            return Ok(());
+        };
+        let Designator::Identifier(architecture_name) = designator.item.clone() else {
+            diagnostics.add(
+                pos,
+                format!(
+                    "Expected the name of an architecture of {}",
+                    entity.describe()
+                ),
+                ErrorCode::MismatchedKinds,
+            );
+            return Ok(());
+        };
+        let Some(architecture) = as_fatal(self.get_architecture(
+            diagnostics,
+            library_name,
+            &pos,
+            entity_name,
+            &architecture_name,
+        ))?
+        else {
+            return Ok(());
+        };
+        designator.set_unique_reference(&architecture);
+
+        let Some(unit) = self.get_secondary_unit(library_name, entity_name, &architecture_name)
+        else {
+            return Ok(());
+        };
+        let data = self.get_analysis(Some(&pos), unit)?;
+        if let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(body)) = data.deref() {
+            let nested = scope.nested();
+            self.analyze_block_configuration_items(
+                &nested,
+                &[&body.statements],
+                block,
+                diagnostics,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Analyze the items of a block configuration,
+    /// where `statements` are the statements of the configured block.
+    fn analyze_block_configuration_items(
+        &self,
+        scope: &Scope<'a>,
+        statements: &[&[LabeledConcurrentStatement]],
+        block: &mut BlockConfiguration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        for use_clause in block.use_clauses.iter_mut() {
+            self.analyze_use_clause(scope, use_clause, diagnostics)?;
+        }
+
+        for item in block.items.iter_mut() {
+            match item {
+                ConfigurationItem::Block(block) => {
+                    let pos = block.block_spec.pos(self.ctx);
+                    let Some(label) = block_specification_label(&mut block.block_spec.item) else {
+                        diagnostics.add(
+                            pos,
+                            "Expected the label of a block or generate statement",
+                            ErrorCode::MismatchedKinds,
+                        );
+                        continue;
+                    };
+                    let Designator::Identifier(symbol) = &label.item else {
+                        continue;
+                    };
+                    let Some(statement) = find_labeled_statement(statements, symbol) else {
+                        diagnostics.add(
+                            pos,
+                            format!("No block or generate statement with label '{symbol}'"),
+                            ErrorCode::Unresolved,
+                        );
+                        continue;
+                    };
+                    let Some(nested_statements) = block_statements(statement) else {
+                        diagnostics.add(
+                            pos,
+                            format!("'{symbol}' is not a block or generate statement"),
+                            ErrorCode::MismatchedKinds,
+                        );
+                        continue;
+                    };
+                    if let Some(id) = statement.label.decl.get() {
+                        label.reference.set(id);
+                    }
+                    let nested = scope.nested();
+                    self.analyze_block_configuration_items(
+                        &nested,
+                        &nested_statements,
+                        block,
+                        diagnostics,
+                    )?;
+                }
+                ConfigurationItem::Component(config) => {
+                    self.analyze_component_configuration(scope, statements, config, diagnostics)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn analyze_component_configuration(
+        &self,
+        scope: &Scope<'a>,
+        statements: &[&[LabeledConcurrentStatement]],
+        config: &mut ComponentConfiguration,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let spec = &mut config.spec;
+        let Some(component_name) = suffix_designator(&spec.component_name.item).cloned() else {
+            return Ok(());
+        };
+
+        let mut component = None;
+        match spec.instantiation_list {
+            InstantiationList::Labels(ref mut labels) => {
+                for label in labels.iter_mut() {
+                    let Some(statement) = find_labeled_statement(statements, &label.item.item)
+                    else {
+                        diagnostics.add(
+                            label.item.pos(self.ctx),
+                            format!(
+                                "No component instantiation statement with label '{}'",
+                                label.item.item
+                            ),
+                            ErrorCode::Unresolved,
+                        );
+                        continue;
+                    };
+                    match instantiated_component(statement) {
+                        Some(Some(id)) => {
+                            let ent = self.arena.get(id);
+                            if ent.designator() != &component_name {
+                                diagnostics.add(
+                                    label.item.pos(self.ctx),
+                                    format!(
+                                        "'{}' is not an instance of component {}",
+                                        label.item.item,
+                                        component_name.describe()
+                                    ),
+                                    ErrorCode::MismatchedKinds,
+                                );
+                                continue;
+                            }
+                            component = Some(ent);
+                        }
+                        // The instantiated component could not be resolved
+                        Some(None) => {}
+                        None => {
+                            diagnostics.add(
+                                label.item.pos(self.ctx),
+                                format!(
+                                    "'{}' is not a component instantiation statement",
+                                    label.item.item
+                                ),
+                                ErrorCode::MismatchedKinds,
+                            );
+                            continue;
+                        }
+                    }
+                    if let Some(id) = statement.label.decl.get() {
+                        label.reference.set(id);
+                    }
+                }
+            }
+            InstantiationList::Others | InstantiationList::All => {
+                component = statements
+                    .iter()
+                    .flat_map(|statements| statements.iter())
+                    .filter_map(|statement| instantiated_component(statement).flatten())
+                    .map(|id| self.arena.get(id))
+                    .find(|ent| ent.designator() == &component_name);
+            }
+        }
+        if let Some(component) = component {
+            spec.component_name.set_unique_reference(component);
+        }
+
+        let Some(bind_ind) = &mut config.bind_ind else {
+            return Ok(());
+        };
+        let Some(entity) =
+            self.analyze_entity_aspect(scope, &mut bind_ind.entity_aspect, diagnostics)?
+        else {
+            return Ok(());
+        };
+        if let Some(block) = &mut config.block_config {
+            self.analyze_block_configuration(scope, entity, block, diagnostics)?;
+        }
+        Ok(())
+    }
+
+    /// Analyze the entity aspect of a binding indication.
+    /// Returns the bound entity if the entity aspect binds an entity.
+    fn analyze_entity_aspect(
+        &self,
+        scope: &Scope<'a>,
+        entity_aspect: &mut Option<EntityAspect>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult<Option<DesignEnt<'a>>> {
+        match entity_aspect {
+            Some(EntityAspect::Entity(entity_name, architecture_name)) => {
+                let Some(design) = self.resolve_design_name(scope, entity_name, diagnostics)?
+                else {
+                    return Ok(None);
+                };
+                if !matches!(design.kind(), Design::Entity(..)) {
+                    diagnostics.push(
+                        ResolvedName::Design(design)
+                            .kind_error(entity_name.suffix_pos().pos(self.ctx), "entity"),
+                    );
+                    return Ok(None);
+                }
+                if let (Some(library_name), Designator::Identifier(entity_ident), Some(name)) = (
+                    design.library_name(),
+                    design.designator(),
+                    architecture_name,
+                ) {
+                    let Some(architecture) = as_fatal(self.get_architecture(
+                        diagnostics,
+                        library_name,
+                        name.item.pos(self.ctx),
+                        entity_ident,
+                        &name.item.item,
+                    ))?
+                    else {
+                        return Ok(None);
+                    };
+                    name.set_unique_reference(&architecture);
+                }
+                Ok(Some(design))
+            }
+            Some(EntityAspect::Configuration(config_name)) => {
+                let Some(design) = self.resolve_design_name(scope, config_name, diagnostics)?
+                else {
+                    return Ok(None);
+                };
+                if !matches!(design.kind(), Design::Configuration) {
+                    diagnostics.push(
+                        ResolvedName::Design(design)
+                            .kind_error(config_name.suffix_pos().pos(self.ctx), "configuration"),
+                    );
+                }
+                Ok(None)
+            }
+            Some(EntityAspect::Open) | None => Ok(None),
+        }
+    }
+
+    /// Resolve the name of a design unit in a binding indication.
+    /// Simple names that are not visible denote units of the work library
+    /// and library names that are not visible are looked up among the libraries.
+    fn resolve_design_name(
+        &self,
+        scope: &Scope<'a>,
+        name: &mut WithTokenSpan<Name>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult<Option<DesignEnt<'a>>> {
+        let pos = name.span.pos(self.ctx);
+        let (library_name, designator) = match name.item {
+            Name::Designator(ref mut designator) if scope.lookup(&designator.item).is_err() => {
+                (self.work_library_name().clone(), designator)
+            }
+            Name::Selected(ref prefix, ref mut suffix) => match &prefix.item {
+                Name::Designator(WithRef {
+                    item: library @ Designator::Identifier(library_name),
+                    ..
+                }) if scope.lookup(library).is_err() => (library_name.clone(), &mut suffix.item),
+                _ => return self.resolve_visible_design_name(scope, name, diagnostics),
+            },
+            _ => return self.resolve_visible_design_name(scope, name, diagnostics),
+        };
+        let Some(design) =
+            as_fatal(self.lookup_in_library(diagnostics, &library_name, &pos, &designator.item))?
+        else {
+            return Ok(None);
+        };
+        designator.set_unique_reference(&design);
+        Ok(Some(design))
+    }
+
+    fn resolve_visible_design_name(
+        &self,
+        scope: &Scope<'a>,
+        name: &mut WithTokenSpan<Name>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult<Option<DesignEnt<'a>>> {
+        let Some(resolved) =
+            as_fatal(self.name_resolve(scope, name.span, &mut name.item, diagnostics))?
+        else {
+            return Ok(None);
+        };
+        match resolved {
+            ResolvedName::Design(design) => Ok(Some(design)),
+            other => {
+                diagnostics.push(other.kind_error(name.suffix_pos().pos(self.ctx), "design unit"));
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// The label of a block specification such as `blk`, `gen(1)` or `gen(0 to 3)`.
+fn block_specification_label(name: &mut Name) -> Option<&mut WithRef<Designator>> {
+    match name {
+        Name::Designator(designator) => Some(designator),
+        Name::CallOrIndexed(call) => match call.name.item {
+            Name::Designator(ref mut designator) => Some(designator),
+            _ => None,
+        },
+        Name::Slice(prefix, _) => match prefix.item {
+            Name::Designator(ref mut designator) => Some(designator),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn suffix_designator(name: &Name) -> Option<&Designator> {
+    match name {
+        Name::Designator(designator) => Some(&designator.item),
+        Name::Selected(_, suffix) => Some(&suffix.item.item),
+        _ => None,
+    }
+}
+
+fn find_labeled_statement<'s>(
+    statements: &[&'s [LabeledConcurrentStatement]],
+    label: &Symbol,
+) -> Option<&'s LabeledConcurrentStatement> {
+    statements
+        .iter()
+        .flat_map(|statements| statements.iter())
+        .find(|statement| {
+            statement
+                .label
+                .tree
+                .as_ref()
+                .is_some_and(|ident| &ident.item == label)
+        })
+}
+
+/// The statements within a block or generate statement,
+/// or `None` if the statement is neither.
+fn block_statements(
+    statement: &LabeledConcurrentStatement,
+) -> Option<Vec<&[LabeledConcurrentStatement]>> {
+    match &statement.statement.item {
+        ConcurrentStatement::Block(block) => Some(vec![&block.statements]),
+        statement => Some(
+            generate_bodies(statement)?
+                .into_iter()
+                .map(|body| body.statements.as_slice())
+                .collect(),
+        ),
+    }
+}
+
+/// The component of a component instantiation statement,
+/// `Some(None)` when the component could not be resolved
+/// and `None` if the statement does not instantiate a component.
+fn instantiated_component(statement: &LabeledConcurrentStatement) -> Option<Option<EntityId>> {
+    match &statement.statement.item {
+        ConcurrentStatement::Instance(instance) => match &instance.unit {
+            InstantiatedUnit::Component(name) => Some(name.item.get_suffix_reference()),
+            _ => None,
+        },
+        // A component instantiation without the component keyword and maps
+        // cannot be told apart from a procedure call
+        ConcurrentStatement::ProcedureCall(_) => Some(None),
+        _ => None,
+    }
+}
@@ -0,0 +1,702 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::root::DesignRoot;
+use crate::ast::search::{DeclarationItem, FoundDeclaration, SearchResult, SearchState, Searcher};
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::data::Latin1String;
+use crate::named_entity::*;
+use crate::TokenAccess;
+use fnv::FnvHashMap;
+use std::fmt::{Display, Formatter};
+
+/// Constants are resolved through at most this many other constants.
+/// This also protects against cyclic definitions in erroneous code.
+pub(super) const MAX_DEPTH: usize = 16;
+
+/// The values of the generics of an instance
+pub(super) type GenericValues = FnvHashMap<EntityId, StaticValue>;
+
+/// The value of a locally or globally static expression.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum StaticValue {
+    Integer(i64),
+    Real(f64),
+    Boolean(bool),
+    /// A literal of an enumeration type other than `boolean` and its position within the type
+    Enum {
+        designator: Designator,
+        pos: usize,
+    },
+    /// A value of a physical type expressed in the primary unit of the type
+    Physical {
+        value: i64,
+        unit: Designator,
+    },
+    String(Latin1String),
+}
+
+impl Display for StaticValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StaticValue::Integer(value) => write!(f, "{value}"),
+            // Always print a decimal point to make it a valid real literal
+            StaticValue::Real(value) => write!(f, "{value:?}"),
+            StaticValue::Boolean(value) => write!(f, "{value}"),
+            StaticValue::Enum { designator, .. } => write!(f, "{designator}"),
+            StaticValue::Physical { value, unit } => write!(f, "{value} {unit}"),
+            StaticValue::String(value) => {
+                write!(f, "\"{}\"", value.to_string().replace('"', "\"\""))
+            }
+        }
+    }
+}
+
+impl StaticValue {
+    fn unary(op: Operator, operand: StaticValue) -> Option<StaticValue> {
+        use StaticValue::*;
+        match (op, operand) {
+            (Operator::Plus, operand @ (Integer(_) | Real(_) | Physical { .. })) => Some(operand),
+            (Operator::Minus, Integer(value)) => value.checked_neg().map(Integer),
+            (Operator::Minus, Real(value)) => Some(Real(-value)),
+            (Operator::Minus, Physical { value, unit }) => Some(Physical {
+                value: value.checked_neg()?,
+                unit,
+            }),
+            (Operator::Abs, Integer(value)) => value.checked_abs().map(Integer),
+            (Operator::Abs, Real(value)) => Some(Real(value.abs())),
+            (Operator::Abs, Physical { value, unit }) => Some(Physical {
+                value: value.checked_abs()?,
+                unit,
+            }),
+            (Operator::Not, Boolean(value)) => Some(Boolean(!value)),
+            _ => None,
+        }
+    }
+
+    fn binary(op: Operator, left: StaticValue, right: StaticValue) -> Option<StaticValue> {
+        use StaticValue::*;
+        if let Some(ordering) = Self::relational(op, &left, &right) {
+            return Some(Boolean(ordering));
+        }
+        match (op, left, right) {
+            (Operator::Plus, Integer(left), Integer(right)) => left.checked_add(right).map(Integer),
+            (Operator::Minus, Integer(left), Integer(right)) => {
+                left.checked_sub(right).map(Integer)
+            }
+            (Operator::Times, Integer(left), Integer(right)) => {
+                left.checked_mul(right).map(Integer)
+            }
+            (Operator::Div, Integer(left), Integer(right)) => left.checked_div(right).map(Integer),
+            (Operator::Rem, Integer(left), Integer(right)) => left.checked_rem(right).map(Integer),
+            (Operator::Mod, Integer(left), Integer(right)) => {
+                // The result of mod has the sign of the right operand
+                let rem = left.checked_rem(right)?;
+                if rem != 0 && (rem < 0) != (right < 0) {
+                    rem.checked_add(right).map(Integer)
+                } else {
+                    Some(Integer(rem))
+                }
+            }
+            (Operator::Pow, Integer(left), Integer(right)) => {
+                left.checked_pow(u32::try_from(right).ok()?).map(Integer)
+            }
+            (Operator::Plus, Real(left), Real(right)) => Some(Real(left + right)),
+            (Operator::Minus, Real(left), Real(right)) => Some(Real(left - right)),
+            (Operator::Times, Real(left), Real(right)) => Some(Real(left * right)),
+            (Operator::Div, Real(left), Real(right)) if right != 0.0 => Some(Real(left / right)),
+            (Operator::Pow, Real(left), Integer(right)) => {
+                Some(Real(left.powi(i32::try_from(right).ok()?)))
+            }
+            (
+                Operator::Plus,
+                Physical { value: left, unit },
+                Physical {
+                    value: right,
+                    unit: right_unit,
+                },
+            ) if unit == right_unit => Some(Physical {
+                value: left.checked_add(right)?,
+                unit,
+            }),
+            (
+                Operator::Minus,
+                Physical { value: left, unit },
+                Physical {
+                    value: right,
+                    unit: right_unit,
+                },
+            ) if unit == right_unit => Some(Physical {
+                value: left.checked_sub(right)?,
+                unit,
+            }),
+            (Operator::Times, Physical { value, unit }, Integer(factor))
+            | (Operator::Times, Integer(factor), Physical { value, unit }) => Some(Physical {
+                value: value.checked_mul(factor)?,
+                unit,
+            }),
+            (Operator::Times, Physical { value, unit }, Real(factor))
+            | (Operator::Times, Real(factor), Physical { value, unit }) => Some(Physical {
+                value: (value as f64 * factor).round() as i64,
+                unit,
+            }),
+            (Operator::Div, Physical { value, unit }, Integer(divisor)) => Some(Physical {
+                value: value.checked_div(divisor)?,
+                unit,
+            }),
+            (
+                Operator::Div,
+                Physical { value: left, unit },
+                Physical {
+                    value: right,
+                    unit: right_unit,
+                },
+            ) if unit == right_unit => left.checked_div(right).map(Integer),
+            (Operator::And, Boolean(left), Boolean(right)) => Some(Boolean(left && right)),
+            (Operator::Or, Boolean(left), Boolean(right)) => Some(Boolean(left || right)),
+            (Operator::Nand, Boolean(left), Boolean(right)) => Some(Boolean(!(left && right))),
+            (Operator::Nor, Boolean(left), Boolean(right)) => Some(Boolean(!(left || right))),
+            (Operator::Xor, Boolean(left), Boolean(right)) => Some(Boolean(left != right)),
+            (Operator::Xnor, Boolean(left), Boolean(right)) => Some(Boolean(left == right)),
+            (Operator::Concat, String(mut left), String(mut right)) => {
+                left.append(&mut right);
+                Some(String(left))
+            }
+            _ => None,
+        }
+    }
+
+    /// The result of a relational operator applied to two values of the same type.
+    fn relational(op: Operator, left: &StaticValue, right: &StaticValue) -> Option<bool> {
+        use StaticValue::*;
+        if !matches!(
+            op,
+            Operator::EQ
+                | Operator::NE
+                | Operator::LT
+                | Operator::LTE
+                | Operator::GT
+                | Operator::GTE
+        ) {
+            return None;
+        }
+        let ordering = match (left, right) {
+            (Integer(left), Integer(right)) => left.partial_cmp(right),
+            (Real(left), Real(right)) => left.partial_cmp(right),
+            (Boolean(left), Boolean(right)) => left.partial_cmp(right),
+            (Enum { pos: left, .. }, Enum { pos: right, .. }) => left.partial_cmp(right),
+            (
+                Physical { value: left, unit },
+                Physical {
+                    value: right,
+                    unit: right_unit,
+                },
+            ) if unit == right_unit => left.partial_cmp(right),
+            (String(left), String(right)) => left.bytes.partial_cmp(&right.bytes),
+            _ => None,
+        }?;
+        match op {
+            Operator::EQ => Some(ordering.is_eq()),
+            Operator::NE => Some(ordering.is_ne()),
+            Operator::LT => Some(ordering.is_lt()),
+            Operator::LTE => Some(ordering.is_le()),
+            Operator::GT => Some(ordering.is_gt()),
+            _ => Some(ordering.is_ge()),
+        }
+    }
+}
+
+/// A range with statically known integer bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct StaticRange {
+    pub left: i64,
+    pub direction: Direction,
+    pub right: i64,
+}
+
+impl StaticRange {
+    pub fn low(&self) -> i64 {
+        match self.direction {
+            Direction::Ascending => self.left,
+            Direction::Descending => self.right,
+        }
+    }
+
+    pub fn high(&self) -> i64 {
+        match self.direction {
+            Direction::Ascending => self.right,
+            Direction::Descending => self.left,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.low() > self.high()
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.low() <= value && value <= self.high()
+    }
+
+    /// The number of values within the range, zero for a null range.
+    pub fn length(&self) -> i64 {
+        if self.is_null() {
+            0
+        } else {
+            self.high().saturating_sub(self.low()).saturating_add(1)
+        }
+    }
+}
+
+impl Display for StaticRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.left, self.direction, self.right)
+    }
+}
+
+/// The values of constants and enumeration literals and whether constants depend on generics.
+/// Computing them requires searching for the declaration, which is too slow to repeat
+/// for every reference.
+#[derive(Default)]
+pub(crate) struct ConstantCache {
+    values: FnvHashMap<EntityId, Option<StaticValue>>,
+    depends_on_generic: FnvHashMap<EntityId, bool>,
+}
+
+impl ConstantCache {
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.depends_on_generic.clear();
+    }
+}
+
+impl DesignRoot {
+    /// The value of the constant or enumeration literal `ent`, computed by `evaluate`
+    /// unless it is known already.
+    fn cached_value(
+        &self,
+        ent: EntRef<'_>,
+        evaluate: impl FnOnce() -> Option<StaticValue>,
+    ) -> Option<StaticValue> {
+        if let Some(value) = self.constants.read().values.get(&ent.id()) {
+            return value.clone();
+        }
+        let value = evaluate();
+        self.constants
+            .write()
+            .values
+            .insert(ent.id(), value.clone());
+        value
+    }
+
+    /// Calls `f` with the declaration of `ent`.
+    pub(super) fn with_declaration<R>(
+        &self,
+        ent: EntRef<'_>,
+        f: impl FnMut(&DeclarationItem<'_>) -> R,
+    ) -> Option<R> {
+        let source = ent.decl_pos()?.source.clone();
+        let mut searcher = WithDeclaration {
+            id: ent.id(),
+            f,
+            result: None,
+        };
+        let _ = self.search_source(&source, &mut searcher);
+        searcher.result
+    }
+
+    /// Evaluates a locally or globally static integer expression.
+    /// Returns `None` when the value cannot be computed.
+    pub(super) fn evaluate_integer(&self, expr: &Expression, depth: usize) -> Option<i64> {
+        match self.evaluate(expr, depth)? {
+            StaticValue::Integer(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Evaluates a locally or globally static expression of a scalar type or of type `string`.
+    /// Constants are replaced by their value and generics by their default value.
+    /// Returns `None` when the value cannot be computed.
+    pub(super) fn evaluate(&self, expr: &Expression, depth: usize) -> Option<StaticValue> {
+        self.evaluate_with(expr, &GenericValues::default(), depth)
+    }
+
+    /// Like [`Self::evaluate`], but generics in `generics` are replaced by the given values
+    /// instead of their default value, i.e. within an instance of an entity.
+    pub(super) fn evaluate_with(
+        &self,
+        expr: &Expression,
+        generics: &GenericValues,
+        depth: usize,
+    ) -> Option<StaticValue> {
+        match expr {
+            Expression::Literal(Literal::AbstractLiteral(AbstractLiteral::Integer(value))) => {
+                i64::try_from(*value).ok().map(StaticValue::Integer)
+            }
+            Expression::Literal(Literal::AbstractLiteral(AbstractLiteral::Real(value))) => {
+                Some(StaticValue::Real(*value))
+            }
+            Expression::Literal(Literal::Physical(literal)) => {
+                self.evaluate_physical_value(literal, depth)
+            }
+            Expression::Literal(Literal::String(value)) => Some(StaticValue::String(value.clone())),
+            Expression::Parenthesized(expr) => self.evaluate_with(&expr.item, generics, depth),
+            Expression::Unary(op, operand) => StaticValue::unary(
+                op.item.item,
+                self.evaluate_with(&operand.item, generics, depth)?,
+            ),
+            Expression::Binary(op, left, right) => StaticValue::binary(
+                op.item.item,
+                self.evaluate_with(&left.item, generics, depth)?,
+                self.evaluate_with(&right.item, generics, depth)?,
+            ),
+            Expression::Name(name) => {
+                let id = name.get_suffix_reference()?;
+                if let Some(value) = generics.get(&id) {
+                    return Some(value.clone());
+                }
+                let ent = self.get_ent(id);
+                if let AnyEntKind::Overloaded(Overloaded::EnumLiteral(_)) = ent.kind() {
+                    return self.cached_value(ent, || self.evaluate_enum_literal(ent));
+                }
+                if generics.is_empty() {
+                    self.cached_value(ent, || {
+                        self.evaluate_constant_with(ent, generics, depth + 1)
+                    })
+                } else {
+                    // The value of the constant may depend on the generics
+                    self.evaluate_constant_with(ent, generics, depth + 1)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluates a static expression of type `boolean` that does not depend on generics,
+    /// i.e. `false`, `not DEBUG` or `WIDTH > 8` where `DEBUG` and `WIDTH` are constants.
+    /// Returns `None` when the value cannot be computed.
+    pub(crate) fn evaluate_boolean(&self, expr: &Expression, depth: usize) -> Option<bool> {
+        match self.evaluate_static(expr, depth)? {
+            StaticValue::Boolean(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Evaluates a static expression that does not depend on generics,
+    /// i.e. whose value is the same within every instance.
+    pub(crate) fn evaluate_static(&self, expr: &Expression, depth: usize) -> Option<StaticValue> {
+        if self.depends_on_generic(expr, depth) {
+            return None;
+        }
+        self.evaluate(expr, depth)
+    }
+
+    /// Evaluates a range whose bounds are static integer expressions that do not depend on generics.
+    pub(crate) fn evaluate_static_range(&self, range: &Range, depth: usize) -> Option<StaticRange> {
+        let Range::Range(constraint) = range else {
+            return None;
+        };
+        let bound =
+            |expr: &WithTokenSpan<Expression>| match self.evaluate_static(&expr.item, depth)? {
+                StaticValue::Integer(value) => Some(value),
+                _ => None,
+            };
+        Some(StaticRange {
+            left: bound(&constraint.left_expr)?,
+            direction: constraint.direction,
+            right: bound(&constraint.right_expr)?,
+        })
+    }
+
+    /// The statically known index ranges of an array subtype indication.
+    /// Subtype indications without a constraint are resolved through the declaration
+    /// of a constrained subtype they refer to.
+    pub(crate) fn static_index_ranges(
+        &self,
+        subtype_indication: &SubtypeIndication,
+        depth: usize,
+    ) -> Option<Vec<StaticRange>> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let Some(constraint) = &subtype_indication.constraint else {
+            let type_mark = self.get_ent(subtype_indication.type_mark.item.get_suffix_reference()?);
+            let typ = TypeEnt::from_any(type_mark)?;
+            if matches!(typ.kind(), Type::Array { .. }) {
+                // A constrained array type definition
+                return self
+                    .with_declaration(type_mark, |decl| match decl {
+                        DeclarationItem::Type(TypeDeclaration {
+                            def: TypeDefinition::Array(indexes, ..),
+                            ..
+                        }) => indexes
+                            .iter()
+                            .map(|index| match index {
+                                ArrayIndex::Discrete(range) => {
+                                    self.evaluate_static_discrete_range(&range.item, depth)
+                                }
+                                ArrayIndex::IndexSubtypeDefintion(_) => None,
+                            })
+                            .collect(),
+                        _ => None,
+                    })
+                    .flatten();
+            }
+            if !typ.is_constrained_subtype() {
+                return None;
+            }
+            return self
+                .with_subtype_indication(type_mark, |subtype_indication| {
+                    self.static_index_ranges(subtype_indication, depth + 1)
+                })
+                .flatten();
+        };
+        let SubtypeConstraint::Array(ranges, _) = &constraint.item else {
+            return None;
+        };
+        ranges
+            .iter()
+            .map(|range| self.evaluate_static_discrete_range(&range.item, depth))
+            .collect()
+    }
+
+    /// Evaluates a discrete range given by an explicit range with static bounds.
+    pub(crate) fn evaluate_static_discrete_range(
+        &self,
+        range: &DiscreteRange,
+        depth: usize,
+    ) -> Option<StaticRange> {
+        match range {
+            DiscreteRange::Discrete(_, Some(range)) | DiscreteRange::Range(range) => {
+                self.evaluate_static_range(range, depth)
+            }
+            DiscreteRange::Discrete(_, None) => None,
+        }
+    }
+
+    /// The statically known index ranges of an array object.
+    pub(crate) fn static_index_ranges_of(&self, ent: EntRef<'_>) -> Option<Vec<StaticRange>> {
+        if !matches!(ent.kind(), AnyEntKind::Object(_)) {
+            return None;
+        }
+        self.with_subtype_indication(ent, |subtype_indication| {
+            self.static_index_ranges(subtype_indication, 0)
+        })
+        .flatten()
+    }
+
+    /// The value of an enumeration literal.
+    /// Literals of type `boolean` evaluate to [`StaticValue::Boolean`].
+    fn evaluate_enum_literal(&self, ent: EntRef<'_>) -> Option<StaticValue> {
+        let AnyEntKind::Overloaded(Overloaded::EnumLiteral(signature)) = ent.kind() else {
+            return None;
+        };
+        let typ = signature.return_type()?.base_type();
+        if self
+            .standard_types
+            .as_ref()
+            .is_some_and(|types| types.boolean == typ.id())
+        {
+            return Some(StaticValue::Boolean(
+                ent.designator() == &Designator::Identifier(self.symbol_utf8("true")),
+            ));
+        }
+        let pos = self
+            .with_declaration(typ.into(), |decl| match decl {
+                DeclarationItem::Type(TypeDeclaration {
+                    def: TypeDefinition::Enumeration(literals),
+                    ..
+                }) => literals
+                    .iter()
+                    .position(|literal| literal.decl.get() == Some(ent.id())),
+                _ => None,
+            })
+            .flatten()?;
+        Some(StaticValue::Enum {
+            designator: ent.designator().clone(),
+            pos,
+        })
+    }
+
+    /// True if the value of `expr` depends on a generic or another interface object,
+    /// either directly or through the value of a constant.
+    fn depends_on_generic(&self, expr: &Expression, depth: usize) -> bool {
+        if depth > MAX_DEPTH {
+            return true;
+        }
+        match expr {
+            Expression::Parenthesized(expr) => self.depends_on_generic(&expr.item, depth),
+            Expression::Unary(_, operand) => self.depends_on_generic(&operand.item, depth),
+            Expression::Binary(_, left, right) => {
+                self.depends_on_generic(&left.item, depth)
+                    || self.depends_on_generic(&right.item, depth)
+            }
+            Expression::Name(name) => {
+                let Some(id) = name.get_suffix_reference() else {
+                    return false;
+                };
+                let ent = self.get_ent(id);
+                match ent.kind() {
+                    AnyEntKind::Object(Object { iface: Some(_), .. }) => true,
+                    AnyEntKind::Object(Object {
+                        class: ObjectClass::Constant,
+                        ..
+                    }) => {
+                        if let Some(depends) = self.constants.read().depends_on_generic.get(&id) {
+                            return *depends;
+                        }
+                        // The value is evaluated as well to search for the declaration only once
+                        let (depends, value) = self
+                            .with_declaration(ent, |decl| match decl {
+                                DeclarationItem::Object(ObjectDeclaration {
+                                    expression: Some(expr),
+                                    ..
+                                }) => (
+                                    self.depends_on_generic(&expr.item, depth + 1),
+                                    self.evaluate(&expr.item, depth + 1),
+                                ),
+                                _ => (false, None),
+                            })
+                            .unwrap_or((false, None));
+                        let mut constants = self.constants.write();
+                        constants.depends_on_generic.insert(id, depends);
+                        constants.values.entry(id).or_insert(value);
+                        depends
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Evaluates the value of a constant or the default value of a generic.
+    pub(super) fn evaluate_constant(&self, ent: EntRef<'_>, depth: usize) -> Option<StaticValue> {
+        self.evaluate_constant_with(ent, &GenericValues::default(), depth)
+    }
+
+    /// Evaluates the default value of a generic or a port.
+    pub(crate) fn evaluate_default(&self, ent: EntRef<'_>, depth: usize) -> Option<StaticValue> {
+        let AnyEntKind::Object(Object { iface: Some(_), .. }) = ent.kind() else {
+            return None;
+        };
+        self.with_declaration(ent, |decl| {
+            let DeclarationItem::InterfaceObject(InterfaceObjectDeclaration {
+                mode: ModeIndication::Simple(mode),
+                ..
+            }) = decl
+            else {
+                return None;
+            };
+            self.evaluate(&mode.expression.as_ref()?.item, depth)
+        })
+        .flatten()
+    }
+
+    fn evaluate_constant_with(
+        &self,
+        ent: EntRef<'_>,
+        generics: &GenericValues,
+        depth: usize,
+    ) -> Option<StaticValue> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let AnyEntKind::Object(object) = ent.kind() else {
+            return None;
+        };
+        if object.class != ObjectClass::Constant {
+            return None;
+        }
+        self.with_declaration(ent, |decl| {
+            let expression = match decl {
+                DeclarationItem::Object(decl) => decl.expression.as_ref(),
+                DeclarationItem::InterfaceObject(InterfaceObjectDeclaration {
+                    mode: ModeIndication::Simple(mode),
+                    ..
+                }) => mode.expression.as_ref(),
+                _ => None,
+            }?;
+            self.evaluate_with(&expression.item, generics, depth)
+        })
+        .flatten()
+    }
+
+    /// The value of one `unit` of a physical type expressed in the primary unit of the type.
+    pub(super) fn physical_unit_factor(&self, unit: EntRef<'_>, depth: usize) -> Option<i64> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        self.with_declaration(unit, |decl| match decl {
+            DeclarationItem::PhysicalTypePrimary(_) => Some(1),
+            DeclarationItem::PhysicalTypeSecondary(_, literal) => {
+                self.evaluate_physical_literal(literal, depth + 1)
+            }
+            _ => None,
+        })
+        .flatten()
+    }
+
+    /// The value of a physical literal expressed in the primary unit of its type.
+    /// Real values are rounded to the nearest multiple of the primary unit.
+    pub(super) fn evaluate_physical_literal(
+        &self,
+        literal: &PhysicalLiteral,
+        depth: usize,
+    ) -> Option<i64> {
+        let unit = self.get_ent(literal.unit.reference.get()?).as_actual();
+        let factor = self.physical_unit_factor(unit, depth)?;
+        match literal.value {
+            AbstractLiteral::Integer(value) => i64::try_from(value).ok()?.checked_mul(factor),
+            AbstractLiteral::Real(value) => Some((value * factor as f64).round() as i64),
+        }
+    }
+
+    /// The value of a physical literal together with the primary unit of its type.
+    fn evaluate_physical_value(
+        &self,
+        literal: &PhysicalLiteral,
+        depth: usize,
+    ) -> Option<StaticValue> {
+        let AnyEntKind::PhysicalLiteral(typ) =
+            self.get_ent(literal.unit.reference.get()?).actual_kind()
+        else {
+            return None;
+        };
+        let unit = typ.implicits.iter().find(|unit| {
+            matches!(unit.kind(), AnyEntKind::PhysicalLiteral(_))
+                && self
+                    .with_declaration(unit, |decl| {
+                        matches!(decl, DeclarationItem::PhysicalTypePrimary(_))
+                    })
+                    .unwrap_or(false)
+        })?;
+        Some(StaticValue::Physical {
+            value: self.evaluate_physical_literal(literal, depth)?,
+            unit: unit.designator().clone(),
+        })
+    }
+}
+
+struct WithDeclaration<F, R> {
+    id: EntityId,
+    f: F,
+    result: Option<R>,
+}
+
+impl<F, R> Searcher for WithDeclaration<F, R>
+where
+    F: FnMut(&DeclarationItem<'_>) -> R,
+{
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        if decl.ent_id() == Some(self.id) {
+            self.result = Some((self.f)(&decl.ast));
+            SearchState::Finished(SearchResult::Found)
+        } else {
+            SearchState::NotFinished
+        }
+    }
+}
@@ -11,7 +11,9 @@ use crate::ast::*;
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
 use crate::named_entity::{Signature, *};
-use crate::{ast, named_entity, HasTokenSpan};
+use crate::syntax::{Kind, Token, TokenAccess, Value};
+use crate::VHDLStandard::VHDL2008;
+use crate::{ast, named_entity, HasTokenSpan, TokenId};
 use analyze::*;
 use fnv::FnvHashMap;
 use itertools::Itertools;
@@ -46,7 +48,8 @@ impl Declaration {
         use ObjectClass::*;
         match parent {
             // LRM: block_declarative_item
-            AnyEntKind::Design(Design::Architecture(..))
+            // PSL: The VHDL declarations of a verification unit are block declarative items
+            AnyEntKind::Design(Design::Architecture(..) | Design::VerificationUnit(..))
             | AnyEntKind::Concurrent(Some(Concurrent::Block | Concurrent::Generate)) => matches!(
                 self,
                 Object(ObjectDeclaration {
@@ -259,10 +262,12 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
 
         let resolved_name = self.name_resolve(scope, name.span, &mut name.item, diagnostics);
 
-        if let Some(ref mut subtype_indication) = subtype_indication {
+        let alias_subtype = if let Some(ref mut subtype_indication) = subtype_indication {
             // Object alias
-            self.analyze_subtype_indication(scope, subtype_indication, diagnostics)?;
-        }
+            as_fatal(self.resolve_subtype_indication(scope, subtype_indication, diagnostics))?
+        } else {
+            None
+        };
 
         let resolved_name = resolved_name?;
 
@@ -275,28 +280,48 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                             signature.pos(self.ctx),
                         ));
                     }
+                    let mut type_mark = oname.type_mark();
+                    if let Some(alias_subtype) = alias_subtype {
+                        // LRM 6.6.2: The base type of the subtype indication must be
+                        // the base type of the aliased object
+                        if alias_subtype.base_type() == type_mark.base_type() {
+                            type_mark = alias_subtype.type_mark();
+                        } else if let Some(subtype_indication) = subtype_indication {
+                            diagnostics.add(
+                                subtype_indication.type_mark.pos(self.ctx),
+                                format!(
+                                    "Subtype indication of alias does not have the same base type as {}",
+                                    oname.describe_type()
+                                ),
+                                ErrorCode::TypeMismatch,
+                            );
+                        }
+                    }
                     match oname.base {
                         ObjectBase::Object(base_object) => AnyEntKind::ObjectAlias {
                             base_object,
-                            type_mark: oname.type_mark(),
+                            type_mark,
                         },
                         ObjectBase::ObjectAlias(base_object, _) => AnyEntKind::ObjectAlias {
                             base_object,
-                            type_mark: oname.type_mark(),
-                        },
-                        ObjectBase::ExternalName(class) => AnyEntKind::ExternalAlias {
-                            class,
-                            type_mark: oname.type_mark(),
+                            type_mark,
                         },
-                        ObjectBase::DeferredConstant(_) => {
-                            // @TODO handle
-                            return Err(EvalError::Unknown);
+                        ObjectBase::ExternalName(class) => {
+                            AnyEntKind::ExternalAlias { class, type_mark }
                         }
+                        ObjectBase::DeferredConstant(ent) => AnyEntKind::Alias(ent),
                     }
                 }
-                ResolvedName::Library(_)
-                | ResolvedName::Design(_)
-                | ResolvedName::Expression(_) => {
+                ResolvedName::Design(design) => {
+                    if let Some(ref signature) = signature {
+                        diagnostics.push(Diagnostic::should_not_have_signature(
+                            "Alias",
+                            signature.pos(self.ctx),
+                        ));
+                    }
+                    AnyEntKind::Alias(design.into())
+                }
+                ResolvedName::Library(_) | ResolvedName::Expression(_) => {
                     if let Some(ref signature) = signature {
                         diagnostics.push(Diagnostic::should_not_have_signature(
                             "Alias",
@@ -343,11 +368,33 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     }
                 }
                 ResolvedName::Final(ent) => {
-                    if let Some(ent) = ViewEnt::from_any(ent) {
-                        AnyEntKind::View(*ent.subtype())
-                    } else {
-                        // @TODO some of these can probably be aliased
-                        return Err(EvalError::Unknown);
+                    if let Some(ref signature) = signature {
+                        diagnostics.push(Diagnostic::should_not_have_signature(
+                            "Alias",
+                            signature.pos(self.ctx),
+                        ));
+                    }
+                    match ent.kind() {
+                        AnyEntKind::View(subtype) => AnyEntKind::View(*subtype),
+                        // LRM 6.6.3: Labels, loop parameters and generate parameters
+                        // cannot be aliased
+                        AnyEntKind::Concurrent(_) | AnyEntKind::Sequential(_) => {
+                            diagnostics.add(
+                                name.pos(self.ctx),
+                                "Labels cannot be aliased",
+                                ErrorCode::MismatchedKinds,
+                            );
+                            return Err(EvalError::Unknown);
+                        }
+                        AnyEntKind::LoopParameter(_) => {
+                            diagnostics.add(
+                                name.pos(self.ctx),
+                                "Loop parameters cannot be aliased",
+                                ErrorCode::MismatchedKinds,
+                            );
+                            return Err(EvalError::Unknown);
+                        }
+                        _ => AnyEntKind::Alias(ent),
                     }
                 }
             }
@@ -424,6 +471,13 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 }
 
                 if let Some(subtype) = as_fatal(subtype)? {
+                    if object_decl.class == ObjectClass::SharedVariable {
+                        self.check_shared_variable_type(
+                            subtype,
+                            &object_decl.subtype_indication,
+                            diagnostics,
+                        );
+                    }
                     for ident in &mut object_decl.idents {
                         let kind = if object_decl.class == ObjectClass::Constant
                             && object_decl.expression.is_none()
@@ -444,6 +498,16 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                         } else {
                             None
                         };
+                        if let (Some(deferred), Some(expr)) = (declared_by, &object_decl.expression)
+                        {
+                            self.check_deferred_constant_conformance(
+                                deferred,
+                                subtype,
+                                object_decl.colon_token,
+                                expr.span,
+                                diagnostics,
+                            );
+                        }
                         let object_ent = self.arena.alloc(
                             ident.tree.item.clone().into(),
                             Some(parent),
@@ -479,13 +543,14 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 ))?;
 
                 if let Some((_, ref mut expr)) = open_info {
-                    self.expr_unknown_ttyp(scope, expr, diagnostics)?;
+                    self.expr_with_ttyp(scope, self.file_open_kind(), expr, diagnostics)?;
                 }
                 if let Some((_, ref mut expr)) = file_name {
-                    self.expr_unknown_ttyp(scope, expr, diagnostics)?;
+                    self.expr_with_ttyp(scope, self.string(), expr, diagnostics)?;
                 }
 
                 if let Some(subtype) = subtype {
+                    self.check_file_type(subtype_indication, subtype, diagnostics);
                     for ident in idents {
                         scope.add(
                             self.define(ident, parent, AnyEntKind::File(subtype), src_span),
@@ -686,6 +751,60 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         Ok(self.define(&mut view.ident, parent, AnyEntKind::View(typ), src_span))
     }
 
+    /// Since VHDL-2008, shared variables must be of a protected type (LRM 6.4.2.4).
+    /// Earlier standards allow any type, so only a portability warning is emitted.
+    fn check_shared_variable_type(
+        &self,
+        subtype: Subtype<'a>,
+        subtype_indication: &SubtypeIndication,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if matches!(
+            subtype.base_type().kind(),
+            named_entity::Type::Protected(..)
+                | named_entity::Type::Interface
+                | named_entity::Type::Incomplete
+        ) {
+            return;
+        }
+        let pos = subtype_indication.type_mark.pos(self.ctx);
+        if self.standard >= VHDL2008 {
+            diagnostics.add(
+                pos,
+                "The type of a shared variable must be a protected type",
+                ErrorCode::NonProtectedSharedVariable,
+            );
+        } else {
+            diagnostics.add(
+                pos,
+                "Shared variable of non-protected type is not allowed in VHDL-2008 and later",
+                ErrorCode::UnportableSharedVariable,
+            );
+        }
+    }
+
+    /// LRM 6.4.2.5 The subtype indication of a file declaration must denote a file type
+    fn check_file_type(
+        &self,
+        subtype_indication: &SubtypeIndication,
+        subtype: Subtype<'a>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if !matches!(
+            subtype.base_type().kind(),
+            named_entity::Type::File | named_entity::Type::Interface
+        ) {
+            diagnostics.add(
+                subtype_indication.type_mark.pos(self.ctx),
+                format!(
+                    "The type of a file must be a file type, got {}",
+                    subtype.type_mark().describe()
+                ),
+                ErrorCode::TypeMismatch,
+            );
+        }
+    }
+
     fn find_deferred_constant_declaration(
         &self,
         scope: &Scope<'a>,
@@ -699,6 +818,65 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         None
     }
 
+    /// The subtype indication of the full declaration of a deferred constant
+    /// must conform to the subtype indication of the deferred constant declaration (LRM 4.8).
+    /// The type marks must denote the same subtype and constraints must be lexically identical.
+    fn check_deferred_constant_conformance(
+        &self,
+        deferred: EntRef<'a>,
+        subtype: Subtype<'a>,
+        colon_token: TokenId,
+        expr_span: TokenSpan,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let AnyEntKind::DeferredConstant(deferred_subtype) = deferred.kind() else {
+            return;
+        };
+        // The subtype indication is located between the colon and the `:=` preceding the expression
+        let (start, end) = (colon_token + 1, expr_span.start_token - 2);
+        let full_tokens = self.ctx.get_token_slice(start, end);
+
+        let conforms = if deferred_subtype.type_mark().id() != subtype.type_mark().id() {
+            false
+        } else if deferred_subtype.constrained || subtype.constrained {
+            self.deferred_subtype_indication(deferred)
+                .is_none_or(|deferred_tokens| tokens_conform(deferred_tokens, full_tokens))
+        } else {
+            true
+        };
+
+        if !conforms {
+            diagnostics.push(
+                Diagnostic::new(
+                    self.ctx.get_span(start, end),
+                    format!(
+                        "Subtype indication of constant '{}' does not conform to the deferred constant declaration",
+                        deferred.designator()
+                    ),
+                    ErrorCode::DeferredConstantMismatch,
+                )
+                .opt_related(deferred.decl_pos(), "Deferred constant declared here"),
+            );
+        }
+    }
+
+    /// The tokens of the subtype indication of a deferred constant declared in the package
+    /// of the package body that is currently analyzed
+    fn deferred_subtype_indication(&self, deferred: EntRef<'a>) -> Option<&'a [Token]> {
+        let unit = self.get_primary_unit(
+            self.current_unit_id().library_name(),
+            self.current_unit_id().primary_name(),
+        )?;
+        let tokens = unit
+            .tokens
+            .get_token_slice(deferred.src_span.start_token, deferred.src_span.end_token);
+        let colon = tokens.iter().position(|token| token.kind == Kind::Colon)?;
+        let semi_colon = tokens
+            .iter()
+            .rposition(|token| token.kind == Kind::SemiColon)?;
+        tokens.get(colon + 1..semi_colon)
+    }
+
     fn attribute_specification(
         &self,
         scope: &Scope<'a>,
@@ -879,6 +1057,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     &mut file_decl.subtype_indication,
                     diagnostics,
                 )?;
+                self.check_file_type(&file_decl.subtype_indication, file_type, diagnostics);
                 file_decl
                     .idents
                     .iter_mut()
@@ -1209,6 +1388,8 @@ fn get_entity_class(ent: EntRef<'_>) -> Option<EntityClass> {
         AnyEntKind::ExternalAlias { .. } => None,
         // Alias is never the direct target of attribute
         AnyEntKind::ObjectAlias { .. } => None,
+        // Alias is never the direct target of attribute
+        AnyEntKind::Alias(_) => None,
         AnyEntKind::File(_) => Some(EntityClass::File),
         AnyEntKind::InterfaceFile(_) => Some(EntityClass::File),
         AnyEntKind::Component(_) => Some(EntityClass::Component),
@@ -1255,6 +1436,7 @@ fn get_entity_class(ent: EntRef<'_>) -> Option<EntityClass> {
             Design::PackageInstance(_) => None,
             Design::InterfacePackageInstance(_) => None,
             Design::Context(_) => None,
+            Design::VerificationUnit(..) => None,
         },
         AnyEntKind::View(_) => None,
     }
@@ -1323,3 +1505,16 @@ fn pretty_format_unassociated_message(unassociated: &HashSet<&RecordElement<'_>>
         format!("Missing association of elements {}", description)
     }
 }
+
+/// Whether two token sequences are lexically identical,
+/// where abstract literals conform when they have the same value
+fn tokens_conform(left: &[Token], right: &[Token]) -> bool {
+    left.len() == right.len()
+        && left.iter().zip(right).all(|(l, r)| {
+            l.kind == r.kind
+                && match (&l.value, &r.value) {
+                    (Value::AbstractLiteral(_, l), Value::AbstractLiteral(_, r)) => l == r,
+                    (l, r) => l == r,
+                }
+        })
+}
@@ -28,6 +28,9 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 self.analyze_package_instance(unit, diagnostics)
             }
             AnyPrimaryUnit::Context(unit) => self.analyze_context(unit, diagnostics),
+            AnyPrimaryUnit::VerificationUnit(unit) => {
+                self.analyze_verification_unit(unit, diagnostics)
+            }
         }
     }
 
@@ -118,6 +121,12 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     );
                 }
             }
+            self.analyze_block_configuration(
+                &root_region,
+                named_entity,
+                &mut unit.block_config,
+                diagnostics,
+            )?;
         };
 
         self.define(
@@ -301,6 +310,117 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         Ok(())
     }
 
+    fn analyze_verification_unit(
+        &self,
+        unit: &mut VerificationUnit,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let src_span = unit.span();
+        let bound = if let Some(ref mut binding) = unit.binding {
+            as_fatal(self.lookup_bound_design(binding, diagnostics))?
+        } else {
+            None
+        };
+
+        let (visibility, region) = match bound.as_ref().map(|design| design.kind()) {
+            Some(Design::Entity(visibility, region))
+            | Some(Design::Architecture(visibility, region, _)) => {
+                (visibility.clone(), Some(region))
+            }
+            _ => (Visibility::default(), None),
+        };
+
+        let root_scope = Scope::new(Region::with_visibility(visibility));
+        if bound.is_none() {
+            self.add_implicit_context_clause(&root_scope)?;
+        }
+        self.analyze_context_clause(&root_scope, &mut unit.context_clause, diagnostics)?;
+
+        let ent = self.define(
+            &mut unit.ident,
+            self.work_library(),
+            AnyEntKind::Design(Design::VerificationUnit(
+                Visibility::default(),
+                Region::default(),
+            )),
+            src_span,
+        );
+        root_scope.add(ent, diagnostics);
+
+        let scope = if let Some(region) = region {
+            Scope::extend(region, Some(&root_scope))
+        } else {
+            root_scope.nested()
+        };
+
+        if let Some(design) = bound {
+            // The name of the bound design unit is visible
+            scope.make_potentially_visible(design.decl_pos(), design.into());
+        }
+
+        self.analyze_declarative_part(&scope, ent, &mut unit.decl, diagnostics)?;
+        scope.close(diagnostics);
+
+        // PSL directives are not analyzed, names that do not denote a VHDL declaration
+        // are PSL keywords, built-in functions or declarations.
+        for name in unit.names.iter_mut() {
+            if let Ok(NamedEntities::Single(ent)) =
+                scope.lookup(&Designator::Identifier(name.item.item.clone()))
+            {
+                name.set_unique_reference(ent);
+            }
+        }
+
+        let region = scope.into_region();
+        let visibility = root_scope.into_visibility();
+
+        unsafe {
+            ent.set_kind(AnyEntKind::Design(Design::VerificationUnit(
+                visibility, region,
+            )))
+        }
+        Ok(())
+    }
+
+    /// Returns the architecture or entity that a verification unit is bound to
+    fn lookup_bound_design(
+        &self,
+        binding: &mut VerificationUnitBinding,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> EvalResult<DesignEnt<'a>> {
+        let entity_name = binding.entity_name.item.item.clone();
+        let entity_pos = binding.entity_name.item.pos(self.ctx).clone();
+        let entity = self.lookup_in_library(
+            diagnostics,
+            self.work_library_name(),
+            &entity_pos,
+            &Designator::Identifier(entity_name.clone()),
+        )?;
+        binding.entity_name.set_unique_reference(entity.into());
+
+        if !matches!(entity.kind(), Design::Entity(..)) {
+            let mut diagnostic = Diagnostic::mismatched_kinds(&entity_pos, "Expected an entity");
+            if let Some(pos) = entity.decl_pos() {
+                diagnostic.add_related(pos, format!("Found {}", entity.describe()))
+            }
+            diagnostics.push(diagnostic);
+            return Err(EvalError::Unknown);
+        }
+
+        let Some(ref mut architecture_name) = binding.architecture_name else {
+            return Ok(entity);
+        };
+        let architecture = self.get_architecture(
+            diagnostics,
+            self.work_library_name(),
+            architecture_name.item.pos(self.ctx),
+            &entity_name,
+            &architecture_name.item.item,
+        )?;
+        architecture_name.set_unique_reference(architecture.into());
+        Ok(architecture)
+    }
+
     fn analyze_package_body(
         &self,
         unit: &mut PackageBody,
@@ -358,7 +478,12 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         scope.make_potentially_visible(primary.decl_pos(), primary.into());
 
         self.analyze_declarative_part(&scope, body, &mut unit.decl, diagnostics)?;
-        scope.close(diagnostics);
+        if unit.is_protected {
+            // The declarations that complete the package are encrypted
+            scope.close(&mut Vec::new());
+        } else {
+            scope.close(diagnostics);
+        }
         let region = scope.into_region();
         let visibility = root_scope.into_visibility();
 
@@ -394,7 +519,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         scope: &Scope<'a>,
         config: &mut ConfigurationDeclaration,
         diagnostics: &mut dyn DiagnosticHandler,
-    ) -> EvalResult<DesignEnt<'_>> {
+    ) -> EvalResult<DesignEnt<'a>> {
         let ent_name = &mut config.entity_name;
         let ent_name_span = ent_name.span;
 
@@ -0,0 +1,143 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::DesignRoot;
+use crate::ast::{AnyKind, HasUnitId};
+use crate::data::{HasSrcPos, SrcPos};
+
+/// A library with all of its design units
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LibraryUnits {
+    pub name: String,
+    /// Primary units sorted by name, each followed by its secondary units
+    pub units: Vec<DesignUnitSummary>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DesignUnitSummary {
+    pub name: String,
+    pub kind: AnyKind,
+    /// The name of the primary unit of a secondary unit, i.e. the entity of an architecture
+    pub primary_name: Option<String>,
+    /// The position of the name of the unit
+    pub pos: SrcPos,
+}
+
+impl DesignRoot {
+    /// All libraries sorted by name together with their design units
+    pub fn list_design_units(&self) -> Vec<LibraryUnits> {
+        let mut libraries: Vec<_> = self
+            .libraries()
+            .map(|library| {
+                let mut primary_units: Vec<_> = library.primary_units().collect();
+                primary_units.sort_by_key(|unit| unit.unit_id().primary_name().name_utf8());
+
+                let mut units = Vec::new();
+                for primary_unit in primary_units {
+                    let primary_name = primary_unit.unit_id().primary_name();
+                    units.push(DesignUnitSummary {
+                        name: primary_name.name_utf8(),
+                        kind: primary_unit.kind(),
+                        primary_name: None,
+                        pos: primary_unit.pos().clone(),
+                    });
+
+                    let mut secondary_units: Vec<_> = library
+                        .secondary_units(primary_name)
+                        .map(|unit| DesignUnitSummary {
+                            name: unit
+                                .unit_id()
+                                .secondary_name()
+                                .unwrap_or(primary_name)
+                                .name_utf8(),
+                            kind: unit.kind(),
+                            primary_name: Some(primary_name.name_utf8()),
+                            pos: unit.pos().clone(),
+                        })
+                        .collect();
+                    secondary_units.sort_by(|a, b| a.name.cmp(&b.name));
+                    units.extend(secondary_units);
+                }
+
+                LibraryUnits {
+                    name: library.name().name_utf8(),
+                    units,
+                }
+            })
+            .collect();
+        libraries.sort_by(|a, b| a.name.cmp(&b.name));
+        libraries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::ast::{PrimaryKind, SecondaryKind};
+
+    #[test]
+    fn lists_units_of_libraries() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture rtl of ent is
+begin
+end architecture;
+
+package pkg is
+end package;
+
+package body pkg is
+end package body;
+
+architecture behav of ent is
+begin
+end architecture;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        let libraries = root.list_design_units();
+        let library = libraries
+            .iter()
+            .find(|library| library.name == "libname")
+            .unwrap();
+
+        let summary: Vec<_> = library
+            .units
+            .iter()
+            .map(|unit| (unit.name.as_str(), unit.kind, unit.primary_name.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ent", AnyKind::Primary(PrimaryKind::Entity), None),
+                (
+                    "behav",
+                    AnyKind::Secondary(SecondaryKind::Architecture),
+                    Some("ent")
+                ),
+                (
+                    "rtl",
+                    AnyKind::Secondary(SecondaryKind::Architecture),
+                    Some("ent")
+                ),
+                ("pkg", AnyKind::Primary(PrimaryKind::Package), None),
+                (
+                    "pkg",
+                    AnyKind::Secondary(SecondaryKind::PackageBody),
+                    Some("pkg")
+                ),
+            ]
+        );
+        assert_eq!(library.units[2].pos, code.s1("rtl").pos());
+        assert!(libraries.iter().any(|library| library.name == "std"));
+    }
+}
@@ -0,0 +1,494 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::references::ReferenceKind;
+use super::root::DesignRoot;
+use crate::ast::search::{DeclarationItem, FindAllWrites, NotFinished, SearchState, Searcher};
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+use crate::TokenAccess;
+
+/// Where a signal is accessed relative to the signal that was asked for
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AccessLevel {
+    /// Within the scope of the signal itself
+    Local,
+    /// Within an entity that is instantiated with the signal connected to one of its ports
+    Instance,
+    /// Within an architecture that instantiates the entity of the port
+    Parent,
+}
+
+/// A place where a signal or port is driven or read
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SignalAccess {
+    /// The position of the name of the accessed signal or port
+    pub pos: SrcPos,
+    /// The name of the accessed signal or port, which differs from the signal that was
+    /// asked for when it is accessed through a port map
+    pub name: String,
+    pub level: AccessLevel,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Drivers,
+    Loads,
+}
+
+/// An association of a port map
+struct Connection {
+    /// The port of the instantiated entity or component
+    formal: EntityId,
+    /// The position of the formal name, `None` for positional associations
+    formal_pos: Option<SrcPos>,
+    /// The position and the declaration of the signal or port that is connected
+    actual: Option<NameReference>,
+}
+
+impl DesignRoot {
+    /// The places that drive `signal`, i.e. assignments and port maps that connect it
+    /// to output ports, including the drivers of these ports within the instantiated entity.
+    /// For an input port, the signals connected to the port in instantiations of its entity
+    /// are included as well.
+    pub fn find_drivers(&self, signal: EntRef<'_>) -> Vec<SignalAccess> {
+        self.find_accesses(signal, Direction::Drivers)
+    }
+
+    /// The places that read `signal`, including the reads of the input ports it is
+    /// connected to within the instantiated entity.
+    /// For an output port, the signals connected to the port in instantiations of its entity
+    /// are included as well.
+    pub fn find_loads(&self, signal: EntRef<'_>) -> Vec<SignalAccess> {
+        self.find_accesses(signal, Direction::Loads)
+    }
+
+    fn find_accesses(&self, signal: EntRef<'_>, direction: Direction) -> Vec<SignalAccess> {
+        let signal = signal.declaration();
+        if !is_signal(signal) {
+            return Vec::new();
+        }
+        let connections = self.port_connections();
+        let mut accesses: Vec<SignalAccess> = self
+            .local_accesses(signal, direction, &connections)
+            .into_iter()
+            .map(|pos| access(pos, signal, AccessLevel::Local))
+            .collect();
+
+        // One level down, through the ports of instances that the signal is connected to
+        for connection in connections.iter() {
+            if !connection
+                .actual
+                .as_ref()
+                .is_some_and(|(_, actual)| *actual == signal.id())
+            {
+                continue;
+            }
+            let formal = self.get_ent(connection.formal);
+            if !is_directed(formal, direction) {
+                continue;
+            }
+            let Some(port) = self.entity_port(formal) else {
+                continue;
+            };
+            accesses.extend(
+                self.local_accesses(port, direction, &connections)
+                    .into_iter()
+                    .map(|pos| access(pos, port, AccessLevel::Instance)),
+            );
+        }
+
+        // One level up, to the signals connected to the port in instantiations
+        if is_port(signal) && is_directed(signal, opposite(direction)) {
+            for connection in connections.iter() {
+                let formal = self.get_ent(connection.formal);
+                if self.entity_port(formal).map(|port| port.id()) != Some(signal.id()) {
+                    continue;
+                }
+                if let Some((pos, actual)) = &connection.actual {
+                    accesses.push(access(
+                        pos.clone(),
+                        self.get_ent(*actual),
+                        AccessLevel::Parent,
+                    ));
+                }
+            }
+        }
+        accesses
+    }
+
+    /// The positions that drive or read `signal` within its own scope
+    fn local_accesses(
+        &self,
+        signal: EntRef<'_>,
+        direction: Direction,
+        connections: &[Connection],
+    ) -> Vec<SrcPos> {
+        // Actuals connected to ports of the opposite direction do not drive or read the signal
+        let connected_to = |pos: &SrcPos, direction: Direction| {
+            connections.iter().any(|connection| {
+                connection
+                    .actual
+                    .as_ref()
+                    .is_some_and(|(actual_pos, _)| actual_pos == pos)
+                    && is_directed(self.get_ent(connection.formal), direction)
+            })
+        };
+        match direction {
+            Direction::Drivers => {
+                let mut writes = FindAllWrites::new(self, signal);
+                let _ = self.search(&mut writes);
+                let mut positions = writes.writes;
+                positions.extend(
+                    self.find_all_references_with_kind(signal)
+                        .into_iter()
+                        .filter(|(pos, kind)| {
+                            *kind == ReferenceKind::Read && connected_to(pos, Direction::Drivers)
+                        })
+                        .map(|(pos, _)| pos),
+                );
+                positions
+            }
+            Direction::Loads => self
+                .find_all_references_with_kind(signal)
+                .into_iter()
+                .filter(|(pos, kind)| {
+                    let is_formal = connections
+                        .iter()
+                        .any(|connection| connection.formal_pos.as_ref() == Some(pos));
+                    let is_actual = connections.iter().any(|connection| {
+                        connection
+                            .actual
+                            .as_ref()
+                            .is_some_and(|(actual_pos, _)| actual_pos == pos)
+                    });
+                    *kind == ReferenceKind::Read
+                        && !is_formal
+                        && (!is_actual || connected_to(pos, Direction::Loads))
+                })
+                .map(|(pos, _)| pos)
+                .collect(),
+        }
+    }
+
+    /// The associations of the port maps of all instantiations
+    fn port_connections(&self) -> Vec<Connection> {
+        let mut searcher = FindPortMaps::default();
+        let _ = self.search(&mut searcher);
+
+        let mut connections = Vec::new();
+        for port_map in searcher.port_maps {
+            let ports = self.ports_of(self.get_ent(port_map.unit));
+            for (position, (formal, actual)) in port_map.associations.into_iter().enumerate() {
+                let (formal_pos, formal) = match formal {
+                    Some((pos, formal)) => (Some(pos), formal),
+                    None => {
+                        let Some(formal) = ports.get(position) else {
+                            continue;
+                        };
+                        (None, *formal)
+                    }
+                };
+                let actual =
+                    actual.map(|(pos, actual)| (pos, self.get_ent(actual).declaration().id()));
+                connections.push(Connection {
+                    formal,
+                    formal_pos,
+                    actual,
+                });
+            }
+        }
+        connections
+    }
+
+    /// The ports of an entity or component in declaration order
+    pub(crate) fn ports_of(&self, ent: EntRef<'_>) -> Vec<EntityId> {
+        self.with_declaration(ent, |decl| {
+            let ports = match decl {
+                DeclarationItem::Entity(entity) => entity.port_clause.as_ref(),
+                DeclarationItem::Component(component) => component.port_list.as_ref(),
+                _ => None,
+            };
+            let mut result = Vec::new();
+            for port in ports.iter().flat_map(|list| list.items.iter()) {
+                if let InterfaceDeclaration::Object(object) = port {
+                    result.extend(object.idents.iter().filter_map(|ident| ident.decl.get()));
+                }
+            }
+            result
+        })
+        .unwrap_or_default()
+    }
+
+    /// The port of the entity that corresponds to a port of an entity or component,
+    /// where components are bound to the entity with the same name in the same library.
+    fn entity_port<'a>(&'a self, port: EntRef<'a>) -> Option<EntRef<'a>> {
+        let parent = port.parent?;
+        match parent.kind() {
+            AnyEntKind::Design(Design::Entity(..)) => Some(port),
+            AnyEntKind::Component(_) => {
+                let Designator::Identifier(name) = parent.designator() else {
+                    return None;
+                };
+                let entity = self.get_design_entity(parent.library_name()?, name)?;
+                let entity: EntRef<'_> = entity.into();
+                self.ports_of(entity)
+                    .into_iter()
+                    .map(|id| self.get_ent(id))
+                    .find(|entity_port| entity_port.designator() == port.designator())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn access(pos: SrcPos, signal: EntRef<'_>, level: AccessLevel) -> SignalAccess {
+    SignalAccess {
+        pos,
+        name: signal.designator().to_string(),
+        level,
+    }
+}
+
+fn opposite(direction: Direction) -> Direction {
+    match direction {
+        Direction::Drivers => Direction::Loads,
+        Direction::Loads => Direction::Drivers,
+    }
+}
+
+fn is_signal(ent: EntRef<'_>) -> bool {
+    matches!(ent.kind(), AnyEntKind::Object(object) if object.class == ObjectClass::Signal)
+}
+
+fn is_port(ent: EntRef<'_>) -> bool {
+    matches!(
+        ent.kind(),
+        AnyEntKind::Object(Object {
+            iface: Some(ObjectInterface::Port(_)),
+            ..
+        })
+    )
+}
+
+/// True if a port of this mode drives the connected signal (`Drivers`)
+/// or reads it (`Loads`). Signals that are not ports are both driven and read.
+fn is_directed(port: EntRef<'_>, direction: Direction) -> bool {
+    let AnyEntKind::Object(Object {
+        iface: Some(ObjectInterface::Port(mode)),
+        ..
+    }) = port.kind()
+    else {
+        return true;
+    };
+    match mode {
+        InterfaceMode::Simple(mode) => match direction {
+            Direction::Drivers => matches!(mode, Mode::Out | Mode::InOut | Mode::Buffer),
+            Direction::Loads => matches!(mode, Mode::In | Mode::InOut | Mode::Buffer),
+        },
+        InterfaceMode::View(_) => true,
+    }
+}
+
+/// The position of a name and the declaration that it denotes
+type NameReference = (SrcPos, EntityId);
+
+/// The associations of a port map, where the formal and the actual are given
+/// by the position and the declaration of their name.
+struct PortMap {
+    unit: EntityId,
+    associations: Vec<(Option<NameReference>, Option<NameReference>)>,
+}
+
+#[derive(Default)]
+struct FindPortMaps {
+    port_maps: Vec<PortMap>,
+}
+
+impl Searcher for FindPortMaps {
+    fn search_instantiation(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        instance: &InstantiationStatement,
+    ) -> SearchState {
+        let (Some(unit), Some(port_map)) = (instance.entity_reference(), &instance.port_map) else {
+            return NotFinished;
+        };
+        let associations = port_map
+            .list
+            .items
+            .iter()
+            .map(|element| {
+                let formal = element
+                    .formal
+                    .as_ref()
+                    .and_then(|formal| name_reference(ctx, &formal.pos(ctx), &formal.item));
+                let actual = match &element.actual.item {
+                    ActualPart::Expression(Expression::Name(name)) => {
+                        name_reference(ctx, &element.actual.pos(ctx), name)
+                    }
+                    _ => None,
+                };
+                (formal, actual)
+            })
+            .collect();
+        self.port_maps.push(PortMap { unit, associations });
+        NotFinished
+    }
+}
+
+/// The position and declaration of the object denoted by a name,
+/// i.e. `sig` in `sig(0)` or `rec.field`
+fn name_reference(ctx: &dyn TokenAccess, pos: &SrcPos, name: &Name) -> Option<NameReference> {
+    match name {
+        Name::Designator(designator) => Some((pos.clone(), designator.reference.get()?)),
+        Name::Selected(_, suffix) => Some((suffix.pos(ctx).clone(), suffix.item.reference.get()?)),
+        Name::SelectedAll(prefix) | Name::Slice(prefix, _) => {
+            name_reference(ctx, &prefix.pos(ctx), &prefix.item)
+        }
+        Name::CallOrIndexed(call) => name_reference(ctx, &call.name.pos(ctx), &call.name.item),
+        Name::Attribute(_) | Name::External(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+
+    #[test]
+    fn finds_drivers_and_loads_through_port_maps() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity child is
+  port (a : in bit; y : out bit);
+end entity;
+
+architecture rtl of child is
+begin
+  y <= not a;
+end architecture;
+
+entity top is
+  port (clk : in bit; q : out bit);
+end entity;
+
+architecture rtl of top is
+  signal s, t : bit;
+begin
+  s <= clk;
+  inst: entity work.child port map (a => s, y => t);
+  q <= t;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        crate::syntax::test::check_no_diagnostics(&diagnostics);
+        let ent = |substr: &str| {
+            root.search_reference(code.source(), code.s1(substr).start())
+                .unwrap()
+        };
+        let describe = |accesses: Vec<SignalAccess>| -> Vec<(SrcPos, String, AccessLevel)> {
+            accesses
+                .into_iter()
+                .map(|access| (access.pos, access.name, access.level))
+                .collect()
+        };
+
+        assert_eq!(
+            describe(root.find_drivers(ent("s, t"))),
+            vec![(
+                code.s1("s <=").s1("s").pos(),
+                "s".to_owned(),
+                AccessLevel::Local
+            )]
+        );
+        assert_eq!(
+            describe(root.find_loads(ent("s, t"))),
+            vec![
+                (
+                    code.s1("=> s").s1("s").pos(),
+                    "s".to_owned(),
+                    AccessLevel::Local
+                ),
+                (
+                    code.s1("not a").s1("a").pos(),
+                    "a".to_owned(),
+                    AccessLevel::Instance
+                ),
+            ]
+        );
+        assert_eq!(
+            describe(root.find_drivers(ent("t : bit"))),
+            vec![
+                (
+                    code.s1("=> t").s1("t").pos(),
+                    "t".to_owned(),
+                    AccessLevel::Local
+                ),
+                (
+                    code.s1("y <=").s1("y").pos(),
+                    "y".to_owned(),
+                    AccessLevel::Instance
+                ),
+            ]
+        );
+        assert_eq!(
+            describe(root.find_drivers(ent("a : in"))),
+            vec![(
+                code.s1("=> s").s1("s").pos(),
+                "s".to_owned(),
+                AccessLevel::Parent
+            )]
+        );
+        assert_eq!(
+            describe(root.find_loads(ent("y : out"))),
+            vec![(
+                code.s1("=> t").s1("t").pos(),
+                "t".to_owned(),
+                AccessLevel::Parent
+            )]
+        );
+    }
+
+    #[test]
+    fn force_assignments_are_drivers() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity top is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of top is
+begin
+  process
+  begin
+    clk <= force '1';
+    wait;
+  end process;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        crate::syntax::test::check_no_diagnostics(&diagnostics);
+        let clk = root
+            .search_reference(code.source(), code.s1("clk").start())
+            .unwrap();
+        assert_eq!(
+            root.find_drivers(clk),
+            vec![SignalAccess {
+                pos: code.s1("clk <=").s1("clk").pos(),
+                name: "clk".to_owned(),
+                level: AccessLevel::Local,
+            }]
+        );
+    }
+}
@@ -316,6 +316,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
         op.set_unique_reference(&overloaded);
+        self.set_ambiguous_operator(op.token, None);
         for (idx, expr) in exprs.iter_mut().enumerate() {
             let target_type = overloaded.formals().nth(idx).unwrap().type_mark();
             self.expr_pos_with_ttyp(scope, target_type, expr.span, &mut expr.item, diagnostics)?;
@@ -413,6 +414,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             self.check_op(scope, op, ent, exprs, diagnostics)?;
             Ok(Disambiguated::Unambiguous(ent))
         } else {
+            self.set_ambiguous_operator(op.token, Some(&candidates));
             Ok(Disambiguated::Ambiguous(candidates))
         }
     }
@@ -464,7 +466,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         expr: &mut Expression,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> EvalResult<ExpressionType<'a>> {
-        match expr {
+        let types = match expr {
             Expression::Binary(ref mut op, ref mut left, ref mut right) => {
                 self.operator_type(scope, op, &mut [left.as_mut(), right.as_mut()], diagnostics)
             }
@@ -557,6 +559,24 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 }
                 Literal::Null => Ok(ExpressionType::Null),
             },
+        }?;
+        if let ExpressionType::Unambiguous(typ) = types {
+            self.set_expression_type(span, typ);
+        }
+        Ok(types)
+    }
+
+    /// The type of an expression of type `typ` where `target_type` is expected.
+    /// Universal expressions take the target type by implicit conversion.
+    pub(super) fn implicitly_converted_type(
+        &self,
+        typ: TypeEnt<'a>,
+        target_type: TypeEnt<'a>,
+    ) -> TypeEnt<'a> {
+        if typ.base() == target_type.base() {
+            typ
+        } else {
+            target_type
         }
     }
 
@@ -675,7 +695,8 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
 
                         match implicit_bool_types.len().cmp(&1) {
                             std::cmp::Ordering::Equal => {
-                                let typ: TypeEnt<'_> = types.into_iter().next().unwrap().into();
+                                let typ: TypeEnt<'_> =
+                                    implicit_bool_types.into_iter().next().unwrap().into();
                                 self.expr_with_ttyp(scope, typ, expr, diagnostics)?;
                             }
                             std::cmp::Ordering::Greater => {
@@ -741,7 +762,12 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 if let Some(type_mark) =
                     as_fatal(self.analyze_qualified_expression(scope, qexpr, diagnostics))?
                 {
-                    if !self.can_be_target_type(type_mark, target_base.base()) {
+                    if self.can_be_target_type(type_mark, target_base.base()) {
+                        self.set_expression_type(
+                            span,
+                            self.implicitly_converted_type(type_mark, target_type),
+                        );
+                    } else {
                         diagnostics.push(Diagnostic::type_mismatch(
                             &span.pos(self.ctx),
                             &type_mark.describe(),
@@ -773,7 +799,12 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     Some(Disambiguated::Unambiguous(overloaded)) => {
                         let op_type = overloaded.return_type().unwrap();
 
-                        if !self.can_be_target_type(op_type, target_type.base()) {
+                        if self.can_be_target_type(op_type, target_type.base()) {
+                            self.set_expression_type(
+                                span,
+                                self.implicitly_converted_type(op_type, target_type),
+                            );
+                        } else {
                             diagnostics.push(Diagnostic::type_mismatch(
                                 &span.pos(self.ctx),
                                 &op_type.describe(),
@@ -816,7 +847,12 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     Some(Disambiguated::Unambiguous(overloaded)) => {
                         let op_type = overloaded.return_type().unwrap();
 
-                        if !self.can_be_target_type(op_type, target_type.base()) {
+                        if self.can_be_target_type(op_type, target_type.base()) {
+                            self.set_expression_type(
+                                span,
+                                self.implicitly_converted_type(op_type, target_type),
+                            );
+                        } else {
                             diagnostics.push(Diagnostic::type_mismatch(
                                 &span.pos(self.ctx),
                                 &op_type.describe(),
@@ -838,6 +874,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 Type::Array {
                     elem_type, indexes, ..
                 } => {
+                    self.check_aggregate_associations(assocs, true, diagnostics);
                     for assoc in assocs.iter_mut() {
                         as_fatal(self.array_assoc_elem(
                             scope,
@@ -848,6 +885,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                             diagnostics,
                         ))?;
                     }
+                    self.set_expression_type(span, target_type);
                 }
                 Type::Record(record_scope) => {
                     self.analyze_record_aggregate(
@@ -858,6 +896,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                         assocs,
                         diagnostics,
                     )?;
+                    self.set_expression_type(span, target_type);
                 }
                 _ => {
                     self.analyze_aggregate(scope, assocs, diagnostics)?;
@@ -880,6 +919,9 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     &mut expr.item,
                     diagnostics,
                 )?;
+                if let Some(typ) = self.expression_type(expr.span) {
+                    self.set_expression_type(span, typ);
+                }
             }
         }
 
@@ -925,6 +967,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         assocs: &mut [WithTokenSpan<ElementAssociation>],
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> FatalResult {
+        self.check_aggregate_associations(assocs, false, diagnostics);
         let mut associated = RecordAssociations::default();
         let mut is_ok_so_far = true;
 
@@ -947,7 +990,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                                             choice.span,
                                             diagnostics,
                                         );
-                                        Some(elem.type_mark().base())
+                                        Some(elem.type_mark())
                                     } else {
                                         is_ok_so_far = false;
                                         diagnostics.push(Diagnostic::no_declaration_within(
@@ -1038,7 +1081,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                                 }
 
                                 if remaining_types.len() == 1 {
-                                    remaining_types.into_iter().next()
+                                    remaining_types.into_iter().next().map(TypeEnt::from)
                                 } else {
                                     None
                                 }
@@ -1060,7 +1103,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     if let Some(typ) = typ {
                         self.expr_pos_with_ttyp(
                             scope,
-                            typ.into(),
+                            typ,
                             actual_expr.span,
                             &mut actual_expr.item,
                             diagnostics,
@@ -1120,6 +1163,58 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         Ok(())
     }
 
+    /// Check the order of the element associations of an aggregate (LRM 9.3.3).
+    /// Positional associations must precede named associations and `others` must be
+    /// the only choice of the last association. Apart from a final association with the choice
+    /// `others`, the associations of an array aggregate must be either all positional or all named.
+    fn check_aggregate_associations(
+        &self,
+        assocs: &[WithTokenSpan<ElementAssociation>],
+        is_array: bool,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let last_positional = assocs
+            .iter()
+            .rposition(|assoc| matches!(assoc.item, ElementAssociation::Positional(_)));
+
+        for (idx, assoc) in assocs.iter().enumerate() {
+            let ElementAssociation::Named(choices, _) = &assoc.item else {
+                continue;
+            };
+            let (Some(first), Some(last)) = (choices.first(), choices.last()) else {
+                continue;
+            };
+            let is_final_others = idx + 1 == assocs.len()
+                && choices.len() == 1
+                && matches!(first.item, Choice::Others);
+
+            for choice in choices.iter() {
+                if matches!(choice.item, Choice::Others) && !is_final_others {
+                    diagnostics.add(
+                        choice.pos(self.ctx),
+                        "'others' must be the only choice of the last element association",
+                        ErrorCode::MisplacedOthers,
+                    );
+                }
+            }
+
+            let choices_pos = first.span.combine(last.span).pos(self.ctx);
+            match last_positional {
+                Some(positional) if positional > idx => diagnostics.add(
+                    choices_pos,
+                    "Named associations are not allowed before positional associations",
+                    ErrorCode::NamedBeforePositional,
+                ),
+                Some(_) if is_array && !is_final_others => diagnostics.add(
+                    choices_pos,
+                    "Array aggregates cannot mix positional and named associations",
+                    ErrorCode::MixedAggregateAssociation,
+                ),
+                _ => {}
+            }
+        }
+    }
+
     pub fn array_assoc_elem(
         &self,
         scope: &Scope<'a>,
@@ -1183,7 +1278,6 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                             }
                         }
                         Choice::Others => {
-                            // @TODO choice must be alone so cannot appear here
                             can_be_array = false;
                         }
                     }
@@ -1195,6 +1289,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
 
         if index_types.len() > 1 {
             if let Expression::Aggregate(ref mut inner) = expr.item {
+                self.check_aggregate_associations(inner, true, diagnostics);
                 for assoc in inner.iter_mut() {
                     as_fatal(self.array_assoc_elem(
                         scope,
@@ -0,0 +1,706 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Resolution of the pathnames of external names (LRM 8.7) in the elaborated design hierarchy
+
+use super::concurrent::generate_bodies;
+use super::constant_folding::MAX_DEPTH;
+use super::hierarchy::{configuration_specifications, Region};
+use super::root::DesignRoot;
+use crate::ast::search::{Search, SearchState, Searcher};
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::config::TopLevel;
+use crate::data::*;
+use crate::named_entity::*;
+use crate::TokenAccess;
+use fnv::FnvHashSet;
+use std::ops::Deref;
+
+/// A concurrent region of the elaborated design hierarchy,
+/// which is the architecture of an instance or the body of a block or generate statement
+struct ConcurrentRegion<'a> {
+    region: Region<'a>,
+    unit: &'a UnitId,
+    /// The entity of an instance
+    entity: Option<EntRef<'a>>,
+    describe: String,
+    interfaces: Vec<&'a InterfaceList>,
+    entity_decl: &'a [WithTokenSpan<Declaration>],
+    decl: Vec<&'a [WithTokenSpan<Declaration>]>,
+    statements: Vec<&'a [LabeledConcurrentStatement]>,
+    parent: Option<&'a ConcurrentRegion<'a>>,
+}
+
+impl<'a> ConcurrentRegion<'a> {
+    fn root(&self) -> &ConcurrentRegion<'a> {
+        let mut region = self;
+        while let Some(parent) = region.parent {
+            region = parent;
+        }
+        region
+    }
+
+    fn statement(&self, label: &Symbol) -> Option<&'a LabeledConcurrentStatement> {
+        self.statements
+            .iter()
+            .flat_map(|statements| statements.iter())
+            .find(|statement| {
+                statement
+                    .label
+                    .tree
+                    .as_ref()
+                    .is_some_and(|ident| &ident.item == label)
+            })
+    }
+
+    /// The generic, port, constant, signal or variable named `name`
+    fn object(&self, name: &Symbol) -> Option<EntityId> {
+        let interfaces = self
+            .interfaces
+            .iter()
+            .flat_map(|list| list.items.iter())
+            .filter_map(|item| match item {
+                InterfaceDeclaration::Object(object) => Some(&object.idents),
+                _ => None,
+            });
+        let objects = std::iter::once(self.entity_decl)
+            .chain(self.decl.iter().copied())
+            .flat_map(|decl| decl.iter())
+            .filter_map(|decl| match &decl.item {
+                Declaration::Object(object) => Some(&object.idents),
+                _ => None,
+            });
+        interfaces
+            .chain(objects)
+            .flatten()
+            .find(|ident| &ident.tree.item == name)
+            .and_then(|ident| ident.decl.get())
+    }
+}
+
+/// A simple name within the pathname of an external name
+struct PathElement<'n> {
+    name: &'n Symbol,
+    pos: SrcPos,
+    reference: &'n Reference,
+}
+
+/// The simple names of a pathname.
+/// The index of a generate statement is ignored since generate statements are not unrolled.
+fn path_elements<'n>(
+    ctx: &dyn TokenAccess,
+    name: &'n WithTokenSpan<Name>,
+    elements: &mut Vec<PathElement<'n>>,
+) -> bool {
+    match &name.item {
+        Name::Designator(designator) => {
+            let Designator::Identifier(symbol) = &designator.item else {
+                return false;
+            };
+            elements.push(PathElement {
+                name: symbol,
+                pos: name.pos(ctx),
+                reference: &designator.reference,
+            });
+            true
+        }
+        Name::Selected(prefix, suffix) => {
+            let Designator::Identifier(symbol) = &suffix.item.item else {
+                return false;
+            };
+            if !path_elements(ctx, prefix, elements) {
+                return false;
+            }
+            elements.push(PathElement {
+                name: symbol,
+                pos: suffix.pos(ctx).clone(),
+                reference: &suffix.item.reference,
+            });
+            true
+        }
+        Name::CallOrIndexed(fcall) => path_elements(ctx, &fcall.name, elements),
+        _ => false,
+    }
+}
+
+impl DesignRoot {
+    /// Resolve the absolute and relative pathnames of external names in the design hierarchy
+    /// below the `top_levels`. The references of the path elements are set to the labels
+    /// and objects that they denote.
+    /// References from an earlier resolution are cleared, also if there are no top levels.
+    pub(crate) fn resolve_external_names(&self, top_levels: &[TopLevel]) -> Vec<Diagnostic> {
+        let mut units = ExternalNameUnits::default();
+        for library in self.libraries() {
+            for unit in library.units() {
+                units.unit = Some(unit.unit_id().clone());
+                let _ = unit.unit.expect_analyzed().search(&unit.tokens, &mut units);
+            }
+        }
+
+        let mut diagnostics = Vec::new();
+        if units.with_external_names.is_empty() {
+            return diagnostics;
+        }
+        for top in top_levels {
+            let library_name = self.symbol_utf8(&top.library);
+            let Some(entity) =
+                self.get_design_entity(&library_name, &self.symbol_utf8(&top.entity))
+            else {
+                continue;
+            };
+            let entity: EntRef<'_> = entity.into();
+            let architecture = top.architecture.as_ref().map(|name| self.symbol_utf8(name));
+            self.with_design_region(
+                entity,
+                architecture.as_ref(),
+                entity.describe(),
+                None,
+                |region| {
+                    let mut stack = vec![entity.id()];
+                    self.resolve_in_region(
+                        region,
+                        &units.with_external_names,
+                        &mut stack,
+                        &mut diagnostics,
+                    );
+                },
+            );
+        }
+        diagnostics
+    }
+
+    /// Resolve the external names of `region` and of the regions nested in it
+    fn resolve_in_region(
+        &self,
+        region: &ConcurrentRegion<'_>,
+        units: &FnvHashSet<UnitId>,
+        stack: &mut Vec<EntityId>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        if units.contains(region.unit) {
+            let mut resolver = ExternalNameResolver {
+                root: self,
+                region,
+                diagnostics: &mut *diagnostics,
+            };
+            for decl in region.decl.iter().flat_map(|decl| decl.iter()) {
+                let _ = decl.search(region.region.ctx, &mut resolver);
+            }
+            for statement in region.statements.iter().flat_map(|s| s.iter()) {
+                if !is_nested_region(statement) {
+                    let _ = statement.search(region.region.ctx, &mut resolver);
+                }
+            }
+        }
+
+        for statement in region.statements.iter().flat_map(|s| s.iter()) {
+            self.with_nested_region(region, statement, |nested| {
+                if let Some(entity) = nested.entity {
+                    if stack.contains(&entity.id()) || stack.len() > MAX_DEPTH * 4 {
+                        return;
+                    }
+                    stack.push(entity.id());
+                    self.resolve_in_region(nested, units, stack, diagnostics);
+                    stack.pop();
+                } else {
+                    self.resolve_in_region(nested, units, stack, diagnostics);
+                }
+            });
+        }
+    }
+
+    /// Call `f` with the region of the architecture bound to `entity`
+    fn with_design_region<R>(
+        &self,
+        entity: EntRef<'_>,
+        architecture: Option<&Symbol>,
+        describe: String,
+        parent: Option<&ConcurrentRegion<'_>>,
+        f: impl FnOnce(&ConcurrentRegion<'_>) -> R,
+    ) -> Option<R> {
+        let Designator::Identifier(entity_name) = entity.designator() else {
+            return None;
+        };
+        let entity_unit = self
+            .get_lib(entity.library_name()?)?
+            .primary_unit(entity_name)?;
+        let entity_data = self.get_analysis(entity_unit);
+        let AnyDesignUnit::Primary(AnyPrimaryUnit::Entity(entity_decl)) = entity_data.deref()
+        else {
+            return None;
+        };
+        let arch_unit = self.bound_architecture(entity, architecture)?;
+        let arch_data = self.get_analysis(arch_unit);
+        let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) = arch_data.deref()
+        else {
+            return None;
+        };
+        let region = ConcurrentRegion {
+            region: Region {
+                ctx: &arch_unit.tokens,
+                library_name: arch_unit.unit_id().library_name(),
+                specs: configuration_specifications(&arch.decl),
+            },
+            unit: arch_unit.unit_id(),
+            entity: Some(entity),
+            describe,
+            interfaces: entity_decl
+                .generic_clause
+                .iter()
+                .chain(entity_decl.port_clause.iter())
+                .collect(),
+            entity_decl: &entity_decl.decl,
+            decl: vec![&arch.decl],
+            statements: vec![&arch.statements],
+            parent,
+        };
+        Some(f(&region))
+    }
+
+    /// Call `f` with the region of the instance, block or generate `statement` in `region`
+    fn with_nested_region<R>(
+        &self,
+        region: &ConcurrentRegion<'_>,
+        statement: &LabeledConcurrentStatement,
+        f: impl FnOnce(&ConcurrentRegion<'_>) -> R,
+    ) -> Option<R> {
+        let label = statement.label.tree.as_ref()?;
+        let describe = |kind: &str| format!("{kind} '{}'", label.item);
+        match &statement.statement.item {
+            ConcurrentStatement::Instance(instance) => {
+                let (entity, architecture) =
+                    self.instance_binding(&region.region, &label.item, instance)?;
+                self.with_design_region(
+                    entity,
+                    architecture.as_ref(),
+                    describe("instance"),
+                    Some(region),
+                    f,
+                )
+            }
+            ConcurrentStatement::Block(block) => Some(f(&ConcurrentRegion {
+                region: region.region.nested(&block.decl),
+                unit: region.unit,
+                entity: None,
+                describe: describe("block"),
+                interfaces: block
+                    .header
+                    .generic_clause
+                    .iter()
+                    .chain(block.header.port_clause.iter())
+                    .collect(),
+                entity_decl: &[],
+                decl: vec![&block.decl],
+                statements: vec![&block.statements],
+                parent: Some(region),
+            })),
+            ConcurrentStatement::ForGenerate(_)
+            | ConcurrentStatement::IfGenerate(_)
+            | ConcurrentStatement::CaseGenerate(_) => {
+                let bodies = generate_bodies(&statement.statement.item).unwrap_or_default();
+                // The alternatives of a generate statement are merged into a single region
+                Some(f(&ConcurrentRegion {
+                    region: region.region.nested(&[]),
+                    unit: region.unit,
+                    entity: None,
+                    describe: describe("generate"),
+                    interfaces: Vec::new(),
+                    entity_decl: &[],
+                    decl: bodies
+                        .iter()
+                        .filter_map(|body| body.decl.as_ref().map(|(decl, _)| decl.as_slice()))
+                        .collect(),
+                    statements: bodies
+                        .iter()
+                        .map(|body| body.statements.as_slice())
+                        .collect(),
+                    parent: Some(region),
+                }))
+            }
+            ConcurrentStatement::ProcedureCall(_)
+            | ConcurrentStatement::Process(_)
+            | ConcurrentStatement::Assert(_)
+            | ConcurrentStatement::Assignment(_) => None,
+        }
+    }
+
+    fn resolve_external_name(
+        &self,
+        region: &ConcurrentRegion<'_>,
+        ctx: &dyn TokenAccess,
+        external: &ExternalName,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let mut elements = Vec::new();
+        let (start, elements) = match &external.path.item {
+            // Package pathnames do not depend on the design hierarchy
+            ExternalPath::Package(_) => return,
+            ExternalPath::Absolute(name) => {
+                if !path_elements(ctx, name, &mut elements) {
+                    return;
+                }
+                let root = region.root();
+                let Some(entity) = root.entity else {
+                    return;
+                };
+                let [first, elements @ ..] = elements.as_slice() else {
+                    return;
+                };
+                if entity.designator() != &Designator::Identifier(first.name.clone()) {
+                    push_unique(
+                        diagnostics,
+                        Diagnostic::new(
+                            &first.pos,
+                            format!(
+                                "'{}' is not the root of the design hierarchy, expected '{}'",
+                                first.name,
+                                entity.designator()
+                            ),
+                            ErrorCode::Unresolved,
+                        ),
+                    );
+                    return;
+                }
+                first.reference.set_unlocked(entity.id());
+                (root, elements)
+            }
+            ExternalPath::Relative(name, up_levels) => {
+                if !path_elements(ctx, name, &mut elements) {
+                    return;
+                }
+                let mut start = region;
+                for _ in 0..*up_levels {
+                    let Some(parent) = start.parent else {
+                        push_unique(
+                            diagnostics,
+                            Diagnostic::new(
+                                external.path.pos(ctx),
+                                "External pathname goes above the root of the design hierarchy",
+                                ErrorCode::Unresolved,
+                            ),
+                        );
+                        return;
+                    };
+                    start = parent;
+                }
+                (start, elements.as_slice())
+            }
+        };
+        self.resolve_path(start, elements, external, diagnostics);
+    }
+
+    fn resolve_path(
+        &self,
+        region: &ConcurrentRegion<'_>,
+        elements: &[PathElement<'_>],
+        external: &ExternalName,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let [element, rest @ ..] = elements else {
+            return;
+        };
+        let no_declaration = || {
+            Diagnostic::new(
+                &element.pos,
+                format!(
+                    "No declaration of '{}' within {}",
+                    element.name, region.describe
+                ),
+                ErrorCode::Unresolved,
+            )
+        };
+        if !rest.is_empty() {
+            let Some(statement) = region.statement(element.name) else {
+                push_unique(diagnostics, no_declaration());
+                return;
+            };
+            if let Some(id) = statement.label.decl.get() {
+                element.reference.set_unlocked(id);
+            }
+            self.with_nested_region(region, statement, |nested| {
+                self.resolve_path(nested, rest, external, diagnostics)
+            });
+            return;
+        }
+
+        let Some(id) = region.object(element.name) else {
+            push_unique(diagnostics, no_declaration());
+            return;
+        };
+        element.reference.set_unlocked(id);
+        let Some(object) = ObjectEnt::from_any(self.get_ent(id)) else {
+            return;
+        };
+        let class = ObjectClass::from(external.class);
+        let class_matches = match (class, object.class()) {
+            (ObjectClass::Variable, ObjectClass::SharedVariable) => true,
+            (class, object_class) => class == object_class,
+        };
+        if !class_matches {
+            push_unique(
+                diagnostics,
+                Diagnostic::new(
+                    &element.pos,
+                    format!("External {class} name denotes {}", object.describe()),
+                    ErrorCode::MismatchedKinds,
+                ),
+            );
+            return;
+        }
+        let Some(type_mark) = external
+            .subtype
+            .type_mark
+            .item
+            .get_suffix_reference()
+            .and_then(|id| TypeEnt::from_any(self.get_ent(id)))
+        else {
+            return;
+        };
+        if type_mark.base_type() != object.type_mark().base_type() {
+            push_unique(
+                diagnostics,
+                Diagnostic::new(
+                    &element.pos,
+                    format!(
+                        "External name of {} denotes {} of {}",
+                        type_mark.describe(),
+                        object.describe(),
+                        object.type_mark().describe()
+                    ),
+                    ErrorCode::TypeMismatch,
+                ),
+            );
+        }
+    }
+}
+
+/// Statements that are resolved as regions of their own
+fn is_nested_region(statement: &LabeledConcurrentStatement) -> bool {
+    matches!(
+        statement.statement.item,
+        ConcurrentStatement::Block(_)
+            | ConcurrentStatement::ForGenerate(_)
+            | ConcurrentStatement::IfGenerate(_)
+            | ConcurrentStatement::CaseGenerate(_)
+    )
+}
+
+/// Architectures are resolved once per instance, so the same diagnostic can be found several times
+fn push_unique(diagnostics: &mut Vec<Diagnostic>, diagnostic: Diagnostic) {
+    if !diagnostics.contains(&diagnostic) {
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// Clears the references of external pathnames and collects the units with external names
+#[derive(Default)]
+struct ExternalNameUnits {
+    unit: Option<UnitId>,
+    with_external_names: FnvHashSet<UnitId>,
+}
+
+impl Searcher for ExternalNameUnits {
+    fn search_name(&mut self, ctx: &dyn TokenAccess, _pos: &SrcPos, name: &Name) -> SearchState {
+        if let Name::External(external) = name {
+            let mut elements = Vec::new();
+            match &external.path.item {
+                ExternalPath::Package(_) => {}
+                ExternalPath::Absolute(name) | ExternalPath::Relative(name, _) => {
+                    path_elements(ctx, name, &mut elements);
+                    if let Some(unit) = &self.unit {
+                        self.with_external_names.insert(unit.clone());
+                    }
+                }
+            }
+            for element in elements {
+                element.reference.clear();
+            }
+        }
+        SearchState::NotFinished
+    }
+}
+
+struct ExternalNameResolver<'r, 'a> {
+    root: &'r DesignRoot,
+    region: &'r ConcurrentRegion<'a>,
+    diagnostics: &'r mut Vec<Diagnostic>,
+}
+
+impl Searcher for ExternalNameResolver<'_, '_> {
+    fn search_name(&mut self, ctx: &dyn TokenAccess, _pos: &SrcPos, name: &Name) -> SearchState {
+        if let Name::External(external) = name {
+            self.root
+                .resolve_external_name(self.region, ctx, external, self.diagnostics);
+        }
+        SearchState::NotFinished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics, Code};
+
+    fn top_level(entity: &str) -> TopLevel {
+        TopLevel {
+            library: "libname".to_owned(),
+            entity: entity.to_owned(),
+            architecture: None,
+        }
+    }
+
+    #[test]
+    fn resolves_external_names_in_the_design_hierarchy() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity dut is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of dut is
+  signal state : natural;
+begin
+  blk: block is
+    signal inner : bit;
+  begin
+  end block;
+end architecture;
+
+entity tb is
+end entity;
+
+architecture sim of tb is
+  signal clk : bit;
+  alias state is << signal .tb.u_dut.state : natural >>;
+begin
+  u_dut: entity work.dut port map (clk => clk);
+  gen: for i in 0 to 1 generate
+    alias inner is << signal ^.u_dut.blk.inner : bit >>;
+  begin
+  end generate;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        check_no_diagnostics(&root.resolve_external_names(&[top_level("tb")]));
+
+        let declaration_of = |name: Code| {
+            root.search_reference(code.source(), name.start())
+                .and_then(|ent| ent.decl_pos().cloned())
+        };
+        let tb = code.s1("entity tb").s1("tb");
+        assert_eq!(declaration_of(code.s1(".tb").s1("tb")), Some(tb.pos()));
+        let u_dut = code.s("u_dut", 2);
+        assert_eq!(declaration_of(code.s1("u_dut")), Some(u_dut.pos()));
+        assert_eq!(declaration_of(code.s("u_dut", 3)), Some(u_dut.pos()));
+        assert_eq!(
+            declaration_of(code.s("state", 3)),
+            Some(code.s1("state").pos())
+        );
+        assert_eq!(
+            declaration_of(code.s1("blk.inner")),
+            Some(code.s1("blk").pos())
+        );
+        assert_eq!(
+            declaration_of(code.s("inner", 3)),
+            Some(code.s1("inner").pos())
+        );
+    }
+
+    #[test]
+    fn error_on_external_names_that_do_not_match_the_design_hierarchy() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity dut is
+  generic (width : natural := 8);
+end entity;
+
+architecture rtl of dut is
+  signal state : natural;
+begin
+end architecture;
+
+entity tb is
+end entity;
+
+architecture sim of tb is
+  alias a is << signal .top.u_dut.state : natural >>;
+  alias b is << signal .tb.u_dut.missing : natural >>;
+  alias c is << signal .tb.u_dut.width : natural >>;
+  alias d is << variable .tb.u_dut.state : natural >>;
+  alias e is << signal .tb.u_dut.state : bit >>;
+  alias f is << signal ^.u_dut.state : natural >>;
+begin
+  u_dut: entity work.dut;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        check_diagnostics(
+            root.resolve_external_names(&[top_level("tb")]),
+            vec![
+                Diagnostic::new(
+                    code.s1("top"),
+                    "'top' is not the root of the design hierarchy, expected 'tb'",
+                    ErrorCode::Unresolved,
+                ),
+                Diagnostic::new(
+                    code.s1("missing"),
+                    "No declaration of 'missing' within instance 'u_dut'",
+                    ErrorCode::Unresolved,
+                ),
+                Diagnostic::new(
+                    code.s("width", 2),
+                    "External signal name denotes generic 'width'",
+                    ErrorCode::MismatchedKinds,
+                ),
+                Diagnostic::new(
+                    code.s("state", 3),
+                    "External variable name denotes signal 'state'",
+                    ErrorCode::MismatchedKinds,
+                ),
+                Diagnostic::new(
+                    code.s("state", 4),
+                    "External name of type 'BIT' denotes signal 'state' of subtype 'NATURAL'",
+                    ErrorCode::TypeMismatch,
+                ),
+                Diagnostic::new(
+                    code.s1("^.u_dut.state"),
+                    "External pathname goes above the root of the design hierarchy",
+                    ErrorCode::Unresolved,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn does_not_resolve_external_names_without_top_levels() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libname",
+            "
+entity tb is
+end entity;
+
+architecture sim of tb is
+  alias a is << signal .top.missing : natural >>;
+begin
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        check_no_diagnostics(&root.resolve_external_names(&[]));
+    }
+}
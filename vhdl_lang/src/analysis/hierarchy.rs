@@ -0,0 +1,925 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::concurrent::generate_bodies;
+use super::constant_folding::{GenericValues, StaticValue, MAX_DEPTH};
+use super::instances::simple_or_selected_name;
+use super::root::DesignRoot;
+use crate::ast::json::Json;
+use crate::ast::search::DeclarationItem;
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+use crate::TokenAccess;
+use itertools::Itertools;
+use std::ops::Deref;
+
+/// A node of the elaborated design hierarchy
+#[derive(Clone, PartialEq, Debug)]
+pub struct HierarchyNode {
+    /// The label of the statement or `None` for the top-level entity
+    pub label: Option<String>,
+    pub kind: HierarchyNodeKind,
+    /// The position of the label or of the name of the top-level entity
+    pub pos: SrcPos,
+    pub children: Vec<HierarchyNode>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum HierarchyNodeKind {
+    /// An instance of an entity
+    Instance(InstanceInfo),
+    Block,
+    /// Generate statements are not unrolled,
+    /// so their children appear once regardless of the number of iterations
+    Generate,
+}
+
+impl HierarchyNode {
+    /// Formats the tree with one line per node, indented by its depth.
+    /// Generics that are not static are shown as `?`.
+    pub fn format_tree(&self) -> String {
+        let mut lines = Vec::new();
+        self.format_lines(0, &mut lines);
+        lines.join("\n")
+    }
+
+    fn format_lines(&self, depth: usize, lines: &mut Vec<String>) {
+        let kind = match &self.kind {
+            HierarchyNodeKind::Instance(info) => {
+                let mut kind = format!("{}.{}", info.library, info.entity);
+                if let Some(architecture) = &info.architecture {
+                    kind.push_str(&format!("({architecture})"));
+                }
+                if !info.generics.is_empty() {
+                    let generics = info
+                        .generics
+                        .iter()
+                        .map(|(name, value)| {
+                            format!("{name} => {}", value.as_deref().unwrap_or("?"))
+                        })
+                        .join(", ");
+                    kind.push_str(&format!(" generic map ({generics})"));
+                }
+                kind
+            }
+            HierarchyNodeKind::Block => "block".to_owned(),
+            HierarchyNodeKind::Generate => "generate".to_owned(),
+        };
+        let line = match &self.label {
+            Some(label) => format!("{label}: {kind}"),
+            None => kind,
+        };
+        lines.push(format!("{}{line}", "  ".repeat(depth)));
+        for child in self.children.iter() {
+            child.format_lines(depth + 1, lines);
+        }
+    }
+
+    /// Converts the tree to JSON with one object per node.
+    /// Positions are the file name and the zero-based line of the label.
+    pub fn to_json(&self) -> Json {
+        let string = |value: &str| Json::String(value.to_owned());
+        let optional = |value: &Option<String>| value.as_deref().map_or(Json::Null, string);
+        let mut members = vec![("label".to_owned(), optional(&self.label))];
+        match &self.kind {
+            HierarchyNodeKind::Instance(info) => {
+                members.extend([
+                    ("kind".to_owned(), string("instance")),
+                    ("library".to_owned(), string(&info.library)),
+                    ("entity".to_owned(), string(&info.entity)),
+                    ("architecture".to_owned(), optional(&info.architecture)),
+                    (
+                        "generics".to_owned(),
+                        Json::Array(
+                            info.generics
+                                .iter()
+                                .map(|(name, value)| {
+                                    Json::Object(vec![
+                                        ("name".to_owned(), string(name)),
+                                        ("value".to_owned(), optional(value)),
+                                    ])
+                                })
+                                .collect(),
+                        ),
+                    ),
+                ]);
+            }
+            HierarchyNodeKind::Block => members.push(("kind".to_owned(), string("block"))),
+            HierarchyNodeKind::Generate => members.push(("kind".to_owned(), string("generate"))),
+        }
+        members.extend([
+            (
+                "file".to_owned(),
+                string(&self.pos.file_name().to_string_lossy()),
+            ),
+            (
+                "line".to_owned(),
+                Json::Number(self.pos.range.start.line.to_string()),
+            ),
+            (
+                "children".to_owned(),
+                Json::Array(self.children.iter().map(HierarchyNode::to_json).collect()),
+            ),
+        ]);
+        Json::Object(members)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct InstanceInfo {
+    pub library: String,
+    pub entity: String,
+    /// The architecture bound to the entity,
+    /// or `None` if the entity has no architecture
+    pub architecture: Option<String>,
+    /// The generics of the entity with their value if it is static
+    pub generics: Vec<(String, Option<String>)>,
+}
+
+/// The statements of an architecture or block that are being elaborated
+pub(super) struct Region<'a> {
+    pub(super) ctx: &'a dyn TokenAccess,
+    /// The library of the architecture, which is the library denoted by `work`
+    pub(super) library_name: &'a Symbol,
+    /// The configuration specifications of the region and of its enclosing regions,
+    /// innermost first
+    pub(super) specs: Vec<&'a ConfigurationSpecification>,
+}
+
+impl<'a> Region<'a> {
+    /// The region of a block with declarations `decl` nested in this region
+    pub(super) fn nested(&self, decl: &'a [WithTokenSpan<Declaration>]) -> Region<'a> {
+        let mut specs = configuration_specifications(decl);
+        specs.extend(self.specs.iter().copied());
+        Region {
+            ctx: self.ctx,
+            library_name: self.library_name,
+            specs,
+        }
+    }
+
+    /// The binding indication of the configuration specification that applies
+    /// to the instance `label` of the component `component_name`.
+    /// Specifications that name the label take precedence over `all` and `others`.
+    fn binding(&self, label: &Symbol, component_name: &Symbol) -> Option<&'a BindingIndication> {
+        let specs = self.specs.iter().filter(|spec| {
+            simple_or_selected_name(&spec.spec.component_name.item)
+                .is_some_and(|(_, name)| name == component_name)
+        });
+        let mut fallback = None;
+        for spec in specs {
+            match &spec.spec.instantiation_list {
+                InstantiationList::Labels(labels) => {
+                    if labels.iter().any(|ident| &ident.item.item == label) {
+                        return Some(&spec.bind_ind);
+                    }
+                }
+                InstantiationList::Others | InstantiationList::All => {
+                    fallback = fallback.or(Some(&spec.bind_ind));
+                }
+            }
+        }
+        fallback
+    }
+}
+
+pub(super) fn configuration_specifications(
+    decl: &[WithTokenSpan<Declaration>],
+) -> Vec<&ConfigurationSpecification> {
+    decl.iter()
+        .filter_map(|decl| match &decl.item {
+            Declaration::Configuration(spec) => Some(spec),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A generic of an entity or component
+struct Generic {
+    name: Symbol,
+    id: Option<EntityId>,
+    default: Option<Expression>,
+}
+
+impl DesignRoot {
+    /// Elaborate the instantiation tree below `entity`.
+    /// Component instances are bound by the configuration specifications of the enclosing
+    /// architecture and blocks, or else to the entity with the same name as the component.
+    /// Without an explicit `architecture`, instances are bound to the last architecture
+    /// of an entity in the order of the source files.
+    /// Recursive instantiations are not followed.
+    pub fn design_hierarchy(
+        &self,
+        library: &Symbol,
+        entity: &Symbol,
+        architecture: Option<&Symbol>,
+    ) -> Option<HierarchyNode> {
+        let entity = self.get_design_entity(library, entity)?;
+        let entity: EntRef<'_> = entity.into();
+        let mut stack = Vec::new();
+        let (info, children) = self.elaborate_entity(
+            entity,
+            architecture,
+            &[],
+            &mut stack,
+            &GenericValues::default(),
+        );
+        Some(HierarchyNode {
+            label: None,
+            kind: HierarchyNodeKind::Instance(info),
+            pos: entity.decl_pos()?.clone(),
+            children,
+        })
+    }
+
+    /// The generics of an entity or component in declaration order
+    fn generics_of(&self, ent: EntRef<'_>) -> Vec<Generic> {
+        self.with_declaration(ent, |decl| {
+            let generics = match decl {
+                DeclarationItem::Entity(entity) => entity.generic_clause.as_ref(),
+                DeclarationItem::Component(component) => component.generic_list.as_ref(),
+                _ => None,
+            };
+            let mut result = Vec::new();
+            for generic in generics.iter().flat_map(|list| list.items.iter()) {
+                match generic {
+                    InterfaceDeclaration::Object(object) => {
+                        let default = match &object.mode {
+                            ModeIndication::Simple(mode) => {
+                                mode.expression.as_ref().map(|expr| expr.item.clone())
+                            }
+                            ModeIndication::View(_) => None,
+                        };
+                        for ident in object.idents.iter() {
+                            result.push(Generic {
+                                name: ident.tree.item.clone(),
+                                id: ident.decl.get(),
+                                default: default.clone(),
+                            });
+                        }
+                    }
+                    InterfaceDeclaration::Type(ident) => result.push(Generic {
+                        name: ident.tree.item.clone(),
+                        id: ident.decl.get(),
+                        default: None,
+                    }),
+                    InterfaceDeclaration::File(_)
+                    | InterfaceDeclaration::Subprogram(_)
+                    | InterfaceDeclaration::Package(_) => {}
+                }
+            }
+            result
+        })
+        .unwrap_or_default()
+    }
+
+    /// The entity and the architecture of the block configuration of a configuration declaration
+    fn configured_design(
+        &self,
+        library_name: &Symbol,
+        name: &Symbol,
+    ) -> Option<(EntRef<'_>, Option<Symbol>)> {
+        let unit = self.get_lib(library_name)?.primary_unit(name)?;
+        let data = self.get_analysis(unit);
+        let AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(config)) = data.deref() else {
+            return None;
+        };
+        let entity = self.get_ent(config.entity_name.item.get_suffix_reference()?);
+        let architecture = match simple_or_selected_name(&config.block_config.block_spec.item) {
+            Some((None, name)) => Some(name.clone()),
+            _ => None,
+        };
+        Some((entity, architecture))
+    }
+
+    /// The entity and architecture that an instance `label` of `component` is bound to.
+    /// Returns `None` if the instance is left unbound with `use open`
+    /// or if the bound entity does not exist.
+    fn bound_design<'a, 'r>(
+        &'a self,
+        component: EntRef<'a>,
+        label: &Symbol,
+        region: &Region<'r>,
+    ) -> Option<(EntRef<'a>, Option<Symbol>)> {
+        let Designator::Identifier(component_name) = component.designator() else {
+            return None;
+        };
+        let entity_aspect = region
+            .binding(label, component_name)
+            .and_then(|bind_ind| bind_ind.entity_aspect.as_ref());
+        // Binding indications are not analyzed, so names are resolved within the library
+        let resolve = |name: &'r Name| {
+            let (library_name, name) = simple_or_selected_name(name)?;
+            let library_name = match library_name {
+                Some(library_name) if library_name.name_utf8() != "work" => library_name,
+                _ => region.library_name,
+            };
+            Some((library_name.clone(), name))
+        };
+        match entity_aspect {
+            Some(EntityAspect::Entity(name, architecture)) => {
+                let (library_name, name) = resolve(&name.item)?;
+                let entity = self.get_design_entity(&library_name, name)?;
+                Some((
+                    entity.into(),
+                    architecture.as_ref().map(|arch| arch.item.item.clone()),
+                ))
+            }
+            Some(EntityAspect::Configuration(name)) => {
+                let (library_name, name) = resolve(&name.item)?;
+                self.configured_design(&library_name, name)
+            }
+            Some(EntityAspect::Open) => None,
+            None => {
+                let entity = self.get_design_entity(component.library_name()?, component_name)?;
+                Some((entity.into(), None))
+            }
+        }
+    }
+
+    /// The architecture of `entity` named `name` or the last architecture in source order
+    pub(super) fn bound_architecture<'a>(
+        &'a self,
+        entity: EntRef<'a>,
+        name: Option<&Symbol>,
+    ) -> Option<&'a super::LockedUnit> {
+        let Designator::Identifier(entity_name) = entity.designator() else {
+            return None;
+        };
+        let library = self.get_lib(entity.library_name()?)?;
+        let mut architectures = library
+            .secondary_units(entity_name)
+            .filter(|unit| matches!(unit.kind(), AnyKind::Secondary(SecondaryKind::Architecture)));
+        match name {
+            Some(name) => architectures.find(|unit| unit.unit_id().secondary_name() == Some(name)),
+            None => architectures.max_by(|a, b| {
+                let (a, b) = (a.pos(), b.pos());
+                (a.source.file_name(), a.range.start).cmp(&(b.source.file_name(), b.range.start))
+            }),
+        }
+    }
+
+    /// Elaborate an instance of `entity` whose generics are associated
+    /// with `actuals`, evaluated with the generics of the enclosing instance.
+    fn elaborate_entity(
+        &self,
+        entity: EntRef<'_>,
+        architecture: Option<&Symbol>,
+        actuals: &[(Symbol, Option<&Expression>)],
+        stack: &mut Vec<EntityId>,
+        outer: &GenericValues,
+    ) -> (InstanceInfo, Vec<HierarchyNode>) {
+        let mut values = GenericValues::default();
+        let mut generics = Vec::new();
+        for generic in self.generics_of(entity) {
+            let value = match actuals.iter().find(|(name, _)| name == &generic.name) {
+                Some((_, actual)) => actual
+                    .as_ref()
+                    .and_then(|expr| self.evaluate_with(expr, outer, 0)),
+                None => generic
+                    .default
+                    .as_ref()
+                    .and_then(|expr| self.evaluate_with(expr, &values, 0)),
+            };
+            generics.push((
+                generic.name.name_utf8(),
+                value.as_ref().map(|value| value.to_string()),
+            ));
+            if let (Some(id), Some(value)) = (generic.id, value) {
+                values.insert(id, value);
+            }
+        }
+
+        let arch_unit = self.bound_architecture(entity, architecture);
+        let mut info = InstanceInfo {
+            library: entity
+                .library_name()
+                .map(|name| name.name_utf8())
+                .unwrap_or_default(),
+            entity: entity.designator().to_string(),
+            architecture: None,
+            generics,
+        };
+        let Some(arch_unit) = arch_unit else {
+            return (info, Vec::new());
+        };
+        info.architecture = arch_unit.unit_id().secondary_name().map(|n| n.name_utf8());
+
+        if stack.contains(&entity.id()) || stack.len() > MAX_DEPTH * 4 {
+            return (info, Vec::new());
+        }
+        stack.push(entity.id());
+        let data = self.get_analysis(arch_unit);
+        let children = match data.deref() {
+            AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) => {
+                let region = Region {
+                    ctx: &arch_unit.tokens,
+                    library_name: arch_unit.unit_id().library_name(),
+                    specs: configuration_specifications(&arch.decl),
+                };
+                self.elaborate_statements(&region, &arch.statements, stack, &values)
+            }
+            _ => Vec::new(),
+        };
+        stack.pop();
+        (info, children)
+    }
+
+    fn elaborate_statements(
+        &self,
+        region: &Region<'_>,
+        statements: &[LabeledConcurrentStatement],
+        stack: &mut Vec<EntityId>,
+        generics: &GenericValues,
+    ) -> Vec<HierarchyNode> {
+        let ctx = region.ctx;
+        let mut nodes = Vec::new();
+        for statement in statements {
+            let Some(label) = statement.label.tree.as_ref() else {
+                continue;
+            };
+            let node = |kind, children| HierarchyNode {
+                label: Some(label.item.name_utf8()),
+                kind,
+                pos: label.pos(ctx).clone(),
+                children,
+            };
+            match &statement.statement.item {
+                ConcurrentStatement::Instance(instance) => {
+                    if let Some((info, children)) =
+                        self.elaborate_instance(region, &label.item, instance, stack, generics)
+                    {
+                        nodes.push(node(HierarchyNodeKind::Instance(info), children));
+                    }
+                }
+                ConcurrentStatement::Block(block) => {
+                    let children = self.elaborate_statements(
+                        &region.nested(&block.decl),
+                        &block.statements,
+                        stack,
+                        generics,
+                    );
+                    nodes.push(node(HierarchyNodeKind::Block, children));
+                }
+                ConcurrentStatement::ForGenerate(_)
+                | ConcurrentStatement::IfGenerate(_)
+                | ConcurrentStatement::CaseGenerate(_) => {
+                    let mut children = Vec::new();
+                    for body in self.selected_generate_bodies(&statement.statement.item, generics) {
+                        children.extend(self.elaborate_statements(
+                            region,
+                            &body.statements,
+                            stack,
+                            generics,
+                        ));
+                    }
+                    nodes.push(node(HierarchyNodeKind::Generate, children));
+                }
+                ConcurrentStatement::ProcedureCall(_)
+                | ConcurrentStatement::Process(_)
+                | ConcurrentStatement::Assert(_)
+                | ConcurrentStatement::Assignment(_) => {}
+            }
+        }
+        nodes
+    }
+
+    /// The bodies of a generate statement that are elaborated.
+    /// Only the branch of an if or case generate statement that is selected by its condition
+    /// or expression is returned, or all branches when the selection is not static.
+    fn selected_generate_bodies<'a>(
+        &self,
+        statement: &'a ConcurrentStatement,
+        generics: &GenericValues,
+    ) -> Vec<&'a GenerateBody> {
+        let selected = match statement {
+            ConcurrentStatement::IfGenerate(generate) => {
+                self.selected_if_generate_body(generate, generics)
+            }
+            ConcurrentStatement::CaseGenerate(generate) => {
+                self.selected_case_generate_body(generate, generics)
+            }
+            _ => None,
+        };
+        match selected {
+            Some(body) => body.into_iter().collect(),
+            None => generate_bodies(statement).unwrap_or_default(),
+        }
+    }
+
+    /// The branch of an if generate statement that is selected by its conditions,
+    /// `Some(None)` when no branch is selected and `None` when a condition is not static.
+    fn selected_if_generate_body<'a>(
+        &self,
+        generate: &'a IfGenerateStatement,
+        generics: &GenericValues,
+    ) -> Option<Option<&'a GenerateBody>> {
+        let Conditionals {
+            conditionals,
+            else_item,
+        } = &generate.conds;
+        for conditional in conditionals.iter() {
+            match self.evaluate_with(&conditional.condition.item, generics, 0)? {
+                StaticValue::Boolean(true) => return Some(Some(&conditional.item)),
+                StaticValue::Boolean(false) => {}
+                _ => return None,
+            }
+        }
+        Some(else_item.as_ref().map(|(body, _)| body))
+    }
+
+    /// The alternative of a case generate statement that is selected by its expression,
+    /// `Some(None)` when no alternative is selected
+    /// and `None` when the expression or a choice is not static.
+    fn selected_case_generate_body<'a>(
+        &self,
+        generate: &'a CaseGenerateStatement,
+        generics: &GenericValues,
+    ) -> Option<Option<&'a GenerateBody>> {
+        let value = self.evaluate_with(&generate.sels.expression.item, generics, 0)?;
+        for alternative in generate.sels.alternatives.iter() {
+            for choice in alternative.choices.iter() {
+                let is_selected = match &choice.item {
+                    Choice::Expression(expr) => self.evaluate_with(expr, generics, 0)? == value,
+                    Choice::DiscreteRange(range) => {
+                        match (&value, self.evaluate_static_discrete_range(range, 0)?) {
+                            (StaticValue::Integer(value), range) => range.contains(*value),
+                            _ => return None,
+                        }
+                    }
+                    Choice::Others => true,
+                };
+                if is_selected {
+                    return Some(Some(&alternative.item));
+                }
+            }
+        }
+        Some(None)
+    }
+
+    fn elaborate_instance(
+        &self,
+        region: &Region<'_>,
+        label: &Symbol,
+        instance: &InstantiationStatement,
+        stack: &mut Vec<EntityId>,
+        generics: &GenericValues,
+    ) -> Option<(InstanceInfo, Vec<HierarchyNode>)> {
+        let unit = self.get_ent(instance.entity_reference()?);
+        let (entity, architecture) = self.instance_binding(region, label, instance)?;
+
+        // Positional actuals are associated with the generics of the component or entity
+        let formals = self.generics_of(unit);
+        let mut actuals = Vec::new();
+        if let Some(generic_map) = &instance.generic_map {
+            for (position, element) in generic_map.list.items.iter().enumerate() {
+                let name = match &element.formal {
+                    Some(formal) => match &formal.item {
+                        Name::Designator(designator) => match &designator.item {
+                            Designator::Identifier(name) => Some(name.clone()),
+                            _ => None,
+                        },
+                        _ => None,
+                    },
+                    None => formals.get(position).map(|generic| generic.name.clone()),
+                };
+                let Some(name) = name else {
+                    continue;
+                };
+                let actual = match &element.actual.item {
+                    ActualPart::Expression(expr) => Some(expr),
+                    ActualPart::Open => None,
+                };
+                actuals.push((name, actual));
+            }
+        }
+        Some(self.elaborate_entity(entity, architecture.as_ref(), &actuals, stack, generics))
+    }
+
+    /// The entity and architecture that the instance `label` in `region` is bound to
+    pub(super) fn instance_binding(
+        &self,
+        region: &Region<'_>,
+        label: &Symbol,
+        instance: &InstantiationStatement,
+    ) -> Option<(EntRef<'_>, Option<Symbol>)> {
+        let unit = self.get_ent(instance.entity_reference()?);
+        match &instance.unit {
+            InstantiatedUnit::Entity(_, architecture) => Some((
+                unit,
+                architecture.as_ref().map(|arch| arch.item.item.clone()),
+            )),
+            InstantiatedUnit::Component(_) => self.bound_design(unit, label, region),
+            InstantiatedUnit::Configuration(_) => {
+                let Designator::Identifier(name) = unit.designator() else {
+                    return None;
+                };
+                self.configured_design(unit.library_name()?, name)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+
+    fn describe(node: &HierarchyNode, depth: usize, lines: &mut Vec<String>) {
+        let label = node.label.as_deref().unwrap_or("<top>");
+        let kind = match &node.kind {
+            HierarchyNodeKind::Instance(info) => {
+                let generics: Vec<String> = info
+                    .generics
+                    .iter()
+                    .map(|(name, value)| format!("{name}={}", value.as_deref().unwrap_or("?")))
+                    .collect();
+                format!(
+                    "{}.{}({}) [{}]",
+                    info.library,
+                    info.entity,
+                    info.architecture.as_deref().unwrap_or("-"),
+                    generics.join(", ")
+                )
+            }
+            HierarchyNodeKind::Block => "block".to_owned(),
+            HierarchyNodeKind::Generate => "generate".to_owned(),
+        };
+        lines.push(format!("{}{label}: {kind}", "  ".repeat(depth)));
+        for child in node.children.iter() {
+            describe(child, depth + 1, lines);
+        }
+    }
+
+    #[test]
+    fn elaborates_instances_with_generic_values() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity leaf is
+  generic (width : natural := 1; depth : natural := width * 2);
+end entity;
+
+architecture a of leaf is
+begin
+end architecture;
+
+architecture b of leaf is
+begin
+end architecture;
+
+entity mid is
+  generic (n : natural);
+end entity;
+
+architecture rtl of mid is
+  component leaf is
+    generic (width : natural := 1; depth : natural := width * 2);
+  end component;
+begin
+  gen: for i in 0 to n - 1 generate
+    u_leaf: leaf generic map (width => n + 1);
+  end generate;
+  blk: block is
+  begin
+    u_explicit: entity work.leaf(a) generic map (4, 5);
+  end block;
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  signal s : bit;
+begin
+  u_mid: entity work.mid generic map (n => 3);
+  u_self: entity work.top;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        crate::syntax::test::check_no_diagnostics(&diagnostics);
+
+        let symbol = |name: &str| root.symbol_utf8(name);
+        let hierarchy = root
+            .design_hierarchy(&symbol("libname"), &symbol("top"), None)
+            .unwrap();
+        let mut lines = Vec::new();
+        describe(&hierarchy, 0, &mut lines);
+        assert_eq!(
+            lines,
+            vec![
+                "<top>: libname.top(rtl) []",
+                "  u_mid: libname.mid(rtl) [n=3]",
+                "    gen: generate",
+                "      u_leaf: libname.leaf(b) [width=4, depth=8]",
+                "    blk: block",
+                "      u_explicit: libname.leaf(a) [width=4, depth=5]",
+                "  u_self: libname.top(rtl) []",
+            ]
+        );
+        assert_eq!(hierarchy.pos, code.s1("top").pos());
+        assert_eq!(hierarchy.children[0].pos, code.s1("u_mid").pos());
+    }
+
+    #[test]
+    fn formats_hierarchy_as_tree_and_json() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libname",
+            "
+entity leaf is
+  generic (width : natural; depth : natural);
+end entity;
+
+architecture rtl of leaf is
+begin
+end architecture;
+
+entity top is
+  generic (n : natural);
+end entity;
+
+architecture rtl of top is
+begin
+  gen: for i in 0 to 1 generate
+    u_leaf: entity work.leaf generic map (width => 8, depth => n);
+  end generate;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        crate::syntax::test::check_no_diagnostics(&diagnostics);
+
+        let symbol = |name: &str| root.symbol_utf8(name);
+        let hierarchy = root
+            .design_hierarchy(&symbol("libname"), &symbol("top"), None)
+            .unwrap();
+        assert_eq!(
+            hierarchy.format_tree(),
+            "\
+libname.top(rtl) generic map (n => ?)
+  gen: generate
+    u_leaf: libname.leaf(rtl) generic map (width => 8, depth => ?)"
+        );
+
+        let json = hierarchy.children[0].children[0].to_json().to_string();
+        assert!(json.starts_with(
+            "{\"label\":\"u_leaf\",\"kind\":\"instance\",\"library\":\"libname\",\"entity\":\"leaf\",\
+             \"architecture\":\"rtl\",\"generics\":[{\"name\":\"width\",\"value\":\"8\"},{\"name\":\"depth\",\"value\":null}],"
+        ));
+        assert!(json.ends_with(",\"line\":16,\"children\":[]}"));
+    }
+
+    #[test]
+    fn binds_components_by_configuration_specifications() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libname",
+            "
+entity leaf is
+end entity;
+
+architecture a of leaf is
+begin
+end architecture;
+
+architecture b of leaf is
+begin
+end architecture;
+
+entity other is
+end entity;
+
+architecture rtl of other is
+begin
+end architecture;
+
+configuration leaf_a of leaf is
+  for a
+  end for;
+end configuration;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component leaf is
+  end component;
+  for u_other : leaf use entity work.other;
+  for u_open : leaf use open;
+  for all : leaf use entity work.leaf(a);
+begin
+  u_other: component leaf;
+  u_open: component leaf;
+  u_all: component leaf;
+  blk: block is
+    for u_config : leaf use configuration work.leaf_a;
+    for others : leaf use entity work.leaf(b);
+  begin
+    u_config: component leaf;
+    u_others: component leaf;
+  end block;
+  u_instance: configuration work.leaf_a;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        crate::syntax::test::check_no_diagnostics(&diagnostics);
+
+        let symbol = |name: &str| root.symbol_utf8(name);
+        let hierarchy = root
+            .design_hierarchy(&symbol("libname"), &symbol("top"), None)
+            .unwrap();
+        let mut lines = Vec::new();
+        describe(&hierarchy, 0, &mut lines);
+        assert_eq!(
+            lines,
+            vec![
+                "<top>: libname.top(rtl) []",
+                "  u_other: libname.other(rtl) []",
+                "  u_all: libname.leaf(a) []",
+                "  blk: block",
+                "    u_config: libname.leaf(a) []",
+                "    u_others: libname.leaf(b) []",
+                "  u_instance: libname.leaf(a) []",
+            ]
+        );
+    }
+
+    #[test]
+    fn elaborates_only_the_selected_branch_of_generate_statements() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libname",
+            "
+entity leaf is
+end entity;
+
+architecture rtl of leaf is
+begin
+end architecture;
+
+entity mid is
+  generic (mode : natural; enable : boolean);
+end entity;
+
+architecture rtl of mid is
+begin
+  gen_if: if enable generate
+    u_enabled: entity work.leaf;
+  else generate
+    u_disabled: entity work.leaf;
+  end generate;
+  gen_case: case mode generate
+    when 0 => u_zero: entity work.leaf;
+    when 1 to 3 => u_small: entity work.leaf;
+    when others => u_large: entity work.leaf;
+  end generate;
+  gen_dynamic: if integer'high > mode generate
+    u_then: entity work.leaf;
+  else generate
+    u_else: entity work.leaf;
+  end generate;
+end architecture;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+begin
+  u_mid: entity work.mid generic map (mode => 2, enable => false);
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        crate::syntax::test::check_no_diagnostics(&diagnostics);
+
+        let symbol = |name: &str| root.symbol_utf8(name);
+        let hierarchy = root
+            .design_hierarchy(&symbol("libname"), &symbol("top"), None)
+            .unwrap();
+        let mut lines = Vec::new();
+        describe(&hierarchy, 0, &mut lines);
+        assert_eq!(
+            lines,
+            vec![
+                "<top>: libname.top(rtl) []",
+                "  u_mid: libname.mid(rtl) [mode=2, enable=false]",
+                "    gen_if: generate",
+                "      u_disabled: libname.leaf(rtl) []",
+                "    gen_case: generate",
+                "      u_small: libname.leaf(rtl) []",
+                "    gen_dynamic: generate",
+                "      u_then: libname.leaf(rtl) []",
+                "      u_else: libname.leaf(rtl) []",
+            ]
+        );
+    }
+}
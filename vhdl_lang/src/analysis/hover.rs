@@ -0,0 +1,418 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::constant_folding::{StaticValue, MAX_DEPTH};
+use super::root::DesignRoot;
+use crate::ast::search::{DeclarationItem, SearchResult, SearchState, Searcher};
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::data::{Position, Source, SrcPos};
+use crate::named_entity::*;
+use crate::TokenAccess;
+
+impl DesignRoot {
+    /// Formats the subtype of an object, element or subtype declaration
+    /// with all statically known bounds evaluated, together with the chain of parent types.
+    /// Generics are evaluated using their default value.
+    /// Returns `None` if there is nothing to add to the declaration itself.
+    pub(crate) fn format_resolved_subtype(&self, ent: EntRef<'_>) -> Option<String> {
+        let chain_start = match ent.kind() {
+            AnyEntKind::Object(object) => object.subtype.type_mark(),
+            AnyEntKind::ElementDeclaration(subtype) => subtype.type_mark(),
+            AnyEntKind::Type(Type::Subtype(_)) => TypeEnt::from_any(ent)?,
+            _ => return None,
+        };
+
+        let mut lines = Vec::new();
+        let resolved = self.with_subtype_indication(ent, |subtype_indication| {
+            self.resolve_subtype_indication(subtype_indication, 0)
+        });
+        if let Some(resolved) = resolved.flatten() {
+            lines.push(format!("-- Resolved subtype: {resolved}"));
+        }
+
+        let chain = type_chain(chain_start);
+        if chain.len() > 1 {
+            lines.push(format!("-- Type chain: {}", chain.join(" -> ")));
+        }
+
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Formats the statically known value of a constant or the default value of a generic.
+    /// Non-negative integers are also shown in hexadecimal and binary notation.
+    pub(crate) fn format_constant_value(&self, ent: EntRef<'_>) -> Option<String> {
+        let AnyEntKind::Object(object) = ent.kind() else {
+            return None;
+        };
+        let label = if matches!(object.iface, Some(ObjectInterface::Generic)) {
+            "Default value"
+        } else {
+            "Value"
+        };
+        match self.evaluate_constant(ent, 0)? {
+            StaticValue::Integer(value) if value >= 0 => {
+                Some(format!("-- {label}: {value} (16#{value:X}#, 2#{value:b}#)"))
+            }
+            value => Some(format!("-- {label}: {value}")),
+        }
+    }
+
+    /// Formats the generics and ports of an entity or component as Markdown tables.
+    pub fn format_interface_table(&self, ent: EntRef<'_>) -> Option<String> {
+        if !matches!(
+            ent.kind(),
+            AnyEntKind::Design(Design::Entity(..)) | AnyEntKind::Component(_)
+        ) {
+            return None;
+        }
+        self.with_declaration(ent, |decl| {
+            let (generics, ports) = match decl {
+                DeclarationItem::Entity(entity) => (&entity.generic_clause, &entity.port_clause),
+                DeclarationItem::Component(component) => {
+                    (&component.generic_list, &component.port_list)
+                }
+                _ => return None,
+            };
+            let mut tables = Vec::new();
+            if let Some(generics) = generics {
+                tables.push(markdown_table(
+                    &["Generic", "Type", "Default"],
+                    generics.items.iter().flat_map(generic_rows).collect(),
+                ));
+            }
+            if let Some(ports) = ports {
+                tables.push(markdown_table(
+                    &["Port", "Mode", "Type", "Default"],
+                    ports.items.iter().flat_map(port_rows).collect(),
+                ));
+            }
+            (!tables.is_empty()).then(|| tables.join("\n\n"))
+        })
+        .flatten()
+    }
+
+    /// Calls `f` with the subtype indication of the declaration of `ent`.
+    /// Formats the value of the physical literal at the cursor in the primary unit of its type
+    /// and in the largest unit of the type that keeps the value at or above one.
+    pub fn format_physical_literal(&self, source: &Source, cursor: Position) -> Option<String> {
+        let mut searcher = PhysicalLiteralAt {
+            cursor,
+            result: None,
+        };
+        let _ = self.search_source(source, &mut searcher);
+        let literal = searcher.result?;
+        let AnyEntKind::PhysicalLiteral(typ) =
+            self.get_ent(literal.unit.reference.get()?).actual_kind()
+        else {
+            return None;
+        };
+        let value = self.evaluate_physical_literal(&literal, 0)?;
+
+        let mut primary = None;
+        let mut convenient: Option<(i64, EntRef<'_>)> = None;
+        for unit in typ.implicits.iter() {
+            if !matches!(unit.kind(), AnyEntKind::PhysicalLiteral(_)) {
+                continue;
+            }
+            let Some(factor) = self.physical_unit_factor(unit, 0) else {
+                continue;
+            };
+            if factor == 1 {
+                primary = Some(*unit);
+            }
+            if factor.unsigned_abs() <= value.unsigned_abs()
+                && convenient.is_none_or(|(largest, _)| factor > largest)
+            {
+                convenient = Some((factor, *unit));
+            }
+        }
+
+        let mut result = format!("-- Value: {value} {}", primary?.designator());
+        if let Some((factor, unit)) = convenient.filter(|(factor, _)| *factor > 1) {
+            if value % factor == 0 {
+                result.push_str(&format!(" ({} {})", value / factor, unit.designator()));
+            } else {
+                result.push_str(&format!(
+                    " ({} {})",
+                    value as f64 / factor as f64,
+                    unit.designator()
+                ));
+            }
+        }
+        Some(result)
+    }
+
+    pub(super) fn with_subtype_indication<R>(
+        &self,
+        ent: EntRef<'_>,
+        mut f: impl FnMut(&SubtypeIndication) -> R,
+    ) -> Option<R> {
+        self.with_declaration(ent, |decl| {
+            let subtype_indication = match decl {
+                DeclarationItem::Object(decl) => &decl.subtype_indication,
+                DeclarationItem::ElementDeclaration(decl) => &decl.subtype,
+                DeclarationItem::InterfaceObject(decl) => match &decl.mode {
+                    ModeIndication::Simple(mode) => &mode.subtype_indication,
+                    ModeIndication::View(_) => return None,
+                },
+                DeclarationItem::Type(TypeDeclaration {
+                    def: TypeDefinition::Subtype(subtype_indication),
+                    ..
+                }) => subtype_indication,
+                _ => return None,
+            };
+            Some(f(subtype_indication))
+        })
+        .flatten()
+    }
+
+    /// Formats a subtype indication with all statically known bounds evaluated.
+    /// Subtype indications without a constraint are resolved through the declaration
+    /// of a constrained subtype they refer to.
+    /// Returns `None` if no bound could be evaluated.
+    fn resolve_subtype_indication(
+        &self,
+        subtype_indication: &SubtypeIndication,
+        depth: usize,
+    ) -> Option<String> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let Some(constraint) = &subtype_indication.constraint else {
+            let type_mark = subtype_indication
+                .type_mark
+                .item
+                .get_suffix_reference()
+                .map(|id| self.get_ent(id))?;
+            if !TypeEnt::from_any(type_mark)?.is_constrained_subtype() {
+                return None;
+            }
+            return self
+                .with_subtype_indication(type_mark, |subtype_indication| {
+                    self.resolve_subtype_indication(subtype_indication, depth + 1)
+                })
+                .flatten();
+        };
+
+        let mut resolved = String::new();
+        if let Some(resolution) = &subtype_indication.resolution {
+            resolved.push_str(&format!("{resolution} "));
+        }
+        resolved.push_str(&subtype_indication.type_mark.to_string());
+        resolved.push_str(&self.resolve_constraint(&constraint.item, depth));
+        // Only of interest when some bound was actually evaluated
+        (resolved != subtype_indication.to_string()).then_some(resolved)
+    }
+
+    fn resolve_constraint(&self, constraint: &SubtypeConstraint, depth: usize) -> String {
+        match constraint {
+            SubtypeConstraint::Range(range) => {
+                format!(" range {}", self.resolve_range(range, depth))
+            }
+            SubtypeConstraint::Array(ranges, element_constraint) => {
+                let ranges: Vec<String> = ranges
+                    .iter()
+                    .map(|drange| match &drange.item {
+                        DiscreteRange::Discrete(type_mark, Some(range)) => {
+                            format!("{type_mark} range {}", self.resolve_range(range, depth))
+                        }
+                        DiscreteRange::Discrete(type_mark, None) => type_mark.to_string(),
+                        DiscreteRange::Range(range) => self.resolve_range(range, depth),
+                    })
+                    .collect();
+                let ranges = if ranges.is_empty() {
+                    "open".to_owned()
+                } else {
+                    ranges.join(", ")
+                };
+                let element_constraint = element_constraint
+                    .as_ref()
+                    .map(|constraint| self.resolve_constraint(&constraint.item, depth))
+                    .unwrap_or_default();
+                format!("({ranges}){element_constraint}")
+            }
+            SubtypeConstraint::Record(_) => constraint.to_string(),
+        }
+    }
+
+    fn resolve_range(&self, range: &Range, depth: usize) -> String {
+        match range {
+            Range::Range(constraint) => {
+                let resolve = |expr: &WithTokenSpan<Expression>| {
+                    self.evaluate_integer(&expr.item, depth)
+                        .map(|value| value.to_string())
+                        .unwrap_or_else(|| expr.to_string())
+                };
+                format!(
+                    "{} {} {}",
+                    resolve(&constraint.left_expr),
+                    constraint.direction,
+                    resolve(&constraint.right_expr)
+                )
+            }
+            Range::Attribute(_) => range.to_string(),
+        }
+    }
+}
+
+/// The designators of `typ` and all types it is a subtype or alias of.
+/// Finds the physical literal that contains the cursor.
+struct PhysicalLiteralAt {
+    cursor: Position,
+    result: Option<PhysicalLiteral>,
+}
+
+impl Searcher for PhysicalLiteralAt {
+    fn search_physical_literal(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        literal: &PhysicalLiteral,
+    ) -> SearchState {
+        if pos.contains(self.cursor) {
+            self.result = Some(literal.clone());
+            SearchState::Finished(SearchResult::Found)
+        } else {
+            SearchState::NotFinished
+        }
+    }
+}
+
+fn type_chain(typ: TypeEnt<'_>) -> Vec<String> {
+    let mut chain = vec![typ.designator().to_string()];
+    let mut typ = typ;
+    loop {
+        typ = match typ.kind() {
+            Type::Subtype(subtype) => subtype.type_mark(),
+            Type::Alias(aliased) => *aliased,
+            _ => break,
+        };
+        if chain.len() > MAX_DEPTH {
+            break;
+        }
+        chain.push(typ.designator().to_string());
+    }
+    chain
+}
+
+fn generic_rows(generic: &InterfaceDeclaration) -> Vec<Vec<String>> {
+    match generic {
+        InterfaceDeclaration::Object(object) => {
+            let (typ, default) = match &object.mode {
+                ModeIndication::Simple(mode) => (
+                    mode.subtype_indication.to_string(),
+                    mode.expression
+                        .as_ref()
+                        .map(|expr| expr.to_string())
+                        .unwrap_or_default(),
+                ),
+                ModeIndication::View(view) => (view.to_string(), String::new()),
+            };
+            object
+                .idents
+                .iter()
+                .map(|ident| vec![ident.tree.to_string(), typ.clone(), default.clone()])
+                .collect()
+        }
+        InterfaceDeclaration::File(file) => file
+            .idents
+            .iter()
+            .map(|ident| {
+                vec![
+                    ident.tree.to_string(),
+                    format!("file {}", file.subtype_indication),
+                    String::new(),
+                ]
+            })
+            .collect(),
+        InterfaceDeclaration::Type(ident) => {
+            vec![vec![
+                ident.tree.to_string(),
+                "type".to_owned(),
+                String::new(),
+            ]]
+        }
+        InterfaceDeclaration::Subprogram(subprogram) => {
+            let (kind, designator) = match &subprogram.specification {
+                SubprogramSpecification::Procedure(procedure) => {
+                    ("procedure", procedure.designator.tree.to_string())
+                }
+                SubprogramSpecification::Function(function) => {
+                    ("function", function.designator.tree.to_string())
+                }
+            };
+            let default = match &subprogram.default {
+                Some(SubprogramDefault::Name(name)) => name.to_string(),
+                Some(SubprogramDefault::Box) => "<>".to_owned(),
+                None => String::new(),
+            };
+            vec![vec![designator, kind.to_owned(), default]]
+        }
+        InterfaceDeclaration::Package(package) => vec![vec![
+            package.ident.tree.to_string(),
+            format!("package {}", package.package_name),
+            String::new(),
+        ]],
+    }
+}
+
+fn port_rows(port: &InterfaceDeclaration) -> Vec<Vec<String>> {
+    let InterfaceDeclaration::Object(object) = port else {
+        return Vec::new();
+    };
+    let (mode, typ, default) = match &object.mode {
+        ModeIndication::Simple(mode) => (
+            mode.mode
+                .as_ref()
+                .map(|mode| mode.item)
+                .unwrap_or_default()
+                .to_string(),
+            mode.subtype_indication.to_string(),
+            mode.expression
+                .as_ref()
+                .map(|expr| expr.to_string())
+                .unwrap_or_default(),
+        ),
+        ModeIndication::View(view) => (
+            "view".to_owned(),
+            view.to_string().trim_start_matches("view ").to_owned(),
+            String::new(),
+        ),
+    };
+    object
+        .idents
+        .iter()
+        .map(|ident| {
+            vec![
+                ident.tree.to_string(),
+                mode.clone(),
+                typ.clone(),
+                default.clone(),
+            ]
+        })
+        .collect()
+}
+
+fn markdown_table(header: &[&str], rows: Vec<Vec<String>>) -> String {
+    let escape = |cell: &str| cell.replace('|', "\\|");
+    let mut table = format!("| {} |\n", header.join(" | "));
+    table.push_str(&format!("|{}\n", "---|".repeat(header.len())));
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| {
+                if cell.is_empty() {
+                    String::new()
+                } else {
+                    format!("`{}`", escape(cell))
+                }
+            })
+            .collect();
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    table.trim_end().to_owned()
+}
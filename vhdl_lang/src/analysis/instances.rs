@@ -0,0 +1,496 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::concurrent::generate_bodies;
+use super::root::DesignRoot;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+use crate::TokenAccess;
+use fnv::FnvHashMap;
+use std::ops::Deref;
+
+/// An instance of an entity within the elaborated design hierarchy.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InstancePath {
+    /// The name of the top-level entity followed by the labels of all block, generate
+    /// and instantiation statements that lead to the instance
+    pub path: Vec<String>,
+    /// The label of the instantiation statement
+    /// or `None` if the entity is itself a top-level entity
+    pub pos: Option<SrcPos>,
+}
+
+impl std::fmt::Display for InstancePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.join("."))
+    }
+}
+
+/// An instantiation of an entity within an architecture of another entity.
+struct Instantiation {
+    parent: EntityId,
+    /// The labels from the architecture to the instantiation statement
+    labels: Vec<String>,
+    pos: SrcPos,
+}
+
+impl DesignRoot {
+    /// Find all paths through which `ent` is instantiated in the design hierarchy.
+    /// Entities that are not instantiated anywhere are considered top-level entities.
+    /// `ent` may be an entity, an architecture of an entity or a component.
+    pub fn find_instances(&self, ent: EntRef<'_>) -> Vec<InstancePath> {
+        let Some(entity) = self.instantiated_entity(ent) else {
+            return Vec::new();
+        };
+        let instantiations = self.instantiations();
+        let mut paths = Vec::new();
+        let mut stack = vec![entity.id];
+        instance_paths(self, &instantiations, &mut stack, &[], None, &mut paths);
+        paths.sort_by(|a, b| a.path.cmp(&b.path));
+        paths
+    }
+
+    /// The entity that is instantiated when instantiating `ent`.
+    /// Components are bound to the entity with the same name in the same library.
+    fn instantiated_entity<'a>(&'a self, ent: EntRef<'a>) -> Option<EntRef<'a>> {
+        match ent.kind() {
+            AnyEntKind::Design(Design::Entity(..)) => Some(ent),
+            AnyEntKind::Design(Design::Architecture(.., entity)) => Some((*entity).into()),
+            AnyEntKind::Component(_) => {
+                let Designator::Identifier(name) = ent.designator() else {
+                    return None;
+                };
+                let entity = self.get_design_entity(ent.library_name()?, name)?;
+                Some(entity.into())
+            }
+            _ => None,
+        }
+    }
+
+    /// The entities that are bound to `component` by configuration specifications
+    /// within architectures or by component configurations within configuration declarations.
+    /// Components are matched by name since bindings are not analyzed.
+    pub(super) fn configured_entities<'a>(&'a self, component: EntRef<'a>) -> Vec<EntRef<'a>> {
+        let Designator::Identifier(component_name) = component.designator() else {
+            return Vec::new();
+        };
+        let work_sym = self.symbol_utf8("work");
+        let mut entities = Vec::new();
+        for library in self.libraries() {
+            let binding = Binding {
+                root: self,
+                library_name: library.name(),
+                work_sym: &work_sym,
+                component_name,
+            };
+            for unit in library.units() {
+                let data = self.get_analysis(unit);
+                match data.deref() {
+                    AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) => {
+                        for decl in arch.decl.iter() {
+                            if let Declaration::Configuration(config) = &decl.item {
+                                binding.add_entity(
+                                    &config.spec,
+                                    Some(&config.bind_ind),
+                                    &mut entities,
+                                );
+                            }
+                        }
+                    }
+                    AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(config)) => {
+                        binding.add_entities_of_block(&config.block_config, &mut entities);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        entities
+    }
+
+    /// The names in binding indications and block configurations that denote `design`,
+    /// which is an entity or an architecture.
+    /// For an entity, this includes the names of components with the same name
+    /// in component specifications.
+    /// These names are not analyzed and are therefore not found as references.
+    pub(crate) fn find_configuration_references(&self, design: EntRef<'_>) -> Vec<SrcPos> {
+        let Designator::Identifier(name) = design.designator() else {
+            return Vec::new();
+        };
+        let (entity, architecture) = match design.kind() {
+            AnyEntKind::Design(Design::Entity(..)) => (design.id(), None),
+            AnyEntKind::Design(Design::Architecture(.., entity)) => (entity.id(), Some(name)),
+            _ => return Vec::new(),
+        };
+        let work_sym = self.symbol_utf8("work");
+        let mut positions = Vec::new();
+        for library in self.libraries() {
+            let references = DesignReferences {
+                root: self,
+                library_name: library.name(),
+                work_sym: &work_sym,
+                entity,
+                architecture,
+            };
+            for unit in library.units() {
+                let data = self.get_analysis(unit);
+                match data.deref() {
+                    AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) => {
+                        for decl in arch.decl.iter() {
+                            if let Declaration::Configuration(config) = &decl.item {
+                                references.add_component(
+                                    &unit.tokens,
+                                    &config.spec,
+                                    &mut positions,
+                                );
+                                references.add_binding(
+                                    &unit.tokens,
+                                    Some(&config.bind_ind),
+                                    &mut positions,
+                                );
+                            }
+                        }
+                    }
+                    AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(config)) => {
+                        let is_entity = references
+                            .resolve_entity(&config.entity_name.item)
+                            .is_some_and(|entity| entity.id() == references.entity);
+                        references.add_block(
+                            &unit.tokens,
+                            &config.block_config,
+                            is_entity,
+                            &mut positions,
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        positions
+    }
+
+    /// All instantiations of all architectures, grouped by the instantiated entity.
+    fn instantiations(&self) -> FnvHashMap<EntityId, Vec<Instantiation>> {
+        let mut instantiations: FnvHashMap<EntityId, Vec<Instantiation>> = FnvHashMap::default();
+        for library in self.libraries() {
+            for unit in library.units() {
+                if !matches!(unit.kind(), AnyKind::Secondary(SecondaryKind::Architecture)) {
+                    continue;
+                }
+                let data = self.get_analysis(unit);
+                let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) = data.deref()
+                else {
+                    continue;
+                };
+                let Some(arch_ent) = arch.ident.decl.get().map(|id| self.get_ent(id)) else {
+                    continue;
+                };
+                let AnyEntKind::Design(Design::Architecture(.., parent)) = arch_ent.kind() else {
+                    continue;
+                };
+                let mut labels = Vec::new();
+                self.collect_instantiations(
+                    &unit.tokens,
+                    parent.id(),
+                    &arch.statements,
+                    &mut labels,
+                    &mut instantiations,
+                );
+            }
+        }
+        instantiations
+    }
+
+    fn collect_instantiations(
+        &self,
+        ctx: &dyn TokenAccess,
+        parent: EntityId,
+        statements: &[LabeledConcurrentStatement],
+        labels: &mut Vec<String>,
+        instantiations: &mut FnvHashMap<EntityId, Vec<Instantiation>>,
+    ) {
+        for statement in statements {
+            let Some(label) = statement.label.tree.as_ref() else {
+                // Only block, generate and instantiation statements are relevant
+                // and these always have a label
+                continue;
+            };
+            labels.push(label.item.to_string());
+            match &statement.statement.item {
+                ConcurrentStatement::Instance(instance) => {
+                    let entity = instance
+                        .entity_reference()
+                        .map(|id| self.get_ent(id))
+                        .and_then(|ent| self.instantiated_entity(ent));
+                    if let Some(entity) = entity {
+                        instantiations
+                            .entry(entity.id())
+                            .or_default()
+                            .push(Instantiation {
+                                parent,
+                                labels: labels.clone(),
+                                pos: label.pos(ctx).clone(),
+                            });
+                    }
+                }
+                ConcurrentStatement::Block(block) => {
+                    self.collect_instantiations(
+                        ctx,
+                        parent,
+                        &block.statements,
+                        labels,
+                        instantiations,
+                    );
+                }
+                ConcurrentStatement::ForGenerate(_)
+                | ConcurrentStatement::IfGenerate(_)
+                | ConcurrentStatement::CaseGenerate(_) => {
+                    for body in generate_bodies(&statement.statement.item).unwrap_or_default() {
+                        self.collect_instantiations(
+                            ctx,
+                            parent,
+                            &body.statements,
+                            labels,
+                            instantiations,
+                        );
+                    }
+                }
+                ConcurrentStatement::ProcedureCall(_)
+                | ConcurrentStatement::Process(_)
+                | ConcurrentStatement::Assert(_)
+                | ConcurrentStatement::Assignment(_) => {}
+            }
+            labels.pop();
+        }
+    }
+}
+
+/// Resolves binding indications of a component within the units of a library.
+struct Binding<'a, 'r> {
+    root: &'a DesignRoot,
+    library_name: &'r Symbol,
+    work_sym: &'r Symbol,
+    component_name: &'r Symbol,
+}
+
+impl<'a> Binding<'a, '_> {
+    fn add_entities_of_block(&self, block: &BlockConfiguration, entities: &mut Vec<EntRef<'a>>) {
+        for item in block.items.iter() {
+            match item {
+                ConfigurationItem::Block(block) => self.add_entities_of_block(block, entities),
+                ConfigurationItem::Component(config) => {
+                    self.add_entity(&config.spec, config.bind_ind.as_ref(), entities);
+                    if let Some(block) = &config.block_config {
+                        self.add_entities_of_block(block, entities);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_entity(
+        &self,
+        spec: &ComponentSpecification,
+        bind_ind: Option<&BindingIndication>,
+        entities: &mut Vec<EntRef<'a>>,
+    ) {
+        let is_component = simple_or_selected_name(&spec.component_name.item)
+            .is_some_and(|(_, name)| name == self.component_name);
+        if !is_component {
+            return;
+        }
+        let Some(EntityAspect::Entity(name, _)) =
+            bind_ind.and_then(|ind| ind.entity_aspect.as_ref())
+        else {
+            return;
+        };
+        let Some((library_name, name)) = simple_or_selected_name(&name.item) else {
+            return;
+        };
+        let library_name = match library_name {
+            Some(library_name) if library_name != self.work_sym => library_name,
+            _ => self.library_name,
+        };
+        if let Some(entity) = self.root.get_design_entity(library_name, name) {
+            let entity: EntRef<'a> = entity.into();
+            if !entities.contains(&entity) {
+                entities.push(entity);
+            }
+        }
+    }
+}
+
+/// Finds the names of an entity or architecture within the units of a library.
+struct DesignReferences<'a, 'r> {
+    root: &'a DesignRoot,
+    library_name: &'r Symbol,
+    work_sym: &'r Symbol,
+    entity: EntityId,
+    /// The name of the architecture when looking for an architecture
+    architecture: Option<&'r Symbol>,
+}
+
+impl<'a> DesignReferences<'a, '_> {
+    fn resolve_entity(&self, name: &Name) -> Option<DesignEnt<'a>> {
+        let (library_name, name) = simple_or_selected_name(name)?;
+        let library_name = match library_name {
+            Some(library_name) if library_name != self.work_sym => library_name,
+            _ => self.library_name,
+        };
+        self.root.get_design_entity(library_name, name)
+    }
+
+    /// Add the architecture name of a block configuration, if `is_entity` the block
+    /// configures the entity, followed by the names within the block.
+    fn add_block(
+        &self,
+        ctx: &dyn TokenAccess,
+        block: &BlockConfiguration,
+        is_entity: bool,
+        positions: &mut Vec<SrcPos>,
+    ) {
+        if is_entity {
+            if let (Some(architecture), Name::Designator(designator)) =
+                (self.architecture, &block.block_spec.item)
+            {
+                if matches!(&designator.item, Designator::Identifier(name) if name == architecture)
+                {
+                    positions.push(block.block_spec.pos(ctx));
+                }
+            }
+        }
+        for item in block.items.iter() {
+            match item {
+                ConfigurationItem::Block(block) => self.add_block(ctx, block, false, positions),
+                ConfigurationItem::Component(config) => {
+                    self.add_component(ctx, &config.spec, positions);
+                    let is_entity = self.add_binding(ctx, config.bind_ind.as_ref(), positions);
+                    if let Some(block) = &config.block_config {
+                        self.add_block(ctx, block, is_entity, positions);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add the component name of a component specification
+    /// when it has the name of the entity.
+    fn add_component(
+        &self,
+        ctx: &dyn TokenAccess,
+        spec: &ComponentSpecification,
+        positions: &mut Vec<SrcPos>,
+    ) {
+        if self.architecture.is_some() {
+            return;
+        }
+        let entity_name = self.root.get_ent(self.entity).designator();
+        if simple_or_selected_name(&spec.component_name.item)
+            .is_some_and(|(_, name)| matches!(entity_name, Designator::Identifier(entity_name) if entity_name == name))
+        {
+            positions.push(ctx.get_pos(spec.component_name.span.end_token).clone());
+        }
+    }
+
+    /// Add the entity or architecture name of a binding indication.
+    /// Returns true if the binding indication binds the entity.
+    fn add_binding(
+        &self,
+        ctx: &dyn TokenAccess,
+        bind_ind: Option<&BindingIndication>,
+        positions: &mut Vec<SrcPos>,
+    ) -> bool {
+        let Some(EntityAspect::Entity(name, architecture)) =
+            bind_ind.and_then(|ind| ind.entity_aspect.as_ref())
+        else {
+            return false;
+        };
+        let is_entity = self
+            .resolve_entity(&name.item)
+            .is_some_and(|entity| entity.id() == self.entity);
+        if !is_entity {
+            return false;
+        }
+        match (self.architecture, architecture) {
+            (None, _) => positions.push(ctx.get_pos(name.span.end_token).clone()),
+            (Some(name), Some(architecture)) if &architecture.item.item == name => {
+                positions.push(architecture.item.pos(ctx).clone());
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+/// The optional prefix and the suffix of names such as `lib.name` or `name`.
+pub(super) fn simple_or_selected_name(name: &Name) -> Option<(Option<&Symbol>, &Symbol)> {
+    match name {
+        Name::Designator(designator) => match &designator.item {
+            Designator::Identifier(name) => Some((None, name)),
+            _ => None,
+        },
+        Name::Selected(prefix, suffix) => {
+            let (None, prefix) = simple_or_selected_name(&prefix.item)? else {
+                return None;
+            };
+            match &suffix.item.item {
+                Designator::Identifier(name) => Some((Some(prefix), name)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Collect the paths of the entity at the top of `stack`, where `suffix` are the labels
+/// that lead from that entity to the original instance.
+/// Entities that are already on the stack are skipped to handle recursive instantiation.
+fn instance_paths(
+    root: &DesignRoot,
+    instantiations: &FnvHashMap<EntityId, Vec<Instantiation>>,
+    stack: &mut Vec<EntityId>,
+    suffix: &[String],
+    pos: Option<&SrcPos>,
+    paths: &mut Vec<InstancePath>,
+) {
+    let entity = *stack.last().unwrap();
+    let parents = instantiations
+        .get(&entity)
+        .map(|parents| parents.as_slice())
+        .unwrap_or_default();
+
+    if parents.is_empty() {
+        let mut path = vec![root.get_ent(entity).designator().to_string()];
+        path.extend(suffix.iter().cloned());
+        paths.push(InstancePath {
+            path,
+            pos: pos.cloned(),
+        });
+        return;
+    }
+
+    for instantiation in parents {
+        if stack.contains(&instantiation.parent) {
+            continue;
+        }
+        let suffix: Vec<String> = instantiation
+            .labels
+            .iter()
+            .chain(suffix.iter())
+            .cloned()
+            .collect();
+        stack.push(instantiation.parent);
+        instance_paths(
+            root,
+            instantiations,
+            stack,
+            &suffix,
+            pos.or(Some(&instantiation.pos)),
+            paths,
+        );
+        stack.pop();
+    }
+}
@@ -60,8 +60,10 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         match literal {
             Literal::AbstractLiteral(abst) => match abst {
                 AbstractLiteral::Integer(_) => {
-                    if !self.can_be_target_type(self.universal_integer().into(), target_type.base())
+                    if self.can_be_target_type(self.universal_integer().into(), target_type.base())
                     {
+                        self.set_expression_type(span, target_type);
+                    } else {
                         diagnostics.add(
                             span.pos(self.ctx),
                             format!("integer literal does not match {}", target_type.describe()),
@@ -70,7 +72,9 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     }
                 }
                 AbstractLiteral::Real(_) => {
-                    if !self.can_be_target_type(self.universal_real().into(), target_type.base()) {
+                    if self.can_be_target_type(self.universal_real().into(), target_type.base()) {
+                        self.set_expression_type(span, target_type);
+                    } else {
                         diagnostics.add(
                             span.pos(self.ctx),
                             format!("real literal does not match {}", target_type.describe()),
@@ -80,17 +84,8 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 }
             },
             Literal::Character(char) => match target_base.kind() {
-                Type::Enum(literals) => {
-                    if !literals.contains(&Designator::Character(*char)) {
-                        diagnostics.add(
-                            span.pos(self.ctx),
-                            format!(
-                                "character literal does not match {}",
-                                target_type.describe()
-                            ),
-                            ErrorCode::TypeMismatch,
-                        );
-                    }
+                Type::Enum(literals) if literals.contains(&Designator::Character(*char)) => {
+                    self.set_expression_type(span, target_type);
                 }
                 _ => {
                     diagnostics.add(
@@ -111,16 +106,24 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     target_type,
                     diagnostics,
                 );
+                if target_type.base().is_compatible_with_string_literal() {
+                    self.set_expression_type(span, target_type);
+                }
             }
             Literal::BitString(bit_string) => {
                 match bit_string_to_string(bit_string) {
-                    Ok(string_lit) => self.analyze_string_literal(
-                        span,
-                        string_lit,
-                        target_base,
-                        target_type,
-                        diagnostics,
-                    ),
+                    Ok(string_lit) => {
+                        self.analyze_string_literal(
+                            span,
+                            string_lit,
+                            target_base,
+                            target_type,
+                            diagnostics,
+                        );
+                        if target_type.base().is_compatible_with_string_literal() {
+                            self.set_expression_type(span, target_type);
+                        }
+                    }
                     Err(err) => {
                         match err {
                             BitStringConversionError::IllegalDecimalCharacter(rel_pos) => {
@@ -157,7 +160,9 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             Literal::Physical(PhysicalLiteral { ref mut unit, .. }) => {
                 match self.resolve_physical_unit(scope, unit) {
                     Ok(physical_type) => {
-                        if physical_type.base_type() != target_base {
+                        if physical_type.base_type() == target_base {
+                            self.set_expression_type(span, physical_type);
+                        } else {
                             diagnostics.push(Diagnostic::type_mismatch(
                                 &span.pos(self.ctx),
                                 &physical_type.describe(),
@@ -171,7 +176,9 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 }
             }
             Literal::Null => {
-                if !matches!(target_base.kind(), Type::Access(_)) {
+                if matches!(target_base.kind(), Type::Access(_)) {
+                    self.set_expression_type(span, target_type);
+                } else {
                     diagnostics.add(
                         span.pos(self.ctx),
                         format!("null literal does not match {}", target_base.describe()),
@@ -33,6 +33,11 @@ impl<T, R> AnalysisLock<T, R> {
         }
     }
 
+    /// Returns the data, discarding the analysis results.
+    pub fn into_inner(self) -> T {
+        self.state.into_inner().data
+    }
+
     /// Returns an immutable reference to the data and result if it has already been analyzed.
     pub fn get(&self) -> Option<ReadGuard<'_, T, R>> {
         let guard = self.state.read();
@@ -6,7 +6,7 @@
 
 use crate::analysis::target::AssignmentType;
 use fnv::FnvHashSet;
-use vhdl_lang::{TokenAccess, TokenSpan};
+use vhdl_lang::{TokenAccess, TokenSpan, VHDLStandard};
 
 use super::analyze::*;
 use super::expression::ExpressionType;
@@ -54,7 +54,7 @@ impl<'a> ObjectBase<'a> {
         }
         match self.mode() {
             None => true,
-            Some(InterfaceMode::Simple(Mode::In)) => false,
+            Some(InterfaceMode::Simple(Mode::In | Mode::Linkage)) => false,
             Some(InterfaceMode::Simple(_)) => true,
             // This is triggered when assigning, e.g.,
             // using foo.bar <= baz where `foo` is a view.
@@ -63,11 +63,19 @@ impl<'a> ObjectBase<'a> {
         }
     }
 
+    /// Whether the object is an interface signal of mode `in`
+    pub fn is_input_signal(&self) -> bool {
+        self.class() == ObjectClass::Signal
+            && matches!(self.mode(), Some(InterfaceMode::Simple(Mode::In)))
+    }
+
     /// Check that a signal is not the target of a variable assignment and vice-versa
     pub fn is_valid_assignment_type(&self, assignment_type: AssignmentType) -> bool {
         let class = self.class();
         match assignment_type {
-            AssignmentType::Signal => matches!(class, ObjectClass::Signal),
+            AssignmentType::Signal | AssignmentType::Force(_) => {
+                matches!(class, ObjectClass::Signal)
+            }
             AssignmentType::Variable => {
                 matches!(class, ObjectClass::Variable | ObjectClass::SharedVariable)
             }
@@ -165,6 +173,7 @@ impl<'a> ResolvedName<'a> {
     /// The name was selected out of a design unit
     fn from_design_not_overloaded(ent: &'a AnyEnt<'_>) -> Result<Self, (String, ErrorCode)> {
         let name = match ent.kind() {
+            AnyEntKind::Alias(base) => return Self::from_design_not_overloaded(base),
             AnyEntKind::Object(_) => ResolvedName::ObjectName(ObjectName {
                 base: ObjectBase::Object(ObjectEnt::from_any(ent).unwrap()),
                 type_mark: None,
@@ -223,6 +232,7 @@ impl<'a> ResolvedName<'a> {
     /// The name was looked up from the current scope
     fn from_scope_not_overloaded(ent: &'a AnyEnt<'_>) -> Result<Self, (String, ErrorCode)> {
         let name = match ent.kind() {
+            AnyEntKind::Alias(base) => return Self::from_scope_not_overloaded(base),
             AnyEntKind::Object(_) => ResolvedName::ObjectName(ObjectName {
                 base: ObjectBase::Object(ObjectEnt::from_any(ent).unwrap()),
                 type_mark: None,
@@ -1189,6 +1199,46 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         }
     }
 
+    /// Keep the functions whose return type the suffix can be applied to,
+    /// all of them if there is no such function
+    fn disambiguate_by_suffix(
+        &self,
+        candidates: Vec<OverloadedEnt<'a>>,
+        suffix: &Suffix<'_>,
+    ) -> Disambiguated<'a> {
+        let mut matching: Vec<_> = candidates
+            .iter()
+            .filter(|ent| {
+                ent.return_type()
+                    .is_some_and(|typ| self.suffix_applies_to(typ, suffix))
+            })
+            .copied()
+            .collect();
+        match matching.len() {
+            0 => Disambiguated::Ambiguous(candidates),
+            1 => Disambiguated::Unambiguous(matching.remove(0)),
+            _ => Disambiguated::Ambiguous(matching),
+        }
+    }
+
+    /// Returns true if the suffix can be applied to a prefix of the type
+    fn suffix_applies_to(&self, typ: TypeEnt<'a>, suffix: &Suffix<'_>) -> bool {
+        match suffix {
+            Suffix::Selected(designator) => typ
+                .selected(self.ctx, designator.token.into(), designator)
+                .is_ok(),
+            Suffix::All => typ.accessed_type().is_some(),
+            Suffix::Slice(_) => typ.sliced_as().is_some(),
+            Suffix::Attribute(_) => true,
+            Suffix::CallOrIndexed(assocs) => {
+                could_be_indexed_name(assocs)
+                    && typ
+                        .array_type()
+                        .is_some_and(|(_, indexes)| indexes.len() == assocs.len())
+            }
+        }
+    }
+
     pub fn name_resolve(
         &self,
         scope: &Scope<'a>,
@@ -1196,7 +1246,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         name: &mut Name,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> EvalResult<ResolvedName<'a>> {
-        self.name_resolve_with_suffixes(scope, name_pos, name, None, false, diagnostics)
+        self.name_resolve_with_suffixes(scope, name_pos, name, None, None, diagnostics)
     }
 
     fn name_resolve_with_suffixes(
@@ -1205,7 +1255,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         span: TokenSpan,
         name: &mut Name,
         ttyp: Option<TypeEnt<'a>>,
-        has_suffix: bool,
+        outer_suffix: Option<&Suffix<'_>>, // The suffix applied to this name when it is a prefix
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> EvalResult<ResolvedName<'a>> {
         let mut suffix;
@@ -1244,7 +1294,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     p.span,
                     &mut p.item,
                     None,
-                    true,
+                    Some(&s),
                     diagnostics,
                 )?;
                 prefix = p;
@@ -1268,13 +1318,16 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     .into_eval_result(diagnostics)?;
 
                 if let Some(disambiguated) = disambiguated {
+                    let disambiguated = match disambiguated {
+                        Disambiguated::Ambiguous(ents) => {
+                            self.disambiguate_by_suffix(ents, &suffix)
+                        }
+                        disambiguated => disambiguated,
+                    };
                     match disambiguated {
                         Disambiguated::Ambiguous(ents) => {
                             if let Some(types) = ambiguous_functions_to_types(&ents) {
-                                if has_suffix || ttyp.is_some() {
-                                    diagnostics
-                                        .push(Diagnostic::ambiguous_call(self.ctx, des, ents));
-                                }
+                                diagnostics.push(Diagnostic::ambiguous_call(self.ctx, des, ents));
                                 resolved =
                                     ResolvedName::Expression(DisambiguatedType::Ambiguous(types));
                             } else {
@@ -1332,23 +1385,38 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                         }
                     }
 
-                    match as_fatal(self.disambiguate(
+                    let disambiguated = as_fatal(self.disambiguate(
                         scope,
                         &span.pos(self.ctx),
                         des,
                         assocs,
-                        SubprogramKind::Function(if has_suffix {
-                            // @TODO disambiguate based on suffixes
-                            None
-                        } else {
-                            ttyp
-                        }),
+                        SubprogramKind::Function(if outer_suffix.is_some() { None } else { ttyp }),
                         overloaded.entities().collect(),
                         diagnostics,
-                    ))? {
+                    ))?;
+
+                    // The return type must be one that the outer suffix can be applied to
+                    let disambiguated = match (disambiguated, outer_suffix) {
+                        (Some(Disambiguated::Ambiguous(ents)), Some(outer_suffix)) => {
+                            let disambiguated = self.disambiguate_by_suffix(ents, outer_suffix);
+                            if let Disambiguated::Unambiguous(ent) = disambiguated {
+                                self.check_call(
+                                    scope,
+                                    &span.pos(self.ctx),
+                                    ent,
+                                    assocs,
+                                    diagnostics,
+                                )?;
+                            }
+                            Some(disambiguated)
+                        }
+                        (disambiguated, _) => disambiguated,
+                    };
+
+                    match disambiguated {
                         Some(Disambiguated::Ambiguous(ents)) => {
                             if let Some(types) = ambiguous_functions_to_types(&ents) {
-                                if has_suffix || ttyp.is_some() {
+                                if outer_suffix.is_some() || ttyp.is_some() {
                                     diagnostics
                                         .push(Diagnostic::ambiguous_call(self.ctx, des, ents));
                                 }
@@ -1444,7 +1512,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     }
                 }
                 DisambiguatedType::Ambiguous(_) => {
-                    // @TODO ambiguous error
+                    // Error reported when disambiguating the prefix
                     return Err(EvalError::Unknown);
                 }
             },
@@ -1661,7 +1729,10 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> EvalResult<DisambiguatedType<'a>> {
         let resolved =
-            self.name_resolve_with_suffixes(scope, span, name, None, false, diagnostics)?;
+            self.name_resolve_with_suffixes(scope, span, name, None, None, diagnostics)?;
+        if let ResolvedName::ObjectName(ref oname) = resolved {
+            self.check_object_read(span, &oname.base, diagnostics);
+        }
         match self.name_to_type(span, name.suffix_reference_mut(), resolved) {
             Ok(Some(typ)) => Ok(typ),
             Ok(None) => Err(EvalError::Unknown),
@@ -1686,14 +1757,22 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             span,
             name,
             Some(ttyp),
-            false,
+            None,
             diagnostics,
         ))? {
+            if let ResolvedName::ObjectName(ref oname) = resolved {
+                self.check_object_read(span, &oname.base, diagnostics);
+            }
             // @TODO target_type already used above, functions could probably be simplified
             match self.name_to_unambiguous_type(span, &resolved, ttyp, name.suffix_reference_mut())
             {
                 Ok(Some(type_mark)) => {
-                    if !self.can_be_target_type(type_mark, ttyp.base()) {
+                    if self.can_be_target_type(type_mark, ttyp.base()) {
+                        self.set_expression_type(
+                            span,
+                            self.implicitly_converted_type(type_mark, ttyp),
+                        );
+                    } else {
                         diagnostics.push(Diagnostic::type_mismatch(
                             &span.pos(self.ctx),
                             &resolved.describe_type(),
@@ -1710,6 +1789,33 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         Ok(())
     }
 
+    /// Check that the value of an object that is read within an expression may be read.
+    /// Interface objects of mode `linkage` may never be read (LRM 6.5.2)
+    /// and interface objects of mode `out` may only be read since VHDL-2008.
+    fn check_object_read(
+        &self,
+        span: TokenSpan,
+        object: &ObjectBase<'a>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let message = match object.mode() {
+            Some(InterfaceMode::Simple(Mode::Linkage)) => {
+                format!("{} may not be read", object.describe_class())
+            }
+            Some(InterfaceMode::Simple(Mode::Out)) if self.standard < VHDLStandard::VHDL2008 => {
+                format!(
+                    "{} may not be read before VHDL-2008",
+                    object.describe_class()
+                )
+            }
+            _ => return,
+        };
+        // Report each name only once, although it is analyzed again when disambiguating
+        if self.illegal_reads.borrow_mut().insert(span) {
+            diagnostics.add(span.pos(self.ctx), message, ErrorCode::IllegalPortRead);
+        }
+    }
+
     /// Analyze an indexed name where the prefix entity is already known
     /// Returns the type of the array element
     pub fn analyze_indexed_name(
@@ -2059,7 +2165,7 @@ mod test {
                 name.span,
                 &mut name.item,
                 ttyp,
-                false,
+                None,
                 diagnostics,
             )
         }
@@ -10,6 +10,7 @@ use vhdl_lang::SrcPos;
 use super::analyze::*;
 use super::names::ResolvedName;
 use super::scope::*;
+use crate::ast::capitalize;
 use crate::ast::AssociationElement;
 use crate::ast::Expression;
 use crate::ast::Literal;
@@ -53,13 +54,15 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
     pub fn generic_map(
         &self,
         scope: &Scope<'a>,
+        error_pos: &SrcPos,
         generics: GpkgRegion<'a>,
         generic_map: &mut [AssociationElement],
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> EvalResult<FnvHashMap<EntityId, TypeEnt<'a>>> {
         let mut mapping = FnvHashMap::default();
+        // The positions of the actuals of the associated generics
+        let mut associated: FnvHashMap<EntityId, SrcPos> = FnvHashMap::default();
 
-        // @TODO check missing associations
         for (idx, assoc) in generic_map.iter_mut().enumerate() {
             let formal = if let Some(formal) = &mut assoc.formal {
                 let formal_pos = formal.pos(self.ctx);
@@ -93,6 +96,24 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 continue;
             };
 
+            if matches!(assoc.actual.item, ActualPart::Open) {
+                // Open actuals are checked as missing associations below
+                continue;
+            }
+            let actual_pos = assoc.actual.pos(self.ctx);
+            if let Some(prev_pos) = associated.get(&formal.id()) {
+                diagnostics.push(
+                    Diagnostic::new(
+                        &actual_pos,
+                        format!("{} has already been associated", formal.describe()),
+                        ErrorCode::AlreadyAssociated,
+                    )
+                    .related(prev_pos, "Previously associated here"),
+                );
+                continue;
+            }
+            associated.insert(formal.id(), actual_pos);
+
             match &mut assoc.actual.item {
                 ActualPart::Expression(expr) => match formal {
                     GpkgInterfaceEnt::Type(uninst_typ) => {
@@ -213,9 +234,16 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                             ErrorCode::MismatchedKinds,
                         ),
                     },
-                    GpkgInterfaceEnt::Package(_) => match expr {
+                    GpkgInterfaceEnt::Package(iface) => match expr {
                         Expression::Name(name) => {
-                            self.name_resolve(scope, assoc.actual.span, name, diagnostics)?;
+                            let resolved =
+                                self.name_resolve(scope, assoc.actual.span, name, diagnostics)?;
+                            self.check_package_actual(
+                                iface,
+                                resolved,
+                                &assoc.actual.pos(self.ctx),
+                                diagnostics,
+                            );
                         }
                         _ => diagnostics.add(
                             assoc.actual.pos(self.ctx),
@@ -224,14 +252,75 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                         ),
                     },
                 },
-                ActualPart::Open => {
-                    // @TODO
-                }
+                ActualPart::Open => {}
             }
         }
+
+        for generic in generics.iter() {
+            if associated.contains_key(&generic.id()) || !generic_requires_actual(&generic) {
+                continue;
+            }
+            diagnostics.push(
+                Diagnostic::new(
+                    error_pos,
+                    format!("No association of {}", generic.describe()),
+                    ErrorCode::Unassociated,
+                )
+                .opt_related(generic.decl_pos(), "Defined here"),
+            );
+        }
+
         Ok(mapping)
     }
 
+    /// Checks that the actual of a generic package is an instance
+    /// of the same uninstantiated package as the generic package.
+    fn check_package_actual(
+        &self,
+        iface: EntRef<'a>,
+        actual: ResolvedName<'a>,
+        actual_pos: &SrcPos,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let AnyEntKind::Design(Design::InterfacePackageInstance(iface_region)) = iface.kind()
+        else {
+            return;
+        };
+        let actual_region = match actual {
+            ResolvedName::Design(ref design) => match design.kind() {
+                Design::PackageInstance(region) | Design::InterfacePackageInstance(region) => {
+                    Some(region)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(actual_region) = actual_region else {
+            diagnostics.add(
+                actual_pos,
+                format!("Cannot map {} to package generic", actual.describe()),
+                ErrorCode::MismatchedKinds,
+            );
+            return;
+        };
+        if let (Some(expected), Some(got)) = (
+            uninstantiated_package(iface_region),
+            uninstantiated_package(actual_region),
+        ) {
+            if expected.id() != got.id() {
+                diagnostics.add(
+                    actual_pos,
+                    format!(
+                        "{} is not an instance of {}",
+                        capitalize(&actual.describe()),
+                        expected.describe()
+                    ),
+                    ErrorCode::MismatchedKinds,
+                );
+            }
+        }
+    }
+
     pub fn generic_instance(
         &self,
         ent: EntRef<'a>,
@@ -244,16 +333,16 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         let nested = scope.nested().in_package_declaration();
         let (generics, other) = uninst_region.to_package_generic();
 
-        let mapping = if let Some(generic_map) = generic_map {
-            self.generic_map(
-                &nested,
-                generics,
-                generic_map.list.items.as_mut_slice(),
-                diagnostics,
-            )?
-        } else {
-            FnvHashMap::default()
-        };
+        let mapping = self.generic_map(
+            &nested,
+            decl_pos,
+            generics,
+            generic_map
+                .as_mut()
+                .map(|it| it.list.items.as_mut_slice())
+                .unwrap_or(&mut []),
+            diagnostics,
+        )?;
 
         for uninst in other {
             match self.instantiate(Some(ent), &mapping, uninst, &nested) {
@@ -340,6 +429,9 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 },
                 type_mark: self.map_type_ent(mapping, *type_mark, scope),
             },
+            AnyEntKind::Alias(ent) => {
+                AnyEntKind::Alias(self.instantiate(None, mapping, ent, scope)?)
+            }
             AnyEntKind::File(subtype) => {
                 AnyEntKind::File(self.map_subtype(mapping, *subtype, scope))
             }
@@ -618,10 +710,33 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         subtype: Subtype<'a>,
         scope: &Scope<'a>,
     ) -> Subtype<'a> {
-        let Subtype { type_mark } = subtype;
+        let Subtype {
+            type_mark,
+            constrained,
+        } = subtype;
 
         Subtype {
             type_mark: self.map_type_ent(mapping, type_mark, scope),
+            constrained,
         }
     }
 }
+
+/// True for generics that must be associated in a generic map.
+/// Interface subprograms are not checked since their default is not known here.
+fn generic_requires_actual(generic: &GpkgInterfaceEnt<'_>) -> bool {
+    match generic {
+        GpkgInterfaceEnt::Type(_) | GpkgInterfaceEnt::Package(_) => true,
+        GpkgInterfaceEnt::Constant(object) => !object.object().has_default,
+        GpkgInterfaceEnt::Subprogram(_) => false,
+    }
+}
+
+/// The uninstantiated package of the declarations within the region of a package
+/// or of an instance of a package.
+fn uninstantiated_package<'a>(region: &Region<'a>) -> Option<EntRef<'a>> {
+    region.immediates().find_map(|ent| match ent.related {
+        Related::InstanceOf(uninst) => uninst.parent,
+        _ => ent.parent,
+    })
+}
@@ -0,0 +1,159 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::DesignRoot;
+use crate::ast::search::{FindAllReferences, FindAllWrites, FindInstantiatedNames};
+use crate::data::SrcPos;
+use crate::named_entity::EntRef;
+use strum::{EnumString, IntoStaticStr};
+
+/// How a named entity is referenced at a position
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ReferenceKind {
+    /// The name of a declaration or its repetition after the `end` keyword
+    Declaration,
+    /// The target of a variable or signal assignment
+    Write,
+    /// The name of the entity, architecture, component or configuration
+    /// of an instantiation statement
+    Instantiation,
+    /// Any other reference, such as reading a signal or using a type
+    Read,
+}
+
+impl DesignRoot {
+    /// All references of `ent` together with how it is referenced.
+    /// References of aliases of the same entity are included.
+    pub fn find_all_references_with_kind(&self, ent: EntRef<'_>) -> Vec<(SrcPos, ReferenceKind)> {
+        let mut references = FindAllReferences::through_aliases(self, ent);
+        let _ = self.search(&mut references);
+        let mut writes = FindAllWrites::through_aliases(self, ent);
+        let _ = self.search(&mut writes);
+        let mut instantiations = FindInstantiatedNames::default();
+        let _ = self.search(&mut instantiations);
+
+        references
+            .references
+            .into_iter()
+            .map(|pos| {
+                let kind = if references.declarations.contains(&pos) {
+                    ReferenceKind::Declaration
+                } else if writes.writes.contains(&pos) {
+                    ReferenceKind::Write
+                } else if instantiations.positions.contains(&pos) {
+                    ReferenceKind::Instantiation
+                } else {
+                    ReferenceKind::Read
+                };
+                (pos, kind)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+
+    #[test]
+    fn classifies_references() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity leaf is
+end entity leaf;
+
+architecture rtl of leaf is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+  signal sig : bit;
+begin
+  sig <= not sig;
+  inst: entity work.leaf(rtl);
+end architecture;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+
+        let sig = root
+            .search_reference(code.source(), code.s1("sig :").start())
+            .unwrap();
+        assert_eq!(
+            root.find_all_references_with_kind(sig),
+            vec![
+                (code.s1("sig :").s1("sig").pos(), ReferenceKind::Declaration),
+                (code.s1("sig <=").s1("sig").pos(), ReferenceKind::Write),
+                (code.s1("not sig").s1("sig").pos(), ReferenceKind::Read),
+            ]
+        );
+
+        let leaf = root
+            .search_reference(code.source(), code.s1("leaf").start())
+            .unwrap();
+        let mut references = root.find_all_references_with_kind(leaf);
+        references.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            references,
+            vec![
+                (code.s("leaf", 1).pos(), ReferenceKind::Declaration),
+                (code.s("leaf", 2).pos(), ReferenceKind::Declaration),
+                (code.s("leaf", 3).pos(), ReferenceKind::Read),
+                (code.s("leaf", 4).pos(), ReferenceKind::Instantiation),
+            ]
+        );
+        assert_eq!("instantiation".parse(), Ok(ReferenceKind::Instantiation));
+    }
+
+    #[test]
+    fn includes_references_through_aliases() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : bit;
+  alias sig_alias is sig;
+begin
+  sig_alias <= not sig;
+end architecture;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+
+        let expected = vec![
+            (code.s1("sig :").s1("sig").pos(), ReferenceKind::Declaration),
+            (code.s1("sig_alias").pos(), ReferenceKind::Declaration),
+            (code.s1("is sig").s1("sig").pos(), ReferenceKind::Read),
+            (
+                code.s1("sig_alias <=").s1("sig_alias").pos(),
+                ReferenceKind::Write,
+            ),
+            (code.s1("not sig").s1("sig").pos(), ReferenceKind::Read),
+        ];
+        for cursor in [code.s1("sig :"), code.s1("sig_alias <=")] {
+            let ent = root
+                .search_reference(code.source(), cursor.start())
+                .unwrap();
+            assert_eq!(root.find_all_references_with_kind(ent), expected);
+        }
+        // Renaming must not see through aliases
+        let sig = root
+            .search_reference(code.source(), code.s1("sig :").start())
+            .unwrap();
+        assert_eq!(root.find_all_references(sig).len(), 3);
+    }
+}
@@ -5,6 +5,7 @@
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
 use super::analyze::*;
+use super::constant_folding::ConstantCache;
 use super::lock::*;
 use super::standard::StandardTypes;
 use super::standard::UniversalTypes;
@@ -14,8 +15,8 @@ use crate::ast::search::*;
 use crate::ast::*;
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
-use crate::syntax::{Symbols, Token, TokenAccess};
-use crate::{HasTokenSpan, TokenSpan};
+use crate::syntax::{Kind, Symbols, Token, TokenAccess};
+use crate::{HasTokenSpan, TokenId, TokenSpan, VHDLStandard};
 use fnv::{FnvHashMap, FnvHashSet};
 use parking_lot::RwLock;
 use std::collections::hash_map::Entry;
@@ -28,6 +29,10 @@ pub(crate) struct AnalysisData {
     pub diagnostics: Vec<Diagnostic>,
     pub has_circular_dependency: bool,
     pub arena: FinalArena,
+    /// The candidates of operators that remain ambiguous after analysis
+    pub ambiguous_operators: FnvHashMap<TokenId, Vec<EntityId>>,
+    /// The types of the expressions that could be resolved to a single type
+    pub expression_types: FnvHashMap<TokenSpan, EntityId>,
 }
 
 pub(super) type UnitReadGuard<'a> = ReadGuard<'a, AnyDesignUnit, AnalysisData>;
@@ -41,6 +46,8 @@ pub(crate) struct LockedUnit {
     unit_id: UnitId,
     pub unit: AnalysisLock<AnyDesignUnit, AnalysisData>,
     pub tokens: Vec<Token>,
+    /// The VHDL standard that the unit was parsed with
+    pub standard: VHDLStandard,
 }
 
 impl HasSrcPos for LockedUnit {
@@ -56,7 +63,12 @@ impl HasUnitId for LockedUnit {
 }
 
 impl LockedUnit {
-    fn new(library_name: &Symbol, unit: AnyDesignUnit, tokens: Vec<Token>) -> LockedUnit {
+    fn new(
+        library_name: &Symbol,
+        unit: AnyDesignUnit,
+        tokens: Vec<Token>,
+        standard: VHDLStandard,
+    ) -> LockedUnit {
         let unit_id = match unit {
             AnyDesignUnit::Primary(ref unit) => {
                 UnitId::primary(library_name, PrimaryKind::kind_of(unit), unit.name())
@@ -75,6 +87,7 @@ impl LockedUnit {
             unit_id,
             unit: AnalysisLock::new(unit),
             tokens,
+            standard,
         }
     }
 }
@@ -165,7 +178,12 @@ impl Library {
 
     fn add_design_file(&mut self, design_file: DesignFile) {
         for (tokens, design_unit) in design_file.design_units {
-            self.add_design_unit(LockedUnit::new(self.name(), design_unit, tokens));
+            self.add_design_unit(LockedUnit::new(
+                self.name(),
+                design_unit,
+                tokens,
+                design_file.standard,
+            ));
         }
     }
 
@@ -208,33 +226,41 @@ impl Library {
         }
     }
 
-    /// Remove all design units defined in source.
+    /// Remove all design units defined in source, including duplicates,
+    /// and return them in the order they appear in the source.
     /// This is used for incremental analysis where only a single source file is updated.
-    fn remove_source(&mut self, source: &Source) {
-        let removed = &mut self.removed;
-        self.units.retain(|_, value| {
-            if value.source() != source {
-                true
-            } else {
-                removed.insert(value.unit_id().clone());
-                false
+    fn remove_source(&mut self, source: &Source) -> Vec<LockedUnit> {
+        let keys: Vec<UnitKey> = self
+            .units
+            .iter()
+            .filter(|(_, value)| value.source() == source)
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut removed_units = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(unit) = self.units.remove(&key) {
+                self.removed.insert(unit.unit_id().clone());
+                removed_units.push(unit);
             }
-        });
+        }
         self.units_by_source.remove(source);
-        self.duplicates
-            .retain(|(_, value)| value.source() != source);
 
         // Try to add duplicates that were duplicated by a design unit in the removed file
         let num_duplicates = self.duplicates.len();
         let duplicates =
             std::mem::replace(&mut self.duplicates, Vec::with_capacity(num_duplicates));
         for (prev_pos, design_unit) in duplicates.into_iter() {
-            if prev_pos.source() == source {
+            if design_unit.source() == source {
+                removed_units.push(design_unit);
+            } else if prev_pos.source() == source {
                 self.add_design_unit(design_unit);
             } else {
                 self.duplicates.push((prev_pos, design_unit));
             }
         }
+
+        removed_units.sort_by_key(|unit| unit.tokens.first().map(|token| token.pos.start()));
+        removed_units
     }
 
     /// Iterate over units in the order they appear in the file.
@@ -312,6 +338,9 @@ pub struct DesignRoot {
     // Tracks which units have a "use library.all;" clause.
     // library name  =>  set(affected)
     users_of_library_all: RwLock<FnvHashMap<Symbol, FnvHashSet<UnitId>>>,
+
+    // The evaluated constants of the analyzed design
+    pub(super) constants: RwLock<ConstantCache>,
 }
 
 impl DesignRoot {
@@ -328,6 +357,7 @@ impl DesignRoot {
             users_of: RwLock::new(FnvHashMap::default()),
             missing_unit: RwLock::new(FnvHashMap::default()),
             users_of_library_all: RwLock::new(FnvHashMap::default()),
+            constants: RwLock::new(ConstantCache::default()),
         }
     }
 
@@ -409,6 +439,20 @@ impl DesignRoot {
             .remove_source(source);
     }
 
+    /// Remove all design units defined in source and return their tokens and syntax trees
+    /// such that they can be reused when only part of the source is parsed again.
+    pub fn take_source(
+        &mut self,
+        library_name: Symbol,
+        source: &Source,
+    ) -> Vec<(Vec<Token>, AnyDesignUnit)> {
+        self.get_or_create_library(library_name)
+            .remove_source(source)
+            .into_iter()
+            .map(|unit| (unit.tokens, unit.unit.into_inner()))
+            .collect()
+    }
+
     /// Search for reference at position
     /// Character offset on a line in a document (zero-based). Assuming that the line is
     /// represented as a string, the `character` value represents the gap between the
@@ -437,15 +481,34 @@ impl DesignRoot {
         None
     }
 
+    /// The kind of the token of a design unit that contains the cursor
+    pub(crate) fn token_kind_at_cursor(&self, source: &Source, cursor: Position) -> Option<Kind> {
+        self.units_by_source(source)
+            .flat_map(|unit| unit.tokens.iter())
+            .find(|token| token.pos.contains(cursor))
+            .map(|token| token.kind)
+    }
+
     pub fn search_reference(&self, source: &Source, cursor: Position) -> Option<EntRef<'_>> {
         let (_, ent) = self.item_at_cursor(source, cursor)?;
         Some(ent)
     }
 
+    /// The definition of `decl`, i.e. the body of a subprogram, protected type or package
+    /// or the full declaration of a deferred constant.
+    /// Aliases are resolved to the definition of the aliased entity.
     pub fn find_definition_of<'a>(&'a self, decl: EntRef<'a>) -> Option<EntRef<'a>> {
+        let actual = decl.as_actual();
+        if actual.id() != decl.id() {
+            return self.find_definition_of(actual);
+        }
         if decl.is_protected_type()
             || decl.is_subprogram_decl()
             || decl.kind().is_deferred_constant()
+            || matches!(
+                decl.kind(),
+                AnyEntKind::Design(Design::Package(..) | Design::UninstPackage(..))
+            )
         {
             let mut searcher = FindEnt::new(self, |ent| ent.is_declared_by(decl));
             let _ = self.search(&mut searcher);
@@ -457,15 +520,65 @@ impl DesignRoot {
         }
     }
 
+    /// The position of the first declaration of `ent`.
+    /// This is the incomplete type declaration for types that are declared incomplete
+    /// and the declaration of the subprogram, constant or design unit otherwise.
+    pub fn find_declaration_pos_of(&self, ent: EntRef<'_>) -> Option<SrcPos> {
+        let decl = ent.declaration();
+        if matches!(decl.kind(), AnyEntKind::Type(_)) {
+            if let Some(source) = decl.decl_pos().map(|pos| pos.source.clone()) {
+                let mut searcher = FindIncompleteType::new(decl.id());
+                let _ = self.search_source(&source, &mut searcher);
+                if searcher.result.is_some() {
+                    return searcher.result;
+                }
+            }
+        }
+        decl.decl_pos().cloned()
+    }
+
+    /// Whether the subtype or one of the subtypes it is declared from has a resolution function,
+    /// such as `std_logic` and `std_logic_vector`.
+    pub(crate) fn is_resolved_subtype(&self, typ: TypeEnt<'_>) -> bool {
+        let mut typ = typ;
+        loop {
+            match typ.kind() {
+                Type::Subtype(subtype) => {
+                    let is_resolved = self.with_declaration(typ.into(), |decl| {
+                        matches!(
+                            decl,
+                            DeclarationItem::Type(TypeDeclaration {
+                                def: TypeDefinition::Subtype(indication),
+                                ..
+                            }) if indication.resolution.is_some()
+                        )
+                    });
+                    if is_resolved == Some(true) {
+                        return true;
+                    }
+                    typ = subtype.type_mark();
+                }
+                Type::Alias(alias) => typ = *alias,
+                _ => return false,
+            }
+        }
+    }
+
     pub fn find_implementation<'a>(&'a self, ent: EntRef<'a>) -> Vec<EntRef<'a>> {
         if let Designator::Identifier(ident) = ent.designator() {
             if let Some(library_name) = ent.library_name() {
                 match ent.kind() {
-                    // Find entity with same name as component in the library
+                    // Find entities bound by configuration and
+                    // the entity with same name as component in the library
                     AnyEntKind::Component(_) => {
+                        let mut entities = self.configured_entities(ent);
                         if let Some(design) = self.get_design_entity(library_name, ident) {
-                            return vec![design.into()];
+                            let design = design.into();
+                            if !entities.contains(&design) {
+                                entities.push(design);
+                            }
                         }
+                        return entities;
                     }
                     // Find components and architectures to entity
                     AnyEntKind::Design(Design::Entity(..)) => {
@@ -488,6 +601,10 @@ impl DesignRoot {
                         let _ = self.search_library(library_name, &mut searcher);
                         return searcher.result;
                     }
+                    // Find architectures of the instantiated entity
+                    AnyEntKind::Concurrent(Some(Concurrent::Instance)) => {
+                        return self.instantiated_architectures(ent);
+                    }
                     _ => {}
                 }
             }
@@ -495,6 +612,41 @@ impl DesignRoot {
         Vec::default()
     }
 
+    /// The architectures that may implement the instance labeled `ent`.
+    /// An architecture named by the instantiation takes precedence,
+    /// components are followed to their bound entities.
+    fn instantiated_architectures<'a>(&'a self, ent: EntRef<'a>) -> Vec<EntRef<'a>> {
+        let Some(Some(id)) = self.with_declaration(ent, |item| {
+            let DeclarationItem::ConcurrentStatement(statement) = item else {
+                return None;
+            };
+            let ConcurrentStatement::Instance(instance) = &statement.statement.item else {
+                return None;
+            };
+            match &instance.unit {
+                InstantiatedUnit::Entity(_, Some(arch)) => {
+                    arch.reference.get().or_else(|| instance.entity_reference())
+                }
+                _ => instance.entity_reference(),
+            }
+        }) else {
+            return Vec::default();
+        };
+
+        let unit = self.get_ent(id);
+        let entities = match unit.kind() {
+            AnyEntKind::Design(Design::Architecture(..)) => return vec![unit],
+            AnyEntKind::Design(Design::Entity(..)) => vec![unit],
+            AnyEntKind::Component(_) => self.find_implementation(unit),
+            _ => Vec::default(),
+        };
+        entities
+            .into_iter()
+            .flat_map(|entity| self.find_implementation(entity))
+            .filter(|ent| matches!(ent.kind(), AnyEntKind::Design(Design::Architecture(..))))
+            .collect()
+    }
+
     #[cfg(test)]
     pub fn search_reference_pos(&self, source: &Source, cursor: Position) -> Option<SrcPos> {
         self.search_reference(source, cursor)
@@ -512,9 +664,83 @@ impl DesignRoot {
             };
 
             let mut searcher = FormatDeclaration::new(ent);
-            let _ = self.search(&mut searcher);
-            searcher.result
+            // The declaration is usually found in the file of its position
+            if let Some(pos) = ent.decl_pos() {
+                let _ = self.search_source(&pos.source, &mut searcher);
+            }
+            if searcher.result.is_none() {
+                let _ = self.search(&mut searcher);
+            }
+            let mut declaration = searcher.result?;
+            for info in [
+                self.format_constant_value(ent),
+                self.format_resolved_subtype(ent),
+                declared_in_of_operator(ent),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                declaration = format!("{declaration}\n{info}");
+            }
+            Some(declaration)
+        }
+    }
+
+    /// The candidates of the operator at the cursor if the operator could not be disambiguated
+    pub fn ambiguous_operator_at(&self, source: &Source, cursor: Position) -> Vec<EntRef<'_>> {
+        for unit in self.units_by_source(source) {
+            let data = unit.unit.expect_analyzed();
+            for (token, candidates) in data.result().ambiguous_operators.iter() {
+                let pos = unit.tokens.get_pos(*token);
+                if pos.start() <= cursor && cursor <= pos.end() {
+                    return candidates.iter().map(|id| self.get_ent(*id)).collect();
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// The innermost expression at the cursor that has a single type, together with the type
+    pub fn expression_type_at(
+        &self,
+        source: &Source,
+        cursor: Position,
+    ) -> Option<(SrcPos, TypeEnt<'_>)> {
+        let mut innermost: Option<(SrcPos, EntityId)> = None;
+        for unit in self.units_by_source(source) {
+            let data = unit.unit.expect_analyzed();
+            for (span, id) in data.result().expression_types.iter() {
+                let pos = span.pos(&unit.tokens);
+                if pos.start() <= cursor
+                    && cursor <= pos.end()
+                    && innermost.as_ref().is_none_or(|(innermost, _)| {
+                        innermost.start() <= pos.start() && pos.end() <= innermost.end()
+                    })
+                {
+                    innermost = Some((pos, *id));
+                }
+            }
         }
+        let (pos, id) = innermost?;
+        Some((pos, TypeEnt::from_any(self.get_ent(id))?))
+    }
+
+    /// Format the candidates of an operator that could not be disambiguated
+    pub fn format_ambiguous_operator(&self, source: &Source, cursor: Position) -> Option<String> {
+        let mut candidates = self.ambiguous_operator_at(source, cursor);
+        let designator = candidates.first()?.designator().clone();
+        candidates.sort_by_key(|ent| ent.describe());
+        let mut result = format!(
+            "-- Ambiguous use of {}, candidates are:",
+            designator.describe()
+        );
+        for candidate in candidates {
+            result.push_str(&format!("\n-- {}", candidate.describe()));
+            if let Some(declared_in) = declared_in_of_operator(candidate) {
+                result.push_str(&format!("\n{declared_in}"));
+            }
+        }
+        Some(result)
     }
 
     /// Search for all references to the declaration at decl_pos
@@ -530,6 +756,29 @@ impl DesignRoot {
         searcher.references
     }
 
+    /// Search for all references to the declaration in the source that are assignment targets
+    pub fn find_all_writes_in_source(&self, source: &Source, ent: EntRef<'_>) -> Vec<SrcPos> {
+        let mut searcher = FindAllWrites::new(self, ent);
+        let _ = self.search_source(source, &mut searcher);
+        searcher.writes
+    }
+
+    /// The name of the declaration at the cursor and its repetition after the `end` keyword,
+    /// such as `foo` in `entity foo is` and `end entity foo;`.
+    /// Empty unless the cursor is on one of the two names.
+    pub fn find_linked_names(&self, source: &Source, cursor: Position) -> Vec<SrcPos> {
+        let Some((pos, ent)) = self.item_at_cursor(source, cursor) else {
+            return Vec::new();
+        };
+        let mut searcher = FindNamePair::new(ent);
+        let _ = self.search_source(source, &mut searcher);
+        if searcher.positions.contains(&pos) {
+            searcher.positions
+        } else {
+            Vec::new()
+        }
+    }
+
     pub fn public_symbols<'a>(&'a self) -> Box<dyn Iterator<Item = EntRef<'a>> + 'a> {
         Box::new(self.libraries.values().flat_map(|library| {
             std::iter::once(self.arenas.get(library.id)).chain(library.units.values().flat_map(
@@ -746,11 +995,12 @@ impl DesignRoot {
         source: Source,
         unit: &mut UnitWriteGuard<'_>,
         ctx: &dyn TokenAccess,
+        standard: VHDLStandard,
     ) {
         // All units reference the standard arena
         // @TODO keep the same ArenaId when re-using unit
         let arena = Arena::new(arena_id);
-        let context = AnalyzeContext::new(self, unit_id, source, &arena, ctx);
+        let context = AnalyzeContext::new(self, unit_id, source, &arena, ctx, standard);
 
         let mut diagnostics = Vec::new();
         let mut has_circular_dependency = false;
@@ -762,10 +1012,14 @@ impl DesignRoot {
                     err.push_into(&mut diagnostics);
                 };
 
+                let ambiguous_operators = context.take_ambiguous_operators();
+                let expression_types = context.take_expression_types();
                 AnalysisData {
                     arena: arena.finalize(),
                     diagnostics,
                     has_circular_dependency,
+                    ambiguous_operators,
+                    expression_types,
                 }
             }
 
@@ -777,10 +1031,14 @@ impl DesignRoot {
                     err.push_into(&mut diagnostics);
                 };
 
+                let ambiguous_operators = context.take_ambiguous_operators();
+                let expression_types = context.take_expression_types();
                 AnalysisData {
                     arena: arena.finalize(),
                     diagnostics,
                     has_circular_dependency,
+                    ambiguous_operators,
+                    expression_types,
                 }
             }
         };
@@ -797,6 +1055,7 @@ impl DesignRoot {
                     locked_unit.pos().source().clone(),
                     &mut unit,
                     &locked_unit.tokens,
+                    locked_unit.standard,
                 );
                 unit.downgrade()
             }
@@ -852,6 +1111,15 @@ impl DesignRoot {
         }
     }
 
+    /// All direct dependencies between library units as `(user, used)` pairs
+    pub(crate) fn unit_dependencies(&self) -> Vec<(UnitId, UnitId)> {
+        self.users_of
+            .read()
+            .iter()
+            .flat_map(|(used, users)| users.iter().map(|user| (user.clone(), used.clone())))
+            .collect()
+    }
+
     /// Register a dependency of library unit for everything within library since .all was used
     pub(super) fn make_use_of_library_all(&self, user: &UnitId, library_name: &Symbol) {
         match self
@@ -1043,6 +1311,7 @@ impl DesignRoot {
             locked_unit.source().clone(),
             &arena,
             &locked_unit.tokens,
+            locked_unit.standard,
         );
 
         let mut diagnostics = Vec::new();
@@ -1106,6 +1375,8 @@ impl DesignRoot {
             arena,
             diagnostics,
             has_circular_dependency: false,
+            ambiguous_operators: FnvHashMap::default(),
+            expression_types: FnvHashMap::default(),
         };
 
         unit.finish(result);
@@ -1144,6 +1415,8 @@ impl DesignRoot {
     // Returns the units that where re-analyzed
     pub fn analyze(&mut self, diagnostics: &mut dyn DiagnosticHandler) -> Vec<UnitId> {
         self.reset();
+        // Constants are evaluated after the analysis, the entities of re-analyzed units are new
+        self.constants.get_mut().clear();
 
         let mut units = Vec::default();
         for library in self.libraries.values() {
@@ -1220,6 +1493,15 @@ impl DesignRoot {
     }
 }
 
+/// The design unit that declares an operator, i.e. `-- Declared in ieee.numeric_std`
+fn declared_in_of_operator(ent: EntRef<'_>) -> Option<String> {
+    if !matches!(ent.designator(), Designator::OperatorSymbol(_)) {
+        return None;
+    }
+    let parent = ent.parent?;
+    Some(format!("-- Declared in {}", parent.path_name()))
+}
+
 fn get_all_affected(
     users_of: &FnvHashMap<UnitId, FnvHashSet<UnitId>>,
     mut affected: FnvHashSet<UnitId>,
@@ -255,6 +255,11 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                     self.choice_with_ttyp(scope, ctyp, choices, diagnostics)?;
                     self.analyze_sequential_part(scope, parent, item, diagnostics)?;
                 }
+                self.check_others_choice(alternatives, diagnostics);
+                self.check_locally_static_choices(alternatives, diagnostics);
+                if let Some(ctyp) = ctyp {
+                    self.check_choice_coverage(ctyp, expression, alternatives, diagnostics);
+                }
             }
             SequentialStatement::Loop(ref mut loop_stmt) => {
                 let LoopStatement {
@@ -291,6 +296,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             }
             SequentialStatement::SignalAssignment(ref mut assign) => {
                 self.analyze_waveform_assignment(scope, assign, diagnostics)?;
+                self.check_signal_assignment_context(parent, &assign.target, diagnostics);
             }
             SequentialStatement::VariableAssignment(ref mut assign) => {
                 let VariableAssignment { target, rhs } = assign;
@@ -305,24 +311,31 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             SequentialStatement::SignalForceAssignment(ref mut assign) => {
                 let SignalForceAssignment {
                     target,
-                    force_mode: _,
+                    force_mode,
                     rhs,
                 } = assign;
                 self.analyze_expr_assignment(
                     scope,
                     target,
-                    AssignmentType::Signal,
+                    AssignmentType::Force(*force_mode),
                     rhs,
                     diagnostics,
                 )?;
+                self.check_signal_assignment_context(parent, target, diagnostics);
             }
             SequentialStatement::SignalReleaseAssignment(ref mut assign) => {
                 let SignalReleaseAssignment {
                     target,
-                    force_mode: _,
+                    force_mode,
                     span: _,
                 } = assign;
-                as_fatal(self.resolve_target(scope, target, AssignmentType::Signal, diagnostics))?;
+                as_fatal(self.resolve_target(
+                    scope,
+                    target,
+                    AssignmentType::Force(*force_mode),
+                    diagnostics,
+                ))?;
+                self.check_signal_assignment_context(parent, target, diagnostics);
             }
             SequentialStatement::Null => {}
         }
@@ -443,3 +456,146 @@ impl<'a> From<EntRef<'a>> for SequentialRoot<'a> {
         }
     }
 }
+
+/// Whether execution may reach the end of `statements`, i.e. not every path
+/// ends with a `return` statement, an unconditional `exit` or `next` statement,
+/// a loop without exit or a report of severity `failure`.
+pub(crate) fn may_reach_end(statements: &[LabeledSequentialStatement]) -> bool {
+    statements.iter().all(|statement| {
+        may_complete(
+            &statement.statement.item,
+            statement.label.tree.as_ref().map(|label| &label.item),
+        )
+    })
+}
+
+/// Whether execution may continue after the statement with the label `label`
+fn may_complete(statement: &SequentialStatement, label: Option<&Symbol>) -> bool {
+    match statement {
+        SequentialStatement::Return(_) => false,
+        SequentialStatement::Exit(exit) => exit.condition.is_some(),
+        SequentialStatement::Next(next) => next.condition.is_some(),
+        SequentialStatement::Report(report) => !report
+            .severity
+            .as_ref()
+            .is_some_and(|severity| is_literal(&severity.item, "failure")),
+        SequentialStatement::Assert(assert) => {
+            !(is_literal(&assert.condition.item, "false")
+                && assert
+                    .severity
+                    .as_ref()
+                    .is_some_and(|severity| is_literal(&severity.item, "failure")))
+        }
+        SequentialStatement::If(if_stmt) => {
+            let Conditionals {
+                conditionals,
+                else_item,
+            } = &if_stmt.conds;
+            conditionals
+                .iter()
+                .any(|conditional| may_reach_end(&conditional.item))
+                || else_item
+                    .as_ref()
+                    .is_none_or(|(statements, _)| may_reach_end(statements))
+        }
+        SequentialStatement::Case(case_stmt) => case_stmt
+            .alternatives
+            .iter()
+            .any(|alternative| may_reach_end(&alternative.item)),
+        SequentialStatement::Loop(loop_stmt) => {
+            let is_infinite = match &loop_stmt.iteration_scheme {
+                None => true,
+                Some(IterationScheme::While(condition)) => is_literal(&condition.item, "true"),
+                Some(IterationScheme::For(..)) => false,
+            };
+            !is_infinite || contains_exit(&loop_stmt.statements, label, false)
+        }
+        SequentialStatement::Wait(_)
+        | SequentialStatement::VariableAssignment(_)
+        | SequentialStatement::SignalAssignment(_)
+        | SequentialStatement::SignalForceAssignment(_)
+        | SequentialStatement::SignalReleaseAssignment(_)
+        | SequentialStatement::ProcedureCall(_)
+        | SequentialStatement::Null => true,
+    }
+}
+
+/// Whether executing `statements` may suspend a process, i.e. they contain a wait statement
+/// or a procedure call that may contain one.
+pub(crate) fn may_wait(statements: &[LabeledSequentialStatement]) -> bool {
+    statements
+        .iter()
+        .any(|statement| match &statement.statement.item {
+            SequentialStatement::Wait(_) | SequentialStatement::ProcedureCall(_) => true,
+            SequentialStatement::If(if_stmt) => {
+                if_stmt
+                    .conds
+                    .conditionals
+                    .iter()
+                    .any(|conditional| may_wait(&conditional.item))
+                    || if_stmt
+                        .conds
+                        .else_item
+                        .as_ref()
+                        .is_some_and(|(statements, _)| may_wait(statements))
+            }
+            SequentialStatement::Case(case_stmt) => case_stmt
+                .alternatives
+                .iter()
+                .any(|alternative| may_wait(&alternative.item)),
+            SequentialStatement::Loop(loop_stmt) => may_wait(&loop_stmt.statements),
+            _ => false,
+        })
+}
+
+/// Whether `statements` contain an exit statement that may leave the enclosing loop
+/// with the label `label`. Within nested loops, only exit statements with this label
+/// are considered.
+fn contains_exit(
+    statements: &[LabeledSequentialStatement],
+    label: Option<&Symbol>,
+    nested: bool,
+) -> bool {
+    statements
+        .iter()
+        .any(|statement| match &statement.statement.item {
+            SequentialStatement::Exit(exit) => match &exit.loop_label {
+                Some(loop_label) => label == Some(&loop_label.item.item),
+                None => !nested,
+            },
+            SequentialStatement::If(if_stmt) => {
+                if_stmt
+                    .conds
+                    .conditionals
+                    .iter()
+                    .any(|conditional| contains_exit(&conditional.item, label, nested))
+                    || if_stmt
+                        .conds
+                        .else_item
+                        .as_ref()
+                        .is_some_and(|(statements, _)| contains_exit(statements, label, nested))
+            }
+            SequentialStatement::Case(case_stmt) => case_stmt
+                .alternatives
+                .iter()
+                .any(|alternative| contains_exit(&alternative.item, label, nested)),
+            SequentialStatement::Loop(loop_stmt) => {
+                contains_exit(&loop_stmt.statements, label, true)
+            }
+            _ => false,
+        })
+}
+
+/// Whether `expr` is a simple name such as `false` or `failure`
+fn is_literal(expr: &Expression, literal: &str) -> bool {
+    let Expression::Name(name) = expr else {
+        return false;
+    };
+    let Name::Designator(designator) = name.as_ref() else {
+        return false;
+    };
+    matches!(
+        &designator.item,
+        Designator::Identifier(ident) if ident.name_utf8().eq_ignore_ascii_case(literal)
+    )
+}
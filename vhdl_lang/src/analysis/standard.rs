@@ -231,7 +231,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         self.arena.get_type(self.standard_types().time)
     }
 
-    fn file_open_kind(&self) -> TypeEnt<'a> {
+    pub(crate) fn file_open_kind(&self) -> TypeEnt<'a> {
         self.arena.get_type(self.standard_types().file_open_kind)
     }
 
@@ -61,6 +61,20 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
 
         scope.add(subpgm_ent, diagnostics);
 
+        if let SubprogramSpecification::Function(function) = &mut body.specification {
+            let return_type = OverloadedEnt::from_any(subpgm_ent).and_then(|ent| ent.return_type());
+            if let (Some(ident), Some(return_type)) = (&mut function.return_identifier, return_type)
+            {
+                let ent = self.define(
+                    ident,
+                    subpgm_ent,
+                    AnyEntKind::Type(Type::Subtype(Subtype::new(return_type))),
+                    function.span,
+                );
+                subpgm_region.add(ent, diagnostics);
+            }
+        }
+
         self.define_labels_for_sequential_part(
             &subpgm_region,
             subpgm_ent,
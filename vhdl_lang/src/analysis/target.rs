@@ -48,7 +48,21 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             ErrorCode::MismatchedKinds,
             diagnostics,
         )?;
-        if !object_name.base.can_be_assigned_to() {
+        // Ports of mode in can be forced with force mode in, which is the default for them
+        let is_input_signal = object_name.base.is_input_signal();
+        if is_input_signal && matches!(assignment_type, AssignmentType::Force(Some(ForceMode::Out)))
+        {
+            diagnostics.add(
+                target_pos.pos(self.ctx),
+                format!(
+                    "{} may not be forced with force mode out",
+                    object_name.base.describe_class()
+                ),
+                ErrorCode::MismatchedKinds,
+            );
+        } else if !object_name.base.can_be_assigned_to()
+            && (!is_input_signal || !matches!(assignment_type, AssignmentType::Force(_)))
+        {
             diagnostics.add(
                 target_pos.pos(self.ctx),
                 format!(
@@ -70,6 +84,104 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         }
         Ok(object_name.type_mark())
     }
+
+    /// Check that a sequential signal assignment may appear where it does.
+    /// Signal assignments are not allowed within functions.
+    /// Within a procedure that is not declared within a process,
+    /// the target must be a formal parameter of the procedure or one of its parents (LRM 10.5.2.1).
+    pub fn check_signal_assignment_context(
+        &self,
+        parent: EntRef<'a>,
+        target: &WithTokenSpan<Target>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let Some(subprogram) = enclosing_subprogram(parent) else {
+            return;
+        };
+        if let AnyEntKind::Overloaded(overloaded) = subprogram.kind() {
+            if overloaded.signature().return_type().is_some() {
+                diagnostics.add(
+                    target.pos(self.ctx),
+                    "Signal assignment is not allowed within a function",
+                    ErrorCode::IllegalSignalAssignment,
+                );
+                return;
+            }
+        }
+        if is_within_process(subprogram) {
+            return;
+        }
+        let names: Vec<&Name> = match &target.item {
+            Target::Name(name) => vec![name],
+            Target::Aggregate(assocs) => assocs
+                .iter()
+                .filter_map(|assoc| match &assoc.item {
+                    ElementAssociation::Positional(expr) | ElementAssociation::Named(_, expr) => {
+                        match &expr.item {
+                            Expression::Name(name) => Some(name.as_ref()),
+                            _ => None,
+                        }
+                    }
+                })
+                .collect(),
+        };
+        for name in names {
+            let Some(id) = innermost_prefix_reference(name) else {
+                continue;
+            };
+            let ent = self.arena.get(id).as_actual();
+            let is_formal = matches!(ent.kind(), AnyEntKind::Object(obj) if obj.is_param())
+                && ent
+                    .parent
+                    .is_some_and(|parent| is_parent_of(parent, subprogram));
+            if !is_formal {
+                diagnostics.add(
+                    target.pos(self.ctx),
+                    "Target of a signal assignment within a procedure that is not declared within a process must be a parameter of the procedure",
+                    ErrorCode::IllegalSignalAssignment,
+                );
+            }
+        }
+    }
+}
+
+/// The subprogram that contains the sequential statements with the given parent, if any
+fn enclosing_subprogram(ent: EntRef<'_>) -> Option<EntRef<'_>> {
+    match ent.kind() {
+        AnyEntKind::Overloaded(_) => Some(ent),
+        AnyEntKind::Sequential(_) => ent.parent.and_then(enclosing_subprogram),
+        _ => None,
+    }
+}
+
+/// Whether the subprogram is declared within the declarative part of a process,
+/// possibly nested within other subprograms
+fn is_within_process(subprogram: EntRef<'_>) -> bool {
+    match subprogram.parent.map(|parent| parent.kind()) {
+        Some(AnyEntKind::Concurrent(Some(Concurrent::Process))) => true,
+        Some(AnyEntKind::Overloaded(_)) => subprogram.parent.is_some_and(is_within_process),
+        _ => false,
+    }
+}
+
+/// Whether `ent` is `subprogram` or one of the subprograms it is nested in
+fn is_parent_of(ent: EntRef<'_>, subprogram: EntRef<'_>) -> bool {
+    ent.id() == subprogram.id()
+        || matches!(subprogram.parent, Some(parent)
+            if matches!(parent.kind(), AnyEntKind::Overloaded(_)) && is_parent_of(ent, parent))
+}
+
+/// The entity denoted by the innermost prefix of a target name, i.e. `sig` for `sig(0).elem`
+fn innermost_prefix_reference(name: &Name) -> Option<EntityId> {
+    match name {
+        Name::Designator(designator) => designator.reference.get(),
+        Name::Selected(prefix, _) | Name::SelectedAll(prefix) | Name::Slice(prefix, _) => {
+            innermost_prefix_reference(&prefix.item)
+        }
+        Name::CallOrIndexed(call) => innermost_prefix_reference(&call.name.item),
+        Name::Attribute(attr) => innermost_prefix_reference(&attr.name.item),
+        Name::External(_) => None,
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -78,6 +190,8 @@ pub enum AssignmentType {
     Signal,
     // Assignment with :=
     Variable,
+    // Assignment with <= force or <= release and an optional force mode
+    Force(Option<ForceMode>),
 }
 
 impl AssignmentType {
@@ -85,6 +199,7 @@ impl AssignmentType {
         match self {
             AssignmentType::Signal => "signal",
             AssignmentType::Variable => "variable",
+            AssignmentType::Force(_) => "force",
         }
     }
 }
@@ -6,6 +6,7 @@
 
 use super::*;
 use vhdl_lang::data::error_codes::ErrorCode;
+use vhdl_lang::VHDLStandard::VHDL1993;
 
 #[test]
 fn overloaded_name_may_not_be_assignment_target() {
@@ -186,9 +187,9 @@ end entity;
 architecture a of ent is
   signal foo1 : natural := 0;
   alias foo2 is foo1;
-  shared variable foo3 : natural := 0;
 begin
   main : process
+    variable foo3 : natural := 0;
     variable foo4 : natural := 0;
   begin
     foo1 <= 1;
@@ -359,6 +360,56 @@ end architecture;
     check_diagnostics(diagnostics, expected);
 }
 
+#[test]
+fn checks_force_and_release_assignment_targets() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+    port (inp : in bit; outp : out bit);
+end entity;
+
+architecture a of ent is
+    signal sig : bit;
+begin
+    main : process
+        variable var : bit;
+    begin
+        sig <= force '1';
+        sig <= release;
+        outp <= force out '1';
+        inp <= force '1';
+        inp <= force in '1';
+        inp <= release;
+        inp <= force out '1';
+        inp <= release out;
+        var <= force '1';
+        wait;
+    end process;
+end architecture;
+",
+    );
+
+    let expected = vec![
+        Diagnostic::mismatched_kinds(
+            code.s("inp", 5),
+            "interface signal 'inp' of mode in may not be forced with force mode out",
+        ),
+        Diagnostic::mismatched_kinds(
+            code.s("inp", 6),
+            "interface signal 'inp' of mode in may not be forced with force mode out",
+        ),
+        Diagnostic::mismatched_kinds(
+            code.s1("var <=").s1("var"),
+            "variable 'var' may not be the target of a force assignment",
+        ),
+    ];
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(diagnostics, expected);
+}
+
 #[test]
 fn indexed_assignment_target() {
     let mut builder = LibraryBuilder::new();
@@ -597,7 +648,6 @@ end bar;
 
 architecture foo of bar is
     signal a : integer;
-    shared variable b: integer;
     file c: integer;
 
     procedure proc(
@@ -611,6 +661,7 @@ architecture foo of bar is
 begin
 
     baz: process is
+        variable b: integer;
         variable d: integer;
         variable e: character;
     begin
@@ -626,6 +677,16 @@ end architecture foo;
     check_diagnostics(
         diagnostics,
         vec![
+            Diagnostic::new(
+                code.s1("c: integer").s1("integer"),
+                "The type of a file must be a file type, got integer type 'INTEGER'",
+                ErrorCode::TypeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("f_d: integer").s1("integer"),
+                "The type of a file must be a file type, got integer type 'INTEGER'",
+                ErrorCode::TypeMismatch,
+            ),
             Diagnostic::new(
                 code.s1("proc(d, c, a, b)").s1("d"),
                 "Name must denote a signal name",
@@ -680,3 +741,692 @@ end package body;
     );
     check_no_diagnostics(&builder.analyze())
 }
+
+#[test]
+fn actual_of_formal_with_mode_out_must_be_assignable() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+    port (inp : in bit);
+end entity;
+
+architecture a of ent is
+    signal sig : bit;
+
+    procedure drive(signal target : out bit; signal source : in bit) is
+    begin
+        target <= source;
+    end procedure;
+
+    procedure update(variable target : inout bit) is
+    begin
+    end procedure;
+begin
+    drive(inp, sig);
+    drive(sig, inp);
+
+    process
+        constant c : bit := '0';
+    begin
+        drive(inp, sig);
+        update(c);
+        wait;
+    end process;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s("drive(inp, sig)", 1).s1("inp"),
+                "interface signal 'inp' of mode in may not be associated with a formal of mode out",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+            Diagnostic::new(
+                code.s("drive(inp, sig)", 2).s1("inp"),
+                "interface signal 'inp' of mode in may not be associated with a formal of mode out",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("update(c)").s1("c"),
+                "Name must denote a variable name",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn selector_of_selected_assignment_must_be_discrete_or_character_array() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+    type int_matrix_t is array (natural range <>, natural range <>) of integer;
+    signal r : real;
+    signal m : int_matrix_t(0 to 1, 0 to 1);
+    signal s : string(1 to 3);
+    signal i : integer;
+    signal b : bit;
+begin
+    with r select
+        b <= '1' when 0.0,
+             '0' when others;
+
+    with m select
+        b <= '1' when others;
+
+    with s select
+        b <= '1' when \"abc\",
+             '0' when others;
+
+    with i select
+        b <= '1' when 0 | 1,
+             '0' when others;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("with r").s1("r"),
+                "Selector must be of a discrete type or a one-dimensional character array type, got real type 'REAL'",
+                ErrorCode::TypeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("with m").s1("m"),
+                "Selector must be of a discrete type or a one-dimensional character array type, got array type 'int_matrix_t'",
+                ErrorCode::TypeMismatch,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn choices_of_selected_assignment_must_cover_enumeration_selector() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+    type state_t is (idle, busy, done);
+    signal state : state_t;
+    signal b : bit;
+begin
+    with state select
+        b <= '1' when idle,
+             '0' when busy;
+
+    with state select
+        b <= '1' when idle,
+             '0' when busy | done;
+
+    with state select
+        b <= '1' when idle,
+             '0' when others;
+
+    process
+        variable v : bit;
+    begin
+        with b select
+            v := '1' when '1';
+        wait;
+    end process;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s("with state", 1).s1("state"),
+                "Missing choice for done",
+                ErrorCode::MissingChoice,
+            ),
+            Diagnostic::new(
+                code.s1("with b").s1("b"),
+                "Missing choice for '0'",
+                ErrorCode::MissingChoice,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn choices_of_case_statements_must_cover_enumeration_selector() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+    generic (mode : bit);
+end entity;
+
+architecture a of ent is
+    type state_t is (idle, busy, done);
+    signal state : state_t;
+    signal b : bit;
+begin
+    process
+    begin
+        case state is
+            when idle => b <= '1';
+            when busy => b <= '0';
+        end case;
+
+        case state is
+            when idle => b <= '1';
+            when others => b <= '0';
+        end case;
+        wait;
+    end process;
+
+    gen: case mode generate
+        when '1' =>
+    end generate;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("case state").s1("state"),
+                "Missing choice for done",
+                ErrorCode::MissingChoice,
+            ),
+            Diagnostic::new(
+                code.s1("case mode").s1("mode"),
+                "Missing choice for '0'",
+                ErrorCode::MissingChoice,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn choices_of_selected_assignment_must_be_unique() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+    type state_t is (idle, busy, done);
+    signal state : state_t;
+    signal b : bit;
+begin
+    with state select
+        b <= '1' when idle | busy,
+             '0' when done | IDLE,
+             '0' when others;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::new(
+            code.s1("IDLE"),
+            "Choice idle is already covered",
+            ErrorCode::DuplicateChoice,
+        )],
+    );
+}
+
+#[test]
+fn choice_coverage_is_not_checked_when_it_requires_static_evaluation() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+    type state_t is (idle, busy, done, error);
+    subtype active_t is state_t range busy to done;
+    constant c_idle : state_t := idle;
+    signal state : state_t;
+    signal active : active_t;
+    signal also_active : state_t range busy to done;
+    signal i : integer;
+    signal b : bit;
+begin
+    with state select
+        b <= '1' when c_idle,
+             '0' when busy to error;
+
+    with active select
+        b <= '1' when busy,
+             '0' when done;
+
+    with also_active select
+        b <= '1' when busy,
+             '0' when done;
+
+    with i select
+        b <= '1' when 0;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn others_must_be_the_only_choice_of_the_last_alternative() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+    signal i : integer;
+    signal b : bit;
+begin
+    with i select
+        b <= '1' when others,
+             '0' when 0;
+
+    process
+    begin
+        case i is
+            when 0 => null;
+            when 1 | others => null;
+        end case;
+        wait;
+    end process;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("others"),
+                "'others' must be the only choice of the last alternative",
+                ErrorCode::MisplacedOthers,
+            ),
+            Diagnostic::new(
+                code.s("others", 2),
+                "'others' must be the only choice of the last alternative",
+                ErrorCode::MisplacedOthers,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn case_choices_must_be_locally_static() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+    generic (g : integer);
+end entity;
+
+architecture a of ent is
+    constant c : integer := 1;
+    signal sig : bit_vector(0 to 1);
+    signal i : integer;
+    signal b : bit;
+begin
+    with i select
+        b <= '1' when c | c + 1,
+             '1' when sig'length to 3,
+             '1' when g,
+             '0' when others;
+
+    process
+        variable v : integer;
+    begin
+        case i is
+            when 4 to v + 1 => null;
+            when others => null;
+        end case;
+        wait;
+    end process;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("when g").s1("g"),
+                "Choice must be locally static, got generic 'g'",
+                ErrorCode::NonStaticChoice,
+            ),
+            Diagnostic::new(
+                code.s1("4 to v + 1"),
+                "Choice must be locally static, got variable 'v'",
+                ErrorCode::NonStaticChoice,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn statically_known_delays_must_be_legal() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+    constant c_delay : time := 1 ns;
+    signal d, q : bit;
+begin
+    q <= d after -1 ns;
+    q <= reject -2 ns inertial d after 1 ns;
+    q <= '0', '1' after 2 ns, '0' after 1500 ps;
+    q <= '1' after 1 us, '0' after 1000 ns;
+    q <= reject 3 ns inertial d after 2 ns;
+
+    q <= '0', '1' after 0.5 ns, '0' after 1 ns;
+    q <= reject 1 ns inertial d after c_delay, '0' after c_delay;
+    q <= transport d after 1 ns;
+
+    process
+    begin
+        q <= '1' after 3 ns, '0' after 3 ns;
+        wait;
+    end process;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("-1 ns"),
+                "Delay must not be negative",
+                ErrorCode::IllegalDelay,
+            ),
+            Diagnostic::new(
+                code.s1("-2 ns"),
+                "Pulse rejection limit must not be negative",
+                ErrorCode::IllegalDelay,
+            ),
+            Diagnostic::new(
+                code.s1("1500 ps"),
+                "Delays of waveform elements must be in ascending order",
+                ErrorCode::IllegalDelay,
+            ),
+            Diagnostic::new(
+                code.s1("1000 ns"),
+                "Delays of waveform elements must be in ascending order",
+                ErrorCode::IllegalDelay,
+            ),
+            Diagnostic::new(
+                code.s1("d after 2 ns").s1("2 ns"),
+                "Pulse rejection limit must not be greater than the delay of the first waveform element",
+                ErrorCode::IllegalDelay,
+            ),
+            Diagnostic::new(
+                code.s("3 ns", 3),
+                "Delays of waveform elements must be in ascending order",
+                ErrorCode::IllegalDelay,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn signal_assignment_is_not_allowed_in_functions() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+    signal sig : bit;
+
+    impure function fun return bit is
+    begin
+        sig <= '1';
+        return '0';
+    end function;
+begin
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::new(
+            code.s1("sig <= '1'").s1("sig"),
+            "Signal assignment is not allowed within a function",
+            ErrorCode::IllegalSignalAssignment,
+        )],
+    );
+}
+
+#[test]
+fn signal_assignment_in_procedure_outside_process_must_target_parameter() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+    type rec_t is record
+        elem : bit;
+    end record;
+    signal sig : bit;
+    signal rec : rec_t;
+
+    procedure proc(signal arg : out bit; signal arg_rec : out rec_t) is
+        procedure nested is
+        begin
+            arg <= '1';
+        end procedure;
+    begin
+        arg <= '1';
+        arg_rec.elem <= '0';
+        sig <= '1';
+        rec.elem <= '0';
+    end procedure;
+begin
+    process
+        procedure local is
+        begin
+            sig <= '0';
+        end procedure;
+    begin
+        local;
+        sig <= '1';
+        wait;
+    end process;
+end architecture;
+",
+    );
+    let diagnostics = builder.analyze();
+    let message = "Target of a signal assignment within a procedure that is not declared within a process must be a parameter of the procedure";
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("sig <= '1'").s1("sig"),
+                message,
+                ErrorCode::IllegalSignalAssignment,
+            ),
+            Diagnostic::new(
+                code.s("rec.elem <= '0'", 2).s1("rec.elem"),
+                message,
+                ErrorCode::IllegalSignalAssignment,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn out_ports_may_not_be_read_before_vhdl_2008() {
+    let source = "
+entity leaf is
+  port (
+    d : in bit;
+    o : out bit);
+end entity;
+
+architecture a of leaf is
+begin
+  o <= d;
+end architecture;
+
+entity ent is
+  port (
+    q : out bit;
+    q_n : out bit;
+    q_leaf : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  q <= '1';
+  q_n <= not q;
+  inst : entity work.leaf port map (d => '0', o => q_leaf);
+end architecture;
+";
+    let mut builder = LibraryBuilder::with_standard(VHDL1993);
+    let code = builder.code("libname", source);
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::new(
+            code.s1("not q").s1("q"),
+            "interface signal 'q' of mode out may not be read before VHDL-2008",
+            ErrorCode::IllegalPortRead,
+        )],
+    );
+
+    let mut builder = LibraryBuilder::new();
+    builder.code("libname", source);
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn linkage_ports_may_not_be_read_or_assigned() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity leaf is
+  port (l : linkage bit);
+end entity;
+
+architecture a of leaf is
+begin
+end architecture;
+
+entity ent is
+  port (
+    l : linkage bit;
+    q : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  q <= l;
+  l <= '1';
+  inst : entity work.leaf port map (l => l);
+end architecture;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::new(
+                code.s1("<= l").s1("l"),
+                "interface signal 'l' of mode linkage may not be read",
+                ErrorCode::IllegalPortRead,
+            ),
+            Diagnostic::new(
+                code.s1("l <= '1'").s1("l"),
+                "interface signal 'l' of mode linkage may not be the target of an assignment",
+                ErrorCode::MismatchedKinds,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn buffer_port_associations_before_vhdl_2008() {
+    let source = "
+entity leaf is
+  port (
+    i : in bit;
+    o : out bit;
+    b : buffer bit);
+end entity;
+
+architecture a of leaf is
+begin
+  o <= i;
+  b <= i;
+end architecture;
+
+entity ent is
+  port (
+    buf_in : buffer bit;
+    buf_out : buffer bit;
+    out_buf : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  inst : entity work.leaf port map (i => buf_in, o => buf_out, b => out_buf);
+end architecture;
+";
+    let mut builder = LibraryBuilder::with_standard(VHDL1993);
+    let code = builder.code("libname", source);
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::new(
+                code.s1("=> buf_out").s1("buf_out"),
+                "interface signal 'buf_out' of mode buffer may not be associated with a formal of mode out before VHDL-2008",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("=> out_buf").s1("out_buf"),
+                "interface signal 'out_buf' of mode out may not be associated with a formal of mode buffer before VHDL-2008",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+        ],
+    );
+
+    let mut builder = LibraryBuilder::new();
+    builder.code("libname", source);
+    check_no_diagnostics(&builder.analyze());
+}
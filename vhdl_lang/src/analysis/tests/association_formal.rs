@@ -744,3 +744,267 @@ end entity;
         )],
     );
 }
+
+#[test]
+fn check_block_generic_and_port_map() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+   signal sig : boolean;
+begin
+   blk: block is
+      generic (thegeneric : natural);
+      generic map (missing => 0);
+      port (theport : in boolean);
+      port map (theport => sig);
+   begin
+   end block;
+end architecture;
+        ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("missing"),
+                "No declaration of 'missing'",
+                ErrorCode::Unresolved,
+            ),
+            Diagnostic::new(
+                code.s1("blk"),
+                "No association of generic 'thegeneric'",
+                ErrorCode::Unassociated,
+            )
+            .related(code.s1("thegeneric"), "Defined here"),
+        ],
+    );
+
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s("theport", 2).end()),
+        Some(code.s1("theport").pos())
+    );
+}
+
+#[test]
+fn ports_of_mode_other_than_in_may_be_unassociated() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent_inst is
+  port (
+    prt_out : out boolean;
+    prt_inout : inout boolean;
+    prt_buffer : buffer boolean;
+    prt_linkage : linkage boolean
+  );
+end entity;
+
+architecture a of ent_inst is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+   inst: entity work.ent_inst
+      port map (prt_out => open);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_no_diagnostics(&diagnostics);
+}
+
+#[test]
+fn open_association_of_formal_without_default() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent_inst is
+  generic (
+    gen : natural;
+    gen_default : natural := 0
+  );
+  port (
+    prt : in boolean;
+    prt_default : in boolean := false
+  );
+end entity;
+
+architecture a of ent_inst is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+   inst: entity work.ent_inst
+      generic map (gen => open, gen_default => open)
+      port map (prt => open, prt_default => open);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("gen => open").s1("open"),
+                "generic 'gen' without a default value cannot be left open",
+                ErrorCode::Unassociated,
+            )
+            .related(code.s1("gen :").s1("gen"), "Defined here"),
+            Diagnostic::new(
+                code.s1("prt => open").s1("open"),
+                "port 'prt' : in without a default value cannot be left open",
+                ErrorCode::Unassociated,
+            )
+            .related(code.s1("prt :").s1("prt"), "Defined here"),
+        ],
+    );
+}
+
+#[test]
+fn output_ports_of_unconstrained_composite_type_must_be_associated() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent_inst is
+  port (
+    prt_unconstrained : out bit_vector;
+    prt_open : out bit_vector;
+    prt_constrained : out bit_vector(7 downto 0)
+  );
+end entity;
+
+architecture a of ent_inst is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+   inst: entity work.ent_inst
+      port map (prt_open => open);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("work.ent_inst"),
+                "No association of port 'prt_unconstrained' : out",
+                ErrorCode::Unassociated,
+            )
+            .related(code.s1("prt_unconstrained"), "Defined here"),
+            Diagnostic::new(
+                code.s1("=> open").s1("open"),
+                "port 'prt_open' : out without a default value cannot be left open",
+                ErrorCode::Unassociated,
+            )
+            .related(code.s1("prt_open"), "Defined here"),
+        ],
+    );
+}
+
+#[test]
+fn actual_of_output_port_must_be_signal_name() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent_inst is
+  port (
+    i : in bit;
+    o : out bit;
+    io : inout bit;
+    b : buffer bit
+  );
+end entity;
+
+architecture a of ent_inst is
+begin
+end architecture;
+
+entity ent is
+  port (
+    iprt : in bit
+  );
+end entity;
+
+architecture a of ent is
+  function to_bit(value : boolean) return bit is
+  begin
+    return '0';
+  end function;
+
+  signal s : bit;
+  signal vec : bit_vector(0 to 1);
+  signal bool : boolean;
+  constant c : bit := '0';
+begin
+   good: entity work.ent_inst
+      port map (i => s and s, o => vec(0), io => to_bit(bool), b => s);
+
+   bad: entity work.ent_inst
+      port map (i => s, o => s and s, io => '1', b => c);
+
+   bad_in: entity work.ent_inst
+      port map (i => s, o => iprt, io => to_bit(true), b => s);
+end architecture;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("o => s and s").s1("s and s"),
+                "Expression must be a name denoting a signal",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("io => '1'").s1("'1'"),
+                "Expression must be a name denoting a signal",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("b => c").s1("c"),
+                "Name must denote a signal name",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("o => iprt").s1("iprt"),
+                "interface signal 'iprt' of mode in may not be associated with a formal of mode out",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("to_bit(true)").s1("true"),
+                "Name must denote a signal name",
+                ErrorCode::InterfaceModeMismatch,
+            ),
+        ],
+    );
+}
@@ -960,7 +960,7 @@ end package body;
 -- Configuration context clause reference
 use work.pkg.all;
 configuration cfg of ename1 is
-for rtl
+for a
 end for;
 end configuration;
 
@@ -156,3 +156,55 @@ end package body;
     let diagnostics = builder.analyze();
     check_diagnostics(diagnostics, vec![duplicate(&code, "a1", 2, 3)]);
 }
+
+#[test]
+fn full_declaration_must_conform_to_deferred_constant() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+constant good1 : natural;
+constant good2 : bit_vector(0 to 3);
+constant good3 : bit_vector(0 to 16#F#);
+constant bad1 : natural;
+constant bad2 : bit_vector(0 to 3);
+constant bad3 : bit_vector;
+end package;
+
+package body pkg is
+constant good1 : std.standard.natural := 0;
+constant good2 : bit_vector(0 TO 3) := \"0000\";
+constant good3 : bit_vector(0 to 15) := (others => '0');
+constant bad1 : integer := 0;
+constant bad2 : bit_vector(1 to 4) := \"0000\";
+constant bad3 : bit_vector(0 to 3) := \"0000\";
+end package body;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("integer"),
+                "Subtype indication of constant 'bad1' does not conform to the deferred constant declaration",
+                ErrorCode::DeferredConstantMismatch,
+            )
+            .related(code.s1("bad1"), "Deferred constant declared here"),
+            Diagnostic::new(
+                code.s1("bit_vector(1 to 4)"),
+                "Subtype indication of constant 'bad2' does not conform to the deferred constant declaration",
+                ErrorCode::DeferredConstantMismatch,
+            )
+            .related(code.s1("bad2"), "Deferred constant declared here"),
+            Diagnostic::new(
+                code.s("bit_vector(0 to 3)", 3),
+                "Subtype indication of constant 'bad3' does not conform to the deferred constant declaration",
+                ErrorCode::DeferredConstantMismatch,
+            )
+            .related(code.s1("bad3"), "Deferred constant declared here"),
+        ],
+    );
+}
@@ -7,6 +7,7 @@
 use super::*;
 use crate::data::error_codes::ErrorCode;
 use crate::EntHierarchy;
+use crate::InstancePath;
 use crate::Source;
 use pretty_assertions::assert_eq;
 
@@ -326,6 +327,126 @@ end architecture;
     assert_eq!(root.find_implementation(comp), vec![ent]);
 }
 
+#[test]
+fn find_implementation_of_configured_component() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity leaf is
+end entity;
+
+entity other_leaf is
+end entity;
+
+entity spec_leaf is
+end entity;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component leaf is
+  end component;
+
+  component spec_comp is
+  end component;
+
+  for all : spec_comp use entity work.spec_leaf;
+begin
+  u_leaf: component leaf;
+  u_spec: component spec_comp;
+end architecture;
+
+configuration cfg of top is
+  for a
+    for u_leaf : leaf
+      use entity libname.other_leaf;
+    end for;
+  end for;
+end configuration;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let leaf = root
+        .search_reference(code.source(), code.s1("entity leaf").s1("leaf").start())
+        .unwrap();
+    let other_leaf = root
+        .search_reference(code.source(), code.s1("other_leaf").start())
+        .unwrap();
+    let spec_leaf = root
+        .search_reference(code.source(), code.s1("spec_leaf").start())
+        .unwrap();
+    let comp = root
+        .search_reference(code.source(), code.s1("component leaf").s1("leaf").start())
+        .unwrap();
+    let spec_comp = root
+        .search_reference(
+            code.source(),
+            code.s1("component spec_comp").s1("spec_comp").start(),
+        )
+        .unwrap();
+
+    assert_eq!(root.find_implementation(comp), vec![other_leaf, leaf]);
+    assert_eq!(root.find_implementation(spec_comp), vec![spec_leaf]);
+}
+
+#[test]
+fn find_implementation_of_instance() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity leaf is
+end entity;
+
+architecture rtl of leaf is
+begin
+end architecture;
+
+architecture sim of leaf is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component leaf is
+  end component;
+begin
+  u_entity: entity work.leaf;
+  u_arch: entity work.leaf(sim);
+  u_comp: component leaf;
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let rtl = root
+        .search_reference(code.source(), code.s1("rtl").start())
+        .unwrap();
+    let sim = root
+        .search_reference(code.source(), code.s1("sim").start())
+        .unwrap();
+    let instance = |label: &str| {
+        root.search_reference(code.source(), code.s1(label).start())
+            .unwrap()
+    };
+
+    assert_eq!(
+        root.find_implementation(instance("u_entity")),
+        vec![rtl, sim]
+    );
+    assert_eq!(root.find_implementation(instance("u_arch")), vec![sim]);
+    assert_eq!(root.find_implementation(instance("u_comp")), vec![rtl, sim]);
+}
+
 #[test]
 fn exit_and_next_outside_of_loop() {
     let mut builder = LibraryBuilder::new();
@@ -424,3 +545,231 @@ end architecture;
         ],
     );
 }
+
+#[test]
+fn exit_and_next_label_must_be_a_loop() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  main: process
+  begin
+    outer: loop
+        cond: if true then
+            exit main;
+            next cond;
+            exit missing;
+        end if;
+    end loop;
+  end process;
+end architecture;
+      ",
+    );
+
+    let (_, diagnostics) = builder.get_analyzed_root();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.sa("exit ", "main"),
+                "Expected loop label, got process 'main'",
+                ErrorCode::MismatchedKinds,
+            ),
+            Diagnostic::new(
+                code.sa("next ", "cond"),
+                "Expected loop label, got if statement 'cond'",
+                ErrorCode::MismatchedKinds,
+            ),
+            Diagnostic::new(
+                code.s1("missing"),
+                "No declaration of 'missing'",
+                ErrorCode::Unresolved,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn goto_loop_from_exit_and_next_label() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  process
+  begin
+    outer: for i in 0 to 3 loop
+        inner: while true loop
+            next outer when i = 1;
+            exit inner;
+        end loop;
+    end loop;
+  end process;
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    for (label, reference) in [("outer", "next outer"), ("inner", "exit inner")] {
+        let decl = code.s1(label);
+        let reference = code.s1(reference).s1(label);
+        assert_eq!(
+            root.search_reference_pos(code.source(), reference.start()),
+            Some(decl.pos())
+        );
+        assert_eq!(
+            root.find_all_references_pos(&decl.pos()),
+            vec![decl.pos(), reference.pos()]
+        );
+    }
+}
+
+#[test]
+fn find_instances_through_multiple_parents() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity leaf is
+end entity;
+
+architecture a of leaf is
+begin
+end architecture;
+
+entity mid is
+end entity;
+
+architecture a of mid is
+  component leaf is
+  end component;
+begin
+  u_leaf: component leaf;
+  gen: for i in 0 to 1 generate
+    u_leaf_gen: entity work.leaf;
+  end generate;
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+begin
+  u_mid0: entity work.mid;
+  blk: block
+  begin
+    u_mid1: entity work.mid(a);
+  end block;
+end architecture;
+
+entity other_top is
+end entity;
+
+architecture a of other_top is
+begin
+  u_leaf: entity work.leaf;
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let ent = root
+        .search_reference(code.source(), code.s1("leaf").start())
+        .unwrap();
+    let paths: Vec<_> = root
+        .find_instances(ent)
+        .into_iter()
+        .map(|instance| (instance.to_string(), instance.pos))
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            (
+                "other_top.u_leaf".to_owned(),
+                Some(code.s1("u_leaf: entity").s1("u_leaf").pos())
+            ),
+            (
+                "top.blk.u_mid1.gen.u_leaf_gen".to_owned(),
+                Some(code.s1("u_leaf_gen").pos())
+            ),
+            (
+                "top.blk.u_mid1.u_leaf".to_owned(),
+                Some(code.s1("u_leaf").pos())
+            ),
+            (
+                "top.u_mid0.gen.u_leaf_gen".to_owned(),
+                Some(code.s1("u_leaf_gen").pos())
+            ),
+            (
+                "top.u_mid0.u_leaf".to_owned(),
+                Some(code.s1("u_leaf").pos())
+            ),
+        ]
+    );
+
+    // Top-level entities are their own single instance
+    let ent = root
+        .search_reference(code.source(), code.s1("entity top").s1("top").start())
+        .unwrap();
+    assert_eq!(
+        root.find_instances(ent),
+        vec![InstancePath {
+            path: vec!["top".to_owned()],
+            pos: None
+        }]
+    );
+}
+
+#[test]
+fn find_instances_of_recursive_entity() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity rec is
+  generic (depth : natural);
+end entity;
+
+architecture a of rec is
+begin
+  gen: if depth > 0 generate
+    u_rec: entity work.rec generic map (depth => depth - 1);
+  end generate;
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+begin
+  u_rec: entity work.rec generic map (depth => 2);
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let ent = root
+        .search_reference(code.source(), code.s1("rec").start())
+        .unwrap();
+    let paths: Vec<_> = root
+        .find_instances(ent)
+        .into_iter()
+        .map(|instance| instance.to_string())
+        .collect();
+    assert_eq!(paths, vec!["top.u_rec".to_owned()]);
+}
@@ -0,0 +1,530 @@
+use super::*;
+use crate::data::error_codes::ErrorCode;
+use pretty_assertions::assert_eq;
+
+fn hover(root: &DesignRoot, code: &Code) -> Option<String> {
+    root.format_declaration(root.search_reference(code.source(), code.start())?)
+}
+
+#[test]
+fn hover_shows_constraint_with_generic_defaults_substituted() {
+    let mut builder = LibraryBuilder::new();
+    builder.add_std_logic_1164();
+    let code = builder.code(
+        "libname",
+        "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+  generic (
+    WIDTH : natural := 8;
+    DEPTH : natural := 2 ** (WIDTH / 2)
+  );
+end entity;
+
+architecture a of ent is
+  constant MAX : integer := DEPTH - 1;
+  signal data : std_logic_vector(WIDTH - 1 downto 0);
+  signal counter : integer range 0 to MAX;
+  signal literal_data : std_logic_vector(7 downto 0);
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        hover(&root, &code.s1("data")),
+        Some(
+            "\
+signal data : std_logic_vector(WIDTH - 1 downto 0);
+-- Resolved subtype: std_logic_vector(7 downto 0)
+-- Type chain: STD_LOGIC_VECTOR -> STD_ULOGIC_VECTOR"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        hover(&root, &code.s1("counter")),
+        Some(
+            "\
+signal counter : integer range 0 to MAX;
+-- Resolved subtype: integer range 0 to 15"
+                .to_owned()
+        )
+    );
+    // Nothing to resolve
+    assert_eq!(
+        hover(&root, &code.s1("literal_data")),
+        Some(
+            "\
+signal literal_data : std_logic_vector(7 downto 0);
+-- Type chain: STD_LOGIC_VECTOR -> STD_ULOGIC_VECTOR"
+                .to_owned()
+        )
+    );
+}
+
+#[test]
+fn hover_resolves_constraint_through_subtype_declarations() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  constant BYTES : natural := 4;
+  subtype word_t is bit_vector(8 * BYTES - 1 downto 0);
+  subtype my_word_t is word_t;
+  signal word : my_word_t;
+  signal unknown : bit_vector(word_t'high downto 0);
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        hover(&root, &code.s1("word_t is")),
+        Some(
+            "\
+subtype word_t is bit_vector(8 * BYTES - 1 downto 0);
+-- Resolved subtype: bit_vector(31 downto 0)
+-- Type chain: word_t -> BIT_VECTOR"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        hover(&root, &code.s1("word :")),
+        Some(
+            "\
+signal word : my_word_t;
+-- Resolved subtype: bit_vector(31 downto 0)
+-- Type chain: my_word_t -> word_t -> BIT_VECTOR"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        hover(&root, &code.s1("unknown")),
+        Some("signal unknown : bit_vector(word_t'high downto 0);".to_owned())
+    );
+}
+
+#[test]
+fn hover_on_operator_shows_selected_overload() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type rec_t is record
+    field : natural;
+  end record;
+  function \"+\"(left, right : rec_t) return rec_t;
+end package;
+
+use work.pkg.all;
+
+package user is
+  constant rec : rec_t := (field => 0);
+  constant sum : rec_t := rec + rec;
+  constant int : integer := 1 + 2;
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        hover(&root, &code.s1("rec + rec").s1("+")),
+        Some(
+            "\
+function \"+\"(
+  constant left, right : rec_t
+) return rec_t
+-- Declared in libname.pkg"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        hover(&root, &code.s1("1 + 2").s1("+")),
+        Some(
+            "\
+-- operator \"+\"[INTEGER, INTEGER return INTEGER]
+
+-- Implicitly defined by:
+type INTEGER is range -2147483647 to 2147483647;
+
+-- Declared in std.standard"
+                .to_owned()
+        )
+    );
+}
+
+#[test]
+fn hover_on_ambiguous_operator_lists_candidates() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type t1 is (a1, b1);
+  type t2 is (a2, b2);
+  function \"+\"(left, right : integer) return t1;
+  function \"+\"(left, right : integer) return t2;
+  procedure proc(arg : t1);
+  procedure proc(arg : t2);
+end package;
+
+use work.pkg.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  process
+    variable i : integer;
+  begin
+    proc(i + i);
+  end process;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::new(
+            code.s1("proc(i + i)").s1("proc"),
+            "Ambiguous call to 'proc'",
+            ErrorCode::AmbiguousCall,
+        )
+        .related(
+            code.s1("proc(arg : t1)").s1("proc"),
+            "Might be procedure proc[t1]",
+        )
+        .related(
+            code.s1("proc(arg : t2)").s1("proc"),
+            "Might be procedure proc[t2]",
+        )],
+    );
+    let cursor = code.s1("i + i").s1("+");
+    assert_eq!(root.search_reference(cursor.source(), cursor.start()), None);
+    assert_eq!(
+        root.format_ambiguous_operator(cursor.source(), cursor.start()),
+        Some(
+            "\
+-- Ambiguous use of operator \"+\", candidates are:
+-- operator \"+\"[INTEGER, INTEGER return INTEGER]
+-- Declared in std.standard
+-- operator \"+\"[INTEGER, INTEGER return t1]
+-- Declared in libname.pkg
+-- operator \"+\"[INTEGER, INTEGER return t2]
+-- Declared in libname.pkg"
+                .to_owned()
+        )
+    );
+}
+
+#[test]
+fn hover_on_constant_shows_evaluated_value() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  generic (
+    WIDTH : natural := 4 * 2
+  );
+end entity;
+
+architecture a of ent is
+  constant MASK : natural := 2 ** WIDTH - 1;
+  constant OFFSET : integer := -(WIDTH mod 3);
+  constant SCALE : real := 1.5 * 2.0;
+  constant UNKNOWN : natural := MASK'high;
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        hover(&root, &code.s1("WIDTH")),
+        Some(
+            "\
+generic WIDTH : natural := 4 * 2;
+-- Default value: 8 (16#8#, 2#1000#)
+-- Type chain: NATURAL -> INTEGER"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        hover(&root, &code.s1("MASK")),
+        Some(
+            "\
+constant MASK : natural := 2 ** WIDTH - 1;
+-- Value: 255 (16#FF#, 2#11111111#)
+-- Type chain: NATURAL -> INTEGER"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        hover(&root, &code.s1("OFFSET")),
+        Some("constant OFFSET : integer := -(WIDTH mod 3);\n-- Value: -2".to_owned())
+    );
+    assert_eq!(
+        hover(&root, &code.s1("SCALE")),
+        Some("constant SCALE : real := 1.5 * 2;\n-- Value: 3.0".to_owned())
+    );
+    assert_eq!(
+        hover(&root, &code.s1("UNKNOWN")),
+        Some(
+            "\
+constant UNKNOWN : natural := MASK'high;
+-- Type chain: NATURAL -> INTEGER"
+                .to_owned()
+        )
+    );
+}
+
+#[test]
+fn hover_on_constant_shows_evaluated_non_numeric_value() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type state_t is (idle, busy, done);
+  constant LAST : state_t := done;
+  constant IS_LATE : boolean := LAST > busy and not (idle = busy);
+  constant PERIOD : time := 2 * 5 ns + 500 ps;
+  constant CYCLES : integer := 1 us / PERIOD;
+  constant NAME : string := \"core\" & \"_\" & \"top\";
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let value = |name: &str| {
+        hover(&root, &code.s1(name))
+            .and_then(|hover| hover.lines().nth(1).map(|line| line.to_owned()))
+    };
+    assert_eq!(value("LAST"), Some("-- Value: done".to_owned()));
+    assert_eq!(value("IS_LATE"), Some("-- Value: true".to_owned()));
+    assert_eq!(value("PERIOD"), Some("-- Value: 10500000 fs".to_owned()));
+    assert_eq!(
+        value("CYCLES"),
+        Some("-- Value: 95 (16#5F#, 2#1011111#)".to_owned())
+    );
+    assert_eq!(value("NAME"), Some("-- Value: \"core_top\"".to_owned()));
+}
+
+#[test]
+fn interface_table_of_entity_and_component() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  generic (
+    WIDTH : natural := 8
+  );
+  port (
+    clk, rst : in bit;
+    data : out bit_vector(WIDTH - 1 downto 0) := (others => '0');
+    valid : buffer boolean
+  );
+end entity;
+
+architecture a of ent is
+  component comp is
+    generic (
+      type data_t
+    );
+    port (
+      d : data_t
+    );
+  end component;
+begin
+  inst: entity work.ent port map (clk => '0', rst => '0', valid => open);
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let table = "\
+| Generic | Type | Default |
+|---|---|---|
+| `WIDTH` | `natural` | `8` |
+
+| Port | Mode | Type | Default |
+|---|---|---|---|
+| `clk` | `in` | `bit` |  |
+| `rst` | `in` | `bit` |  |
+| `data` | `out` | `bit_vector(WIDTH - 1 downto 0)` | `(others => '0')` |
+| `valid` | `buffer` | `boolean` |  |";
+
+    let ent = root
+        .search_reference(code.source(), code.s1("entity ent is").s1("ent is").start())
+        .unwrap();
+    assert_eq!(root.format_interface_table(ent), Some(table.to_owned()));
+    let instantiated = root
+        .search_reference(code.source(), code.s1("work.ent").s1("ent").start())
+        .unwrap();
+    assert_eq!(
+        root.format_interface_table(instantiated),
+        Some(table.to_owned())
+    );
+
+    let comp = root
+        .search_reference(
+            code.source(),
+            code.s1("component comp is").s1("comp is").start(),
+        )
+        .unwrap();
+    assert_eq!(
+        root.format_interface_table(comp),
+        Some(
+            "\
+| Generic | Type | Default |
+|---|---|---|
+| `data_t` | `type` |  |
+
+| Port | Mode | Type | Default |
+|---|---|---|---|
+| `d` | `in` | `data_t` |  |"
+                .to_owned()
+        )
+    );
+
+    let arch = root
+        .search_reference(code.source(), code.s1("a of ent").start())
+        .unwrap();
+    assert_eq!(root.format_interface_table(arch), None);
+}
+
+#[test]
+fn hover_shows_normalized_value_of_physical_literals() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type distance_t is range 0 to 1e9
+    units
+      um;
+      mm = 1000 um;
+      m = 1000 mm;
+    end units;
+
+  constant DELAY : time := 2500 ns;
+  constant SHORT : distance_t := 3 um;
+  constant LONG : distance_t := 1.5 mm;
+  constant EXACT : distance_t := 2000 mm;
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let literal = |code: &Code| root.format_physical_literal(code.source(), code.start());
+
+    assert_eq!(
+        literal(&code.s1("2500")),
+        Some("-- Value: 2500000000 fs (2.5 us)".to_owned())
+    );
+    assert_eq!(
+        literal(&code.s1("2500 ns").s1("ns")),
+        Some("-- Value: 2500000000 fs (2.5 us)".to_owned())
+    );
+    assert_eq!(literal(&code.s1("3 um")), Some("-- Value: 3 um".to_owned()));
+    assert_eq!(
+        literal(&code.s1("1.5 mm")),
+        Some("-- Value: 1500 um (1.5 mm)".to_owned())
+    );
+    assert_eq!(
+        literal(&code.s1("2000 mm")),
+        Some("-- Value: 2000000 um (2 m)".to_owned())
+    );
+    assert_eq!(literal(&code.s1("DELAY")), None);
+}
+
+#[test]
+fn hover_shows_comments_that_document_the_declaration() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+-- File header that does not document the entity
+
+-- The documentation
+-- of the entity
+entity ent is
+  port (
+    clk : in bit; -- The clock
+    rst : in bit
+  );
+end entity;
+
+architecture a of ent is
+  -- The documentation of the signal
+  signal sig : bit; -- with a trailing comment
+  /* Block comment */
+  constant const : bit := '0';
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        hover(&root, &code.s1("ent is").s1("ent")).map(|value| value
+            .lines()
+            .take(3)
+            .collect::<Vec<_>>()
+            .join("\n")),
+        Some(
+            "\
+-- The documentation
+-- of the entity
+entity ent is"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        hover(&root, &code.s1("clk")),
+        Some("-- The clock\nport clk : in bit;".to_owned())
+    );
+    assert_eq!(
+        hover(&root, &code.s1("rst")),
+        Some("port rst : in bit;".to_owned())
+    );
+    assert_eq!(
+        hover(&root, &code.s1("sig :").s1("sig")),
+        Some(
+            "\
+-- The documentation of the signal
+-- with a trailing comment
+signal sig : bit;"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        hover(&root, &code.s1("const :").s1("const")),
+        Some("/* Block comment */\nconstant const : bit := '0';".to_owned())
+    );
+}
@@ -47,6 +47,111 @@ end package body;
     );
 }
 
+#[test]
+fn error_on_file_declarations_with_wrong_types() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+end package;
+
+package body pkg is
+  type binary_file_t is file of character;
+
+  procedure proc is
+    file not_a_file : character;
+    file bad_kind : binary_file_t open 0 is \"foo.txt\";
+    file bad_name : binary_file_t open write_mode is 0;
+    file good : binary_file_t open write_mode is \"foo.txt\";
+  begin
+  end procedure;
+end package body;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("not_a_file : character").s1("character"),
+                "The type of a file must be a file type, got type 'CHARACTER'",
+                ErrorCode::TypeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("open 0").s1("0"),
+                "integer literal does not match type 'FILE_OPEN_KIND'",
+                ErrorCode::TypeMismatch,
+            ),
+            Diagnostic::new(
+                code.s1("is 0").s1("0"),
+                "integer literal does not match array type 'STRING'",
+                ErrorCode::TypeMismatch,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn implicit_file_subprograms_are_declared_by_the_file_type() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type binary_file_t is file of character;
+end package;
+
+use work.pkg.binary_file_t;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  process
+    file f : binary_file_t;
+  begin
+    if not endfile(f) then
+      file_close(f);
+    end if;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    for name in ["endfile", "file_close"] {
+        let ent = root
+            .search_reference(code.source(), code.s1(name).start())
+            .unwrap();
+        assert_eq!(ent.designator().to_string().to_lowercase(), name);
+        assert!(ent.is_implicit());
+        assert_eq!(
+            root.find_definition_of(ent)
+                .and_then(|ent| ent.decl_pos().cloned()),
+            Some(code.s1("binary_file_t").pos())
+        );
+    }
+
+    let options =
+        crate::list_completion_options(&root, code.source(), code.s1("file_close").start());
+    for name in ["FILE_OPEN", "FILE_CLOSE", "READ", "WRITE", "ENDFILE"] {
+        assert!(
+            options.iter().any(|option| match option {
+                crate::CompletionItem::Overloaded(designator, _) => designator.to_string() == name,
+                crate::CompletionItem::Simple(ent) => ent.designator().to_string() == name,
+                _ => false,
+            }),
+            "Expected {name} to be completed"
+        );
+    }
+}
+
 #[test]
 fn adds_to_string_for_integer_types() {
     check_code_with_no_diagnostics(
@@ -283,3 +388,40 @@ end package;
 ",
     );
 }
+
+#[test]
+fn guarded_block_declares_guard_signal() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : integer;
+  signal is_guarded : boolean;
+begin
+  blk: block (sig = 1) is
+  begin
+    is_guarded <= guard;
+  end block;
+
+  is_guarded <= guard;
+end architecture;
+",
+    );
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::new(
+            code.s("<= guard", 2).s1("guard"),
+            "No declaration of 'guard'",
+            ErrorCode::Unresolved,
+        )],
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("<= guard").s1("guard").start()),
+        Some(code.s1("sig = 1").pos())
+    );
+}
@@ -158,7 +158,7 @@ end architecture;
         "libname",
         "
 configuration cfg of ent is
-for rtl
+for a
 end for;
 end configuration;
 ",
@@ -13,6 +13,7 @@ mod declarations;
 mod deferred_constant;
 mod hierarchy;
 mod homographs;
+mod hover;
 mod implicit;
 mod incomplete_type;
 mod incremental_analysis;
@@ -47,6 +48,7 @@ use super::scope::*;
 use super::DesignRoot;
 use crate::named_entity::*;
 use crate::Source;
+use crate::VHDLStandard;
 
 pub(super) struct TestSetup<'a> {
     builder: RefCell<LibraryBuilder>,
@@ -81,6 +83,7 @@ impl<'a> TestSetup<'a> {
             Source::inline(&PathBuf::new(), ""),
             &self.arena,
             tokens,
+            VHDLStandard::default(),
         );
         ctx.add_implicit_context_clause(&self.scope).unwrap();
         ctx
@@ -114,6 +114,12 @@ package ipkg3 is new work.gpkg
                 "No declaration of 'missing'",
                 ErrorCode::Unresolved,
             ),
+            Diagnostic::new(
+                code.s1("ipkg1"),
+                "No association of type 'type_t'",
+                ErrorCode::Unassociated,
+            )
+            .related(code.s1("type_t"), "Defined here"),
             Diagnostic::new(
                 code.s("missing", 2),
                 "No declaration of 'missing'",
@@ -548,7 +554,7 @@ end package;
 
     assert_eq!(
         root.format_declaration(sub_t),
-        Some("subtype sub_t is type_t;".to_owned())
+        Some("subtype sub_t is type_t;\n-- Type chain: sub_t -> type_t".to_owned())
     );
 
     assert_eq!(
@@ -685,3 +691,103 @@ end architecture;
     let diag = builder.analyze();
     check_no_diagnostics(&diag);
 }
+
+#[test]
+fn error_on_missing_and_repeated_generic_associations() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package gpkg is
+  generic (
+    type type_t;
+    width : natural;
+    depth : natural := 4
+  );
+end package;
+
+package ipkg1 is new work.gpkg
+  generic map (
+    type_t => integer,
+    type_t => natural,
+    depth => open
+  );
+
+package ipkg2 is new work.gpkg
+  generic map (
+    type_t => integer,
+    width => 1,
+    depth => open
+  );
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::new(
+                code.s1("type_t => natural").s1("natural"),
+                "type 'type_t' has already been associated",
+                ErrorCode::AlreadyAssociated,
+            )
+            .related(
+                code.s1("type_t => integer").s1("integer"),
+                "Previously associated here",
+            ),
+            Diagnostic::new(
+                code.s1("ipkg1"),
+                "No association of generic 'width'",
+                ErrorCode::Unassociated,
+            )
+            .related(code.s1("width"), "Defined here"),
+        ],
+    );
+}
+
+#[test]
+fn error_on_package_actual_of_other_package() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package gpkg_base is
+  generic (type type_t);
+  subtype elem_t is type_t;
+end package;
+
+package gpkg_other is
+  generic (type type_t);
+  subtype elem_t is type_t;
+end package;
+
+package gpkg is
+  generic (
+    package iface_pkg is new work.gpkg_base generic map (<>)
+  );
+end package;
+
+package ipkg_base is new work.gpkg_base generic map (type_t => integer);
+package ipkg_other is new work.gpkg_other generic map (type_t => integer);
+
+package ipkg1 is new work.gpkg generic map (iface_pkg => work.ipkg_base);
+package ipkg2 is new work.gpkg generic map (iface_pkg => work.ipkg_other);
+package ipkg3 is new work.gpkg generic map (iface_pkg => work.gpkg_base);
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::new(
+                code.s1("work.ipkg_other"),
+                "Package instance 'ipkg_other' is not an instance of uninstantiated package 'gpkg_base'",
+                ErrorCode::MismatchedKinds,
+            ),
+            Diagnostic::new(
+                code.s1("=> work.gpkg_base").s1("work.gpkg_base"),
+                "Cannot map uninstantiated package 'gpkg_base' to package generic",
+                ErrorCode::MismatchedKinds,
+            ),
+        ],
+    );
+}
@@ -6,6 +6,7 @@
 
 use super::*;
 use vhdl_lang::data::error_codes::ErrorCode;
+use vhdl_lang::VHDLStandard::VHDL1993;
 
 #[test]
 fn error_on_missing_protected_body() {
@@ -361,3 +362,53 @@ end package body;
         2
     );
 }
+
+#[test]
+fn shared_variable_must_be_of_protected_type() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type prot_t is protected
+end protected;
+
+type prot_t is protected body
+end protected body;
+
+subtype sub_prot_t is prot_t;
+
+shared variable good1 : prot_t;
+shared variable good2 : sub_prot_t;
+shared variable bad : natural;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::new(
+            code.s1("bad : natural").s1("natural"),
+            "The type of a shared variable must be a protected type",
+            ErrorCode::NonProtectedSharedVariable,
+        )],
+    );
+}
+
+#[test]
+fn shared_variable_of_non_protected_type_is_not_portable_before_vhdl2008() {
+    let mut builder = LibraryBuilder::with_standard(VHDL1993);
+    let code = builder.in_declarative_region(
+        "
+shared variable var : natural;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::new(
+            code.s1("natural"),
+            "Shared variable of non-protected type is not allowed in VHDL-2008 and later",
+            ErrorCode::UnportableSharedVariable,
+        )],
+    );
+}
@@ -22,6 +22,10 @@ end configuration;
 
 entity ent is
 end entity;
+
+architecture rtl of ent is
+begin
+end architecture;
 ",
     );
 
@@ -67,6 +71,10 @@ fn good_configurations() {
 entity ent is
 end entity;
 
+architecture rtl of ent is
+begin
+end architecture;
+
 configuration cfg_good1 of ent is
 for rtl
 end for;
@@ -126,6 +134,10 @@ fn search_reference_from_configuration_to_entity() {
 entity decl is
 end entity;
 
+architecture rtl of decl is
+begin
+end architecture;
+
 configuration cfg_good1 of decl is
 for rtl
 end for;
@@ -247,6 +259,87 @@ end architecture;
     );
 }
 
+#[test]
+fn resolves_reference_from_verification_unit_to_entity() {
+    check_search_reference(
+        "
+entity decl is
+end entity;
+
+vunit props (decl) {
+}
+",
+    );
+}
+
+#[test]
+fn resolves_names_of_verification_units_in_the_bound_architecture() {
+    check_search_reference(
+        "
+entity ent is
+end entity;
+
+architecture rtl of ent is
+  signal decl : bit;
+begin
+end architecture;
+
+vunit props (ent(rtl)) {
+  signal copy : bit := decl;
+  assert always decl -> next copy;
+}
+",
+    );
+}
+
+#[test]
+fn error_on_verification_unit_of_missing_architecture() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk : in bit);
+end entity;
+
+vunit props (ent(rtl)) {
+  default clock is rising_edge(clk);
+  assert always clk -> next unknown;
+}
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::new(
+            code.s1("rtl"),
+            "No architecture 'rtl' for entity 'libname.ent'",
+            ErrorCode::Unresolved,
+        )],
+    );
+}
+
+#[test]
+fn error_on_verification_unit_declarations_that_are_homographs_of_the_bound_design() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk : in bit);
+end entity;
+
+vunit props (ent) {
+  signal clk : bit;
+}
+",
+    );
+
+    let diagnostics = builder.analyze();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].pos, code.s("clk", 2).pos());
+}
+
 #[test]
 fn resolves_reference_from_package_body_to_package() {
     check_search_reference(
@@ -365,12 +458,19 @@ fn search_configuration_instance() {
 entity ent is
 end entity;
 
+architecture a of ent is
+begin
+end architecture;
+
 configuration decl of ent is
   for a
   end for;
 end configuration;
 
-architecture a of ent is
+entity top is
+end entity;
+
+architecture a of top is
 begin
   inst : configuration work.decl;
 end architecture;
@@ -548,3 +648,250 @@ end architecture;
         Some(&code.s1("empty").pos())
     );
 }
+
+#[test]
+fn error_on_configuration_of_missing_architecture() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+configuration cfg of ent is
+for rtl
+end for;
+end configuration;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::new(
+            code.s1("rtl"),
+            "No architecture 'rtl' for entity 'libname.ent'",
+            ErrorCode::Unresolved,
+        )],
+    );
+}
+
+#[test]
+fn good_block_and_component_configurations() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity leaf is
+end entity;
+
+architecture rtl of leaf is
+begin
+  blk : block
+  begin
+  end block;
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component leaf is
+  end component;
+begin
+  inst0 : component leaf;
+
+  gen : for i in 0 to 1 generate
+    inst1 : leaf;
+  end generate;
+
+  blk : block
+  begin
+    inst2 : component leaf;
+  end block;
+end architecture;
+
+configuration leaf_cfg of leaf is
+  for rtl
+  end for;
+end configuration;
+
+configuration cfg of ent is
+  for a
+    for inst0 : leaf
+      use entity work.leaf(rtl);
+      for rtl
+        for blk
+        end for;
+      end for;
+    end for;
+    for gen(0)
+      for all : leaf
+        use configuration work.leaf_cfg;
+      end for;
+    end for;
+    for blk
+      for others : leaf
+        use open;
+      end for;
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn error_on_block_and_component_configurations_that_do_not_match_the_architecture() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity leaf is
+end entity;
+
+architecture rtl of leaf is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component leaf is
+  end component;
+  component other is
+  end component;
+  signal sig : bit;
+begin
+  inst0 : component leaf;
+  inst1 : component other;
+  proc : process
+  begin
+    wait;
+  end process;
+end architecture;
+
+configuration cfg of ent is
+  for a
+    for missing_blk
+    end for;
+    for proc
+    end for;
+    for missing_inst : leaf
+    end for;
+    for inst1 : leaf
+    end for;
+    for proc : leaf
+    end for;
+    for inst0 : leaf
+      use entity work.leaf(missing_arch);
+    end for;
+    for all : other
+      use entity work.ent_missing;
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    check_diagnostics(
+        builder.analyze(),
+        vec![
+            Diagnostic::new(
+                code.s1("missing_blk"),
+                "No block or generate statement with label 'missing_blk'",
+                ErrorCode::Unresolved,
+            ),
+            Diagnostic::new(
+                code.s("proc", 4),
+                "'proc' is not a block or generate statement",
+                ErrorCode::MismatchedKinds,
+            ),
+            Diagnostic::new(
+                code.s1("missing_inst"),
+                "No component instantiation statement with label 'missing_inst'",
+                ErrorCode::Unresolved,
+            ),
+            Diagnostic::new(
+                code.s("inst1", 2),
+                "'inst1' is not an instance of component 'leaf'",
+                ErrorCode::MismatchedKinds,
+            ),
+            Diagnostic::new(
+                code.s("proc", 5),
+                "'proc' is not a component instantiation statement",
+                ErrorCode::MismatchedKinds,
+            ),
+            Diagnostic::new(
+                code.s1("missing_arch"),
+                "No architecture 'missing_arch' for entity 'libname.leaf'",
+                ErrorCode::Unresolved,
+            ),
+            Diagnostic::new(
+                code.s1("ent_missing"),
+                "No primary unit 'ent_missing' within library 'libname'",
+                ErrorCode::Unresolved,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn goto_from_configuration_items_to_configured_statements() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity leaf is
+end entity;
+
+architecture rtl of leaf is
+begin
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component leaf is
+  end component;
+begin
+  blk : block
+  begin
+    inst : component leaf;
+  end block;
+end architecture;
+
+configuration cfg of ent is
+  for a
+    for blk
+      for inst : leaf
+        use entity work.leaf(rtl);
+      end for;
+    end for;
+  end for;
+end configuration;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let decl_pos_of = |substr: Code| {
+        root.search_reference(code.source(), substr.start())
+            .and_then(|ent| ent.decl_pos().cloned())
+    };
+    assert_eq!(
+        decl_pos_of(code.sa("for ", "a")),
+        Some(code.sa("architecture ", "a").pos())
+    );
+    assert_eq!(decl_pos_of(code.s("blk", 2)), Some(code.s1("blk").pos()));
+    assert_eq!(decl_pos_of(code.s("inst", 2)), Some(code.s1("inst").pos()));
+    assert_eq!(
+        decl_pos_of(code.sa("inst : ", "leaf")),
+        Some(code.sa("component ", "leaf").pos())
+    );
+    assert_eq!(decl_pos_of(code.s("rtl", 2)), Some(code.s1("rtl").pos()));
+}
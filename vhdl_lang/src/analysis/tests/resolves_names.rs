@@ -1185,6 +1185,163 @@ end package body;
     );
 }
 
+#[test]
+fn aliases_of_non_object_entities() {
+    check_code_with_no_diagnostics(
+        "
+package pkg is
+  constant deferred : natural;
+  alias deferred_alias is deferred;
+end package;
+
+package body pkg is
+  constant deferred : natural := 4;
+end package body;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  component comp is
+  end component;
+  alias comp_alias is comp;
+  alias pkg_alias is work.pkg;
+  alias nanos is ns;
+  type text_file_t is file of string;
+  file text_file : text_file_t;
+  alias file_alias is text_file;
+  constant delay : time := 5 nanos;
+  constant value : natural := pkg_alias.deferred_alias;
+begin
+  inst: comp_alias;
+
+  process
+    variable line : string(1 to 4);
+  begin
+    read(file_alias, line);
+    wait;
+  end process;
+end architecture;
+",
+    );
+}
+
+#[test]
+fn error_on_alias_of_label_or_loop_parameter() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+begin
+  blk: block
+    alias blk_alias is blk;
+  begin
+  end block;
+
+  gen: for i in 0 to 1 generate
+    alias i_alias is i;
+  begin
+  end generate;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("is blk").s1("blk"),
+                "Labels cannot be aliased",
+                ErrorCode::MismatchedKinds,
+            ),
+            Diagnostic::new(
+                code.s1("is i;").s("i", 2),
+                "Loop parameters cannot be aliased",
+                ErrorCode::MismatchedKinds,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn error_on_alias_subtype_with_different_base_type() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : bit_vector(7 downto 0);
+  alias good : bit_vector(3 downto 0) is sig(7 downto 4);
+  alias bad : string(1 to 8) is sig;
+begin
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![Diagnostic::new(
+            code.s1("string"),
+            "Subtype indication of alias does not have the same base type as signal 'sig' of array type 'BIT_VECTOR'",
+            ErrorCode::TypeMismatch,
+        )],
+    );
+}
+
+#[test]
+fn goto_definition_sees_through_aliases() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  function fun(arg: natural) return natural;
+  alias fun_alias is fun[natural return natural];
+  constant deferred : natural;
+  alias deferred_alias is deferred;
+end package;
+
+package body pkg is
+  function fun(arg: natural) return natural is
+  begin
+    return arg;
+  end;
+
+  constant deferred : natural := fun_alias(0) + deferred_alias;
+end package body;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let definition_of = |cursor| {
+        root.find_definition_of(root.search_reference(code.source(), cursor).unwrap())
+            .and_then(|ent| ent.decl_pos().cloned())
+    };
+    assert_eq!(
+        definition_of(code.s1("fun_alias(0)").start()),
+        Some(
+            code.s1("function fun(arg: natural) return natural is")
+                .s("fun", 2)
+                .pos()
+        )
+    );
+    assert_eq!(
+        definition_of(code.s1("+ deferred_alias").s1("deferred_alias").start()),
+        Some(code.s1("deferred : natural :=").s1("deferred").pos())
+    );
+}
+
 #[test]
 fn overloaded_name_can_be_selected() {
     let mut builder = LibraryBuilder::new();
@@ -1583,7 +1740,7 @@ constant bad : rec_t := (missing => 0);
 
     assert_eq!(
         root.format_declaration(field),
-        Some("field : natural".to_string())
+        Some("field : natural\n-- Type chain: NATURAL -> INTEGER".to_string())
     );
 }
 
@@ -1870,6 +2027,146 @@ end package body;
     );
 }
 
+#[test]
+fn find_declaration_vs_definition_of_split_declarations() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type node_t;
+  type node_ptr_t is access node_t;
+  type node_t is record
+    succ : node_ptr_t;
+  end record;
+
+  constant c0 : natural;
+end package;
+
+package body pkg is
+  constant c0 : natural := 0;
+end package body;
+
+use work.pkg.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s : node_t;
+  constant c1 : natural := c0;
+begin
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let node_t = code.s1("signal s : node_t").s1("node_t");
+    assert_eq!(
+        root.find_declaration_pos_of(
+            root.search_reference(code.source(), node_t.start())
+                .unwrap()
+        ),
+        Some(code.s("node_t", 1).pos())
+    );
+    assert_eq!(
+        root.find_definition_of(
+            root.search_reference(code.source(), node_t.start())
+                .unwrap()
+        )
+        .unwrap()
+        .decl_pos(),
+        Some(&code.s("node_t", 3).pos())
+    );
+
+    let c0 = code.s1(":= c0").s1("c0");
+    assert_eq!(
+        root.find_declaration_pos_of(root.search_reference(code.source(), c0.start()).unwrap()),
+        Some(code.s("c0", 1).pos())
+    );
+    assert_eq!(
+        root.find_definition_of(root.search_reference(code.source(), c0.start()).unwrap())
+            .unwrap()
+            .decl_pos(),
+        Some(&code.s("c0", 2).pos())
+    );
+
+    let pkg = code.s1("work.pkg").s1("pkg");
+    assert_eq!(
+        root.find_declaration_pos_of(root.search_reference(code.source(), pkg.start()).unwrap()),
+        Some(code.s("pkg", 1).pos())
+    );
+    assert_eq!(
+        root.find_definition_of(root.search_reference(code.source(), pkg.start()).unwrap())
+            .unwrap()
+            .decl_pos(),
+        Some(&code.s("pkg", 2).pos())
+    );
+}
+
+#[test]
+fn find_all_writes_of_assignment_targets() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+end entity;
+
+architecture a of ent is
+  type rec_t is record
+    field : natural;
+  end record;
+  signal data : bit_vector(0 to 3);
+  signal rec : rec_t;
+  signal other : bit;
+begin
+  data(1) <= other;
+  other <= data(0);
+  (other, data(2)) <= bit_vector'(\"01\");
+
+  process
+    variable idx : natural;
+  begin
+    idx := rec.field;
+    data(idx) <= '1';
+    rec.field <= idx;
+    wait;
+  end process;
+end architecture;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let writes = |decl: Code| {
+        let ent = root.search_reference(code.source(), decl.start()).unwrap();
+        root.find_all_writes_in_source(code.source(), ent)
+    };
+
+    assert_eq!(
+        writes(code.s1("data")),
+        vec![
+            code.s("data", 2).pos(),
+            code.s("data", 4).pos(),
+            code.s("data", 5).pos()
+        ]
+    );
+    assert_eq!(
+        writes(code.s1("other")),
+        vec![code.s("other", 3).pos(), code.s("other", 4).pos()]
+    );
+    assert_eq!(writes(code.s1("idx")), vec![code.s("idx", 2).pos()]);
+    assert_eq!(
+        writes(code.s1("rec :")),
+        vec![code.s("rec.", 2).s1("rec").pos()]
+    );
+    assert_eq!(writes(code.s1("field")), vec![code.s("field", 3).pos()]);
+}
+
 #[test]
 fn find_architecture_references() {
     let mut builder = LibraryBuilder::new();
@@ -1979,7 +2276,7 @@ package body pkg is
 end package body pkg;
 
 configuration cfg1 of ent1 is
-  for rtl(0)
+  for a1
   end for;
 end configuration cfg1;
 
@@ -2185,3 +2482,83 @@ end package;
     );
     check_no_diagnostics(&builder.analyze())
 }
+
+#[test]
+fn find_linked_names_of_declarations_and_end_names() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity foo is
+end entity foo;
+
+architecture rtl of foo is
+begin
+  proc: process
+  begin
+    lp: for i in 0 to 3 loop
+      exit lp;
+    end loop lp;
+    wait;
+  end process proc;
+end architecture rtl;
+
+package pkg is
+end package;
+      ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let linked = |cursor: Code| root.find_linked_names(code.source(), cursor.start());
+
+    let foo = vec![code.s1("foo").pos(), code.s("foo", 2).pos()];
+    assert_eq!(linked(code.s1("foo")), foo);
+    assert_eq!(linked(code.s("foo", 2)), foo);
+    // The entity name within the architecture is a reference
+    assert_eq!(linked(code.s("foo", 3)), vec![]);
+    assert_eq!(
+        linked(code.s("rtl", 2)),
+        vec![code.s1("rtl").pos(), code.s("rtl", 2).pos()]
+    );
+    assert_eq!(
+        linked(code.s1("proc")),
+        vec![code.s1("proc").pos(), code.s1("proc;").s1("proc").pos()]
+    );
+    let lp = vec![code.s1("lp").pos(), code.s("lp", 3).pos()];
+    assert_eq!(linked(code.s("lp", 3)), lp);
+    assert_eq!(linked(code.s("lp", 2)), vec![]);
+    // Without a name after `end` there is nothing to edit together
+    assert_eq!(linked(code.s1("pkg")), vec![]);
+}
+
+#[test]
+fn resolves_return_identifier_of_function() {
+    let mut builder = LibraryBuilder::with_standard(crate::VHDLStandard::VHDL2019);
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  function invert(arg : bit_vector) return bit_vector;
+end package;
+
+package body pkg is
+  function invert(arg : bit_vector) return ret of bit_vector is
+    variable result : ret(arg'range);
+  begin
+    result := not arg;
+    return result;
+  end function;
+end package body;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("ret(").start()),
+        Some(code.s1("ret of").s1("ret").pos())
+    );
+}
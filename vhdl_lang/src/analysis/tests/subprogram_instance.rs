@@ -205,7 +205,7 @@ function proc generic (type T) return bit is
 begin
 end proc;
 
-function proc is new proc;
+function proc is new proc generic map (T => natural);
     ",
     );
 
@@ -218,7 +218,7 @@ procedure proc generic (type T) is
 begin
 end proc;
 
-procedure proc is new proc;
+procedure proc is new proc generic map (T => natural);
     ",
     );
 
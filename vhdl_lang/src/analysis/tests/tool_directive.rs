@@ -31,3 +31,33 @@ end my_ent;
     );
     check_no_diagnostics(&builder.analyze());
 }
+
+#[test]
+fn design_units_around_protected_envelopes() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "\
+entity my_ent is
+end my_ent;
+
+architecture rtl of my_ent is
+`protect begin_protected
+`protect version = 1
+`protect encrypt_agent = \"XILINX\"
+`protect key_block
+SGkgdGhlcmUh+/3gZm9vYmFy==
+`protect data_method = \"AES128-CBC\"
+`protect data_block
+bm90IGEgdmhkbCBkZXNpZ24gdW5pdA==#'(;
+`protect end_protected
+end architecture;
+
+configuration cfg of my_ent is
+    for rtl
+    end for;
+end configuration;
+        ",
+    );
+    check_no_diagnostics(&builder.analyze());
+}
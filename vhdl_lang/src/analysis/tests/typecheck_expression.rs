@@ -1057,6 +1057,63 @@ constant bad3 : rec_t := ('a', 0, 0, others => 3);
     );
 }
 
+#[test]
+fn aggregate_association_order() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type rec_t is record
+    f1 : natural;
+    f2 : natural;
+end record;
+
+type matrix_t is array (0 to 1, 0 to 1) of bit;
+
+constant good1 : bit_vector(0 to 3) := ('1', '0', others => '0');
+constant good2 : bit_vector(0 to 3) := (0 => '1', others => '0');
+constant good3 : rec_t := (0, f2 => 1);
+constant good4 : matrix_t := (('0', others => '1'), (0 => '1', 1 => '0'));
+constant bad1 : bit_vector(0 to 3) := (others => '0', 0 => '1');
+constant bad2 : bit_vector(0 to 3) := (0 | others => '0');
+constant bad3 : bit_vector(0 to 3) := ('1', 1 to 3 => '0');
+constant bad4 : rec_t := (f1 => 0, 1);
+constant bad5 : matrix_t := (('0', 1 => '1'), others => (others => '0'));
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("(others => '0', 0").s1("others"),
+                "'others' must be the only choice of the last element association",
+                ErrorCode::MisplacedOthers,
+            ),
+            Diagnostic::new(
+                code.s1("0 | others").s1("others"),
+                "'others' must be the only choice of the last element association",
+                ErrorCode::MisplacedOthers,
+            ),
+            Diagnostic::new(
+                code.s1("1 to 3 =>").s1("1 to 3"),
+                "Array aggregates cannot mix positional and named associations",
+                ErrorCode::MixedAggregateAssociation,
+            ),
+            Diagnostic::new(
+                code.s1("(f1 => 0, 1)").s1("f1"),
+                "Named associations are not allowed before positional associations",
+                ErrorCode::NamedBeforePositional,
+            ),
+            Diagnostic::new(
+                code.s1("('0', 1 => '1')").s1("1"),
+                "Array aggregates cannot mix positional and named associations",
+                ErrorCode::MixedAggregateAssociation,
+            ),
+        ],
+    );
+}
+
 #[test]
 fn typecheck_aggregate_element_association_expr() {
     let mut builder = LibraryBuilder::new();
@@ -1994,3 +2051,220 @@ end package;",
         )],
     );
 }
+
+#[test]
+fn qq_conversion_of_only_candidate_with_qq_operator() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type typ1_t is (alpha, beta);
+type typ2_t is (alpha, beta);
+type typ3_t is (alpha, beta);
+
+function \"??\"(val : typ2_t) return boolean is
+begin
+  return true;
+end function;
+
+procedure wrapper is
+begin
+   assert alpha;
+end;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+    let cursor = code.s1("assert alpha").s1("alpha");
+    assert_eq!(
+        root.search_reference_pos(cursor.source(), cursor.start()),
+        Some(code.s1("typ2_t is (alpha").s1("alpha").pos())
+    );
+}
+
+#[test]
+fn records_type_of_subexpressions() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type rec_t is record
+  field : natural;
+end record;
+
+signal nat : natural;
+
+procedure wrapper(
+  signal int : out integer;
+  signal vec : out bit_vector;
+  signal rec : out rec_t) is
+  variable t : time;
+begin
+  int <= (nat + 1) * 2;
+  vec <= \"0101\" and (others => '1');
+  rec <= (field => 3);
+  t := 5 ns;
+  if nat = 0 then
+  end if;
+end;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+    let expression_type = |cursor: Code| {
+        root.expression_type_at(cursor.source(), cursor.start())
+            .map(|(pos, typ)| (pos, typ.designator().to_string()))
+    };
+    let expression = |expr: Code, typ: &str| Some((expr.pos(), typ.to_owned()));
+
+    assert_eq!(
+        expression_type(code.s1("nat + 1")),
+        expression(code.s1("nat + 1").s1("nat"), "NATURAL")
+    );
+    assert_eq!(
+        expression_type(code.s1("1) * 2")),
+        expression(code.s1("+ 1").s1("1"), "INTEGER")
+    );
+    assert_eq!(
+        expression_type(code.s1("+ 1")),
+        expression(code.s1("nat + 1"), "INTEGER")
+    );
+    assert_eq!(
+        expression_type(code.s1("(nat")),
+        expression(code.s1("(nat + 1)"), "INTEGER")
+    );
+    assert_eq!(
+        expression_type(code.s1("* 2")),
+        expression(code.s1("(nat + 1) * 2"), "INTEGER")
+    );
+    assert_eq!(
+        expression_type(code.s1("\"0101\"")),
+        expression(code.s1("\"0101\""), "BIT_VECTOR")
+    );
+    assert_eq!(
+        expression_type(code.s1("(others")),
+        expression(code.s1("(others => '1')"), "BIT_VECTOR")
+    );
+    assert_eq!(
+        expression_type(code.s1("'1'")),
+        expression(code.s1("'1'"), "BIT")
+    );
+    assert_eq!(
+        expression_type(code.s1("(field")),
+        expression(code.s1("(field => 3)"), "rec_t")
+    );
+    assert_eq!(
+        expression_type(code.s1("=> 3").s1("3")),
+        expression(code.s1("=> 3").s1("3"), "NATURAL")
+    );
+    assert_eq!(
+        expression_type(code.s1("5 ns")),
+        expression(code.s1("5 ns"), "TIME")
+    );
+    assert_eq!(
+        expression_type(code.s1("= 0")),
+        expression(code.s1("nat = 0"), "BOOLEAN")
+    );
+    assert_eq!(
+        expression_type(code.s1("0 then")),
+        expression(code.s1("= 0").s1("0"), "INTEGER")
+    );
+    assert_eq!(expression_type(code.s1("wrapper")), None);
+}
+
+#[test]
+fn disambiguates_function_call_by_suffix() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type rec_t is record
+  field : natural;
+end record;
+
+function fun(arg : natural) return rec_t;
+function fun(arg : natural) return bit_vector;
+function fun(arg : natural) return integer;
+
+function nullary return rec_t;
+function nullary return bit_vector;
+
+constant selected : natural := fun(0).field;
+constant indexed : bit := fun(1)(0);
+constant nullary_selected : natural := nullary.field;
+        ",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("fun(0)").start()),
+        Some(code.s1("fun(arg").s1("fun").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("fun(1)").start()),
+        Some(code.s("fun(arg", 2).s1("fun").pos())
+    );
+    assert_eq!(
+        root.search_reference_pos(code.source(), code.s1("nullary.field").start()),
+        Some(code.s1("nullary return").s1("nullary").pos())
+    );
+}
+
+#[test]
+fn ambiguous_call_that_suffix_cannot_disambiguate() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.in_declarative_region(
+        "
+type rec1_t is record
+  field : natural;
+end record;
+
+type rec2_t is record
+  field : natural;
+end record;
+
+function fun(arg : natural) return rec1_t;
+function fun(arg : natural) return rec2_t;
+function fun(arg : natural) return integer;
+
+function nullary return rec1_t;
+function nullary return rec2_t;
+
+constant bad : natural := fun(0).field;
+constant nullary_bad : natural := nullary.field;
+        ",
+    );
+
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::new(
+                code.s1("fun(0)").s1("fun"),
+                "Ambiguous call to 'fun'",
+                ErrorCode::AmbiguousCall,
+            )
+            .related(
+                code.s1("fun(arg").s1("fun"),
+                "Might be function fun[NATURAL return rec1_t]",
+            )
+            .related(
+                code.s("fun(arg", 2).s1("fun"),
+                "Might be function fun[NATURAL return rec2_t]",
+            ),
+            Diagnostic::new(
+                code.s1("nullary.field").s1("nullary"),
+                "Ambiguous call to 'nullary'",
+                ErrorCode::AmbiguousCall,
+            )
+            .related(
+                code.s1("nullary return").s1("nullary"),
+                "Might be function nullary[return rec1_t]",
+            )
+            .related(
+                code.s("nullary return", 2).s1("nullary"),
+                "Might be function nullary[return rec2_t]",
+            ),
+        ],
+    );
+}
@@ -9,8 +9,10 @@ use crate::data::error_codes::ErrorCode;
 use crate::data::*;
 use crate::syntax::test::*;
 use crate::syntax::Symbols;
+use crate::Config;
 use pretty_assertions::assert_eq;
 use std::collections::{hash_map::Entry, HashMap};
+use std::path::Path;
 use std::sync::Arc;
 use vhdl_lang::VHDLStandard;
 
@@ -146,6 +148,16 @@ fn std_logic_1164_package() -> Source {
     )
 }
 
+/// Returns a config that declares an empty library `libname`.
+/// The `options` are appended below the `[libraries]` section.
+pub fn test_config(options: &str) -> Config {
+    Config::from_str(
+        &format!("[libraries]\nlibname.files = []\n{options}"),
+        Path::new(""),
+    )
+    .unwrap()
+}
+
 pub fn add_standard_library(symbols: Arc<Symbols>, root: &mut DesignRoot) {
     let builder = CodeBuilder {
         symbols: symbols.clone(),
@@ -235,6 +247,11 @@ pub fn check_search_reference_with_name(decl_name: &str, contents: &str) {
     assert!(occurences > 0);
 
     let (root, diagnostics) = builder.get_analyzed_root();
+    // The object is used in all kinds of places, including case choices that must be static
+    let diagnostics: Vec<_> = diagnostics
+        .into_iter()
+        .filter(|diagnostic| diagnostic.code != ErrorCode::NonStaticChoice)
+        .collect();
     check_no_diagnostics(&diagnostics);
 
     let mut references = Vec::new();
@@ -36,9 +36,10 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
                 &mut constraint.item,
                 diagnostics,
             )?;
+            Ok(Subtype::constrained(base_type))
+        } else {
+            Ok(Subtype::new(base_type))
         }
-
-        Ok(Subtype::new(base_type))
     }
 
     pub(crate) fn analyze_type_declaration(
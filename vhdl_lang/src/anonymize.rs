@@ -0,0 +1,356 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Anonymization of VHDL sources.
+//!
+//! The [Anonymizer] consistently renames user identifiers and string literals
+//! and removes the contents of comments while keeping the structure of the code intact.
+//! Names declared in third-party libraries (such as `std` and `ieee`)
+//! as well as the names of predefined attributes are preserved so that
+//! anonymized code can still be analyzed the same way as the original code.
+
+use crate::ast::Operator;
+use crate::data::{ContentReader, Symbol};
+use crate::syntax::{Comment, Kind, Symbols, Tokenizer, Value};
+use crate::{
+    apply_edits, Config, Latin1String, Message, MessageHandler, Source, TextEdit, VHDLStandard,
+};
+use fnv::{FnvHashMap, FnvHashSet};
+
+/// Letters used for anonymized string literals.
+/// Excludes the letters of the `std_ulogic` literals so that anonymized strings
+/// are never mistaken for a logic vector.
+const STRING_ALPHABET: &[u8] = b"abcdefgijkmnopqrstvy";
+
+pub struct Anonymizer {
+    symbols: Symbols,
+    preserved: FnvHashSet<Symbol>,
+    identifiers: FnvHashMap<Symbol, String>,
+    strings: FnvHashMap<Latin1String, String>,
+}
+
+impl Anonymizer {
+    /// Creates an anonymizer that preserves the names of predefined attributes
+    /// and the `std`, `ieee` and `work` library names.
+    pub fn new(standard: VHDLStandard) -> Anonymizer {
+        let mut anonymizer = Anonymizer {
+            symbols: Symbols::from_standard(standard),
+            preserved: FnvHashSet::default(),
+            identifiers: FnvHashMap::default(),
+            strings: FnvHashMap::default(),
+        };
+        for attr in standard.builtin_attributes() {
+            anonymizer.preserve(&attr.to_string());
+        }
+        for name in ["std", "ieee", "work"] {
+            anonymizer.preserve(name);
+        }
+        anonymizer
+    }
+
+    /// Creates an anonymizer that, in addition, preserves all identifiers
+    /// that occur in the files and names of third-party libraries of `config`.
+    pub fn from_config(config: &Config, messages: &mut dyn MessageHandler) -> Anonymizer {
+        let mut anonymizer = Anonymizer::new(config.standard());
+        for library in config
+            .iter_libraries()
+            .filter(|library| library.is_third_party)
+        {
+            anonymizer.preserve(library.name());
+            for file_name in library.file_names(messages) {
+                match Source::from_latin1_file(&file_name) {
+                    Ok(source) => anonymizer.preserve_source(&source),
+                    Err(err) => messages.push(Message::file_error(err.to_string(), &file_name)),
+                }
+            }
+        }
+        anonymizer
+    }
+
+    /// Never rename the identifier `name`.
+    pub fn preserve(&mut self, name: &str) {
+        if let Ok(name) = Latin1String::from_utf8(name) {
+            self.preserved.insert(self.symbols.symtab().insert(&name));
+        }
+    }
+
+    /// Never rename any of the identifiers of `source`.
+    pub fn preserve_source(&mut self, source: &Source) {
+        let contents = source.contents();
+        let mut tokenizer = Tokenizer::new(&self.symbols, source, ContentReader::new(&contents));
+        let mut preserved = Vec::new();
+        loop {
+            match tokenizer.pop() {
+                Ok(Some(token)) => {
+                    if let Value::Identifier(sym) = token.value {
+                        preserved.push(sym);
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => continue,
+            }
+        }
+        self.preserved.extend(preserved);
+    }
+
+    /// Returns the anonymized version of the identifier `name`,
+    /// for example to rename a library in a configuration file.
+    pub fn anonymize_name(&mut self, name: &str) -> String {
+        match Latin1String::from_utf8(name) {
+            Ok(name) => {
+                let sym = self.symbols.symtab().insert(&name);
+                self.identifier(&sym)
+            }
+            Err(_) => name.to_owned(),
+        }
+    }
+
+    /// Returns the anonymized contents of `source`.
+    /// Line endings are normalized to `\n`.
+    pub fn anonymize(&mut self, source: &Source) -> String {
+        let contents = source.contents();
+        let text = (0..contents.num_lines())
+            .filter_map(|line| contents.get_line(line))
+            .collect::<String>();
+
+        let mut tokens = Vec::new();
+        let mut final_comments = Vec::new();
+        {
+            let mut tokenizer =
+                Tokenizer::new(&self.symbols, source, ContentReader::new(&contents));
+            loop {
+                match tokenizer.pop() {
+                    Ok(Some(token)) => tokens.push(token),
+                    Ok(None) => break,
+                    Err(_) => continue,
+                }
+            }
+            final_comments.extend(tokenizer.get_final_comments().unwrap_or_default());
+        }
+
+        let mut edits = Vec::new();
+        for token in &tokens {
+            match (&token.kind, &token.value) {
+                (Kind::Identifier, Value::Identifier(sym)) if !self.preserved.contains(sym) => {
+                    edits.push(TextEdit::new(token.pos.range(), self.identifier(sym)));
+                }
+                (Kind::StringLiteral, Value::String(value)) => {
+                    if let Some(replacement) = self.string(value) {
+                        edits.push(TextEdit::new(
+                            token.pos.range(),
+                            format!("\"{replacement}\""),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+            if let Some(comments) = &token.comments {
+                edits.extend(comments.leading.iter().map(anonymize_comment));
+                edits.extend(comments.trailing.iter().map(anonymize_comment));
+            }
+        }
+        edits.extend(final_comments.iter().map(anonymize_comment));
+        apply_edits(&text, &edits)
+    }
+
+    fn identifier(&mut self, sym: &Symbol) -> String {
+        if self.preserved.contains(sym) {
+            return sym.name_utf8();
+        }
+        if let Some(name) = self.identifiers.get(sym) {
+            return name.clone();
+        }
+        let is_extended = sym.name().bytes.first() == Some(&b'\\');
+        let mut idx = self.identifiers.len();
+        let name = loop {
+            idx += 1;
+            let name = format!("id_{idx}");
+            let candidate = self
+                .symbols
+                .symtab()
+                .insert(&Latin1String::new(name.as_bytes()));
+            if !self.preserved.contains(&candidate) {
+                break name;
+            }
+        };
+        let name = if is_extended {
+            format!("\\{name}\\")
+        } else {
+            name
+        };
+        self.identifiers.insert(sym.clone(), name.clone());
+        name
+    }
+
+    /// Returns the replacement of a string literal or `None` if the literal is preserved.
+    /// Operator symbols and literals that could be logic vectors are preserved.
+    /// Replacements have the same length as the original literal if possible.
+    fn string(&mut self, value: &Latin1String) -> Option<String> {
+        if Operator::from_latin1(value.clone()).is_some()
+            || value
+                .bytes
+                .iter()
+                .all(|byte| b"01uxzwlh-".contains(&byte.to_ascii_lowercase()))
+        {
+            return None;
+        }
+        if let Some(replacement) = self.strings.get(value) {
+            return Some(replacement.clone());
+        }
+        let mut idx = self.strings.len();
+        let mut replacement = Vec::new();
+        loop {
+            replacement.push(STRING_ALPHABET[idx % STRING_ALPHABET.len()]);
+            idx /= STRING_ALPHABET.len();
+            if idx == 0 {
+                break;
+            }
+        }
+        while replacement.len() < value.len() {
+            replacement.push(STRING_ALPHABET[0]);
+        }
+        replacement.reverse();
+        let replacement = String::from_utf8(replacement).unwrap();
+        self.strings.insert(value.clone(), replacement.clone());
+        Some(replacement)
+    }
+}
+
+/// Empties a comment, keeping the line breaks of multi-line comments
+/// so that the code after the comment stays on the same lines.
+fn anonymize_comment(comment: &Comment) -> TextEdit {
+    let new_text = if comment.multi_line {
+        let line_breaks = comment.range.end.line - comment.range.start.line;
+        format!("/*{} */", "\n".repeat(line_breaks as usize))
+    } else {
+        "--".to_owned()
+    };
+    TextEdit::new(comment.range, new_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::syntax::test::check_no_diagnostics;
+    use std::path::Path;
+
+    /// An anonymizer that preserves the names of the `std.standard` package.
+    fn std_anonymizer() -> Anonymizer {
+        let mut anonymizer = Anonymizer::new(VHDLStandard::default());
+        anonymizer.preserve_source(&Source::inline(
+            Path::new("standard.vhd"),
+            &Latin1String::new(include_bytes!("../../vhdl_libraries/std/standard.vhd")).to_string(),
+        ));
+        anonymizer
+    }
+
+    fn anonymize(anonymizer: &mut Anonymizer, code: &str) -> String {
+        anonymizer.anonymize(&Source::inline(Path::new("file.vhd"), code))
+    }
+
+    #[test]
+    fn renames_identifiers_consistently() {
+        let mut anonymizer = std_anonymizer();
+        assert_eq!(
+            anonymize(
+                &mut anonymizer,
+                "\
+entity secret is
+  port (clk : in bit);
+end entity SECRET;
+"
+            ),
+            "\
+entity id_1 is
+  port (id_2 : in bit);
+end entity id_1;
+"
+        );
+        assert_eq!(
+            anonymize(&mut anonymizer, "use work.Secret;"),
+            "use work.id_1;"
+        );
+    }
+
+    #[test]
+    fn preserves_predefined_attributes_and_third_party_names() {
+        let mut anonymizer = Anonymizer::new(VHDLStandard::default());
+        anonymizer.preserve_source(&Source::inline(
+            Path::new("std_logic_1164.vhd"),
+            "package std_logic_1164 is type std_logic is ('0', '1'); end package;",
+        ));
+        assert_eq!(
+            anonymize(
+                &mut anonymizer,
+                "\
+library ieee;
+use ieee.std_logic_1164.all;
+package pkg is
+  constant c : std_logic_vector := foo'length;
+end package;
+"
+            ),
+            "\
+library ieee;
+use ieee.std_logic_1164.all;
+package id_1 is
+  constant id_2 : id_3 := id_4'length;
+end package;
+"
+        );
+    }
+
+    #[test]
+    fn anonymizes_strings_and_comments() {
+        let mut anonymizer = std_anonymizer();
+        assert_eq!(
+            anonymize(
+                &mut anonymizer,
+                "\
+-- Copyright secret corp.
+constant str1 : string := \"secret\"; -- trailing
+constant str2 : bit_vector := \"0101\";
+constant str3 : string := \"secret\" & \"x\" & \"and\";
+/* multi
+   line */
+"
+            ),
+            "\
+--
+constant id_1 : string := \"aaaaaa\"; --
+constant id_2 : bit_vector := \"0101\";
+constant id_3 : string := \"aaaaaa\" & \"x\" & \"and\";
+/*
+ */
+"
+        );
+    }
+
+    #[test]
+    fn anonymized_code_analyzes_without_errors() {
+        let mut anonymizer = std_anonymizer();
+        let code = anonymize(
+            &mut anonymizer,
+            "\
+entity ent is
+  generic (width : natural := 8);
+  port (data : in bit_vector(width - 1 downto 0));
+end entity;
+
+architecture rtl of ent is
+  signal copy : bit_vector(data'range);
+begin
+  copy <= data;
+  assert copy'length = width report \"wrong width\";
+end architecture;
+",
+        );
+        let mut builder = LibraryBuilder::new();
+        builder.code("libname", &code);
+        check_no_diagnostics(&builder.analyze());
+    }
+}
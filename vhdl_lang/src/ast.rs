@@ -14,7 +14,9 @@ mod any_design_unit;
 #[macro_use]
 pub mod search;
 mod ast_span;
+pub mod json;
 pub mod token_range;
+pub mod visitor;
 
 pub(crate) use self::util::*;
 use crate::ast::token_range::*;
@@ -22,11 +24,13 @@ use crate::data::*;
 use crate::named_entity::{EntityId, Reference};
 use crate::syntax::{Token, TokenAccess, TokenId};
 use crate::TokenSpan;
+use crate::VHDLStandard;
 pub(crate) use any_design_unit::*;
+pub use any_design_unit::{AnyKind, PrimaryKind, SecondaryKind};
 use vhdl_lang::HasTokenSpan;
 
 /// LRM 15.8 Bit string literals
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, ToJson)]
 pub enum BaseSpecifier {
     B,
     O,
@@ -40,7 +44,7 @@ pub enum BaseSpecifier {
     D,
 }
 
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, ToJson)]
 pub enum Operator {
     And,
     Or,
@@ -85,7 +89,7 @@ pub enum Operator {
 }
 
 /// LRM 8.6 Attribute names
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct AttributeName {
     pub name: WithTokenSpan<Name>,
     pub signature: Option<WithTokenSpan<Signature>>,
@@ -93,19 +97,19 @@ pub struct AttributeName {
     pub expr: Option<Box<WithTokenSpan<Expression>>>,
 }
 
-#[derive(PartialEq, Debug, Copy, Clone, Eq)]
+#[derive(PartialEq, Debug, Copy, Clone, Eq, ToJson)]
 pub enum TypeAttribute {
     Subtype,
     Element,
 }
 
-#[derive(PartialEq, Debug, Copy, Clone, Eq)]
+#[derive(PartialEq, Debug, Copy, Clone, Eq, ToJson)]
 pub enum RangeAttribute {
     Range,
     ReverseRange,
 }
 
-#[derive(PartialEq, Debug, Clone, Eq)]
+#[derive(PartialEq, Debug, Clone, Eq, ToJson)]
 pub enum AttributeDesignator {
     Type(TypeAttribute),
     Range(RangeAttribute),
@@ -131,7 +135,7 @@ pub enum AttributeDesignator {
     Converse,
 }
 
-#[derive(PartialEq, Debug, Copy, Clone, Eq)]
+#[derive(PartialEq, Debug, Copy, Clone, Eq, ToJson)]
 pub enum SignalAttribute {
     Delayed,
     Stable,
@@ -147,7 +151,7 @@ pub enum SignalAttribute {
 }
 
 /// LRM 8.7 External names
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, ToJson)]
 pub enum ExternalObjectClass {
     Constant,
     Signal,
@@ -165,7 +169,7 @@ impl From<ExternalObjectClass> for ObjectClass {
 }
 
 /// LRM 8.7 External names
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum ExternalPath {
     Package(WithTokenSpan<Name>),
     Absolute(WithTokenSpan<Name>),
@@ -175,7 +179,7 @@ pub enum ExternalPath {
 }
 
 /// LRM 8.7 External names
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ExternalName {
     pub class: ExternalObjectClass,
     pub path: WithTokenSpan<ExternalPath>,
@@ -184,7 +188,7 @@ pub struct ExternalName {
 }
 
 /// LRM 8. Names
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Name {
     Designator(WithRef<Designator>),
     Selected(Box<WithTokenSpan<Name>>, WithToken<WithRef<Designator>>),
@@ -196,14 +200,14 @@ pub enum Name {
 }
 
 /// LRM 9.3.4 Function calls
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct CallOrIndexed {
     pub name: WithTokenSpan<Name>,
     pub parameters: SeparatedList<AssociationElement>,
 }
 
 /// LRM 9.3.3 Aggregates
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Choice {
     Expression(Expression),
     DiscreteRange(DiscreteRange),
@@ -211,49 +215,49 @@ pub enum Choice {
 }
 
 /// LRM 9.3.3 Aggregates
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum ElementAssociation {
     Positional(WithTokenSpan<Expression>),
     Named(Vec<WithTokenSpan<Choice>>, WithTokenSpan<Expression>),
 }
 
 /// LRM 6.5.7 Association Lists
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum ActualPart {
     Expression(Expression),
     Open,
 }
 
 /// LRM 6.5.7 Association Lists
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct AssociationElement {
     pub formal: Option<WithTokenSpan<Name>>,
     pub actual: WithTokenSpan<ActualPart>,
 }
 
 /// LRM 15.5 Abstract literals
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Debug, Clone, Copy, ToJson)]
 pub enum AbstractLiteral {
     Integer(u64),
     Real(f64),
 }
 
 /// LRM 15.8 Bit string literals
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, ToJson)]
 pub struct BitString {
     pub length: Option<u32>,
     pub base: BaseSpecifier,
     pub value: Latin1String,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct PhysicalLiteral {
     pub value: AbstractLiteral,
     pub unit: WithRef<Ident>,
 }
 
 /// LRM 9.3.2 Literals
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Literal {
     String(Latin1String),
     BitString(BitString),
@@ -264,21 +268,21 @@ pub enum Literal {
 }
 
 /// LRM 9.3.7 Allocators
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Allocator {
     Qualified(QualifiedExpression),
     Subtype(SubtypeIndication),
 }
 
 /// LRM 9.3.5 Qualified expressions
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct QualifiedExpression {
     pub type_mark: WithTokenSpan<Name>,
     pub expr: WithTokenSpan<Expression>,
 }
 
 /// LRM 9. Expressions
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Expression {
     Binary(
         WithToken<WithRef<Operator>>,
@@ -307,7 +311,7 @@ pub enum Expression {
 /// An identifier together with the lexical source location it occurs in.
 pub type Ident = WithToken<Symbol>;
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, ToJson)]
 pub enum Direction {
     Ascending,
     Descending,
@@ -318,13 +322,13 @@ pub enum Direction {
 /// range ::=
 ///     range_attribute_name
 ///   | simple_expression direction simple_expression
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum DiscreteRange {
     Discrete(WithTokenSpan<Name>, Option<Range>),
     Range(Range),
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct RangeConstraint {
     pub direction: Direction,
     pub left_expr: Box<WithTokenSpan<Expression>>,
@@ -337,20 +341,20 @@ impl RangeConstraint {
     }
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Range {
     Range(RangeConstraint),
     Attribute(Box<AttributeName>),
 }
 
 /// LRM: record_element_constraint
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ElementConstraint {
     pub ident: Ident,
     pub constraint: Box<WithTokenSpan<SubtypeConstraint>>,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum SubtypeConstraint {
     Range(Range),
     /// Empty Vec means Open
@@ -362,14 +366,14 @@ pub enum SubtypeConstraint {
 }
 
 /// LRM 6.3 Subtype declarations
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct RecordElementResolution {
     pub ident: Ident,
     pub resolution: Box<ResolutionIndication>,
 }
 
 /// LRM 6.3 Subtype declarations
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum ResolutionIndication {
     FunctionName(WithTokenSpan<Name>),
     ArrayElement(WithTokenSpan<Name>),
@@ -395,7 +399,7 @@ impl HasTokenSpan for ResolutionIndication {
 }
 
 /// LRM 6.3 Subtype declarations
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SubtypeIndication {
     pub resolution: Option<ResolutionIndication>,
     pub type_mark: WithTokenSpan<Name>,
@@ -403,7 +407,7 @@ pub struct SubtypeIndication {
 }
 
 /// LRM 5.3 Array Types
-#[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[derive(PartialEq, Debug, Clone, TokenSpan, ToJson)]
 pub enum ArrayIndex {
     /// Unbounded
     /// {identifier} range <>
@@ -415,7 +419,7 @@ pub enum ArrayIndex {
 
 /// LRM 5.3.3 Record types
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ElementDeclaration {
     pub idents: Vec<WithDecl<Ident>>,
     pub colon_token: TokenId,
@@ -423,12 +427,12 @@ pub struct ElementDeclaration {
 }
 
 /// LRM 5.6.2 Protected type declarations
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum ProtectedTypeDeclarativeItem {
     Subprogram(SubprogramDeclaration),
 }
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, ToJson)]
 pub enum Designator {
     Identifier(Symbol),
     OperatorSymbol(Operator),
@@ -437,7 +441,7 @@ pub enum Designator {
 }
 
 /// An item which has a reference to a declaration
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, ToJson)]
 pub struct WithRef<T> {
     pub item: T,
     pub reference: Reference,
@@ -453,7 +457,7 @@ impl<T> WithRef<T> {
 }
 
 /// An item which declares a named entity
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct WithDecl<T> {
     pub tree: T,
     pub decl: Reference,
@@ -493,7 +497,7 @@ impl HasDesignator for WithToken<WithRef<Designator>> {
 }
 
 /// LRM 6.6 Alias declarations
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct AliasDeclaration {
     pub designator: WithDecl<WithToken<Designator>>,
     pub subtype_indication: Option<SubtypeIndication>,
@@ -503,21 +507,21 @@ pub struct AliasDeclaration {
 }
 
 /// LRM 6.7 Attribute declarations
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct AttributeDeclaration {
     pub ident: WithDecl<Ident>,
     pub type_mark: WithTokenSpan<Name>,
 }
 
 /// LRM 7.2 Attribute specification
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct EntityTag {
     pub designator: WithToken<WithRef<Designator>>,
     pub signature: Option<WithTokenSpan<Signature>>,
 }
 
 /// LRM 7.2 Attribute specification
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum EntityName {
     Name(EntityTag),
     All,
@@ -526,7 +530,7 @@ pub enum EntityName {
 
 /// LRM 7.2 Attribute specification
 // @TODO there are more classes
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, ToJson)]
 pub enum EntityClass {
     Entity,
     Architecture,
@@ -550,7 +554,7 @@ pub enum EntityClass {
 }
 
 /// LRM 7.2 Attribute specification
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct AttributeSpecification {
     pub ident: WithRef<Ident>,
     pub entity_name: EntityName,
@@ -560,26 +564,26 @@ pub struct AttributeSpecification {
 }
 
 /// LRM 7.2 Attribute specification
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Attribute {
     Specification(AttributeSpecification),
     Declaration(AttributeDeclaration),
 }
 
 /// LRM 5.6.2 Protected type declarations
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ProtectedTypeDeclaration {
     pub items: Vec<ProtectedTypeDeclarativeItem>,
 }
 
 /// LRM 5.6.3 Protected type bodies
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ProtectedTypeBody {
     pub decl: Vec<WithTokenSpan<Declaration>>,
 }
 
 /// LRM 5.4.2 Physical type declaration
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct PhysicalTypeDeclaration {
     pub range: Range,
     pub units_token: TokenId,
@@ -588,14 +592,14 @@ pub struct PhysicalTypeDeclaration {
 }
 
 /// LRM 5.2.2 Enumeration types
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, ToJson)]
 pub enum EnumerationLiteral {
     Identifier(Symbol),
     Character(u8),
 }
 
 /// LRM 5 Types
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum TypeDefinition {
     /// LRM 5.2 Scalar Types
     /// LRM 5.2.2 Enumeration types
@@ -626,7 +630,7 @@ pub enum TypeDefinition {
 
 /// LRM 6.2 Type declarations
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct TypeDeclaration {
     pub ident: WithDecl<Ident>,
     pub def: TypeDefinition,
@@ -645,7 +649,7 @@ impl TypeDeclaration {
 }
 
 /// LRM 6.4.2 Object Declarations
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, ToJson)]
 pub enum ObjectClass {
     Signal,
     Constant,
@@ -653,14 +657,14 @@ pub enum ObjectClass {
     SharedVariable,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, ToJson)]
 pub enum InterfaceType {
     Port,
     Generic,
     Parameter,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ObjectDeclaration {
     pub class: ObjectClass,
     pub colon_token: TokenId,
@@ -669,7 +673,7 @@ pub struct ObjectDeclaration {
     pub expression: Option<WithTokenSpan<Expression>>,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct FileDeclaration {
     pub idents: Vec<WithDecl<Ident>>,
     pub colon_token: TokenId,
@@ -678,14 +682,14 @@ pub struct FileDeclaration {
     pub file_name: Option<(TokenId, WithTokenSpan<Expression>)>,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, ToJson)]
 pub enum SubprogramDesignator {
     Identifier(Symbol),
     OperatorSymbol(Operator),
 }
 
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct InterfaceList {
     pub interface_type: InterfaceType,
     pub items: Vec<InterfaceDeclaration>,
@@ -693,7 +697,7 @@ pub struct InterfaceList {
 
 /// LRM 4.2 Subprogram declaration
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ProcedureSpecification {
     pub designator: WithDecl<WithToken<SubprogramDesignator>>,
     pub header: Option<SubprogramHeader>,
@@ -702,18 +706,21 @@ pub struct ProcedureSpecification {
 
 /// LRM 4.2 Subprogram declaration
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct FunctionSpecification {
     pub pure: bool,
     pub designator: WithDecl<WithToken<SubprogramDesignator>>,
     pub header: Option<SubprogramHeader>,
     pub parameter_list: Option<InterfaceList>,
+    /// VHDL-2019: The identifier of `return identifier of type_mark`,
+    /// which denotes the subtype of the returned value within the function body
+    pub return_identifier: Option<WithDecl<Ident>>,
     pub return_type: WithTokenSpan<Name>,
 }
 
 /// LRM 4.3 Subprogram bodies
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SubprogramBody {
     pub specification: SubprogramSpecification,
     pub declarations: Vec<WithTokenSpan<Declaration>>,
@@ -727,13 +734,13 @@ pub struct SubprogramBody {
 /// Note that, as opposed to the standard, the header is not optional.
 /// Instead, the element that contains the header (e.g., procedure specifications)
 /// mark this element as optional.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SubprogramHeader {
     pub generic_list: InterfaceList,
     pub map_aspect: Option<MapAspect>,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Debug, Clone, Copy, ToJson)]
 pub enum SubprogramKind {
     Function,
     Procedure,
@@ -741,7 +748,7 @@ pub enum SubprogramKind {
 
 /// LRM 4.4 Subprogram Instantiation Statement
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SubprogramInstantiation {
     pub kind: SubprogramKind,
     pub ident: WithDecl<Ident>,
@@ -751,13 +758,13 @@ pub struct SubprogramInstantiation {
 }
 
 /// LRM 4.5.3 Signatures
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Signature {
     Function(Vec<WithTokenSpan<Name>>, WithTokenSpan<Name>),
     Procedure(Vec<WithTokenSpan<Name>>),
 }
 
-#[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[derive(PartialEq, Debug, Clone, TokenSpan, ToJson)]
 pub enum SubprogramSpecification {
     Procedure(ProcedureSpecification),
     Function(FunctionSpecification),
@@ -765,13 +772,13 @@ pub enum SubprogramSpecification {
 
 /// LRM 4.2 Subprogram declarations
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SubprogramDeclaration {
     pub specification: SubprogramSpecification,
 }
 
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct InterfaceFileDeclaration {
     pub idents: Vec<WithDecl<Ident>>,
     pub colon_token: TokenId,
@@ -780,7 +787,7 @@ pub struct InterfaceFileDeclaration {
 
 /// LRM 6.5.2 Interface object declarations
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct InterfaceObjectDeclaration {
     pub list_type: InterfaceType,
     pub colon_token: TokenId,
@@ -788,13 +795,13 @@ pub struct InterfaceObjectDeclaration {
     pub mode: ModeIndication,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum ModeIndication {
     Simple(SimpleModeIndication),
     View(ModeViewIndication),
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SimpleModeIndication {
     pub mode: Option<WithToken<Mode>>,
     pub class: ObjectClass,
@@ -803,14 +810,14 @@ pub struct SimpleModeIndication {
     pub expression: Option<WithTokenSpan<Expression>>,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Debug, Clone, Copy, ToJson)]
 pub enum ModeViewIndicationKind {
     Array,
     Record,
 }
 
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ModeViewIndication {
     pub kind: ModeViewIndicationKind,
     pub name: WithTokenSpan<Name>,
@@ -818,7 +825,7 @@ pub struct ModeViewIndication {
 }
 
 /// LRM 6.5.5 Interface package declaration
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum InterfacePackageGenericMapAspect {
     Map(SeparatedList<AssociationElement>),
     Box,
@@ -827,27 +834,27 @@ pub enum InterfacePackageGenericMapAspect {
 
 /// LRM 6.5.5 Interface package declaration
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct InterfacePackageDeclaration {
     pub ident: WithDecl<Ident>,
     pub package_name: WithTokenSpan<Name>,
     pub generic_map: WithTokenSpan<InterfacePackageGenericMapAspect>,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum SubprogramDefault {
     Name(WithTokenSpan<Name>),
     Box,
 }
 
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct InterfaceSubprogramDeclaration {
     pub specification: SubprogramSpecification,
     pub default: Option<SubprogramDefault>,
 }
 
-#[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[derive(PartialEq, Debug, Clone, TokenSpan, ToJson)]
 pub enum InterfaceDeclaration {
     Object(InterfaceObjectDeclaration),
     File(InterfaceFileDeclaration),
@@ -858,7 +865,7 @@ pub enum InterfaceDeclaration {
     Package(InterfacePackageDeclaration),
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default, ToJson)]
 pub enum Mode {
     #[default]
     In,
@@ -870,7 +877,7 @@ pub enum Mode {
 
 /// LRM 6.8 Component declarations
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ComponentDeclaration {
     pub ident: WithDecl<Ident>,
     pub is_token: Option<TokenId>,
@@ -880,7 +887,7 @@ pub struct ComponentDeclaration {
     pub end_ident_pos: Option<TokenId>,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Declaration {
     Object(ObjectDeclaration),
     File(FileDeclaration),
@@ -927,7 +934,7 @@ impl Declaration {
 }
 
 /// LRM 10.2 Wait statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct WaitStatement {
     pub sensitivity_clause: Option<Vec<WithTokenSpan<Name>>>,
     pub condition_clause: Option<WithTokenSpan<Expression>>,
@@ -935,7 +942,7 @@ pub struct WaitStatement {
 }
 
 /// LRM 10.3 Assertion statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct AssertStatement {
     pub condition: WithTokenSpan<Expression>,
     pub report: Option<WithTokenSpan<Expression>>,
@@ -943,21 +950,21 @@ pub struct AssertStatement {
 }
 
 /// LRM 10.4 Report statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ReportStatement {
     pub report: WithTokenSpan<Expression>,
     pub severity: Option<WithTokenSpan<Expression>>,
 }
 
 /// LRM 10.5 Signal assignment statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Target {
     Name(Name),
     Aggregate(Vec<WithTokenSpan<ElementAssociation>>),
 }
 
 /// LRM 10.5 Signal assignment statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct WaveformElement {
     pub value: WithTokenSpan<Expression>,
     pub after: Option<WithTokenSpan<Expression>>,
@@ -977,14 +984,14 @@ impl HasTokenSpan for WaveformElement {
 }
 
 /// LRM 10.5 Signal assignment statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum Waveform {
     Elements(Vec<WaveformElement>),
     Unaffected(TokenId),
 }
 
 /// LRM 10.5 Signal assignment statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum DelayMechanism {
     Transport,
     Inertial {
@@ -993,20 +1000,20 @@ pub enum DelayMechanism {
 }
 
 /// LRM 10.5 Signal assignment statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SignalAssignment {
     pub target: WithTokenSpan<Target>,
     pub delay_mechanism: Option<WithTokenSpan<DelayMechanism>>,
     pub rhs: AssignmentRightHand<Waveform>,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, ToJson)]
 pub enum ForceMode {
     In,
     Out,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SignalForceAssignment {
     pub target: WithTokenSpan<Target>,
     pub force_mode: Option<ForceMode>,
@@ -1014,14 +1021,14 @@ pub struct SignalForceAssignment {
 }
 
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SignalReleaseAssignment {
     pub target: WithTokenSpan<Target>,
     pub force_mode: Option<ForceMode>,
 }
 
 /// LRM 10.6 Variable assignment statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct VariableAssignment {
     pub target: WithTokenSpan<Target>,
     pub rhs: AssignmentRightHand<WithTokenSpan<Expression>>,
@@ -1029,47 +1036,47 @@ pub struct VariableAssignment {
 
 /// LRM 10.5 Signal assignment statement
 /// LRM 10.6 Variable assignment statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum AssignmentRightHand<T> {
     Simple(T),
     Conditional(Conditionals<T>),
     Selected(Selection<T>),
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct Conditional<T> {
     pub condition: WithTokenSpan<Expression>,
     pub item: T,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct Conditionals<T> {
     pub conditionals: Vec<Conditional<T>>,
     pub else_item: Option<(T, TokenId)>,
 }
 
 /// LRM 10.8 If statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct IfStatement {
     pub conds: Conditionals<Vec<LabeledSequentialStatement>>,
     pub end_label_pos: Option<SrcPos>,
 }
 
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct Alternative<T> {
     pub choices: Vec<WithTokenSpan<Choice>>,
     pub item: T,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct Selection<T> {
     pub expression: WithTokenSpan<Expression>,
     pub alternatives: Vec<Alternative<T>>,
 }
 
 /// LRM 10.9 Case statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct CaseStatement {
     pub is_matching: bool,
     pub expression: WithTokenSpan<Expression>,
@@ -1079,14 +1086,14 @@ pub struct CaseStatement {
 }
 
 /// LRM 10.10 Loop statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum IterationScheme {
     While(WithTokenSpan<Expression>),
     For(WithDecl<Ident>, DiscreteRange),
 }
 
 /// LRM 10.10 Loop statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct LoopStatement {
     pub iteration_scheme: Option<IterationScheme>,
     pub loop_token: TokenId,
@@ -1096,27 +1103,27 @@ pub struct LoopStatement {
 }
 
 /// LRM 10.11 Next statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct NextStatement {
     pub loop_label: Option<WithRef<Ident>>,
     pub condition: Option<WithTokenSpan<Expression>>,
 }
 
 /// LRM 10.12 Exit statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ExitStatement {
     pub loop_label: Option<WithRef<Ident>>,
     pub condition: Option<WithTokenSpan<Expression>>,
 }
 
 /// LRM 10.13 Return statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ReturnStatement {
     pub expression: Option<WithTokenSpan<Expression>>,
 }
 
 /// LRM 10. Sequential statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum SequentialStatement {
     Wait(WaitStatement),
     Assert(AssertStatement),
@@ -1136,7 +1143,7 @@ pub enum SequentialStatement {
 }
 
 /// LRM 10. Sequential statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct LabeledSequentialStatement {
     pub label: WithDecl<Option<Ident>>,
     pub statement: WithTokenSpan<SequentialStatement>,
@@ -1144,7 +1151,7 @@ pub struct LabeledSequentialStatement {
 
 /// LRM 11.2 Block statement
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct BlockStatement {
     pub guard_condition: Option<WithTokenSpan<Expression>>,
     pub header: BlockHeader,
@@ -1157,7 +1164,7 @@ pub struct BlockStatement {
 }
 
 /// LRM 11.2 Block statement
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct BlockHeader {
     pub generic_clause: Option<InterfaceList>,
     pub generic_map: Option<MapAspect>,
@@ -1165,7 +1172,7 @@ pub struct BlockHeader {
     pub port_map: Option<MapAspect>,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum SensitivityList {
     Names(Vec<WithTokenSpan<Name>>),
     All,
@@ -1173,7 +1180,7 @@ pub enum SensitivityList {
 
 /// LRM 11.3 Process statement
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ProcessStatement {
     pub postponed: bool,
     pub sensitivity_list: Option<WithTokenSpan<SensitivityList>>,
@@ -1186,21 +1193,21 @@ pub struct ProcessStatement {
 }
 
 /// LRM 11.4 Concurrent procedure call statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ConcurrentProcedureCall {
     pub postponed: bool,
     pub call: WithTokenSpan<CallOrIndexed>,
 }
 
 /// LRM 11.5 Concurrent assertion statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ConcurrentAssertStatement {
     pub postponed: bool,
     pub statement: AssertStatement,
 }
 
 /// 11.6 Concurrent signal assignment statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ConcurrentSignalAssignment {
     pub postponed: bool,
     pub guarded: bool,
@@ -1208,7 +1215,7 @@ pub struct ConcurrentSignalAssignment {
 }
 
 /// 11.7 Component instantiation statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum InstantiatedUnit {
     Component(WithTokenSpan<Name>),
     Entity(WithTokenSpan<Name>, Option<WithRef<Ident>>),
@@ -1227,7 +1234,7 @@ impl InstantiatedUnit {
 }
 
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct MapAspect {
     pub list: SeparatedList<AssociationElement>,
 }
@@ -1241,7 +1248,7 @@ impl MapAspect {
 
 /// 11.7 Component instantiation statements
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct InstantiationStatement {
     pub unit: InstantiatedUnit,
     pub generic_map: Option<MapAspect>,
@@ -1256,7 +1263,7 @@ impl InstantiationStatement {
 }
 
 /// 11.8 Generate statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct GenerateBody {
     pub alternative_label: Option<WithDecl<Ident>>,
     pub decl: Option<(Vec<WithTokenSpan<Declaration>>, TokenId)>,
@@ -1267,7 +1274,7 @@ pub struct GenerateBody {
 
 /// 11.8 Generate statements
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ForGenerateStatement {
     pub index_name: WithDecl<Ident>,
     pub discrete_range: DiscreteRange,
@@ -1279,14 +1286,14 @@ pub struct ForGenerateStatement {
 
 /// 11.8 Generate statements
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct IfGenerateStatement {
     pub conds: Conditionals<GenerateBody>,
     pub end_label_pos: Option<SrcPos>,
 }
 
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct CaseGenerateStatement {
     pub sels: Selection<GenerateBody>,
     pub end_token: TokenId,
@@ -1294,7 +1301,7 @@ pub struct CaseGenerateStatement {
 }
 
 /// LRM 6.5.2 Interface Object Declarations - Mode view declarations
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ModeViewDeclaration {
     pub ident: WithDecl<Ident>,
     pub typ: SubtypeIndication,
@@ -1305,14 +1312,14 @@ pub struct ModeViewDeclaration {
 }
 
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ModeViewElement {
     pub names: Vec<WithDecl<Ident>>,
     pub colon_token: TokenId,
     pub mode: ElementMode,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum ElementMode {
     Simple(WithToken<Mode>),
     Record(WithTokenSpan<Name>),
@@ -1320,7 +1327,7 @@ pub enum ElementMode {
 }
 
 /// LRM 11. Concurrent statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum ConcurrentStatement {
     ProcedureCall(ConcurrentProcedureCall),
     Block(BlockStatement),
@@ -1334,7 +1341,7 @@ pub enum ConcurrentStatement {
 }
 
 /// LRM 11. Concurrent statements
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct LabeledConcurrentStatement {
     pub label: WithDecl<Option<Ident>>,
     pub statement: WithTokenSpan<ConcurrentStatement>,
@@ -1342,13 +1349,13 @@ pub struct LabeledConcurrentStatement {
 
 /// LRM 13. Design units and their analysis
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct LibraryClause {
     pub name_list: Vec<WithRef<Ident>>,
 }
 
 /// Represents a token-separated list of some generic type `T`
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct SeparatedList<T> {
     pub items: Vec<T>,
     pub tokens: Vec<TokenId>,
@@ -1387,20 +1394,20 @@ impl<T> SeparatedList<T> {
 
 /// LRM 12.4. Use clauses
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct UseClause {
     pub name_list: Vec<WithTokenSpan<Name>>,
 }
 
 /// LRM 13.4 Context clauses
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ContextReference {
     pub name_list: Vec<WithTokenSpan<Name>>,
 }
 
 /// LRM 13.4 Context clauses
-#[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[derive(PartialEq, Debug, Clone, TokenSpan, ToJson)]
 pub enum ContextItem {
     Use(UseClause),
     Library(LibraryClause),
@@ -1409,7 +1416,7 @@ pub enum ContextItem {
 
 /// LRM 13.4 Context clauses
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ContextDeclaration {
     pub ident: WithDecl<Ident>,
     pub items: ContextClause,
@@ -1419,7 +1426,7 @@ pub struct ContextDeclaration {
 
 /// LRM 4.9 Package instantiation declaration
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct PackageInstantiation {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1428,24 +1435,24 @@ pub struct PackageInstantiation {
 }
 
 /// LRM 7.3 Configuration specification
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, ToJson)]
 pub enum InstantiationList {
-    Labels(Vec<Ident>),
+    Labels(Vec<WithRef<Ident>>),
     Others,
     All,
 }
 
 /// LRM 7.3.2 Binding indication
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum EntityAspect {
-    Entity(WithTokenSpan<Name>, Option<Ident>),
+    Entity(WithTokenSpan<Name>, Option<WithRef<Ident>>),
     Configuration(WithTokenSpan<Name>),
     Open,
 }
 
 /// LRM 7.3.2 Binding indication
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct BindingIndication {
     pub entity_aspect: Option<EntityAspect>,
     pub generic_map: Option<MapAspect>,
@@ -1454,7 +1461,7 @@ pub struct BindingIndication {
 
 /// LRM 7.3 Configuration specification
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ComponentSpecification {
     pub instantiation_list: InstantiationList,
     pub colon_token: TokenId,
@@ -1463,14 +1470,14 @@ pub struct ComponentSpecification {
 
 /// LRM 7.3.4 Verification unit binding indication
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct VUnitBindingIndication {
     pub vunit_list: Vec<WithTokenSpan<Name>>,
 }
 
 /// LRM 7.3 Configuration specification
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ConfigurationSpecification {
     pub spec: ComponentSpecification,
     pub bind_ind: BindingIndication,
@@ -1480,7 +1487,7 @@ pub struct ConfigurationSpecification {
 
 /// LRM 3.4 Configuration declarations
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ComponentConfiguration {
     pub spec: ComponentSpecification,
     pub bind_ind: Option<BindingIndication>,
@@ -1489,7 +1496,7 @@ pub struct ComponentConfiguration {
 }
 
 /// LRM 3.4 Configuration declarations
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub enum ConfigurationItem {
     Block(BlockConfiguration),
     Component(ComponentConfiguration),
@@ -1497,7 +1504,7 @@ pub enum ConfigurationItem {
 
 /// LRM 3.4 Configuration declarations
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct BlockConfiguration {
     pub block_spec: WithTokenSpan<Name>,
     pub use_clauses: Vec<UseClause>,
@@ -1506,7 +1513,7 @@ pub struct BlockConfiguration {
 
 /// LRM 3.4 Configuration declarations
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ConfigurationDeclaration {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1520,7 +1527,7 @@ pub struct ConfigurationDeclaration {
 
 /// LRM 3.2 Entity declarations
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct EntityDeclaration {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1543,7 +1550,7 @@ impl EntityDeclaration {
 
 /// LRM 3.3 Architecture bodies
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct ArchitectureBody {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1553,6 +1560,8 @@ pub struct ArchitectureBody {
     pub statements: Vec<LabeledConcurrentStatement>,
     pub end_token: TokenId,
     pub end_ident_pos: Option<TokenId>,
+    /// The declarations and statements are encrypted in a protected envelope
+    pub is_protected: bool,
 }
 
 impl ArchitectureBody {
@@ -1578,7 +1587,7 @@ impl ArchitectureBody {
 
 /// LRM 4.7 Package declarations
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct PackageDeclaration {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1590,17 +1599,42 @@ pub struct PackageDeclaration {
 
 /// LRM 4.8 Package bodies
 #[with_token_span]
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, ToJson)]
 pub struct PackageBody {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
     pub decl: Vec<WithTokenSpan<Declaration>>,
     pub end_token: TokenId,
     pub end_ident_pos: Option<TokenId>,
+    /// The declarations are encrypted in a protected envelope
+    pub is_protected: bool,
+}
+
+/// The design unit that a verification unit is bound to, i.e.,
+/// `ent` or `ent(arch)`
+#[derive(PartialEq, Debug, Clone, ToJson)]
+pub struct VerificationUnitBinding {
+    pub entity_name: WithRef<Ident>,
+    pub architecture_name: Option<WithRef<Ident>>,
+}
+
+/// PSL 7.2 Verification units
+///
+/// Only the VHDL declarations of the verification unit are part of the AST.
+/// PSL directives and declarations are skipped, only the identifiers within them
+/// are kept such that they can refer to VHDL declarations.
+#[with_token_span]
+#[derive(PartialEq, Debug, Clone, ToJson)]
+pub struct VerificationUnit {
+    pub context_clause: ContextClause,
+    pub ident: WithDecl<Ident>,
+    pub binding: Option<VerificationUnitBinding>,
+    pub decl: Vec<WithTokenSpan<Declaration>>,
+    pub names: Vec<WithRef<Ident>>,
 }
 
 /// LRM 13.1 Design units
-#[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[derive(PartialEq, Debug, Clone, TokenSpan, ToJson)]
 pub enum AnyPrimaryUnit {
     /// LRM 3.2 Entity declaration
     Entity(EntityDeclaration),
@@ -1616,10 +1650,13 @@ pub enum AnyPrimaryUnit {
 
     /// LRM 13.4 Context clauses
     Context(ContextDeclaration),
+
+    /// PSL 7.2 Verification units
+    VerificationUnit(VerificationUnit),
 }
 
 /// LRM 13.1 Design units
-#[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[derive(PartialEq, Debug, Clone, TokenSpan, ToJson)]
 pub enum AnySecondaryUnit {
     /// LRM 3.3 Architecture bodies
     Architecture(ArchitectureBody),
@@ -1631,7 +1668,7 @@ pub enum AnySecondaryUnit {
 pub type ContextClause = Vec<ContextItem>;
 
 /// LRM 13.1 Design units
-#[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[derive(PartialEq, Debug, Clone, TokenSpan, ToJson)]
 pub enum AnyDesignUnit {
     Primary(AnyPrimaryUnit),
     Secondary(AnySecondaryUnit),
@@ -1641,9 +1678,30 @@ impl AnyDesignUnit {
     pub fn is_entity(&self) -> bool {
         matches!(self, AnyDesignUnit::Primary(AnyPrimaryUnit::Entity(_)))
     }
+
+    /// The context clause that precedes this design unit.
+    /// Context declarations do not have a context clause.
+    pub fn context_clause(&self) -> Option<&ContextClause> {
+        match self {
+            AnyDesignUnit::Primary(unit) => match unit {
+                AnyPrimaryUnit::Entity(unit) => Some(&unit.context_clause),
+                AnyPrimaryUnit::Configuration(unit) => Some(&unit.context_clause),
+                AnyPrimaryUnit::Package(unit) => Some(&unit.context_clause),
+                AnyPrimaryUnit::PackageInstance(unit) => Some(&unit.context_clause),
+                AnyPrimaryUnit::Context(_) => None,
+                AnyPrimaryUnit::VerificationUnit(unit) => Some(&unit.context_clause),
+            },
+            AnyDesignUnit::Secondary(unit) => match unit {
+                AnySecondaryUnit::Architecture(unit) => Some(&unit.context_clause),
+                AnySecondaryUnit::PackageBody(unit) => Some(&unit.context_clause),
+            },
+        }
+    }
 }
 
 #[derive(PartialEq, Debug, Clone, Default)]
 pub struct DesignFile {
     pub design_units: Vec<(Vec<Token>, AnyDesignUnit)>,
+    /// The VHDL standard that the file was parsed with
+    pub standard: VHDLStandard,
 }
@@ -13,6 +13,7 @@ pub enum PrimaryKind {
     Package,
     PackageInstance,
     Context,
+    VerificationUnit,
 }
 
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
@@ -139,6 +140,7 @@ macro_rules! delegate_primary {
             AnyPrimaryUnit::PackageInstance($unit) => $block,
             AnyPrimaryUnit::Context($unit) => $block,
             AnyPrimaryUnit::Configuration($unit) => $block,
+            AnyPrimaryUnit::VerificationUnit($unit) => $block,
         }
     };
 }
@@ -178,6 +180,7 @@ impl PrimaryKind {
             AnyPrimaryUnit::Package(..) => PrimaryKind::Package,
             AnyPrimaryUnit::PackageInstance(..) => PrimaryKind::PackageInstance,
             AnyPrimaryUnit::Context(..) => PrimaryKind::Context,
+            AnyPrimaryUnit::VerificationUnit(..) => PrimaryKind::VerificationUnit,
         }
     }
 
@@ -188,6 +191,7 @@ impl PrimaryKind {
             PrimaryKind::Package => "package",
             PrimaryKind::PackageInstance => "package instance",
             PrimaryKind::Context => "context",
+            PrimaryKind::VerificationUnit => "vunit",
         }
     }
 }
@@ -850,7 +850,11 @@ impl Display for FunctionSpecification {
                 write!(f, "\n)")?;
             }
         }
-        write!(f, " return {}", self.return_type)
+        write!(f, " return ")?;
+        if let Some(ident) = &self.return_identifier {
+            write!(f, "{ident} of ")?;
+        }
+        write!(f, "{}", self.return_type)
     }
 }
 
@@ -1157,6 +1161,27 @@ impl Display for ContextDeclaration {
     }
 }
 
+impl Display for VerificationUnitBinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.entity_name)?;
+        if let Some(ref architecture_name) = self.architecture_name {
+            write!(f, "({architecture_name})")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for VerificationUnit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        // Not used: context_clause, decl, names
+        write!(f, "vunit {}", self.ident)?;
+        if let Some(ref binding) = self.binding {
+            write!(f, " ({binding})")?;
+        }
+        Ok(())
+    }
+}
+
 impl Display for PackageInstantiation {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         // Not used: context_clause
@@ -0,0 +1,537 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Conversion of the syntax tree to JSON for tools that are not written in Rust.
+//!
+//! Structs become objects with one member per field.
+//! Variants of enums without fields become strings, all other variants become an object
+//! with the name of the variant as the only member, i.e. `{"Identifier": "foo"}`.
+//! Tokens and token spans become the range of the source that they cover,
+//! with zero-based lines and characters.
+//! References to named entities are `null` unless the design unit has been analyzed.
+
+use super::*;
+use crate::data::Range;
+use std::fmt;
+use std::fmt::Write;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+pub trait ToJson {
+    /// Converts `self` to JSON, using `ctx` to find the positions of tokens
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json;
+}
+
+impl DesignFile {
+    /// Converts all design units of this file to JSON
+    pub fn to_json(&self) -> Json {
+        Json::Object(vec![
+            (
+                "standard".to_owned(),
+                Json::String(format!("{:?}", self.standard)),
+            ),
+            (
+                "design_units".to_owned(),
+                Json::Array(
+                    self.design_units
+                        .iter()
+                        .map(|(tokens, unit)| unit.to_json(tokens))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
+impl Json {
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pretty = f.alternate();
+        let newline = |f: &mut fmt::Formatter<'_>, indent: usize| {
+            if pretty {
+                write!(f, "\n{:indent$}", "", indent = 2 * indent)
+            } else {
+                Ok(())
+            }
+        };
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{value}"),
+            Json::Number(value) => write!(f, "{value}"),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) if values.is_empty() => write!(f, "[]"),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    newline(f, indent + 1)?;
+                    value.write(f, indent + 1)?;
+                }
+                newline(f, indent)?;
+                write!(f, "]")
+            }
+            Json::Object(members) if members.is_empty() => write!(f, "{{}}"),
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    newline(f, indent + 1)?;
+                    write_string(f, key)?;
+                    write!(f, "{}", if pretty { ": " } else { ":" })?;
+                    value.write(f, indent + 1)?;
+                }
+                newline(f, indent)?;
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for chr in value.chars() {
+        match chr {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            chr if chr.is_control() => write!(f, "\\u{:04x}", chr as u32)?,
+            chr => f.write_char(chr)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Writes compact JSON, or indented JSON when using the alternate flag `{:#}`
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl Json {
+    /// The value of the member `key` of an object
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Parses JSON, such as files written by tools reading the output of `vhdl_lang`
+impl std::str::FromStr for Json {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Json, String> {
+        let mut parser = JsonParser {
+            chars: text.char_indices().peekable(),
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        match parser.chars.next() {
+            Some((idx, _)) => Err(format!("Unexpected trailing characters at offset {idx}")),
+            None => Ok(value),
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .next_if(|(_, chr)| chr.is_ascii_whitespace())
+            .is_some()
+        {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.chars.next() {
+            Some((_, chr)) if chr == expected => Ok(()),
+            Some((idx, chr)) => Err(format!(
+                "Expected '{expected}' at offset {idx}, got '{chr}'"
+            )),
+            None => Err(format!("Expected '{expected}', got end of input")),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Json) -> Result<Json, String> {
+        for chr in keyword.chars() {
+            self.expect(chr)?;
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        let Some(&(idx, chr)) = self.chars.peek() else {
+            return Err("Expected a value, got end of input".to_owned());
+        };
+        match chr {
+            'n' => self.keyword("null", Json::Null),
+            't' => self.keyword("true", Json::Bool(true)),
+            'f' => self.keyword("false", Json::Bool(false)),
+            '"' => Ok(Json::String(self.string()?)),
+            '[' => {
+                self.chars.next();
+                let mut values = Vec::new();
+                self.skip_whitespace();
+                if self.chars.next_if(|(_, chr)| *chr == ']').is_none() {
+                    loop {
+                        values.push(self.value()?);
+                        self.skip_whitespace();
+                        if self.chars.next_if(|(_, chr)| *chr == ',').is_none() {
+                            self.expect(']')?;
+                            break;
+                        }
+                    }
+                }
+                Ok(Json::Array(values))
+            }
+            '{' => {
+                self.chars.next();
+                let mut members = Vec::new();
+                self.skip_whitespace();
+                if self.chars.next_if(|(_, chr)| *chr == '}').is_none() {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.skip_whitespace();
+                        self.expect(':')?;
+                        members.push((key, self.value()?));
+                        self.skip_whitespace();
+                        if self.chars.next_if(|(_, chr)| *chr == ',').is_none() {
+                            self.expect('}')?;
+                            break;
+                        }
+                    }
+                }
+                Ok(Json::Object(members))
+            }
+            chr if chr == '-' || chr.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some((_, chr)) = self
+                    .chars
+                    .next_if(|(_, chr)| chr.is_ascii_digit() || "+-.eE".contains(*chr))
+                {
+                    number.push(chr);
+                }
+                Ok(Json::Number(number))
+            }
+            chr => Err(format!("Unexpected '{chr}' at offset {idx}")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((idx, '\\')) => {
+                    let chr = match self.chars.next().map(|(_, chr)| chr) {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => {
+                            let code = self.hex()?;
+                            if (0xD800..0xDC00).contains(&code) {
+                                // A surrogate pair
+                                self.expect('\\')?;
+                                self.expect('u')?;
+                                let low = self.hex()?;
+                                char::from_u32(
+                                    0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00)),
+                                )
+                            } else {
+                                char::from_u32(code)
+                            }
+                            .ok_or_else(|| format!("Invalid escape at offset {idx}"))?
+                        }
+                        Some(chr @ ('"' | '\\' | '/')) => chr,
+                        _ => return Err(format!("Invalid escape at offset {idx}")),
+                    };
+                    value.push(chr);
+                }
+                Some((_, chr)) => value.push(chr),
+                None => return Err("Unterminated string".to_owned()),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .chars
+                .next()
+                .and_then(|(_, chr)| chr.to_digit(16))
+                .ok_or("Expected four hexadecimal digits")?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json {
+        (**self).to_json(ctx)
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json {
+        self.as_ref().to_json(ctx)
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json {
+        match self {
+            Some(value) => value.to_json(ctx),
+            None => Json::Null,
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json {
+        Json::Array(self.iter().map(|value| value.to_json(ctx)).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json {
+        self.as_slice().to_json(ctx)
+    }
+}
+
+impl<A: ToJson, B: ToJson> ToJson for (A, B) {
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json {
+        Json::Array(vec![self.0.to_json(ctx), self.1.to_json(ctx)])
+    }
+}
+
+macro_rules! number_to_json {
+    ($($typ:ty),*) => {
+        $(
+            impl ToJson for $typ {
+                fn to_json(&self, _ctx: &dyn TokenAccess) -> Json {
+                    Json::Number(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+number_to_json!(u8, u32, u64, i32, i64, usize);
+
+impl ToJson for f64 {
+    fn to_json(&self, _ctx: &dyn TokenAccess) -> Json {
+        if self.is_finite() {
+            Json::Number(format!("{self:?}"))
+        } else {
+            Json::Null
+        }
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self, _ctx: &dyn TokenAccess) -> Json {
+        Json::Bool(*self)
+    }
+}
+
+impl ToJson for char {
+    fn to_json(&self, _ctx: &dyn TokenAccess) -> Json {
+        Json::String(self.to_string())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self, _ctx: &dyn TokenAccess) -> Json {
+        Json::String(self.clone())
+    }
+}
+
+impl ToJson for Latin1String {
+    fn to_json(&self, _ctx: &dyn TokenAccess) -> Json {
+        Json::String(self.to_string())
+    }
+}
+
+impl ToJson for Symbol {
+    fn to_json(&self, _ctx: &dyn TokenAccess) -> Json {
+        Json::String(self.name_utf8())
+    }
+}
+
+impl ToJson for Reference {
+    fn to_json(&self, _ctx: &dyn TokenAccess) -> Json {
+        match self.get() {
+            Some(id) => Json::Number(id.to_raw().to_string()),
+            None => Json::Null,
+        }
+    }
+}
+
+impl ToJson for Range {
+    fn to_json(&self, _ctx: &dyn TokenAccess) -> Json {
+        let position = |pos: Position| {
+            Json::Object(vec![
+                ("line".to_owned(), Json::Number(pos.line.to_string())),
+                (
+                    "character".to_owned(),
+                    Json::Number(pos.character.to_string()),
+                ),
+            ])
+        };
+        Json::Object(vec![
+            ("start".to_owned(), position(self.start)),
+            ("end".to_owned(), position(self.end)),
+        ])
+    }
+}
+
+impl ToJson for SrcPos {
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json {
+        self.range().to_json(ctx)
+    }
+}
+
+impl ToJson for TokenId {
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json {
+        ctx.get_pos(*self).to_json(ctx)
+    }
+}
+
+impl ToJson for TokenSpan {
+    fn to_json(&self, ctx: &dyn TokenAccess) -> Json {
+        self.pos(ctx).to_json(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+
+    #[test]
+    fn design_units_are_converted_with_their_positions() {
+        let code = Code::new("entity ent is\nend entity;");
+        let json = code.design_file().to_json().to_string();
+        assert!(json.starts_with(
+            "{\"standard\":\"VHDL2008\",\"design_units\":[{\"Primary\":{\"Entity\":{\"context_clause\":[]"
+        ));
+        assert!(json.contains(
+            "\"ident\":{\"tree\":{\"item\":\"ent\",\"token\":{\"start\":{\"line\":0,\"character\":7},\
+             \"end\":{\"line\":0,\"character\":10}}},\"decl\":null}"
+        ));
+    }
+
+    #[test]
+    fn written_json_can_be_parsed() {
+        let json = Json::Object(vec![
+            (
+                "name".to_owned(),
+                Json::String("a \"b\"\n\\ \u{1}é".to_owned()),
+            ),
+            (
+                "values".to_owned(),
+                Json::Array(vec![
+                    Json::Null,
+                    Json::Bool(true),
+                    Json::Number("-1.5e3".to_owned()),
+                ]),
+            ),
+            ("empty".to_owned(), Json::Object(Vec::new())),
+        ]);
+        assert_eq!(json.to_string().parse::<Json>(), Ok(json.clone()));
+        assert_eq!(format!("{json:#}").parse::<Json>(), Ok(json.clone()));
+        assert_eq!(
+            "\"\\ud83d\\ude00\"".parse::<Json>(),
+            Ok(Json::String("😀".to_owned()))
+        );
+        assert!("{\"a\": 1,}".parse::<Json>().is_err());
+        assert!("[1] 2".parse::<Json>().is_err());
+    }
+
+    #[test]
+    fn enums_are_converted_by_variant() {
+        let ctx: Vec<Token> = Vec::new();
+        assert_eq!(
+            Direction::Ascending.to_json(&ctx).to_string(),
+            "\"Ascending\""
+        );
+        assert_eq!(
+            AbstractLiteral::Integer(1).to_json(&ctx).to_string(),
+            "{\"Integer\":1}"
+        );
+        assert_eq!(
+            AbstractLiteral::Real(0.5).to_json(&ctx).to_string(),
+            "{\"Real\":0.5}"
+        );
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        assert_eq!(
+            Json::String("a\"b\\c\n".to_owned()).to_string(),
+            "\"a\\\"b\\\\c\\n\""
+        );
+    }
+
+    #[test]
+    fn pretty_printing() {
+        let json = Json::Object(vec![
+            (
+                "values".to_owned(),
+                Json::Array(vec![Json::Bool(true), Json::Number("1".to_owned())]),
+            ),
+            ("empty".to_owned(), Json::Array(vec![])),
+        ]);
+        assert_eq!(json.to_string(), "{\"values\":[true,1],\"empty\":[]}");
+        assert_eq!(
+            format!("{json:#}"),
+            "\
+{
+  \"values\": [
+    true,
+    1
+  ],
+  \"empty\": []
+}"
+        );
+    }
+}
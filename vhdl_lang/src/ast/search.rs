@@ -61,9 +61,11 @@ pub enum DeclarationItem<'a> {
     Entity(&'a EntityDeclaration),
     Architecture(&'a ArchitectureBody),
     Context(&'a ContextDeclaration),
+    VerificationUnit(&'a VerificationUnit),
     ForIndex(&'a WithDecl<Ident>, &'a DiscreteRange),
     ForGenerateIndex(Option<&'a Ident>, &'a ForGenerateStatement),
     GenerateBody(&'a WithDecl<Ident>),
+    ReturnIdentifier(&'a WithDecl<Ident>),
     ConcurrentStatement(&'a LabeledConcurrentStatement),
     SequentialStatement(&'a LabeledSequentialStatement),
     View(&'a ModeViewDeclaration),
@@ -106,6 +108,58 @@ pub trait Searcher {
         self.search_pos_with_ref(ctx, ident.item.pos(ctx), &ident.reference)
     }
 
+    /// Search a physical literal such as `10 ns`
+    fn search_physical_literal(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        _pos: &SrcPos,
+        literal: &PhysicalLiteral,
+    ) -> SearchState {
+        self.search_ident_ref(ctx, &literal.unit)
+    }
+
+    /// Search a name before its prefix and suffix are searched
+    fn search_name(&mut self, _ctx: &dyn TokenAccess, _pos: &SrcPos, _name: &Name) -> SearchState {
+        NotFinished
+    }
+
+    /// Search an expression before its operands and names are searched
+    fn search_expression(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _pos: &SrcPos,
+        _expr: &Expression,
+    ) -> SearchState {
+        NotFinished
+    }
+
+    /// Search the target of a variable or signal assignment
+    fn search_target(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _target: &WithTokenSpan<Target>,
+    ) -> SearchState {
+        NotFinished
+    }
+
+    /// Search an instantiation statement before its names are searched
+    fn search_instantiation(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _instance: &InstantiationStatement,
+    ) -> SearchState {
+        NotFinished
+    }
+
+    /// Search a concurrent or sequential procedure call before its names are searched
+    fn search_procedure_call(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _call: &WithTokenSpan<CallOrIndexed>,
+    ) -> SearchState {
+        NotFinished
+    }
+
     /// Search a declaration of a named entity
     fn search_decl(&mut self, _ctx: &dyn TokenAccess, _decl: FoundDeclaration<'_>) -> SearchState {
         NotFinished
@@ -281,6 +335,7 @@ impl Search for WithTokenSpan<ElementAssociation> {
 
 impl Search for WithTokenSpan<Target> {
     fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_finished!(searcher.search_target(ctx, self));
         match self.item {
             Target::Name(ref name) => search_pos_name(&self.pos(ctx), name, searcher, ctx),
             Target::Aggregate(ref assocs) => assocs.search(ctx, searcher),
@@ -312,6 +367,7 @@ impl Search for LabeledSequentialStatement {
             }
             SequentialStatement::ProcedureCall(ref pcall) => {
                 return_if_finished!(searcher.search_with_pos(ctx, &pcall.pos(ctx)));
+                return_if_finished!(searcher.search_procedure_call(ctx, pcall));
                 return_if_found!(pcall.item.search(ctx, searcher));
             }
             SequentialStatement::If(ref ifstmt) => {
@@ -399,8 +455,12 @@ impl Search for LabeledSequentialStatement {
                 }
             }
             SequentialStatement::SignalAssignment(ref assign) => {
-                // @TODO more
-                let SignalAssignment { target, rhs, .. } = assign;
+                let SignalAssignment {
+                    target,
+                    delay_mechanism,
+                    rhs,
+                } = assign;
+                return_if_found!(delay_mechanism.search(ctx, searcher));
                 return_if_found!(search_assignment(target, rhs, searcher, ctx));
             }
             SequentialStatement::VariableAssignment(ref assign) => {
@@ -472,6 +532,7 @@ impl Search for GenerateBody {
 
 impl Search for InstantiationStatement {
     fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_finished!(searcher.search_instantiation(ctx, self));
         match self.unit {
             InstantiatedUnit::Entity(ref ent_name, ref architecture_name) => {
                 return_if_found!(ent_name.search(ctx, searcher));
@@ -518,7 +579,11 @@ impl Search for LabeledConcurrentStatement {
             .or_not_found());
         match self.statement.item {
             ConcurrentStatement::Block(ref block) => {
-                // @TODO guard condition
+                return_if_found!(block.guard_condition.search(ctx, searcher));
+                return_if_found!(block.header.generic_clause.search(ctx, searcher));
+                return_if_found!(block.header.generic_map.search(ctx, searcher));
+                return_if_found!(block.header.port_clause.search(ctx, searcher));
+                return_if_found!(block.header.port_map.search(ctx, searcher));
                 return_if_found!(block.decl.search(ctx, searcher));
                 return_if_found!(block.statements.search(ctx, searcher));
             }
@@ -568,6 +633,7 @@ impl Search for LabeledConcurrentStatement {
             }
             ConcurrentStatement::Assignment(ref assign) => {
                 let ConcurrentSignalAssignment { assignment, .. } = assign;
+                return_if_found!(assignment.delay_mechanism.search(ctx, searcher));
                 return_if_found!(search_assignment(
                     &assignment.target,
                     &assignment.rhs,
@@ -582,6 +648,7 @@ impl Search for LabeledConcurrentStatement {
                     ..
                 } = pcall;
                 return_if_finished!(searcher.search_with_pos(ctx, &call.pos(ctx)));
+                return_if_finished!(searcher.search_procedure_call(ctx, call));
                 return_if_found!(call.item.search(ctx, searcher));
             }
             ConcurrentStatement::Assert(ref assert) => {
@@ -625,6 +692,7 @@ fn search_pos_name(
     searcher: &mut impl Searcher,
     ctx: &dyn TokenAccess,
 ) -> SearchResult {
+    return_if_finished!(searcher.search_name(ctx, pos, name));
     match name {
         Name::Selected(ref prefix, ref designator) => {
             return_if_found!(prefix.search(ctx, searcher));
@@ -663,7 +731,14 @@ fn search_pos_name(
             NotFound
         }
         Name::External(ref ename) => {
-            let ExternalName { subtype, .. } = ename.as_ref();
+            let ExternalName { path, subtype, .. } = ename.as_ref();
+            match &path.item {
+                ExternalPath::Package(name)
+                | ExternalPath::Absolute(name)
+                | ExternalPath::Relative(name, _) => {
+                    return_if_found!(name.search(ctx, searcher));
+                }
+            }
             return_if_found!(subtype.search(ctx, searcher));
             NotFound
         }
@@ -908,6 +983,7 @@ fn search_pos_expr(
     searcher: &mut impl Searcher,
 ) -> SearchResult {
     return_if_finished!(searcher.search_with_pos(ctx, pos));
+    return_if_finished!(searcher.search_expression(ctx, pos, expr));
     match expr {
         Expression::Binary(ref op, ref left, ref right) => {
             return_if_found!(searcher
@@ -933,9 +1009,9 @@ fn search_pos_expr(
             }
         }
         Expression::Literal(literal) => match literal {
-            Literal::Physical(PhysicalLiteral { unit, .. }) => {
-                searcher.search_ident_ref(ctx, unit).or_not_found()
-            }
+            Literal::Physical(literal) => searcher
+                .search_physical_literal(ctx, pos, literal)
+                .or_not_found(),
             _ => NotFound,
         },
         Expression::Parenthesized(expr) => {
@@ -1015,6 +1091,15 @@ impl Search for Waveform {
     }
 }
 
+impl Search for WithTokenSpan<DelayMechanism> {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        match &self.item {
+            DelayMechanism::Inertial { reject } => reject.search(ctx, searcher),
+            DelayMechanism::Transport => NotFound,
+        }
+    }
+}
+
 impl Search for WithTokenSpan<Expression> {
     fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
         search_pos_expr(ctx, &self.span.pos(ctx), &self.item, searcher)
@@ -1367,6 +1452,17 @@ fn search_subpgm_inner(
         SubprogramSpecification::Function(ref decl) => {
             return_if_found!(decl.header.search(ctx, searcher));
             return_if_found!(decl.parameter_list.search(ctx, searcher));
+            if let Some(ident) = &decl.return_identifier {
+                return_if_found!(searcher
+                    .search_decl(
+                        ctx,
+                        FoundDeclaration::new(
+                            &ident.decl,
+                            DeclarationItem::ReturnIdentifier(ident)
+                        )
+                    )
+                    .or_not_found());
+            }
             decl.return_type.search(ctx, searcher)
         }
         SubprogramSpecification::Procedure(ref decl) => {
@@ -1517,7 +1613,65 @@ impl Search for ConfigurationDeclaration {
                 FoundDeclaration::new(&self.ident.decl, DeclarationItem::Configuration(self))
             )
             .or_not_found());
-        self.entity_name.search(ctx, searcher)
+        return_if_found!(self.entity_name.search(ctx, searcher));
+        self.block_config.search(ctx, searcher)
+    }
+}
+
+impl Search for BlockConfiguration {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_found!(self.block_spec.search(ctx, searcher));
+        for use_clause in self.use_clauses.iter() {
+            return_if_found!(searcher
+                .search_with_pos(ctx, &use_clause.get_pos(ctx))
+                .or_not_found());
+            return_if_found!(use_clause.name_list.search(ctx, searcher));
+        }
+        for item in self.items.iter() {
+            match item {
+                ConfigurationItem::Block(block) => {
+                    return_if_found!(block.search(ctx, searcher));
+                }
+                ConfigurationItem::Component(config) => {
+                    return_if_found!(config.search(ctx, searcher));
+                }
+            }
+        }
+        NotFound
+    }
+}
+
+impl Search for ComponentConfiguration {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        if let InstantiationList::Labels(ref labels) = self.spec.instantiation_list {
+            for label in labels.iter() {
+                return_if_found!(searcher
+                    .search_pos_with_ref(ctx, label.item.pos(ctx), &label.reference)
+                    .or_not_found());
+            }
+        }
+        return_if_found!(self.spec.component_name.search(ctx, searcher));
+        if let Some(ref bind_ind) = self.bind_ind {
+            match bind_ind.entity_aspect {
+                Some(EntityAspect::Entity(ref entity_name, ref architecture_name)) => {
+                    return_if_found!(entity_name.search(ctx, searcher));
+                    if let Some(ref architecture_name) = architecture_name {
+                        return_if_found!(searcher
+                            .search_pos_with_ref(
+                                ctx,
+                                architecture_name.item.pos(ctx),
+                                &architecture_name.reference
+                            )
+                            .or_not_found());
+                    }
+                }
+                Some(EntityAspect::Configuration(ref config_name)) => {
+                    return_if_found!(config_name.search(ctx, searcher));
+                }
+                Some(EntityAspect::Open) | None => {}
+            }
+        }
+        self.block_config.search(ctx, searcher)
     }
 }
 
@@ -1533,6 +1687,33 @@ impl Search for ContextDeclaration {
     }
 }
 
+impl Search for VerificationUnit {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        return_if_found!(self.context_clause.search(ctx, searcher));
+        return_if_found!(searcher
+            .search_decl(
+                ctx,
+                FoundDeclaration::new(&self.ident.decl, DeclarationItem::VerificationUnit(self))
+            )
+            .or_not_found());
+        if let Some(ref binding) = self.binding {
+            return_if_found!(searcher
+                .search_ident_ref(ctx, &binding.entity_name)
+                .or_not_found());
+            if let Some(ref architecture_name) = binding.architecture_name {
+                return_if_found!(searcher
+                    .search_ident_ref(ctx, architecture_name)
+                    .or_not_found());
+            }
+        }
+        return_if_found!(self.decl.search(ctx, searcher));
+        for name in self.names.iter() {
+            return_if_found!(searcher.search_ident_ref(ctx, name).or_not_found());
+        }
+        NotFound
+    }
+}
+
 impl Search for CaseStatement {
     fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
         let CaseStatement {
@@ -1681,6 +1862,36 @@ impl<'a, T: Fn(EntRef<'a>) -> bool> Searcher for FindEnt<'a, T> {
     }
 }
 
+// Search for the incomplete type declaration that is completed by a type
+pub struct FindIncompleteType {
+    id: EntityId,
+    pub result: Option<SrcPos>,
+}
+
+impl FindIncompleteType {
+    pub fn new(id: EntityId) -> FindIncompleteType {
+        FindIncompleteType { id, result: None }
+    }
+}
+
+impl Searcher for FindIncompleteType {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        if let DeclarationItem::Type(TypeDeclaration {
+            ident,
+            def: TypeDefinition::Incomplete(reference),
+            ..
+        }) = decl.ast
+        {
+            if reference.get() == Some(self.id) {
+                self.result = Some(ident.pos(ctx).clone());
+                return SearchState::Finished(SearchResult::Found);
+            }
+        }
+
+        SearchState::NotFinished
+    }
+}
+
 pub struct FindAllEnt<'a, T: FnMut(EntRef<'a>) -> bool> {
     root: &'a DesignRoot,
     cond: T,
@@ -1723,7 +1934,7 @@ impl<'a> FormatDeclaration<'a> {
 }
 
 impl<'a> Searcher for FormatDeclaration<'a> {
-    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
         let id = if let Some(id) = decl.ent_id() {
             id
         } else {
@@ -1739,8 +1950,13 @@ impl<'a> Searcher for FormatDeclaration<'a> {
             ));
             return Finished(Found);
         } else if self.ent.id() == id {
-            // Explicit
-            self.result = Some(decl.ast.to_string());
+            // Explicit, preceded by the comments that document it
+            let mut result = String::new();
+            for comment in self.ent.src_span.documentation(ctx) {
+                result.push_str(&format!("{comment}\n"));
+            }
+            result.push_str(&decl.ast.to_string());
+            self.result = Some(result);
             return Finished(Found);
         }
         NotFinished
@@ -1751,7 +1967,11 @@ impl<'a> Searcher for FormatDeclaration<'a> {
 pub struct FindAllReferences<'a> {
     root: &'a DesignRoot,
     ent: EntRef<'a>,
+    /// Whether references of aliases of the same entity are included
+    through_aliases: bool,
     pub references: Vec<SrcPos>,
+    /// The subset of `references` that are the names of declarations
+    pub declarations: Vec<SrcPos>,
 }
 
 pub fn is_reference(ent: EntRef<'_>, other: EntRef<'_>) -> bool {
@@ -1770,12 +1990,36 @@ pub fn is_reference(ent: EntRef<'_>, other: EntRef<'_>) -> bool {
     false
 }
 
+/// Whether `other` references `ent` or an alias of the same entity
+fn is_reference_through_aliases(ent: EntRef<'_>, other: EntRef<'_>) -> bool {
+    is_reference(ent, other) || is_reference(ent.as_actual(), other.as_actual())
+}
+
 impl<'a> FindAllReferences<'a> {
     pub fn new(root: &'a DesignRoot, ent: EntRef<'a>) -> FindAllReferences<'a> {
         FindAllReferences {
             root,
             ent,
+            through_aliases: false,
             references: Vec::new(),
+            declarations: Vec::new(),
+        }
+    }
+
+    /// Also finds the references of aliases of the entity
+    /// and of the entity that is aliased by it.
+    pub fn through_aliases(root: &'a DesignRoot, ent: EntRef<'a>) -> FindAllReferences<'a> {
+        FindAllReferences {
+            through_aliases: true,
+            ..FindAllReferences::new(root, ent)
+        }
+    }
+
+    fn is_reference(&self, other: EntRef<'_>) -> bool {
+        if self.through_aliases {
+            is_reference_through_aliases(self.ent, other)
+        } else {
+            is_reference(self.ent, other)
         }
     }
 }
@@ -1785,12 +2029,14 @@ impl<'a> Searcher for FindAllReferences<'a> {
         if let Some(id) = decl.ent_id() {
             let other = self.root.get_ent(id);
 
-            if is_reference(self.ent, other) {
+            if self.is_reference(other) {
                 if let Some(decl_pos) = other.decl_pos() {
                     self.references.push(decl_pos.clone());
+                    self.declarations.push(decl_pos.clone());
                 }
                 if let Some(pos) = decl.end_ident_pos() {
                     self.references.push(ctx.get_pos(pos).clone());
+                    self.declarations.push(ctx.get_pos(pos).clone());
                 }
             }
         }
@@ -1805,7 +2051,7 @@ impl<'a> Searcher for FindAllReferences<'a> {
     ) -> SearchState {
         if let Some(id) = reference.get() {
             let other = self.root.get_ent(id);
-            if is_reference(self.ent, other) {
+            if self.is_reference(other) {
                 self.references.push(pos.clone());
             }
         };
@@ -1813,8 +2059,146 @@ impl<'a> Searcher for FindAllReferences<'a> {
     }
 }
 
+// Search for all references to a declaration that are assigned to
+pub struct FindAllWrites<'a> {
+    root: &'a DesignRoot,
+    ent: EntRef<'a>,
+    /// Whether writes through aliases of the same entity are included
+    through_aliases: bool,
+    pub writes: Vec<SrcPos>,
+}
+
+impl<'a> FindAllWrites<'a> {
+    pub fn new(root: &'a DesignRoot, ent: EntRef<'a>) -> FindAllWrites<'a> {
+        FindAllWrites {
+            root,
+            ent,
+            through_aliases: false,
+            writes: Vec::new(),
+        }
+    }
+
+    /// Also finds the writes through aliases of the entity
+    /// and of the entity that is aliased by it.
+    pub fn through_aliases(root: &'a DesignRoot, ent: EntRef<'a>) -> FindAllWrites<'a> {
+        FindAllWrites {
+            through_aliases: true,
+            ..FindAllWrites::new(root, ent)
+        }
+    }
+
+    /// Collect the written designators of a target name, i.e. `sig` and `field`
+    /// but not `idx` in `sig(idx).field`
+    fn add_written_name(&mut self, ctx: &dyn TokenAccess, pos: &SrcPos, name: &Name) {
+        match name {
+            Name::Designator(designator) => self.add_write(pos, &designator.reference),
+            Name::Selected(prefix, suffix) => {
+                self.add_written_name(ctx, &prefix.pos(ctx), &prefix.item);
+                self.add_write(suffix.pos(ctx), &suffix.item.reference);
+            }
+            Name::SelectedAll(prefix) | Name::Slice(prefix, _) => {
+                self.add_written_name(ctx, &prefix.pos(ctx), &prefix.item)
+            }
+            Name::CallOrIndexed(call) => {
+                self.add_written_name(ctx, &call.name.pos(ctx), &call.name.item)
+            }
+            Name::Attribute(_) | Name::External(_) => {}
+        }
+    }
+
+    fn add_write(&mut self, pos: &SrcPos, reference: &Reference) {
+        if let Some(id) = reference.get() {
+            let other = self.root.get_ent(id);
+            let is_write = if self.through_aliases {
+                is_reference_through_aliases(self.ent, other)
+            } else {
+                is_reference(self.ent, other)
+            };
+            if is_write {
+                self.writes.push(pos.clone());
+            }
+        }
+    }
+}
+
+impl<'a> Searcher for FindAllWrites<'a> {
+    fn search_target(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        target: &WithTokenSpan<Target>,
+    ) -> SearchState {
+        match &target.item {
+            Target::Name(name) => self.add_written_name(ctx, &target.pos(ctx), name),
+            Target::Aggregate(assocs) => {
+                for assoc in assocs {
+                    let (ElementAssociation::Named(_, expr) | ElementAssociation::Positional(expr)) =
+                        &assoc.item;
+                    if let Expression::Name(name) = &expr.item {
+                        self.add_written_name(ctx, &expr.pos(ctx), name);
+                    }
+                }
+            }
+        }
+        NotFinished
+    }
+}
+
+// Search for the names of the instantiated units of instantiation statements
+#[derive(Default)]
+pub struct FindInstantiatedNames {
+    pub positions: Vec<SrcPos>,
+}
+
+impl Searcher for FindInstantiatedNames {
+    fn search_instantiation(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        instance: &InstantiationStatement,
+    ) -> SearchState {
+        let name = match &instance.unit {
+            InstantiatedUnit::Entity(name, architecture) => {
+                if let Some(architecture) = architecture {
+                    self.positions.push(architecture.item.pos(ctx).clone());
+                }
+                name
+            }
+            InstantiatedUnit::Component(name) | InstantiatedUnit::Configuration(name) => name,
+        };
+        self.positions
+            .push(ctx.get_pos(name.span.end_token).clone());
+        NotFinished
+    }
+}
+
+// Search for the name of a declaration and its repetition after the `end` keyword
+pub struct FindNamePair<'a> {
+    ent: EntRef<'a>,
+    pub positions: Vec<SrcPos>,
+}
+
+impl<'a> FindNamePair<'a> {
+    pub fn new(ent: EntRef<'a>) -> FindNamePair<'a> {
+        FindNamePair {
+            ent,
+            positions: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Searcher for FindNamePair<'a> {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        if decl.ent_id() != Some(self.ent.id()) {
+            return NotFinished;
+        }
+        if let (Some(decl_pos), Some(end_pos)) = (self.ent.decl_pos(), decl.end_name_pos(ctx)) {
+            self.positions = vec![decl_pos.clone(), end_pos];
+        }
+        Finished(Found)
+    }
+}
+
 impl<'a> FoundDeclaration<'a> {
-    fn end_ident_pos(&self) -> Option<TokenId> {
+    pub(crate) fn end_ident_pos(&self) -> Option<TokenId> {
         match &self.ast {
             DeclarationItem::InterfaceObject(_) => None,
             DeclarationItem::ForIndex(..) => None,
@@ -1841,7 +2225,9 @@ impl<'a> FoundDeclaration<'a> {
             DeclarationItem::Entity(value) => value.end_ident_pos,
             DeclarationItem::Architecture(value) => value.end_ident_pos,
             DeclarationItem::Context(value) => value.end_ident_pos,
+            DeclarationItem::VerificationUnit(..) => None,
             DeclarationItem::GenerateBody(..) => None,
+            DeclarationItem::ReturnIdentifier(..) => None,
             DeclarationItem::ConcurrentStatement(..) => None,
             DeclarationItem::SequentialStatement(..) => None,
             DeclarationItem::SubprogramInstantiation(_) => None,
@@ -1852,6 +2238,19 @@ impl<'a> FoundDeclaration<'a> {
     fn ent_id_ref(&self) -> &Reference {
         self.reference
     }
+
+    /// The identifier or label that repeats the name of the declaration after `end`
+    fn end_name_pos(&self, ctx: &dyn TokenAccess) -> Option<SrcPos> {
+        match &self.ast {
+            DeclarationItem::ConcurrentStatement(statement) => {
+                statement.statement.item.end_label_pos().cloned()
+            }
+            DeclarationItem::SequentialStatement(statement) => {
+                statement.statement.item.end_label_pos().cloned()
+            }
+            _ => self.end_ident_pos().map(|id| ctx.get_pos(id).clone()),
+        }
+    }
 }
 
 impl SubprogramSpecification {
@@ -1954,9 +2353,15 @@ impl std::fmt::Display for DeclarationItem<'_> {
             DeclarationItem::Context(ref value) => {
                 write!(f, "{value}")
             }
+            DeclarationItem::VerificationUnit(ref value) => {
+                write!(f, "{value}")
+            }
             DeclarationItem::GenerateBody(value) => {
                 write!(f, "{value}")
             }
+            DeclarationItem::ReturnIdentifier(value) => {
+                write!(f, "{value}")
+            }
             DeclarationItem::ConcurrentStatement(value) => {
                 if let Some(ref label) = value.label.tree {
                     write!(f, "{label}")
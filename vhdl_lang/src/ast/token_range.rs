@@ -12,7 +12,7 @@
 use crate::{SrcPos, TokenAccess, TokenId, TokenSpan};
 
 /// A struct that associates some generic item to a single token.
-#[derive(Eq, PartialEq, Debug, Clone)]
+#[derive(Eq, PartialEq, Debug, Clone, ToJson)]
 pub struct WithToken<T> {
     pub item: T,
     pub token: TokenId,
@@ -55,7 +55,7 @@ impl<T> WithToken<T> {
 }
 
 /// A struct that associates some generic item to a contiguous span of tokens.
-#[derive(PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, ToJson)]
 pub struct WithTokenSpan<T> {
     pub item: T,
     pub span: TokenSpan,
@@ -173,6 +173,12 @@ impl HasIdent for ContextDeclaration {
     }
 }
 
+impl HasIdent for VerificationUnit {
+    fn ident(&self) -> &Ident {
+        self.ident.ident()
+    }
+}
+
 impl HasIdent for ConfigurationDeclaration {
     fn ident(&self) -> &Ident {
         self.ident.ident()
@@ -187,6 +193,7 @@ impl HasIdent for AnyPrimaryUnit {
             AnyPrimaryUnit::Package(ref unit) => unit.ident(),
             AnyPrimaryUnit::PackageInstance(ref unit) => unit.ident(),
             AnyPrimaryUnit::Context(ref unit) => unit.ident(),
+            AnyPrimaryUnit::VerificationUnit(ref unit) => unit.ident(),
         }
     }
 }
@@ -429,6 +436,62 @@ impl AttributeName {
     }
 }
 
+impl AttributeDesignator {
+    /// A short, human-readable description of a predefined attribute (LRM 16.2).
+    /// Returns `None` for user-defined attributes.
+    pub fn documentation(&self) -> Option<&'static str> {
+        use AttributeDesignator::*;
+        Some(match self {
+            Ident(_) => return None,
+            Type(TypeAttribute::Subtype) => "The fully constrained subtype of the prefix",
+            Type(TypeAttribute::Element) => "The element subtype of the array prefix",
+            Range(RangeAttribute::Range) => "The index range of the array prefix",
+            Range(RangeAttribute::ReverseRange) => {
+                "The index range of the array prefix in reverse direction"
+            }
+            Ascending => "TRUE if the range of the prefix is ascending",
+            Left => "The left bound of the prefix",
+            Right => "The right bound of the prefix",
+            High => "The upper bound of the prefix",
+            Low => "The lower bound of the prefix",
+            Length => "The number of elements of the array prefix",
+            Image => "The string representation of a value of the prefix type",
+            Value => "The value of the prefix type denoted by a string",
+            Pos => "The position number of a value of the prefix type",
+            Val => "The value of the prefix type at a position number",
+            Succ => "The value at the next higher position",
+            Pred => "The value at the next lower position",
+            LeftOf => "The value at the position to the left",
+            RightOf => "The value at the position to the right",
+            Signal(signal) => match signal {
+                SignalAttribute::Delayed => "The prefix signal delayed by the given time",
+                SignalAttribute::Stable => {
+                    "TRUE when no event has occurred on the prefix for the given time"
+                }
+                SignalAttribute::Quiet => {
+                    "TRUE when the prefix has not been active for the given time"
+                }
+                SignalAttribute::Transaction => {
+                    "A BIT signal that toggles on every transaction of the prefix"
+                }
+                SignalAttribute::Event => "TRUE if an event occurred on the prefix in this cycle",
+                SignalAttribute::Active => "TRUE if the prefix is active in this cycle",
+                SignalAttribute::LastEvent => "The time elapsed since the last event",
+                SignalAttribute::LastActive => "The time elapsed since the prefix was last active",
+                SignalAttribute::LastValue => "The value of the prefix before the last event",
+                SignalAttribute::Driving => "TRUE if the current process drives the prefix",
+                SignalAttribute::DrivingValue => {
+                    "The value the current process contributes to the prefix"
+                }
+            },
+            SimpleName => "The simple name of the prefix",
+            InstanceName => "The hierarchical path of the prefix including instantiated entities",
+            PathName => "The hierarchical path of the prefix",
+            Converse => "The converse of the mode view prefix",
+        })
+    }
+}
+
 impl RangeConstraint {
     pub fn span(&self) -> TokenSpan {
         self.left_expr.span.combine(self.right_expr.span)
@@ -0,0 +1,260 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Walking the syntax tree with a [Visitor].
+//!
+//! A visitor only implements the methods for the nodes that it is interested in,
+//! the [walk] function takes care of reaching every node of the tree in source order.
+//! Nodes are visited before their children, and a visitor can stop the walk by returning
+//! [ControlFlow::Break] from any of its methods.
+
+use super::search::{
+    DeclarationItem, Finished, Found, FoundDeclaration, NotFinished, NotFound, Search, SearchState,
+    Searcher,
+};
+use super::*;
+use std::ops::ControlFlow;
+
+pub trait Visitor {
+    /// Visit a design unit or a declaration of a named entity, such as an object, type or subprogram.
+    /// Labeled statements are visited through [Visitor::visit_concurrent_statement]
+    /// and [Visitor::visit_sequential_statement] instead.
+    fn visit_declaration(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _decl: &FoundDeclaration<'_>,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Visit a concurrent statement before its contents
+    fn visit_concurrent_statement(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _statement: &LabeledConcurrentStatement,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Visit a sequential statement before its contents
+    fn visit_sequential_statement(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _statement: &LabeledSequentialStatement,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Visit an expression before its operands
+    fn visit_expression(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _pos: &SrcPos,
+        _expr: &Expression,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Visit a name before its prefix and suffix
+    fn visit_name(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _pos: &SrcPos,
+        _name: &Name,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Visit the target of a variable or signal assignment
+    fn visit_target(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _target: &WithTokenSpan<Target>,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Walks `tree` with `visitor`, where `ctx` gives access to the tokens of the tree,
+/// such as the tokens of a design unit in a [DesignFile].
+/// Returns [ControlFlow::Break] when the visitor stopped the walk.
+pub fn walk(
+    tree: &impl Search,
+    ctx: &dyn TokenAccess,
+    visitor: &mut impl Visitor,
+) -> ControlFlow<()> {
+    match tree.search(ctx, &mut Walker::new(visitor)) {
+        Found => ControlFlow::Break(()),
+        NotFound => ControlFlow::Continue(()),
+    }
+}
+
+/// Adapts a [Visitor] to a [Searcher],
+/// such that it can walk all design units of a [Project](crate::Project) using its `search` method.
+pub struct Walker<'a, V> {
+    visitor: &'a mut V,
+}
+
+impl<'a, V: Visitor> Walker<'a, V> {
+    pub fn new(visitor: &'a mut V) -> Walker<'a, V> {
+        Walker { visitor }
+    }
+}
+
+fn state(flow: ControlFlow<()>) -> SearchState {
+    match flow {
+        ControlFlow::Break(()) => Finished(Found),
+        ControlFlow::Continue(()) => NotFinished,
+    }
+}
+
+impl<V: Visitor> Searcher for Walker<'_, V> {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        state(match decl.ast {
+            DeclarationItem::ConcurrentStatement(statement) => {
+                self.visitor.visit_concurrent_statement(ctx, statement)
+            }
+            DeclarationItem::SequentialStatement(statement) => {
+                self.visitor.visit_sequential_statement(ctx, statement)
+            }
+            _ => self.visitor.visit_declaration(ctx, &decl),
+        })
+    }
+
+    fn search_expression(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        expr: &Expression,
+    ) -> SearchState {
+        state(self.visitor.visit_expression(ctx, pos, expr))
+    }
+
+    fn search_name(&mut self, ctx: &dyn TokenAccess, pos: &SrcPos, name: &Name) -> SearchState {
+        state(self.visitor.visit_name(ctx, pos, name))
+    }
+
+    fn search_target(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        target: &WithTokenSpan<Target>,
+    ) -> SearchState {
+        state(self.visitor.visit_target(ctx, target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+
+    #[derive(Default)]
+    struct Collect {
+        visited: Vec<String>,
+        stop_at: Option<String>,
+    }
+
+    impl Collect {
+        fn push(&mut self, kind: &str, pos: &SrcPos) -> ControlFlow<()> {
+            let contents = pos.source.contents();
+            let line = contents.get_line(pos.start().line as usize).unwrap();
+            let text =
+                line[pos.start().character as usize..pos.end().character as usize].to_owned();
+            self.visited.push(format!("{kind} {text}"));
+            if self.stop_at.as_ref() == Some(&text) {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    impl Visitor for Collect {
+        fn visit_declaration(
+            &mut self,
+            ctx: &dyn TokenAccess,
+            decl: &FoundDeclaration<'_>,
+        ) -> ControlFlow<()> {
+            match decl.ast {
+                DeclarationItem::Object(object) => {
+                    self.push("object", object.idents[0].tree.pos(ctx))
+                }
+                DeclarationItem::Architecture(arch) => {
+                    self.push("architecture", arch.ident.tree.pos(ctx))
+                }
+                _ => ControlFlow::Continue(()),
+            }
+        }
+
+        fn visit_concurrent_statement(
+            &mut self,
+            ctx: &dyn TokenAccess,
+            statement: &LabeledConcurrentStatement,
+        ) -> ControlFlow<()> {
+            self.push("concurrent", &statement.statement.pos(ctx))
+        }
+
+        fn visit_expression(
+            &mut self,
+            _ctx: &dyn TokenAccess,
+            pos: &SrcPos,
+            _expr: &Expression,
+        ) -> ControlFlow<()> {
+            self.push("expression", pos)
+        }
+
+        fn visit_target(
+            &mut self,
+            ctx: &dyn TokenAccess,
+            target: &WithTokenSpan<Target>,
+        ) -> ControlFlow<()> {
+            self.push("target", &target.pos(ctx))
+        }
+    }
+
+    const CODE: &str = "\
+architecture a of ent is
+  signal sig : bit;
+begin
+  sig <= not sig;
+end architecture;";
+
+    #[test]
+    fn visits_nodes_in_source_order() {
+        let code = Code::new(CODE);
+        let (tokens, unit) = &code.design_file().design_units[0];
+
+        let mut visitor = Collect::default();
+        assert_eq!(walk(unit, tokens, &mut visitor), ControlFlow::Continue(()));
+        assert_eq!(
+            visitor.visited,
+            vec![
+                "architecture a",
+                "object sig",
+                "concurrent sig <= not sig;",
+                "target sig",
+                "expression not sig",
+                "expression sig",
+            ]
+        );
+    }
+
+    #[test]
+    fn visitor_can_stop_the_walk() {
+        let code = Code::new(CODE);
+        let (tokens, unit) = &code.design_file().design_units[0];
+
+        let mut visitor = Collect {
+            stop_at: Some("sig <= not sig;".to_owned()),
+            ..Collect::default()
+        };
+        assert_eq!(walk(unit, tokens, &mut visitor), ControlFlow::Break(()));
+        assert_eq!(
+            visitor.visited,
+            vec!["architecture a", "object sig", "concurrent sig <= not sig;"]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! A record of the diagnostics of a project at some point in time,
+//! such that legacy projects can adopt the analysis incrementally
+//! by only reporting diagnostics that are not part of the baseline.
+//!
+//! Diagnostics are matched by file, code, message and the text of the line they are on,
+//! but not by line number, so that they are still matched after lines are added or removed above them.
+//! A diagnostic that is recorded `n` times suppresses at most `n` diagnostics.
+
+use crate::ast::json::Json;
+use crate::{Diagnostic, SeverityMap};
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use std::path::Path;
+
+const BASELINE_VERSION: &str = "1";
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+struct Fingerprint {
+    /// The path of the file, relative to the base directory if it is within it
+    file: String,
+    code: String,
+    message: String,
+    /// The text of the line of the diagnostic without leading and trailing whitespace
+    line_text: String,
+}
+
+impl Fingerprint {
+    fn of(diagnostic: &Diagnostic, base_dir: &Path) -> Fingerprint {
+        let file_name = diagnostic.pos.file_name();
+        let file = match file_name.strip_prefix(base_dir) {
+            Ok(relative) => relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .join("/"),
+            Err(_) => file_name.to_string_lossy().into_owned(),
+        };
+        let line_text = diagnostic
+            .pos
+            .source
+            .contents()
+            .get_line(diagnostic.pos.range.start.line as usize)
+            .unwrap_or_default()
+            .trim()
+            .to_owned();
+        Fingerprint {
+            file,
+            code: diagnostic.code.as_str().to_owned(),
+            message: diagnostic.message.clone(),
+            line_text,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Baseline {
+    /// The recorded diagnostics with their one-based line when the baseline was written
+    entries: Vec<(Fingerprint, u32)>,
+}
+
+impl Baseline {
+    /// Records all diagnostics that are not disabled in `severities`
+    pub fn from_diagnostics(
+        diagnostics: &[Diagnostic],
+        severities: &SeverityMap,
+        base_dir: &Path,
+    ) -> Baseline {
+        let entries = diagnostics
+            .iter()
+            .filter(|diagnostic| severities[diagnostic.code].is_some())
+            .map(|diagnostic| {
+                (
+                    Fingerprint::of(diagnostic, base_dir),
+                    diagnostic.pos.range.start.line + 1,
+                )
+            })
+            .sorted_by(|(a, a_line), (b, b_line)| (&a.file, a_line).cmp(&(&b.file, b_line)))
+            .collect();
+        Baseline { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes the diagnostics that are part of the baseline
+    pub fn retain_new(&self, diagnostics: &mut Vec<Diagnostic>, base_dir: &Path) {
+        let mut remaining: FnvHashMap<&Fingerprint, usize> = FnvHashMap::default();
+        for (fingerprint, _) in self.entries.iter() {
+            *remaining.entry(fingerprint).or_default() += 1;
+        }
+        diagnostics.retain(|diagnostic| {
+            match remaining.get_mut(&Fingerprint::of(diagnostic, base_dir)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            }
+        });
+    }
+
+    pub fn to_json(&self) -> Json {
+        let string = |value: &str| Json::String(value.to_owned());
+        Json::Object(vec![
+            (
+                "version".to_owned(),
+                Json::Number(BASELINE_VERSION.to_owned()),
+            ),
+            (
+                "diagnostics".to_owned(),
+                Json::Array(
+                    self.entries
+                        .iter()
+                        .map(|(fingerprint, line)| {
+                            Json::Object(vec![
+                                ("file".to_owned(), string(&fingerprint.file)),
+                                ("line".to_owned(), Json::Number(line.to_string())),
+                                ("code".to_owned(), string(&fingerprint.code)),
+                                ("message".to_owned(), string(&fingerprint.message)),
+                                ("line_text".to_owned(), string(&fingerprint.line_text)),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+
+    pub fn from_json(json: &Json) -> Result<Baseline, String> {
+        match json.get("version") {
+            Some(Json::Number(version)) if version == BASELINE_VERSION => {}
+            _ => return Err(format!("Expected baseline version {BASELINE_VERSION}")),
+        }
+        let Some(Json::Array(diagnostics)) = json.get("diagnostics") else {
+            return Err("Expected diagnostics to be an array".to_owned());
+        };
+        let entries = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let member = |key: &str| {
+                    diagnostic
+                        .get(key)
+                        .and_then(Json::as_str)
+                        .map(str::to_owned)
+                        .ok_or_else(|| format!("Expected {key} of diagnostic to be a string"))
+                };
+                let line = match diagnostic.get("line") {
+                    Some(Json::Number(line)) => line.parse().ok(),
+                    _ => None,
+                }
+                .ok_or("Expected line of diagnostic to be a number")?;
+                Ok((
+                    Fingerprint {
+                        file: member("file")?,
+                        code: member("code")?,
+                        message: member("message")?,
+                        line_text: member("line_text")?,
+                    },
+                    line,
+                ))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Baseline { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::error_codes::ErrorCode;
+    use crate::syntax::test::Code;
+
+    #[test]
+    fn baseline_matches_diagnostics_after_line_shifts() {
+        let before = Code::new("signal a : bit;\nsignal b : bit;");
+        let unused = |code: &Code, line: &str, occurrence: usize| {
+            Diagnostic::new(
+                code.s(line, occurrence),
+                format!("Unused declaration of signal '{}'", &line[7..8]),
+                ErrorCode::Unused,
+            )
+        };
+        let base_dir = before.source().file_name().parent().unwrap();
+        let baseline = Baseline::from_diagnostics(
+            &[
+                unused(&before, "signal a", 1),
+                unused(&before, "signal b", 1),
+            ],
+            &SeverityMap::default(),
+            base_dir,
+        );
+        assert_eq!(baseline.len(), 2);
+
+        let json = baseline.to_json().to_string();
+        let baseline = Baseline::from_json(&json.parse().unwrap()).unwrap();
+
+        // A line was added above the diagnostics, and `b` is declared once more
+        let after = Code::new_with_file_name(
+            before.source().file_name(),
+            "signal new : bit;\nsignal a : bit;\nsignal b : bit;\nsignal b : bit;",
+        );
+        let mut diagnostics = vec![
+            unused(&after, "signal a", 1),
+            unused(&after, "signal b", 1),
+            unused(&after, "signal b", 2),
+            unused(&after, "signal new", 1),
+        ];
+        baseline.retain_new(&mut diagnostics, base_dir);
+        assert_eq!(
+            diagnostics,
+            vec![
+                unused(&after, "signal b", 2),
+                unused(&after, "signal new", 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_baseline_is_an_error() {
+        assert_eq!(
+            Baseline::from_json(&"{\"version\": 2}".parse().unwrap()),
+            Err("Expected baseline version 1".to_owned())
+        );
+        assert_eq!(
+            Baseline::from_json(
+                &"{\"version\": 1, \"diagnostics\": [{\"file\": \"a.vhd\"}]}"
+                    .parse()
+                    .unwrap()
+            ),
+            Err("Expected line of diagnostic to be a number".to_owned())
+        );
+    }
+}
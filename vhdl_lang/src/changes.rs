@@ -0,0 +1,178 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! The files and lines that were changed relative to a git revision,
+//! such that only the diagnostics of a change are reported, i.e., when checking a pull request.
+
+use crate::Diagnostic;
+use fnv::FnvHashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The lines of the working tree that were added or modified, by file.
+/// Files that are not tracked by git count as changed entirely.
+#[derive(PartialEq, Debug, Default)]
+pub struct Changes {
+    /// Canonical paths of the changed files with their changed zero-based lines,
+    /// or `None` if the whole file is new
+    files: FnvHashMap<PathBuf, Option<Vec<Range<u32>>>>,
+}
+
+impl Changes {
+    /// Finds the changes of the working tree in `directory` relative to `revision` using git
+    pub fn since(revision: &str, directory: &Path) -> Result<Changes, String> {
+        let root = git(directory, &["rev-parse", "--show-toplevel"])?;
+        let root = PathBuf::from(root.trim());
+        let diff = git(
+            &root,
+            &[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+                "--no-prefix",
+                revision,
+                "--",
+            ],
+        )?;
+        let mut changes = Changes::from_diff(&diff, &root);
+        let untracked = git(&root, &["ls-files", "--others", "--exclude-standard"])?;
+        for file in untracked.lines() {
+            changes.files.insert(canonical(&root.join(file)), None);
+        }
+        Ok(changes)
+    }
+
+    /// Parses the output of `git diff --unified=0 --no-prefix`, where files are relative to `root`
+    fn from_diff(diff: &str, root: &Path) -> Changes {
+        let mut files = FnvHashMap::default();
+        let mut current: Option<&mut Option<Vec<Range<u32>>>> = None;
+        for line in diff.lines() {
+            if let Some(file) = line.strip_prefix("+++ ") {
+                current = if file == "/dev/null" {
+                    // A deleted file
+                    None
+                } else {
+                    Some(
+                        files
+                            .entry(canonical(&root.join(file)))
+                            .or_insert_with(|| Some(Vec::new())),
+                    )
+                };
+            } else if let (Some(hunk), Some(Some(lines))) =
+                (line.strip_prefix("@@ "), current.as_mut())
+            {
+                if let Some(added) = added_lines(hunk) {
+                    lines.push(added);
+                }
+            }
+        }
+        Changes { files }
+    }
+
+    /// Whether the diagnostic is in a changed file, and on a changed line if `lines_only` is set
+    pub fn contains(&self, diagnostic: &Diagnostic, lines_only: bool) -> bool {
+        let Some(lines) = self.files.get(&canonical(diagnostic.pos.file_name())) else {
+            return false;
+        };
+        match lines {
+            Some(lines) if lines_only => {
+                let range = diagnostic.pos.range;
+                lines
+                    .iter()
+                    .any(|lines| lines.start <= range.end.line && range.start.line < lines.end)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// The zero-based lines added by a hunk with the header `-a,b +c,d @@`
+fn added_lines(hunk: &str) -> Option<Range<u32>> {
+    let added = hunk.split(' ').find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match added.split_once(',') {
+        Some((start, count)) => (start.parse::<u32>().ok()?, count.parse::<u32>().ok()?),
+        None => (added.parse::<u32>().ok()?, 1),
+    };
+    if count == 0 {
+        None
+    } else {
+        Some(start - 1..start - 1 + count)
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
+
+fn git(directory: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(directory)
+        .output()
+        .map_err(|err| format!("Could not run git: {err}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::error_codes::ErrorCode;
+    use crate::syntax::test::Code;
+
+    #[test]
+    fn parses_added_lines_of_hunks() {
+        assert_eq!(added_lines("-3,2 +3,4 @@ entity ent is"), Some(2..6));
+        assert_eq!(added_lines("-3 +7 @@"), Some(6..7));
+        assert_eq!(added_lines("-3,2 +2,0 @@"), None);
+    }
+
+    #[test]
+    fn diagnostics_on_changed_lines() {
+        let code = Code::new("signal a : bit;\nsignal b : bit;\nsignal c : bit;");
+        let file_name = code.source().file_name();
+        let root = file_name.parent().unwrap();
+        let diff = format!(
+            "\
+diff --git {0} {0}
+--- {0}
++++ {0}
+@@ -2 +2 @@
+-signal x : bit;
++signal b : bit;
+diff --git deleted.vhd deleted.vhd
+--- deleted.vhd
++++ /dev/null
+@@ -1 +0,0 @@
+-entity deleted is end entity;
+",
+            file_name.file_name().unwrap().to_string_lossy()
+        );
+        let changes = Changes::from_diff(&diff, root);
+        let diagnostic = |name: &str| {
+            Diagnostic::new(code.s1(&format!("{name} :")), "Unused", ErrorCode::Unused)
+        };
+
+        assert!(changes.contains(&diagnostic("a"), false));
+        assert!(!changes.contains(&diagnostic("a"), true));
+        assert!(changes.contains(&diagnostic("b"), true));
+
+        let other = Code::new("signal a : bit;");
+        let diagnostic = Diagnostic::new(other.s1("a :"), "Unused", ErrorCode::Unused);
+        assert!(!changes.contains(&diagnostic, false));
+    }
+}
@@ -0,0 +1,318 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! The order in which external simulators must compile the files of a project,
+//! derived from the dependencies between design units that were found during analysis.
+//!
+//! Files of third-party libraries, such as `ieee`, are assumed to be precompiled
+//! by the simulator and are left out.
+
+use crate::analysis::{DesignRoot, Library};
+use crate::ast::json::Json;
+use crate::ast::{HasUnitId, UnitId};
+use crate::data::HasSrcPos;
+use crate::{Config, Source, VHDLStandard};
+use fnv::{FnvHashMap, FnvHashSet};
+use itertools::Itertools;
+use std::collections::BTreeSet;
+use std::io;
+use std::io::Write;
+use strum::{EnumString, IntoStaticStr};
+
+/// The format of a compile order.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum CompileOrderFormat {
+    /// One line per file with the library and the path of the file
+    Text,
+    /// An array of objects with the library, path and standard of each file
+    Json,
+    /// A shell script analyzing the files with GHDL
+    Ghdl,
+    /// A script for ModelSim and Questa
+    Modelsim,
+    /// A script for Riviera-PRO and Active-HDL
+    Riviera,
+}
+
+impl CompileOrderFormat {
+    /// The conventional name of the written file
+    pub fn default_file_name(&self) -> &'static str {
+        match self {
+            CompileOrderFormat::Text => "compile_order.txt",
+            CompileOrderFormat::Json => "compile_order.json",
+            CompileOrderFormat::Ghdl => "compile_ghdl.sh",
+            CompileOrderFormat::Modelsim => "compile_modelsim.do",
+            CompileOrderFormat::Riviera => "compile_riviera.do",
+        }
+    }
+}
+
+/// Compiling `source` into `library`.
+/// A file that is part of several libraries is compiled once for every library.
+#[derive(PartialEq, Debug, Clone)]
+pub struct CompileStep {
+    pub library: String,
+    pub source: Source,
+    pub standard: VHDLStandard,
+}
+
+pub(crate) fn compile_order(root: &DesignRoot, config: &Config) -> Vec<CompileStep> {
+    let libraries = root
+        .libraries()
+        .filter(|library| {
+            config
+                .get_library(&library.name().name_utf8())
+                .is_some_and(|library_config| !library_config.is_third_party)
+        })
+        .collect_vec();
+    compile_order_of_libraries(root, &libraries)
+}
+
+type StepKey = (String, Source);
+
+/// Sorts the files of `libraries` such that every file is compiled after the files it depends on.
+/// Files that do not depend on each other are sorted by library and path.
+/// Files that depend on each other in a cycle are compiled in the same order.
+fn compile_order_of_libraries(root: &DesignRoot, libraries: &[&Library]) -> Vec<CompileStep> {
+    let mut step_of_unit: FnvHashMap<&UnitId, StepKey> = FnvHashMap::default();
+    let mut standards: FnvHashMap<StepKey, VHDLStandard> = FnvHashMap::default();
+    for library in libraries {
+        for unit in library.units() {
+            let key = (library.name().name_utf8(), unit.pos().source.clone());
+            standards.insert(key.clone(), unit.standard);
+            step_of_unit.insert(unit.unit_id(), key);
+        }
+    }
+
+    let mut dependencies: FnvHashMap<&StepKey, FnvHashSet<&StepKey>> = standards
+        .keys()
+        .map(|key| (key, FnvHashSet::default()))
+        .collect();
+    let unit_dependencies = root.unit_dependencies();
+    for (user, used) in unit_dependencies.iter() {
+        if let (Some(user), Some(used)) = (step_of_unit.get(user), step_of_unit.get(used)) {
+            if user != used {
+                dependencies.get_mut(user).unwrap().insert(used);
+            }
+        }
+    }
+
+    let mut remaining: BTreeSet<&StepKey> = standards.keys().collect();
+    let mut order = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let next = remaining
+            .iter()
+            .find(|key| {
+                dependencies[**key]
+                    .iter()
+                    .all(|used| !remaining.contains(used))
+            })
+            .or_else(|| remaining.first())
+            .copied()
+            .unwrap();
+        remaining.remove(next);
+        order.push(CompileStep {
+            library: next.0.clone(),
+            source: next.1.clone(),
+            standard: standards[next],
+        });
+    }
+    order
+}
+
+/// Writes `steps` in `format`, where files are referred to by their path as configured
+pub fn write_compile_order(
+    steps: &[CompileStep],
+    format: CompileOrderFormat,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let path = |step: &CompileStep| step.source.file_name().to_string_lossy().into_owned();
+    match format {
+        CompileOrderFormat::Text => {
+            for step in steps {
+                writeln!(writer, "{} {}", step.library, path(step))?;
+            }
+        }
+        CompileOrderFormat::Json => {
+            let json = Json::Array(
+                steps
+                    .iter()
+                    .map(|step| {
+                        Json::Object(vec![
+                            ("library".to_owned(), Json::String(step.library.clone())),
+                            ("file".to_owned(), Json::String(path(step))),
+                            (
+                                "standard".to_owned(),
+                                Json::String(step.standard.as_ref().to_owned()),
+                            ),
+                        ])
+                    })
+                    .collect(),
+            );
+            writeln!(writer, "{json:#}")?;
+        }
+        CompileOrderFormat::Ghdl => {
+            writeln!(writer, "#!/bin/sh")?;
+            writeln!(writer, "set -e")?;
+            for step in steps {
+                writeln!(
+                    writer,
+                    "ghdl -a --std={} --work={} {}",
+                    ghdl_standard(step.standard),
+                    step.library,
+                    shell_quote(&path(step))
+                )?;
+            }
+        }
+        CompileOrderFormat::Modelsim | CompileOrderFormat::Riviera => {
+            for library in steps.iter().map(|step| &step.library).unique() {
+                writeln!(writer, "vlib {library}")?;
+                if format == CompileOrderFormat::Modelsim {
+                    writeln!(writer, "vmap {library} {library}")?;
+                }
+            }
+            for step in steps {
+                writeln!(
+                    writer,
+                    "vcom -{} -work {} {}",
+                    vcom_standard(step.standard),
+                    step.library,
+                    tcl_quote(&path(step))
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn ghdl_standard(standard: VHDLStandard) -> &'static str {
+    match standard {
+        VHDLStandard::VHDL1993 => "93c",
+        VHDLStandard::VHDL2008 => "08",
+        VHDLStandard::VHDL2019 => "19",
+    }
+}
+
+fn vcom_standard(standard: VHDLStandard) -> &'static str {
+    match standard {
+        VHDLStandard::VHDL1993 => "93",
+        VHDLStandard::VHDL2008 => "2008",
+        VHDLStandard::VHDL2019 => "2019",
+    }
+}
+
+fn shell_quote(path: &str) -> String {
+    if path
+        .chars()
+        .all(|chr| chr.is_ascii_alphanumeric() || "/._-+".contains(chr))
+    {
+        path.to_owned()
+    } else {
+        format!("'{}'", path.replace('\'', "'\\''"))
+    }
+}
+
+fn tcl_quote(path: &str) -> String {
+    if path
+        .chars()
+        .all(|chr| chr.is_ascii_alphanumeric() || "/._-+:".contains(chr))
+    {
+        path.to_owned()
+    } else {
+        format!("{{{path}}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::syntax::test::check_no_diagnostics;
+
+    #[test]
+    fn files_are_compiled_after_their_dependencies() {
+        let mut builder = LibraryBuilder::new();
+        let arch = builder.code(
+            "libname",
+            "
+architecture rtl of ent is
+begin
+end architecture;",
+        );
+        let ent = builder.code(
+            "libname",
+            "
+library other;
+use other.pkg.all;
+entity ent is
+end entity;",
+        );
+        let pkg = builder.code(
+            "other",
+            "
+package pkg is
+end package;",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let libraries = root
+            .libraries()
+            .filter(|library| ["libname", "other"].contains(&library.name().name_utf8().as_str()))
+            .collect_vec();
+        let order = compile_order_of_libraries(&root, &libraries);
+        assert_eq!(
+            order
+                .iter()
+                .map(|step| (step.library.as_str(), &step.source))
+                .collect_vec(),
+            vec![
+                ("other", pkg.source()),
+                ("libname", ent.source()),
+                ("libname", arch.source())
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_simulator_scripts() {
+        let code = crate::syntax::test::Code::new("");
+        let steps = vec![CompileStep {
+            library: "lib".to_owned(),
+            source: code.source().clone(),
+            standard: VHDLStandard::VHDL2008,
+        }];
+        let path = code.source().file_name().to_string_lossy().into_owned();
+
+        let mut ghdl = Vec::new();
+        write_compile_order(&steps, CompileOrderFormat::Ghdl, &mut ghdl).unwrap();
+        assert_eq!(
+            String::from_utf8(ghdl).unwrap(),
+            format!(
+                "#!/bin/sh\nset -e\nghdl -a --std=08 --work=lib {}\n",
+                shell_quote(&path)
+            )
+        );
+
+        let mut modelsim = Vec::new();
+        write_compile_order(&steps, CompileOrderFormat::Modelsim, &mut modelsim).unwrap();
+        assert_eq!(
+            String::from_utf8(modelsim).unwrap(),
+            format!(
+                "vlib lib\nvmap lib lib\nvcom -2008 -work lib {}\n",
+                tcl_quote(&path)
+            )
+        );
+    }
+
+    #[test]
+    fn quotes_paths() {
+        assert_eq!(shell_quote("src/my_file.vhd"), "src/my_file.vhd");
+        assert_eq!(shell_quote("my dir/it's.vhd"), "'my dir/it'\\''s.vhd'");
+        assert_eq!(tcl_quote("C:/my dir/file.vhd"), "{C:/my dir/file.vhd}");
+    }
+}
@@ -10,12 +10,14 @@ use crate::completion::attributes::completions_for_attribute_name;
 use crate::completion::generic::generic_completions;
 use crate::completion::libraries::list_all_libraries;
 use crate::completion::map_aspect::completions_for_map_aspect;
-use crate::completion::selected::completions_for_selected_name;
+use crate::completion::selected::{completions_for_selected_name, list_primaries_for_lib};
 use crate::completion::tokenizer::tokenize_input;
 use crate::syntax::Kind;
-use crate::{EntRef, Position, Source};
+use crate::syntax::Value;
+use crate::{EntRef, Position, Source, TextEdit};
 
 mod attributes;
+mod auto_import;
 mod entity_instantiation;
 mod generic;
 mod libraries;
@@ -61,6 +63,9 @@ pub enum CompletionItem<'a> {
     Instantiation(EntRef<'a>, Vec<EntRef<'a>>),
     /// Complete an attribute designator (i.e. `'range`, `'stable`, ...)
     Attribute(AttributeDesignator),
+    /// Complete an entity that is not visible at the cursor position.
+    /// The edits add the `library` and `use` clauses that make the entity visible.
+    AutoImport(EntRef<'a>, Vec<TextEdit>),
 }
 
 macro_rules! kind {
@@ -86,6 +91,8 @@ pub fn list_completion_options<'a>(
         [.., kind!(SemiColon)] => vec![],
         [.., kind!(Library)]
         | [.., kind!(Library), kind!(Identifier)]
+        | [.., kind!(Library), kind!(Identifier), kind!(Comma)]
+        | [.., kind!(Library), kind!(Identifier), kind!(Comma), kind!(Identifier)]
         | [.., kind!(Use)]
         | [.., kind!(Use), kind!(Identifier)] => list_all_libraries(root),
         [.., token, kind!(Dot)] | [.., token, kind!(Dot), kind!(Identifier)] => {
@@ -94,6 +101,10 @@ pub fn list_completion_options<'a>(
             // Otherwise, we just return an empty value.
             if let Some((_, ent)) = root.item_at_cursor(source, token.pos.start()) {
                 completions_for_selected_name(root, ent)
+            } else if let Value::Identifier(sym) = &token.value {
+                // The library might not be visible (yet), e.g. when the library clause
+                // is still missing. Fall back to the libraries of the project.
+                list_primaries_for_lib(root, sym)
             } else {
                 vec![]
             }
@@ -108,6 +119,13 @@ pub fn list_completion_options<'a>(
         [.., kind!(LeftPar | Comma)] | [.., kind!(LeftPar | Comma), kind!(Identifier)] => {
             completions_for_map_aspect(root, cursor, source)
         }
-        _ => generic_completions(root, cursor, source),
+        [.., token @ kind!(Identifier)] => {
+            let prefix = match &token.value {
+                Value::Identifier(sym) => Some(sym.name_utf8()),
+                _ => None,
+            };
+            generic_completions(root, cursor, source, prefix.as_deref())
+        }
+        _ => generic_completions(root, cursor, source, None),
     }
 }
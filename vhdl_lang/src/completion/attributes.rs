@@ -21,6 +21,9 @@ pub(crate) fn completions_for_attribute_name(ent: EntRef<'_>) -> Vec<CompletionI
     match ent.kind() {
         AnyEntKind::Type(typ) => extend_attributes_of_type(typ, &mut attributes),
         AnyEntKind::Object(obj) => extend_attributes_of_objects(obj, &mut attributes),
+        AnyEntKind::ObjectAlias { base_object, .. } => {
+            extend_attributes_of_objects(base_object.object(), &mut attributes)
+        }
         AnyEntKind::View(_) => attributes.push(AttributeDesignator::Converse),
         _ => {}
     }
@@ -186,4 +189,39 @@ end package;
 
         assert_eq_unordered(&options, &expected_options);
     }
+
+    #[test]
+    pub fn completes_attributes_of_object_alias() {
+        use crate::ast::AttributeDesignator::*;
+        use crate::ast::SignalAttribute::*;
+
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libA",
+            "\
+package my_pkg is
+    signal foo : BIT := '0';
+    alias foo_alias is foo;
+    constant bar: BOOLEAN := foo_alias'
+end package;
+",
+        );
+
+        let (root, _) = builder.get_analyzed_root();
+        let cursor = code.s1("foo_alias'").end();
+        let options = list_completion_options(&root, code.source(), cursor);
+
+        assert!(options.contains(&CompletionItem::Attribute(Signal(Event))));
+        assert!(options.contains(&CompletionItem::Attribute(Pos)));
+    }
+
+    #[test]
+    pub fn predefined_attributes_have_documentation() {
+        use crate::ast::AttributeDesignator::*;
+        use crate::ast::SignalAttribute::*;
+
+        assert!(Signal(Event).documentation().is_some());
+        assert!(Length.documentation().is_some());
+        assert!(Range(RangeAttribute::Range).documentation().is_some());
+    }
 }
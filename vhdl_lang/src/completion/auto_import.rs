@@ -0,0 +1,286 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::{ContextClause, Designator};
+use crate::named_entity::DesignEnt;
+use crate::{
+    AnyEntKind, CompletionItem, Design, HasEntityId, HasTokenSpan, Position, Range, TextEdit,
+    TokenAccess,
+};
+use fnv::FnvHashSet;
+
+/// The position where new context items are inserted into a design unit.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ContextInsertion {
+    pos: Position,
+    /// Whether the position is at the end of an existing context item.
+    after_context_item: bool,
+}
+
+impl ContextInsertion {
+    /// New context items are added after the last context item of the unit
+    /// or before the start of the unit if it has no context clause.
+    pub fn new(
+        ctx: &dyn TokenAccess,
+        context_clause: &ContextClause,
+        unit: &impl HasTokenSpan,
+    ) -> ContextInsertion {
+        match context_clause.last() {
+            Some(item) => ContextInsertion {
+                pos: item.get_pos(ctx).end(),
+                after_context_item: true,
+            },
+            None => ContextInsertion {
+                pos: unit.get_pos(ctx).start(),
+                after_context_item: false,
+            },
+        }
+    }
+
+    fn edit(&self, clauses: &[String]) -> TextEdit {
+        let text: String = if self.after_context_item {
+            clauses.iter().map(|clause| format!("\n{clause}")).collect()
+        } else {
+            clauses.iter().map(|clause| format!("{clause}\n")).collect()
+        };
+        TextEdit::new(Range::new(self.pos, self.pos), text)
+    }
+}
+
+/// Produces completions for primary units and package declarations
+/// that are not visible within `unit` but whose name starts with `prefix`.
+/// Each completion carries the edit that adds the required `library` and `use` clauses.
+pub(crate) fn auto_import_completions<'a>(
+    root: &'a DesignRoot,
+    unit: DesignEnt<'a>,
+    insertion: ContextInsertion,
+    visible: &[CompletionItem<'a>],
+    prefix: &str,
+) -> Vec<CompletionItem<'a>> {
+    let prefix = prefix.to_lowercase();
+    let matches_prefix = |designator: &Designator| {
+        matches!(designator, Designator::Identifier(_))
+            && designator.to_string().to_lowercase().starts_with(&prefix)
+    };
+
+    let mut visible_designators = FnvHashSet::default();
+    let mut visible_libraries = FnvHashSet::default();
+    for item in visible {
+        match item {
+            CompletionItem::Simple(ent)
+            | CompletionItem::Formal(ent)
+            | CompletionItem::Instantiation(ent, _) => {
+                if let AnyEntKind::Library = ent.kind() {
+                    visible_libraries.extend(ent.library_name().cloned());
+                }
+                visible_designators.insert(ent.designator().clone());
+            }
+            CompletionItem::Overloaded(designator, _) => {
+                visible_designators.insert(designator.clone());
+            }
+            _ => {}
+        }
+    }
+
+    let Some(work_name) = unit.library_name() else {
+        return vec![];
+    };
+    let primary_name = match unit.kind() {
+        Design::Architecture(_, _, entity) => entity.designator(),
+        _ => unit.designator(),
+    };
+
+    let mut completions = Vec::new();
+    for library in root.libraries() {
+        let is_work = library.name() == work_name;
+        let library_clause = (!is_work
+            && library.name().name_utf8() != "std"
+            && !visible_libraries.contains(library.name()))
+        .then(|| format!("library {};", library.name()));
+        let library_prefix = if is_work {
+            "work".to_owned()
+        } else {
+            library.name().to_string()
+        };
+
+        for primary in library
+            .primary_units()
+            .filter_map(|unit| unit.unit.get().and_then(|unit| unit.ent_id()))
+            .map(|id| root.get_ent(id))
+        {
+            if is_work && primary.designator() == primary_name {
+                continue;
+            }
+            let import = |use_clause: String| {
+                let clauses: Vec<String> =
+                    library_clause.iter().cloned().chain([use_clause]).collect();
+                vec![insertion.edit(&clauses)]
+            };
+
+            match primary.kind() {
+                AnyEntKind::Design(Design::Entity(..)) => {}
+                AnyEntKind::Design(Design::Package(_, region)) => {
+                    let use_clause = format!("use {library_prefix}.{}.all;", primary.designator());
+                    for ent in region
+                        .entities
+                        .values()
+                        .map(|entities| entities.first())
+                        .filter(|ent| !ent.is_implicit())
+                    {
+                        if !visible_designators.contains(ent.designator())
+                            && matches_prefix(ent.designator())
+                        {
+                            completions
+                                .push(CompletionItem::AutoImport(ent, import(use_clause.clone())));
+                        }
+                    }
+                }
+                _ => continue,
+            }
+
+            if !visible_designators.contains(primary.designator())
+                && matches_prefix(primary.designator())
+            {
+                completions.push(CompletionItem::AutoImport(
+                    primary,
+                    import(format!("use {library_prefix}.{};", primary.designator())),
+                ));
+            }
+        }
+    }
+    completions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::{list_completion_options, CompletionItem, Position, Range, TextEdit};
+
+    fn auto_imports<'a>(options: &[CompletionItem<'a>]) -> Vec<(String, Vec<TextEdit>)> {
+        options
+            .iter()
+            .filter_map(|item| match item {
+                CompletionItem::AutoImport(ent, edits) => {
+                    Some((ent.designator().to_string(), edits.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn completes_declarations_of_packages_that_are_not_visible() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libb",
+            "\
+package pkg is
+    constant my_constant : natural := 0;
+end package;",
+        );
+        let code = builder.code(
+            "liba",
+            "\
+package other_pkg is
+    constant my_other_constant : natural := 0;
+end package;
+
+entity ent is
+end entity;
+
+library ieee;
+
+architecture arch of ent is
+    constant c : natural := my
+begin
+end architecture;",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        let cursor = code.s1(":= my").end();
+        let options = list_completion_options(&root, code.source(), cursor);
+
+        let insert_pos = code.s1("library ieee;").end();
+        let insertion = |text: &str| {
+            vec![TextEdit::new(
+                Range::new(insert_pos, insert_pos),
+                text.to_owned(),
+            )]
+        };
+        let mut imports = auto_imports(&options);
+        imports.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            imports,
+            vec![
+                (
+                    "my_constant".to_owned(),
+                    insertion("\nlibrary libb;\nuse libb.pkg.all;")
+                ),
+                (
+                    "my_other_constant".to_owned(),
+                    insertion("\nuse work.other_pkg.all;")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn inserts_before_unit_without_context_clause() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libname",
+            "\
+package pkg is
+    constant my_constant : natural := 0;
+end package;",
+        );
+        let code = builder.code(
+            "libname",
+            "\
+package other_pkg is
+    constant c : natural := my
+end package;",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        let cursor = code.s1(":= my").end();
+        let options = list_completion_options(&root, code.source(), cursor);
+        assert_eq!(
+            auto_imports(&options),
+            vec![(
+                "my_constant".to_owned(),
+                vec![TextEdit::new(
+                    Range::new(Position::new(0, 0), Position::new(0, 0)),
+                    "use work.pkg.all;\n"
+                )]
+            )]
+        );
+    }
+
+    #[test]
+    fn does_not_complete_visible_declarations() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libname",
+            "\
+package pkg is
+    constant my_constant : natural := 0;
+end package;",
+        );
+        let code = builder.code(
+            "libname",
+            "\
+use work.pkg.all;
+
+package other_pkg is
+    constant c : natural := my
+end package;",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        let cursor = code.s1(":= my").end();
+        let options = list_completion_options(&root, code.source(), cursor);
+        assert!(auto_imports(&options).is_empty());
+    }
+}
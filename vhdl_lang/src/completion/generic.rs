@@ -7,6 +7,7 @@ use crate::ast::search::{
     DeclarationItem, Finished, Found, FoundDeclaration, NotFinished, SearchState, Searcher,
 };
 use crate::ast::ArchitectureBody;
+use crate::completion::auto_import::{auto_import_completions, ContextInsertion};
 use crate::completion::entity_instantiation::get_visible_entities_from_architecture;
 use crate::completion::region::completion_items_from_region;
 use crate::named_entity::{DesignEnt, Visibility};
@@ -14,14 +15,23 @@ use crate::{CompletionItem, Design, HasTokenSpan, Position, Source, TokenAccess}
 use itertools::{chain, Itertools};
 use vhdl_lang::analysis::DesignRoot;
 
+/// Produces all completions visible at the cursor.
+/// If a `prefix` is typed at the cursor, completions for items that are not visible,
+/// but would become visible by adding a context item, are added as well.
 pub(crate) fn generic_completions<'a>(
     root: &'a DesignRoot,
     cursor: Position,
     source: &Source,
+    prefix: Option<&str>,
 ) -> Vec<CompletionItem<'a>> {
     let mut searcher = CompletionSearcher::new(cursor, root);
     let _ = root.search_source(source, &mut searcher);
-    searcher.completions
+    let mut completions = searcher.completions;
+    if let (Some(prefix), Some((unit, insertion))) = (prefix, searcher.unit) {
+        let auto_imports = auto_import_completions(root, unit, insertion, &completions, prefix);
+        completions.extend(auto_imports);
+    }
+    completions
 }
 
 /// This is the most general-purpose completion provider.
@@ -33,6 +43,8 @@ struct CompletionSearcher<'a> {
     root: &'a DesignRoot,
     cursor: Position,
     completions: Vec<CompletionItem<'a>>,
+    /// The design unit at the cursor and where to add context items to it
+    unit: Option<(DesignEnt<'a>, ContextInsertion)>,
 }
 
 impl<'a> CompletionSearcher<'a> {
@@ -41,6 +53,7 @@ impl<'a> CompletionSearcher<'a> {
             root: design_root,
             cursor,
             completions: Vec::new(),
+            unit: None,
         }
     }
 }
@@ -61,12 +74,15 @@ impl<'a> CompletionSearcher<'a> {
 
 impl<'a> Searcher for CompletionSearcher<'a> {
     fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
-        let ent_id = match &decl.ast {
+        let (ent_id, insertion) = match &decl.ast {
             DeclarationItem::Entity(ent_decl) => {
                 if !ent_decl.get_pos(ctx).contains(self.cursor) {
                     return NotFinished;
                 }
-                ent_decl.ident.decl.get()
+                (
+                    ent_decl.ident.decl.get(),
+                    ContextInsertion::new(ctx, &ent_decl.context_clause, *ent_decl),
+                )
             }
             DeclarationItem::Architecture(body) => {
                 if !body.get_pos(ctx).contains(self.cursor) {
@@ -75,19 +91,28 @@ impl<'a> Searcher for CompletionSearcher<'a> {
                 if body.statement_span().get_pos(ctx).contains(self.cursor) {
                     self.add_entity_instantiations(body);
                 }
-                body.ident.decl.get()
+                (
+                    body.ident.decl.get(),
+                    ContextInsertion::new(ctx, &body.context_clause, *body),
+                )
             }
             DeclarationItem::Package(package) => {
                 if !package.get_pos(ctx).contains(self.cursor) {
                     return NotFinished;
                 }
-                package.ident.decl.get()
+                (
+                    package.ident.decl.get(),
+                    ContextInsertion::new(ctx, &package.context_clause, *package),
+                )
             }
             DeclarationItem::PackageBody(package) => {
                 if !package.get_pos(ctx).contains(self.cursor) {
                     return NotFinished;
                 }
-                package.ident.decl.get()
+                (
+                    package.ident.decl.get(),
+                    ContextInsertion::new(ctx, &package.context_clause, *package),
+                )
             }
             DeclarationItem::Subprogram(subprogram) => {
                 if !subprogram.get_pos(ctx).contains(self.cursor) {
@@ -112,6 +137,7 @@ impl<'a> Searcher for CompletionSearcher<'a> {
         };
         self.completions
             .extend(visible_entities_from(self.root, ent.kind()));
+        self.unit = Some((ent, insertion));
         NotFinished
     }
 }
@@ -126,7 +152,8 @@ fn visible_entities_from<'a>(
         | UninstPackage(visibility, region)
         | Architecture(visibility, region, _)
         | Package(visibility, region)
-        | PackageBody(visibility, region) => chain(
+        | PackageBody(visibility, region)
+        | VerificationUnit(visibility, region) => chain(
             completion_items_from_region(root, region),
             completion_items_from_visibility(root, visibility),
         )
@@ -13,6 +13,7 @@ use crate::{
     named_entity, AnyEntKind, CompletionItem, Design, EntityId, HasTokenSpan, Overloaded, Position,
     Source, TokenAccess,
 };
+use itertools::Itertools;
 use std::collections::HashSet;
 
 /// Produces completions for the left hand side of a map aspect, i.e.,
@@ -128,11 +129,14 @@ enum MapAspectKind {
 
 /// From this region, extracts those `AnyEntKind::Object`s where the class of the
 /// object matches the specified class.
+/// The objects are returned in declaration order.
 fn extract_objects_with_class(region: &Region<'_>, object_class: ObjectClass) -> Vec<EntityId> {
     region
         .entities
         .values()
         .filter_map(|ent| ent.as_unique())
+        // Sorting by source file position gives declaration order
+        .sorted_by_key(|ent| ent.decl_pos().map(|pos| pos.range().start))
         .filter_map(|ent| match &ent.kind {
             AnyEntKind::Object(obj) if obj.class == object_class => Some(ent.id),
             AnyEntKind::Overloaded(Overloaded::InterfaceSubprogram(_))
@@ -350,4 +354,48 @@ end arch;
         assert!(options.contains(&CompletionItem::Simple(ent1)));
         assert!(options.contains(&CompletionItem::Simple(ent2)));
     }
+
+    #[test]
+    pub fn completes_unassociated_formals_in_declaration_order() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+entity child is
+    port (
+        d : in bit;
+        a : in bit;
+        c : out bit;
+        b : out bit
+    );
+end entity;
+
+entity parent is
+end entity;
+
+architecture arch of parent is
+    signal sig : bit;
+begin
+    inst: entity work.child
+    port map (
+        a => sig,
+    );
+end architecture;
+",
+        );
+
+        let (root, _) = builder.get_analyzed_root();
+        let formal = |name: &str| {
+            CompletionItem::Formal(
+                root.search_reference(code.source(), code.s1(name).start())
+                    .unwrap(),
+            )
+        };
+        let cursor = code.s1("a => sig,").end();
+        let options = list_completion_options(&root, code.source(), cursor);
+        assert_eq!(
+            options,
+            vec![formal("d : in"), formal("c : out"), formal("b : out")]
+        );
+    }
 }
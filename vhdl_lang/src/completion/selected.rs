@@ -71,7 +71,10 @@ fn completions_for_design<'a>(
 
 /// List the name of all primary units for a given library.
 /// If the library is non-resolvable, list an empty vector
-fn list_primaries_for_lib<'a>(root: &'a DesignRoot, lib: &Symbol) -> Vec<CompletionItem<'a>> {
+pub(crate) fn list_primaries_for_lib<'a>(
+    root: &'a DesignRoot,
+    lib: &Symbol,
+) -> Vec<CompletionItem<'a>> {
     let Some(lib) = root.get_lib(lib) else {
         return vec![];
     };
@@ -86,7 +89,7 @@ mod tests {
     use crate::analysis::tests::{assert_eq_unordered, LibraryBuilder};
     use crate::ast::Designator;
     use crate::syntax::Kind::All;
-    use crate::{list_completion_options, CompletionItem};
+    use crate::{list_completion_options, CompletionItem, HasEntityId};
 
     #[test]
     pub fn completes_selected_names() {
@@ -174,6 +177,38 @@ end package x;
         );
     }
 
+    #[test]
+    pub fn completing_primaries_of_library_without_library_clause() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libb",
+            "\
+package pkg is
+end package pkg;
+",
+        );
+        let code = builder.code(
+            "liba",
+            "\
+use libb.
+
+package x is
+end package x;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        let cursor = code.s1("use libb.").end();
+        let options = list_completion_options(&root, code.source(), cursor);
+        let pkg = root
+            .get_lib(&root.symbol_utf8("libb"))
+            .unwrap()
+            .primary_units()
+            .find_map(|unit| unit.unit.get().and_then(|unit| unit.ent_id()))
+            .map(|id| root.get_ent(id))
+            .unwrap();
+        assert_eq_unordered(&options, &[CompletionItem::Simple(pkg)]);
+    }
+
     #[test]
     pub fn completing_declarations() {
         let mut input = LibraryBuilder::new();
@@ -201,4 +236,43 @@ end package x;
             ],
         );
     }
+
+    #[test]
+    pub fn completes_declarations_of_package_instances() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+package gpkg is
+    generic (
+        type type_t;
+        value : natural);
+    constant width : natural := value;
+    subtype elem_t is type_t;
+end package;
+
+package pkg is
+    package ipkg is new work.gpkg generic map (type_t => bit, value => 1);
+    constant z : natural := ipkg.
+end package;
+        ",
+        );
+
+        let (root, _) = builder.get_analyzed_root();
+        let cursor = code.s1("ipkg.").end();
+        let options = list_completion_options(&root, code.source(), cursor);
+        let designators: Vec<_> = options
+            .iter()
+            .filter_map(|option| match option {
+                CompletionItem::Simple(ent)
+                    if matches!(ent.designator(), Designator::Identifier(_)) =>
+                {
+                    Some(ent.designator().to_string())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq_unordered(&designators, &["width".to_owned(), "elem_t".to_owned()]);
+        assert!(options.contains(&CompletionItem::Keyword(All)));
+    }
 }
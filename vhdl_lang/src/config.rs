@@ -12,13 +12,14 @@ use std::io;
 use std::io::prelude::*;
 use std::path::Path;
 
-use fnv::FnvHashMap;
-use itertools::Itertools;
+use fnv::{FnvHashMap, FnvHashSet};
 use subst::VariableMap;
 use toml::{Table, Value};
 
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
+use crate::formatting::FormattingOptions;
+use crate::lint::banned::BanList;
 use crate::standard::VHDLStandard;
 
 #[derive(Clone, PartialEq, Eq, Default, Debug)]
@@ -28,6 +29,72 @@ pub struct Config {
     standard: VHDLStandard,
     // Defines the severity that diagnostics are displayed with
     severities: SeverityMap,
+    // The constructs and packages that are not allowed
+    ban_list: BanList,
+    // The entities at the top of the design hierarchy
+    top_levels: Vec<TopLevel>,
+    // The values of user-defined conditional analysis identifiers, keyed by their upper case name
+    conditional_identifiers: FnvHashMap<String, String>,
+    // The style of files formatted by the format command
+    formatting: FormattingOptions,
+    // The configuration file of the standard libraries, replacing the libraries shipped with vhdl_ls
+    standard_libraries: Option<PathBuf>,
+    // The top-level keys of the configuration file, all other settings have their default value
+    keys: FnvHashSet<String>,
+}
+
+/// An entity at the top of the design hierarchy,
+/// written as `library.entity` or `library.entity(architecture)`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TopLevel {
+    pub library: String,
+    pub entity: String,
+    pub architecture: Option<String>,
+}
+
+impl TopLevel {
+    fn parse(name: &str) -> Option<TopLevel> {
+        let (name, architecture) = match name.trim().split_once('(') {
+            Some((name, rest)) => (name, Some(rest.strip_suffix(')')?.trim())),
+            None => (name, None),
+        };
+        let (library, entity) = name.trim().split_once('.')?;
+        let is_identifier =
+            |name: &str| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if !is_identifier(library)
+            || !is_identifier(entity)
+            || !architecture.is_none_or(is_identifier)
+        {
+            return None;
+        }
+        Some(TopLevel {
+            library: library.to_owned(),
+            entity: entity.to_owned(),
+            architecture: architecture.map(|name| name.to_owned()),
+        })
+    }
+}
+
+impl std::fmt::Display for TopLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.library, self.entity)?;
+        if let Some(architecture) = &self.architecture {
+            write!(f, "({architecture})")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for TopLevel {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<TopLevel, String> {
+        TopLevel::parse(name).ok_or_else(|| {
+            format!(
+                "Expected top level '{name}' to be of the form library.entity or library.entity(architecture)"
+            )
+        })
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Default, Debug)]
@@ -35,6 +102,94 @@ pub struct LibraryConfig {
     name: String,
     patterns: Vec<String>,
     pub(crate) is_third_party: bool,
+    pub(crate) is_synthesizable: bool,
+    /// The synthesis target ignores initial values of signals, such as most ASIC technologies
+    pub(crate) ignores_initial_values: bool,
+    pub(crate) is_testbench: bool,
+    /// The VHDL standard that the library is analyzed with, overriding the standard of the project
+    pub(crate) standard: Option<VHDLStandard>,
+    /// Files listing one file of the library per line, such as the `.f` files of simulators
+    file_lists: Vec<PathBuf>,
+    /// The standards of the files matched by a pattern, overriding the standard of the library
+    file_standards: FnvHashMap<String, VHDLStandard>,
+}
+
+/// Adds the files matching `pattern` to `result`
+fn add_matching_files(pattern: &str, result: &mut Vec<PathBuf>, messages: &mut dyn MessageHandler) {
+    let stripped_pattern = if cfg!(windows) {
+        pattern.strip_prefix("\\\\?\\").unwrap_or(pattern)
+    } else {
+        pattern
+    };
+
+    if is_literal(stripped_pattern) {
+        let file_path = PathBuf::from(pattern);
+
+        if file_path.exists() {
+            result.push(file_path);
+        } else {
+            messages.push(Message::warning(format! {"File {pattern} does not exist"}));
+        }
+    } else {
+        match glob::glob(stripped_pattern) {
+            Ok(paths) => {
+                let mut empty_pattern = true;
+
+                for file_path_or_error in paths {
+                    empty_pattern = false;
+                    match file_path_or_error {
+                        Ok(file_path) => {
+                            result.push(file_path);
+                        }
+                        Err(err) => {
+                            messages.push(Message::error(err.to_string()));
+                        }
+                    }
+                }
+
+                if empty_pattern {
+                    messages.push(Message::warning(format!(
+                        "Pattern '{stripped_pattern}' did not match any file"
+                    )));
+                }
+            }
+            Err(err) => {
+                messages.push(Message::error(format!("Invalid pattern '{pattern}' {err}")));
+            }
+        }
+    }
+}
+
+/// Reads the file patterns of a file list, relative to the directory of the file list.
+/// Empty lines, comments starting with `#` or `//` and tool options starting with `-` or `+`
+/// are ignored. Environment variables are substituted like in `vhdl_ls.toml`.
+fn read_file_list(file_list: &Path, messages: &mut dyn MessageHandler) -> Vec<String> {
+    let contents = match std::fs::read_to_string(file_list) {
+        Ok(contents) => contents,
+        Err(err) => {
+            messages.push(Message::warning(format!(
+                "Could not read file list {}: {err}",
+                file_list.to_string_lossy()
+            )));
+            return Vec::new();
+        }
+    };
+    let parent = file_list.parent().unwrap_or(Path::new(""));
+    let mut patterns = Vec::new();
+    for line in contents.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with(['#', '-', '+']) {
+            continue;
+        }
+        match substitute_environment_variables(line, &subst::Env) {
+            Ok(file) => patterns.push(parent.join(file).to_string_lossy().into_owned()),
+            Err(err) => messages.push(Message::error(format!(
+                "Error in file list {}: {err}",
+                file_list.to_string_lossy()
+            ))),
+        }
+    }
+    patterns
 }
 
 impl LibraryConfig {
@@ -42,64 +197,78 @@ impl LibraryConfig {
     /// Only include files that exists
     /// Files that do not exist produce a warning message
     pub fn file_names(&self, messages: &mut dyn MessageHandler) -> Vec<PathBuf> {
-        let mut result = Vec::new();
-        for pattern in self.patterns.iter() {
-            let stripped_pattern = if cfg!(windows) {
-                pattern.strip_prefix("\\\\?\\").unwrap_or(pattern.as_str())
-            } else {
-                pattern.as_str()
-            };
-
-            if is_literal(stripped_pattern) {
-                let file_path = PathBuf::from(pattern);
-
-                if file_path.exists() {
-                    result.push(file_path);
-                } else {
-                    messages.push(Message::warning(format! {"File {pattern} does not exist"}));
-                }
-            } else {
-                match glob::glob(stripped_pattern) {
-                    Ok(paths) => {
-                        let mut empty_pattern = true;
-
-                        for file_path_or_error in paths {
-                            empty_pattern = false;
-                            match file_path_or_error {
-                                Ok(file_path) => {
-                                    result.push(file_path);
-                                }
-                                Err(err) => {
-                                    messages.push(Message::error(err.to_string()));
-                                }
-                            }
-                        }
+        self.file_names_with_standards(messages)
+            .into_iter()
+            .map(|(file_name, _)| file_name)
+            .collect()
+    }
 
-                        if empty_pattern {
-                            messages.push(Message::warning(format!(
-                                "Pattern '{stripped_pattern}' did not match any file"
-                            )));
-                        }
-                    }
-                    Err(err) => {
-                        messages.push(Message::error(format!("Invalid pattern '{pattern}' {err}")));
+    /// Like [LibraryConfig::file_names], together with the standard of files that are analyzed
+    /// with another standard than the library.
+    /// A file that is matched by several patterns has the standard of any of them that defines one
+    pub(crate) fn file_names_with_standards(
+        &self,
+        messages: &mut dyn MessageHandler,
+    ) -> Vec<(PathBuf, Option<VHDLStandard>)> {
+        let mut result: Vec<(PathBuf, Option<VHDLStandard>)> = Vec::new();
+        let mut indices: FnvHashMap<PathBuf, usize> = FnvHashMap::default();
+        let mut add_files = |files: Vec<PathBuf>, standard: Option<VHDLStandard>| {
+            // Remove duplicate file names from the result
+            for file_name in files {
+                match indices.get(&file_name) {
+                    Some(&index) => result[index].1 = result[index].1.or(standard),
+                    None => {
+                        indices.insert(file_name.clone(), result.len());
+                        result.push((file_name, standard));
                     }
                 }
             }
+        };
+        for pattern in self.patterns.iter() {
+            let mut files = Vec::new();
+            add_matching_files(pattern, &mut files, messages);
+            add_files(files, self.file_standards.get(pattern).copied());
         }
-        // Remove duplicate file names from the result
-        result.into_iter().unique().collect()
+        for file_list in self.file_lists.iter() {
+            for pattern in read_file_list(file_list, messages) {
+                let mut files = Vec::new();
+                add_matching_files(&pattern, &mut files, messages);
+                add_files(files, None);
+            }
+        }
+        result
     }
 
     /// Returns the name of the library
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
+
+    /// Adds the files of another definition of the library.
+    /// The options of the library are those of this definition.
+    fn merge(&mut self, other: &LibraryConfig) {
+        for pattern in other.patterns.iter() {
+            if !self.patterns.contains(pattern) {
+                self.patterns.push(pattern.clone());
+            }
+        }
+        for file_list in other.file_lists.iter() {
+            if !self.file_lists.contains(file_list) {
+                self.file_lists.push(file_list.clone());
+            }
+        }
+        for (pattern, standard) in other.file_standards.iter() {
+            self.file_standards
+                .entry(pattern.clone())
+                .or_insert(*standard);
+        }
+    }
 }
 
 impl Config {
     pub fn from_str(string: &str, parent: &Path) -> Result<Config, String> {
-        let config = string.parse::<Value>().map_err(|err| err.to_string())?;
+        let mut config = string.parse::<Table>().map_err(|err| err.to_string())?;
+        resolve_includes(&mut config, parent, &mut Vec::new())?;
         let mut libraries = FnvHashMap::default();
 
         let standard = if let Some(std) = config.get("standard") {
@@ -124,17 +293,61 @@ impl Config {
                 ));
             }
 
-            let file_arr = lib
-                .get("files")
-                .ok_or_else(|| format!("missing field files for library {name}"))?
-                .as_array()
-                .ok_or_else(|| format!("files for library {name} is not array"))?;
+            let mut file_lists = Vec::new();
+            if let Some(lists) = lib.get("filelists") {
+                let lists = lists
+                    .as_array()
+                    .ok_or_else(|| format!("filelists for library {name} is not array"))?;
+                for list in lists.iter() {
+                    let list = list
+                        .as_str()
+                        .ok_or_else(|| format!("not a string {list}"))?;
+                    file_lists
+                        .push(parent.join(substitute_environment_variables(list, &subst::Env)?));
+                }
+            }
+
+            let file_arr = match lib.get("files") {
+                Some(files) => files
+                    .as_array()
+                    .ok_or_else(|| format!("files for library {name} is not array"))?
+                    .as_slice(),
+                None if !file_lists.is_empty() => &[],
+                None => return Err(format!("missing field files for library {name}")),
+            };
 
             let mut patterns = Vec::new();
+            let mut file_standards = FnvHashMap::default();
             for file in file_arr.iter() {
-                let file = file
-                    .as_str()
-                    .ok_or_else(|| format!("not a string {file}"))?;
+                // Either a pattern, or a table of a pattern with the options of its files
+                let (file, file_standard) = match file {
+                    Value::Table(options) => {
+                        let path = options
+                            .get("path")
+                            .and_then(|path| path.as_str())
+                            .ok_or_else(|| {
+                                format!("Expected path to be a string for a file of library {name}")
+                            })?;
+                        let standard = match options.get("standard") {
+                            Some(standard) => {
+                                let std_str = standard.as_str().ok_or_else(|| {
+                                    format!("Expected standard to be a string for file {path}")
+                                })?;
+                                Some(
+                                    VHDLStandard::try_from(std_str)
+                                        .map_err(|_| format!("Unsupported standard '{std_str}'"))?,
+                                )
+                            }
+                            None => None,
+                        };
+                        (path, standard)
+                    }
+                    file => (
+                        file.as_str()
+                            .ok_or_else(|| format!("not a string {file}"))?,
+                        None,
+                    ),
+                };
 
                 let file = substitute_environment_variables(file, &subst::Env)?;
 
@@ -143,6 +356,9 @@ impl Config {
                     .to_str()
                     .ok_or_else(|| format!("Could not convert {path:?} to string"))?
                     .to_owned();
+                if let Some(standard) = file_standard {
+                    file_standards.insert(path.clone(), standard);
+                }
                 patterns.push(path);
             }
 
@@ -157,12 +373,62 @@ impl Config {
                 }
             }
 
+            let mut is_synthesizable = false;
+            if let Some(opt) = lib.get("is_synthesizable") {
+                if let Some(opt) = opt.as_bool() {
+                    is_synthesizable = opt;
+                } else {
+                    return Err(format!(
+                        "Expected is_synthesizable to be boolean for library {name}"
+                    ));
+                }
+            }
+
+            let mut ignores_initial_values = false;
+            if let Some(opt) = lib.get("ignores_initial_values") {
+                if let Some(opt) = opt.as_bool() {
+                    ignores_initial_values = opt;
+                } else {
+                    return Err(format!(
+                        "Expected ignores_initial_values to be boolean for library {name}"
+                    ));
+                }
+            }
+
+            let mut is_testbench = false;
+            if let Some(opt) = lib.get("is_testbench") {
+                if let Some(opt) = opt.as_bool() {
+                    is_testbench = opt;
+                } else {
+                    return Err(format!(
+                        "Expected is_testbench to be boolean for library {name}"
+                    ));
+                }
+            }
+
+            let mut library_standard = None;
+            if let Some(opt) = lib.get("standard") {
+                let std_str = opt.as_str().ok_or_else(|| {
+                    format!("Expected standard to be a string for library {name}")
+                })?;
+                library_standard = Some(
+                    VHDLStandard::try_from(std_str)
+                        .map_err(|_| format!("Unsupported standard '{std_str}'"))?,
+                );
+            }
+
             libraries.insert(
                 name.to_owned(),
                 LibraryConfig {
                     name: name.to_owned(),
                     patterns,
                     is_third_party,
+                    is_synthesizable,
+                    ignores_initial_values,
+                    is_testbench,
+                    standard: library_standard,
+                    file_lists,
+                    file_standards,
                 },
             );
         }
@@ -173,10 +439,73 @@ impl Config {
             SeverityMap::default()
         };
 
+        let ban_list = if let Some(banned) = config.get("banned") {
+            BanList::from_table(banned.as_table().ok_or("banned must be a table")?)?
+        } else {
+            BanList::default()
+        };
+
+        let mut top_levels = Vec::new();
+        if let Some(top) = config.get("top") {
+            for name in top.as_array().ok_or("top must be an array")? {
+                let name = name.as_str().ok_or("top must be an array of strings")?;
+                top_levels.push(name.parse()?);
+            }
+        }
+
+        let mut conditional_identifiers = FnvHashMap::default();
+        if let Some(identifiers) = config.get("conditional_analysis") {
+            let identifiers = identifiers
+                .as_table()
+                .ok_or("conditional_analysis must be a table")?;
+            for (name, value) in identifiers.iter() {
+                let value = value.as_str().ok_or_else(|| {
+                    format!("Expected conditional analysis identifier {name} to be a string")
+                })?;
+                let name = name.to_ascii_uppercase();
+                if name == "VHDL_VERSION" {
+                    return Err(
+                        "VHDL_VERSION is determined by the standard and cannot be configured"
+                            .to_owned(),
+                    );
+                }
+                conditional_identifiers.insert(name, value.to_owned());
+            }
+        }
+
+        let mut formatting = FormattingOptions::default();
+        if let Some(format) = config.get("format") {
+            let format = format.as_table().ok_or("format must be a table")?;
+            if let Some(indent_width) = format.get("indent_width") {
+                formatting.indent_width = indent_width
+                    .as_integer()
+                    .and_then(|width| usize::try_from(width).ok())
+                    .ok_or("Expected indent_width to be a non-negative integer")?;
+            }
+            if let Some(use_tabs) = format.get("use_tabs") {
+                formatting.use_tabs = use_tabs
+                    .as_bool()
+                    .ok_or("Expected use_tabs to be boolean")?;
+            }
+        }
+
+        let standard_libraries = if let Some(path) = config.get("standard_libraries") {
+            let path = path.as_str().ok_or("standard_libraries must be a string")?;
+            Some(parent.join(substitute_environment_variables(path, &subst::Env)?))
+        } else {
+            None
+        };
+
         Ok(Config {
             libraries,
             severities,
             standard,
+            ban_list,
+            top_levels,
+            conditional_identifiers,
+            formatting,
+            standard_libraries,
+            keys: config.keys().cloned().collect(),
         })
     }
 
@@ -187,6 +516,7 @@ impl Config {
             let error_code = ErrorCode::try_from(name.as_str())
                 .map_err(|_| format!("'{name}' is not a valid error code"))?;
             match severity {
+                Value::String(severity) if severity == "ignore" => severities[error_code] = None,
                 Value::String(severity) => {
                     let severity = Severity::try_from(severity.as_str())
                         .map_err(|_| format!("'{severity}' is not a valid severity level"))?;
@@ -239,6 +569,55 @@ impl Config {
             }
         }
         self.severities = config.severities;
+        self.ban_list = config.ban_list.clone();
+        self.top_levels = config.top_levels.clone();
+        self.conditional_identifiers
+            .extend(config.conditional_identifiers.clone());
+        self.formatting = config.formatting;
+        if config.standard_libraries.is_some() {
+            self.standard_libraries = config.standard_libraries.clone();
+        }
+        self.keys.extend(config.keys.iter().cloned());
+    }
+
+    /// Merge the configuration of another workspace folder into self.
+    ///
+    /// Unlike [Config::append], the configurations are peers: Only the settings that the other
+    /// configuration defines take precedence, and a library defined by both configurations
+    /// contains the files of both.
+    pub fn merge(&mut self, config: &Config) {
+        let defines = |key: &str| config.keys.contains(key);
+        for library in config.iter_libraries() {
+            match self.libraries.get_mut(&library.name) {
+                Some(existing) => existing.merge(library),
+                None => {
+                    self.libraries.insert(library.name.clone(), library.clone());
+                }
+            }
+        }
+        if defines("standard") {
+            self.standard = config.standard;
+        }
+        if defines("lint") {
+            self.severities = config.severities;
+        }
+        if defines("banned") {
+            self.ban_list = config.ban_list.clone();
+        }
+        for top_level in config.top_levels.iter() {
+            if !self.top_levels.contains(top_level) {
+                self.top_levels.push(top_level.clone());
+            }
+        }
+        self.conditional_identifiers
+            .extend(config.conditional_identifiers.clone());
+        if defines("format") {
+            self.formatting = config.formatting;
+        }
+        if self.standard_libraries.is_none() {
+            self.standard_libraries = config.standard_libraries.clone();
+        }
+        self.keys.extend(config.keys.iter().cloned());
     }
 
     /// Load configuration file from installation folder
@@ -341,11 +720,41 @@ impl Config {
         &self.severities
     }
 
+    /// The constructs and packages that are not allowed in the project
+    pub(crate) fn ban_list(&self) -> &BanList {
+        &self.ban_list
+    }
+
+    /// The entities at the top of the design hierarchy
+    pub fn top_levels(&self) -> &[TopLevel] {
+        &self.top_levels
+    }
+
+    /// The values of user-defined conditional analysis identifiers, keyed by their upper case name
+    pub fn conditional_identifiers(&self) -> &FnvHashMap<String, String> {
+        &self.conditional_identifiers
+    }
+
+    /// The style of files formatted by the format command
+    pub fn formatting_options(&self) -> &FormattingOptions {
+        &self.formatting
+    }
+
+    /// The configuration file of the standard libraries that replaces the libraries
+    /// shipped with vhdl_ls, such as tool-specific variants of the `ieee` packages
+    pub fn standard_libraries(&self) -> Option<&Path> {
+        self.standard_libraries.as_deref()
+    }
+
     /// The VHDL standard to use if no more specific config is present.
     /// By default, VHDL 2008 is assumed
     pub fn standard(&self) -> VHDLStandard {
         self.standard
     }
+
+    pub fn set_standard(&mut self, standard: VHDLStandard) {
+        self.standard = standard;
+    }
 }
 
 fn substitute_environment_variables<'a, M>(s: &str, map: &'a M) -> Result<String, String>
@@ -399,6 +808,103 @@ where
     }
 }
 
+/// Replaces the `include` array of `config` by the contents of the included files.
+///
+/// The included files are merged in order, such that later files take precedence over earlier ones,
+/// and `config` takes precedence over all of them.
+/// Tables are merged key by key, i.e., a library can be marked as third-party without repeating its files,
+/// while all other values, such as the files of a library, replace the included value.
+/// Files of libraries are relative to the file that defines them.
+/// `including` are the canonical paths of the files that are currently being included
+fn resolve_includes(
+    config: &mut Table,
+    parent: &Path,
+    including: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let Some(include) = config.remove("include") else {
+        return Ok(());
+    };
+    let include = include.as_array().ok_or("include must be an array")?;
+    let mut merged = Table::new();
+    for file_name in include {
+        let file_name = file_name
+            .as_str()
+            .ok_or("include must be an array of strings")?;
+        let file_name = parent.join(substitute_environment_variables(file_name, &subst::Env)?);
+        let canonical = dunce::canonicalize(&file_name)
+            .map_err(|err| format!("Could not include {}: {err}", file_name.to_string_lossy()))?;
+        if including.contains(&canonical) {
+            return Err(format!(
+                "{} is included recursively",
+                file_name.to_string_lossy()
+            ));
+        }
+        let mut included = std::fs::read_to_string(&file_name)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| contents.parse::<Table>().map_err(|err| err.to_string()))
+            .map_err(|err| format!("Could not include {}: {err}", file_name.to_string_lossy()))?;
+        let included_parent = canonical.parent().unwrap_or(Path::new(""));
+        including.push(canonical.clone());
+        resolve_includes(&mut included, included_parent, including)?;
+        including.pop();
+        make_paths_absolute(&mut included, included_parent)?;
+        merge_tables(&mut merged, included);
+    }
+    let own = std::mem::replace(config, merged);
+    merge_tables(config, own);
+    Ok(())
+}
+
+/// Joins the files and file lists of all libraries and the standard libraries with `parent`,
+/// such that they no longer depend on the location of the configuration file
+fn make_paths_absolute(config: &mut Table, parent: &Path) -> Result<(), String> {
+    let make_absolute = |path: &mut String| -> Result<(), String> {
+        let absolute = parent.join(substitute_environment_variables(path, &subst::Env)?);
+        *path = absolute
+            .to_str()
+            .ok_or_else(|| format!("Could not convert {absolute:?} to string"))?
+            .to_owned();
+        Ok(())
+    };
+    if let Some(Value::String(path)) = config.get_mut("standard_libraries") {
+        make_absolute(path)?;
+    }
+    let Some(Value::Table(libraries)) = config.get_mut("libraries") else {
+        return Ok(());
+    };
+    for (_, library) in libraries.iter_mut() {
+        for key in ["files", "filelists"] {
+            let Some(Value::Array(files)) = library.get_mut(key) else {
+                continue;
+            };
+            for file in files.iter_mut() {
+                match file {
+                    Value::String(pattern) => make_absolute(pattern)?,
+                    Value::Table(options) => {
+                        if let Some(Value::String(pattern)) = options.get_mut("path") {
+                            make_absolute(pattern)?;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Merges `other` into `table`, where the values of `other` take precedence
+fn merge_tables(table: &mut Table, other: Table) {
+    for (key, value) in other {
+        match (table.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(value)) => merge_tables(existing, value),
+            (_, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
 /// Returns true if the pattern is a plain file name and not a glob pattern
 fn is_literal(pattern: &str) -> bool {
     !pattern.chars().any(|chr| matches!(&chr, '?' | '*' | '['))
@@ -464,6 +970,7 @@ lib1.files = [
 [lint]
 unused = 'error'
 duplicate = false
+unnecessary_work_library = 'ignore'
 ",
                 absolute_vhd.to_str().unwrap()
             ),
@@ -489,6 +996,7 @@ duplicate = false
         let mut expected_map = SeverityMap::default();
         expected_map[ErrorCode::Unused] = Some(Severity::Error);
         expected_map[ErrorCode::Duplicate] = None;
+        expected_map[ErrorCode::UnnecessaryWorkLibrary] = None;
         assert_eq!(config.severities, expected_map)
     }
 
@@ -552,6 +1060,59 @@ lib3.files = [
         assert_eq!(merged_config, expected_config);
     }
 
+    #[test]
+    fn merge_keeps_the_settings_that_the_other_config_does_not_define() {
+        let config0 = Config::from_str(
+            "
+standard = '1993'
+top = ['lib.ent']
+[libraries]
+lib.files = ['ent.vhd']
+[lint]
+unused = 'error'
+[banned]
+constructs = ['block']
+",
+            Path::new("folder0"),
+        )
+        .unwrap();
+        let config1 = Config::from_str(
+            "
+top = ['lib.tb']
+[libraries]
+lib.files = ['tb.vhd']
+",
+            Path::new("folder1"),
+        )
+        .unwrap();
+
+        let mut merged = config0.clone();
+        merged.merge(&config1);
+        assert_eq!(merged.standard(), VHDLStandard::VHDL1993);
+        assert_eq!(merged.severities(), config0.severities());
+        assert_eq!(merged.ban_list(), config0.ban_list());
+        assert_eq!(
+            merged
+                .top_levels()
+                .iter()
+                .map(|top| top.to_string())
+                .collect::<Vec<_>>(),
+            vec!["lib.ent", "lib.tb"]
+        );
+        assert_eq!(
+            merged.get_library("lib").unwrap().patterns,
+            vec![
+                Path::new("folder0").join("ent.vhd").to_string_lossy(),
+                Path::new("folder1").join("tb.vhd").to_string_lossy()
+            ]
+        );
+
+        let mut merged = config1.clone();
+        merged.merge(&config0);
+        assert_eq!(merged.standard(), VHDLStandard::VHDL1993);
+        assert_eq!(merged.severities(), config0.severities());
+    }
+
     #[test]
     fn test_warning_on_missing_file() {
         let parent = Path::new("parent_folder");
@@ -668,6 +1229,287 @@ work.files = [
         assert_eq!(config.expect_err("Expected erroneous config"), "The 'work' library is not a valid library.\nHint: To use a library that contains all files, use a common name for all libraries, i.e., 'defaultlib'")
     }
 
+    #[test]
+    fn library_standard_overrides_project_standard() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+standard = '2008'
+[libraries]
+legacy.files = []
+legacy.standard = '93'
+lib.files = []
+",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(config.standard(), VHDLStandard::VHDL2008);
+        assert_eq!(
+            config.get_library("legacy").unwrap().standard,
+            Some(VHDLStandard::VHDL1993)
+        );
+        assert_eq!(config.get_library("lib").unwrap().standard, None);
+
+        let config = Config::from_str("[libraries]\nlib.files = []\nlib.standard = '1987'", parent);
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "Unsupported standard '1987'"
+        );
+    }
+
+    #[test]
+    fn top_levels() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+top = ['lib.top', 'lib.tb(sim)']
+[libraries]
+lib.files = []
+",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(
+            config.top_levels(),
+            &[
+                TopLevel {
+                    library: "lib".to_owned(),
+                    entity: "top".to_owned(),
+                    architecture: None,
+                },
+                TopLevel {
+                    library: "lib".to_owned(),
+                    entity: "tb".to_owned(),
+                    architecture: Some("sim".to_owned()),
+                },
+            ]
+        );
+
+        let config = Config::from_str("top = ['top']\n[libraries]\nlib.files = []", parent);
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "Expected top level 'top' to be of the form library.entity or library.entity(architecture)"
+        );
+    }
+
+    #[test]
+    fn conditional_analysis_identifiers() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = []
+
+[conditional_analysis]
+tool_type = 'SYNTHESIS'
+MY_OPTION = '1'
+",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(
+            config.conditional_identifiers(),
+            &FnvHashMap::from_iter([
+                ("TOOL_TYPE".to_owned(), "SYNTHESIS".to_owned()),
+                ("MY_OPTION".to_owned(), "1".to_owned()),
+            ])
+        );
+
+        let config = Config::from_str(
+            "[libraries]\nlib.files = []\n[conditional_analysis]\nVHDL_VERSION = '2019'",
+            parent,
+        );
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "VHDL_VERSION is determined by the standard and cannot be configured"
+        );
+    }
+
+    #[test]
+    fn files_of_file_lists() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = abspath(tempdir.path());
+        let sim = root.join("sim");
+        std::fs::create_dir(&sim).unwrap();
+        let pkg = touch(&root, "pkg.vhd");
+        let ent = touch(&root, "ent.vhd");
+        let tb = touch(&sim, "tb.vhd");
+        std::fs::write(
+            sim.join("compile.f"),
+            "\
+# Compile order of the design
+-work lib
++define+SIM
+../pkg.vhd
+../ent.vhd // the design
+
+tb.vhd
+",
+        )
+        .unwrap();
+
+        let config = Config::from_str(
+            "
+[libraries]
+lib.filelists = ['sim/compile.f']
+other.filelists = ['missing.f']
+other.files = ['pkg.vhd']
+",
+            &root,
+        )
+        .unwrap();
+        let mut messages = vec![];
+        let lib = config.get_library("lib").unwrap();
+        assert_files_eq(&lib.file_names(&mut messages), &[pkg.clone(), ent, tb]);
+        assert_eq!(messages, vec![]);
+
+        let other = config.get_library("other").unwrap();
+        assert_files_eq(&other.file_names(&mut messages), &[pkg]);
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].message.starts_with("Could not read file list"));
+    }
+
+    #[test]
+    fn included_configs_are_merged() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = abspath(tempdir.path());
+        let common = root.join("common");
+        let board = root.join("board");
+        std::fs::create_dir(&common).unwrap();
+        std::fs::create_dir(&board).unwrap();
+        let common_pkg = touch(&common, "pkg.vhd");
+        let vendor_pkg = touch(&common, "vendor.vhd");
+        let board_top = touch(&board, "top.vhd");
+        std::fs::write(
+            common.join("vhdl_ls.toml"),
+            "
+top = ['shared.top']
+standard_libraries = 'ieee/vhdl_ls.toml'
+[libraries]
+shared.files = ['pkg.vhd']
+vendor.files = ['vendor.vhd']
+[lint]
+unused = 'error'
+",
+        )
+        .unwrap();
+
+        let config = Config::from_str(
+            "
+include = ['../common/vhdl_ls.toml']
+[libraries]
+vendor.is_third_party = true
+board.files = ['top.vhd']
+[lint]
+duplicate = false
+",
+            &board,
+        )
+        .unwrap();
+
+        let mut messages = vec![];
+        let shared = config.get_library("shared").unwrap();
+        assert_files_eq(&shared.file_names(&mut messages), &[common_pkg]);
+        let vendor = config.get_library("vendor").unwrap();
+        assert!(vendor.is_third_party);
+        assert_files_eq(&vendor.file_names(&mut messages), &[vendor_pkg]);
+        let board_library = config.get_library("board").unwrap();
+        assert_files_eq(&board_library.file_names(&mut messages), &[board_top]);
+        assert_eq!(messages, vec![]);
+        assert_eq!(config.top_levels()[0].entity, "top");
+        assert_eq!(
+            config.standard_libraries(),
+            Some(common.join("ieee/vhdl_ls.toml").as_path())
+        );
+        assert_eq!(config.severities[ErrorCode::Unused], Some(Severity::Error));
+        assert_eq!(config.severities[ErrorCode::Duplicate], None);
+    }
+
+    #[test]
+    fn recursive_include_is_an_error() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = abspath(tempdir.path());
+        std::fs::write(root.join("a.toml"), "include = ['b.toml']").unwrap();
+        std::fs::write(root.join("b.toml"), "include = ['a.toml']").unwrap();
+
+        let config = Config::from_str(
+            "include = ['a.toml']
+[libraries]",
+            &root,
+        );
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            format!(
+                "{} is included recursively",
+                root.join("a.toml").to_string_lossy()
+            )
+        );
+
+        let config = Config::from_str(
+            "include = ['missing.toml']
+[libraries]",
+            &root,
+        );
+        assert!(config
+            .expect_err("Expected erroneous config")
+            .starts_with("Could not include"));
+    }
+
+    #[test]
+    fn standard_libraries_are_relative_to_config_file() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str("[libraries]", parent).unwrap();
+        assert_eq!(config.standard_libraries(), None);
+
+        let config = Config::from_str(
+            "standard_libraries = 'vendor/vhdl_ls.toml'\n[libraries]",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(
+            config.standard_libraries(),
+            Some(parent.join("vendor/vhdl_ls.toml").as_path())
+        );
+
+        let mut appended = Config::default();
+        appended.append(&config, &mut Vec::new());
+        appended.append(&Config::default(), &mut Vec::new());
+        assert_eq!(appended.standard_libraries(), config.standard_libraries());
+    }
+
+    #[test]
+    fn formatting_options() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str("[libraries]\nlib.files = []", parent).unwrap();
+        assert_eq!(config.formatting_options(), &FormattingOptions::default());
+
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = []
+
+[format]
+indent_width = 2
+use_tabs = true
+",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(
+            config.formatting_options(),
+            &FormattingOptions {
+                indent_width: 2,
+                use_tabs: true,
+            }
+        );
+
+        let config = Config::from_str("[libraries]\n[format]\nindent_width = -1", parent);
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "Expected indent_width to be a non-negative integer"
+        );
+    }
+
     #[test]
     #[cfg(unix)]
     fn substitute() {
@@ -48,26 +48,30 @@ impl Contents {
         Position { line, character }
     }
 
-    #[cfg(test)]
     pub fn range(&self) -> Range {
         Range::new(self.start(), self.end())
     }
 
     #[cfg(test)]
     pub fn crop(&self, range: Range) -> Contents {
+        Contents {
+            lines: split_lines(&self.text(range)),
+        }
+    }
+
+    /// The text within `range`, where line endings are `\n`
+    pub fn text(&self, range: Range) -> String {
         let mut reader = ContentReader::new(self);
         reader.seek_pos(range.start);
 
         let mut result = String::new();
         while reader.pos() < range.end {
-            if let Some(chr) = reader.pop_char() {
-                result.push(chr);
+            match reader.pop_char() {
+                Some(chr) => result.push(chr),
+                None => break,
             }
         }
-
-        Contents {
-            lines: split_lines(&result),
-        }
+        result
     }
 
     pub fn num_lines(&self) -> usize {
@@ -324,7 +328,6 @@ impl<'a> ContentReader<'a> {
         self.state.pos()
     }
 
-    #[cfg(test)]
     pub fn seek_pos(&mut self, pos: Position) {
         self.state = ReaderState {
             pos: Position {
@@ -7,9 +7,12 @@
 use super::SrcPos;
 use crate::data::error_codes::{ErrorCode, SeverityMap};
 use std::convert::{AsRef, Into};
-use strum::{EnumString, IntoStaticStr};
+use strum::{EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
 
-#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash, EnumString, IntoStaticStr)]
+/// Severities are ordered from the least to the most severe
+#[derive(
+    PartialEq, PartialOrd, Ord, Debug, Clone, Copy, Eq, Hash, EnumString, IntoStaticStr, EnumIter,
+)]
 #[strum(serialize_all = "snake_case")]
 pub enum Severity {
     Hint,
@@ -18,6 +21,17 @@ pub enum Severity {
     Error,
 }
 
+impl Severity {
+    pub fn as_str(&self) -> &str {
+        self.into()
+    }
+
+    /// All severities from the least to the most severe
+    pub fn all() -> impl Iterator<Item = Severity> {
+        Severity::iter()
+    }
+}
+
 #[must_use]
 #[derive(PartialEq, Debug, Clone, Eq, Hash)]
 pub struct Diagnostic {
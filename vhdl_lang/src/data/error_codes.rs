@@ -8,9 +8,14 @@ use crate::{Diagnostic, Severity, SrcPos};
 use enum_map::{enum_map, Enum, EnumMap};
 use std::fmt::{Display, Formatter};
 use std::ops::{Index, IndexMut};
-use strum::{EnumString, IntoStaticStr};
+use strum::{EnumIter, EnumMessage, EnumString, IntoEnumIterator, IntoStaticStr};
 
-#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash, EnumString, IntoStaticStr, Enum)]
+/// The location of the online documentation of all error codes
+const DOCUMENTATION_URL: &str = "https://docs.rs/vhdl_lang/latest/vhdl_lang/enum.ErrorCode.html";
+
+#[derive(
+    PartialEq, Debug, Clone, Copy, Eq, Hash, EnumString, IntoStaticStr, EnumMessage, EnumIter, Enum,
+)]
 #[strum(serialize_all = "snake_case")]
 pub enum ErrorCode {
     /// A syntax error happens during tokenization or parsing.
@@ -94,6 +99,7 @@ pub enum ErrorCode {
     AmbiguousCall,
 
     /// Named arguments appear before positional arguments when calling a function
+    /// or named element associations appear before positional element associations in an aggregate
     ///
     /// # Example
     /// ```vhdl
@@ -102,6 +108,34 @@ pub enum ErrorCode {
     /// ```
     NamedBeforePositional,
 
+    /// The choice `others` of an aggregate is not the only choice of the last element association
+    ///
+    /// # Example
+    /// ```vhdl
+    /// constant vec : bit_vector(0 to 3) := (others => '0', 0 => '1');
+    /// ```
+    MisplacedOthers,
+
+    /// A choice of a case statement or of a selected assignment that is not locally static
+    ///
+    /// # Example
+    /// ```vhdl
+    /// case sel is
+    ///     when other_sig => null;
+    ///     when others => null;
+    /// end case;
+    /// ```
+    NonStaticChoice,
+
+    /// An array aggregate contains both positional and named element associations
+    /// apart from a final association with the choice `others`
+    ///
+    /// # Example
+    /// ```vhdl
+    /// constant vec : bit_vector(0 to 3) := ('1', 1 to 3 => '0');
+    /// ```
+    MixedAggregateAssociation,
+
     /// When calling a function, more arguments are passed to that function than it can accept.
     ///
     /// # Example
@@ -258,6 +292,31 @@ pub enum ErrorCode {
     /// A deferred constant is not allowed in the given context
     IllegalDeferredConstant,
 
+    /// The subtype indication of the full declaration of a deferred constant
+    /// does not conform to the subtype indication of the deferred constant declaration
+    ///
+    /// # Example
+    ///
+    /// ```vhdl
+    /// package pkg is
+    ///     constant c : natural;
+    /// end package;
+    ///
+    /// package body pkg is
+    ///     constant c : integer := 0;
+    /// end package body;
+    /// ```
+    DeferredConstantMismatch,
+
+    /// Since VHDL-2008, the type of a shared variable must be a protected type
+    ///
+    /// # Example
+    ///
+    /// ```vhdl
+    /// shared variable counter : natural;
+    /// ```
+    NonProtectedSharedVariable,
+
     /// The signature between an uninstantiated subprogram and it's instantiated
     /// counterpart does not match
     ///
@@ -285,6 +344,20 @@ pub enum ErrorCode {
     /// Procedure returns with value
     NonVoidReturn,
 
+    /// A control path of a function body reaches the end of the function
+    /// without a return statement
+    ///
+    /// # Example
+    /// ```vhdl
+    /// function fun(arg : boolean) return natural is
+    /// begin
+    ///     if arg then
+    ///         return 1;
+    ///     end if;
+    /// end function;
+    /// ```
+    MissingReturn,
+
     /// Illegal return statement, for example in a process
     ///
     /// # Example
@@ -299,6 +372,21 @@ pub enum ErrorCode {
     /// ```
     IllegalReturn,
 
+    /// Signal assignment within a function, or within a procedure that is not declared
+    /// within a process to a target that is not a signal parameter of the procedure
+    ///
+    /// # Example
+    ///
+    /// ```vhdl
+    /// signal sig : bit;
+    ///
+    /// procedure proc is
+    /// begin
+    ///     sig <= '1';
+    /// end procedure;
+    /// ```
+    IllegalSignalAssignment,
+
     /// Exit statement called outside a loop
     ExitOutsideLoop,
 
@@ -388,6 +476,90 @@ pub enum ErrorCode {
     /// Calling a name like a function or procedure where that is not applicable
     InvalidCall,
 
+    /// A value of the selector of a case statement, case generate statement or selected assignment
+    /// is not covered by any choice
+    ///
+    /// # Example
+    /// ```vhdl
+    /// type state_t is (idle, busy, done);
+    /// -- ...
+    /// with state select
+    ///     ready <= '1' when idle,
+    ///              '0' when busy; -- done is not covered
+    /// ```
+    MissingChoice,
+
+    /// A value of the selector of a case statement or selected assignment
+    /// is covered by more than one choice
+    ///
+    /// # Example
+    /// ```vhdl
+    /// with state select
+    ///     ready <= '1' when idle,
+    ///              '0' when idle | busy,
+    ///              '0' when others;
+    /// ```
+    DuplicateChoice,
+
+    /// A delay of a signal assignment that is negative, a pulse rejection limit
+    /// that is greater than the first delay of the waveform
+    /// or waveform elements whose delays are not in ascending order
+    ///
+    /// # Example
+    /// ```vhdl
+    /// sig <= '1' after 2 ns, '0' after 1 ns;
+    /// ```
+    IllegalDelay,
+
+    /// A static index or bound of a slice that does not belong to the
+    /// statically known index range of the array
+    ///
+    /// # Example
+    /// ```vhdl
+    /// signal data : bit_vector(7 downto 0);
+    /// -- ...
+    /// data(8) <= '1';
+    /// ```
+    IndexOutOfRange,
+
+    /// A slice whose direction differs from the direction of the index range of the array
+    ///
+    /// # Example
+    /// ```vhdl
+    /// signal data : bit_vector(7 downto 0);
+    /// -- ...
+    /// data(0 to 3) <= "0000";
+    /// ```
+    SliceDirectionMismatch,
+
+    /// The statically known length of an array value differs from
+    /// the statically known length of the target it is assigned to
+    ///
+    /// # Example
+    /// ```vhdl
+    /// signal data : bit_vector(7 downto 0) := x"FFF";
+    /// ```
+    LengthMismatch,
+
+    /// An interface object whose mode does not permit reading its value,
+    /// i.e., a port of mode `linkage` or, before VHDL-2008, an interface object of mode `out`
+    ///
+    /// # Example
+    /// ```vhdl
+    /// entity ent is
+    ///     port (
+    ///         q : out bit;
+    ///         q_n : out bit
+    ///     );
+    /// end entity;
+    ///
+    /// architecture rtl of ent is
+    /// begin
+    ///     q_n <= not q; -- illegal in VHDL-1993
+    /// end architecture;
+    /// ```
+    IllegalPortRead,
+
     // Linting
     /// A declaration that is unused
     Unused,
@@ -410,6 +582,182 @@ pub enum ErrorCode {
     /// ```
     UnassociatedContext,
 
+    /// A delay within a library that is marked as synthesizable.
+    /// Synthesis tools ignore delays, so the synthesized design may behave
+    /// differently from the simulated design.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// q <= d after 1 ns;
+    /// ```
+    IgnoredDelay,
+
+    /// An initial value of a signal that is assigned in clocked logic
+    /// within a synthesizable library whose target ignores initial values.
+    /// Use a reset or a resolved type instead.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// signal count : natural := 0;
+    /// -- ...
+    /// count <= count + 1 when rising_edge(clk);
+    /// ```
+    IgnoredInitialValue,
+
+    /// A signal without an initial value that is read by a process
+    /// before its first wait statement within a testbench library.
+    /// The signal still has the value `'U'` at that time.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// signal clk : std_logic;
+    /// -- ...
+    /// process
+    /// begin
+    ///     clk <= not clk;
+    ///     wait for 5 ns;
+    /// end process;
+    /// ```
+    UninitializedRead,
+
+    /// A signal that is read but never driven by an assignment or a port map.
+    /// The signal keeps its initial value.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// signal enable : std_logic;
+    /// -- ...
+    /// q <= d when enable = '1';
+    /// ```
+    UndrivenSignal,
+
+    /// A signal that is driven but never read.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// signal count : natural;
+    /// -- ...
+    /// count <= count_next;
+    /// ```
+    UnreadSignal,
+
+    /// A signal in the sensitivity list of a process that is never read by the process.
+    /// The process is resumed needlessly when the signal changes.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// process (clk, d)
+    /// begin
+    ///     if rising_edge(clk) then
+    ///         q <= '1';
+    ///     end if;
+    /// end process;
+    /// ```
+    SuperfluousSensitivity,
+
+    /// A statement that follows an unconditional `return`, `exit`, `next` or `wait`
+    /// statement within the same sequence of statements, or a branch of an if-generate
+    /// statement whose condition is statically false.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// return value;
+    /// report "never executed";
+    /// ```
+    UnreachableCode,
+
+    /// A shared variable of a type that is not protected.
+    /// This is allowed before VHDL-2008 but illegal in later revisions of the standard.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// shared variable counter : natural;
+    /// ```
+    UnportableSharedVariable,
+
+    /// A process that has neither a sensitivity list nor a wait statement.
+    /// The process never suspends and simulation hangs at time zero.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// process
+    /// begin
+    ///     q <= d;
+    /// end process;
+    /// ```
+    MissingWait,
+
+    /// A generic or port of a component that differs in name, type or default value
+    /// from the entity that the component is bound to, by default binding or by a configuration.
+    /// Such differences are a common cause of errors during elaboration.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// entity fifo is
+    ///     generic (depth : natural := 16);
+    /// end entity;
+    /// -- ...
+    /// component fifo is
+    ///     generic (depth : natural := 8);
+    /// end component;
+    /// ```
+    ComponentMismatch,
+
+    /// A construct or package that is banned by the `[banned]` table of the configuration
+    ///
+    /// # Example
+    /// With `constructs = ['shared_variable']`:
+    /// ```vhdl
+    /// architecture arch of ent is
+    ///     shared variable counter : counter_t;
+    /// begin
+    /// end architecture;
+    /// ```
+    BannedConstruct,
+
+    /// A `` `warning `` conditional analysis directive (VHDL-2019) within analyzed text
+    ///
+    /// # Example
+    /// ```vhdl
+    /// `if TOOL_TYPE = "SYNTHESIS" then
+    /// `warning "The model is not synthesizable"
+    /// `end if
+    /// ```
+    WarningDirective,
+
+    /// An `` `error `` conditional analysis directive (VHDL-2019) within analyzed text
+    ///
+    /// # Example
+    /// ```vhdl
+    /// `if VHDL_VERSION < "2019" then
+    /// `error "VHDL-2019 is required"
+    /// `end if
+    /// ```
+    ErrorDirective,
+
+    /// Text that is not analyzed since the condition of its conditional analysis directive
+    /// (VHDL-2019) is false
+    ///
+    /// # Example
+    /// ```vhdl
+    /// `if TOOL_TYPE = "SYNTHESIS" then
+    /// signal not_analyzed : bit;
+    /// `end if
+    /// ```
+    InactiveCode,
+
+    /// A VHDL-2008 feature in a library that is analyzed as VHDL-1993
+    ///
+    /// # Example
+    /// With `lib.standard = '1993'`:
+    /// ```vhdl
+    /// process (all)
+    /// begin
+    ///     q <= d;
+    /// end process;
+    /// ```
+    UnsupportedFeature,
+
     // Misc
     /// An internal error that signifies that some precondition within vhdl_lang wasn't met.
     /// If an error with this error code occurs,
@@ -448,6 +796,9 @@ impl Default for SeverityMap {
             | TypeMismatch
             | AmbiguousCall
             | NamedBeforePositional
+            | MisplacedOthers
+            | NonStaticChoice
+            | MixedAggregateAssociation
             | TooManyArguments
             | Unassociated
             | AlreadyAssociated
@@ -464,12 +815,15 @@ impl Default for SeverityMap {
             | ConflictingUseClause
             | MissingProtectedBodyType
             | IllegalDeferredConstant
+            | DeferredConstantMismatch
+            | NonProtectedSharedVariable
             | SignatureMismatch
             | AmbiguousInstantiation
             | MismatchedSubprogramInstantiation
             | VoidReturn
             | NonVoidReturn
             | IllegalReturn
+            | IllegalSignalAssignment
             | ExitOutsideLoop
             | NextOutsideLoop
             | InvalidLoopLabel
@@ -491,12 +845,34 @@ impl Default for SeverityMap {
             | UnexpectedSignature
             | MissingDeferredDeclaration
             | MissingFullTypeDeclaration
-            | InvalidCall => Some(Error),
+            | InvalidCall
+            | MissingChoice
+            | DuplicateChoice
+            | IllegalDelay
+            | IndexOutOfRange
+            | SliceDirectionMismatch
+            | LengthMismatch
+            | IllegalPortRead
+            | ErrorDirective
+            | UnsupportedFeature => Some(Error),
             Unused
             | UnnecessaryWorkLibrary
-            | UnassociatedContext => Some(Warning),
+            | UnassociatedContext
+            | IgnoredDelay
+            | IgnoredInitialValue
+            | UninitializedRead
+            | UndrivenSignal
+            | UnreadSignal
+            | SuperfluousSensitivity
+            | UnreachableCode
+            | UnportableSharedVariable
+            | ComponentMismatch
+            | BannedConstruct
+            | MissingWait
+            | MissingReturn
+            | WarningDirective => Some(Warning),
             Internal => Some(Error),
-            Related => Some(Hint)
+            InactiveCode | Related => Some(Hint)
         };
         SeverityMap { inner: map }
     }
@@ -520,6 +896,21 @@ impl ErrorCode {
     pub fn as_str(&self) -> &str {
         self.into()
     }
+
+    /// All error codes in the order they are declared
+    pub fn all() -> impl Iterator<Item = ErrorCode> {
+        ErrorCode::iter()
+    }
+
+    /// The extended explanation of this error code, usually including an example
+    pub fn explanation(&self) -> &'static str {
+        self.get_documentation().unwrap_or_default()
+    }
+
+    /// A link to the section of the online documentation that explains this error code
+    pub fn documentation_url(&self) -> String {
+        format!("{DOCUMENTATION_URL}#variant.{self:?}")
+    }
 }
 
 #[test]
@@ -552,6 +943,17 @@ fn serialize_to_string() {
     assert_eq!(ErrorCode::SyntaxError.as_str(), "syntax_error");
 }
 
+#[test]
+fn every_error_code_is_explained() {
+    for code in ErrorCode::all() {
+        assert!(!code.explanation().is_empty(), "{code} is not explained");
+    }
+    assert_eq!(
+        ErrorCode::VoidReturn.documentation_url(),
+        format!("{DOCUMENTATION_URL}#variant.VoidReturn")
+    );
+}
+
 /// Specialized diagnostics with pre-defined messages and error codes
 impl Diagnostic {
     pub fn syntax_error(item: impl AsRef<SrcPos>, msg: impl Into<String>) -> Diagnostic {
@@ -525,7 +525,7 @@ impl<T: HasSrcPos> HasSource for T {
 /// A wrapper around a PathBuf that ensures the path is absolute and simplified.
 ///
 /// This struct can be used similar to a [PathBuf], i.e., dereferencing it will return a [Path]
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
 pub(crate) struct FilePath(PathBuf);
 
 impl std::ops::Deref for FilePath {
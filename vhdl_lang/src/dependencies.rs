@@ -0,0 +1,476 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Export of the dependencies between design units of a project,
+//! such that the coupling of packages can be visualized and cycles can be found.
+//!
+//! Both the Graphviz DOT format and JSON are supported.
+//! The graph contains the design units of all libraries that are not third-party libraries,
+//! together with the units of third-party libraries that they depend on.
+//! The implicit dependency of every design unit on `std.standard` is left out.
+
+use crate::analysis::{DesignRoot, Library};
+use crate::ast::json::Json;
+use crate::ast::{AnyKind, HasUnitId, PrimaryKind, SecondaryKind, UnitId};
+use crate::Config;
+use fnv::{FnvHashMap, FnvHashSet};
+use itertools::Itertools;
+use std::fmt::Write;
+use strum::{EnumString, IntoStaticStr};
+
+/// The format of an exported dependency graph.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum GraphFormat {
+    /// The DOT language of Graphviz
+    Dot,
+    /// A JSON object with the units, dependencies and cycles of the graph
+    Json,
+}
+
+impl GraphFormat {
+    /// The conventional name of the exported file
+    pub fn default_file_name(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dependencies.dot",
+            GraphFormat::Json => "dependencies.json",
+        }
+    }
+}
+
+/// Why a design unit depends on another design unit.
+/// The reason is derived from the kind of the unit that is depended upon.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum DependencyKind {
+    /// A secondary unit depends on its primary unit, i.e. an architecture on its entity
+    Primary,
+    /// A use clause or selected name denotes a package or a package instance
+    Use,
+    /// A context reference denotes a context declaration
+    Context,
+    /// An instantiation or binding denotes an entity, architecture or configuration
+    Instance,
+}
+
+impl DependencyKind {
+    pub fn as_str(&self) -> &'static str {
+        self.into()
+    }
+
+    fn of(user: &UnitId, used: &UnitId) -> DependencyKind {
+        match used.kind() {
+            AnyKind::Primary(_)
+                if user.secondary_name().is_some()
+                    && user.library_name() == used.library_name()
+                    && user.primary_name() == used.primary_name() =>
+            {
+                DependencyKind::Primary
+            }
+            AnyKind::Primary(PrimaryKind::Context) => DependencyKind::Context,
+            AnyKind::Primary(PrimaryKind::Entity | PrimaryKind::Configuration)
+            | AnyKind::Secondary(SecondaryKind::Architecture) => DependencyKind::Instance,
+            _ => DependencyKind::Use,
+        }
+    }
+}
+
+/// A design unit, named `library.unit`, `library.entity(architecture)`
+/// or `library.package body`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct UnitNode {
+    pub name: String,
+    /// The kind of design unit, i.e. `entity` or `package body`
+    pub kind: String,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Dependency {
+    pub user: String,
+    pub used: String,
+    pub kind: DependencyKind,
+}
+
+/// The direct dependencies between design units, sorted by name.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct DependencyGraph {
+    pub units: Vec<UnitNode>,
+    pub dependencies: Vec<Dependency>,
+}
+
+pub(crate) fn dependency_graph(root: &DesignRoot, config: &Config) -> DependencyGraph {
+    let libraries = root
+        .libraries()
+        .filter(|library| {
+            config
+                .get_library(&library.name().name_utf8())
+                .is_some_and(|library_config| !library_config.is_third_party)
+        })
+        .collect_vec();
+    graph_of_libraries(root, &libraries)
+}
+
+/// The graph of all units of `libraries` and the units that they depend on
+fn graph_of_libraries(root: &DesignRoot, libraries: &[&Library]) -> DependencyGraph {
+    let mut units: FnvHashMap<UnitId, String> = FnvHashMap::default();
+    for library in root.libraries() {
+        for unit in library.units() {
+            units.insert(unit.unit_id().clone(), unit_name(unit.unit_id()));
+        }
+    }
+    let included: FnvHashSet<&UnitId> = libraries
+        .iter()
+        .flat_map(|library| library.units())
+        .map(|unit| unit.unit_id())
+        .collect();
+
+    let dependencies = root
+        .unit_dependencies()
+        .into_iter()
+        .filter(|(user, used)| {
+            included.contains(user) && units.contains_key(used) && !is_std_standard(used)
+        })
+        .map(|(user, used)| Dependency {
+            kind: DependencyKind::of(&user, &used),
+            user: units[&user].clone(),
+            used: units[&used].clone(),
+        })
+        .sorted_by(|a, b| (&a.user, &a.used).cmp(&(&b.user, &b.used)))
+        .collect_vec();
+
+    let used: FnvHashSet<&str> = dependencies
+        .iter()
+        .map(|dependency| dependency.used.as_str())
+        .collect();
+    let units = units
+        .iter()
+        .filter(|(id, name)| included.contains(id) || used.contains(name.as_str()))
+        .map(|(id, name)| UnitNode {
+            name: name.clone(),
+            kind: id.kind().describe().to_owned(),
+        })
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .collect_vec();
+
+    DependencyGraph {
+        units,
+        dependencies,
+    }
+}
+
+fn is_std_standard(id: &UnitId) -> bool {
+    id.library_name().name_utf8().eq_ignore_ascii_case("std")
+        && id
+            .primary_name()
+            .name_utf8()
+            .eq_ignore_ascii_case("standard")
+}
+
+fn unit_name(id: &UnitId) -> String {
+    let prefix = format!("{}.{}", id.library_name(), id.primary_name());
+    match (id.kind(), id.secondary_name()) {
+        (AnyKind::Secondary(SecondaryKind::Architecture), Some(name)) => {
+            format!("{prefix}({name})")
+        }
+        (AnyKind::Secondary(SecondaryKind::PackageBody), _) => format!("{prefix} body"),
+        _ => prefix,
+    }
+}
+
+impl DependencyGraph {
+    /// All sets of units that depend on each other, in the order of their names.
+    /// Each set contains at least two units.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let index: FnvHashMap<&str, usize> = self
+            .units
+            .iter()
+            .enumerate()
+            .map(|(idx, unit)| (unit.name.as_str(), idx))
+            .collect();
+        let mut edges = vec![Vec::new(); self.units.len()];
+        for dependency in self.dependencies.iter() {
+            edges[index[dependency.user.as_str()]].push(index[dependency.used.as_str()]);
+        }
+
+        let mut components = StronglyConnected::new(&edges);
+        for node in 0..self.units.len() {
+            if components.index[node].is_none() {
+                components.visit(node);
+            }
+        }
+        components
+            .components
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|node| self.units[node].name.clone())
+                    .sorted()
+                    .collect_vec()
+            })
+            .sorted()
+            .collect()
+    }
+
+    /// The graph in the DOT language, where dependencies within a cycle are red
+    pub fn to_dot(&self) -> String {
+        let in_cycle: FnvHashSet<String> = self.cycles().into_iter().flatten().collect();
+        let mut dot = String::from("digraph dependencies {\n");
+        for unit in self.units.iter() {
+            let _ = writeln!(
+                dot,
+                "  {} [label={}];",
+                dot_string(&unit.name),
+                dot_string(&format!("{}\n{}", unit.name, unit.kind))
+            );
+        }
+        for dependency in self.dependencies.iter() {
+            let color =
+                if in_cycle.contains(&dependency.user) && in_cycle.contains(&dependency.used) {
+                    ", color=red"
+                } else {
+                    ""
+                };
+            let _ = writeln!(
+                dot,
+                "  {} -> {} [label={}{color}];",
+                dot_string(&dependency.user),
+                dot_string(&dependency.used),
+                dot_string(dependency.kind.as_str()),
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_json(&self) -> Json {
+        let string = |value: &str| Json::String(value.to_owned());
+        let member = |key: &str, value: Json| (key.to_owned(), value);
+        Json::Object(vec![
+            member(
+                "units",
+                Json::Array(
+                    self.units
+                        .iter()
+                        .map(|unit| {
+                            Json::Object(vec![
+                                member("name", string(&unit.name)),
+                                member("kind", string(&unit.kind)),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            member(
+                "dependencies",
+                Json::Array(
+                    self.dependencies
+                        .iter()
+                        .map(|dependency| {
+                            Json::Object(vec![
+                                member("user", string(&dependency.user)),
+                                member("used", string(&dependency.used)),
+                                member("kind", string(dependency.kind.as_str())),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            member(
+                "cycles",
+                Json::Array(
+                    self.cycles()
+                        .iter()
+                        .map(|cycle| Json::Array(cycle.iter().map(|name| string(name)).collect()))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
+fn dot_string(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+/// Tarjan's algorithm for the strongly connected components of a graph
+struct StronglyConnected<'a> {
+    edges: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl<'a> StronglyConnected<'a> {
+    fn new(edges: &'a [Vec<usize>]) -> StronglyConnected<'a> {
+        StronglyConnected {
+            edges,
+            index: vec![None; edges.len()],
+            low_link: vec![0; edges.len()],
+            on_stack: vec![false; edges.len()],
+            stack: Vec::new(),
+            next_index: 0,
+            components: Vec::new(),
+        }
+    }
+
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.next_index);
+        self.low_link[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &next in self.edges[node].iter() {
+            match self.index[next] {
+                None => {
+                    self.visit(next);
+                    self.low_link[node] = self.low_link[node].min(self.low_link[next]);
+                }
+                Some(index) if self.on_stack[next] => {
+                    self.low_link[node] = self.low_link[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_link[node]) == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::syntax::test::check_no_diagnostics;
+
+    fn graph_of(code: &str) -> DependencyGraph {
+        let mut builder = LibraryBuilder::new();
+        builder.code("libname", code);
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        let libraries = root
+            .libraries()
+            .filter(|library| library.name().name_utf8() == "libname")
+            .collect_vec();
+        graph_of_libraries(&root, &libraries)
+    }
+
+    #[test]
+    fn lists_dependencies_by_kind() {
+        let graph = graph_of(
+            "
+package pkg is
+end package;
+
+context ctx is
+  library libname;
+  use libname.pkg.all;
+end context;
+
+entity child is
+end entity;
+
+context work.ctx;
+entity ent is
+end entity;
+
+architecture rtl of ent is
+begin
+  inst: entity work.child;
+end architecture;
+",
+        );
+        assert_eq!(
+            graph
+                .units
+                .iter()
+                .map(|unit| format!("{} {}", unit.name, unit.kind))
+                .collect_vec(),
+            vec![
+                "libname.child entity",
+                "libname.ctx context",
+                "libname.ent entity",
+                "libname.ent(rtl) architecture",
+                "libname.pkg package",
+            ]
+        );
+        assert_eq!(
+            graph
+                .dependencies
+                .iter()
+                .map(|dependency| format!(
+                    "{} -> {} {}",
+                    dependency.user,
+                    dependency.used,
+                    dependency.kind.as_str()
+                ))
+                .collect_vec(),
+            vec![
+                "libname.ctx -> libname.pkg use",
+                "libname.ent -> libname.ctx context",
+                "libname.ent(rtl) -> libname.child instance",
+                "libname.ent(rtl) -> libname.ent primary",
+            ]
+        );
+        assert_eq!(graph.cycles(), Vec::<Vec<String>>::new());
+        assert!(graph
+            .to_dot()
+            .contains("  \"libname.ent(rtl)\" -> \"libname.ent\" [label=\"primary\"];\n"));
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let graph = DependencyGraph {
+            units: ["a", "b", "c"]
+                .into_iter()
+                .map(|name| UnitNode {
+                    name: name.to_owned(),
+                    kind: "package".to_owned(),
+                })
+                .collect(),
+            dependencies: [("a", "b"), ("b", "a"), ("b", "c")]
+                .into_iter()
+                .map(|(user, used)| Dependency {
+                    user: user.to_owned(),
+                    used: used.to_owned(),
+                    kind: DependencyKind::Use,
+                })
+                .collect(),
+        };
+        assert_eq!(graph.cycles(), vec![vec!["a", "b"]]);
+        let dot = graph.to_dot();
+        assert!(dot.contains("  \"a\" -> \"b\" [label=\"use\", color=red];\n"));
+        assert!(dot.contains("  \"b\" -> \"c\" [label=\"use\"];\n"));
+        assert_eq!(
+            graph.to_json().to_string(),
+            "{\"units\":[{\"name\":\"a\",\"kind\":\"package\"},{\"name\":\"b\",\"kind\":\"package\"},\
+             {\"name\":\"c\",\"kind\":\"package\"}],\"dependencies\":[{\"user\":\"a\",\"used\":\"b\",\"kind\":\"use\"},\
+             {\"user\":\"b\",\"used\":\"a\",\"kind\":\"use\"},{\"user\":\"b\",\"used\":\"c\",\"kind\":\"use\"}],\
+             \"cycles\":[[\"a\",\"b\"]]}"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Machine-applicable fixes.
+//!
+//! A [Fix] is a set of [TextEdit]s for a single source file that can be applied without
+//! any user interaction. Fixes are grouped by the [FixRule] that produced them.
+
+mod end_label;
+mod keyword_case;
+mod superfluous_sensitivity;
+mod unused_use;
+
+use crate::analysis::DesignRoot;
+use crate::{Config, Position, Range, Source};
+use itertools::Itertools;
+use strum::{EnumString, IntoStaticStr};
+
+pub(crate) use end_label::end_label_fixes;
+pub(crate) use keyword_case::keyword_case_fixes;
+pub(crate) use superfluous_sensitivity::superfluous_sensitivity_fixes;
+pub(crate) use unused_use::unused_use_fixes;
+
+/// A rule that can produce machine-applicable fixes.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum FixRule {
+    /// Keywords are written in lower case, i.e. `ENTITY` becomes `entity`.
+    KeywordCase,
+    /// Use clauses in a context clause that do not make any referenced name visible
+    /// are removed.
+    UnusedUse,
+    /// Signals in the sensitivity list of a process that are never read by the process
+    /// are removed.
+    SuperfluousSensitivity,
+    /// Design units, subprogram bodies and labeled compound statements repeat their name
+    /// after `end`, i.e. `end entity;` becomes `end entity ent;`.
+    EndLabel,
+}
+
+impl FixRule {
+    pub fn as_str(&self) -> &'static str {
+        self.into()
+    }
+}
+
+/// Replace the text within `range` with `new_text`.
+/// An empty range denotes an insertion, an empty text denotes a deletion.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TextEdit {
+    pub range: Range,
+    pub new_text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range, new_text: impl Into<String>) -> TextEdit {
+        TextEdit {
+            range,
+            new_text: new_text.into(),
+        }
+    }
+
+    pub fn delete(range: Range) -> TextEdit {
+        TextEdit::new(range, "")
+    }
+}
+
+/// A set of edits for a single source that fixes a single issue.
+#[derive(Debug, Clone)]
+pub struct Fix {
+    pub rule: FixRule,
+    pub title: String,
+    pub source: Source,
+    pub edits: Vec<TextEdit>,
+}
+
+/// Compute all fixes for the selected rules.
+/// Fixes that originate from the same source are sorted by position
+/// and duplicates, for example from files mapped to multiple libraries, are removed.
+/// Third party libraries are never fixed.
+pub(crate) fn list_fixes(root: &DesignRoot, config: &Config, rules: &[FixRule]) -> Vec<Fix> {
+    let libraries = root
+        .libraries()
+        .filter(|library| {
+            config
+                .get_library(&library.name().name_utf8())
+                .is_some_and(|library_config| !library_config.is_third_party)
+        })
+        .collect_vec();
+    let mut fixes = Vec::new();
+    for rule in rules.iter().unique() {
+        match rule {
+            FixRule::KeywordCase => fixes.extend(keyword_case_fixes(&libraries)),
+            FixRule::UnusedUse => fixes.extend(unused_use_fixes(root, &libraries)),
+            FixRule::SuperfluousSensitivity => {
+                fixes.extend(superfluous_sensitivity_fixes(root, &libraries))
+            }
+            FixRule::EndLabel => fixes.extend(end_label_fixes(&libraries)),
+        }
+    }
+    fixes.sort_by(|a, b| {
+        a.source.cmp(&b.source).then_with(|| {
+            a.edits
+                .first()
+                .map(|e| e.range.start)
+                .cmp(&b.edits.first().map(|e| e.range.start))
+        })
+    });
+    fixes.dedup_by(|a, b| a.source == b.source && a.edits == b.edits);
+    fixes
+}
+
+/// Apply `edits` to `text`.
+///
+/// Positions are interpreted the same way as for [Source], i.e. lines may end with
+/// `\n`, `\r\n` or `\r` and characters are counted in UTF-16 code units.
+/// Line endings outside the edited ranges are retained.
+/// Edits that overlap a previous edit are skipped.
+pub fn apply_edits(text: &str, edits: &[TextEdit]) -> String {
+    let mut edits = edits.iter().collect_vec();
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+    let line_starts = line_starts(text);
+
+    let mut result = String::with_capacity(text.len());
+    let mut offset = 0;
+    let mut last_end = Position::default();
+    for edit in edits {
+        if edit.range.start < last_end {
+            continue;
+        }
+        let start = byte_offset(text, &line_starts, edit.range.start);
+        let end = byte_offset(text, &line_starts, edit.range.end).max(start);
+        result.push_str(&text[offset..start]);
+        result.push_str(&edit.new_text);
+        offset = end;
+        last_end = edit.range.end;
+    }
+    result.push_str(&text[offset..]);
+    result
+}
+
+/// Split `text` into lines that include their line ending.
+/// Lines may end with `\n`, `\r\n` or `\r` the same way as for [apply_edits].
+pub fn split_lines_inclusive(text: &str) -> Vec<&str> {
+    let mut starts = line_starts(text);
+    starts.push(text.len());
+    starts
+        .windows(2)
+        .map(|bounds| &text[bounds[0]..bounds[1]])
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// The byte offsets at which each line of `text` starts.
+pub(crate) fn line_starts(text: &str) -> Vec<usize> {
+    let bytes = text.as_bytes();
+    let mut starts = vec![0];
+    for (idx, byte) in bytes.iter().enumerate() {
+        if *byte == b'\n' || (*byte == b'\r' && bytes.get(idx + 1) != Some(&b'\n')) {
+            starts.push(idx + 1);
+        }
+    }
+    starts
+}
+
+/// Convert a position into a byte offset into `text`.
+/// Positions beyond the end of a line are clamped to the end of that line.
+pub(crate) fn byte_offset(text: &str, line_starts: &[usize], pos: Position) -> usize {
+    let Some(&line_start) = line_starts.get(pos.line as usize) else {
+        return text.len();
+    };
+    let mut character = 0;
+    for (idx, chr) in text[line_start..].char_indices() {
+        if character >= pos.character || chr == '\n' || chr == '\r' {
+            return line_start + idx;
+        }
+        character += chr.len_utf16() as u32;
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    }
+
+    #[test]
+    fn apply_edits_replaces_text() {
+        let text = "ENTITY foo IS\nEND ENTITY;\n";
+        let edits = [
+            TextEdit::new(range((1, 0), (1, 3)), "end"),
+            TextEdit::new(range((0, 0), (0, 6)), "entity"),
+        ];
+        assert_eq!(apply_edits(text, &edits), "entity foo IS\nend ENTITY;\n");
+    }
+
+    #[test]
+    fn apply_edits_keeps_line_endings() {
+        let text = "use a.b;\r\nuse c.d;\r\nentity foo is\r\n";
+        let edits = [TextEdit::delete(range((0, 0), (1, 0)))];
+        assert_eq!(apply_edits(text, &edits), "use c.d;\r\nentity foo is\r\n");
+    }
+
+    #[test]
+    fn split_lines_inclusive_keeps_line_endings() {
+        assert_eq!(
+            split_lines_inclusive("a\nb\r\nc\rd"),
+            vec!["a\n", "b\r\n", "c\r", "d"]
+        );
+        assert_eq!(split_lines_inclusive("a\r"), vec!["a\r"]);
+        assert!(split_lines_inclusive("").is_empty());
+    }
+
+    #[test]
+    fn apply_edits_skips_overlapping_edits() {
+        let text = "abcdef";
+        let edits = [
+            TextEdit::new(range((0, 0), (0, 3)), "x"),
+            TextEdit::new(range((0, 2), (0, 4)), "y"),
+        ];
+        assert_eq!(apply_edits(text, &edits), "xdef");
+    }
+
+    #[test]
+    fn parses_rule_names() {
+        assert_eq!("keyword_case".parse(), Ok(FixRule::KeywordCase));
+        assert_eq!("unused_use".parse(), Ok(FixRule::UnusedUse));
+        assert_eq!("end_label".parse(), Ok(FixRule::EndLabel));
+        assert_eq!(FixRule::UnusedUse.as_str(), "unused_use");
+    }
+}
@@ -0,0 +1,201 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::keyword_case::text_at;
+use super::{Fix, FixRule, TextEdit};
+use crate::analysis::Library;
+use crate::ast::search::{DeclarationItem, FoundDeclaration};
+use crate::ast::visitor::{walk, Visitor};
+use crate::ast::{
+    ConcurrentStatement, LabeledConcurrentStatement, LabeledSequentialStatement,
+    SequentialStatement, SubprogramSpecification,
+};
+use crate::syntax::{Kind, TokenAccess};
+use crate::{Range, TokenId};
+use std::ops::ControlFlow;
+
+#[derive(Default)]
+struct EndLabels {
+    fixes: Vec<Fix>,
+}
+
+impl EndLabels {
+    /// Inserts the text of `name` before the semicolon that terminates the declaration
+    /// or statement, which is the first semicolon at or after `end_token`.
+    fn add(&mut self, ctx: &dyn TokenAccess, name: TokenId, end_token: TokenId) {
+        let Some(semicolon) = (end_token.idx()..)
+            .map_while(|idx| ctx.get_token(TokenId::new(idx)))
+            .find(|token| token.kind == Kind::SemiColon)
+        else {
+            return;
+        };
+        let Some(name) = text_at(ctx.get_pos(name)) else {
+            return;
+        };
+        let start = semicolon.pos.start();
+        self.fixes.push(Fix {
+            rule: FixRule::EndLabel,
+            title: format!("Repeat '{name}' after 'end'"),
+            source: semicolon.pos.source.clone(),
+            edits: vec![TextEdit::new(Range::new(start, start), format!(" {name}"))],
+        });
+    }
+}
+
+impl Visitor for EndLabels {
+    fn visit_declaration(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        decl: &FoundDeclaration<'_>,
+    ) -> ControlFlow<()> {
+        let (name, end_ident_pos, end_token) = match decl.ast {
+            DeclarationItem::Entity(unit) => {
+                (unit.ident.tree.token, unit.end_ident_pos, unit.end_token)
+            }
+            DeclarationItem::Architecture(unit) => {
+                (unit.ident.tree.token, unit.end_ident_pos, unit.end_token)
+            }
+            DeclarationItem::Package(unit) => {
+                (unit.ident.tree.token, unit.end_ident_pos, unit.end_token)
+            }
+            DeclarationItem::PackageBody(unit) => {
+                (unit.ident.tree.token, unit.end_ident_pos, unit.end_token)
+            }
+            DeclarationItem::Configuration(unit) => {
+                (unit.ident.tree.token, unit.end_ident_pos, unit.end_token)
+            }
+            DeclarationItem::Context(unit) => {
+                (unit.ident.tree.token, unit.end_ident_pos, unit.end_token)
+            }
+            DeclarationItem::Subprogram(body) => {
+                let designator = match &body.specification {
+                    SubprogramSpecification::Procedure(procedure) => &procedure.designator,
+                    SubprogramSpecification::Function(function) => &function.designator,
+                };
+                (designator.tree.token, body.end_ident_pos, body.end_token)
+            }
+            _ => return ControlFlow::Continue(()),
+        };
+        if end_ident_pos.is_none() {
+            self.add(ctx, name, end_token);
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_concurrent_statement(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        statement: &LabeledConcurrentStatement,
+    ) -> ControlFlow<()> {
+        use ConcurrentStatement::*;
+        if let Some(label) = &statement.label.tree {
+            if matches!(
+                statement.statement.item,
+                Block(_) | Process(_) | ForGenerate(_) | IfGenerate(_) | CaseGenerate(_)
+            ) && statement.statement.item.end_label_pos().is_none()
+            {
+                self.add(ctx, label.token, statement.statement.span.end_token);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_sequential_statement(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        statement: &LabeledSequentialStatement,
+    ) -> ControlFlow<()> {
+        use SequentialStatement::*;
+        if let Some(label) = &statement.label.tree {
+            if matches!(statement.statement.item, If(_) | Case(_) | Loop(_))
+                && statement.statement.item.end_label_pos().is_none()
+            {
+                self.add(ctx, label.token, statement.statement.span.end_token);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Produces one fix for every design unit, subprogram body and labeled compound statement
+/// that does not repeat its name after `end`, i.e. `end entity;` becomes `end entity ent;`.
+pub(crate) fn end_label_fixes(libraries: &[&Library]) -> Vec<Fix> {
+    let mut labels = EndLabels::default();
+    for library in libraries {
+        for unit in library.units() {
+            let data = unit.unit.expect_analyzed();
+            let _ = walk(&*data, &unit.tokens, &mut labels);
+        }
+    }
+    labels.fixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::fix::apply_edits;
+    use crate::syntax::test::check_no_diagnostics;
+
+    #[test]
+    fn adds_missing_end_labels() {
+        let mut builder = LibraryBuilder::new();
+        let text = "\
+entity Ent is
+end entity;
+
+architecture a of ent is
+  procedure proc is
+  begin
+    lp: loop
+    end loop;
+  end;
+begin
+  main: process
+  begin
+  end process main;
+
+  gen: if true generate
+  end generate;
+end architecture a;";
+        let code = builder.code("libname", text);
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let libraries = root
+            .libraries()
+            .filter(|library| library.name().name_utf8() == "libname")
+            .collect::<Vec<_>>();
+        let fixes = end_label_fixes(&libraries);
+        assert_eq!(fixes.len(), 4);
+        assert!(fixes
+            .iter()
+            .any(|fix| fix.title == "Repeat 'Ent' after 'end'"));
+        assert!(fixes.iter().all(|fix| fix.source == *code.source()));
+        let edits: Vec<_> = fixes.into_iter().flat_map(|fix| fix.edits).collect();
+        assert_eq!(
+            apply_edits(text, &edits),
+            "\
+entity Ent is
+end entity Ent;
+
+architecture a of ent is
+  procedure proc is
+  begin
+    lp: loop
+    end loop lp;
+  end proc;
+begin
+  main: process
+  begin
+  end process main;
+
+  gen: if true generate
+  end generate gen;
+end architecture a;"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::{Fix, FixRule, TextEdit};
+use crate::analysis::Library;
+use crate::{kind_str, SrcPos};
+
+/// Returns the text of a single-line source position.
+pub(super) fn text_at(pos: &SrcPos) -> Option<String> {
+    let range = pos.range();
+    if range.start.line != range.end.line {
+        return None;
+    }
+    let contents = pos.source.contents();
+    let line = contents.get_line(range.start.line as usize)?;
+    let mut character = 0;
+    let mut text = String::new();
+    for chr in line.chars() {
+        if character >= range.end.character {
+            break;
+        }
+        if character >= range.start.character {
+            text.push(chr);
+        }
+        character += chr.len_utf16() as u32;
+    }
+    Some(text)
+}
+
+/// Produces one fix for every keyword that is not written in lower case.
+/// The keywords are those of the standard that each unit was parsed with.
+pub(crate) fn keyword_case_fixes(libraries: &[&Library]) -> Vec<Fix> {
+    let mut fixes = Vec::new();
+    for library in libraries {
+        for unit in library.units() {
+            let keywords = unit.standard.keywords();
+            for token in unit.tokens.iter() {
+                if !keywords.contains(&token.kind) {
+                    continue;
+                }
+                let expected = kind_str(token.kind);
+                let Some(actual) = text_at(&token.pos) else {
+                    continue;
+                };
+                if actual != expected && actual.eq_ignore_ascii_case(expected) {
+                    fixes.push(Fix {
+                        rule: FixRule::KeywordCase,
+                        title: format!("Write keyword '{actual}' as '{expected}'"),
+                        source: token.pos.source.clone(),
+                        edits: vec![TextEdit::new(token.pos.range(), expected)],
+                    });
+                }
+            }
+        }
+    }
+    fixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::fix::apply_edits;
+    use crate::syntax::test::check_no_diagnostics;
+    use crate::VHDLStandard;
+
+    #[test]
+    fn lowercases_keywords() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+ENTITY ent IS
+End Entity;
+
+architecture a of ent is
+begin
+end architecture;",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let libraries = root.libraries().collect::<Vec<_>>();
+        let fixes = keyword_case_fixes(&libraries);
+        assert_eq!(fixes.len(), 4);
+        let edits: Vec<_> = fixes.into_iter().flat_map(|fix| fix.edits).collect();
+        let text = code.source().contents().get_line(0).unwrap().to_owned()
+            + code.source().contents().get_line(1).unwrap();
+        assert_eq!(apply_edits(&text, &edits), "entity ent is\nend entity;\n");
+    }
+
+    #[test]
+    fn uses_the_keywords_of_the_standard_of_the_unit() {
+        let mut builder = LibraryBuilder::with_standard(VHDLStandard::VHDL2019);
+        let code = builder.code(
+            "libname",
+            "\
+package pkg is
+  type rec_t is record
+    foo : bit;
+  end record;
+  VIEW rec_view of rec_t is
+    foo : in;
+  end view;
+end package;",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let libraries = root.libraries().collect::<Vec<_>>();
+        let fixes = keyword_case_fixes(&libraries);
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(
+            fixes[0].edits,
+            vec![TextEdit::new(code.s1("VIEW").pos().range(), "view")]
+        );
+    }
+}
@@ -0,0 +1,100 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::{Fix, FixRule, TextEdit};
+use crate::analysis::{DesignRoot, Library};
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::SensitivityList;
+use crate::lint::sensitivity::{for_each_process, superfluous_sensitivity};
+use crate::Range;
+
+/// Produces one fix for every name of a sensitivity list that denotes a signal
+/// that is never read by the process. The name is removed together with
+/// the comma that separates it from its neighbour.
+/// No fixes are produced when none of the names would remain.
+pub(crate) fn superfluous_sensitivity_fixes(root: &DesignRoot, libraries: &[&Library]) -> Vec<Fix> {
+    let mut fixes = Vec::new();
+    for library in libraries {
+        for unit in library.units() {
+            for_each_process(unit, |ctx, process| {
+                let Some(WithTokenSpan {
+                    item: SensitivityList::Names(names),
+                    ..
+                }) = &process.sensitivity_list
+                else {
+                    return;
+                };
+                let superfluous = superfluous_sensitivity(root, ctx, process);
+                if superfluous.len() == names.len() {
+                    return;
+                }
+                for (idx, name, _) in superfluous {
+                    let pos = name.pos(ctx);
+                    // Remove `name, ` for the first name and `, name` otherwise
+                    let range = if idx == 0 {
+                        Range::new(pos.start(), names[1].pos(ctx).start())
+                    } else {
+                        Range::new(names[idx - 1].pos(ctx).end(), pos.end())
+                    };
+                    fixes.push(Fix {
+                        rule: FixRule::SuperfluousSensitivity,
+                        title: "Remove signal from sensitivity list".to_owned(),
+                        source: pos.source.clone(),
+                        edits: vec![TextEdit::delete(range)],
+                    });
+                }
+            });
+        }
+    }
+    fixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::fix::apply_edits;
+    use crate::syntax::test::check_no_diagnostics;
+
+    #[test]
+    fn removes_signals_that_are_not_read_from_sensitivity_lists() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "\
+entity ent is
+  port (a, b, c : in bit; q : out bit);
+end entity;
+
+architecture arch of ent is
+begin
+  process (a, b, c)
+  begin
+    q <= b;
+  end process;
+
+  process (a)
+  begin
+    q <= '0';
+  end process;
+end architecture;",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let libraries = root.libraries().collect::<Vec<_>>();
+        let fixes = superfluous_sensitivity_fixes(&root, &libraries);
+        assert_eq!(fixes.len(), 2);
+        let edits: Vec<_> = fixes.into_iter().flat_map(|fix| fix.edits).collect();
+        let contents = code.source().contents();
+        let text = (0..contents.num_lines())
+            .map(|line| contents.get_line(line).unwrap().to_owned())
+            .collect::<String>();
+        let fixed = apply_edits(&text, &edits);
+        assert_eq!(fixed.lines().nth(6), Some("  process (b)"));
+        assert_eq!(fixed.lines().nth(11), Some("  process (a)"));
+    }
+}
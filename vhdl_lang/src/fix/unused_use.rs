@@ -0,0 +1,300 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::{Fix, FixRule, TextEdit};
+use crate::analysis::{DesignRoot, Library, LockedUnit};
+use crate::ast::search::{Search, SearchState, Searcher};
+use crate::ast::{ContextItem, HasIdent, Name};
+use crate::named_entity::{Reference, Related};
+use crate::syntax::{HasTokenSpan, TokenAccess};
+use crate::{AnyEntKind, Design, EntRef, Position, Range, SrcPos};
+
+/// Collects all referenced named entities outside of use clauses.
+struct ReferenceSearcher<'a> {
+    root: &'a DesignRoot,
+    excluded: Vec<SrcPos>,
+    references: Vec<EntRef<'a>>,
+}
+
+impl<'a> Searcher for ReferenceSearcher<'a> {
+    fn search_pos_with_ref(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        reference: &Reference,
+    ) -> SearchState {
+        if let Some(id) = reference.get() {
+            let excluded = self.excluded.iter().any(|use_pos| {
+                use_pos.source == pos.source
+                    && use_pos.range().contains(pos.start())
+                    && use_pos.range().contains(pos.end())
+            });
+            if !excluded {
+                self.references.push(self.root.get_ent(id));
+            }
+        }
+        SearchState::NotFinished
+    }
+}
+
+/// What a single name of a use clause makes visible
+enum UsedName<'a> {
+    /// `use lib.pkg.all`
+    All(EntRef<'a>),
+    /// `use lib.pkg.name`
+    Selected(EntRef<'a>),
+}
+
+impl<'a> UsedName<'a> {
+    fn from_name(root: &'a DesignRoot, name: &Name) -> Option<UsedName<'a>> {
+        match name {
+            Name::SelectedAll(prefix) => {
+                let ent = root.get_ent(prefix.item.get_suffix_reference()?);
+                // Only packages are considered, `use lib.all` is always kept
+                if matches!(
+                    ent.kind(),
+                    AnyEntKind::Design(Design::Package(..) | Design::PackageInstance(..))
+                ) {
+                    Some(UsedName::All(ent))
+                } else {
+                    None
+                }
+            }
+            Name::Selected(..) => {
+                let ent = root.get_ent(name.get_suffix_reference()?);
+                if matches!(ent.kind(), AnyEntKind::Library | AnyEntKind::Design(_)) {
+                    // Use clauses of design units are still needed for dependencies
+                    None
+                } else {
+                    Some(UsedName::Selected(ent))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn is_used_by(&self, ent: EntRef<'_>) -> bool {
+        match self {
+            UsedName::All(pkg) => is_declared_within(ent, pkg),
+            UsedName::Selected(used) => {
+                ent.id() == used.id()
+                    || (ent.designator() == used.designator()
+                        && ent.parent.map(|p| p.id()) == used.parent.map(|p| p.id()))
+                    || matches!(ent.related, Related::ImplicitOf(of) if of.id() == used.id())
+            }
+        }
+    }
+}
+
+fn is_declared_within(ent: EntRef<'_>, region: EntRef<'_>) -> bool {
+    let mut parent = ent.parent;
+    while let Some(ent) = parent {
+        if ent.id() == region.id() {
+            return true;
+        }
+        parent = ent.parent;
+    }
+    false
+}
+
+/// Returns the range to delete when removing `pos`.
+/// When the use clause is the only thing on its lines, the entire lines are removed.
+fn deletion_range(pos: &SrcPos) -> Range {
+    let range = pos.range();
+    let contents = pos.source.contents();
+    let is_blank = |text: &str| text.chars().all(|chr| chr.is_whitespace());
+
+    let before = contents
+        .get_line(range.start.line as usize)
+        .map(|line| {
+            line.chars()
+                .scan(0, |character, chr| {
+                    let current = *character;
+                    *character += chr.len_utf16() as u32;
+                    Some((current, chr))
+                })
+                .take_while(|(character, _)| *character < range.start.character)
+                .map(|(_, chr)| chr)
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+    let after = contents
+        .get_line(range.end.line as usize)
+        .map(|line| {
+            line.chars()
+                .scan(0, |character, chr| {
+                    let current = *character;
+                    *character += chr.len_utf16() as u32;
+                    Some((current, chr))
+                })
+                .skip_while(|(character, _)| *character < range.end.character)
+                .map(|(_, chr)| chr)
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+
+    if is_blank(&before) && is_blank(&after) {
+        Range::new(
+            Position::new(range.start.line, 0),
+            Position::new(range.end.line + 1, 0),
+        )
+    } else {
+        range
+    }
+}
+
+fn unused_use_fixes_in_unit(
+    root: &DesignRoot,
+    unit: &LockedUnit,
+    scope: &[&LockedUnit],
+) -> Vec<Fix> {
+    let design_unit = unit.unit.expect_analyzed();
+    let Some(context_clause) = design_unit.context_clause() else {
+        return Vec::new();
+    };
+
+    let use_clauses: Vec<_> = context_clause
+        .iter()
+        .filter_map(|item| match item {
+            ContextItem::Use(use_clause) => Some((use_clause, item.get_pos(&unit.tokens))),
+            _ => None,
+        })
+        .collect();
+    if use_clauses.is_empty() {
+        return Vec::new();
+    }
+
+    let mut searcher = ReferenceSearcher {
+        root,
+        excluded: use_clauses.iter().map(|(_, pos)| pos.clone()).collect(),
+        references: Vec::new(),
+    };
+    for other in scope {
+        let other_unit = other.unit.expect_analyzed();
+        // Do not risk removing needed use clauses when names are possibly unresolved
+        if !other_unit.result().diagnostics.is_empty() {
+            return Vec::new();
+        }
+        let _ = other_unit.search(&other.tokens, &mut searcher);
+    }
+
+    let mut fixes = Vec::new();
+    for (use_clause, pos) in use_clauses {
+        let all_unused = use_clause.name_list.iter().all(|name| {
+            UsedName::from_name(root, &name.item)
+                .is_some_and(|used| !searcher.references.iter().any(|ent| used.is_used_by(ent)))
+        });
+        if all_unused {
+            fixes.push(Fix {
+                rule: FixRule::UnusedUse,
+                title: "Remove unused use clause".to_owned(),
+                source: pos.source.clone(),
+                edits: vec![TextEdit::delete(deletion_range(&pos))],
+            });
+        }
+    }
+    fixes
+}
+
+/// Produces one fix for every use clause in a context clause where
+/// none of the names made visible are referenced.
+/// Use clauses of primary units are considered used when referenced
+/// from the primary unit or any of its secondary units.
+pub(crate) fn unused_use_fixes(root: &DesignRoot, libraries: &[&Library]) -> Vec<Fix> {
+    let mut fixes = Vec::new();
+    for library in libraries {
+        for primary in library.primary_units() {
+            let secondaries: Vec<_> = library.secondary_units(primary.name()).collect();
+            let mut scope = vec![primary];
+            scope.extend(secondaries.iter().copied());
+            fixes.extend(unused_use_fixes_in_unit(root, primary, &scope));
+            for secondary in secondaries {
+                fixes.extend(unused_use_fixes_in_unit(root, secondary, &[secondary]));
+            }
+        }
+    }
+    fixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::fix::apply_edits;
+    use crate::syntax::test::check_no_diagnostics;
+
+    #[test]
+    fn removes_unused_use_clauses() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libname",
+            "\
+package pkg is
+  constant c : natural := 0;
+end package;
+
+package pkg2 is
+  constant d : natural := 0;
+end package;",
+        );
+        let code = builder.code(
+            "libname",
+            "\
+use work.pkg.all;
+use work.pkg2.all;
+use work.pkg2.d;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s : natural := c;
+begin
+end architecture;",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let libraries = root.libraries().collect::<Vec<_>>();
+        let fixes = unused_use_fixes(&root, &libraries);
+        assert_eq!(fixes.len(), 2);
+        let edits: Vec<_> = fixes.into_iter().flat_map(|fix| fix.edits).collect();
+        let text = (0..3)
+            .map(|line| code.source().contents().get_line(line).unwrap().to_owned())
+            .collect::<String>();
+        assert_eq!(apply_edits(&text, &edits), "use work.pkg.all;\n");
+    }
+
+    #[test]
+    fn keeps_use_clauses_of_selected_names() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "libname",
+            "\
+package pkg is
+  type enum_t is (alpha, beta);
+end package;",
+        );
+        builder.code(
+            "libname",
+            "\
+use work.pkg.enum_t;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s : enum_t;
+begin
+end architecture;",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let libraries = root.libraries().collect::<Vec<_>>();
+        assert!(unused_use_fixes(&root, &libraries).is_empty());
+    }
+}
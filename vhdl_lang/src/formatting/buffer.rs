@@ -5,6 +5,7 @@
 //
 // Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
 
+use crate::formatting::FormattingOptions;
 use crate::syntax::{Comment, Value};
 use crate::{kind_str, Token};
 use std::cmp::max;
@@ -28,12 +29,21 @@ pub struct Buffer {
 
 impl Buffer {
     pub fn new() -> Buffer {
+        Buffer::with_options(&FormattingOptions::default())
+    }
+
+    pub fn with_options(options: &FormattingOptions) -> Buffer {
+        let (indent_char, indent_width) = if options.use_tabs {
+            ('\t', 1)
+        } else {
+            (' ', options.indent_width)
+        };
         Buffer {
             inner: String::new(),
             insert_extra_newline: false,
             indentation: 0,
-            indent_char: ' ',
-            indent_width: 4,
+            indent_char,
+            indent_width,
         }
     }
 }
@@ -78,14 +88,7 @@ impl Buffer {
     }
 
     fn format_comment(&mut self, comment: &Comment) {
-        if !comment.multi_line {
-            self.push_str("--");
-            self.push_str(comment.value.trim_end())
-        } else {
-            self.push_str("/*");
-            self.push_str(&comment.value);
-            self.push_str("*/");
-        }
+        self.push_str(&comment.to_string())
     }
 
     fn format_leading_comments(&mut self, comments: &[Comment]) {
@@ -126,9 +126,9 @@ impl VHDLFormatter<'_> {
                 EntityAspect::Entity(entity, architecture) => {
                     self.format_name(entity.as_ref(), buffer);
                     if let Some(arch) = architecture {
-                        self.format_token_id(arch.token - 1, buffer);
-                        self.format_token_id(arch.token, buffer);
-                        self.format_token_id(arch.token + 1, buffer);
+                        self.format_token_id(arch.item.token - 1, buffer);
+                        self.format_token_id(arch.item.token, buffer);
+                        self.format_token_id(arch.item.token + 1, buffer);
                     }
                 }
                 EntityAspect::Configuration(config) => {
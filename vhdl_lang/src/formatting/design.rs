@@ -5,12 +5,14 @@
 //
 // Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
 
-use crate::ast::{AnyDesignUnit, AnyPrimaryUnit, AnySecondaryUnit, PackageBody};
+use crate::ast::{AnyDesignUnit, AnyPrimaryUnit, AnySecondaryUnit, PackageBody, VerificationUnit};
 use crate::formatting::buffer::Buffer;
+use crate::syntax::Kind::LeftBrace;
+use crate::syntax::TokenAccess;
 use crate::HasTokenSpan;
 use vhdl_lang::ast::PackageDeclaration;
 use vhdl_lang::formatting::VHDLFormatter;
-use vhdl_lang::{indented, TokenSpan};
+use vhdl_lang::{indented, TokenId, TokenSpan};
 
 impl VHDLFormatter<'_> {
     pub fn format_any_design_unit(&self, unit: &AnyDesignUnit, buffer: &mut Buffer, is_last: bool) {
@@ -34,6 +36,7 @@ impl VHDLFormatter<'_> {
                 self.format_package_instance(package_instance, buffer)
             }
             Context(context) => self.format_context(context, buffer),
+            VerificationUnit(unit) => self.format_verification_unit(unit, buffer),
         }
     }
 
@@ -88,12 +91,59 @@ impl VHDLFormatter<'_> {
         );
         self.format_token_id(body.span.end_token, buffer);
     }
+
+    /// PSL directives are not part of the AST.
+    /// Therefore, the items of verification units are formatted token by token,
+    /// keeping the line breaks and the presence of whitespace between tokens.
+    pub fn format_verification_unit(&self, unit: &VerificationUnit, buffer: &mut Buffer) {
+        self.format_context_clause(&unit.context_clause, buffer);
+        if let Some(item) = unit.context_clause.last() {
+            self.line_break_preserve_whitespace(item.span().end_token, buffer);
+        }
+        let end_token = unit.span.end_token;
+        let open_brace = unit
+            .span
+            .iter()
+            .find(|id| matches!(self.tokens.get_token(*id), Some(token) if token.kind == LeftBrace))
+            .unwrap_or(end_token);
+        // vunit <ident> [(<binding>)] {
+        self.format_tokens_verbatim(TokenSpan::new(unit.span.start_token, open_brace), buffer);
+        if open_brace + 1 < end_token {
+            indented!(buffer, {
+                self.whitespace_after(open_brace, buffer);
+                self.format_tokens_verbatim(TokenSpan::new(open_brace + 1, end_token - 1), buffer);
+            });
+        }
+        self.whitespace_after(end_token - 1, buffer);
+        self.format_token_id(end_token, buffer);
+    }
+
+    fn format_tokens_verbatim(&self, span: TokenSpan, buffer: &mut Buffer) {
+        for id in span.iter() {
+            self.format_token_id(id, buffer);
+            if id < span.end_token {
+                self.whitespace_after(id, buffer);
+            }
+        }
+    }
+
+    fn whitespace_after(&self, token_id: TokenId, buffer: &mut Buffer) {
+        let end = self.tokens.get_pos(token_id).end();
+        let next_start = self.tokens.get_pos(token_id + 1).start();
+        if next_start.line > end.line {
+            self.line_break_preserve_whitespace(token_id, buffer);
+        } else if next_start > end {
+            buffer.push_whitespace();
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use crate::analysis::tests::Code;
     use vhdl_lang::formatting::test_utils::check_formatted;
+    use vhdl_lang::formatting::{FormattingOptions, VHDLFormatter};
+    use vhdl_lang::{Position, Range};
 
     fn check_package_formatted(input: &str) {
         check_formatted(
@@ -301,4 +351,141 @@ package body foo is
 end package body;",
         )
     }
+
+    #[test]
+    fn format_verification_unit() {
+        check_design_unit_formatted(
+            "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+vunit props (ent(rtl)) {
+    signal tmp : std_logic;
+
+    default clock is rising_edge(clk);
+    assert always {req; ack} |=> rec.valid'stable;
+}",
+        );
+        check_design_unit_formatted(
+            "\
+vunit props {}",
+        );
+    }
+
+    #[test]
+    fn format_design_file_with_options() {
+        let code = Code::new(
+            "\
+entity ent is
+port (clk : in bit);
+end entity;",
+        );
+        let file = code.design_file();
+        assert_eq!(
+            VHDLFormatter::format_design_file_with_options(
+                &file,
+                &FormattingOptions {
+                    indent_width: 2,
+                    use_tabs: false,
+                }
+            ),
+            "entity ent is\n  port (\n    clk: in bit\n  );\nend entity;"
+        );
+        assert_eq!(
+            VHDLFormatter::format_design_file_with_options(
+                &file,
+                &FormattingOptions {
+                    indent_width: 2,
+                    use_tabs: true,
+                }
+            ),
+            "entity ent is\n\tport (\n\t\tclk: in bit\n\t);\nend entity;"
+        );
+    }
+
+    #[test]
+    fn format_design_units_in_range() {
+        let text = "\
+-- the entity
+entity ent is
+port (clk : in bit);
+end entity; -- ent
+
+  -- the architecture
+architecture a of ent is
+signal sig : bit;
+begin
+end architecture;
+";
+        let code = Code::new(text);
+        let range = code.s1("signal").pos().range();
+        assert_eq!(
+            VHDLFormatter::format_design_units_in_range(
+                code.design_file(),
+                text,
+                range,
+                &FormattingOptions::default(),
+            ),
+            Some(
+                "\
+-- the entity
+entity ent is
+port (clk : in bit);
+end entity; -- ent
+
+  -- the architecture
+architecture a of ent is
+    signal sig: bit;
+begin
+end architecture;
+"
+                .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn format_design_units_in_range_keeps_line_endings() {
+        let text = "entity ent is\r\nend entity;\r\n\r\narchitecture a of ent is\r\nsignal sig : bit;\r\nbegin\r\nend architecture;\r\n";
+        let code = Code::new(text);
+        let range = code.s1("signal").pos().range();
+        assert_eq!(
+            VHDLFormatter::format_design_units_in_range(
+                code.design_file(),
+                text,
+                range,
+                &FormattingOptions::default(),
+            ),
+            Some(
+                "entity ent is\r\nend entity;\r\n\r\narchitecture a of ent is\r\n    signal sig: bit;\r\nbegin\r\nend architecture;\r\n"
+                    .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn format_design_units_in_range_without_overlapping_units() {
+        let text = "entity ent is\nend entity;\n\n\n";
+        let code = Code::new(text);
+        let range = Range::new(Position::new(3, 0), Position::new(3, 0));
+        assert_eq!(
+            VHDLFormatter::format_design_units_in_range(
+                code.design_file(),
+                text,
+                range,
+                &FormattingOptions::default(),
+            ),
+            None
+        );
+        let code = Code::new("");
+        assert_eq!(
+            VHDLFormatter::format_design_units_in_range(
+                code.design_file(),
+                "",
+                range,
+                &FormattingOptions::default(),
+            ),
+            None
+        );
+    }
 }
@@ -8,7 +8,7 @@
 use crate::ast::DesignFile;
 use crate::formatting::buffer::Buffer;
 use crate::syntax::Kind;
-use crate::{Token, TokenAccess};
+use crate::{Range, Token, TokenAccess, TokenId, TokenSpan};
 use vhdl_lang::ast::HasIdent;
 
 mod architecture;
@@ -28,6 +28,24 @@ mod statement;
 mod subprogram;
 mod token;
 
+/// The style of formatted files, which can be configured per project.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FormattingOptions {
+    /// The number of spaces per indentation level
+    pub indent_width: usize,
+    /// Indent using one tab per indentation level instead of spaces
+    pub use_tabs: bool,
+}
+
+impl Default for FormattingOptions {
+    fn default() -> Self {
+        FormattingOptions {
+            indent_width: 4,
+            use_tabs: false,
+        }
+    }
+}
+
 /// The formatter is the main entry point used for formatting a single
 /// Design Unit from AST representation to string representation. In that sense,
 /// the Formatter is the inverse to the Parser.
@@ -48,7 +66,15 @@ impl<'b> VHDLFormatter<'b> {
 
     /// Format a whole design file.
     pub fn format_design_file(file: &DesignFile) -> String {
-        let mut result = Buffer::new();
+        VHDLFormatter::format_design_file_with_options(file, &FormattingOptions::default())
+    }
+
+    /// Format a whole design file using the style of `options`.
+    pub fn format_design_file_with_options(
+        file: &DesignFile,
+        options: &FormattingOptions,
+    ) -> String {
+        let mut result = Buffer::with_options(options);
         for (i, (tokens, design_unit)) in file.design_units.iter().enumerate() {
             let formatter = VHDLFormatter::new(tokens);
             formatter.format_any_design_unit(
@@ -59,6 +85,53 @@ impl<'b> VHDLFormatter<'b> {
         }
         result.into()
     }
+
+    /// Format the design units of `file` that overlap `range`, where `file` was parsed
+    /// from the exact text `text`. The text of all other design units is kept as it is,
+    /// and so are the whitespace and comments before and after a formatted design unit.
+    /// Formatted design units use the line ending of the first line of `text`.
+    /// Returns `None` when no design unit overlaps `range`.
+    pub fn format_design_units_in_range(
+        file: DesignFile,
+        text: &str,
+        range: Range,
+        options: &FormattingOptions,
+    ) -> Option<String> {
+        let line_ending = line_ending(text);
+        let mut any_overlaps = false;
+        let mut result = String::new();
+        for (mut lossless, unit) in file.into_lossless(text) {
+            let (Some(first), Some(last)) = (lossless.tokens.first(), lossless.tokens.last())
+            else {
+                continue;
+            };
+            if first.pos.start() <= range.end && range.start <= last.pos.end() {
+                any_overlaps = true;
+                let mut tokens = lossless.tokens.clone();
+                if let Some(comments) = tokens.first_mut().and_then(|t| t.comments.as_mut()) {
+                    comments.leading.clear();
+                }
+                if let Some(comments) = tokens.last_mut().and_then(|t| t.comments.as_mut()) {
+                    comments.trailing = None;
+                }
+                let mut buffer = Buffer::with_options(options);
+                VHDLFormatter::new(&tokens).format_any_design_unit(&unit, &mut buffer, true);
+                let span = TokenSpan::new(TokenId::new(0), TokenId::new(tokens.len() - 1));
+                lossless.replace(span, buffer.as_str().replace('\n', line_ending));
+            }
+            result.push_str(&lossless.to_string());
+        }
+        any_overlaps.then_some(result)
+    }
+}
+
+/// The line ending of the first line of `text`, or `\n` if it has no line ending
+fn line_ending(text: &str) -> &'static str {
+    match text.find(['\n', '\r']) {
+        Some(idx) if text[idx..].starts_with("\r\n") => "\r\n",
+        Some(idx) if text[idx..].starts_with('\r') => "\r",
+        _ => "\n",
+    }
 }
 
 impl VHDLFormatter<'_> {
@@ -89,8 +89,18 @@ impl VHDLFormatter<'_> {
             self.format_interface_list(parameter, buffer);
         }
         buffer.push_whitespace();
-        // return
-        self.format_token_id(specification.return_type.span.start_token - 1, buffer);
+        if let Some(ident) = &specification.return_identifier {
+            // return
+            self.format_token_id(ident.tree.token - 1, buffer);
+            buffer.push_whitespace();
+            self.format_token_id(ident.tree.token, buffer);
+            buffer.push_whitespace();
+            // of
+            self.format_token_id(ident.tree.token + 1, buffer);
+        } else {
+            // return
+            self.format_token_id(specification.return_type.span.start_token - 1, buffer);
+        }
         buffer.push_whitespace();
         self.format_name(specification.return_type.as_ref(), buffer);
     }
@@ -179,7 +189,8 @@ impl VHDLFormatter<'_> {
 
 #[cfg(test)]
 mod test {
-    use crate::formatting::test_utils::check_formatted;
+    use crate::formatting::test_utils::{check_formatted, check_formatted_std};
+    use crate::VHDLStandard::VHDL2019;
 
     fn check_signature(input: &str) {
         check_formatted(
@@ -214,6 +225,13 @@ mod test {
         check_subprogram_declaration("function \"+\" return natural;");
         check_subprogram_declaration("impure function foo return natural;");
         check_subprogram_declaration("pure function foo return natural;");
+        check_formatted_std(
+            "function foo return ret of natural;",
+            "function foo return ret of natural;",
+            VHDL2019,
+            |code| code.subprogram_decl(),
+            |formatter, ast, buffer| formatter.format_subprogram_declaration(ast, buffer),
+        );
     }
 
     #[test]
@@ -0,0 +1,151 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Export of diagnostics as a JUnit XML report, such that CI systems like Jenkins and GitLab
+//! show them as failed tests.
+//!
+//! Every file with diagnostics is a test suite with one failed test case per diagnostic.
+//! Without any diagnostics, the report contains a single passed test case,
+//! since some CI systems treat an empty report as an error.
+//! Diagnostics that are disabled in the [SeverityMap] are not exported.
+
+use crate::{Diagnostic, SeverityMap};
+use itertools::Itertools;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Converts `diagnostics` to a JUnit XML report.
+/// Files within `base_dir` are referred to relative to it, such as files of a repository.
+pub fn to_junit(diagnostics: &[Diagnostic], severities: &SeverityMap, base_dir: &Path) -> String {
+    let shown = diagnostics
+        .iter()
+        .filter_map(|diagnostic| Some((diagnostic, severities[diagnostic.code]?)))
+        .collect_vec();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let tests = shown.len().max(1);
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"vhdl_lang\" tests=\"{tests}\" failures=\"{}\">",
+        shown.len()
+    );
+    if shown.is_empty() {
+        xml.push_str("  <testsuite name=\"vhdl_lang\" tests=\"1\" failures=\"0\">\n");
+        xml.push_str("    <testcase name=\"analysis\" classname=\"vhdl_lang\"/>\n");
+        xml.push_str("  </testsuite>\n");
+    }
+
+    let by_file = shown
+        .into_iter()
+        .into_group_map_by(|(diagnostic, _)| file_name(diagnostic, base_dir));
+    for (file_name, diagnostics) in by_file.into_iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
+        let file_name = escape(&file_name);
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{file_name}\" tests=\"{0}\" failures=\"{0}\">",
+            diagnostics.len()
+        );
+        for (diagnostic, severity) in diagnostics
+            .into_iter()
+            .sorted_by_key(|(diagnostic, _)| diagnostic.pos.range.start)
+        {
+            let line = diagnostic.pos.range.start.line + 1;
+            let severity: &str = severity.into();
+            let _ = writeln!(
+                xml,
+                "    <testcase name=\"{}: {} (line {line})\" classname=\"{file_name}\" file=\"{file_name}\" line=\"{line}\">",
+                diagnostic.code.as_str(),
+                escape(&diagnostic.message),
+            );
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\" type=\"{severity}\">{}</failure>",
+                escape(&diagnostic.message),
+                escape(&diagnostic.show(severities).unwrap_or_default()),
+            );
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>");
+    xml
+}
+
+fn file_name(diagnostic: &Diagnostic, base_dir: &Path) -> String {
+    let file_name = diagnostic.pos.file_name();
+    match file_name.strip_prefix(base_dir) {
+        Ok(relative) => relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .join("/"),
+        Err(_) => file_name.to_string_lossy().into_owned(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for chr in text.chars() {
+        match chr {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\t' => escaped.push(chr),
+            // Not allowed in XML 1.0
+            chr if chr.is_control() => {}
+            chr => escaped.push(chr),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::error_codes::ErrorCode;
+    use crate::syntax::test::Code;
+
+    #[test]
+    fn diagnostics_are_failed_test_cases_grouped_by_file() {
+        let code = Code::new("signal sig : bit;\nsignal sig : bit;");
+        let duplicate = Diagnostic::new(
+            code.s("sig :", 2).s1("sig"),
+            "Duplicate declaration of 'sig'",
+            ErrorCode::Duplicate,
+        );
+        let mut severities = SeverityMap::default();
+        severities[ErrorCode::Unused] = None;
+        let unused = Diagnostic::new(code.s1("sig"), "Unused", ErrorCode::Unused);
+
+        let base_dir = code.source().file_name().parent().unwrap();
+        let file_name = code.source().file_name().file_name().unwrap();
+        let file_name = escape(&file_name.to_string_lossy());
+        let junit = to_junit(&[duplicate.clone(), unused], &severities, base_dir);
+        assert_eq!(
+            junit,
+            format!(
+                "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites name=\"vhdl_lang\" tests=\"1\" failures=\"1\">
+  <testsuite name=\"{file_name}\" tests=\"1\" failures=\"1\">
+    <testcase name=\"duplicate: Duplicate declaration of &apos;sig&apos; (line 2)\" classname=\"{file_name}\" file=\"{file_name}\" line=\"2\">
+      <failure message=\"Duplicate declaration of &apos;sig&apos;\" type=\"error\">{}</failure>
+    </testcase>
+  </testsuite>
+</testsuites>",
+                escape(&duplicate.show_default())
+            )
+        );
+    }
+
+    #[test]
+    fn report_without_diagnostics_has_a_passed_test_case() {
+        let junit = to_junit(&[], &SeverityMap::default(), Path::new(""));
+        assert!(junit.contains("<testcase name=\"analysis\" classname=\"vhdl_lang\"/>"));
+        assert!(junit.contains("failures=\"0\""));
+    }
+}
@@ -0,0 +1,388 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Tokenization of VHDL sources.
+//!
+//! The [Lexer] splits the text of a source into [tokens](Token) without parsing or analyzing it,
+//! such that tools like formatters, style checkers and syntax highlighters can reuse it.
+//! Each token has a [kind](Kind), a [value](Value) and a position in the source.
+//! Comments are attached to the token that they precede or, on the same line, follow.
+//! Tool directives are tokenized as a grave accent, the directive identifier
+//! and the remaining text of the line as a single [Text](Kind::Text) token.
+//!
+//! [LosslessTokens] additionally keep the whitespace and comments between the tokens,
+//! such that a source can be rewritten without changing the text that is not touched.
+//! The formatter uses them to format only some of the design units of a source, see
+//! [VHDLFormatter::format_design_units_in_range](crate::VHDLFormatter::format_design_units_in_range).
+//! The text is kept as one string per token rather than in a rope, which suffices for
+//! rewriting a source once. Repeated incremental edits of large sources are not supported yet.
+
+pub use crate::syntax::{kind_str, Comment, Kind, Token, TokenComments, Value};
+
+use crate::ast::{AnyDesignUnit, DesignFile};
+use crate::data::{ContentReader, DiagnosticHandler};
+use crate::fix::{byte_offset, line_starts};
+use crate::syntax::{Symbols, Tokenizer};
+use crate::{Position, Range, Source, TokenAccess, TokenId, TokenSpan, VHDLStandard};
+use std::fmt;
+
+/// The tokens of a source
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Tokens {
+    pub tokens: Vec<Token>,
+    /// Comments after the last token of the source
+    pub final_comments: Vec<Comment>,
+}
+
+pub struct Lexer {
+    symbols: Symbols,
+}
+
+impl Lexer {
+    /// Creates a lexer with the keywords of `standard`
+    pub fn new(standard: VHDLStandard) -> Lexer {
+        Lexer {
+            symbols: Symbols::from_standard(standard),
+        }
+    }
+
+    /// Returns the tokens of `source`.
+    /// Text that cannot be tokenized is reported as a diagnostic and skipped.
+    pub fn tokenize(&self, source: &Source, diagnostics: &mut dyn DiagnosticHandler) -> Tokens {
+        let contents = source.contents();
+        let mut tokenizer = Tokenizer::new(&self.symbols, source, ContentReader::new(&contents));
+        let mut tokens = Vec::new();
+        loop {
+            match tokenizer.pop() {
+                Ok(Some(token)) => {
+                    let is_directive = token.kind == Kind::GraveAccent;
+                    tokens.push(token);
+                    if is_directive {
+                        match tokenizer.pop() {
+                            Ok(Some(identifier)) => tokens.push(identifier),
+                            Ok(None) => break,
+                            Err(err) => diagnostics.push(err),
+                        }
+                        match tokenizer.text_until_newline() {
+                            Ok(text) => tokens.push(text),
+                            Err(err) => diagnostics.push(err),
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(err) => diagnostics.push(err),
+            }
+        }
+        Tokens {
+            tokens,
+            final_comments: tokenizer.get_final_comments().unwrap_or_default(),
+        }
+    }
+
+    /// Returns the tokens of `source` together with all text between them,
+    /// where `text` is the exact text that `source` was created from
+    pub fn tokenize_lossless(
+        &self,
+        source: &Source,
+        text: &str,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> LosslessTokens {
+        let tokens = self.tokenize(source, diagnostics).tokens;
+        let range = Range::new(Position::default(), end_of(text));
+        LosslessTokens::from_tokens(text, tokens, range)
+    }
+}
+
+/// Tokens together with the exact text of the source around them.
+/// Writing the tokens using [Display](fmt::Display) reproduces the text of the source,
+/// including its line endings.
+///
+/// The tokens are indexed the same way as the tokens that they were created from,
+/// such that the [TokenSpan](struct@TokenSpan) of an AST element denotes the same tokens.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct LosslessTokens {
+    pub tokens: Vec<Token>,
+    /// The text of each token, as it appears in the source unless it has been replaced
+    pub text: Vec<String>,
+    /// The whitespace and comments before each token
+    pub leading: Vec<String>,
+    /// The whitespace and comments after the last token
+    pub trailing: String,
+}
+
+impl LosslessTokens {
+    /// Creates lossless tokens from `tokens` of a source with the exact text `source_text`,
+    /// where the text of `range` that is not part of a token becomes whitespace.
+    /// Positions are interpreted the same way as for [apply_edits](crate::apply_edits).
+    pub fn from_tokens(source_text: &str, tokens: Vec<Token>, range: Range) -> LosslessTokens {
+        let line_starts = line_starts(source_text);
+        let offset = |pos| byte_offset(source_text, &line_starts, pos);
+        let mut text = Vec::with_capacity(tokens.len());
+        let mut leading = Vec::with_capacity(tokens.len());
+        let mut end = offset(range.start);
+        for token in tokens.iter() {
+            let token_range = token.pos.range();
+            let start = offset(token_range.start).max(end);
+            leading.push(source_text[end..start].to_owned());
+            end = offset(token_range.end).max(start);
+            text.push(source_text[start..end].to_owned());
+        }
+        let range_end = offset(range.end).max(end);
+        LosslessTokens {
+            tokens,
+            text,
+            leading,
+            trailing: source_text[end..range_end].to_owned(),
+        }
+    }
+
+    /// The exact text of `span`, including the whitespace and comments within it
+    pub fn text_of(&self, span: TokenSpan) -> String {
+        let start = span.start_token.idx();
+        let end = span.end_token.idx();
+        let mut result = self.text[start].clone();
+        for idx in start + 1..=end {
+            result.push_str(&self.leading[idx]);
+            result.push_str(&self.text[idx]);
+        }
+        result
+    }
+
+    /// Replaces the text of `span` with `text`.
+    /// The whitespace before the span and after it is kept.
+    pub fn replace(&mut self, span: TokenSpan, text: impl Into<String>) {
+        let start = span.start_token.idx();
+        let end = span.end_token.idx();
+        self.text[start] = text.into();
+        for idx in start + 1..=end {
+            self.leading[idx].clear();
+            self.text[idx].clear();
+        }
+    }
+}
+
+impl TokenAccess for LosslessTokens {
+    fn get_token(&self, id: TokenId) -> Option<&Token> {
+        self.tokens.get_token(id)
+    }
+
+    fn index(&self, id: TokenId) -> &Token {
+        self.tokens.index(id)
+    }
+
+    fn get_token_slice(&self, start_id: TokenId, end_id: TokenId) -> &[Token] {
+        self.tokens.get_token_slice(start_id, end_id)
+    }
+}
+
+impl fmt::Display for LosslessTokens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (leading, text) in self.leading.iter().zip(self.text.iter()) {
+            write!(f, "{leading}{text}")?;
+        }
+        write!(f, "{}", self.trailing)
+    }
+}
+
+/// The position after the last character of `text`, including its final line ending
+fn end_of(text: &str) -> Position {
+    let line_starts = line_starts(text);
+    let last_line = &text[line_starts[line_starts.len() - 1]..];
+    Position::new(
+        (line_starts.len() - 1) as u32,
+        last_line.chars().map(|chr| chr.len_utf16() as u32).sum(),
+    )
+}
+
+impl DesignFile {
+    /// Converts the tokens of each design unit of this file, that was parsed from
+    /// the exact text `text`, to [LosslessTokens]. The whitespace and text between two
+    /// design units becomes part of the second unit, such that writing all units
+    /// reproduces the text.
+    pub fn into_lossless(self, text: &str) -> Vec<(LosslessTokens, AnyDesignUnit)> {
+        let mut start = Position::default();
+        let end = end_of(text);
+        let num_units = self.design_units.len();
+        self.design_units
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (tokens, unit))| {
+                let unit_end = if idx + 1 == num_units {
+                    end
+                } else {
+                    tokens.last().map_or(start, |token| token.pos.end())
+                };
+                let range = Range::new(start, unit_end);
+                start = unit_end;
+                (LosslessTokens::from_tokens(text, tokens, range), unit)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+    use crate::Diagnostic;
+
+    fn tokenize(code: &Code) -> (Tokens, Vec<Diagnostic>) {
+        let mut diagnostics = Vec::new();
+        let tokens = Lexer::new(VHDLStandard::default()).tokenize(code.source(), &mut diagnostics);
+        (tokens, diagnostics)
+    }
+
+    #[test]
+    fn tokenizes_kinds_positions_and_comments() {
+        let code = Code::new(
+            "\
+-- leading
+signal sig : bit; -- trailing
+-- final
+",
+        );
+        let (tokens, diagnostics) = tokenize(&code);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(
+            tokens
+                .tokens
+                .iter()
+                .map(|token| token.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                Kind::Signal,
+                Kind::Identifier,
+                Kind::Colon,
+                Kind::Identifier,
+                Kind::SemiColon
+            ]
+        );
+        assert_eq!(tokens.tokens[1].pos, code.s1("sig :").s1("sig").pos());
+
+        let comments = tokens.tokens[0].comments.as_ref().unwrap();
+        assert_eq!(comments.leading[0].value, " leading");
+        let comments = tokens.tokens[4].comments.as_ref().unwrap();
+        assert_eq!(comments.trailing.as_ref().unwrap().value, " trailing");
+        assert_eq!(tokens.final_comments.len(), 1);
+        assert_eq!(tokens.final_comments[0].value, " final");
+    }
+
+    #[test]
+    fn tokenizes_tool_directives() {
+        let code = Code::new("`warning \"some text\"\nend;");
+        let (tokens, diagnostics) = tokenize(&code);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(
+            tokens
+                .tokens
+                .iter()
+                .map(|token| token.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                Kind::GraveAccent,
+                Kind::Identifier,
+                Kind::Text,
+                Kind::End,
+                Kind::SemiColon
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_text_that_cannot_be_tokenized() {
+        let code = Code::new("signal ! sig");
+        let (tokens, diagnostics) = tokenize(&code);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            tokens
+                .tokens
+                .iter()
+                .map(|token| token.kind)
+                .collect::<Vec<_>>(),
+            vec![Kind::Signal, Kind::Identifier]
+        );
+    }
+
+    #[test]
+    fn lossless_tokens_reproduce_the_source() {
+        let text = "\
+-- leading
+entity  ent is\t-- trailing
+  /* block
+     comment */ port (clk : in bit);
+`warning \"some text\"
+end entity;
+
+";
+        let code = Code::new(text);
+        let mut diagnostics = Vec::new();
+        let tokens = Lexer::new(VHDLStandard::default()).tokenize_lossless(
+            code.source(),
+            text,
+            &mut diagnostics,
+        );
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(tokens.to_string(), text);
+        assert_eq!(tokens.leading[0], "-- leading\n");
+        assert_eq!(tokens.text[1], "ent");
+        assert_eq!(tokens.leading[1], "  ");
+        assert_eq!(tokens.trailing, "\n\n");
+    }
+
+    #[test]
+    fn lossless_tokens_keep_line_endings() {
+        let text = "entity ent is -- comment\r\n\rend entity;\r\n";
+        let code = Code::new(text);
+        let mut diagnostics = Vec::new();
+        let tokens = Lexer::new(VHDLStandard::default()).tokenize_lossless(
+            code.source(),
+            text,
+            &mut diagnostics,
+        );
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(tokens.to_string(), text);
+        assert_eq!(tokens.leading[3], " -- comment\r\n\r");
+        assert_eq!(tokens.trailing, "\r\n");
+    }
+
+    #[test]
+    fn lossless_design_units_can_be_rewritten() {
+        let text = "\
+entity ent is -- the entity
+end  entity;
+
+-- the architecture
+architecture a of ent is
+begin
+end architecture;
+";
+        let code = Code::new(text);
+        let mut units = code.design_file().into_lossless(text);
+        assert_eq!(
+            units
+                .iter()
+                .map(|(tokens, _)| tokens.to_string())
+                .collect::<String>(),
+            text
+        );
+
+        let (tokens, unit) = &mut units[1];
+        let AnyDesignUnit::Secondary(crate::ast::AnySecondaryUnit::Architecture(arch)) = unit
+        else {
+            panic!("Expected an architecture");
+        };
+        assert_eq!(
+            tokens.text_of(arch.span),
+            &text[text.find("architecture a").unwrap()..text.len() - 1]
+        );
+        let ident = arch.ident.tree.token;
+        tokens.replace(ident.into(), "rtl");
+        assert_eq!(
+            tokens.to_string(),
+            "\n\n-- the architecture\narchitecture rtl of ent is\nbegin\nend architecture;\n"
+        );
+    }
+}
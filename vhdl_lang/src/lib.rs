@@ -14,31 +14,51 @@ extern crate self as vhdl_lang;
 pub mod ast;
 #[macro_use]
 mod analysis;
+pub mod anonymize;
+pub mod baseline;
+pub mod changes;
+pub mod compile_order;
 mod config;
 mod data;
+pub mod dependencies;
+pub mod junit;
+pub mod lexer;
 mod lint;
+pub mod lsif;
 mod named_entity;
 mod project;
+mod rename;
+pub mod sarif;
+pub mod scaffold;
+pub mod snapshot;
 mod syntax;
+pub mod tags;
 
 mod completion;
+mod fix;
 mod formatting;
 mod standard;
 
-pub use crate::config::Config;
+pub use crate::config::{Config, TopLevel};
 pub use crate::data::{
-    Diagnostic, Latin1String, Message, MessageHandler, MessagePrinter, MessageType,
+    Diagnostic, ErrorCode, Latin1String, Message, MessageHandler, MessagePrinter, MessageType,
     NullDiagnostics, NullMessages, Position, Range, Severity, SeverityMap, Source, SrcPos,
 };
-pub use formatting::VHDLFormatter;
+pub use crate::lint::banned::BannedConstruct;
+pub use fix::{apply_edits, split_lines_inclusive, Fix, FixRule, TextEdit};
+pub use formatting::{FormattingOptions, VHDLFormatter};
 
-pub use crate::analysis::EntHierarchy;
+pub use crate::analysis::{
+    AccessLevel, DesignUnitSummary, EntHierarchy, HierarchyNode, HierarchyNodeKind, InstanceInfo,
+    InstancePath, LibraryUnits, ReferenceKind, SignalAccess,
+};
 pub use crate::named_entity::{
     AnyEnt, AnyEntKind, Concurrent, Design, EntRef, EntityId, HasEntityId, InterfaceEnt, Object,
     Overloaded, Reference, Related, Sequential, Type,
 };
 
 pub use crate::project::{Project, SourceFile};
+pub use crate::rename::RenameError;
 pub use crate::syntax::{
     kind_str, HasTokenSpan, ParserResult, Token, TokenAccess, TokenId, TokenSpan, VHDLParser,
 };
@@ -4,4 +4,14 @@
 //
 // Copyright (c) 2022, Olof Kraigher olof.kraigher@gmail.com
 
+pub mod banned;
+pub mod compatibility;
+pub mod component_binding;
 pub mod dead_code;
+pub mod delay;
+pub mod initialization;
+pub mod missing_return;
+pub mod sensitivity;
+pub mod signal_usage;
+pub mod static_bounds;
+pub mod unreachable;
@@ -0,0 +1,401 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{DeclarationItem, FoundDeclaration, Search, SearchState, Searcher};
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::named_entity::{AnyEntKind, Design, Reference};
+use crate::syntax::TokenAccess;
+use crate::Config;
+use fnv::FnvHashSet;
+use itertools::Itertools;
+use strum::{EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
+use toml::{Table, Value};
+
+/// A language construct that can be banned by the `[banned]` table of the configuration
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, EnumString, IntoStaticStr, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum BannedConstruct {
+    SharedVariable,
+    SelectedSignalAssignment,
+    Block,
+    Exit,
+    Next,
+}
+
+impl BannedConstruct {
+    /// All constructs that can be banned
+    pub fn all() -> impl Iterator<Item = BannedConstruct> {
+        BannedConstruct::iter()
+    }
+
+    /// The name of the construct within the `[banned]` table
+    pub fn as_str(&self) -> &'static str {
+        self.into()
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            BannedConstruct::SharedVariable => "shared variable",
+            BannedConstruct::SelectedSignalAssignment => "selected signal assignment",
+            BannedConstruct::Block => "block statement",
+            BannedConstruct::Exit => "exit statement",
+            BannedConstruct::Next => "next statement",
+        }
+    }
+}
+
+/// The constructs and packages that a project does not allow
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct BanList {
+    constructs: FnvHashSet<BannedConstruct>,
+    /// Lower case names of the form `library.package`
+    packages: Vec<String>,
+}
+
+impl BanList {
+    /// Read the ban list from the `[banned]` table
+    ///
+    /// ```toml
+    /// [banned]
+    /// constructs = ['shared_variable', 'block']
+    /// packages = ['ieee.std_logic_arith']
+    /// ```
+    pub(crate) fn from_table(table: &Table) -> Result<BanList, String> {
+        let mut ban_list = BanList::default();
+        for (key, value) in table {
+            let names = value
+                .as_array()
+                .ok_or_else(|| format!("banned {key} must be an array"))?
+                .iter()
+                .map(|name| {
+                    name.as_str()
+                        .ok_or_else(|| format!("banned {key} must be an array of strings"))
+                });
+            match key.as_str() {
+                "constructs" => {
+                    for name in names {
+                        let name = name?;
+                        let construct = BannedConstruct::try_from(name).map_err(|_| {
+                            format!("'{name}' is not a construct that can be banned")
+                        })?;
+                        ban_list.constructs.insert(construct);
+                    }
+                }
+                "packages" => {
+                    for name in names {
+                        let name = name?;
+                        if name.split('.').count() != 2 {
+                            return Err(format!(
+                                "Expected banned package '{name}' to be of the form library.package"
+                            ));
+                        }
+                        ban_list.packages.push(name.to_lowercase());
+                    }
+                }
+                _ => return Err(format!("Unknown key '{key}' in banned table")),
+            }
+        }
+        Ok(ban_list)
+    }
+
+    /// The `[banned]` table that [`Self::from_table`] reads back into this ban list
+    pub(crate) fn to_table(&self) -> Table {
+        let mut table = Table::new();
+        if !self.constructs.is_empty() {
+            let constructs = self
+                .constructs
+                .iter()
+                .map(|construct| construct.as_str())
+                .sorted()
+                .map(|name| Value::String(name.to_owned()))
+                .collect();
+            table.insert("constructs".to_owned(), Value::Array(constructs));
+        }
+        if !self.packages.is_empty() {
+            let packages = self.packages.iter().cloned().map(Value::String).collect();
+            table.insert("packages".to_owned(), Value::Array(packages));
+        }
+        table
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.constructs.is_empty() && self.packages.is_empty()
+    }
+}
+
+/// Returns a warning for each use of a construct or package that is banned by the configuration.
+/// Third party libraries are never linted.
+pub(crate) fn banned_construct_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+    let ban_list = config.ban_list();
+    let mut diagnostics = Vec::new();
+    if ban_list.is_empty() {
+        return diagnostics;
+    }
+    for library in root.libraries() {
+        let Some(library_config) = config.get_library(&library.name().name_utf8()) else {
+            continue;
+        };
+        if library_config.is_third_party {
+            continue;
+        }
+        for unit in library.units() {
+            let mut searcher = BannedUses {
+                root,
+                ban_list,
+                diagnostics: Vec::new(),
+            };
+            let _ = unit
+                .unit
+                .expect_analyzed()
+                .search(&unit.tokens, &mut searcher);
+            diagnostics.extend(searcher.diagnostics);
+        }
+    }
+    diagnostics
+}
+
+struct BannedUses<'a> {
+    root: &'a DesignRoot,
+    ban_list: &'a BanList,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl BannedUses<'_> {
+    fn check(&mut self, construct: BannedConstruct, pos: &SrcPos) {
+        if self.ban_list.constructs.contains(&construct) {
+            self.diagnostics.push(Diagnostic::new(
+                pos,
+                format!("Use of banned {}", construct.describe()),
+                ErrorCode::BannedConstruct,
+            ));
+        }
+    }
+}
+
+impl Searcher for BannedUses<'_> {
+    fn search_pos_with_ref(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        reference: &Reference,
+    ) -> SearchState {
+        let Some(id) = reference.get() else {
+            return SearchState::NotFinished;
+        };
+        let ent = self.root.get_ent(id);
+        if !matches!(ent.kind(), AnyEntKind::Design(Design::Package(..))) {
+            return SearchState::NotFinished;
+        }
+        let Some(library_name) = ent.library_name() else {
+            return SearchState::NotFinished;
+        };
+        let name = format!("{}.{}", library_name.name_utf8(), ent.designator()).to_lowercase();
+        if self.ban_list.packages.contains(&name) {
+            self.diagnostics.push(Diagnostic::new(
+                pos,
+                format!("Use of banned package '{name}'"),
+                ErrorCode::BannedConstruct,
+            ));
+        }
+        SearchState::NotFinished
+    }
+
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        match decl.ast {
+            DeclarationItem::Object(object) if object.class == ObjectClass::SharedVariable => {
+                // Search is called once for each identifier of the declaration
+                if let Some(ident) = object
+                    .idents
+                    .iter()
+                    .find(|ident| std::ptr::eq(&ident.decl, decl.reference))
+                {
+                    self.check(BannedConstruct::SharedVariable, ident.tree.pos(ctx));
+                }
+            }
+            DeclarationItem::ConcurrentStatement(statement) => {
+                let pos = ctx.get_pos(statement.statement.span.start_token);
+                match &statement.statement.item {
+                    ConcurrentStatement::Block(_) => self.check(BannedConstruct::Block, pos),
+                    ConcurrentStatement::Assignment(assignment)
+                        if matches!(
+                            assignment.assignment.rhs,
+                            AssignmentRightHand::Selected(_)
+                        ) =>
+                    {
+                        self.check(BannedConstruct::SelectedSignalAssignment, pos)
+                    }
+                    _ => {}
+                }
+            }
+            DeclarationItem::SequentialStatement(statement) => {
+                let pos = ctx.get_pos(statement.statement.span.start_token);
+                match &statement.statement.item {
+                    SequentialStatement::Exit(_) => self.check(BannedConstruct::Exit, pos),
+                    SequentialStatement::Next(_) => self.check(BannedConstruct::Next, pos),
+                    SequentialStatement::SignalAssignment(assignment)
+                        if matches!(assignment.rhs, AssignmentRightHand::Selected(_)) =>
+                    {
+                        self.check(BannedConstruct::SelectedSignalAssignment, pos)
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        SearchState::NotFinished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{test_config, LibraryBuilder};
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics};
+    use std::path::Path;
+
+    fn banned(builder: LibraryBuilder, banned: &str) -> Vec<Diagnostic> {
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        let config = test_config(&format!("[banned]\n{banned}"));
+        banned_construct_diagnostics(&root, &config)
+    }
+
+    #[test]
+    fn warns_on_banned_constructs() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  type prot_t is protected
+  end protected;
+  type prot_t is protected body
+  end protected body;
+
+  shared variable shared_a, shared_b : prot_t;
+  signal sel, d, q : bit;
+begin
+  blk : block
+  begin
+    with sel select q <= d when '1', '0' when others;
+  end block;
+
+  process
+  begin
+    loop
+      next when d = '0';
+      exit;
+    end loop;
+    wait;
+  end process;
+end architecture;
+",
+        );
+
+        let diagnostics = banned(
+            builder,
+            "constructs = ['shared_variable', 'selected_signal_assignment', 'block', 'exit', 'next']",
+        );
+        check_diagnostics(
+            diagnostics,
+            vec![
+                Diagnostic::new(
+                    code.s1("shared_a"),
+                    "Use of banned shared variable",
+                    ErrorCode::BannedConstruct,
+                ),
+                Diagnostic::new(
+                    code.s1("shared_b"),
+                    "Use of banned shared variable",
+                    ErrorCode::BannedConstruct,
+                ),
+                Diagnostic::new(
+                    code.s1("block"),
+                    "Use of banned block statement",
+                    ErrorCode::BannedConstruct,
+                ),
+                Diagnostic::new(
+                    code.s1("with"),
+                    "Use of banned selected signal assignment",
+                    ErrorCode::BannedConstruct,
+                ),
+                Diagnostic::new(
+                    code.s1("next"),
+                    "Use of banned next statement",
+                    ErrorCode::BannedConstruct,
+                ),
+                Diagnostic::new(
+                    code.s1("exit"),
+                    "Use of banned exit statement",
+                    ErrorCode::BannedConstruct,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn warns_on_banned_packages() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "ieee",
+            "
+package std_logic_arith is
+  constant c : natural := 0;
+end package;
+",
+        );
+        let code = builder.code(
+            "libname",
+            "
+library ieee;
+use ieee.std_logic_arith.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  constant d : natural := ieee.std_logic_arith.c;
+begin
+end architecture;
+",
+        );
+
+        let diagnostics = banned(builder, "packages = ['IEEE.std_logic_arith']");
+        check_diagnostics(
+            diagnostics,
+            vec![
+                Diagnostic::new(
+                    code.s("std_logic_arith", 1),
+                    "Use of banned package 'ieee.std_logic_arith'",
+                    ErrorCode::BannedConstruct,
+                ),
+                Diagnostic::new(
+                    code.s("std_logic_arith", 2),
+                    "Use of banned package 'ieee.std_logic_arith'",
+                    ErrorCode::BannedConstruct,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_constructs() {
+        let config = Config::from_str(
+            "[libraries]\nlibname.files = []\n[banned]\nconstructs = ['goto']",
+            Path::new(""),
+        );
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "'goto' is not a construct that can be banned"
+        );
+    }
+}
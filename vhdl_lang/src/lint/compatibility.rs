@@ -0,0 +1,198 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{DeclarationItem, FoundDeclaration, Search, SearchState, Searcher};
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::standard::VHDLStandard;
+use crate::syntax::{HasTokenSpan, TokenAccess};
+use crate::Config;
+
+/// Returns an error for each VHDL-2008 feature in a library that is analyzed with an earlier
+/// standard, such that the code stays portable to tools that do not support VHDL-2008.
+/// Third party libraries are never linted.
+pub(crate) fn compatibility_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for library in root.libraries() {
+        if config
+            .get_library(&library.name().name_utf8())
+            .is_some_and(|library| library.is_third_party)
+        {
+            continue;
+        }
+        for unit in library.units() {
+            if unit.standard >= VHDLStandard::VHDL2008 {
+                continue;
+            }
+            let mut searcher = UnsupportedFeatures {
+                standard: unit.standard,
+                diagnostics: Vec::new(),
+            };
+            for token in unit.tokens.iter() {
+                let Some(comments) = &token.comments else {
+                    continue;
+                };
+                for comment in comments.leading.iter().chain(&comments.trailing) {
+                    if comment.multi_line {
+                        let pos = SrcPos::new(token.pos.source.clone(), comment.range);
+                        searcher.push(&pos, "Block comments");
+                    }
+                }
+            }
+            let design_unit = unit.unit.expect_analyzed();
+            if let Some(context_clause) = design_unit.context_clause() {
+                for item in context_clause.iter() {
+                    if let ContextItem::Context(..) = item {
+                        searcher.push(&item.get_pos(&unit.tokens), "Context references");
+                    }
+                }
+            }
+            let _ = design_unit.search(&unit.tokens, &mut searcher);
+            diagnostics.extend(searcher.diagnostics);
+        }
+    }
+    diagnostics
+}
+
+struct UnsupportedFeatures {
+    standard: VHDLStandard,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl UnsupportedFeatures {
+    fn push(&mut self, pos: &SrcPos, feature: &str) {
+        self.diagnostics.push(Diagnostic::new(
+            pos,
+            format!(
+                "{feature} are not supported in VHDL-{}",
+                self.standard.as_ref()
+            ),
+            ErrorCode::UnsupportedFeature,
+        ));
+    }
+}
+
+impl Searcher for UnsupportedFeatures {
+    fn search_name(&mut self, _ctx: &dyn TokenAccess, pos: &SrcPos, name: &Name) -> SearchState {
+        if let Name::External(..) = name {
+            self.push(pos, "External names");
+        }
+        SearchState::NotFinished
+    }
+
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        match decl.ast {
+            DeclarationItem::Context(context) => {
+                self.push(context.ident.tree.pos(ctx), "Context declarations")
+            }
+            DeclarationItem::ConcurrentStatement(statement) => {
+                if let ConcurrentStatement::Process(process) = &statement.statement.item {
+                    if let Some(sensitivity_list) = &process.sensitivity_list {
+                        if let SensitivityList::All = sensitivity_list.item {
+                            self.push(
+                                &sensitivity_list.span.pos(ctx),
+                                "Process sensitivity lists with 'all'",
+                            );
+                        }
+                    }
+                }
+            }
+            DeclarationItem::SequentialStatement(statement) => {
+                if let SequentialStatement::Case(case) = &statement.statement.item {
+                    if case.is_matching {
+                        let start = statement.statement.span.start_token;
+                        self.push(&ctx.get_span(start, start + 1), "Matching case statements");
+                    }
+                }
+            }
+            _ => {}
+        }
+        SearchState::NotFinished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{add_standard_library, test_config, LibraryBuilder};
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics, Code};
+
+    const CODE: &str = "
+context ctx is
+end context;
+
+/* The design under test */
+context work.ctx;
+entity ent is
+end entity;
+
+architecture a of ent is
+    signal d, q : bit;
+    alias ext is << signal .tb.dut.q : bit >>;
+begin
+    process (all)
+    begin
+        case? d is
+            when others => q <= d;
+        end case?;
+    end process;
+end architecture;
+";
+
+    fn analyze_as(standard: VHDLStandard) -> (Code, DesignRoot) {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.snippet(CODE);
+        let mut root = DesignRoot::new(builder.symbols());
+        let mut diagnostics = Vec::new();
+        add_standard_library(builder.symbols(), &mut root);
+        let mut design_file = code.design_file();
+        design_file.standard = standard;
+        root.add_design_file(
+            builder.symbols().symtab().insert_utf8("libname"),
+            design_file,
+        );
+        root.analyze(&mut diagnostics);
+        (code, root)
+    }
+
+    fn unsupported(pos: impl AsRef<SrcPos>, feature: &str) -> Diagnostic {
+        Diagnostic::new(
+            pos,
+            format!("{feature} are not supported in VHDL-1993"),
+            ErrorCode::UnsupportedFeature,
+        )
+    }
+
+    #[test]
+    fn vhdl_2008_features_in_vhdl_1993_library() {
+        let (code, root) = analyze_as(VHDLStandard::VHDL1993);
+        check_diagnostics(
+            compatibility_diagnostics(&root, &test_config("")),
+            vec![
+                unsupported(code.s1("ctx"), "Context declarations"),
+                unsupported(code.s1("/* The design under test */"), "Block comments"),
+                unsupported(code.s1("context work.ctx;"), "Context references"),
+                unsupported(code.s1("<< signal .tb.dut.q : bit >>"), "External names"),
+                unsupported(code.s1("(all)"), "Process sensitivity lists with 'all'"),
+                unsupported(code.s1("case?"), "Matching case statements"),
+            ],
+        );
+    }
+
+    #[test]
+    fn vhdl_2008_features_in_other_libraries() {
+        let (_, root) = analyze_as(VHDLStandard::VHDL2008);
+        check_no_diagnostics(&compatibility_diagnostics(&root, &test_config("")));
+
+        let (_, root) = analyze_as(VHDLStandard::VHDL1993);
+        check_no_diagnostics(&compatibility_diagnostics(
+            &root,
+            &test_config("libname.is_third_party = true"),
+        ));
+    }
+}
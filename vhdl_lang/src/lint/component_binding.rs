@@ -0,0 +1,357 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{DeclarationItem, FoundDeclaration, Search, SearchState, Searcher};
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::named_entity::{AnyEntKind, Design, EntRef, InterfaceEnt, InterfaceMode, Object};
+use crate::syntax::TokenAccess;
+use crate::Config;
+
+/// Returns a warning for each generic or port of a component that does not exist in an entity
+/// that the component is bound to, or that has a different type or static default value there.
+/// Returns a warning for generics without default value and ports of mode `in` without default value
+/// of such an entity that are missing from the component.
+/// Components are bound to entities by default binding and by configurations.
+pub(crate) fn component_binding_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for library in root.libraries() {
+        let Some(library_config) = config.get_library(&library.name().name_utf8()) else {
+            continue;
+        };
+        if library_config.is_third_party {
+            continue;
+        }
+        for unit in library.units() {
+            let mut searcher = Components {
+                root,
+                components: Vec::new(),
+            };
+            let _ = unit
+                .unit
+                .expect_analyzed()
+                .search(&unit.tokens, &mut searcher);
+            for component in searcher.components {
+                for entity in root.find_implementation(component) {
+                    check_binding(root, component, entity, &mut diagnostics);
+                }
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Collects the component declarations of a design unit.
+struct Components<'a> {
+    root: &'a DesignRoot,
+    components: Vec<EntRef<'a>>,
+}
+
+impl Searcher for Components<'_> {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        if let DeclarationItem::Component(component) = decl.ast {
+            if let Some(id) = component.ident.decl.get() {
+                self.components.push(self.root.get_ent(id));
+            }
+        }
+        SearchState::NotFinished
+    }
+}
+
+fn check_binding<'a>(
+    root: &'a DesignRoot,
+    component: EntRef<'a>,
+    entity: EntRef<'a>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let (
+        AnyEntKind::Component(component_region),
+        AnyEntKind::Design(Design::Entity(_, entity_region)),
+    ) = (component.kind(), entity.kind())
+    else {
+        return;
+    };
+    let (component_ports, component_generics) = component_region.ports_and_generics();
+    let (entity_ports, entity_generics) = entity_region.ports_and_generics();
+    let binding = Binding {
+        root,
+        component,
+        entity,
+    };
+    binding.check_interface(
+        "Generic",
+        &component_generics,
+        &entity_generics,
+        diagnostics,
+    );
+    binding.check_interface("Port", &component_ports, &entity_ports, diagnostics);
+}
+
+struct Binding<'a> {
+    root: &'a DesignRoot,
+    component: EntRef<'a>,
+    entity: EntRef<'a>,
+}
+
+impl<'a> Binding<'a> {
+    fn check_interface(
+        &self,
+        kind: &str,
+        component_elements: &[InterfaceEnt<'a>],
+        entity_elements: &[InterfaceEnt<'a>],
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        for element in component_elements {
+            let Some(pos) = element.decl_pos() else {
+                continue;
+            };
+            let Some(entity_element) = entity_elements
+                .iter()
+                .find(|entity_element| entity_element.designator() == element.designator())
+            else {
+                diagnostics.push(
+                    Diagnostic::new(
+                        pos,
+                        format!(
+                            "{kind} '{}' of {} does not exist in {}",
+                            element.designator(),
+                            self.component.describe(),
+                            self.entity.describe()
+                        ),
+                        ErrorCode::ComponentMismatch,
+                    )
+                    .opt_related(self.entity.decl_pos(), "Entity declared here"),
+                );
+                continue;
+            };
+            if !is_object(element) || !is_object(entity_element) {
+                continue;
+            }
+            if element.base_type().id() != entity_element.base_type().id() {
+                diagnostics.push(
+                    Diagnostic::new(
+                        pos,
+                        format!(
+                            "{kind} '{}' of {} has type '{}' but has type '{}' in {}",
+                            element.designator(),
+                            self.component.describe(),
+                            element.type_mark().designator(),
+                            entity_element.type_mark().designator(),
+                            self.entity.describe()
+                        ),
+                        ErrorCode::ComponentMismatch,
+                    )
+                    .opt_related(entity_element.decl_pos(), "Defined here"),
+                );
+                continue;
+            }
+            if let (Some(value), Some(entity_value)) = (
+                self.root.evaluate_default(element.inner(), 0),
+                self.root.evaluate_default(entity_element.inner(), 0),
+            ) {
+                if value != entity_value {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            pos,
+                            format!(
+                                "{kind} '{}' of {} has default value {value} but has default value {entity_value} in {}",
+                                element.designator(),
+                                self.component.describe(),
+                                self.entity.describe()
+                            ),
+                            ErrorCode::ComponentMismatch,
+                        )
+                        .opt_related(entity_element.decl_pos(), "Defined here"),
+                    );
+                }
+            }
+        }
+
+        let Some(pos) = self.component.decl_pos() else {
+            return;
+        };
+        for entity_element in entity_elements {
+            if !requires_association(entity_element)
+                || component_elements
+                    .iter()
+                    .any(|element| element.designator() == entity_element.designator())
+            {
+                continue;
+            }
+            diagnostics.push(
+                Diagnostic::new(
+                    pos,
+                    format!(
+                        "{kind} '{}' of {} is missing from {}",
+                        entity_element.designator(),
+                        self.entity.describe(),
+                        self.component.describe()
+                    ),
+                    ErrorCode::ComponentMismatch,
+                )
+                .opt_related(entity_element.decl_pos(), "Defined here"),
+            );
+        }
+    }
+}
+
+fn is_object(element: &InterfaceEnt<'_>) -> bool {
+    matches!(element.kind(), AnyEntKind::Object(_))
+}
+
+/// True for generic constants and ports of mode `in` without default value,
+/// which must be associated when the entity is instantiated.
+fn requires_association(element: &InterfaceEnt<'_>) -> bool {
+    match element.kind() {
+        AnyEntKind::Object(object @ Object { has_default, .. }) => {
+            !has_default && matches!(object.mode(), None | Some(InterfaceMode::Simple(Mode::In)))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{
+        check_diagnostics, check_no_diagnostics, test_config, LibraryBuilder,
+    };
+
+    fn component_binding(builder: LibraryBuilder) -> Vec<Diagnostic> {
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        let config = test_config("");
+        component_binding_diagnostics(&root, &config)
+    }
+
+    #[test]
+    fn warns_on_component_that_differs_from_the_bound_entity() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity fifo is
+  generic (
+    depth : natural := 16;
+    width : natural);
+  port (
+    clk : in bit;
+    data : in bit_vector(7 downto 0);
+    full : out bit);
+end entity;
+
+architecture a of fifo is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component fifo is
+    generic (
+      depth : natural := 8;
+      mode : natural := 0);
+    port (
+      data : in string(1 to 8);
+      empty : out bit);
+  end component;
+begin
+end architecture;
+",
+        );
+
+        check_diagnostics(
+            component_binding(builder),
+            vec![
+                Diagnostic::new(
+                    code.s("depth", 2),
+                    "Generic 'depth' of component 'fifo' has default value 8 but has default value 16 in entity 'fifo'",
+                    ErrorCode::ComponentMismatch,
+                )
+                .related(code.s1("depth"), "Defined here"),
+                Diagnostic::new(
+                    code.s1("mode"),
+                    "Generic 'mode' of component 'fifo' does not exist in entity 'fifo'",
+                    ErrorCode::ComponentMismatch,
+                )
+                .related(code.s1("fifo"), "Entity declared here"),
+                Diagnostic::new(
+                    code.s("fifo", 3),
+                    "Generic 'width' of entity 'fifo' is missing from component 'fifo'",
+                    ErrorCode::ComponentMismatch,
+                )
+                .related(code.s1("width"), "Defined here"),
+                Diagnostic::new(
+                    code.s("data", 2),
+                    "Port 'data' of component 'fifo' has type 'STRING' but has type 'BIT_VECTOR' in entity 'fifo'",
+                    ErrorCode::ComponentMismatch,
+                )
+                .related(code.s1("data"), "Defined here"),
+                Diagnostic::new(
+                    code.s1("empty"),
+                    "Port 'empty' of component 'fifo' does not exist in entity 'fifo'",
+                    ErrorCode::ComponentMismatch,
+                )
+                .related(code.s1("fifo"), "Entity declared here"),
+                Diagnostic::new(
+                    code.s("fifo", 3),
+                    "Port 'clk' of entity 'fifo' is missing from component 'fifo'",
+                    ErrorCode::ComponentMismatch,
+                )
+                .related(code.s1("clk"), "Defined here"),
+            ],
+        );
+    }
+
+    #[test]
+    fn checks_entities_bound_by_configurations() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity impl is
+  port (d : in bit);
+end entity;
+
+architecture a of impl is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component comp is
+    port (d : in bit; q : out bit);
+  end component;
+begin
+  inst : component comp port map (d => '0', q => open);
+end architecture;
+
+configuration cfg of top is
+  for a
+    for inst : comp
+      use entity work.impl;
+    end for;
+  end for;
+end configuration;
+",
+        );
+
+        check_diagnostics(
+            component_binding(builder),
+            vec![Diagnostic::new(
+                code.s1("q :").s1("q"),
+                "Port 'q' of component 'comp' does not exist in entity 'impl'",
+                ErrorCode::ComponentMismatch,
+            )
+            .related(code.s1("impl"), "Entity declared here")],
+        );
+    }
+}
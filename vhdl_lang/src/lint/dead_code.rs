@@ -11,6 +11,7 @@ use crate::ast::search::Search;
 use crate::ast::search::SearchState;
 use crate::ast::search::Searcher;
 use crate::ast::UnitId;
+use crate::ast::{AnyDesignUnit, AnyPrimaryUnit, AnySecondaryUnit, HasUnitId};
 use crate::data::error_codes::ErrorCode;
 use crate::data::DiagnosticHandler;
 use crate::data::Symbol;
@@ -76,6 +77,30 @@ fn search_unit(unit: &LockedUnit, searcher: &mut impl Searcher) {
     let _ = unit.unit.write().search(&unit.tokens, searcher);
 }
 
+fn is_protected(unit: &LockedUnit) -> bool {
+    match &*unit.unit.write() {
+        AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(architecture)) => {
+            architecture.is_protected
+        }
+        AnyDesignUnit::Secondary(AnySecondaryUnit::PackageBody(package_body)) => {
+            package_body.is_protected
+        }
+        AnyDesignUnit::Primary(_) => false,
+    }
+}
+
+/// The name of the entity that a verification unit is bound to
+fn bound_entity_name(unit: &LockedUnit) -> Option<Symbol> {
+    if let AnyDesignUnit::Primary(AnyPrimaryUnit::VerificationUnit(vunit)) = &*unit.unit.write() {
+        vunit
+            .binding
+            .as_ref()
+            .map(|binding| binding.entity_name.item.item.clone())
+    } else {
+        None
+    }
+}
+
 fn is_package_header(ent: EntRef<'_>) -> bool {
     matches!(
         ent.kind(),
@@ -171,13 +196,28 @@ fn find_unused_declarations<'a>(
     let mut searcher = DeadCodeSearcher::new(root);
 
     if let Some(unit) = lib.primary_unit(primary_unit_name) {
+        // Searched together with the entity that it is bound to
+        if bound_entity_name(unit).is_some() {
+            return FnvHashSet::default();
+        }
         search_unit(unit, &mut searcher);
     }
 
     for unit in lib.secondary_units(primary_unit_name) {
+        // Declarations may be used by the encrypted text of a protected envelope
+        if is_protected(unit) {
+            return FnvHashSet::default();
+        }
         search_unit(unit, &mut searcher);
     }
 
+    // Verification units use the declarations of the entity that they are bound to
+    for unit in lib.primary_units() {
+        if bound_entity_name(unit).as_ref() == Some(primary_unit_name) {
+            search_unit(unit, &mut searcher);
+        }
+    }
+
     searcher
         .declarations
         .difference(&searcher.references)
@@ -214,6 +254,15 @@ impl UnusedDeclarationsLinter {
         for unit in analyzed_units {
             let key = (unit.library_name().clone(), unit.primary_name().clone());
             self.diagnostics.remove(&key);
+
+            let bound_entity = root
+                .get_lib(unit.library_name())
+                .and_then(|library| library.get_unit(unit.key()))
+                .and_then(bound_entity_name);
+            if let Some(entity_name) = bound_entity {
+                self.diagnostics
+                    .remove(&(unit.library_name().clone(), entity_name));
+            }
         }
 
         // Prune diagnostics for units that no longer exist
@@ -352,6 +401,77 @@ end architecture;",
         )
     }
 
+    #[test]
+    fn ports_may_be_used_by_protected_architectures() {
+        let mut builder = LibraryBuilder::new();
+
+        builder.code(
+            "libname",
+            "
+entity ent is
+  port (
+    unused : out boolean
+  );
+end entity;
+
+architecture a of ent is
+`protect begin_protected
+`protect data_block
+dW51c2VkIDw9IHRydWU7
+`protect end_protected
+end architecture;",
+        );
+
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let lib = root.get_lib(&root.symbol_utf8("libname")).unwrap();
+        check_unused(
+            find_unused_declarations(&root, lib, &root.symbol_utf8("ent")),
+            FnvHashSet::default(),
+        )
+    }
+
+    #[test]
+    fn declarations_used_by_verification_units() {
+        let mut builder = LibraryBuilder::new();
+
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  port (
+    req, ack : in boolean
+  );
+end entity;
+
+architecture a of ent is
+  signal busy : boolean;
+begin
+end architecture;
+
+vunit props (ent(a)) {
+  signal unused : boolean;
+  assert always req -> next (ack or busy);
+}",
+        );
+
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let lib = root.get_lib(&root.symbol_utf8("libname")).unwrap();
+        let ent = get_ent(&root, code.s1("unused"));
+
+        check_unused(
+            find_unused_declarations(&root, lib, &root.symbol_utf8("ent")),
+            FnvHashSet::from_iter(vec![ent]),
+        );
+        check_unused(
+            find_unused_declarations(&root, lib, &root.symbol_utf8("props")),
+            FnvHashSet::default(),
+        );
+    }
+
     /// Since the focus of the unused declaration lint is local declarations
     /// we have to assume that a package header declaration could be used somewhere else.
     #[test]
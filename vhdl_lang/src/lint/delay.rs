@@ -0,0 +1,91 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::syntax::Kind;
+use crate::Config;
+
+/// Returns a warning for every `after` clause in libraries that are marked as synthesizable.
+/// Third party libraries are never linted.
+pub(crate) fn ignored_delay_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for library in root.libraries() {
+        let is_synthesizable = config
+            .get_library(&library.name().name_utf8())
+            .is_some_and(|library| library.is_synthesizable && !library.is_third_party);
+        if !is_synthesizable {
+            continue;
+        }
+        for unit in library.units() {
+            diagnostics.extend(
+                unit.tokens
+                    .iter()
+                    .filter(|token| token.kind == Kind::After)
+                    .map(|token| {
+                        Diagnostic::new(
+                            &token.pos,
+                            "Delays are ignored by synthesis",
+                            ErrorCode::IgnoredDelay,
+                        )
+                    }),
+            );
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{test_config, LibraryBuilder};
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics};
+
+    const CODE: &str = "
+entity ent is
+end entity;
+
+architecture a of ent is
+    signal d, q : bit;
+begin
+    q <= d after 1 ns;
+end architecture;
+";
+
+    #[test]
+    fn warns_on_after_clause_in_synthesizable_library() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code("libname", CODE);
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let config = test_config("libname.is_synthesizable = true");
+        check_diagnostics(
+            ignored_delay_diagnostics(&root, &config),
+            vec![Diagnostic::new(
+                code.s1("after"),
+                "Delays are ignored by synthesis",
+                ErrorCode::IgnoredDelay,
+            )],
+        );
+    }
+
+    #[test]
+    fn does_not_warn_on_after_clause_in_other_libraries() {
+        let mut builder = LibraryBuilder::new();
+        builder.code("libname", CODE);
+        let (root, _) = builder.get_analyzed_root();
+
+        for options in [
+            "",
+            "libname.is_synthesizable = true\nlibname.is_third_party = true",
+        ] {
+            let config = test_config(options);
+            check_no_diagnostics(&ignored_delay_diagnostics(&root, &config));
+        }
+    }
+}
@@ -0,0 +1,523 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{Search, SearchResult, SearchState, Searcher};
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::named_entity::{AnyEntKind, EntityId, Object, Reference, Type, TypeEnt};
+use crate::syntax::{TokenAccess, Value};
+use crate::Config;
+use fnv::FnvHashSet;
+use std::ops::Deref;
+
+/// Returns a warning for initial values of signals with unresolved types that are
+/// assigned in clocked logic, when the synthesis target of the library ignores initial values.
+/// Returns a warning for signals without initial value that are read by a process
+/// before its first wait statement in testbench libraries.
+/// Only signals that are declared in architectures are considered.
+pub(crate) fn initialization_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for library in root.libraries() {
+        let Some(library_config) = config.get_library(&library.name().name_utf8()) else {
+            continue;
+        };
+        if library_config.is_third_party {
+            continue;
+        }
+        let check_initial_values =
+            library_config.is_synthesizable && library_config.ignores_initial_values;
+        if !check_initial_values && !library_config.is_testbench {
+            continue;
+        }
+        for unit in library.units() {
+            let data = unit.unit.expect_analyzed();
+            let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) = data.deref()
+            else {
+                continue;
+            };
+            let ctx: &dyn TokenAccess = &unit.tokens;
+            let mut processes = Vec::new();
+            collect_processes(&arch.statements, &mut processes);
+            if check_initial_values {
+                ignored_initial_values(root, ctx, arch, &processes, &mut diagnostics);
+            }
+            if library_config.is_testbench {
+                uninitialized_reads(root, ctx, arch, &processes, &mut diagnostics);
+            }
+        }
+    }
+    diagnostics
+}
+
+fn ignored_initial_values(
+    root: &DesignRoot,
+    ctx: &dyn TokenAccess,
+    arch: &ArchitectureBody,
+    processes: &[&ProcessStatement],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut clocked = Vec::new();
+    for process in processes {
+        collect_clocked_statements(ctx, &process.statements, &mut clocked);
+    }
+    let mut searcher = ClockedTargets::default();
+    for statement in clocked.into_iter().flatten() {
+        let _ = statement.search(ctx, &mut searcher);
+    }
+
+    for (ident, decl) in signals(arch) {
+        if decl.expression.is_none() || decl.subtype_indication.resolution.is_some() {
+            continue;
+        }
+        let Some((id, subtype)) = signal_type(root, ident) else {
+            continue;
+        };
+        if searcher.targets.contains(&id) && !root.is_resolved_subtype(subtype) {
+            diagnostics.push(Diagnostic::new(
+                ident.tree.pos(ctx),
+                format!(
+                    "Initial value of signal '{}' may be ignored by synthesis",
+                    ident.tree.item
+                ),
+                ErrorCode::IgnoredInitialValue,
+            ));
+        }
+    }
+}
+
+fn uninitialized_reads(
+    root: &DesignRoot,
+    ctx: &dyn TokenAccess,
+    arch: &ArchitectureBody,
+    processes: &[&ProcessStatement],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut searcher = SignalReads::default();
+    for (ident, decl) in signals(arch) {
+        if decl.expression.is_some() {
+            continue;
+        }
+        if let Some((id, subtype)) = signal_type(root, ident) {
+            if is_initially_uninitialized(subtype) {
+                searcher.signals.insert(id);
+            }
+        }
+    }
+    if searcher.signals.is_empty() {
+        return;
+    }
+    for process in processes {
+        search_initial_statements(ctx, &process.statements, &mut searcher);
+    }
+
+    let mut reported = FnvHashSet::default();
+    for (id, pos) in searcher.reads {
+        if reported.insert(id) {
+            diagnostics.push(Diagnostic::new(
+                pos,
+                format!(
+                    "Signal '{}' has no initial value and is read during initialization",
+                    root.get_ent(id).designator()
+                ),
+                ErrorCode::UninitializedRead,
+            ));
+        }
+    }
+}
+
+/// The signals that are declared in the declarative part of the architecture
+pub(super) fn signals(
+    arch: &ArchitectureBody,
+) -> impl Iterator<Item = (&WithDecl<Ident>, &ObjectDeclaration)> {
+    arch.decl.iter().flat_map(|decl| match &decl.item {
+        Declaration::Object(object) if object.class == ObjectClass::Signal => object
+            .idents
+            .iter()
+            .map(|ident| (ident, object))
+            .collect::<Vec<_>>(),
+        _ => Vec::new(),
+    })
+}
+
+fn signal_type<'a>(
+    root: &'a DesignRoot,
+    ident: &WithDecl<Ident>,
+) -> Option<(EntityId, TypeEnt<'a>)> {
+    let id = ident.decl.get()?;
+    match root.get_ent(id).kind() {
+        AnyEntKind::Object(Object { subtype, .. }) => Some((id, subtype.type_mark())),
+        _ => None,
+    }
+}
+
+/// Whether the default value of the type is `'U'`, such as for `std_ulogic` and arrays thereof
+fn is_initially_uninitialized(typ: TypeEnt<'_>) -> bool {
+    match typ.base_type().kind() {
+        Type::Enum(literals) => literals.contains(&Designator::Character(b'U')),
+        Type::Array { elem_type, .. } => is_initially_uninitialized(*elem_type),
+        _ => false,
+    }
+}
+
+pub(super) fn collect_processes<'a>(
+    statements: &'a [LabeledConcurrentStatement],
+    processes: &mut Vec<&'a ProcessStatement>,
+) {
+    for statement in statements {
+        match &statement.statement.item {
+            ConcurrentStatement::Process(process) => processes.push(process),
+            ConcurrentStatement::Block(block) => collect_processes(&block.statements, processes),
+            ConcurrentStatement::ForGenerate(generate) => {
+                collect_processes(&generate.body.statements, processes)
+            }
+            ConcurrentStatement::IfGenerate(generate) => {
+                let Conditionals {
+                    conditionals,
+                    else_item,
+                } = &generate.conds;
+                for body in conditionals
+                    .iter()
+                    .map(|conditional| &conditional.item)
+                    .chain(else_item.as_ref().map(|(body, _)| body))
+                {
+                    collect_processes(&body.statements, processes);
+                }
+            }
+            ConcurrentStatement::CaseGenerate(generate) => {
+                for alternative in generate.sels.alternatives.iter() {
+                    collect_processes(&alternative.item.statements, processes);
+                }
+            }
+            ConcurrentStatement::ProcedureCall(_)
+            | ConcurrentStatement::Assert(_)
+            | ConcurrentStatement::Assignment(_)
+            | ConcurrentStatement::Instance(_) => {}
+        }
+    }
+}
+
+/// Conditions such as `rising_edge(clk)` or `clk'event and clk = '1'`
+fn is_clock_edge(ctx: &dyn TokenAccess, condition: &WithTokenSpan<Expression>) -> bool {
+    condition
+        .span
+        .iter()
+        .filter_map(|id| ctx.get_token(id))
+        .any(|token| match &token.value {
+            Value::Identifier(symbol) => {
+                let name = symbol.name_utf8();
+                ["rising_edge", "falling_edge", "event"]
+                    .iter()
+                    .any(|edge| name.eq_ignore_ascii_case(edge))
+            }
+            _ => false,
+        })
+}
+
+/// Collect the statements that are executed on a clock edge, i.e. the branches of
+/// `if rising_edge(clk)` and the statements after `wait until rising_edge(clk)`.
+fn collect_clocked_statements<'a>(
+    ctx: &dyn TokenAccess,
+    statements: &'a [LabeledSequentialStatement],
+    clocked: &mut Vec<&'a [LabeledSequentialStatement]>,
+) {
+    for (idx, statement) in statements.iter().enumerate() {
+        match &statement.statement.item {
+            SequentialStatement::Wait(wait) => {
+                if let Some(condition) = &wait.condition_clause {
+                    if is_clock_edge(ctx, condition) {
+                        clocked.push(&statements[idx + 1..]);
+                        return;
+                    }
+                }
+            }
+            SequentialStatement::If(if_stmt) => {
+                for conditional in if_stmt.conds.conditionals.iter() {
+                    if is_clock_edge(ctx, &conditional.condition) {
+                        clocked.push(&conditional.item);
+                    } else {
+                        collect_clocked_statements(ctx, &conditional.item, clocked);
+                    }
+                }
+                if let Some((statements, _)) = &if_stmt.conds.else_item {
+                    collect_clocked_statements(ctx, statements, clocked);
+                }
+            }
+            SequentialStatement::Case(case_stmt) => {
+                for alternative in case_stmt.alternatives.iter() {
+                    collect_clocked_statements(ctx, &alternative.item, clocked);
+                }
+            }
+            SequentialStatement::Loop(loop_stmt) => {
+                collect_clocked_statements(ctx, &loop_stmt.statements, clocked);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Search the statements that are executed when a process is initialized,
+/// i.e. those before the first wait statement that are not within a clock edge condition.
+/// Returns true when a wait statement may have been reached.
+fn search_initial_statements(
+    ctx: &dyn TokenAccess,
+    statements: &[LabeledSequentialStatement],
+    searcher: &mut SignalReads,
+) -> bool {
+    for statement in statements {
+        let reaches_wait = match &statement.statement.item {
+            SequentialStatement::Wait(_) => true,
+            SequentialStatement::If(if_stmt) => {
+                let mut reaches_wait = false;
+                for conditional in if_stmt.conds.conditionals.iter() {
+                    let _ = conditional.condition.search(ctx, searcher);
+                    if !is_clock_edge(ctx, &conditional.condition) {
+                        reaches_wait |= search_initial_statements(ctx, &conditional.item, searcher);
+                    }
+                }
+                if let Some((statements, _)) = &if_stmt.conds.else_item {
+                    reaches_wait |= search_initial_statements(ctx, statements, searcher);
+                }
+                reaches_wait
+            }
+            SequentialStatement::Case(case_stmt) => {
+                let _ = case_stmt.expression.search(ctx, searcher);
+                let mut reaches_wait = false;
+                for alternative in case_stmt.alternatives.iter() {
+                    reaches_wait |= search_initial_statements(ctx, &alternative.item, searcher);
+                }
+                reaches_wait
+            }
+            SequentialStatement::Loop(loop_stmt) => {
+                if let Some(IterationScheme::While(condition)) = &loop_stmt.iteration_scheme {
+                    let _ = condition.search(ctx, searcher);
+                }
+                search_initial_statements(ctx, &loop_stmt.statements, searcher)
+            }
+            _ => {
+                let _ = statement.search(ctx, searcher);
+                false
+            }
+        };
+        if reaches_wait {
+            return true;
+        }
+    }
+    false
+}
+
+/// Collects the signals that are assigned to
+#[derive(Default)]
+struct ClockedTargets {
+    targets: FnvHashSet<EntityId>,
+}
+
+impl Searcher for ClockedTargets {
+    fn search_target(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        target: &WithTokenSpan<Target>,
+    ) -> SearchState {
+        match &target.item {
+            Target::Name(name) => self.targets.extend(prefix_reference(name)),
+            Target::Aggregate(assocs) => {
+                for assoc in assocs {
+                    let (ElementAssociation::Named(_, expr) | ElementAssociation::Positional(expr)) =
+                        &assoc.item;
+                    if let Expression::Name(name) = &expr.item {
+                        self.targets.extend(prefix_reference(name));
+                    }
+                }
+            }
+        }
+        SearchState::NotFinished
+    }
+}
+
+/// Collects the reads of a set of signals, excluding assignment targets
+#[derive(Default)]
+struct SignalReads {
+    signals: FnvHashSet<EntityId>,
+    reads: Vec<(EntityId, SrcPos)>,
+}
+
+impl Searcher for SignalReads {
+    fn search_target(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        _target: &WithTokenSpan<Target>,
+    ) -> SearchState {
+        SearchState::Finished(SearchResult::NotFound)
+    }
+
+    fn search_pos_with_ref(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        reference: &Reference,
+    ) -> SearchState {
+        if let Some(id) = reference.get().filter(|id| self.signals.contains(id)) {
+            self.reads.push((id, pos.clone()));
+        }
+        SearchState::NotFinished
+    }
+}
+
+/// The object that is assigned by a target such as `sig` in `sig(idx).field`
+fn prefix_reference(name: &Name) -> Option<EntityId> {
+    match name {
+        Name::Designator(designator) => designator.reference.get(),
+        Name::Selected(prefix, _) | Name::SelectedAll(prefix) | Name::Slice(prefix, _) => {
+            prefix_reference(&prefix.item)
+        }
+        Name::CallOrIndexed(call) => prefix_reference(&call.name.item),
+        Name::Attribute(_) | Name::External(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{test_config, LibraryBuilder};
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics};
+
+    const SYNTHESIS_CODE: &str = "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+  port (clk, d : in std_logic);
+end entity;
+
+architecture a of ent is
+  signal count : natural := 0;
+  signal q : std_ulogic := '0';
+  signal r : std_logic := '0';
+  signal comb : std_ulogic := '0';
+begin
+  process (clk)
+  begin
+    if rising_edge(clk) then
+      count <= count + 1;
+      q <= d;
+      r <= d;
+    end if;
+  end process;
+
+  process (d)
+  begin
+    comb <= d;
+  end process;
+end architecture;
+";
+
+    #[test]
+    fn warns_on_initial_values_of_clocked_unresolved_signals() {
+        let mut builder = LibraryBuilder::new();
+        builder.add_std_logic_1164();
+        let code = builder.code("libname", SYNTHESIS_CODE);
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        check_diagnostics(
+            initialization_diagnostics(
+                &root,
+                &test_config(
+                    "libname.is_synthesizable = true\nlibname.ignores_initial_values = true",
+                ),
+            ),
+            vec![
+                Diagnostic::new(
+                    code.s1("count"),
+                    "Initial value of signal 'count' may be ignored by synthesis",
+                    ErrorCode::IgnoredInitialValue,
+                ),
+                Diagnostic::new(
+                    code.s1("q :").s1("q"),
+                    "Initial value of signal 'q' may be ignored by synthesis",
+                    ErrorCode::IgnoredInitialValue,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn initial_values_are_only_checked_when_the_target_ignores_them() {
+        let mut builder = LibraryBuilder::new();
+        builder.add_std_logic_1164();
+        builder.code("libname", SYNTHESIS_CODE);
+        let (root, _) = builder.get_analyzed_root();
+
+        for options in [
+            "libname.is_synthesizable = true",
+            "libname.ignores_initial_values = true",
+            "libname.is_synthesizable = true\nlibname.ignores_initial_values = true\nlibname.is_third_party = true",
+        ] {
+            check_no_diagnostics(&initialization_diagnostics(&root, &test_config(options)));
+        }
+    }
+
+    #[test]
+    fn warns_on_uninitialized_reads_in_testbenches() {
+        let mut builder = LibraryBuilder::new();
+        builder.add_std_logic_1164();
+        let code = builder.code(
+            "libname",
+            "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity tb is
+end entity;
+
+architecture a of tb is
+  signal clk : std_logic;
+  signal rst : std_logic := '1';
+  signal data : std_logic_vector(7 downto 0);
+  signal valid : std_logic;
+  signal count : natural;
+begin
+  clkgen: process
+  begin
+    clk <= not clk;
+    wait for 5 ns;
+  end process;
+
+  main: process
+  begin
+    if rst = '1' then
+      count <= count + 1;
+    end if;
+    wait until rising_edge(clk);
+    valid <= data(0);
+    wait;
+  end process;
+
+  check: process (clk)
+  begin
+    if rising_edge(clk) then
+      assert valid = '0';
+    end if;
+  end process;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        check_diagnostics(
+            initialization_diagnostics(&root, &test_config("libname.is_testbench = true")),
+            vec![Diagnostic::new(
+                code.s1("not clk").s1("clk"),
+                "Signal 'clk' has no initial value and is read during initialization",
+                ErrorCode::UninitializedRead,
+            )],
+        );
+        check_no_diagnostics(&initialization_diagnostics(&root, &test_config("")));
+    }
+}
@@ -0,0 +1,169 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::{may_reach_end, DesignRoot};
+use crate::ast::search::{DeclarationItem, FoundDeclaration, Search, SearchState, Searcher};
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::syntax::TokenAccess;
+use crate::Config;
+
+/// Returns a warning for each function body that may reach its end without a return statement.
+/// Reaching the end of a function is an error at run time, the check is approximate
+/// since conditions are not evaluated.
+pub(crate) fn missing_return_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+    let mut searcher = MissingReturn {
+        diagnostics: Vec::new(),
+    };
+    for library in root.libraries() {
+        let Some(library_config) = config.get_library(&library.name().name_utf8()) else {
+            continue;
+        };
+        if library_config.is_third_party {
+            continue;
+        }
+        for unit in library.units() {
+            let _ = unit
+                .unit
+                .expect_analyzed()
+                .search(&unit.tokens, &mut searcher);
+        }
+    }
+    searcher.diagnostics
+}
+
+struct MissingReturn {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Searcher for MissingReturn {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        if let DeclarationItem::Subprogram(body) = decl.ast {
+            if let SubprogramSpecification::Function(function) = &body.specification {
+                if may_reach_end(&body.statements) {
+                    self.diagnostics.push(Diagnostic::new(
+                        ctx.get_pos(body.end_token),
+                        format!(
+                            "Function '{}' may reach the end without a return statement",
+                            function.designator.tree.item
+                        ),
+                        ErrorCode::MissingReturn,
+                    ));
+                }
+            }
+        }
+        SearchState::NotFinished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{test_config, LibraryBuilder};
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics};
+
+    #[test]
+    fn warns_on_functions_that_may_reach_the_end_without_return() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.in_declarative_region(
+            "
+function good_if(arg : boolean) return integer is
+begin
+  if arg then
+    return 1;
+  else
+    return 0;
+  end if;
+end function good_if;
+
+function good_loop return integer is
+begin
+  loop
+    return 0;
+  end loop;
+end function good_loop;
+
+function good_while_true(arg : integer) return integer is
+begin
+  while true loop
+    return arg;
+  end loop;
+end function good_while_true;
+
+function good_nested_exit(x : integer) return integer is
+  variable i : integer := 0;
+begin
+  outer: loop
+    inner: loop
+      i := i + 1;
+      exit inner when i > x;
+    end loop;
+    return i;
+  end loop;
+end function good_nested_exit;
+
+function good_report return integer is
+begin
+  report \"unreachable\" severity failure;
+end function good_report;
+
+function bad_if(arg : boolean) return integer is
+begin
+  if arg then
+    return 1;
+  end if;
+end function bad_if;
+
+function bad_loop return integer is
+begin
+  for i in 0 to 1 loop
+    return i;
+  end loop;
+end function bad_loop;
+
+function bad_exit(x : integer) return integer is
+begin
+  outer: loop
+    inner: loop
+      exit outer when x > 0;
+    end loop;
+    return 0;
+  end loop;
+end function bad_exit;
+
+procedure proc is
+begin
+end procedure proc;
+",
+        );
+
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let config = test_config("");
+        check_diagnostics(
+            missing_return_diagnostics(&root, &config),
+            vec![
+                Diagnostic::new(
+                    code.s1("end function bad_if").s1("end"),
+                    "Function 'bad_if' may reach the end without a return statement",
+                    ErrorCode::MissingReturn,
+                ),
+                Diagnostic::new(
+                    code.s1("end function bad_loop").s1("end"),
+                    "Function 'bad_loop' may reach the end without a return statement",
+                    ErrorCode::MissingReturn,
+                ),
+                Diagnostic::new(
+                    code.s1("end function bad_exit").s1("end"),
+                    "Function 'bad_exit' may reach the end without a return statement",
+                    ErrorCode::MissingReturn,
+                ),
+            ],
+        );
+    }
+}
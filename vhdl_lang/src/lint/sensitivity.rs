@@ -0,0 +1,264 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::initialization::collect_processes;
+use super::signal_usage::{object_name, SignalUsage};
+use crate::analysis::{may_wait, DesignRoot, LockedUnit};
+use crate::ast::search::{DeclarationItem, FoundDeclaration, Search, SearchState, Searcher};
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::named_entity::EntityId;
+use crate::syntax::TokenAccess;
+use crate::Config;
+use std::ops::Deref;
+
+/// Returns a warning for each name in the sensitivity list of a process
+/// that denotes a signal that is never read by the process.
+pub(crate) fn superfluous_sensitivity_diagnostics(
+    root: &DesignRoot,
+    config: &Config,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for library in root.libraries() {
+        let Some(library_config) = config.get_library(&library.name().name_utf8()) else {
+            continue;
+        };
+        if library_config.is_third_party {
+            continue;
+        }
+        for unit in library.units() {
+            for_each_process(unit, |ctx, process| {
+                for (_, name, id) in superfluous_sensitivity(root, ctx, process) {
+                    diagnostics.push(Diagnostic::new(
+                        name.pos(ctx),
+                        format!(
+                            "Signal '{}' is in the sensitivity list but never read by the process",
+                            root.get_ent(id).designator()
+                        ),
+                        ErrorCode::SuperfluousSensitivity,
+                    ));
+                }
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Returns a warning for each process that has neither a sensitivity list nor a wait statement.
+/// Such a process never suspends and hangs the simulation at time zero.
+/// Procedure calls are assumed to possibly wait.
+pub(crate) fn missing_wait_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+    let mut searcher = MissingWait {
+        diagnostics: Vec::new(),
+    };
+    for library in root.libraries() {
+        let Some(library_config) = config.get_library(&library.name().name_utf8()) else {
+            continue;
+        };
+        if library_config.is_third_party {
+            continue;
+        }
+        for unit in library.units() {
+            let _ = unit
+                .unit
+                .expect_analyzed()
+                .search(&unit.tokens, &mut searcher);
+        }
+    }
+    searcher.diagnostics
+}
+
+struct MissingWait {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Searcher for MissingWait {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        if let DeclarationItem::ConcurrentStatement(statement) = decl.ast {
+            if let ConcurrentStatement::Process(process) = &statement.statement.item {
+                if process.sensitivity_list.is_none() && !may_wait(&process.statements) {
+                    self.diagnostics.push(Diagnostic::new(
+                        ctx.get_pos(statement.statement.span.start_token),
+                        "Process has neither a sensitivity list nor a wait statement",
+                        ErrorCode::MissingWait,
+                    ));
+                }
+            }
+        }
+        SearchState::NotFinished
+    }
+}
+
+/// Call `f` for each process of an architecture
+pub(crate) fn for_each_process(
+    unit: &LockedUnit,
+    mut f: impl FnMut(&dyn TokenAccess, &ProcessStatement),
+) {
+    let data = unit.unit.expect_analyzed();
+    let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) = data.deref() else {
+        return;
+    };
+    let mut processes = Vec::new();
+    collect_processes(&arch.statements, &mut processes);
+    for process in processes {
+        f(&unit.tokens, process);
+    }
+}
+
+/// The names of the sensitivity list of `process` that denote a signal that is not read
+/// within the declarations and statements of the process,
+/// together with their index within the list and the denoted signal.
+/// For names such as `rec.field` or `vec(0)`, any read of `rec` or `vec` counts as a read.
+pub(crate) fn superfluous_sensitivity<'a>(
+    root: &DesignRoot,
+    ctx: &dyn TokenAccess,
+    process: &'a ProcessStatement,
+) -> Vec<(usize, &'a WithTokenSpan<Name>, EntityId)> {
+    let Some(WithTokenSpan {
+        item: SensitivityList::Names(names),
+        ..
+    }) = &process.sensitivity_list
+    else {
+        return Vec::new();
+    };
+    let signals: Vec<_> = names
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, name)| {
+            let (_, id) = object_name(ctx, &name.pos(ctx), &name.item)?;
+            Some((idx, name, root.get_ent(id).as_actual().id()))
+        })
+        .collect();
+    if signals.is_empty() {
+        return Vec::new();
+    }
+
+    let mut searcher = SignalUsage::new(root);
+    searcher
+        .signals
+        .extend(signals.iter().map(|(_, _, id)| *id));
+    let _ = process.decl.search(ctx, &mut searcher);
+    let _ = process.statements.search(ctx, &mut searcher);
+
+    signals
+        .into_iter()
+        .filter(|(_, _, id)| !searcher.read.contains(id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{test_config, LibraryBuilder};
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics};
+
+    #[test]
+    fn warns_on_signals_in_sensitivity_list_that_are_not_read() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  port (clk, d, unread : in bit; q : out bit);
+end entity;
+
+architecture a of ent is
+  signal vec : bit_vector(0 to 1);
+  signal idx : natural;
+  signal target : bit_vector(0 to 1);
+begin
+  process (clk, unread, vec(0), idx, target)
+  begin
+    if clk = '1' then
+      q <= d or vec(1);
+      target(idx) <= '0';
+    end if;
+  end process;
+
+  process (all)
+  begin
+    assert d = '1';
+  end process;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let config = test_config("");
+        check_diagnostics(
+            superfluous_sensitivity_diagnostics(&root, &config),
+            vec![
+                Diagnostic::new(
+                    code.s1("unread, vec").s1("unread"),
+                    "Signal 'unread' is in the sensitivity list but never read by the process",
+                    ErrorCode::SuperfluousSensitivity,
+                ),
+                Diagnostic::new(
+                    code.s1("target)").s1("target"),
+                    "Signal 'target' is in the sensitivity list but never read by the process",
+                    ErrorCode::SuperfluousSensitivity,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn warns_on_processes_that_never_suspend() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  port (d : in bit; q : out bit);
+end entity;
+
+architecture a of ent is
+  procedure wait_a_bit is
+  begin
+    wait for 1 ns;
+  end procedure;
+begin
+  comb : process
+  begin
+    q <= d;
+  end process;
+
+  process
+  begin
+    if d = '1' then
+      wait until d = '0';
+    end if;
+  end process;
+
+  process
+  begin
+    wait_a_bit;
+  end process;
+
+  process (d)
+  begin
+    q <= d;
+  end process;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let config = test_config("");
+        check_diagnostics(
+            missing_wait_diagnostics(&root, &config),
+            vec![Diagnostic::new(
+                code.s1("comb : process").s1("process"),
+                "Process has neither a sensitivity list nor a wait statement",
+                ErrorCode::MissingWait,
+            )],
+        );
+    }
+}
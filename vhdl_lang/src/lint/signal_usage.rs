@@ -0,0 +1,325 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::initialization::signals;
+use crate::analysis::DesignRoot;
+use crate::ast::search::{Search, SearchState, Searcher};
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::named_entity::{
+    AnyEntKind, EntityId, InterfaceMode, Object, ObjectInterface, Reference,
+};
+use crate::syntax::TokenAccess;
+use crate::Config;
+use fnv::{FnvHashMap, FnvHashSet};
+use std::ops::Deref;
+
+/// Returns a warning for signals that are read but never driven
+/// and for signals that are driven but never read.
+/// Signals are driven by assignments and by port maps that connect them to ports of mode
+/// `out`, `inout` or `buffer`. Signals that are associated with parameters of procedure calls
+/// or with ports of unknown mode are considered both driven and read.
+/// Only signals that are declared in architectures are considered,
+/// signals that are neither driven nor read are reported as unused instead.
+pub(crate) fn signal_usage_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for library in root.libraries() {
+        let Some(library_config) = config.get_library(&library.name().name_utf8()) else {
+            continue;
+        };
+        if library_config.is_third_party {
+            continue;
+        }
+        for unit in library.units() {
+            let data = unit.unit.expect_analyzed();
+            let AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(arch)) = data.deref()
+            else {
+                continue;
+            };
+            let ctx: &dyn TokenAccess = &unit.tokens;
+            let mut searcher = SignalUsage::new(root);
+            for (ident, _) in signals(arch) {
+                searcher.signals.extend(ident.decl.get());
+            }
+            if searcher.signals.is_empty() {
+                continue;
+            }
+            let _ = arch.search(ctx, &mut searcher);
+
+            for (ident, _) in signals(arch) {
+                let Some(id) = ident.decl.get() else {
+                    continue;
+                };
+                let is_driven = searcher.driven.contains(&id);
+                let is_read = searcher.read.contains(&id);
+                if is_read && !is_driven {
+                    diagnostics.push(Diagnostic::new(
+                        ident.tree.pos(ctx),
+                        format!("Signal '{}' is read but never driven", ident.tree.item),
+                        ErrorCode::UndrivenSignal,
+                    ));
+                } else if is_driven && !is_read {
+                    diagnostics.push(Diagnostic::new(
+                        ident.tree.pos(ctx),
+                        format!("Signal '{}' is driven but never read", ident.tree.item),
+                        ErrorCode::UnreadSignal,
+                    ));
+                }
+            }
+        }
+    }
+    diagnostics
+}
+
+/// How the name at a position accesses the signal that it denotes
+#[derive(Clone, Copy)]
+struct Access {
+    drives: bool,
+    reads: bool,
+}
+
+impl Access {
+    const DRIVE: Access = Access {
+        drives: true,
+        reads: false,
+    };
+    const READ: Access = Access {
+        drives: false,
+        reads: true,
+    };
+    const UNKNOWN: Access = Access {
+        drives: true,
+        reads: true,
+    };
+}
+
+/// Collects which of a set of signals are driven and which are read.
+/// Assignment targets, port maps and procedure calls are searched before their names,
+/// so the access of a name is known when its reference is searched.
+pub(super) struct SignalUsage<'a> {
+    root: &'a DesignRoot,
+    pub(super) signals: FnvHashSet<EntityId>,
+    accesses: FnvHashMap<SrcPos, Access>,
+    pub(super) driven: FnvHashSet<EntityId>,
+    pub(super) read: FnvHashSet<EntityId>,
+}
+
+impl<'a> SignalUsage<'a> {
+    pub(super) fn new(root: &'a DesignRoot) -> Self {
+        SignalUsage {
+            root,
+            signals: FnvHashSet::default(),
+            accesses: FnvHashMap::default(),
+            driven: FnvHashSet::default(),
+            read: FnvHashSet::default(),
+        }
+    }
+
+    fn add_access(&mut self, ctx: &dyn TokenAccess, pos: &SrcPos, name: &Name, access: Access) {
+        if let Some((pos, _)) = object_name(ctx, pos, name) {
+            self.accesses.insert(pos, access);
+        }
+    }
+
+    fn add_actual(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        actual: &WithTokenSpan<ActualPart>,
+        access: Access,
+    ) {
+        if let ActualPart::Expression(Expression::Name(name)) = &actual.item {
+            self.add_access(ctx, &actual.pos(ctx), name, access);
+        }
+    }
+
+    /// How an actual that is associated with `formal` accesses the signal that it denotes
+    fn port_access(&self, formal: Option<EntityId>) -> Access {
+        let Some(formal) = formal else {
+            return Access::UNKNOWN;
+        };
+        match self.root.get_ent(formal).kind() {
+            AnyEntKind::Object(Object {
+                iface: Some(ObjectInterface::Port(InterfaceMode::Simple(mode))),
+                ..
+            }) => match mode {
+                Mode::In => Access::READ,
+                Mode::Out | Mode::Buffer => Access::DRIVE,
+                Mode::InOut | Mode::Linkage => Access::UNKNOWN,
+            },
+            _ => Access::UNKNOWN,
+        }
+    }
+}
+
+impl Searcher for SignalUsage<'_> {
+    fn search_target(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        target: &WithTokenSpan<Target>,
+    ) -> SearchState {
+        match &target.item {
+            Target::Name(name) => self.add_access(ctx, &target.pos(ctx), name, Access::DRIVE),
+            Target::Aggregate(assocs) => {
+                for assoc in assocs {
+                    let (ElementAssociation::Named(_, expr) | ElementAssociation::Positional(expr)) =
+                        &assoc.item;
+                    if let Expression::Name(name) = &expr.item {
+                        self.add_access(ctx, &expr.pos(ctx), name, Access::DRIVE);
+                    }
+                }
+            }
+        }
+        SearchState::NotFinished
+    }
+
+    fn search_instantiation(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        instance: &InstantiationStatement,
+    ) -> SearchState {
+        let Some(port_map) = &instance.port_map else {
+            return SearchState::NotFinished;
+        };
+        let ports = instance
+            .entity_reference()
+            .map(|unit| self.root.ports_of(self.root.get_ent(unit)))
+            .unwrap_or_default();
+        for (position, element) in port_map.list.items.iter().enumerate() {
+            let formal = match &element.formal {
+                Some(formal) => {
+                    object_name(ctx, &formal.pos(ctx), &formal.item).map(|(_, formal)| formal)
+                }
+                None => ports.get(position).copied(),
+            };
+            let access = self.port_access(formal);
+            self.add_actual(ctx, &element.actual, access);
+        }
+        SearchState::NotFinished
+    }
+
+    fn search_procedure_call(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        call: &WithTokenSpan<CallOrIndexed>,
+    ) -> SearchState {
+        for element in call.item.parameters.items.iter() {
+            self.add_actual(ctx, &element.actual, Access::UNKNOWN);
+        }
+        SearchState::NotFinished
+    }
+
+    fn search_pos_with_ref(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        reference: &Reference,
+    ) -> SearchState {
+        let Some(id) = reference.get() else {
+            return SearchState::NotFinished;
+        };
+        // Accesses through an alias access the aliased signal
+        let id = self.root.get_ent(id).as_actual().id();
+        if !self.signals.contains(&id) {
+            return SearchState::NotFinished;
+        }
+        let access = self.accesses.get(pos).copied().unwrap_or(Access::READ);
+        if access.drives {
+            self.driven.insert(id);
+        }
+        if access.reads {
+            self.read.insert(id);
+        }
+        SearchState::NotFinished
+    }
+}
+
+/// The position and declaration of the object that is accessed by a name,
+/// i.e. `sig` in `sig(idx).field`
+pub(super) fn object_name(
+    ctx: &dyn TokenAccess,
+    pos: &SrcPos,
+    name: &Name,
+) -> Option<(SrcPos, EntityId)> {
+    match name {
+        Name::Designator(designator) => Some((pos.clone(), designator.reference.get()?)),
+        Name::Selected(prefix, _) | Name::SelectedAll(prefix) | Name::Slice(prefix, _) => {
+            object_name(ctx, &prefix.pos(ctx), &prefix.item)
+        }
+        Name::CallOrIndexed(call) => object_name(ctx, &call.name.pos(ctx), &call.name.item),
+        Name::Attribute(_) | Name::External(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{test_config, LibraryBuilder};
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics};
+
+    #[test]
+    fn warns_on_undriven_and_unread_signals() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity leaf is
+  port (i : in bit; o : out bit);
+end entity;
+
+architecture a of leaf is
+begin
+  o <= i;
+end architecture;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal undriven : bit;
+  signal unread : bit;
+  signal used : bit_vector(0 to 1);
+  signal from_leaf, to_leaf, to_proc : bit;
+  signal unused : bit;
+  procedure proc(signal s : out bit) is
+  begin
+    s <= '0';
+  end procedure;
+begin
+  unread <= undriven;
+  used(0) <= used(1);
+  used(1) <= '1';
+  inst: entity work.leaf port map (to_leaf, o => from_leaf);
+  to_leaf <= from_leaf;
+  proc(to_proc);
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        check_diagnostics(
+            signal_usage_diagnostics(&root, &test_config("")),
+            vec![
+                Diagnostic::new(
+                    code.s1("undriven :").s1("undriven"),
+                    "Signal 'undriven' is read but never driven",
+                    ErrorCode::UndrivenSignal,
+                ),
+                Diagnostic::new(
+                    code.s1("unread :").s1("unread"),
+                    "Signal 'unread' is driven but never read",
+                    ErrorCode::UnreadSignal,
+                ),
+            ],
+        );
+        check_no_diagnostics(&signal_usage_diagnostics(
+            &root,
+            &test_config("libname.is_third_party = true"),
+        ));
+    }
+}
@@ -0,0 +1,521 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::{bit_string_to_string, DesignRoot, StaticRange, StaticValue};
+use crate::ast::search::{DeclarationItem, FoundDeclaration, Search, SearchState, Searcher};
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::named_entity::EntityId;
+use crate::syntax::TokenAccess;
+use crate::Config;
+use fnv::FnvHashMap;
+
+/// Reports an error for static indices and slice bounds that are outside the statically known
+/// index range of an array, for slices whose direction differs from the direction of the index range
+/// and for array values whose statically known length differs from the length of their target.
+/// Bounds that depend on generics are not checked since they may differ between instances.
+///
+/// Only the units that were analyzed again are searched, the diagnostics of all other units
+/// are kept from the previous call.
+#[derive(Default)]
+pub(crate) struct StaticBoundsLinter {
+    diagnostics: FnvHashMap<UnitId, Vec<Diagnostic>>,
+}
+
+impl StaticBoundsLinter {
+    pub fn lint(
+        &mut self,
+        root: &DesignRoot,
+        config: &Config,
+        analyzed_units: &[UnitId],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        // Prune diagnostics for units that no longer exist
+        self.diagnostics.retain(|unit_id, _| {
+            root.get_lib(unit_id.library_name())
+                .and_then(|library| library.get_unit(unit_id.key()))
+                .is_some()
+        });
+
+        let mut searcher = StaticBounds {
+            root,
+            ranges: FnvHashMap::default(),
+            diagnostics: Vec::new(),
+        };
+        for unit_id in analyzed_units {
+            self.diagnostics.remove(unit_id);
+            if !is_linted(config, unit_id.library_name()) {
+                continue;
+            }
+            let Some(unit) = root
+                .get_lib(unit_id.library_name())
+                .and_then(|library| library.get_unit(unit_id.key()))
+            else {
+                continue;
+            };
+            let _ = unit
+                .unit
+                .expect_analyzed()
+                .search(&unit.tokens, &mut searcher);
+            self.diagnostics
+                .insert(unit_id.clone(), std::mem::take(&mut searcher.diagnostics));
+        }
+
+        for (unit_id, unit_diagnostics) in self.diagnostics.iter() {
+            if is_linted(config, unit_id.library_name()) {
+                diagnostics.append(unit_diagnostics.iter().cloned());
+            }
+        }
+    }
+}
+
+/// Third-party libraries and the standard libraries are not checked.
+fn is_linted(config: &Config, library_name: &Symbol) -> bool {
+    let name = library_name.name_utf8();
+    name != "std"
+        && name != "ieee"
+        && config
+            .get_library(&name)
+            .is_some_and(|library_config| !library_config.is_third_party)
+}
+
+struct StaticBounds<'a> {
+    root: &'a DesignRoot,
+    /// The static index ranges of array objects, looked up at most once per object
+    ranges: FnvHashMap<EntityId, Option<Vec<StaticRange>>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl StaticBounds<'_> {
+    /// The static index ranges of the object denoted by a simple name.
+    fn index_ranges(&mut self, name: &Name) -> Option<Vec<StaticRange>> {
+        let Name::Designator(designator) = name else {
+            return None;
+        };
+        let id = designator.reference.get()?;
+        let root = self.root;
+        self.ranges
+            .entry(id)
+            .or_insert_with(|| root.static_index_ranges_of(root.get_ent(id)))
+            .clone()
+    }
+
+    fn check_indices(&mut self, ctx: &dyn TokenAccess, call: &CallOrIndexed) {
+        let Some(ranges) = self.index_ranges(&call.name.item) else {
+            return;
+        };
+        if ranges.len() != call.parameters.items.len() {
+            return;
+        }
+        for (range, assoc) in ranges.iter().zip(call.parameters.items.iter()) {
+            let ActualPart::Expression(expr) = &assoc.actual.item else {
+                continue;
+            };
+            if assoc.formal.is_some() {
+                continue;
+            }
+            if let Some(StaticValue::Integer(index)) = self.root.evaluate_static(expr, 0) {
+                if !range.contains(index) {
+                    self.diagnostics.push(Diagnostic::new(
+                        assoc.actual.pos(ctx),
+                        format!("Index {index} is out of range {range}"),
+                        ErrorCode::IndexOutOfRange,
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_slice(
+        &mut self,
+        pos: &SrcPos,
+        prefix: &WithTokenSpan<Name>,
+        discrete_range: &DiscreteRange,
+    ) {
+        let range = match self.index_ranges(&prefix.item).as_deref() {
+            Some([range]) => *range,
+            _ => return,
+        };
+        let Some(slice) = self.root.evaluate_static_discrete_range(discrete_range, 0) else {
+            return;
+        };
+        // A null slice is never out of range
+        if slice.is_null() {
+            return;
+        }
+        if slice.direction != range.direction {
+            self.diagnostics.push(Diagnostic::new(
+                pos,
+                format!(
+                    "Direction of slice {slice} does not match the direction of the index range {range}"
+                ),
+                ErrorCode::SliceDirectionMismatch,
+            ));
+        } else if !range.contains(slice.left) || !range.contains(slice.right) {
+            self.diagnostics.push(Diagnostic::new(
+                pos,
+                format!("Slice {slice} is out of range {range}"),
+                ErrorCode::IndexOutOfRange,
+            ));
+        }
+    }
+
+    /// The statically known length of an object or slice name.
+    fn name_length(&mut self, name: &Name) -> Option<i64> {
+        match name {
+            Name::Designator(_) => match self.index_ranges(name)?.as_slice() {
+                [range] => Some(range.length()),
+                _ => None,
+            },
+            Name::Slice(_, discrete_range) => self
+                .root
+                .evaluate_static_discrete_range(discrete_range, 0)
+                .map(|range| range.length()),
+            _ => None,
+        }
+    }
+
+    /// The statically known length of an array value.
+    fn value_length(&mut self, expr: &Expression) -> Option<i64> {
+        match expr {
+            Expression::Literal(Literal::String(value)) => i64::try_from(value.len()).ok(),
+            Expression::Literal(Literal::BitString(value)) => {
+                i64::try_from(bit_string_to_string(value).ok()?.len()).ok()
+            }
+            Expression::Name(name) => self.name_length(name),
+            _ => None,
+        }
+    }
+
+    fn check_length(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        target_length: Option<i64>,
+        value: &WithTokenSpan<Expression>,
+    ) {
+        let Some(length) = self.value_length(&value.item) else {
+            return;
+        };
+        let Some(target_length) = target_length else {
+            return;
+        };
+        if length != target_length {
+            self.diagnostics.push(Diagnostic::new(
+                value.pos(ctx),
+                format!(
+                    "Length of the value ({length}) does not match the length of the target ({target_length})"
+                ),
+                ErrorCode::LengthMismatch,
+            ));
+        }
+    }
+
+    fn check_assignment<'e>(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        target: &WithTokenSpan<Target>,
+        values: impl Iterator<Item = &'e WithTokenSpan<Expression>>,
+    ) {
+        let Target::Name(name) = &target.item else {
+            return;
+        };
+        let mut target_length = None;
+        for value in values {
+            // Only look up the target when there is a value of known length
+            if self.value_length(&value.item).is_none() {
+                continue;
+            }
+            if target_length.is_none() {
+                target_length = Some(self.name_length(name));
+            }
+            self.check_length(ctx, target_length.flatten(), value);
+        }
+    }
+
+    fn check_signal_assignment(&mut self, ctx: &dyn TokenAccess, assignment: &SignalAssignment) {
+        let values = right_hand_items(&assignment.rhs)
+            .into_iter()
+            .flat_map(|waveform| match waveform {
+                Waveform::Elements(elements) => elements.as_slice(),
+                Waveform::Unaffected(_) => &[],
+            })
+            .map(|element| &element.value);
+        self.check_assignment(ctx, &assignment.target, values);
+    }
+}
+
+impl Searcher for StaticBounds<'_> {
+    fn search_name(&mut self, ctx: &dyn TokenAccess, pos: &SrcPos, name: &Name) -> SearchState {
+        match name {
+            Name::CallOrIndexed(call) => self.check_indices(ctx, call),
+            Name::Slice(prefix, discrete_range) => self.check_slice(pos, prefix, discrete_range),
+            _ => {}
+        }
+        SearchState::NotFinished
+    }
+
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        match decl.ast {
+            // Search is called once for each identifier of the declaration
+            DeclarationItem::Object(object)
+                if object
+                    .idents
+                    .first()
+                    .is_some_and(|ident| std::ptr::eq(&ident.decl, decl.reference)) =>
+            {
+                if let Some(expr) = &object.expression {
+                    if self.value_length(&expr.item).is_some() {
+                        let target_length = match self
+                            .root
+                            .static_index_ranges(&object.subtype_indication, 0)
+                            .as_deref()
+                        {
+                            Some([range]) => Some(range.length()),
+                            _ => None,
+                        };
+                        self.check_length(ctx, target_length, expr);
+                    }
+                }
+            }
+            DeclarationItem::SequentialStatement(statement) => match &statement.statement.item {
+                SequentialStatement::VariableAssignment(assignment) => self.check_assignment(
+                    ctx,
+                    &assignment.target,
+                    right_hand_items(&assignment.rhs).into_iter(),
+                ),
+                SequentialStatement::SignalForceAssignment(assignment) => self.check_assignment(
+                    ctx,
+                    &assignment.target,
+                    right_hand_items(&assignment.rhs).into_iter(),
+                ),
+                SequentialStatement::SignalAssignment(assignment) => {
+                    self.check_signal_assignment(ctx, assignment)
+                }
+                _ => {}
+            },
+            DeclarationItem::ConcurrentStatement(statement) => {
+                if let ConcurrentStatement::Assignment(assignment) = &statement.statement.item {
+                    self.check_signal_assignment(ctx, &assignment.assignment)
+                }
+            }
+            _ => {}
+        }
+        SearchState::NotFinished
+    }
+}
+
+/// All alternatives of the right hand side of an assignment.
+fn right_hand_items<T>(rhs: &AssignmentRightHand<T>) -> Vec<&T> {
+    match rhs {
+        AssignmentRightHand::Simple(item) => vec![item],
+        AssignmentRightHand::Conditional(conditionals) => conditionals
+            .conditionals
+            .iter()
+            .map(|conditional| &conditional.item)
+            .chain(conditionals.else_item.iter().map(|(item, _)| item))
+            .collect(),
+        AssignmentRightHand::Selected(selection) => selection
+            .alternatives
+            .iter()
+            .map(|alternative| &alternative.item)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{test_config, LibraryBuilder};
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics};
+
+    fn all_units(root: &DesignRoot) -> Vec<UnitId> {
+        root.libraries()
+            .flat_map(|library| library.units())
+            .map(|unit| unit.unit_id().clone())
+            .collect()
+    }
+
+    fn static_bounds_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        StaticBoundsLinter::default().lint(root, config, &all_units(root), &mut diagnostics);
+        diagnostics
+    }
+
+    #[test]
+    fn errors_on_static_indices_and_slices_out_of_range() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  generic (WIDTH : natural := 8);
+end entity;
+
+architecture a of ent is
+  constant LAST : natural := 7;
+  subtype byte_t is bit_vector(LAST downto 0);
+  type matrix_t is array (0 to 3, 1 to 2) of bit;
+  signal data : byte_t;
+  signal matrix : matrix_t;
+  signal wide : bit_vector(WIDTH - 1 downto 0);
+begin
+  data(LAST + 1) <= '1';
+  data(LAST) <= data(0);
+  matrix(4, 1) <= '0';
+  matrix(3, 0) <= '0';
+  data(0 to 3) <= \"0000\";
+  data(9 downto 6) <= \"0000\";
+  data(3 downto 4) <= \"\";
+  wide(8) <= '1';
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        check_diagnostics(
+            static_bounds_diagnostics(&root, &test_config("")),
+            vec![
+                Diagnostic::new(
+                    code.s1("LAST + 1"),
+                    "Index 8 is out of range 7 downto 0",
+                    ErrorCode::IndexOutOfRange,
+                ),
+                Diagnostic::new(
+                    code.s1("(4, 1)").s1("4"),
+                    "Index 4 is out of range 0 to 3",
+                    ErrorCode::IndexOutOfRange,
+                ),
+                Diagnostic::new(
+                    code.s1("(3, 0)").s1("0"),
+                    "Index 0 is out of range 1 to 2",
+                    ErrorCode::IndexOutOfRange,
+                ),
+                Diagnostic::new(
+                    code.s1("data(0 to 3)"),
+                    "Direction of slice 0 to 3 does not match the direction of the index range 7 downto 0",
+                    ErrorCode::SliceDirectionMismatch,
+                ),
+                Diagnostic::new(
+                    code.s1("data(9 downto 6)"),
+                    "Slice 9 downto 6 is out of range 7 downto 0",
+                    ErrorCode::IndexOutOfRange,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn errors_on_values_whose_length_differs_from_the_target() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal data : bit_vector(7 downto 0) := x\"FFF\";
+  signal nibble : bit_vector(3 downto 0) := \"0000\";
+  constant NAME : string := \"unconstrained\";
+begin
+  nibble <= data;
+  data <= x\"AB\";
+  data(3 downto 0) <= \"101\" when nibble = \"0000\" else nibble;
+
+  process
+    variable word : bit_vector(0 to 15);
+  begin
+    word := data;
+    word(0 to 7) := data;
+    wait;
+  end process;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        check_diagnostics(
+            static_bounds_diagnostics(&root, &test_config("")),
+            vec![
+                Diagnostic::new(
+                    code.s1("x\"FFF\""),
+                    "Length of the value (12) does not match the length of the target (8)",
+                    ErrorCode::LengthMismatch,
+                ),
+                Diagnostic::new(
+                    code.s1("nibble <= data").s1("data"),
+                    "Length of the value (8) does not match the length of the target (4)",
+                    ErrorCode::LengthMismatch,
+                ),
+                Diagnostic::new(
+                    code.s1("\"101\""),
+                    "Length of the value (3) does not match the length of the target (4)",
+                    ErrorCode::LengthMismatch,
+                ),
+                Diagnostic::new(
+                    code.s1("word := data").s1("data"),
+                    "Length of the value (8) does not match the length of the target (16)",
+                    ErrorCode::LengthMismatch,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn keeps_the_diagnostics_of_units_that_were_not_analyzed_again() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal data : bit_vector(7 downto 0);
+begin
+  data(8) <= '1';
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let expected = vec![Diagnostic::new(
+            code.s1("data(8)").s1("8"),
+            "Index 8 is out of range 7 downto 0",
+            ErrorCode::IndexOutOfRange,
+        )];
+        let mut linter = StaticBoundsLinter::default();
+        let mut diagnostics = Vec::new();
+        linter.lint(&root, &test_config(""), &all_units(&root), &mut diagnostics);
+        check_diagnostics(diagnostics, expected.clone());
+
+        let mut diagnostics = Vec::new();
+        linter.lint(&root, &test_config(""), &[], &mut diagnostics);
+        check_diagnostics(diagnostics, expected);
+    }
+
+    #[test]
+    fn does_not_check_standard_libraries() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "ieee",
+            "
+package pkg is
+  constant data : bit_vector(7 downto 0) := (others => '0');
+  constant bit8 : bit := data(8);
+end package;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        let config = test_config("ieee.files = []");
+        check_no_diagnostics(&static_bounds_diagnostics(&root, &config));
+    }
+}
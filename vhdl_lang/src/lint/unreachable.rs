@@ -0,0 +1,496 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::analysis::{DesignRoot, StaticValue};
+use crate::ast::search::{DeclarationItem, FoundDeclaration, Search, SearchState, Searcher};
+use crate::ast::*;
+use crate::data::error_codes::ErrorCode;
+use crate::data::*;
+use crate::syntax::TokenAccess;
+use crate::Config;
+
+/// Returns a warning for the first statement that follows an unconditional
+/// `return`, `exit`, `next` or `wait;` statement within the same sequence of statements
+/// and for the branches of if-generate and case-generate statements that are never elaborated
+/// because of the static values of their conditions or case expression.
+pub(crate) fn unreachable_code_diagnostics(root: &DesignRoot, config: &Config) -> Vec<Diagnostic> {
+    let mut searcher = UnreachableCode {
+        root,
+        diagnostics: Vec::new(),
+    };
+    for library in root.libraries() {
+        let Some(library_config) = config.get_library(&library.name().name_utf8()) else {
+            continue;
+        };
+        if library_config.is_third_party {
+            continue;
+        }
+        for unit in library.units() {
+            let _ = unit
+                .unit
+                .expect_analyzed()
+                .search(&unit.tokens, &mut searcher);
+        }
+    }
+    searcher.diagnostics
+}
+
+struct UnreachableCode<'a> {
+    root: &'a DesignRoot,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl UnreachableCode<'_> {
+    fn check_statements(
+        &mut self,
+        ctx: &dyn TokenAccess,
+        statements: &[LabeledSequentialStatement],
+    ) {
+        let Some(idx) = statements
+            .iter()
+            .position(|statement| is_unconditional_jump(&statement.statement.item))
+        else {
+            return;
+        };
+        if let Some(unreachable) = statements.get(idx + 1) {
+            self.diagnostics.push(
+                Diagnostic::new(
+                    unreachable.statement.pos(ctx),
+                    "Unreachable statement",
+                    ErrorCode::UnreachableCode,
+                )
+                .related(
+                    statements[idx].statement.pos(ctx),
+                    "Any statement after this statement is unreachable",
+                ),
+            );
+        }
+    }
+
+    fn check_generate(&mut self, ctx: &dyn TokenAccess, generate: &IfGenerateStatement) {
+        let mut true_condition: Option<SrcPos> = None;
+        for conditional in generate.conds.conditionals.iter() {
+            if let Some(true_pos) = &true_condition {
+                self.diagnostics.push(
+                    Diagnostic::new(
+                        conditional.condition.pos(ctx),
+                        "A previous condition is statically true, the generate branch is never elaborated",
+                        ErrorCode::UnreachableCode,
+                    )
+                    .related(true_pos, "Condition is statically true"),
+                );
+                continue;
+            }
+            match self.root.evaluate_boolean(&conditional.condition.item, 0) {
+                Some(false) => self.diagnostics.push(Diagnostic::new(
+                    conditional.condition.pos(ctx),
+                    "Condition is statically false, the generate branch is never elaborated",
+                    ErrorCode::UnreachableCode,
+                )),
+                Some(true) => true_condition = Some(conditional.condition.pos(ctx)),
+                None => {}
+            }
+        }
+        if let (Some(true_pos), Some((_, else_token))) = (true_condition, &generate.conds.else_item)
+        {
+            self.diagnostics.push(
+                Diagnostic::new(
+                    ctx.get_pos(*else_token),
+                    "A previous condition is statically true, the generate branch is never elaborated",
+                    ErrorCode::UnreachableCode,
+                )
+                .related(true_pos, "Condition is statically true"),
+            );
+        }
+    }
+
+    fn check_case_generate(&mut self, ctx: &dyn TokenAccess, generate: &CaseGenerateStatement) {
+        let Some(value) = self.root.evaluate_static(&generate.sels.expression.item, 0) else {
+            return;
+        };
+        // Whether each alternative matches the value, `None` if it is not statically known
+        let mut matches: Vec<Option<bool>> = generate
+            .sels
+            .alternatives
+            .iter()
+            .map(|alternative| {
+                let mut result = Some(false);
+                for choice in alternative.choices.iter() {
+                    match self.choice_matches(&choice.item, &value) {
+                        Some(true) => return Some(true),
+                        Some(false) => {}
+                        None => result = None,
+                    }
+                }
+                result
+            })
+            .collect();
+        // `others` matches when no other alternative does
+        let others_match = if matches.contains(&Some(true)) {
+            Some(false)
+        } else if matches.contains(&None) {
+            None
+        } else {
+            Some(true)
+        };
+        for (alternative, is_match) in generate.sels.alternatives.iter().zip(matches.iter_mut()) {
+            if alternative
+                .choices
+                .iter()
+                .any(|choice| matches!(choice.item, Choice::Others))
+            {
+                *is_match = others_match;
+            }
+        }
+
+        for (alternative, is_match) in generate.sels.alternatives.iter().zip(matches) {
+            if is_match != Some(false) {
+                continue;
+            }
+            let (Some(first), Some(last)) =
+                (alternative.choices.first(), alternative.choices.last())
+            else {
+                continue;
+            };
+            self.diagnostics.push(Diagnostic::new(
+                ctx.get_span(first.span.start_token, last.span.end_token),
+                format!(
+                    "Case generate expression is statically {value}, the generate branch is never elaborated"
+                ),
+                ErrorCode::UnreachableCode,
+            ));
+        }
+    }
+
+    /// Whether a choice matches a static value, `None` if it is not statically known.
+    /// `others` is handled by the caller.
+    fn choice_matches(&self, choice: &Choice, value: &StaticValue) -> Option<bool> {
+        match choice {
+            Choice::Expression(expr) => Some(self.root.evaluate_static(expr, 0)? == *value),
+            Choice::DiscreteRange(range) => {
+                let StaticValue::Integer(value) = value else {
+                    return None;
+                };
+                Some(
+                    self.root
+                        .evaluate_static_discrete_range(range, 0)?
+                        .contains(*value),
+                )
+            }
+            Choice::Others => None,
+        }
+    }
+}
+
+impl Searcher for UnreachableCode<'_> {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        match decl.ast {
+            DeclarationItem::Subprogram(body) => self.check_statements(ctx, &body.statements),
+            DeclarationItem::ConcurrentStatement(statement) => match &statement.statement.item {
+                ConcurrentStatement::Process(process) => {
+                    self.check_statements(ctx, &process.statements)
+                }
+                ConcurrentStatement::IfGenerate(generate) => self.check_generate(ctx, generate),
+                ConcurrentStatement::CaseGenerate(generate) => {
+                    self.check_case_generate(ctx, generate)
+                }
+                _ => {}
+            },
+            DeclarationItem::SequentialStatement(statement) => match &statement.statement.item {
+                SequentialStatement::If(if_stmt) => {
+                    for conditional in if_stmt.conds.conditionals.iter() {
+                        self.check_statements(ctx, &conditional.item);
+                    }
+                    if let Some((statements, _)) = &if_stmt.conds.else_item {
+                        self.check_statements(ctx, statements);
+                    }
+                }
+                SequentialStatement::Case(case_stmt) => {
+                    for alternative in case_stmt.alternatives.iter() {
+                        self.check_statements(ctx, &alternative.item);
+                    }
+                }
+                SequentialStatement::Loop(loop_stmt) => {
+                    self.check_statements(ctx, &loop_stmt.statements)
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        SearchState::NotFinished
+    }
+}
+
+/// Statements after which the following statements of the sequence are never executed
+fn is_unconditional_jump(statement: &SequentialStatement) -> bool {
+    match statement {
+        SequentialStatement::Return(_) => true,
+        SequentialStatement::Exit(exit) => exit.condition.is_none(),
+        SequentialStatement::Next(next) => next.condition.is_none(),
+        SequentialStatement::Wait(wait) => {
+            wait.sensitivity_clause.is_none()
+                && wait.condition_clause.is_none()
+                && wait.timeout_clause.is_none()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{test_config, LibraryBuilder};
+    use crate::syntax::test::{check_diagnostics, check_no_diagnostics};
+
+    #[test]
+    fn warns_on_statements_after_unconditional_jumps() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  function fun(arg : natural) return natural is
+  begin
+    return arg;
+    report \"after return\";
+  end function;
+
+  signal sig : natural;
+begin
+  process
+  begin
+    for i in 0 to 3 loop
+      next when i = 1;
+      exit;
+      sig <= i;
+    end loop;
+    wait;
+    sig <= 0;
+  end process;
+
+  process
+  begin
+    sig <= fun(1);
+    wait on sig;
+  end process;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let config = test_config("");
+        check_diagnostics(
+            unreachable_code_diagnostics(&root, &config),
+            vec![
+                Diagnostic::new(
+                    code.s1("report \"after return\";"),
+                    "Unreachable statement",
+                    ErrorCode::UnreachableCode,
+                )
+                .related(
+                    code.s1("return arg;"),
+                    "Any statement after this statement is unreachable",
+                ),
+                Diagnostic::new(
+                    code.s1("sig <= i;"),
+                    "Unreachable statement",
+                    ErrorCode::UnreachableCode,
+                )
+                .related(
+                    code.s1("exit;"),
+                    "Any statement after this statement is unreachable",
+                ),
+                Diagnostic::new(
+                    code.s1("sig <= 0;"),
+                    "Unreachable statement",
+                    ErrorCode::UnreachableCode,
+                )
+                .related(
+                    code.s1("wait;"),
+                    "Any statement after this statement is unreachable",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn warns_on_generate_branches_with_statically_false_condition() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+package pkg is
+  constant DEBUG : boolean := false;
+  constant WIDTH : natural := 8;
+  type mode_t is (slow, fast);
+  constant MODE : mode_t := fast;
+end package;
+
+use work.pkg.all;
+
+entity ent is
+  generic (ENABLE : boolean := false; DEPTH : natural := 0);
+end entity;
+
+architecture a of ent is
+begin
+  gen_false: if false generate
+  end generate;
+
+  gen_debug: if not DEBUG and WIDTH > 4 generate
+  end generate;
+
+  gen_width: if WIDTH /= 8 generate
+  elsif DEBUG generate
+  else generate
+  end generate;
+
+  gen_enable: if ENABLE generate
+  end generate;
+
+  gen_depth: if DEPTH > 0 generate
+  end generate;
+
+  gen_mode: if MODE = slow generate
+  end generate;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let message = "Condition is statically false, the generate branch is never elaborated";
+        let config = test_config("");
+        check_diagnostics(
+            unreachable_code_diagnostics(&root, &config),
+            vec![
+                Diagnostic::new(
+                    code.s1("if false").s1("false"),
+                    message,
+                    ErrorCode::UnreachableCode,
+                ),
+                Diagnostic::new(code.s1("WIDTH /= 8"), message, ErrorCode::UnreachableCode),
+                Diagnostic::new(
+                    code.s1("elsif DEBUG").s1("DEBUG"),
+                    message,
+                    ErrorCode::UnreachableCode,
+                ),
+                Diagnostic::new(code.s1("MODE = slow"), message, ErrorCode::UnreachableCode),
+            ],
+        );
+    }
+
+    #[test]
+    fn warns_on_generate_branches_after_statically_true_condition() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  generic (ENABLE : boolean := false);
+end entity;
+
+architecture a of ent is
+  constant WIDTH : natural := 8;
+begin
+  gen_width: if ENABLE generate
+  elsif WIDTH = 8 generate
+  elsif WIDTH = 16 generate
+  else generate
+  end generate;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let message =
+            "A previous condition is statically true, the generate branch is never elaborated";
+        let config = test_config("");
+        check_diagnostics(
+            unreachable_code_diagnostics(&root, &config),
+            vec![
+                Diagnostic::new(code.s1("WIDTH = 16"), message, ErrorCode::UnreachableCode)
+                    .related(code.s1("WIDTH = 8"), "Condition is statically true"),
+                Diagnostic::new(code.s1("else"), message, ErrorCode::UnreachableCode)
+                    .related(code.s1("WIDTH = 8"), "Condition is statically true"),
+            ],
+        );
+    }
+
+    #[test]
+    fn warns_on_case_generate_alternatives_that_never_match() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+  generic (SEL : natural := 0);
+end entity;
+
+architecture a of ent is
+  type mode_t is (slow, medium, fast);
+  constant MODE : mode_t := fast;
+  constant WIDTH : natural := 8;
+begin
+  gen_mode: case MODE generate
+    when slow | medium =>
+    when fast =>
+  end generate;
+
+  gen_width: case WIDTH generate
+    when 0 to 4 =>
+    when 5 to 8 =>
+    when others =>
+  end generate;
+
+  gen_other: case WIDTH + 1 generate
+    when 0 =>
+    when others =>
+  end generate;
+
+  gen_sel: case SEL generate
+    when 0 =>
+    when others =>
+  end generate;
+end architecture;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let config = test_config("");
+        check_diagnostics(
+            unreachable_code_diagnostics(&root, &config),
+            vec![
+                Diagnostic::new(
+                    code.s1("slow | medium"),
+                    "Case generate expression is statically fast, the generate branch is never elaborated",
+                    ErrorCode::UnreachableCode,
+                ),
+                Diagnostic::new(
+                    code.s1("0 to 4"),
+                    "Case generate expression is statically 8, the generate branch is never elaborated",
+                    ErrorCode::UnreachableCode,
+                ),
+                Diagnostic::new(
+                    code.s("others", 1),
+                    "Case generate expression is statically 8, the generate branch is never elaborated",
+                    ErrorCode::UnreachableCode,
+                ),
+                Diagnostic::new(
+                    code.s1("when 0 =>").s1("0"),
+                    "Case generate expression is statically 9, the generate branch is never elaborated",
+                    ErrorCode::UnreachableCode,
+                ),
+            ],
+        );
+    }
+}
@@ -0,0 +1,491 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Export of a project as an LSIF 0.4.3 index, such that code browsers like Sourcegraph
+//! can offer go to definition, find references and hovers without running a language server.
+//!
+//! Only files of libraries that are not third-party libraries are indexed.
+//! References to declarations of third-party libraries, such as `ieee`, still have hovers.
+
+use crate::analysis::DesignRoot;
+use crate::ast::json::Json;
+use crate::ast::search::{FoundDeclaration, SearchState, Searcher};
+use crate::data::HasSrcPos;
+use crate::named_entity::{EntRef, EntityId, HasEntityId, Reference, Related};
+use crate::syntax::TokenAccess;
+use crate::{Config, Position, Source, SrcPos};
+use fnv::{FnvHashMap, FnvHashSet};
+use itertools::Itertools;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+const LSIF_VERSION: &str = "0.4.3";
+
+/// Returns the vertices and edges of the index in the order they must be written.
+/// URIs of documents are absolute, `project_root` is the root of the repository.
+pub(crate) fn lsif_index(root: &DesignRoot, config: &Config, project_root: &Path) -> Vec<Json> {
+    let sources: FnvHashSet<Source> = root
+        .libraries()
+        .filter(|library| {
+            config
+                .get_library(&library.name().name_utf8())
+                .is_some_and(|library_config| !library_config.is_third_party)
+        })
+        .flat_map(|library| library.units().map(|unit| unit.pos().source.clone()))
+        .collect();
+    index_sources(root, &sources, project_root)
+}
+
+/// A name in an indexed file that declares or references an entity
+struct Occurrence {
+    pos: SrcPos,
+    ent: EntityId,
+    is_definition: bool,
+}
+
+struct OccurrenceSearcher<'a> {
+    root: &'a DesignRoot,
+    sources: &'a FnvHashSet<Source>,
+    occurrences: Vec<Occurrence>,
+    // Files that are part of several libraries are searched more than once
+    visited: FnvHashSet<SrcPos>,
+}
+
+impl OccurrenceSearcher<'_> {
+    fn add(&mut self, pos: &SrcPos, ent: EntRef<'_>, is_definition: bool) {
+        if self.sources.contains(&pos.source) && self.visited.insert(pos.clone()) {
+            self.occurrences.push(Occurrence {
+                pos: pos.clone(),
+                ent: declaring_ent(ent).id(),
+                is_definition,
+            });
+        }
+    }
+}
+
+impl Searcher for OccurrenceSearcher<'_> {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        if let Some(ent) = decl.ent_id().map(|id| self.root.get_ent(id)) {
+            if let Some(pos) = ent.decl_pos() {
+                // Bodies are references to the declaration they complete
+                let is_definition = !matches!(ent.related, Related::DeclaredBy(_));
+                self.add(pos, ent, is_definition);
+            }
+            if let Some(token) = decl.end_ident_pos() {
+                self.add(ctx.get_pos(token), ent, false);
+            }
+        }
+        SearchState::NotFinished
+    }
+
+    fn search_pos_with_ref(
+        &mut self,
+        _ctx: &dyn TokenAccess,
+        pos: &SrcPos,
+        reference: &Reference,
+    ) -> SearchState {
+        if let Some(id) = reference.get() {
+            self.add(pos, self.root.get_ent(id), false);
+        }
+        SearchState::NotFinished
+    }
+}
+
+/// The entity that is shown for a name, such as the declaration of a subprogram for its body
+fn declaring_ent(ent: EntRef<'_>) -> EntRef<'_> {
+    match ent.related {
+        Related::DeclaredBy(declaration) => declaration,
+        _ => ent,
+    }
+}
+
+/// The contents of a hover, like the hover of the language server
+fn hover(root: &DesignRoot, ent: EntRef<'_>) -> Option<String> {
+    let mut value = format!("```vhdl\n{}\n```", root.format_declaration(ent)?);
+    if let Some(interface_table) = root.format_interface_table(ent) {
+        value = format!("{value}\n\n{interface_table}");
+    }
+    Some(value)
+}
+
+fn index_sources(
+    root: &DesignRoot,
+    sources: &FnvHashSet<Source>,
+    project_root: &Path,
+) -> Vec<Json> {
+    let mut searcher = OccurrenceSearcher {
+        root,
+        sources,
+        occurrences: Vec::new(),
+        visited: FnvHashSet::default(),
+    };
+    let _ = root.search(&mut searcher);
+    let occurrences = searcher
+        .occurrences
+        .into_iter()
+        .sorted_by(|a, b| {
+            (a.pos.file_name(), a.pos.range.start).cmp(&(b.pos.file_name(), b.pos.range.start))
+        })
+        .collect_vec();
+
+    let mut index = Index::default();
+    index.vertex(
+        "metaData",
+        [
+            ("version", string(LSIF_VERSION)),
+            ("projectRoot", string(&file_uri(project_root))),
+            ("positionEncoding", string("utf-16")),
+            (
+                "toolInfo",
+                object([
+                    ("name", string("vhdl_lang")),
+                    ("version", string(env!("CARGO_PKG_VERSION"))),
+                ]),
+            ),
+        ],
+    );
+    let project = index.vertex("project", [("kind", string("vhdl"))]);
+
+    let mut documents: FnvHashMap<&Source, usize> = FnvHashMap::default();
+    for source in sources.iter().sorted_by_key(|source| source.file_name()) {
+        let document = index.vertex(
+            "document",
+            [
+                ("uri", string(&file_uri(source.file_name()))),
+                ("languageId", string("vhdl")),
+            ],
+        );
+        documents.insert(source, document);
+    }
+    index.edge("contains", project, documents.values().copied().sorted());
+
+    // One result set per entity that is shared by all of its occurrences
+    let mut result_sets: FnvHashMap<EntityId, usize> = FnvHashMap::default();
+    for occurrence in occurrences.iter() {
+        if result_sets.contains_key(&occurrence.ent) {
+            continue;
+        }
+        let result_set = index.vertex("resultSet", []);
+        result_sets.insert(occurrence.ent, result_set);
+        if let Some(value) = hover(root, root.get_ent(occurrence.ent)) {
+            let hover_result = index.vertex(
+                "hoverResult",
+                [(
+                    "result",
+                    object([(
+                        "contents",
+                        object([("kind", string("markdown")), ("value", string(&value))]),
+                    )]),
+                )],
+            );
+            index.edge("textDocument/hover", result_set, [hover_result]);
+        }
+    }
+
+    let mut ranges: Vec<usize> = Vec::with_capacity(occurrences.len());
+    for occurrence in occurrences.iter() {
+        let range = occurrence.pos.range;
+        let vertex = index.vertex(
+            "range",
+            [
+                ("start", position(range.start)),
+                ("end", position(range.end)),
+            ],
+        );
+        index.edge("next", vertex, [result_sets[&occurrence.ent]]);
+        ranges.push(vertex);
+    }
+
+    for (source, group) in &zip_ranges(&occurrences, &ranges).chunk_by(|(occ, _)| &occ.pos.source) {
+        index.edge(
+            "contains",
+            documents[source],
+            group.map(|(_, range)| range).collect_vec(),
+        );
+    }
+
+    let by_ent = zip_ranges(&occurrences, &ranges).into_group_map_by(|(occ, _)| occ.ent);
+    for ent in occurrences.iter().map(|occ| occ.ent).unique() {
+        let result_set = result_sets[&ent];
+        let occurrences = &by_ent[&ent];
+
+        let definitions = occurrences
+            .iter()
+            .filter(|(occ, _)| occ.is_definition)
+            .collect_vec();
+        if !definitions.is_empty() {
+            let definition_result = index.vertex("definitionResult", []);
+            index.edge("textDocument/definition", result_set, [definition_result]);
+            for (source, group) in &definitions.iter().chunk_by(|(occ, _)| &occ.pos.source) {
+                index.item_edge(
+                    definition_result,
+                    group.map(|(_, range)| *range).collect_vec(),
+                    documents[source],
+                    None,
+                );
+            }
+        }
+
+        let reference_result = index.vertex("referenceResult", []);
+        index.edge("textDocument/references", result_set, [reference_result]);
+        for ((source, is_definition), group) in &occurrences
+            .iter()
+            .sorted_by_key(|(occ, _)| (occ.pos.file_name(), !occ.is_definition))
+            .chunk_by(|(occ, _)| (&occ.pos.source, occ.is_definition))
+        {
+            index.item_edge(
+                reference_result,
+                group.map(|(_, range)| *range).collect_vec(),
+                documents[source],
+                Some(if is_definition {
+                    "definitions"
+                } else {
+                    "references"
+                }),
+            );
+        }
+    }
+    index.elements
+}
+
+fn zip_ranges<'a>(
+    occurrences: &'a [Occurrence],
+    ranges: &'a [usize],
+) -> impl Iterator<Item = (&'a Occurrence, usize)> {
+    occurrences.iter().zip(ranges.iter().copied())
+}
+
+/// Writes the index with one vertex or edge per line
+pub fn write_lsif(index: &[Json], writer: &mut impl Write) -> io::Result<()> {
+    for element in index {
+        writeln!(writer, "{element}")?;
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Index {
+    elements: Vec<Json>,
+}
+
+impl Index {
+    fn add<const N: usize>(
+        &mut self,
+        kind: &str,
+        label: &str,
+        members: [(&str, Json); N],
+    ) -> usize {
+        let id = self.elements.len() + 1;
+        let mut element = vec![
+            ("id".to_owned(), number(id)),
+            ("type".to_owned(), string(kind)),
+            ("label".to_owned(), string(label)),
+        ];
+        element.extend(
+            members
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value)),
+        );
+        self.elements.push(Json::Object(element));
+        id
+    }
+
+    fn vertex<const N: usize>(&mut self, label: &str, members: [(&str, Json); N]) -> usize {
+        self.add("vertex", label, members)
+    }
+
+    fn edge(&mut self, label: &str, out_v: usize, in_vs: impl IntoIterator<Item = usize>) {
+        let in_vs = in_vs.into_iter().collect_vec();
+        if in_vs.len() == 1 && !matches!(label, "contains" | "item") {
+            self.add(
+                "edge",
+                label,
+                [("outV", number(out_v)), ("inV", number(in_vs[0]))],
+            );
+        } else {
+            self.add(
+                "edge",
+                label,
+                [
+                    ("outV", number(out_v)),
+                    ("inVs", Json::Array(in_vs.into_iter().map(number).collect())),
+                ],
+            );
+        }
+    }
+
+    fn item_edge(
+        &mut self,
+        out_v: usize,
+        in_vs: Vec<usize>,
+        document: usize,
+        property: Option<&str>,
+    ) {
+        let in_vs = ("inVs", Json::Array(in_vs.into_iter().map(number).collect()));
+        match property {
+            Some(property) => self.add(
+                "edge",
+                "item",
+                [
+                    ("outV", number(out_v)),
+                    in_vs,
+                    ("document", number(document)),
+                    ("property", string(property)),
+                ],
+            ),
+            None => self.add(
+                "edge",
+                "item",
+                [
+                    ("outV", number(out_v)),
+                    in_vs,
+                    ("document", number(document)),
+                ],
+            ),
+        };
+    }
+}
+
+/// A `file://` URI of an absolute path, where reserved characters are percent-encoded
+fn file_uri(path: &Path) -> String {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_owned());
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        // Windows paths such as C:/dir
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~:".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+fn position(position: Position) -> Json {
+    object([
+        ("line", number(position.line as usize)),
+        ("character", number(position.character as usize)),
+    ])
+}
+
+fn number(value: usize) -> Json {
+    Json::Number(value.to_string())
+}
+
+fn string(value: &str) -> Json {
+    Json::String(value.to_owned())
+}
+
+fn object<const N: usize>(members: [(&str, Json); N]) -> Json {
+    Json::Object(
+        members
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::syntax::test::check_no_diagnostics;
+
+    fn member<'a>(element: &'a Json, key: &str) -> Option<&'a Json> {
+        match element {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn label(element: &Json) -> String {
+        match member(element, "label") {
+            Some(Json::String(label)) => label.clone(),
+            _ => panic!("Missing label"),
+        }
+    }
+
+    #[test]
+    fn indexes_definitions_references_and_hovers() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+package pkg is
+  -- The width of the bus
+  constant width : natural := 8;
+  constant max : natural := width - 1;
+end package;",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let sources = FnvHashSet::from_iter([code.source().clone()]);
+        let index = index_sources(&root, &sources, Path::new("/"));
+
+        // Ids are the one-based position in the index
+        for (i, element) in index.iter().enumerate() {
+            assert_eq!(member(element, "id"), Some(&number(i + 1)));
+        }
+        let labels = index.iter().map(label).collect_vec();
+        assert_eq!(labels[0], "metaData");
+        assert_eq!(
+            labels.iter().filter(|label| *label == "document").count(),
+            1
+        );
+        // pkg, width, max, the reference to width, the end label pkg and natural twice
+        assert_eq!(labels.iter().filter(|label| *label == "range").count(), 7);
+
+        let width = code.s("width", 2);
+        let hover = index
+            .iter()
+            .find(|element| {
+                label(element) == "hoverResult" && element.to_string().contains("width : natural")
+            })
+            .expect("Expected a hover of width");
+        assert!(hover
+            .to_string()
+            .contains("-- The width of the bus\\nconstant width : natural := 8;"));
+
+        let references = index
+            .iter()
+            .filter(|element| {
+                label(element) == "item"
+                    && member(element, "property") == Some(&string("references"))
+            })
+            .count();
+        // The references to width, pkg and natural, which is declared in a file that is not indexed
+        assert_eq!(references, 3);
+        assert_eq!(
+            index
+                .iter()
+                .filter(|element| label(element) == "definitionResult")
+                .count(),
+            3
+        );
+        let width_range = index.iter().find(|element| {
+            label(element) == "range"
+                && member(element, "start") == Some(&position(width.pos().range.start))
+        });
+        assert!(width_range.is_some());
+    }
+
+    #[test]
+    fn uris_are_percent_encoded() {
+        assert_eq!(
+            file_uri(Path::new("/my dir/file.vhd")),
+            "file:///my%20dir/file.vhd"
+        );
+    }
+}
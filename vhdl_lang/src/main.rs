@@ -8,10 +8,24 @@ use clap::Parser;
 use itertools::Itertools;
 use std::iter::zip;
 use std::path::{Path, PathBuf};
-use vhdl_lang::ast::DesignFile;
+use vhdl_lang::ast::json::Json;
+use vhdl_lang::ast::{AnyKind, DesignFile, PrimaryKind};
+use vhdl_lang::baseline::Baseline;
+use vhdl_lang::changes::Changes;
+use vhdl_lang::compile_order::{write_compile_order, CompileOrderFormat};
+use vhdl_lang::dependencies::GraphFormat;
+use vhdl_lang::junit::to_junit;
+use vhdl_lang::lsif::write_lsif;
+use vhdl_lang::sarif::to_sarif;
+use vhdl_lang::scaffold::{suggest_libraries, write_config, LibraryGrouping};
+use vhdl_lang::snapshot::{
+    extract_snapshot, write_anonymized_project, write_snapshot, SNAPSHOT_CONFIG,
+};
+use vhdl_lang::tags::{write_ctags, write_etags, TagsFormat};
 use vhdl_lang::{
-    Config, Diagnostic, MessagePrinter, Project, Severity, SeverityMap, Source, VHDLFormatter,
-    VHDLParser, VHDLStandard,
+    apply_edits, split_lines_inclusive, Config, Diagnostic, FixRule, FormattingOptions,
+    Latin1String, MessageHandler, MessagePrinter, NullMessages, Position, Project, Range, Severity,
+    SeverityMap, Source, TextEdit, TopLevel, VHDLFormatter, VHDLParser, VHDLStandard,
 };
 
 #[derive(Debug, clap::Args)]
@@ -26,31 +40,374 @@ pub struct Group {
     /// This is experimental and the formatting behavior will change in the future.
     #[arg(short, long)]
     format: Option<String>,
+
+    /// Parse the passed file and write its syntax tree as JSON to stdout.
+    ///
+    /// Tokens are written as the range of the source they cover, using zero-based lines and characters.
+    #[arg(long)]
+    dump_ast: Option<String>,
 }
 
 /// Run vhdl analysis
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Args {
     /// The number of threads to use. By default, the maximum is selected based on process cores
     #[arg(short = 'p', long)]
     num_threads: Option<usize>,
 
     /// Path to the config file for the VHDL standard libraries (i.e., IEEE std_logic_1164).
-    /// If omitted, will search for these libraries in a set of standard paths
+    /// If omitted, the `standard_libraries` of the config file are used,
+    /// or these libraries are searched for in a set of standard paths
     #[arg(short = 'l', long)]
     libraries: Option<String>,
 
+    /// The format of the diagnostics of a project passed using `--config`.
+    /// `sarif` writes a SARIF 2.1.0 log for the code scanning of CI systems,
+    /// `junit` a JUnit XML report for CI systems that show test results
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    output_format: OutputFormat,
+
+    /// The least severity of a diagnostic that makes the analysis fail with exit code 1,
+    /// one of `error`, `warning`, `info` and `hint`
+    #[arg(long, default_value = "error")]
+    fail_on: Severity,
+
+    /// The maximum number of diagnostics to print.
+    /// Diagnostics that are not printed still determine the exit code
+    #[arg(long)]
+    max_diagnostics: Option<usize>,
+
+    /// Write all diagnostics of the project passed using `--config` to a baseline file
+    /// instead of printing them
+    #[arg(long, conflicts_with = "baseline")]
+    write_baseline: Option<PathBuf>,
+
+    /// Only report diagnostics that are not part of the baseline file written by `--write-baseline`.
+    /// Diagnostics are matched by file, code, message and the text of their line,
+    /// so that they are still matched when lines are added or removed above them
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Only report diagnostics in files that were changed relative to a git revision,
+    /// i.e., the target branch of a pull request. Files that are not tracked by git count as changed
+    #[arg(long, value_name = "REVISION")]
+    changed_since: Option<String>,
+
+    /// Only report diagnostics on lines that were changed relative to the `--changed-since` revision
+    #[arg(long, requires = "changed_since")]
+    changed_lines: bool,
+
+    /// Analyze the contents of the file passed using `--filename` from stdin instead of the file,
+    /// i.e., an unsaved editor buffer. Only the diagnostics of this file are printed.
+    /// Files that are not part of the project passed using `--config` are analyzed in library `work`
+    #[arg(long, requires = "filename", conflicts_with_all = ["format", "dump_ast"])]
+    stdin: bool,
+
+    /// The path of the file that is read from stdin
+    #[arg(long, requires = "stdin")]
+    filename: Option<PathBuf>,
+
     #[clap(flatten)]
     group: Group,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum OutputFormat {
+    /// One message per diagnostic
+    Text,
+    /// A SARIF 2.1.0 log
+    Sarif,
+    /// A JUnit XML report with one failed test case per diagnostic
+    Junit,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum TreeFormat {
+    /// One indented line per instance, block or generate statement
+    Text,
+    /// An array with one nested object per top level
+    Json,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Apply all machine-applicable fixes of the selected rules to the project files.
+    ///
+    /// Requires the project to be passed using `--config`.
+    Fix {
+        /// Comma-separated list of rules to fix, i.e. `keyword_case,unused_use`.
+        /// Available rules are `keyword_case`, `unused_use`, `superfluous_sensitivity` and `end_label`
+        #[arg(long, value_delimiter = ',', required = true)]
+        rules: Vec<FixRule>,
+
+        /// Print a diff of the changes instead of writing them to the files
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Bundle the configuration and all files of the project into a single archive.
+    ///
+    /// The archive can be replayed using the `replay` command to reproduce the analysis
+    /// without access to the original project.
+    /// Requires the project to be passed using `--config`.
+    Snapshot {
+        /// Path of the archive to create
+        #[arg(short, long, default_value = "snapshot.tar")]
+        output: PathBuf,
+
+        /// Anonymize identifiers, string literals and comments of all files
+        /// that are not part of a third-party library
+        #[arg(long)]
+        anonymize: bool,
+    },
+    /// Write an anonymized copy of the project that can be shared for debugging.
+    ///
+    /// User identifiers and string literals are renamed consistently and comments are removed.
+    /// Names from third-party libraries, such as `ieee`, are preserved.
+    /// Requires the project to be passed using `--config`.
+    Anonymize {
+        /// Directory to write the anonymized project to
+        #[arg(short, long, default_value = "anonymized")]
+        output: PathBuf,
+    },
+    /// Write a tags file with all declarations of the project for editors without LSP support.
+    ///
+    /// Requires the project to be passed using `--config`.
+    Tags {
+        /// Either `ctags` (universal-ctags compatible, for Vim) or `etags` (for Emacs)
+        #[arg(long, default_value = "ctags")]
+        format: TagsFormat,
+
+        /// Path of the tags file to create. Defaults to `tags` for ctags and `TAGS` for etags
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write the dependencies between the design units of the project as a graph.
+    ///
+    /// Dependencies within a cycle are highlighted in DOT and listed in JSON.
+    /// Requires the project to be passed using `--config`.
+    Dependencies {
+        /// Either `dot` (for Graphviz) or `json`
+        #[arg(long, default_value = "dot")]
+        format: GraphFormat,
+
+        /// Path of the file to create. Defaults to `dependencies.dot` or `dependencies.json`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write the order in which a simulator must compile the files of the project.
+    ///
+    /// Files of third-party libraries are left out.
+    /// Requires the project to be passed using `--config`.
+    CompileOrder {
+        /// One of `text`, `json`, `ghdl` (a shell script), `modelsim` or `riviera` (a do file)
+        #[arg(long, default_value = "text")]
+        format: CompileOrderFormat,
+
+        /// Path of the file to create. Defaults to a name depending on the format,
+        /// i.e. `compile_order.txt` or `compile_ghdl.sh`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write an LSIF index of the project for code browsers such as Sourcegraph.
+    ///
+    /// The index contains the definitions, references and hovers of all files
+    /// that are not part of a third-party library.
+    /// Requires the project to be passed using `--config`.
+    Index {
+        /// Path of the index to create
+        #[arg(short, long, default_value = "dump.lsif")]
+        output: PathBuf,
+
+        /// The root of the repository that contains the project.
+        /// Defaults to the current directory
+        #[arg(long)]
+        project_root: Option<PathBuf>,
+    },
+    /// Print the elaborated instance hierarchy below a top-level entity.
+    ///
+    /// Instances are shown with their label, entity, architecture and static generic values.
+    /// Requires the project to be passed using `--config`.
+    Tree {
+        /// The top-level entity, written as `library.entity` or `library.entity(architecture)`.
+        /// The library `work` denotes any library containing the entity.
+        /// Defaults to the top levels of the configuration
+        #[arg(long)]
+        top: Option<TopLevel>,
+
+        /// Either `text` or `json`
+        #[arg(long, value_enum, default_value_t = TreeFormat::Text)]
+        format: TreeFormat,
+    },
+    /// Format files in place, or check that they are formatted.
+    ///
+    /// Directories are searched recursively for files ending in `.vhd` or `.vhdl`.
+    /// The style and the standard are read from the project passed using `--config`, if any.
+    Format {
+        /// Do not write any file, but fail when a file is not formatted
+        #[arg(long)]
+        check: bool,
+
+        /// Only format the design units that overlap the lines `FIRST:LAST` of each file,
+        /// counting from 1. All other text is kept as it is, including its line endings.
+        /// Files without a design unit on these lines are not changed.
+        #[arg(long, value_name = "FIRST:LAST", value_parser = parse_line_range)]
+        lines: Option<Range>,
+
+        /// The files and directories to format
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Write a starter `vhdl_ls.toml` for the VHDL files found below a directory.
+    ///
+    /// Directories are searched recursively for files ending in `.vhd` or `.vhdl`,
+    /// skipping hidden directories. The configuration is written to the scanned directory.
+    Init {
+        /// The directory to scan
+        #[arg(default_value = ".")]
+        directory: PathBuf,
+
+        /// How files are grouped into libraries. Either `directory` (one library per directory,
+        /// named after it), `top-directory` (one library per top-level directory) or `single`
+        #[arg(long, default_value = "directory")]
+        group_by: LibraryGrouping,
+
+        /// The name of the library when grouping by `single`,
+        /// and of files whose directory is not a valid library name
+        #[arg(long, default_value = "lib")]
+        library: String,
+
+        /// Overwrite an existing `vhdl_ls.toml`
+        #[arg(long)]
+        force: bool,
+    },
+    /// Analyze a project archive created using the `snapshot` command
+    Replay {
+        /// Path of the archive to replay
+        snapshot: PathBuf,
+    },
 }
 
 fn main() {
     let args = Args::parse();
-    if let Some(config_path) = args.group.config {
-        parse_and_analyze_project(config_path, args.num_threads, args.libraries);
-    } else if let Some(format) = args.group.format {
-        format_file(format);
+    match args.command {
+        Some(Command::Fix { rules, dry_run }) => {
+            let config_path = require_config(args.group.config, "fix");
+            fix_project(
+                config_path,
+                args.num_threads,
+                args.libraries,
+                &rules,
+                dry_run,
+            );
+        }
+        Some(Command::Snapshot { output, anonymize }) => {
+            let config_path = require_config(args.group.config, "snapshot");
+            snapshot_project(config_path, args.libraries, &output, anonymize);
+        }
+        Some(Command::Anonymize { output }) => {
+            let config_path = require_config(args.group.config, "anonymize");
+            anonymize_project(config_path, args.libraries, &output);
+        }
+        Some(Command::Tags { format, output }) => {
+            let config_path = require_config(args.group.config, "tags");
+            let output = output.unwrap_or_else(|| PathBuf::from(format.default_file_name()));
+            write_tags(
+                config_path,
+                args.num_threads,
+                args.libraries,
+                format,
+                &output,
+            );
+        }
+        Some(Command::Dependencies { format, output }) => {
+            let config_path = require_config(args.group.config, "dependencies");
+            let output = output.unwrap_or_else(|| PathBuf::from(format.default_file_name()));
+            write_dependencies(
+                config_path,
+                args.num_threads,
+                args.libraries,
+                format,
+                &output,
+            );
+        }
+        Some(Command::CompileOrder { format, output }) => {
+            let config_path = require_config(args.group.config, "compile-order");
+            let output = output.unwrap_or_else(|| PathBuf::from(format.default_file_name()));
+            write_compile_order_file(
+                config_path,
+                args.num_threads,
+                args.libraries,
+                format,
+                &output,
+            );
+        }
+        Some(Command::Index {
+            output,
+            project_root,
+        }) => {
+            let config_path = require_config(args.group.config, "index");
+            let project_root =
+                project_root.unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+            write_index(
+                config_path,
+                args.num_threads,
+                args.libraries,
+                &project_root,
+                &output,
+            );
+        }
+        Some(Command::Tree { top, format }) => {
+            let config_path = require_config(args.group.config, "tree");
+            print_tree(config_path, args.num_threads, args.libraries, top, format);
+        }
+        Some(Command::Format {
+            check,
+            lines,
+            paths,
+        }) => format_files(args.group.config, &paths, check, lines),
+        Some(Command::Init {
+            directory,
+            group_by,
+            library,
+            force,
+        }) => init_config(&directory, group_by, &library, force),
+        Some(Command::Replay { snapshot }) => replay_snapshot(
+            &snapshot,
+            args.num_threads,
+            ExitOptions {
+                fail_on: args.fail_on,
+                max_diagnostics: args.max_diagnostics,
+            },
+        ),
+        None => {
+            if let Some(config_path) = args.group.config {
+                parse_and_analyze_project(
+                    config_path,
+                    args.num_threads,
+                    args.libraries,
+                    args.output_format,
+                    args.filename.as_deref(),
+                    ReportOptions {
+                        baseline: args.baseline,
+                        write_baseline: args.write_baseline,
+                        changed_since: args.changed_since,
+                        changed_lines: args.changed_lines,
+                    },
+                    ExitOptions {
+                        fail_on: args.fail_on,
+                        max_diagnostics: args.max_diagnostics,
+                    },
+                );
+            } else if let Some(format) = args.group.format {
+                format_file(format);
+            } else if let Some(path) = args.group.dump_ast {
+                dump_ast(path);
+            }
+        }
     }
 }
 
@@ -62,7 +419,7 @@ fn format_file(format: String) {
     match result {
         Ok((_, design_file)) => {
             if !diagnostics.is_empty() {
-                show_diagnostics(&diagnostics, &SeverityMap::default());
+                show_diagnostics(&diagnostics, &SeverityMap::default(), None);
                 std::process::exit(1);
             }
             let result = VHDLFormatter::format_design_file(&design_file);
@@ -77,12 +434,194 @@ fn format_file(format: String) {
     }
 }
 
+fn format_files(
+    config_path: Option<String>,
+    paths: &[PathBuf],
+    check: bool,
+    lines: Option<Range>,
+) -> ! {
+    let config = match config_path {
+        Some(config_path) => match Config::read_file_path(Path::new(&config_path)) {
+            Ok(config) => config,
+            Err(err) => {
+                println!("Could not read {config_path}: {err}");
+                std::process::exit(1);
+            }
+        },
+        None => Config::default(),
+    };
+    let parser = VHDLParser::new(config.standard());
+
+    let mut files = Vec::new();
+    for path in paths {
+        collect_vhdl_files(path, &mut files);
+    }
+    let mut num_unformatted = 0;
+    let mut num_failed = 0;
+    for path in files.iter() {
+        let formatted = match format_source(&parser, path, config.formatting_options(), lines) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                println!("Could not format {}: {err}", path.to_string_lossy());
+                num_failed += 1;
+                continue;
+            }
+        };
+        let Some(formatted) = formatted else {
+            continue;
+        };
+        num_unformatted += 1;
+        if check {
+            println!("Would reformat {}", path.to_string_lossy());
+            continue;
+        }
+        let result = Latin1String::from_utf8(&formatted)
+            .map_err(|err| err.message())
+            .and_then(|latin1| {
+                std::fs::write(path, latin1.chars().copied().collect_vec())
+                    .map_err(|err| err.to_string())
+            });
+        match result {
+            Ok(_) => println!("Formatted {}", path.to_string_lossy()),
+            Err(err) => {
+                println!("Could not write {}: {err}", path.to_string_lossy());
+                num_failed += 1;
+            }
+        }
+    }
+
+    if check {
+        println!(
+            "{num_unformatted} of {} files would be reformatted",
+            files.len()
+        );
+    } else {
+        println!("Formatted {num_unformatted} of {} files", files.len());
+    }
+    if num_failed > 0 || (check && num_unformatted > 0) {
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
+
+/// Adds `path`, or all VHDL files within the directory `path`, to `files`
+fn collect_vhdl_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        files.push(path.to_owned());
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        println!("Could not read directory {}", path.to_string_lossy());
+        return;
+    };
+    for entry in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .sorted()
+    {
+        let is_vhdl = entry
+            .extension()
+            .is_some_and(|extension| extension == "vhd" || extension == "vhdl");
+        if entry.is_dir() || is_vhdl {
+            collect_vhdl_files(&entry, files);
+        }
+    }
+}
+
+/// Parses lines `FIRST:LAST`, counting from 1, to the range from the start of the first line
+/// to the end of the last line.
+fn parse_line_range(value: &str) -> Result<Range, String> {
+    let parse_line = |line: &str| match line.parse::<u32>() {
+        Ok(line) if line > 0 => Ok(line - 1),
+        _ => Err(format!("'{line}' is not a line number")),
+    };
+    let (first, last) = value
+        .split_once(':')
+        .ok_or_else(|| "Expected FIRST:LAST".to_owned())?;
+    let (first, last) = (parse_line(first)?, parse_line(last)?);
+    if first > last {
+        return Err("The first line must not be after the last line".to_owned());
+    }
+    Ok(Range::new(
+        Position::new(first, 0),
+        Position::new(last, u32::MAX),
+    ))
+}
+
+/// Returns the formatted contents of the file `path`, or `None` if it is already formatted.
+/// When `lines` is given, only the design units that overlap it are formatted.
+/// Files with syntax errors are not formatted, and neither are files whose tokens
+/// would be changed by formatting.
+fn format_source(
+    parser: &VHDLParser,
+    path: &Path,
+    options: &FormattingOptions,
+    lines: Option<Range>,
+) -> Result<Option<String>, String> {
+    let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+    let text = Latin1String::from_vec(bytes).to_string();
+    let mut diagnostics = Vec::new();
+    let source = Source::inline(path, &text);
+    let design_file = parser.parse_design_source(&source, &mut diagnostics);
+    if !diagnostics.is_empty() {
+        show_diagnostics(&diagnostics, &SeverityMap::default(), None);
+        return Err("The file contains syntax errors".to_owned());
+    }
+    let formatted = match lines {
+        Some(range) => {
+            let Some(formatted) = VHDLFormatter::format_design_units_in_range(
+                design_file.clone(),
+                &text,
+                range,
+                options,
+            ) else {
+                return Ok(None);
+            };
+            formatted
+        }
+        None => VHDLFormatter::format_design_file_with_options(&design_file, options) + "\n",
+    };
+
+    let formatted_file =
+        parser.parse_design_source(&Source::inline(path, &formatted), &mut diagnostics);
+    let preserves_tokens = diagnostics.is_empty()
+        && formatted_file.design_units.len() == design_file.design_units.len()
+        && zip(&formatted_file.design_units, &design_file.design_units).all(
+            |((tokens_a, _), (tokens_b, _))| {
+                tokens_a.len() == tokens_b.len()
+                    && zip(tokens_a, tokens_b).all(|(a, b)| a.equal_format(b))
+            },
+        );
+    if !preserves_tokens {
+        return Err("Formatting would change the tokens of the file".to_owned());
+    }
+    Ok((formatted != text).then_some(formatted))
+}
+
+fn dump_ast(path: String) {
+    let parser = VHDLParser::new(VHDLStandard::default());
+    let mut diagnostics = Vec::new();
+    match parser.parse_design_file(Path::new(&path), &mut diagnostics) {
+        Ok((_, design_file)) => {
+            if !diagnostics.is_empty() {
+                show_diagnostics(&diagnostics, &SeverityMap::default(), None);
+                std::process::exit(1);
+            }
+            println!("{:#}", design_file.to_json());
+        }
+        Err(err) => {
+            println!("{err}");
+            std::process::exit(1);
+        }
+    }
+}
+
 fn check_formatted_file(path: &Path, parser: VHDLParser, design_file: DesignFile, result: &str) {
     let mut diagnostics: Vec<Diagnostic> = Vec::new();
     let new_file = parser.parse_design_source(&Source::inline(path, result), &mut diagnostics);
     if !diagnostics.is_empty() {
         println!("Formatting failed as it resulted in a syntactically incorrect file.");
-        show_diagnostics(&diagnostics, &SeverityMap::default());
+        show_diagnostics(&diagnostics, &SeverityMap::default(), None);
         std::process::exit(1);
     }
     for ((tokens_a, _), (tokens_b, _)) in zip(new_file.design_units, design_file.design_units) {
@@ -102,34 +641,207 @@ fn check_formatted_file(path: &Path, parser: VHDLParser, design_file: DesignFile
     }
 }
 
-fn parse_and_analyze_project(
+fn require_config(config_path: Option<String>, command: &str) -> String {
+    let Some(config_path) = config_path else {
+        println!("The {command} command requires a project passed using --config");
+        std::process::exit(1);
+    };
+    config_path
+}
+
+fn load_config(
     config_path: String,
-    num_threads: Option<usize>,
     libraries: Option<String>,
-) {
+    messages: &mut dyn MessageHandler,
+) -> Config {
+    let project_config =
+        Config::read_file_path(Path::new(&config_path)).expect("Failed to read config file");
+    // The standard libraries passed using `--libraries` take precedence over the config file
+    let libraries = libraries.or_else(|| {
+        project_config
+            .standard_libraries()
+            .map(|path| path.to_string_lossy().into_owned())
+    });
+    let mut config = Config::default();
+    config.load_external_config(messages, libraries);
+    config.append(&project_config, messages);
+    config
+}
+
+fn init_thread_pool(num_threads: Option<usize>) {
     rayon::ThreadPoolBuilder::new()
         .num_threads(num_threads.unwrap_or(0))
         .build_global()
         .unwrap();
+}
 
-    let mut config = Config::default();
-    let mut msg_printer = MessagePrinter::default();
-    config.load_external_config(&mut msg_printer, libraries.clone());
-    config.append(
-        &Config::read_file_path(Path::new(&config_path)).expect("Failed to read config file"),
-        &mut msg_printer,
-    );
-
+fn load_project(
+    config_path: String,
+    num_threads: Option<usize>,
+    libraries: Option<String>,
+    messages: &mut dyn MessageHandler,
+) -> (Project, SeverityMap) {
+    init_thread_pool(num_threads);
+    let config = load_config(config_path, libraries, messages);
     let severity_map = *config.severities();
-    let mut project = Project::from_config(config, &mut msg_printer);
+    (Project::from_config(config, messages), severity_map)
+}
+
+fn parse_and_analyze_project(
+    config_path: String,
+    num_threads: Option<usize>,
+    libraries: Option<String>,
+    output_format: OutputFormat,
+    stdin_file: Option<&Path>,
+    report: ReportOptions,
+    options: ExitOptions,
+) {
+    let (mut project, severity_map) = match output_format {
+        OutputFormat::Text => load_project(
+            config_path,
+            num_threads,
+            libraries,
+            &mut MessagePrinter::default(),
+        ),
+        // Messages would make the log on stdout invalid
+        OutputFormat::Sarif | OutputFormat::Junit => {
+            load_project(config_path, num_threads, libraries, &mut NullMessages)
+        }
+    };
+    let base_dir = std::env::current_dir().unwrap_or_default();
+    let changes = report
+        .changed_since
+        .map(|revision| match Changes::since(&revision, &base_dir) {
+            Ok(changes) => changes,
+            Err(err) => {
+                println!("Could not find the changes since {revision}: {err}");
+                std::process::exit(1);
+            }
+        });
     project.enable_unused_declaration_detection();
-    let diagnostics = project.analyse();
+    let stdin_source = stdin_file.map(|path| read_stdin_source(&mut project, path));
+    let mut diagnostics = project.analyse();
+    if let Some(source) = stdin_source {
+        diagnostics.retain(|diag| diag.pos.source == source);
+    }
+    if let Some(path) = report.write_baseline {
+        write_baseline(&diagnostics, &severity_map, &base_dir, &path);
+    }
+    if let Some(path) = report.baseline {
+        read_baseline(&path).retain_new(&mut diagnostics, &base_dir);
+    }
+    if let Some(changes) = changes {
+        diagnostics.retain(|diag| changes.contains(diag, report.changed_lines));
+    }
+
+    match output_format {
+        OutputFormat::Text => {
+            show_diagnostics(&diagnostics, &severity_map, options.max_diagnostics)
+        }
+        OutputFormat::Sarif | OutputFormat::Junit => {
+            let shown = diagnostics
+                .iter()
+                .filter(|diag| severity_map[diag.code].is_some())
+                .take(options.max_diagnostics.unwrap_or(usize::MAX))
+                .cloned()
+                .collect_vec();
+            if let OutputFormat::Sarif = output_format {
+                println!("{:#}", to_sarif(&shown, &severity_map, &base_dir));
+            } else {
+                println!("{}", to_junit(&shown, &severity_map, &base_dir));
+            }
+        }
+    }
+    exit_with_diagnostics(&diagnostics, &severity_map, options.fail_on);
+}
+
+/// Replaces the contents of `path` in the project with the contents of stdin
+fn read_stdin_source(project: &mut Project, path: &Path) -> Source {
+    let contents = match std::io::read_to_string(std::io::stdin()) {
+        Ok(contents) => contents,
+        Err(err) => {
+            println!("Could not read stdin: {err}");
+            std::process::exit(1);
+        }
+    };
+    match project.get_source(path) {
+        Some(source) => {
+            project.change_source(&source, None, &contents);
+            source
+        }
+        None => {
+            let source = Source::inline(path, &contents);
+            project.update_source(&source);
+            source
+        }
+    }
+}
+
+/// Which diagnostics of an analysis are reported
+#[derive(Debug, Clone)]
+struct ReportOptions {
+    /// The baseline file of diagnostics that are not reported
+    baseline: Option<PathBuf>,
+    /// The baseline file that all diagnostics are written to instead of reporting them
+    write_baseline: Option<PathBuf>,
+    /// The git revision that reported diagnostics must be changed relative to
+    changed_since: Option<String>,
+    /// Whether diagnostics must be on changed lines instead of in changed files
+    changed_lines: bool,
+}
+
+fn write_baseline(
+    diagnostics: &[Diagnostic],
+    severity_map: &SeverityMap,
+    base_dir: &Path,
+    path: &Path,
+) -> ! {
+    let baseline = Baseline::from_diagnostics(diagnostics, severity_map, base_dir);
+    match std::fs::write(path, format!("{:#}\n", baseline.to_json())) {
+        Ok(_) => {
+            println!(
+                "Wrote {} diagnostics to the baseline {}",
+                baseline.len(),
+                path.to_string_lossy()
+            );
+            std::process::exit(0);
+        }
+        Err(err) => {
+            println!("Could not write baseline: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn read_baseline(path: &Path) -> Baseline {
+    let result = std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|contents| contents.parse())
+        .and_then(|json| Baseline::from_json(&json));
+    match result {
+        Ok(baseline) => baseline,
+        Err(err) => {
+            println!("Could not read baseline {}: {err}", path.to_string_lossy());
+            std::process::exit(1);
+        }
+    }
+}
 
-    show_diagnostics(&diagnostics, &severity_map);
+/// How the diagnostics of an analysis are printed and determine the exit code
+#[derive(Debug, Clone, Copy)]
+struct ExitOptions {
+    fail_on: Severity,
+    max_diagnostics: Option<usize>,
+}
 
+fn exit_with_diagnostics(
+    diagnostics: &[Diagnostic],
+    severity_map: &SeverityMap,
+    fail_on: Severity,
+) -> ! {
     if diagnostics
         .iter()
-        .any(|diag| severity_map[diag.code].is_some_and(|severity| severity == Severity::Error))
+        .any(|diag| severity_map[diag.code].is_some_and(|severity| severity >= fail_on))
     {
         std::process::exit(1);
     } else {
@@ -137,16 +849,472 @@ fn parse_and_analyze_project(
     }
 }
 
-fn show_diagnostics(diagnostics: &[Diagnostic], severity_map: &SeverityMap) {
+fn snapshot_project(
+    config_path: String,
+    libraries: Option<String>,
+    output: &Path,
+    anonymize: bool,
+) {
+    let config = load_config(config_path, libraries, &mut MessagePrinter::default());
+    let result = std::fs::File::create(output).and_then(|file| {
+        write_snapshot(
+            &config,
+            std::io::BufWriter::new(file),
+            &mut MessagePrinter::default(),
+            anonymize,
+        )
+    });
+    match result {
+        Ok(_) => println!("Wrote snapshot to {}", output.to_string_lossy()),
+        Err(err) => {
+            println!("Could not write snapshot: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn anonymize_project(config_path: String, libraries: Option<String>, output: &Path) {
+    let config = load_config(config_path, libraries, &mut MessagePrinter::default());
+    match write_anonymized_project(&config, output, &mut MessagePrinter::default()) {
+        Ok(_) => println!(
+            "Wrote anonymized project to {}",
+            output.join(SNAPSHOT_CONFIG).to_string_lossy()
+        ),
+        Err(err) => {
+            println!("Could not write anonymized project: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn write_tags(
+    config_path: String,
+    num_threads: Option<usize>,
+    libraries: Option<String>,
+    format: TagsFormat,
+    output: &Path,
+) {
+    let (mut project, _) = load_project(
+        config_path,
+        num_threads,
+        libraries,
+        &mut MessagePrinter::default(),
+    );
+    project.analyse();
+
+    let tags = project.tags();
+    let base_dir = output.parent().unwrap_or(Path::new(""));
+    let result = std::fs::File::create(output).and_then(|file| {
+        let mut writer = std::io::BufWriter::new(file);
+        match format {
+            TagsFormat::Ctags => write_ctags(&tags, base_dir, &mut writer),
+            TagsFormat::Etags => write_etags(&tags, base_dir, &mut writer),
+        }
+    });
+    match result {
+        Ok(_) => println!("Wrote {} tags to {}", tags.len(), output.to_string_lossy()),
+        Err(err) => {
+            println!("Could not write tags: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn write_dependencies(
+    config_path: String,
+    num_threads: Option<usize>,
+    libraries: Option<String>,
+    format: GraphFormat,
+    output: &Path,
+) {
+    let (mut project, _) = load_project(
+        config_path,
+        num_threads,
+        libraries,
+        &mut MessagePrinter::default(),
+    );
+    project.analyse();
+
+    let graph = project.dependency_graph();
+    let contents = match format {
+        GraphFormat::Dot => graph.to_dot(),
+        GraphFormat::Json => format!("{:#}\n", graph.to_json()),
+    };
+    match std::fs::write(output, contents) {
+        Ok(_) => {
+            println!(
+                "Wrote {} dependencies between {} design units to {}",
+                graph.dependencies.len(),
+                graph.units.len(),
+                output.to_string_lossy()
+            );
+            for cycle in graph.cycles() {
+                println!("Found circular dependency between {}", cycle.join(", "));
+            }
+        }
+        Err(err) => {
+            println!("Could not write dependencies: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn write_index(
+    config_path: String,
+    num_threads: Option<usize>,
+    libraries: Option<String>,
+    project_root: &Path,
+    output: &Path,
+) {
+    let (mut project, _) = load_project(
+        config_path,
+        num_threads,
+        libraries,
+        &mut MessagePrinter::default(),
+    );
+    project.analyse();
+
+    let index = project.lsif_index(project_root);
+    let result = std::fs::File::create(output).and_then(|file| {
+        let mut writer = std::io::BufWriter::new(file);
+        write_lsif(&index, &mut writer)
+    });
+    match result {
+        Ok(_) => println!(
+            "Wrote {} vertices and edges to {}",
+            index.len(),
+            output.to_string_lossy()
+        ),
+        Err(err) => {
+            println!("Could not write index: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_tree(
+    config_path: String,
+    num_threads: Option<usize>,
+    libraries: Option<String>,
+    top: Option<TopLevel>,
+    format: TreeFormat,
+) {
+    let (mut project, _) = load_project(config_path, num_threads, libraries, &mut NullMessages);
+    project.analyse();
+
+    let top_levels = match top {
+        Some(top) => vec![top],
+        None => project.config().top_levels().to_vec(),
+    };
+    if top_levels.is_empty() {
+        println!("No top level given, pass one using --top or configure `top` in vhdl_ls.toml");
+        std::process::exit(1);
+    }
+
+    let mut trees = Vec::new();
+    for mut top in top_levels {
+        if top.library.eq_ignore_ascii_case("work") {
+            if let Some(library) = project.list_design_units().into_iter().find(|library| {
+                library.units.iter().any(|unit| {
+                    unit.kind == AnyKind::Primary(PrimaryKind::Entity)
+                        && unit.name.eq_ignore_ascii_case(&top.entity)
+                })
+            }) {
+                top.library = library.name;
+            }
+        }
+        match project.design_hierarchy(&top.library, &top.entity, top.architecture.as_deref()) {
+            Some(tree) => trees.push(tree),
+            None => {
+                println!(
+                    "Could not find entity {} in library {}",
+                    top.entity, top.library
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+
+    match format {
+        TreeFormat::Text => println!("{}", trees.iter().map(|tree| tree.format_tree()).join("\n")),
+        TreeFormat::Json => println!(
+            "{:#}",
+            Json::Array(trees.iter().map(|tree| tree.to_json()).collect())
+        ),
+    }
+}
+
+fn init_config(directory: &Path, grouping: LibraryGrouping, library_name: &str, force: bool) {
+    let output = directory.join("vhdl_ls.toml");
+    if output.exists() && !force {
+        println!(
+            "{} already exists, use --force to overwrite it",
+            output.to_string_lossy()
+        );
+        std::process::exit(1);
+    }
+    let libraries = match suggest_libraries(directory, grouping, library_name) {
+        Ok(libraries) => libraries,
+        Err(err) => {
+            println!(
+                "Could not scan directory {}: {err}",
+                directory.to_string_lossy()
+            );
+            std::process::exit(1);
+        }
+    };
+    if libraries.is_empty() {
+        println!("Found no VHDL files in {}", directory.to_string_lossy());
+        std::process::exit(1);
+    }
+    let result = std::fs::File::create(&output).and_then(|file| {
+        let mut writer = std::io::BufWriter::new(file);
+        write_config(&libraries, &mut writer)
+    });
+    match result {
+        Ok(_) => {
+            for library in &libraries {
+                println!("{}: {}", library.name, library.files.join(", "));
+            }
+            println!(
+                "Wrote {} libraries to {}",
+                libraries.len(),
+                output.to_string_lossy()
+            )
+        }
+        Err(err) => {
+            println!("Could not write configuration: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn write_compile_order_file(
+    config_path: String,
+    num_threads: Option<usize>,
+    libraries: Option<String>,
+    format: CompileOrderFormat,
+    output: &Path,
+) {
+    let (mut project, _) = load_project(
+        config_path,
+        num_threads,
+        libraries,
+        &mut MessagePrinter::default(),
+    );
+    project.analyse();
+
+    let steps = project.compile_order();
+    let result = std::fs::File::create(output).and_then(|file| {
+        let mut writer = std::io::BufWriter::new(file);
+        write_compile_order(&steps, format, &mut writer)
+    });
+    match result {
+        Ok(_) => println!(
+            "Wrote the compile order of {} files to {}",
+            steps.len(),
+            output.to_string_lossy()
+        ),
+        Err(err) => {
+            println!("Could not write compile order: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn replay_snapshot(snapshot: &Path, num_threads: Option<usize>, options: ExitOptions) -> ! {
+    init_thread_pool(num_threads);
+    let directory = std::env::temp_dir().join(format!("vhdl_lang_replay_{}", std::process::id()));
+    let result = std::fs::File::open(snapshot)
+        .and_then(|file| extract_snapshot(std::io::BufReader::new(file), &directory));
+    let snapshot = match result {
+        Ok(snapshot) => snapshot,
+        Err(err) => {
+            let _ = std::fs::remove_dir_all(&directory);
+            println!("Could not read snapshot: {err}");
+            std::process::exit(1);
+        }
+    };
+    if snapshot.version != env!("CARGO_PKG_VERSION") {
+        println!(
+            "Snapshot was created by vhdl_lang {}, replaying with {}",
+            snapshot.version,
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    let severity_map = *snapshot.config.severities();
+    let mut project = Project::from_config(snapshot.config, &mut MessagePrinter::default());
+    project.enable_unused_declaration_detection();
+    let diagnostics = project.analyse();
+    show_diagnostics(&diagnostics, &severity_map, options.max_diagnostics);
+    let _ = std::fs::remove_dir_all(&directory);
+    exit_with_diagnostics(&diagnostics, &severity_map, options.fail_on);
+}
+
+fn fix_project(
+    config_path: String,
+    num_threads: Option<usize>,
+    libraries: Option<String>,
+    rules: &[FixRule],
+    dry_run: bool,
+) {
+    let (mut project, _) = load_project(
+        config_path,
+        num_threads,
+        libraries,
+        &mut MessagePrinter::default(),
+    );
+    project.analyse();
+
+    let fixes = project.list_fixes(rules);
+    let counts = rules
+        .iter()
+        .unique()
+        .map(|rule| {
+            let count = fixes.iter().filter(|fix| fix.rule == *rule).count();
+            format!("{}: {count}", rule.as_str())
+        })
+        .join(", ");
+    let num_fixes = fixes.len();
+    let mut num_files = 0;
+    let mut num_failed = 0;
+    for (source, fixes) in &fixes.into_iter().chunk_by(|fix| fix.source.clone()) {
+        let edits = fixes.flat_map(|fix| fix.edits).collect_vec();
+        let path = source.file_name();
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                println!("Could not read {}: {err}", path.to_string_lossy());
+                num_failed += 1;
+                continue;
+            }
+        };
+        let text = Latin1String::from_vec(bytes).to_string();
+        if dry_run {
+            print_diff(path, &text, &edits);
+            num_files += 1;
+        } else {
+            let fixed = apply_edits(&text, &edits);
+            let result = Latin1String::from_utf8(&fixed)
+                .map_err(|err| err.message())
+                .and_then(|latin1| {
+                    std::fs::write(path, latin1.chars().copied().collect_vec())
+                        .map_err(|err| err.to_string())
+                });
+            match result {
+                Ok(_) => {
+                    println!("Fixed {}", path.to_string_lossy());
+                    num_files += 1;
+                }
+                Err(err) => {
+                    println!("Could not write {}: {err}", path.to_string_lossy());
+                    num_failed += 1;
+                }
+            }
+        }
+    }
+    if num_files == 0 && num_failed == 0 {
+        println!("Nothing to fix");
+    } else if dry_run {
+        println!("Found {num_fixes} fixes in {num_files} files ({counts})");
+    } else {
+        println!("Applied {num_fixes} fixes to {num_files} files ({counts})");
+    }
+    if num_failed > 0 {
+        println!("Could not fix {num_failed} files");
+        std::process::exit(1);
+    }
+}
+
+/// Print the changes caused by `edits` as a unified diff.
+/// Each set of adjacent lines touched by the edits forms a hunk without surrounding context.
+fn print_diff(path: &Path, text: &str, edits: &[TextEdit]) {
+    let lines = split_lines_inclusive(text);
+    let last_line = |edit: &TextEdit| {
+        if edit.range.end.character == 0 && edit.range.end.line > edit.range.start.line {
+            edit.range.end.line - 1
+        } else {
+            edit.range.end.line
+        }
+    };
+
+    // Merge edits that touch the same or adjacent lines into hunks
+    let mut hunks: Vec<(u32, u32, Vec<&TextEdit>)> = Vec::new();
+    for edit in edits.iter().sorted_by_key(|edit| edit.range.start) {
+        let (start, end) = (edit.range.start.line, last_line(edit));
+        match hunks.last_mut() {
+            Some((_, hunk_end, hunk_edits)) if start <= *hunk_end + 1 => {
+                *hunk_end = (*hunk_end).max(end);
+                hunk_edits.push(edit);
+            }
+            _ => hunks.push((start, end, vec![edit])),
+        }
+    }
+
+    println!("--- {}", path.to_string_lossy());
+    println!("+++ {}", path.to_string_lossy());
+    let mut offset: i64 = 0;
+    for (start, end, hunk_edits) in hunks {
+        // Insertions at the end of the text may start after the last line
+        let old = if (start as usize) < lines.len() {
+            let end = end.clamp(start, lines.len() as u32 - 1);
+            lines[start as usize..=end as usize].concat()
+        } else {
+            String::new()
+        };
+        let shift = |pos: Position| Position::new(pos.line - start, pos.character);
+        let shifted = hunk_edits
+            .into_iter()
+            .map(|edit| {
+                TextEdit::new(
+                    Range::new(shift(edit.range.start), shift(edit.range.end)),
+                    edit.new_text.clone(),
+                )
+            })
+            .collect_vec();
+        let new = apply_edits(&old, &shifted);
+        let old_lines = split_lines_inclusive(&old);
+        let new_lines = split_lines_inclusive(&new);
+        let new_start = start as i64 + offset;
+        println!(
+            "@@ -{},{} +{},{} @@",
+            start + 1,
+            old_lines.len(),
+            new_start + 1,
+            new_lines.len()
+        );
+        for line in &old_lines {
+            println!("-{}", line.trim_end_matches(['\r', '\n']));
+        }
+        for line in &new_lines {
+            println!("+{}", line.trim_end_matches(['\r', '\n']));
+        }
+        offset += new_lines.len() as i64 - old_lines.len() as i64;
+    }
+}
+
+fn show_diagnostics(
+    diagnostics: &[Diagnostic],
+    severity_map: &SeverityMap,
+    max_diagnostics: Option<usize>,
+) {
     let diagnostics = diagnostics
         .iter()
         .filter_map(|diag| diag.show(severity_map))
         .collect_vec();
-    for str in &diagnostics {
+    let max_diagnostics = max_diagnostics.unwrap_or(usize::MAX);
+    for str in diagnostics.iter().take(max_diagnostics) {
         println!("{str}");
     }
 
-    if !diagnostics.is_empty() {
+    if diagnostics.len() > max_diagnostics {
+        println!(
+            "Found {} diagnostics, {} are not shown",
+            diagnostics.len(),
+            diagnostics.len() - max_diagnostics
+        );
+    } else if !diagnostics.is_empty() {
         println!("Found {} diagnostics", diagnostics.len());
     }
 }
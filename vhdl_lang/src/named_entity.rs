@@ -57,6 +57,10 @@ pub enum AnyEntKind<'a> {
         /// The type of the base object
         type_mark: TypeEnt<'a>,
     },
+    /// An alias of a named entity that is not covered by the other alias kinds,
+    /// such as a component, a file, a package, a physical unit or a deferred constant.
+    /// The associated [EntRef] is the aliased entity.
+    Alias(EntRef<'a>),
     /// A file declared in a declarative region (i.e., an architecture body).
     /// The associated [Subtype] refers to the type of the file.
     File(Subtype<'a>),
@@ -182,6 +186,7 @@ impl<'a> AnyEntKind<'a> {
         match self {
             ObjectAlias { .. } => "object alias",
             ExternalAlias { .. } => "external alias",
+            Alias(..) => "alias",
             File(..) => "file",
             InterfaceFile(..) => "file parameter",
             ElementDeclaration(..) => "record element",
@@ -523,6 +528,7 @@ impl<'a> AnyEnt<'a> {
             AnyEntKind::Overloaded(Overloaded::Alias(ref ent)) => ent.as_actual(),
             AnyEntKind::Type(Type::Alias(ref ent)) => ent.as_actual(),
             AnyEntKind::ObjectAlias { base_object, .. } => base_object.as_actual(),
+            AnyEntKind::Alias(ent) => ent.as_actual(),
             _ => self,
         }
     }
@@ -666,6 +672,7 @@ impl HasEntityId for AnyDesignUnit {
                 AnyPrimaryUnit::Package(pkg) => pkg.ident.decl.get(),
                 AnyPrimaryUnit::PackageInstance(inst) => inst.ident.decl.get(),
                 AnyPrimaryUnit::Context(ctx) => ctx.ident.decl.get(),
+                AnyPrimaryUnit::VerificationUnit(vunit) => vunit.ident.decl.get(),
             },
             AnyDesignUnit::Secondary(secondary) => match secondary {
                 AnySecondaryUnit::Architecture(arch) => arch.ident.decl.get(),
@@ -380,6 +380,13 @@ impl Reference {
         // We only clear in a single thread so relaxed ordering should be fine
         self.id.store(id.to_raw(), Ordering::Relaxed);
     }
+
+    /// Set a reference that is resolved after the analysis of its design unit,
+    /// such as the path of an external name
+    pub(crate) fn set_unlocked(&self, id: EntityId) {
+        // We only set in a single thread so relaxed ordering should be fine
+        self.id.store(id.to_raw(), Ordering::Relaxed);
+    }
 }
 
 impl PartialEq for Reference {
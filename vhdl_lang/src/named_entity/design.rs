@@ -35,6 +35,9 @@ pub enum Design<'a> {
     /// ```
     InterfacePackageInstance(Region<'a>),
     Context(Region<'a>),
+    /// A PSL verification unit.
+    /// The region contains the declarations of the design unit that it is bound to.
+    VerificationUnit(Visibility<'a>, Region<'a>),
 }
 
 impl<'a> Design<'a> {
@@ -49,6 +52,7 @@ impl<'a> Design<'a> {
             UninstPackage(..) => "uninstantiated package",
             PackageInstance(_) | InterfacePackageInstance(_) => "package instance",
             Context(..) => "context",
+            VerificationUnit(..) => "vunit",
         }
     }
 }
@@ -80,19 +80,31 @@ impl<'a> InterfaceEnt<'a> {
         self.interface_class() == InterfaceClass::Signal
     }
 
-    pub fn is_out_or_inout_signal(&self) -> bool {
+    /// Returns true for signals of mode out, inout, buffer or linkage
+    pub fn is_output_signal(&self) -> bool {
         match self.ent.kind() {
             AnyEntKind::Object(obj) => {
                 obj.class == ObjectClass::Signal
                     && matches!(
                         obj.mode(),
-                        Some(InterfaceMode::Simple(Mode::Out | Mode::InOut))
+                        Some(InterfaceMode::Simple(
+                            Mode::Out | Mode::InOut | Mode::Buffer | Mode::Linkage
+                        ))
                     )
             }
             _ => false,
         }
     }
 
+    /// Returns true for objects of an array type without a constraint
+    /// or a record type with an element of such a type
+    pub fn is_unconstrained_composite(&self) -> bool {
+        match self.ent.kind() {
+            AnyEntKind::Object(obj) => obj.subtype.is_unconstrained_composite(),
+            _ => false,
+        }
+    }
+
     pub fn type_mark(&self) -> TypeEnt<'a> {
         match self.ent.kind() {
             AnyEntKind::Object(obj) => obj.subtype.type_mark(),
@@ -344,4 +356,8 @@ impl<'a> GpkgRegion<'a> {
     pub fn nth(&self, idx: usize) -> Option<GpkgInterfaceEnt<'a>> {
         self.entities.get(idx).cloned()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = GpkgInterfaceEnt<'a>> + '_ {
+        self.entities.iter().cloned()
+    }
 }
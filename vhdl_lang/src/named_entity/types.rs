@@ -190,6 +190,16 @@ impl<'a> TypeEnt<'a> {
         BaseType::from(*self)
     }
 
+    /// Returns whether this type is a subtype (or an alias of a subtype)
+    /// that restricts the values of its base type.
+    pub fn is_constrained_subtype(&self) -> bool {
+        match self.kind() {
+            Type::Alias(alias) => alias.is_constrained_subtype(),
+            Type::Subtype(subtype) => subtype.is_constrained(),
+            _ => false,
+        }
+    }
+
     pub fn accessed_type(&self) -> Option<TypeEnt<'a>> {
         self.base().accessed_type()
     }
@@ -471,11 +481,45 @@ impl<'a> Deref for BaseType<'a> {
 #[derive(Clone, Copy)]
 pub struct Subtype<'a> {
     pub(crate) type_mark: TypeEnt<'a>,
+    /// Whether the subtype indication has an explicit constraint
+    pub(crate) constrained: bool,
 }
 
 impl<'a> Subtype<'a> {
     pub fn new(type_mark: TypeEnt<'a>) -> Subtype<'a> {
-        Subtype { type_mark }
+        Subtype {
+            type_mark,
+            constrained: false,
+        }
+    }
+
+    pub fn constrained(type_mark: TypeEnt<'a>) -> Subtype<'a> {
+        Subtype {
+            type_mark,
+            constrained: true,
+        }
+    }
+
+    /// Returns whether this subtype or any subtype it is derived from
+    /// restricts the values of the base type.
+    pub fn is_constrained(&self) -> bool {
+        self.constrained || self.type_mark.is_constrained_subtype()
+    }
+
+    /// Returns whether this is an array subtype without a constraint
+    /// or a record subtype with an element of such a subtype.
+    pub fn is_unconstrained_composite(&self) -> bool {
+        if self.is_constrained() {
+            return false;
+        }
+        match self.base_type().kind() {
+            Type::Array { .. } => true,
+            Type::Record(region) => region.elems.iter().any(|elem| {
+                matches!(elem.kind(), AnyEntKind::ElementDeclaration(subtype)
+                    if subtype.is_unconstrained_composite())
+            }),
+            _ => false,
+        }
     }
 
     pub fn type_mark(&self) -> TypeEnt<'a> {
@@ -4,16 +4,33 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use crate::analysis::DesignRoot;
+use crate::analysis::{DesignRoot, HierarchyNode, LibraryUnits, ReferenceKind, SignalAccess};
+use crate::ast::json::Json;
 use crate::ast::search::Searcher;
 use crate::ast::DesignFile;
+use crate::compile_order::{compile_order, CompileStep};
 use crate::completion::{list_completion_options, CompletionItem};
 use crate::config::Config;
+use crate::dependencies::{dependency_graph, DependencyGraph};
+use crate::fix::{list_fixes, Fix, FixRule};
+use crate::lint::banned::banned_construct_diagnostics;
+use crate::lint::compatibility::compatibility_diagnostics;
+use crate::lint::component_binding::component_binding_diagnostics;
 use crate::lint::dead_code::UnusedDeclarationsLinter;
-use crate::named_entity::EntRef;
+use crate::lint::delay::ignored_delay_diagnostics;
+use crate::lint::initialization::initialization_diagnostics;
+use crate::lint::missing_return::missing_return_diagnostics;
+use crate::lint::sensitivity::{missing_wait_diagnostics, superfluous_sensitivity_diagnostics};
+use crate::lint::signal_usage::signal_usage_diagnostics;
+use crate::lint::static_bounds::StaticBoundsLinter;
+use crate::lint::unreachable::unreachable_code_diagnostics;
+use crate::lsif::lsif_index;
+use crate::named_entity::{EntRef, TypeEnt};
+use crate::rename::{rename_positions, rename_target, RenameError};
 use crate::standard::VHDLStandard;
 use crate::syntax::VHDLParser;
-use crate::{data::*, EntHierarchy, EntityId};
+use crate::tags::{list_tags, Tag};
+use crate::{data::*, EntHierarchy, EntityId, InstancePath};
 use fnv::{FnvHashMap, FnvHashSet};
 use std::collections::hash_map::Entry;
 use std::path::Path;
@@ -25,18 +42,29 @@ pub struct Project {
     root: DesignRoot,
     files: FnvHashMap<FilePath, SourceFile>,
     empty_libraries: FnvHashSet<Symbol>,
+    // The standards of files that are analyzed with another standard than their libraries
+    file_standards: FnvHashMap<FilePath, VHDLStandard>,
+    // The position of each file in the configured file list of each of its libraries
+    file_positions: FnvHashMap<(Symbol, FilePath), usize>,
     lint: Option<UnusedDeclarationsLinter>,
+    static_bounds: StaticBoundsLinter,
 }
 
 impl Project {
     pub fn new(vhdl_standard: VHDLStandard) -> Project {
-        let parser = VHDLParser::new(vhdl_standard);
+        Project::with_parser(VHDLParser::new(vhdl_standard))
+    }
+
+    fn with_parser(parser: VHDLParser) -> Project {
         Project {
             root: DesignRoot::new(parser.symbols.clone()),
             files: FnvHashMap::default(),
             empty_libraries: FnvHashSet::default(),
+            file_standards: FnvHashMap::default(),
+            file_positions: FnvHashMap::default(),
             parser,
             lint: None,
+            static_bounds: StaticBoundsLinter::default(),
             config: Config::default(),
         }
     }
@@ -48,10 +76,13 @@ impl Project {
     /// Create instance from given configuration.
     /// Files referred by configuration are parsed into corresponding libraries.
     pub fn from_config(config: Config, messages: &mut dyn MessageHandler) -> Project {
-        let mut project = Project::new(config.standard());
+        let mut project = Project::with_parser(
+            VHDLParser::new(config.standard())
+                .with_conditional_identifiers(config.conditional_identifiers().clone()),
+        );
         let files = project.load_files_from_config(&config, messages);
-        project.parse_and_add_files(files, messages);
         project.config = config;
+        project.parse_and_add_files(files, messages);
         project
     }
 
@@ -59,7 +90,8 @@ impl Project {
     /// The design state is reset, new files are added and parsed. Existing source files will be
     /// kept and parsed from in-memory source (required for incremental document updates).
     pub fn update_config(&mut self, config: Config, messages: &mut dyn MessageHandler) {
-        self.parser = VHDLParser::new(config.standard());
+        self.parser = VHDLParser::new(config.standard())
+            .with_conditional_identifiers(config.conditional_identifiers().clone());
         self.root = DesignRoot::new(self.parser.symbols.clone());
 
         // Reset library associations for known files,
@@ -78,10 +110,12 @@ impl Project {
         for (file_name, library_names) in known_files {
             if let Some(source_file) = self.files.get_mut(&file_name) {
                 source_file.parser_diagnostics.clear();
+                source_file.design_file = self.parser.parse_design_source_with_standard(
+                    &source_file.source,
+                    file_standard(&config, &self.file_standards, &file_name, &library_names),
+                    &mut source_file.parser_diagnostics,
+                );
                 source_file.library_names = library_names;
-                source_file.design_file = self
-                    .parser
-                    .parse_design_source(&source_file.source, &mut source_file.parser_diagnostics);
             }
         }
 
@@ -89,6 +123,10 @@ impl Project {
         self.parse_and_add_files(new_files, messages);
     }
 
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
     fn load_files_from_config(
         &mut self,
         config: &Config,
@@ -96,6 +134,8 @@ impl Project {
     ) -> FnvHashMap<FilePath, FnvHashSet<Symbol>> {
         let mut files: FnvHashMap<FilePath, FnvHashSet<Symbol>> = FnvHashMap::default();
         self.empty_libraries.clear();
+        self.file_standards.clear();
+        self.file_positions.clear();
 
         for library in config.iter_libraries() {
             let library_name =
@@ -103,10 +143,20 @@ impl Project {
             let library_name = self.parser.symbol(&library_name);
 
             let mut empty_library = true;
-            for file_name in library.file_names(messages) {
+            for (position, (file_name, standard)) in library
+                .file_names_with_standards(messages)
+                .into_iter()
+                .enumerate()
+            {
                 empty_library = false;
+                let file_name = FilePath::new(&file_name);
+                if let Some(standard) = standard {
+                    self.file_standards.insert(file_name.clone(), standard);
+                }
+                self.file_positions
+                    .insert((library_name.clone(), file_name.clone()), position);
 
-                match files.entry(FilePath::new(&file_name)) {
+                match files.entry(file_name) {
                     Entry::Occupied(mut entry) => {
                         entry.get_mut().insert(library_name.clone());
                     }
@@ -132,13 +182,19 @@ impl Project {
     ) {
         use rayon::prelude::*;
 
+        let config = &self.config;
+        let file_standards = &self.file_standards;
         let parsed: Vec<_> = files_to_parse
             .into_par_iter()
             .map_init(
                 || &self.parser,
                 |parser, (file_name, library_names)| {
                     let mut diagnostics = Vec::new();
-                    let result = parser.parse_design_file(&file_name, &mut diagnostics);
+                    let result = parser.parse_design_file_with_standard(
+                        &file_name,
+                        file_standard(config, file_standards, &file_name, &library_names),
+                        &mut diagnostics,
+                    );
                     (file_name, library_names, diagnostics, result)
                 },
             )
@@ -209,9 +265,121 @@ impl Project {
             }
         };
         source_file.parser_diagnostics.clear();
-        source_file.design_file = self
+        source_file.design_file = self.parser.parse_design_source_with_standard(
+            source,
+            file_standard(
+                &self.config,
+                &self.file_standards,
+                source.file_path(),
+                &source_file.library_names,
+            ),
+            &mut source_file.parser_diagnostics,
+        );
+        self.files
+            .insert(source.file_path().to_owned(), source_file);
+    }
+
+    /// Replace the text within `range` of a source that is part of the project,
+    /// or all of its text when there is no range.
+    /// Only the design units that overlap the changed text are parsed again,
+    /// the tokens and syntax trees of the design units before the change are reused.
+    /// Design units after the change are also reused when the number of lines is unchanged.
+    pub fn change_source(&mut self, source: &Source, range: Option<&Range>, content: &str) {
+        let Some(range) = range else {
+            source.change(None, content);
+            self.update_source(source);
+            return;
+        };
+        let Some(mut source_file) = self.files.remove(source.file_path()) else {
+            source.change(Some(range), content);
+            self.update_source(source);
+            return;
+        };
+
+        // The design units are part of the design root once the project has been analyzed
+        let mut design_units = source_file.take_design_file().design_units;
+        for library_name in source_file.library_names.iter() {
+            let units = self.root.take_source(library_name.clone(), source);
+            if design_units.is_empty() {
+                design_units = units;
+            }
+        }
+
+        let num_lines = source.contents().num_lines();
+        source.change(Some(range), content);
+        let is_same_num_lines = source.contents().num_lines() == num_lines;
+
+        // Comments on the same line as a changed token may belong to that token,
+        // so design units on the lines of the change are parsed again
+        let mut design_units = design_units.into_iter().peekable();
+        let mut prefix = Vec::new();
+        while let Some(design_unit) = design_units.next_if(|(tokens, _)| {
+            tokens
+                .last()
+                .is_some_and(|token| token.full_range().end.line < range.start.line)
+        }) {
+            prefix.push(design_unit);
+        }
+        let suffix: Vec<_> = design_units
+            .filter(|(tokens, _)| {
+                is_same_num_lines
+                    && tokens
+                        .first()
+                        .is_some_and(|token| token.full_range().start.line > range.end.line)
+            })
+            .collect();
+
+        let start = prefix
+            .last()
+            .and_then(|(tokens, _)| tokens.last())
+            .map(|token| token.full_range().end)
+            .unwrap_or_default();
+        let end = suffix
+            .first()
+            .and_then(|(tokens, _)| tokens.first())
+            .map(|token| token.full_range().start);
+
+        let standard = file_standard(
+            &self.config,
+            &self.file_standards,
+            source.file_path(),
+            &source_file.library_names,
+        );
+        let mut diagnostics = Vec::new();
+        match self
             .parser
-            .parse_design_source(source, &mut source_file.parser_diagnostics);
+            .parse_design_source_range(source, standard, start, end, &mut diagnostics)
+        {
+            Some(design_file) => {
+                let mut parser_diagnostics = std::mem::take(&mut source_file.parser_diagnostics);
+                parser_diagnostics.retain(|diagnostic| {
+                    diagnostic.pos.end() <= start
+                        || end.is_some_and(|end| diagnostic.pos.start() >= end)
+                });
+                let num_before = parser_diagnostics
+                    .iter()
+                    .take_while(|diagnostic| diagnostic.pos.end() <= start)
+                    .count();
+                parser_diagnostics.splice(num_before..num_before, diagnostics);
+                source_file.parser_diagnostics = parser_diagnostics;
+
+                let mut design_units = prefix;
+                design_units.extend(design_file.design_units);
+                design_units.extend(suffix);
+                source_file.design_file = DesignFile {
+                    design_units,
+                    standard,
+                };
+            }
+            None => {
+                source_file.parser_diagnostics.clear();
+                source_file.design_file = self.parser.parse_design_source_with_standard(
+                    source,
+                    standard,
+                    &mut source_file.parser_diagnostics,
+                );
+            }
+        }
         self.files
             .insert(source.file_path().to_owned(), source_file);
     }
@@ -219,28 +387,89 @@ impl Project {
     pub fn analyse(&mut self) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
 
-        for source_file in self.files.values_mut() {
+        let mut source_files: Vec<_> = self.files.iter_mut().collect();
+        source_files.sort_unstable_by_key(|(path, _)| *path);
+
+        let mut library_files = Vec::new();
+        for (file_name, source_file) in source_files {
             let design_file = source_file.take_design_file();
+            let standard = self.file_standards.get(file_name).copied();
             // Avoid cloning design files for single library
             let mut design_files = multiply(design_file, source_file.library_names.len());
 
             for library_name in source_file.library_names.iter() {
-                let design_file = design_files.pop().unwrap();
-                self.root.add_design_file(library_name.clone(), design_file);
+                let mut design_file = design_files.pop().unwrap();
+                if let Some(standard) = standard.or_else(|| {
+                    self.config
+                        .get_library(&library_name.name_utf8())
+                        .and_then(|library| library.standard)
+                }) {
+                    design_file.standard = standard;
+                }
+                let position = self
+                    .file_positions
+                    .get(&(library_name.clone(), file_name.clone()))
+                    .copied()
+                    .unwrap_or(usize::MAX);
+                library_files.push((position, file_name, library_name.clone(), design_file));
             }
 
             diagnostics.extend(source_file.parser_diagnostics.iter().cloned());
         }
 
+        // Add files in the order of the file list of their library, such that the first of
+        // several design units with the same name is the one that is kept and the others are
+        // reported as duplicates. Files that are not part of the config are added last.
+        // A file that is changed later on is removed from its library, which promotes the
+        // duplicates of its design units, and added again. Its design units are then reported
+        // as duplicates until the config is loaded again.
+        library_files.sort_by_key(|(position, file_name, _, _)| (*position, *file_name));
+        for (_, _, library_name, design_file) in library_files {
+            self.root.add_design_file(library_name, design_file);
+        }
+
         for library_name in self.empty_libraries.iter() {
             self.root.ensure_library(library_name.clone());
         }
 
         let analyzed_units = self.root.analyze(&mut diagnostics);
+        diagnostics.extend(self.root.resolve_external_names(self.config.top_levels()));
 
         if let Some(ref mut lint) = self.lint {
             lint.lint(&self.root, &self.config, &analyzed_units, &mut diagnostics);
         }
+        self.static_bounds
+            .lint(&self.root, &self.config, &analyzed_units, &mut diagnostics);
+        diagnostics.extend(banned_construct_diagnostics(&self.root, &self.config));
+        diagnostics.extend(compatibility_diagnostics(&self.root, &self.config));
+        diagnostics.extend(component_binding_diagnostics(&self.root, &self.config));
+        diagnostics.extend(ignored_delay_diagnostics(&self.root, &self.config));
+        diagnostics.extend(initialization_diagnostics(&self.root, &self.config));
+        diagnostics.extend(missing_return_diagnostics(&self.root, &self.config));
+        diagnostics.extend(missing_wait_diagnostics(&self.root, &self.config));
+        diagnostics.extend(signal_usage_diagnostics(&self.root, &self.config));
+        diagnostics.extend(superfluous_sensitivity_diagnostics(
+            &self.root,
+            &self.config,
+        ));
+        diagnostics.extend(unreachable_code_diagnostics(&self.root, &self.config));
+
+        // Third-party libraries are only analyzed for navigation,
+        // their diagnostics would hide the diagnostics of the user's own code
+        let third_party_sources: FnvHashSet<&Source> = self
+            .files
+            .values()
+            .filter(|source_file| {
+                !source_file.library_names.is_empty()
+                    && source_file.library_names.iter().all(|library_name| {
+                        self.config
+                            .get_library(&library_name.name_utf8())
+                            .is_some_and(|library| library.is_third_party)
+                    })
+            })
+            .map(|source_file| &source_file.source)
+            .collect();
+        diagnostics.retain(|diagnostic| !third_party_sources.contains(&diagnostic.pos.source));
 
         diagnostics
     }
@@ -262,6 +491,13 @@ impl Project {
         Some(ent.declaration())
     }
 
+    /// The position of the declaration of the item at the cursor,
+    /// where [`find_definition`](Self::find_definition) finds the completing definition.
+    pub fn find_declaration_pos(&self, source: &Source, cursor: Position) -> Option<SrcPos> {
+        let ent = self.root.search_reference(source, cursor)?;
+        self.root.find_declaration_pos_of(ent)
+    }
+
     pub fn item_at_cursor(
         &self,
         source: &Source,
@@ -270,6 +506,23 @@ impl Project {
         self.root.item_at_cursor(source, cursor)
     }
 
+    /// The position of the identifier at the cursor and the declaration to rename.
+    /// Fails for keywords, literals and items that must not be edited,
+    /// such as those declared in a third party library.
+    pub fn rename_target(
+        &self,
+        source: &Source,
+        cursor: Position,
+    ) -> Result<Option<(SrcPos, EntRef<'_>)>, RenameError> {
+        rename_target(&self.root, &self.config, source, cursor)
+    }
+
+    /// All positions to edit when renaming `ent`, i.e. the references of `ent`
+    /// and, for an entity, its default bound components and names in configurations.
+    pub fn rename_positions(&self, ent: EntRef<'_>) -> Vec<SrcPos> {
+        rename_positions(&self.root, ent)
+    }
+
     pub fn search(&self, searcher: &mut impl Searcher) {
         let _ = self.root.search(searcher);
     }
@@ -296,11 +549,77 @@ impl Project {
         }
     }
 
+    pub fn list_design_units(&self) -> Vec<LibraryUnits> {
+        self.root.list_design_units()
+    }
+
+    /// The elaborated instantiation tree below an entity.
+    /// Returns `None` if the library or entity does not exist.
+    pub fn design_hierarchy(
+        &self,
+        library: &str,
+        entity: &str,
+        architecture: Option<&str>,
+    ) -> Option<HierarchyNode> {
+        let architecture = architecture.map(|name| self.root.symbol_utf8(name));
+        self.root.design_hierarchy(
+            &self.root.symbol_utf8(library),
+            &self.root.symbol_utf8(entity),
+            architecture.as_ref(),
+        )
+    }
+
+    /// The elaborated instantiation trees below the top levels of the configuration.
+    /// Top levels whose library or entity does not exist are skipped.
+    pub fn elaborate(&self) -> Vec<HierarchyNode> {
+        self.config
+            .top_levels()
+            .iter()
+            .filter_map(|top| {
+                self.design_hierarchy(&top.library, &top.entity, top.architecture.as_deref())
+            })
+            .collect()
+    }
+
+    /// Find all paths through which the entity, architecture or component
+    /// at the cursor is instantiated in the design hierarchy.
+    pub fn find_instances(&self, source: &Source, cursor: Position) -> Vec<InstancePath> {
+        if let Some(ent) = self.find_declaration(source, cursor) {
+            self.root.find_instances(ent)
+        } else {
+            Vec::default()
+        }
+    }
+
     /// Search for the declaration at decl_pos and format it
     pub fn format_declaration(&self, ent: EntRef<'_>) -> Option<String> {
         self.root.format_declaration(ent)
     }
 
+    /// Format the generics and ports of an entity or component as Markdown tables
+    pub fn format_interface_table(&self, ent: EntRef<'_>) -> Option<String> {
+        self.root.format_interface_table(ent)
+    }
+
+    /// The innermost expression at the cursor with a single type, together with the type
+    pub fn expression_type(
+        &self,
+        source: &Source,
+        cursor: Position,
+    ) -> Option<(SrcPos, TypeEnt<'_>)> {
+        self.root.expression_type_at(source, cursor)
+    }
+
+    /// Format the candidates of an operator at the cursor that could not be disambiguated
+    pub fn format_ambiguous_operator(&self, source: &Source, cursor: Position) -> Option<String> {
+        self.root.format_ambiguous_operator(source, cursor)
+    }
+
+    /// Format the normalized value of the physical literal at the cursor
+    pub fn format_physical_literal(&self, source: &Source, cursor: Position) -> Option<String> {
+        self.root.format_physical_literal(source, cursor)
+    }
+
     pub fn format_entity(&self, id: EntityId) -> Option<String> {
         let ent = self.root.get_ent(id);
         self.format_declaration(ent)
@@ -311,10 +630,46 @@ impl Project {
         self.root.find_all_references(ent)
     }
 
+    /// The places that drive the signal or port at the cursor,
+    /// following port maps one level up and down the design hierarchy.
+    pub fn find_drivers(&self, source: &Source, cursor: Position) -> Vec<SignalAccess> {
+        if let Some(ent) = self.find_declaration(source, cursor) {
+            self.root.find_drivers(ent)
+        } else {
+            Vec::default()
+        }
+    }
+
+    /// The places that read the signal or port at the cursor,
+    /// following port maps one level up and down the design hierarchy.
+    pub fn find_loads(&self, source: &Source, cursor: Position) -> Vec<SignalAccess> {
+        if let Some(ent) = self.find_declaration(source, cursor) {
+            self.root.find_loads(ent)
+        } else {
+            Vec::default()
+        }
+    }
+
+    /// All references of `ent` together with how it is referenced.
+    pub fn find_all_references_with_kind(&self, ent: EntRef<'_>) -> Vec<(SrcPos, ReferenceKind)> {
+        self.root.find_all_references_with_kind(ent)
+    }
+
     pub fn find_all_references_in_source(&self, source: &Source, ent: EntRef<'_>) -> Vec<SrcPos> {
         self.root.find_all_references_in_source(source, ent)
     }
 
+    /// Get the references to the declaration in the source that are assignment targets
+    pub fn find_all_writes_in_source(&self, source: &Source, ent: EntRef<'_>) -> Vec<SrcPos> {
+        self.root.find_all_writes_in_source(source, ent)
+    }
+
+    /// Get the name of the declaration at the cursor and its repetition after `end`,
+    /// which are edited together
+    pub fn find_linked_names(&self, source: &Source, cursor: Position) -> Vec<SrcPos> {
+        self.root.find_linked_names(source, cursor)
+    }
+
     /// Get source positions that are not resolved to a declaration
     /// This is used for development to test where the language server is blind
     pub fn find_all_unresolved(&self) -> (usize, Vec<SrcPos>) {
@@ -336,6 +691,60 @@ impl Project {
     pub fn entity_id_from_raw(&self, raw: usize) -> Option<EntityId> {
         self.root.entity_id_from_raw(raw)
     }
+
+    /// List machine-applicable fixes for the selected rules.
+    /// The project must have been analyzed.
+    pub fn list_fixes(&self, rules: &[FixRule]) -> Vec<Fix> {
+        list_fixes(&self.root, &self.config, rules)
+    }
+
+    /// List all named declarations for export as a tags file.
+    /// The project must have been analyzed.
+    pub fn tags(&self) -> Vec<Tag> {
+        list_tags(&self.root)
+    }
+
+    /// The dependencies between the design units of all libraries that are not third-party libraries.
+    /// The project must have been analyzed.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        dependency_graph(&self.root, &self.config)
+    }
+
+    /// The order in which the files of all libraries that are not third-party libraries
+    /// must be compiled by a simulator. The project must have been analyzed.
+    pub fn compile_order(&self) -> Vec<CompileStep> {
+        compile_order(&self.root, &self.config)
+    }
+
+    /// An LSIF index of all libraries that are not third-party libraries,
+    /// with one vertex or edge per element. The project must have been analyzed.
+    pub fn lsif_index(&self, project_root: &Path) -> Vec<Json> {
+        lsif_index(&self.root, &self.config, project_root)
+    }
+}
+
+/// The standard that a file is parsed with, which is either the standard configured for the file,
+/// or the standard of its libraries.
+/// A file that is part of libraries with different standards is parsed with the standard of the project.
+fn file_standard(
+    config: &Config,
+    file_standards: &FnvHashMap<FilePath, VHDLStandard>,
+    file_name: &FilePath,
+    library_names: &FnvHashSet<Symbol>,
+) -> VHDLStandard {
+    if let Some(standard) = file_standards.get(file_name) {
+        return *standard;
+    }
+    let mut standards = library_names.iter().map(|library_name| {
+        config
+            .get_library(&library_name.name_utf8())
+            .and_then(|library| library.standard)
+            .unwrap_or(config.standard())
+    });
+    match standards.next() {
+        Some(standard) if standards.all(|other| other == standard) => standard,
+        _ => config.standard(),
+    }
 }
 
 /// Multiply cloneable value by cloning
@@ -375,6 +784,7 @@ impl SourceFile {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::search::clear_references;
     use crate::syntax::test::check_no_diagnostics;
 
     /// Test that an empty library is created
@@ -408,6 +818,37 @@ lib.files = ['file.vhd']
         check_no_diagnostics(&project.analyse());
     }
 
+    #[test]
+    fn diagnostics_of_third_party_libraries_are_suppressed() {
+        let root = tempfile::tempdir().unwrap();
+        let code = "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : missing_type;
+begin
+end architecture;
+";
+        std::fs::write(root.path().join("vendor.vhd"), code).unwrap();
+        std::fs::write(root.path().join("own.vhd"), code.replace("ent", "own")).unwrap();
+
+        let config_str = "
+[libraries]
+vendor.files = ['vendor.vhd']
+vendor.is_third_party = true
+lib.files = ['own.vhd']
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].pos.file_name().ends_with("own.vhd"));
+    }
+
     #[test]
     fn unmapped_libraries_are_analyzed() {
         let mut messages = Vec::new();
@@ -443,6 +884,122 @@ end architecture;
         assert_eq!(diag.message, "Duplicate architecture 'rtl' of entity 'ent'")
     }
 
+    #[test]
+    fn duplicate_units_of_different_files_keep_the_first_configured_file() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("standard.vhd"),
+            include_bytes!("../../vhdl_libraries/std/standard.vhd"),
+        )
+        .unwrap();
+        let first_path = root.path().join("b.vhd");
+        let second_path = root.path().join("a.vhd");
+        std::fs::write(
+            &first_path,
+            "
+package pkg is
+  type enum_t is (alpha, beta);
+end package;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            &second_path,
+            "
+package pkg is
+end package;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("use_pkg.vhd"),
+            "
+use work.pkg.enum_t;
+
+entity ent is
+end entity;
+",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+std.files = ['standard.vhd']
+lib.files = ['b.vhd', 'use_pkg.vhd', 'a.vhd']
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(
+            diagnostic.message,
+            "A primary unit has already been declared with name 'pkg' in library 'lib'"
+        );
+        assert_eq!(diagnostic.pos.file_name(), second_path.as_path());
+        assert_eq!(diagnostic.related.len(), 1);
+        assert_eq!(diagnostic.related[0].0.file_name(), first_path.as_path());
+    }
+
+    #[test]
+    fn elaborates_configured_top_levels() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("file.vhd"),
+            "
+entity leaf is
+end entity;
+
+architecture rtl of leaf is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture a of top is
+begin
+  u_leaf: entity work.leaf;
+end architecture;
+
+architecture b of top is
+begin
+end architecture;
+",
+        )
+        .unwrap();
+
+        let config_str = "
+top = ['lib.top(a)', 'lib.top(b)', 'lib.missing']
+[libraries]
+lib.files = ['file.vhd']
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        project.analyse();
+
+        let tops: Vec<_> = project
+            .elaborate()
+            .into_iter()
+            .map(|node| {
+                let crate::HierarchyNodeKind::Instance(info) = node.kind else {
+                    panic!("Expected instance");
+                };
+                (info.architecture, node.children.len())
+            })
+            .collect();
+        assert_eq!(
+            tops,
+            vec![(Some("a".to_owned()), 1), (Some("b".to_owned()), 0)]
+        );
+    }
+
     /// Test that the same file can be added to several libraries
     #[test]
     fn test_same_file_in_multiple_libraries() {
@@ -490,6 +1047,148 @@ use_lib.files = ['use_file.vhd']
         check_no_diagnostics(&project.analyse());
     }
 
+    #[test]
+    fn libraries_are_analyzed_with_their_standard() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("standard.vhd"),
+            include_bytes!("../../vhdl_libraries/std/standard.vhd"),
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("file.vhd"),
+            "
+entity ent is
+  port (q : out bit; q_n : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  q <= '0';
+  q_n <= not q;
+end architecture;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+std.files = ['standard.vhd']
+lib.files = ['file.vhd']
+legacy.files = ['file.vhd']
+legacy.standard = '1993'
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, ErrorCode::IllegalPortRead);
+    }
+
+    #[test]
+    fn libraries_are_parsed_with_their_standard() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("standard.vhd"),
+            include_bytes!("../../vhdl_libraries/std/standard.vhd"),
+        )
+        .unwrap();
+        // 'force' and 'context' are only reserved words since VHDL-2008
+        let contents = "
+entity ent is
+  port (context : in bit; force : out bit);
+end entity;
+
+architecture a of ent is
+begin
+  force <= context;
+end architecture;
+        ";
+        std::fs::write(root.path().join("file.vhd"), contents).unwrap();
+
+        let config_str = "
+[libraries]
+std.files = ['standard.vhd']
+legacy.files = ['file.vhd']
+legacy.standard = '1993'
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+
+        let source = Source::inline(&root.path().join("file.vhd"), contents);
+        project.update_source(&source);
+        check_no_diagnostics(&project.analyse());
+
+        let config = Config::from_str(
+            "
+[libraries]
+std.files = ['standard.vhd']
+lib.files = ['file.vhd']
+        ",
+            root.path(),
+        )
+        .unwrap();
+        project.update_config(config, &mut messages);
+        assert!(!project.analyse().is_empty());
+        project.update_source(&source);
+        assert!(!project.analyse().is_empty());
+    }
+
+    #[test]
+    fn files_are_parsed_with_their_standard() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("standard.vhd"),
+            include_bytes!("../../vhdl_libraries/std/standard.vhd"),
+        )
+        .unwrap();
+        // 'force' is only a reserved word since VHDL-2008
+        let legacy = "
+entity legacy is
+  port (force : out bit);
+end entity;
+        ";
+        std::fs::write(root.path().join("legacy.vhd"), legacy).unwrap();
+        std::fs::write(
+            root.path().join("modern.vhd"),
+            "
+entity modern is
+end entity;
+
+architecture a of modern is
+begin
+  process (all)
+  begin
+  end process;
+end architecture;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+std.files = ['standard.vhd']
+lib.files = ['*.vhd', { path = 'legacy.vhd', standard = '1993' }]
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+
+        let source = Source::inline(&root.path().join("legacy.vhd"), legacy);
+        project.update_source(&source);
+        check_no_diagnostics(&project.analyse());
+    }
+
     fn update(project: &mut Project, source: &mut Source, contents: &str) {
         std::fs::write(Path::new(source.file_name()), contents).unwrap();
         *source = Source::from_latin1_file(source.file_name()).unwrap();
@@ -548,11 +1247,10 @@ package is
         ",
         );
         let diagnostics = project.analyse();
-        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics.len(), 2);
         // Syntax error comes first
         assert_eq!(diagnostics[0].pos.source, source1);
-        assert_eq!(diagnostics[1].pos.source, source1);
-        assert_eq!(diagnostics[2].pos.source, source2);
+        assert_eq!(diagnostics[1].pos.source, source2);
 
         // Make it good again
         update(
@@ -659,4 +1357,150 @@ lib.files = ['file2.vhd']
         assert_eq!(diagnostics[0].pos.source, source2); // No such library
         assert_eq!(diagnostics[1].pos.source, source2); // No declaration
     }
+
+    fn token_ptrs(project: &Project, source: &Source) -> Vec<*const Token> {
+        project.files[source.file_path()]
+            .design_file
+            .design_units
+            .iter()
+            .map(|(tokens, _)| tokens.as_ptr())
+            .collect()
+    }
+
+    #[test]
+    fn changed_design_units_are_parsed_again() {
+        let source = Source::inline(
+            Path::new("file.vhd"),
+            "\
+entity ent is
+end entity;
+
+architecture rtl of ent is
+  signal sig : bit;
+begin
+end architecture;
+
+package pkg is
+end package;
+",
+        );
+        let mut project = Project::new(VHDLStandard::default());
+        project.update_source(&source);
+        let before = token_ptrs(&project, &source);
+        project.analyse();
+
+        project.change_source(
+            &source,
+            Some(&Range::new(Position::new(4, 9), Position::new(4, 12))),
+            "renamed",
+        );
+        let after = token_ptrs(&project, &source);
+        assert_eq!(after.len(), 3);
+        assert_eq!(after[0], before[0]);
+        assert_ne!(after[1], before[1]);
+        assert_eq!(after[2], before[2]);
+
+        let contents = source.contents();
+        assert_eq!(contents.get_line(4), Some("  signal renamed : bit;\n"));
+    }
+
+    /// Test that parsing only the changed design units gives the same result
+    /// as parsing the whole source after each change
+    #[test]
+    fn incremental_parsing_matches_parsing_the_whole_source() {
+        let source = Source::inline(
+            Path::new("file.vhd"),
+            "\
+entity ent is
+end entity;
+
+architecture rtl of ent is
+begin
+  process
+  begin
+    null;
+  end process;
+end architecture;
+
+-- Package documentation
+package pkg is
+end package;
+",
+        );
+        let mut project = Project::new(VHDLStandard::default());
+        project.update_source(&source);
+        project.analyse();
+
+        let changes = [
+            // Within a design unit
+            ((7, 4), (7, 9), "if true then\n      null;"),
+            // Missing end if
+            ((8, 6), (8, 11), ""),
+            // Comment that extends into the following design units
+            ((11, 0), (11, 0), "/*"),
+            ((11, 0), (11, 2), ""),
+            // Design unit that continues into the following design units
+            ((10, 0), (10, 17), ""),
+            ((10, 0), (10, 0), "end architecture;"),
+            // New design unit between design units
+            ((11, 0), (11, 0), "package other is end package;"),
+            // Tool directive
+            ((0, 0), (0, 0), "`warning \"directive\"\n"),
+        ];
+
+        for (start, end, text) in changes {
+            project.change_source(
+                &source,
+                Some(&Range::new(
+                    Position::new(start.0, start.1),
+                    Position::new(end.0, end.1),
+                )),
+                text,
+            );
+            let design_units: Vec<_> = project.files[source.file_path()]
+                .design_file
+                .design_units
+                .iter()
+                .map(|(tokens, unit)| {
+                    // Reused design units keep the references of the previous analysis
+                    let mut unit = unit.clone();
+                    clear_references(&mut unit, tokens);
+                    (tokens.clone(), unit)
+                })
+                .collect();
+            let diagnostics: Vec<_> = project
+                .analyse()
+                .into_iter()
+                .map(|diagnostic| (diagnostic.message, diagnostic.pos.range()))
+                .collect();
+
+            let contents = source.contents().crop(source.contents().range());
+            let reference_source = Source::from_contents(Path::new("file.vhd"), contents);
+            let mut reference = Project::new(VHDLStandard::default());
+            reference.update_source(&reference_source);
+            // Parse with the same symbols such that the syntax trees can be compared
+            let reference_units = project
+                .parser
+                .parse_design_source(&reference_source, &mut Vec::new())
+                .design_units;
+            assert_eq!(design_units.len(), reference_units.len());
+            for ((tokens, unit), (reference_tokens, reference_unit)) in
+                design_units.iter().zip(reference_units.iter())
+            {
+                assert_eq!(unit, reference_unit);
+                assert_eq!(tokens.len(), reference_tokens.len());
+                for (token, reference_token) in tokens.iter().zip(reference_tokens.iter()) {
+                    assert_eq!(token.pos.range(), reference_token.pos.range());
+                    assert_eq!(token.kind, reference_token.kind);
+                    assert_eq!(token.comments, reference_token.comments);
+                }
+            }
+            let reference_diagnostics: Vec<_> = reference
+                .analyse()
+                .into_iter()
+                .map(|diagnostic| (diagnostic.message, diagnostic.pos.range()))
+                .collect();
+            assert_eq!(diagnostics, reference_diagnostics);
+        }
+    }
 }
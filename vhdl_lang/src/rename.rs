@@ -0,0 +1,227 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Validation of rename targets.
+
+use crate::analysis::DesignRoot;
+use crate::ast::Designator;
+use crate::named_entity::{AnyEntKind, Design, EntRef};
+use crate::syntax::Kind;
+use crate::{Config, Position, Source, SrcPos};
+use std::fmt::{Display, Formatter};
+
+/// The reason why the item at the cursor cannot be renamed
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum RenameError {
+    Keyword,
+    Literal,
+    /// Operator symbols and character literals have different representations,
+    /// such as `function "+"` that is used as `a + b`, which cannot be renamed consistently.
+    Operator,
+    /// Predefined items such as implicitly declared functions of a type
+    Implicit,
+    /// Libraries are named by the configuration
+    Library,
+    /// The item is declared in a third party library, which is never edited
+    ReadOnlyLibrary(String),
+}
+
+impl Display for RenameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenameError::Keyword => write!(f, "Keywords cannot be renamed"),
+            RenameError::Literal => write!(f, "Literals cannot be renamed"),
+            RenameError::Operator => {
+                write!(
+                    f,
+                    "Operator symbols and character literals cannot be renamed"
+                )
+            }
+            RenameError::Implicit => write!(f, "Implicitly declared items cannot be renamed"),
+            RenameError::Library => write!(
+                f,
+                "Libraries cannot be renamed, change the library name in the configuration instead"
+            ),
+            RenameError::ReadOnlyLibrary(library) => write!(
+                f,
+                "Items declared in the third party library '{library}' cannot be renamed"
+            ),
+        }
+    }
+}
+
+/// The position of the identifier at the cursor and the declaration that it denotes.
+/// Returns `Ok(None)` if there is nothing at the cursor.
+pub(crate) fn rename_target<'a>(
+    root: &'a DesignRoot,
+    config: &Config,
+    source: &Source,
+    cursor: Position,
+) -> Result<Option<(SrcPos, EntRef<'a>)>, RenameError> {
+    let Some((pos, ent)) = root.item_at_cursor(source, cursor) else {
+        return match root.token_kind_at_cursor(source, cursor) {
+            Some(
+                Kind::AbstractLiteral | Kind::StringLiteral | Kind::BitString | Kind::Character,
+            ) => Err(RenameError::Literal),
+            Some(kind) if config.standard().keywords().contains(&kind) => Err(RenameError::Keyword),
+            _ => Ok(None),
+        };
+    };
+    let decl = ent.declaration();
+    if !matches!(decl.designator(), Designator::Identifier(_)) {
+        return Err(RenameError::Operator);
+    }
+    if matches!(decl.kind(), AnyEntKind::Library) {
+        return Err(RenameError::Library);
+    }
+    if decl.is_implicit() || decl.decl_pos().is_none() {
+        return Err(RenameError::Implicit);
+    }
+    if let Some(library) = decl.library_name() {
+        let library = library.name_utf8();
+        if config
+            .get_library(&library)
+            .is_some_and(|library_config| library_config.is_third_party)
+        {
+            return Err(RenameError::ReadOnlyLibrary(library));
+        }
+    }
+    Ok(Some((pos, decl)))
+}
+
+/// All positions to edit when renaming `ent`.
+/// Besides the references of `ent`, renaming an entity also renames the components
+/// that are bound to it by default binding, together with the names in configurations
+/// that are not analyzed.
+pub(crate) fn rename_positions(root: &DesignRoot, ent: EntRef<'_>) -> Vec<SrcPos> {
+    let mut positions = root.find_all_references(ent);
+    positions.extend(root.find_configuration_references(ent));
+    if matches!(ent.kind(), AnyEntKind::Design(Design::Entity(..))) {
+        for implementation in root.find_implementation(ent) {
+            if matches!(implementation.kind(), AnyEntKind::Component(_)) {
+                positions.extend(root.find_all_references(implementation));
+            }
+        }
+    }
+    positions.sort();
+    positions.dedup();
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::{test_config, LibraryBuilder};
+
+    #[test]
+    fn validates_rename_targets() {
+        let mut builder = LibraryBuilder::new();
+        builder.code(
+            "vendor",
+            "
+package pkg is
+  constant vendor_const : natural := 0;
+end package;
+",
+        );
+        let code = builder.code(
+            "libname",
+            "
+library vendor;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal sig : natural := vendor.pkg.vendor_const;
+  signal str : string(1 to 3) := \"abc\";
+begin
+  sig <= sig + 16;
+end architecture;
+",
+        );
+        let (root, _) = builder.get_analyzed_root();
+        let config = test_config("vendor.files = []\nvendor.is_third_party = true");
+        let target = |substr: &str| {
+            rename_target(&root, &config, code.source(), code.s1(substr).start())
+                .map(|target| target.map(|(pos, ent)| (pos, ent.designator().to_string())))
+        };
+
+        assert_eq!(
+            target("sig <="),
+            Ok(Some((code.s1("sig <=").s1("sig").pos(), "sig".to_owned())))
+        );
+        assert_eq!(target("architecture a"), Err(RenameError::Keyword));
+        assert_eq!(target("16"), Err(RenameError::Literal));
+        assert_eq!(target("\"abc\""), Err(RenameError::Literal));
+        assert_eq!(target("+ 16"), Err(RenameError::Operator));
+        assert_eq!(target("vendor;"), Err(RenameError::Library));
+        assert_eq!(
+            target("vendor_const"),
+            Err(RenameError::ReadOnlyLibrary("vendor".to_owned()))
+        );
+    }
+
+    #[test]
+    fn renaming_an_entity_renames_associated_names() {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code(
+            "libname",
+            "
+entity old_name is
+end entity old_name;
+
+architecture rtl of old_name is
+begin
+end architecture rtl;
+
+entity top is
+end entity;
+
+architecture a of top is
+  component old_name is
+  end component old_name;
+  for spec_inst : old_name use entity work.old_name(rtl);
+begin
+  inst: old_name;
+  spec_inst: old_name;
+  ent_inst: entity work.old_name(rtl);
+end architecture;
+
+configuration cfg of top is
+  for a
+    for inst : old_name
+      use entity libname.old_name(rtl);
+      for rtl
+      end for;
+    end for;
+  end for;
+end configuration;
+
+configuration cfg2 of old_name is
+  for rtl
+  end for;
+end configuration;
+",
+        );
+        let (root, diagnostics) = builder.get_analyzed_root();
+        crate::syntax::test::check_no_diagnostics(&diagnostics);
+
+        let entity = root
+            .search_reference(code.source(), code.s1("old_name").start())
+            .unwrap();
+        let mut expected: Vec<_> = (1..=13).map(|occ| code.s("old_name", occ).pos()).collect();
+        expected.sort();
+        assert_eq!(rename_positions(&root, entity), expected);
+
+        let architecture = root
+            .search_reference(code.source(), code.s1("rtl").start())
+            .unwrap();
+        let mut expected: Vec<_> = (1..=7).map(|occ| code.s("rtl", occ).pos()).collect();
+        expected.sort();
+        assert_eq!(rename_positions(&root, architecture), expected);
+    }
+}
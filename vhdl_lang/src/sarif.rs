@@ -0,0 +1,212 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Export of diagnostics in the SARIF 2.1.0 format,
+//! such that code scanning of CI systems can show them on the lines of a pull request.
+//!
+//! Each [ErrorCode] is a rule with its explanation and a link to its documentation.
+//! Diagnostics that are disabled in the [SeverityMap] are not exported.
+
+use crate::ast::json::Json;
+use crate::data::error_codes::ErrorCode;
+use crate::{Diagnostic, Severity, SeverityMap, SrcPos};
+use std::path::Path;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/VHDL-LS/rust_hdl";
+
+/// Converts `diagnostics` to a SARIF log.
+/// Files within `base_dir` are referred to relative to it, such as files of a repository.
+pub fn to_sarif(diagnostics: &[Diagnostic], severities: &SeverityMap, base_dir: &Path) -> Json {
+    let rules = ErrorCode::all()
+        .map(|code| rule(code, severities))
+        .collect();
+    let results = diagnostics
+        .iter()
+        .filter_map(|diagnostic| {
+            let severity = severities[diagnostic.code]?;
+            Some(result(diagnostic, severity, base_dir))
+        })
+        .collect();
+    object([
+        ("$schema", string(SARIF_SCHEMA)),
+        ("version", string("2.1.0")),
+        (
+            "runs",
+            Json::Array(vec![object([
+                (
+                    "tool",
+                    object([(
+                        "driver",
+                        object([
+                            ("name", string("vhdl_lang")),
+                            ("version", string(env!("CARGO_PKG_VERSION"))),
+                            ("informationUri", string(INFORMATION_URI)),
+                            ("rules", Json::Array(rules)),
+                        ]),
+                    )]),
+                ),
+                ("results", Json::Array(results)),
+            ])]),
+        ),
+    ])
+}
+
+fn rule(code: ErrorCode, severities: &SeverityMap) -> Json {
+    let explanation = code.explanation();
+    let summary = explanation.lines().next().unwrap_or_default();
+    let configuration = match severities[code] {
+        Some(severity) => object([("level", string(level(severity)))]),
+        None => object([("enabled", Json::Bool(false))]),
+    };
+    object([
+        ("id", string(code.as_str())),
+        ("name", string(&format!("{code:?}"))),
+        ("shortDescription", message(summary)),
+        ("fullDescription", message(explanation)),
+        ("helpUri", string(&code.documentation_url())),
+        ("defaultConfiguration", configuration),
+    ])
+}
+
+fn result(diagnostic: &Diagnostic, severity: Severity, base_dir: &Path) -> Json {
+    let related = diagnostic
+        .related
+        .iter()
+        .enumerate()
+        .map(|(idx, (pos, text))| {
+            object([
+                ("id", Json::Number(idx.to_string())),
+                ("physicalLocation", physical_location(pos, base_dir)),
+                ("message", message(text)),
+            ])
+        })
+        .collect();
+    object([
+        ("ruleId", string(diagnostic.code.as_str())),
+        (
+            "ruleIndex",
+            Json::Number(
+                ErrorCode::all()
+                    .position(|code| code == diagnostic.code)
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+        ),
+        ("level", string(level(severity))),
+        ("message", message(&diagnostic.message)),
+        (
+            "locations",
+            Json::Array(vec![object([(
+                "physicalLocation",
+                physical_location(&diagnostic.pos, base_dir),
+            )])]),
+        ),
+        ("relatedLocations", Json::Array(related)),
+    ])
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info | Severity::Hint => "note",
+    }
+}
+
+/// A location with one-based lines and columns, where columns count UTF-16 code units
+/// like the positions of the language server protocol
+fn physical_location(pos: &SrcPos, base_dir: &Path) -> Json {
+    let file_name = pos.file_name();
+    let uri = match file_name.strip_prefix(base_dir) {
+        Ok(relative) => relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => format!("file://{}", file_name.to_string_lossy().replace('\\', "/")),
+    };
+    let range = pos.range();
+    let number = |value: u32| Json::Number((value + 1).to_string());
+    object([
+        ("artifactLocation", object([("uri", string(&uri))])),
+        (
+            "region",
+            object([
+                ("startLine", number(range.start.line)),
+                ("startColumn", number(range.start.character)),
+                ("endLine", number(range.end.line)),
+                ("endColumn", number(range.end.character)),
+            ]),
+        ),
+    ])
+}
+
+fn message(text: &str) -> Json {
+    object([("text", string(text))])
+}
+
+fn string(value: &str) -> Json {
+    Json::String(value.to_owned())
+}
+
+fn object<const N: usize>(members: [(&str, Json); N]) -> Json {
+    Json::Object(
+        members
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+
+    #[test]
+    fn diagnostics_are_results_of_rules() {
+        let code = Code::new("signal sig : bit;\nsignal sig : bit;");
+        let diagnostic = Diagnostic::new(
+            code.s("sig :", 2).s1("sig"),
+            "Duplicate declaration of 'sig'",
+            ErrorCode::Duplicate,
+        )
+        .related(code.s1("sig :").s1("sig"), "Previously defined here");
+        let mut severities = SeverityMap::default();
+        severities[ErrorCode::Unused] = None;
+        let unused = Diagnostic::new(code.s1("sig"), "Unused", ErrorCode::Unused);
+
+        let base_dir = code.source().file_name().parent().unwrap();
+        let sarif = to_sarif(&[diagnostic, unused], &severities, base_dir).to_string();
+        let file_name = code.source().file_name().file_name().unwrap();
+        let file_name = file_name.to_string_lossy();
+
+        assert!(sarif.starts_with(
+            "{\"$schema\":\"https://json.schemastore.org/sarif-2.1.0.json\",\"version\":\"2.1.0\""
+        ));
+        assert!(sarif.contains(&format!(
+            "\"results\":[{{\"ruleId\":\"duplicate\",\"ruleIndex\":{},\"level\":\"error\",\
+             \"message\":{{\"text\":\"Duplicate declaration of 'sig'\"}},\
+             \"locations\":[{{\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":\"{file_name}\"}},\
+             \"region\":{{\"startLine\":2,\"startColumn\":8,\"endLine\":2,\"endColumn\":11}}}}}}],\
+             \"relatedLocations\":[{{\"id\":0,\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":\"{file_name}\"}},\
+             \"region\":{{\"startLine\":1,\"startColumn\":8,\"endLine\":1,\"endColumn\":11}}}},\
+             \"message\":{{\"text\":\"Previously defined here\"}}}}]}}]",
+            ErrorCode::all()
+                .position(|code| code == ErrorCode::Duplicate)
+                .unwrap()
+        )));
+        assert!(sarif.contains(&format!(
+            "{{\"id\":\"unused\",\"name\":\"Unused\",\"shortDescription\":{{\"text\":\"{}\"}}",
+            ErrorCode::Unused.explanation().lines().next().unwrap()
+        )));
+        assert!(sarif.contains(&format!(
+            "\"helpUri\":\"{}\",\"defaultConfiguration\":{{\"enabled\":false}}",
+            ErrorCode::Unused.documentation_url()
+        )));
+    }
+}
@@ -0,0 +1,276 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Suggests a starter `vhdl_ls.toml` for an existing source tree.
+//!
+//! The suggestion only maps files to libraries and is meant to be edited afterwards,
+//! i.e., to mark vendor libraries as third-party.
+
+use itertools::Itertools;
+use std::collections::BTreeMap;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use strum::{EnumString, IntoStaticStr};
+
+/// How the files of a source tree are grouped into libraries.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum LibraryGrouping {
+    /// One library per directory that contains VHDL files, named after the directory
+    Directory,
+    /// One library per top-level directory, containing all files below it
+    TopDirectory,
+    /// A single library containing all files
+    Single,
+}
+
+/// A library of the suggested configuration with the glob patterns of its files,
+/// relative to the scanned directory.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SuggestedLibrary {
+    pub name: String,
+    pub files: Vec<String>,
+}
+
+/// Recursively scans `root` for files ending in `.vhd` or `.vhdl` and groups them into libraries.
+/// Hidden directories are skipped.
+/// `library_name` is used for the single library, and for files directly inside `root`
+/// if the name of `root` is not a valid library name.
+pub fn suggest_libraries(
+    root: &Path,
+    grouping: LibraryGrouping,
+    library_name: &str,
+) -> io::Result<Vec<SuggestedLibrary>> {
+    // Directories relative to `root` containing VHDL files, with the extensions found in them
+    let mut directories: BTreeMap<Vec<String>, Vec<String>> = BTreeMap::new();
+    scan_directory(root, &mut Vec::new(), &mut directories)?;
+
+    let root_name = root
+        .canonicalize()
+        .ok()
+        .and_then(|root| {
+            root.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .and_then(|name| library_name_of(&name))
+        .unwrap_or_else(|| library_name.to_owned());
+
+    let mut libraries: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (directory, extensions) in directories.iter() {
+        let (name, pattern_directory) = match grouping {
+            LibraryGrouping::Directory => (
+                directory.last().and_then(|name| library_name_of(name)),
+                directory.iter().map(|name| format!("{name}/")).join(""),
+            ),
+            LibraryGrouping::TopDirectory => match directory.first() {
+                Some(top) => (library_name_of(top), format!("{top}/**/")),
+                None => (None, String::new()),
+            },
+            LibraryGrouping::Single => (Some(library_name.to_owned()), "**/".to_owned()),
+        };
+        let files = libraries
+            .entry(name.unwrap_or_else(|| root_name.clone()))
+            .or_default();
+        for extension in extensions {
+            let pattern = format!("{pattern_directory}*.{extension}");
+            if !files.contains(&pattern) {
+                files.push(pattern);
+            }
+        }
+    }
+
+    Ok(libraries
+        .into_iter()
+        .map(|(name, files)| SuggestedLibrary { name, files })
+        .collect())
+}
+
+fn scan_directory(
+    path: &Path,
+    relative: &mut Vec<String>,
+    directories: &mut BTreeMap<Vec<String>, Vec<String>>,
+) -> io::Result<()> {
+    let entries = std::fs::read_dir(path)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .sorted();
+    for entry in entries {
+        let Some(name) = entry
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        if entry.is_dir() {
+            if !name.starts_with('.') {
+                relative.push(name);
+                scan_directory(&entry, relative, directories)?;
+                relative.pop();
+            }
+        } else if let Some(extension) = entry
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .filter(|extension| ["vhd", "vhdl"].contains(extension))
+        {
+            let extensions = directories.entry(relative.clone()).or_default();
+            if !extensions.iter().any(|existing| existing == extension) {
+                extensions.push(extension.to_owned());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Turns the name of a directory into a VHDL identifier that can be used as a library name,
+/// or returns `None` if that is not possible.
+fn library_name_of(directory: &str) -> Option<String> {
+    let name = directory
+        .to_lowercase()
+        .chars()
+        .map(|chr| {
+            if chr.is_ascii_alphanumeric() {
+                chr
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .join("_");
+    if name.is_empty() || name == "work" {
+        None
+    } else if name.starts_with(|chr: char| chr.is_ascii_digit()) {
+        Some(format!("lib_{name}"))
+    } else {
+        Some(name)
+    }
+}
+
+/// Writes the `vhdl_ls.toml` configuration of `libraries`
+pub fn write_config(libraries: &[SuggestedLibrary], writer: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        writer,
+        "# File names are relative to the parent folder of this file"
+    )?;
+    writeln!(writer, "[libraries]")?;
+    for library in libraries {
+        writeln!(writer, "{}.files = [", library.name)?;
+        for file in &library.files {
+            writeln!(writer, "    {},", toml_string(file))?;
+        }
+        writeln!(writer, "]")?;
+    }
+    Ok(())
+}
+
+fn toml_string(string: &str) -> String {
+    if string.contains(['\'', '\n']) {
+        format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        format!("'{string}'")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use std::fs;
+
+    fn create_tree(files: &[&str]) -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        for file in files {
+            let path = root.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        root
+    }
+
+    fn library(name: &str, files: &[&str]) -> SuggestedLibrary {
+        SuggestedLibrary {
+            name: name.to_owned(),
+            files: files.iter().map(|file| file.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn groups_files_by_directory() {
+        let root = create_tree(&[
+            "uart/rtl/uart.vhd",
+            "uart/tb/tb_uart.vhdl",
+            "My-Lib/pkg.vhd",
+            "My-Lib/sub/ent.vhd",
+            ".git/ignored.vhd",
+            "doc/readme.md",
+        ]);
+        assert_eq!(
+            suggest_libraries(root.path(), LibraryGrouping::Directory, "lib").unwrap(),
+            vec![
+                library("my_lib", &["My-Lib/*.vhd"]),
+                library("rtl", &["uart/rtl/*.vhd"]),
+                library("sub", &["My-Lib/sub/*.vhd"]),
+                library("tb", &["uart/tb/*.vhdl"]),
+            ]
+        );
+        assert_eq!(
+            suggest_libraries(root.path(), LibraryGrouping::TopDirectory, "lib").unwrap(),
+            vec![
+                library("my_lib", &["My-Lib/**/*.vhd"]),
+                library("uart", &["uart/**/*.vhd", "uart/**/*.vhdl"]),
+            ]
+        );
+        assert_eq!(
+            suggest_libraries(root.path(), LibraryGrouping::Single, "design").unwrap(),
+            vec![library("design", &["**/*.vhd", "**/*.vhdl"])]
+        );
+    }
+
+    #[test]
+    fn names_libraries_after_valid_identifiers() {
+        assert_eq!(library_name_of("Core"), Some("core".to_owned()));
+        assert_eq!(library_name_of("my lib-2"), Some("my_lib_2".to_owned()));
+        assert_eq!(library_name_of("7series"), Some("lib_7series".to_owned()));
+        assert_eq!(library_name_of("work"), None);
+        assert_eq!(library_name_of("--"), None);
+    }
+
+    #[test]
+    fn written_config_can_be_read() {
+        let libraries = vec![
+            library("rtl", &["src/*.vhd"]),
+            library("tb", &["it's/*.vhd"]),
+        ];
+        let mut config = Vec::new();
+        write_config(&libraries, &mut config).unwrap();
+        let config = String::from_utf8(config).unwrap();
+        assert_eq!(
+            config,
+            "\
+# File names are relative to the parent folder of this file
+[libraries]
+rtl.files = [
+    'src/*.vhd',
+]
+tb.files = [
+    \"it's/*.vhd\",
+]
+"
+        );
+
+        let parsed = Config::from_str(&config, Path::new("root")).unwrap();
+        assert_eq!(
+            parsed
+                .iter_libraries()
+                .map(|library| library.name())
+                .sorted()
+                .collect_vec(),
+            vec!["rtl", "tb"]
+        );
+    }
+}
@@ -0,0 +1,385 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Project snapshots for reproducible bug reports.
+//!
+//! A snapshot is a tar archive that contains
+//! * `vhdl_ls.toml` - a configuration mapping all libraries to files within the archive
+//! * `VERSION` - the version of `vhdl_lang` that created the snapshot
+//! * `files/<library>/...` - the contents of all files of the project
+//!
+//! Extracting a snapshot and analyzing the contained configuration
+//! reproduces the analysis of the original project.
+//! Snapshots of proprietary code can be anonymized, see [crate::anonymize].
+
+use crate::anonymize::Anonymizer;
+use crate::data::error_codes::ErrorCode;
+use crate::{Config, Latin1String, MessageHandler, Source, TopLevel};
+use enum_map::Enum;
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use std::io;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Name of the configuration file within a snapshot
+pub const SNAPSHOT_CONFIG: &str = "vhdl_ls.toml";
+/// Name of the file containing the version within a snapshot
+pub const SNAPSHOT_VERSION: &str = "VERSION";
+
+/// Write a snapshot of all files referenced by `config` to `writer`.
+/// If `anonymize` is set, all files and libraries that are not third-party are anonymized
+/// using an [Anonymizer].
+pub fn write_snapshot(
+    config: &Config,
+    writer: impl Write,
+    messages: &mut dyn MessageHandler,
+    anonymize: bool,
+) -> io::Result<()> {
+    let mut builder = tar::Builder::new(writer);
+    for (path, data) in snapshot_files(config, messages, anonymize)? {
+        append_file(&mut builder, &path, &data)?;
+    }
+    builder.into_inner()?.flush()
+}
+
+/// Write an anonymized copy of all files referenced by `config` to `directory`.
+/// The directory has the same layout as an extracted snapshot.
+pub fn write_anonymized_project(
+    config: &Config,
+    directory: &Path,
+    messages: &mut dyn MessageHandler,
+) -> io::Result<()> {
+    for (path, data) in snapshot_files(config, messages, true)? {
+        let path = directory.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, data)?;
+    }
+    Ok(())
+}
+
+/// Returns the relative path and contents of every file of a snapshot.
+fn snapshot_files(
+    config: &Config,
+    messages: &mut dyn MessageHandler,
+    anonymize: bool,
+) -> io::Result<Vec<(String, Vec<u8>)>> {
+    let mut anonymizer = anonymize.then(|| Anonymizer::from_config(config, messages));
+    let mut snapshot_files = Vec::new();
+    // Files mapped to multiple libraries are only stored once
+    let mut archived: FnvHashMap<PathBuf, String> = FnvHashMap::default();
+    let mut libraries = Table::new();
+
+    for library in config
+        .iter_libraries()
+        .sorted_by_key(|library| library.name())
+    {
+        let mut anonymizer = anonymizer.as_mut().filter(|_| !library.is_third_party);
+        let library_name = match &mut anonymizer {
+            Some(anonymizer) => anonymizer.anonymize_name(library.name()),
+            None => library.name().to_owned(),
+        };
+        let mut files = Vec::new();
+        for (file_name, standard) in library.file_names_with_standards(messages) {
+            let archive_name = if let Some(archive_name) = archived.get(&file_name) {
+                archive_name.clone()
+            } else {
+                let (base_name, data) = match &mut anonymizer {
+                    Some(anonymizer) => (
+                        anonymized_file_name(&file_name),
+                        anonymized_contents(anonymizer, &file_name)?,
+                    ),
+                    None => (
+                        file_name
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                        std::fs::read(&file_name)?,
+                    ),
+                };
+                let archive_name = format!("files/{library_name}/{}_{base_name}", archived.len());
+                snapshot_files.push((archive_name.clone(), data));
+                archived.insert(file_name, archive_name.clone());
+                archive_name
+            };
+            match standard {
+                Some(standard) => {
+                    let mut file = Table::new();
+                    file.insert("path".to_owned(), Value::String(archive_name));
+                    file.insert(
+                        "standard".to_owned(),
+                        Value::String(standard.as_ref().to_owned()),
+                    );
+                    files.push(Value::Table(file));
+                }
+                None => files.push(Value::String(archive_name)),
+            }
+        }
+
+        let mut table = Table::new();
+        table.insert("files".to_owned(), Value::Array(files));
+        if let Some(standard) = library.standard {
+            table.insert(
+                "standard".to_owned(),
+                Value::String(standard.as_ref().to_owned()),
+            );
+        }
+        if library.is_third_party {
+            table.insert("is_third_party".to_owned(), Value::Boolean(true));
+        }
+        if library.is_synthesizable {
+            table.insert("is_synthesizable".to_owned(), Value::Boolean(true));
+        }
+        if library.ignores_initial_values {
+            table.insert("ignores_initial_values".to_owned(), Value::Boolean(true));
+        }
+        if library.is_testbench {
+            table.insert("is_testbench".to_owned(), Value::Boolean(true));
+        }
+        libraries.insert(library_name, Value::Table(table));
+    }
+
+    let mut lint = Table::new();
+    for code in (0..ErrorCode::LENGTH).map(ErrorCode::from_usize) {
+        let code_name: &str = code.into();
+        let value = match config.severities()[code] {
+            Some(severity) => {
+                let severity: &str = severity.into();
+                Value::String(severity.to_owned())
+            }
+            None => Value::Boolean(false),
+        };
+        lint.insert(code_name.to_owned(), value);
+    }
+
+    let mut table = Table::new();
+    table.insert(
+        "standard".to_owned(),
+        Value::String(config.standard().as_ref().to_owned()),
+    );
+    table.insert("libraries".to_owned(), Value::Table(libraries));
+    table.insert("lint".to_owned(), Value::Table(lint));
+    if !config.top_levels().is_empty() {
+        let top_levels = config
+            .top_levels()
+            .iter()
+            .map(|top| {
+                let is_third_party = config
+                    .get_library(&top.library)
+                    .is_some_and(|library| library.is_third_party);
+                let top = match anonymizer.as_mut().filter(|_| !is_third_party) {
+                    Some(anonymizer) => TopLevel {
+                        library: anonymizer.anonymize_name(&top.library),
+                        entity: anonymizer.anonymize_name(&top.entity),
+                        architecture: top
+                            .architecture
+                            .as_ref()
+                            .map(|architecture| anonymizer.anonymize_name(architecture)),
+                    },
+                    None => top.clone(),
+                };
+                Value::String(top.to_string())
+            })
+            .collect();
+        table.insert("top".to_owned(), Value::Array(top_levels));
+    }
+    if !config.conditional_identifiers().is_empty() {
+        let identifiers = config
+            .conditional_identifiers()
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect();
+        table.insert("conditional_analysis".to_owned(), Value::Table(identifiers));
+    }
+    if !config.ban_list().is_empty() {
+        table.insert(
+            "banned".to_owned(),
+            Value::Table(config.ban_list().to_table()),
+        );
+    }
+
+    snapshot_files.push((SNAPSHOT_CONFIG.to_owned(), table.to_string().into_bytes()));
+    snapshot_files.push((
+        SNAPSHOT_VERSION.to_owned(),
+        env!("CARGO_PKG_VERSION").as_bytes().to_vec(),
+    ));
+    Ok(snapshot_files)
+}
+
+/// Anonymized files only retain the extension of the original file name.
+fn anonymized_file_name(file_name: &Path) -> String {
+    match file_name.extension() {
+        Some(extension) => format!("file.{}", extension.to_string_lossy()),
+        None => "file".to_owned(),
+    }
+}
+
+fn anonymized_contents(anonymizer: &mut Anonymizer, file_name: &Path) -> io::Result<Vec<u8>> {
+    let source = Source::from_latin1_file(file_name)?;
+    let latin1 = Latin1String::from_utf8(&anonymizer.anonymize(&source))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.message()))?;
+    Ok(latin1.bytes)
+}
+
+fn append_file<W: Write>(builder: &mut tar::Builder<W>, path: &str, data: &[u8]) -> io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, path, data)
+}
+
+/// A snapshot that was extracted to a directory.
+pub struct ExtractedSnapshot {
+    /// The version of `vhdl_lang` that created the snapshot
+    pub version: String,
+    /// The configuration of the snapshot, referring to the extracted files
+    pub config: Config,
+}
+
+/// Extract the snapshot read from `reader` into `directory`.
+pub fn extract_snapshot(reader: impl Read, directory: &Path) -> io::Result<ExtractedSnapshot> {
+    tar::Archive::new(reader).unpack(directory)?;
+    let version = std::fs::read_to_string(directory.join(SNAPSHOT_VERSION))?;
+    let config = Config::read_file_path(&directory.join(SNAPSHOT_CONFIG))?;
+    Ok(ExtractedSnapshot {
+        version: version.trim().to_owned(),
+        config,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NullMessages, Severity, VHDLStandard};
+    use std::fs::File;
+
+    #[test]
+    fn snapshot_roundtrip() {
+        let project = tempfile::tempdir().unwrap();
+        let file_name = project.path().join("foo.vhd");
+        std::fs::write(&file_name, "entity foo is end entity;").unwrap();
+        let config = Config::from_str(
+            "
+standard = \"1993\"
+top = ['lib1.foo(rtl)']
+
+[libraries]
+lib1.files = ['foo.vhd']
+lib2.files = ['foo.vhd']
+lib2.is_third_party = true
+lib1.is_synthesizable = true
+lib1.standard = \"2019\"
+
+[lint]
+unused = 'error'
+
+[conditional_analysis]
+target = 'sim'
+
+[banned]
+constructs = ['shared_variable', 'block']
+packages = ['ieee.std_logic_arith']
+",
+            project.path(),
+        )
+        .unwrap();
+
+        let mut archive = Vec::new();
+        write_snapshot(&config, &mut archive, &mut NullMessages, false).unwrap();
+
+        let extracted = tempfile::tempdir().unwrap();
+        let snapshot = extract_snapshot(archive.as_slice(), extracted.path()).unwrap();
+        assert_eq!(snapshot.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(snapshot.config.standard(), VHDLStandard::VHDL1993);
+        assert_eq!(
+            snapshot.config.severities()[ErrorCode::Unused],
+            Some(Severity::Error)
+        );
+        assert_eq!(snapshot.config.top_levels(), config.top_levels());
+        assert_eq!(snapshot.config.ban_list(), config.ban_list());
+        assert_eq!(
+            snapshot.config.conditional_identifiers(),
+            config.conditional_identifiers()
+        );
+        assert!(snapshot.config.get_library("lib2").unwrap().is_third_party);
+        assert!(
+            snapshot
+                .config
+                .get_library("lib1")
+                .unwrap()
+                .is_synthesizable
+        );
+        assert_eq!(
+            snapshot.config.get_library("lib1").unwrap().standard,
+            Some(VHDLStandard::VHDL2019)
+        );
+        assert_eq!(snapshot.config.get_library("lib2").unwrap().standard, None);
+
+        let lib1_files = snapshot
+            .config
+            .get_library("lib1")
+            .unwrap()
+            .file_names(&mut NullMessages);
+        let lib2_files = snapshot
+            .config
+            .get_library("lib2")
+            .unwrap()
+            .file_names(&mut NullMessages);
+        assert_eq!(lib1_files, lib2_files);
+        assert_eq!(lib1_files.len(), 1);
+        let mut contents = String::new();
+        File::open(&lib1_files[0])
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "entity foo is end entity;");
+    }
+
+    #[test]
+    fn anonymized_snapshot() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(
+            project.path().join("secret.vhd"),
+            "-- secret\nentity secret is end entity;",
+        )
+        .unwrap();
+        let config = Config::from_str(
+            "
+top = ['secret_lib.secret']
+
+[libraries]
+secret_lib.files = ['secret.vhd']
+",
+            project.path(),
+        )
+        .unwrap();
+
+        let anonymized = tempfile::tempdir().unwrap();
+        write_anonymized_project(&config, anonymized.path(), &mut NullMessages).unwrap();
+        let config = std::fs::read_to_string(anonymized.path().join(SNAPSHOT_CONFIG)).unwrap();
+        assert!(!config.contains("secret"));
+        let config = Config::read_file_path(&anonymized.path().join(SNAPSHOT_CONFIG)).unwrap();
+        let library = config.iter_libraries().exactly_one().ok().unwrap();
+        assert_eq!(
+            config.top_levels(),
+            &[TopLevel {
+                library: library.name().to_owned(),
+                entity: "id_2".to_owned(),
+                architecture: None,
+            }]
+        );
+        let files = library.file_names(&mut NullMessages);
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&files[0]).unwrap(),
+            "--\nentity id_2 is end entity;"
+        );
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Ord, PartialOrd)]
+use strum::{EnumIter, IntoEnumIterator};
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Ord, PartialOrd, Hash, EnumIter)]
 pub enum VHDLStandard {
     VHDL1993,
     #[default]
@@ -25,6 +27,13 @@ impl TryFrom<&str> for VHDLStandard {
     }
 }
 
+impl VHDLStandard {
+    /// All supported standards from the oldest to the newest
+    pub fn all() -> impl Iterator<Item = VHDLStandard> {
+        VHDLStandard::iter()
+    }
+}
+
 impl AsRef<str> for VHDLStandard {
     fn as_ref(&self) -> &str {
         use VHDLStandard::*;
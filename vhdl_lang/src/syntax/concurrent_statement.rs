@@ -22,7 +22,7 @@ use super::waveform::{parse_delay_mechanism, parse_waveform};
 use crate::ast::token_range::WithTokenSpan;
 use crate::ast::*;
 use crate::data::*;
-use crate::syntax::recover::{expect_semicolon, expect_semicolon_or_last};
+use crate::syntax::recover::{expect_end_kind, expect_semicolon, expect_semicolon_or_last};
 use crate::syntax::{Kind, TokenAccess};
 use crate::TokenId;
 use vhdl_lang::syntax::parser::ParsingContext;
@@ -236,9 +236,15 @@ pub fn parse_process_statement(
             ));
         }
     }
-    ctx.stream.expect_kind(Process)?;
-    let end_ident = ctx.stream.pop_optional_ident();
-    let end_tok = expect_semicolon_or_last(ctx);
+    let mut end_label_pos = None;
+    let end_tok = if expect_end_kind(ctx, Process)? {
+        let end_ident = ctx.stream.pop_optional_ident();
+        let end_tok = expect_semicolon_or_last(ctx);
+        end_label_pos = check_label_identifier_mismatch(ctx, label, end_ident);
+        end_tok
+    } else {
+        ctx.stream.get_last_token_id()
+    };
     Ok(ProcessStatement {
         postponed: postponed.is_some(),
         sensitivity_list,
@@ -247,7 +253,7 @@ pub fn parse_process_statement(
         begin_token,
         statements,
         end_token,
-        end_label_pos: check_label_identifier_mismatch(ctx, label, end_ident),
+        end_label_pos,
         span: TokenSpan::new(postponed.unwrap_or(process_token), end_tok),
     })
 }
@@ -30,7 +30,7 @@ pub(crate) fn parse_entity_aspect(ctx: &mut ParsingContext<'_>) -> ParseResult<E
                 if ctx.stream.skip_if_kind(LeftPar) {
                     let ident = ctx.stream.expect_ident()?;
                     ctx.stream.expect_kind(RightPar)?;
-                    Some(ident)
+                    Some(WithRef::new(ident))
                 } else {
                     None
                 }
@@ -166,7 +166,7 @@ fn parse_component_specification_or_name(
                     let component_name = parse_selected_name(ctx)?;
                     let end_token = component_name.span.end_token;
                     Ok(ComponentSpecificationOrName::ComponentSpec(ComponentSpecification {
-                        instantiation_list: InstantiationList::Labels(vec![ident]),
+                        instantiation_list: InstantiationList::Labels(vec![WithRef::new(ident)]),
                         component_name,
                         colon_token,
                         span: TokenSpan::new(start_token, end_token),
@@ -174,9 +174,9 @@ fn parse_component_specification_or_name(
                 }
                 Comma => {
                     ctx.stream.skip();
-                    let mut idents = vec![to_simple_name(ctx.stream, name)?];
+                    let mut idents = vec![WithRef::new(to_simple_name(ctx.stream, name)?)];
                     let colon_token = loop {
-                        idents.push(ctx.stream.expect_ident()?);
+                        idents.push(WithRef::new(ctx.stream.expect_ident()?));
                         expect_token!(
                             ctx.stream,
                             next_token,
@@ -652,9 +652,9 @@ end configuration cfg;
                     use_clauses: vec![],
                     items: vec![ConfigurationItem::Component(ComponentConfiguration {
                         spec: ComponentSpecification {
-                            instantiation_list: InstantiationList::Labels(vec![code
-                                .s1("inst")
-                                .ident()]),
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
                             colon_token: code.s1(":").token(),
                             component_name: code.s1("lib.pkg.comp").name(),
                             span: code.s1("for inst : lib.pkg.comp").token_span()
@@ -722,9 +722,9 @@ end configuration cfg;
                     use_clauses: vec![],
                     items: vec![ConfigurationItem::Component(ComponentConfiguration {
                         spec: ComponentSpecification {
-                            instantiation_list: InstantiationList::Labels(vec![code
-                                .s1("inst")
-                                .ident()]),
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
                             colon_token: code.s1(":").token(),
                             component_name: code.s1("lib.pkg.comp").name(),
                             span: code.s1("for inst : lib.pkg.comp").token_span()
@@ -804,9 +804,9 @@ end configuration cfg;
                     use_clauses: vec![],
                     items: vec![ConfigurationItem::Component(ComponentConfiguration {
                         spec: ComponentSpecification {
-                            instantiation_list: InstantiationList::Labels(vec![code
-                                .s1("inst")
-                                .ident()]),
+                            instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                code.s1("inst").ident()
+                            )]),
                             colon_token: code.s1(":").token(),
                             component_name: code.s1("lib.pkg.comp").name(),
                             span: code.s1("for inst : lib.pkg.comp").token_span()
@@ -875,9 +875,9 @@ end configuration cfg;
                     items: vec![
                         ConfigurationItem::Component(ComponentConfiguration {
                             spec: ComponentSpecification {
-                                instantiation_list: InstantiationList::Labels(vec![code
-                                    .s1("inst")
-                                    .ident()]),
+                                instantiation_list: InstantiationList::Labels(vec![WithRef::new(
+                                    code.s1("inst").ident()
+                                )]),
                                 colon_token: code.s(":", 1).token(),
                                 component_name: code.s1("lib.pkg.comp").name(),
                                 span: code.s1("for inst : lib.pkg.comp").token_span()
@@ -893,9 +893,9 @@ end configuration cfg;
                         ConfigurationItem::Component(ComponentConfiguration {
                             spec: ComponentSpecification {
                                 instantiation_list: InstantiationList::Labels(vec![
-                                    code.s1("inst1").ident(),
-                                    code.s1("inst2").ident(),
-                                    code.s1("inst3").ident()
+                                    WithRef::new(code.s1("inst1").ident()),
+                                    WithRef::new(code.s1("inst2").ident()),
+                                    WithRef::new(code.s1("inst3").ident())
                                 ]),
                                 colon_token: code.s(":", 2).token(),
                                 component_name: code.s1("lib2.pkg.comp").name(),
@@ -977,7 +977,7 @@ end configuration cfg;
             code.with_stream(parse_entity_aspect),
             EntityAspect::Entity(
                 code.s1("lib.foo.name").name(),
-                Some(code.s1("arch").ident())
+                Some(WithRef::new(code.s1("arch").ident()))
             )
         );
     }
@@ -1014,7 +1014,7 @@ end configuration cfg;
                 bind_ind: BindingIndication {
                     entity_aspect: Some(EntityAspect::Entity(
                         code.s1("work.foo").name(),
-                        Some(code.s1("rtl").ident())
+                        Some(WithRef::new(code.s1("rtl").ident()))
                     )),
                     generic_map: None,
                     port_map: None,
@@ -1043,7 +1043,7 @@ end configuration cfg;
                 bind_ind: BindingIndication {
                     entity_aspect: Some(EntityAspect::Entity(
                         code.s1("work.foo").name(),
-                        Some(code.s1("rtl").ident())
+                        Some(WithRef::new(code.s1("rtl").ident()))
                     )),
                     generic_map: None,
                     port_map: None,
@@ -1074,7 +1074,7 @@ end configuration cfg;
                 bind_ind: BindingIndication {
                     entity_aspect: Some(EntityAspect::Entity(
                         code.s1("work.foo").name(),
-                        Some(code.s1("rtl").ident())
+                        Some(WithRef::new(code.s1("rtl").ident()))
                     )),
                     generic_map: None,
                     port_map: None,
@@ -71,6 +71,22 @@ pub fn parse_declarative_part(
 ) -> ParseResult<Vec<WithTokenSpan<Declaration>>> {
     let mut declarations: Vec<WithTokenSpan<Declaration>> = Vec::new();
 
+    while let Some(token) = ctx.stream.peek() {
+        match token.kind {
+            Begin | End => break,
+            _ => parse_declarative_item(ctx, &mut declarations)?,
+        }
+    }
+
+    Ok(declarations)
+}
+
+/// Parse a single declarative item, i.e., the declarations of a single statement.
+/// Recoverable errors are reported to the diagnostics of the context.
+pub fn parse_declarative_item(
+    ctx: &mut ParsingContext<'_>,
+    declarations: &mut Vec<WithTokenSpan<Declaration>>,
+) -> ParseResult<()> {
     fn is_recover_token(kind: Kind) -> bool {
         matches!(
             kind,
@@ -96,98 +112,80 @@ pub fn parse_declarative_part(
         )
     }
 
-    while let Some(token) = ctx.stream.peek() {
-        let start_token = ctx.stream.get_current_token_id();
-        match token.kind {
-            Begin | End => break,
-            Type | Subtype | Component | Impure | Pure | Function | Procedure | Package | For => {
-                let decl = match token.kind {
-                    Type | Subtype => parse_type_declaration(ctx).map(Declaration::Type)?,
-                    Component => parse_component_declaration(ctx).map(Declaration::Component)?,
-                    Impure | Pure | Function | Procedure => parse_subprogram(ctx)?,
-                    Package => parse_package_instantiation(ctx).map(Declaration::Package)?,
-                    For => {
-                        parse_configuration_specification(ctx).map(Declaration::Configuration)?
-                    }
-                    _ => unreachable!(),
-                };
-                let end_token = ctx.stream.get_last_token_id();
-                declarations.push(WithTokenSpan::new(
-                    decl,
-                    TokenSpan::new(start_token, end_token),
-                ));
-            }
+    let token = ctx.stream.peek_expect()?;
+    let start_token = ctx.stream.get_current_token_id();
+    match token.kind {
+        Type | Subtype | Component | Impure | Pure | Function | Procedure | Package | For => {
+            let decl = match token.kind {
+                Type | Subtype => parse_type_declaration(ctx).map(Declaration::Type)?,
+                Component => parse_component_declaration(ctx).map(Declaration::Component)?,
+                Impure | Pure | Function | Procedure => parse_subprogram(ctx)?,
+                Package => parse_package_instantiation(ctx).map(Declaration::Package)?,
+                For => parse_configuration_specification(ctx).map(Declaration::Configuration)?,
+                _ => unreachable!(),
+            };
+            let end_token = ctx.stream.get_last_token_id();
+            declarations.push(WithTokenSpan::new(
+                decl,
+                TokenSpan::new(start_token, end_token),
+            ));
+        }
 
-            File | Shared | Constant | Signal | Variable | Attribute => {
-                let decls: ParseResult<Vec<WithTokenSpan<Declaration>>> = match token.kind {
-                    File => parse_file_declaration(ctx)
-                        .map(|decl| vec![decl.map_into(Declaration::File)]),
-                    Shared | Constant | Signal | Variable => parse_object_declaration(ctx)
-                        .map(|decl| vec![decl.map_into(Declaration::Object)]),
-                    Attribute => parse_attribute(ctx).map(|decls| {
-                        decls
-                            .into_iter()
-                            .map(|decl| decl.map_into(Declaration::Attribute))
-                            .collect()
-                    }),
-                    _ => unreachable!(),
-                };
-                match decls.or_recover_until(ctx, is_recover_token) {
-                    Ok(ref mut decls) => declarations.append(decls),
-                    Err(err) => {
-                        ctx.diagnostics.push(err);
-                        continue;
-                    }
+        File | Shared | Constant | Signal | Variable | Attribute => {
+            let decls: ParseResult<Vec<WithTokenSpan<Declaration>>> = match token.kind {
+                File => {
+                    parse_file_declaration(ctx).map(|decl| vec![decl.map_into(Declaration::File)])
                 }
+                Shared | Constant | Signal | Variable => parse_object_declaration(ctx)
+                    .map(|decl| vec![decl.map_into(Declaration::Object)]),
+                Attribute => parse_attribute(ctx).map(|decls| {
+                    decls
+                        .into_iter()
+                        .map(|decl| decl.map_into(Declaration::Attribute))
+                        .collect()
+                }),
+                _ => unreachable!(),
+            };
+            match decls.or_recover_until(ctx, is_recover_token) {
+                Ok(ref mut decls) => declarations.append(decls),
+                Err(err) => ctx.diagnostics.push(err),
             }
+        }
 
-            Use | Alias => {
-                let decl: ParseResult<WithTokenSpan<Declaration>> = match token.kind {
-                    Use => parse_use_clause(ctx).map(|decl| decl.map_into(Declaration::Use)),
-                    Alias => {
-                        parse_alias_declaration(ctx).map(|decl| decl.map_into(Declaration::Alias))
-                    }
-                    _ => unreachable!(),
-                };
-                match decl.or_recover_until(ctx, is_recover_token) {
-                    Ok(decl) => declarations.push(decl),
-                    Err(err) => {
-                        ctx.diagnostics.push(err);
-                        continue;
-                    }
-                }
+        Use | Alias => {
+            let decl: ParseResult<WithTokenSpan<Declaration>> = match token.kind {
+                Use => parse_use_clause(ctx).map(|decl| decl.map_into(Declaration::Use)),
+                Alias => parse_alias_declaration(ctx).map(|decl| decl.map_into(Declaration::Alias)),
+                _ => unreachable!(),
+            };
+            match decl.or_recover_until(ctx, is_recover_token) {
+                Ok(decl) => declarations.push(decl),
+                Err(err) => ctx.diagnostics.push(err),
             }
+        }
 
-            View => {
-                match parse_mode_view_declaration(ctx).or_recover_until(ctx, is_recover_token) {
-                    Ok(decl) => declarations.push(decl.map_into(Declaration::View)),
-                    Err(err) => {
-                        ctx.diagnostics.push(err);
-                        continue;
-                    }
-                }
-            }
+        View => match parse_mode_view_declaration(ctx).or_recover_until(ctx, is_recover_token) {
+            Ok(decl) => declarations.push(decl.map_into(Declaration::View)),
+            Err(err) => ctx.diagnostics.push(err),
+        },
 
-            _ => {
-                use crate::VHDLStandard::*;
-                let expected: &[Kind] = match ctx.standard {
-                    VHDL2008 | VHDL1993 => &[
-                        Type, Subtype, Component, Impure, Pure, Function, Procedure, Package, For,
-                        File, Shared, Constant, Signal, Variable, Attribute, Use, Alias,
-                    ],
-                    VHDL2019 => &[
-                        Type, Subtype, Component, Impure, Pure, Function, Procedure, Package, For,
-                        File, Shared, Constant, Signal, Variable, Attribute, Use, Alias, View,
-                    ],
-                };
-                ctx.diagnostics.push(token.kinds_error(expected));
-                ctx.stream.skip_until(is_recover_token)?;
-                continue;
-            }
+        _ => {
+            use crate::VHDLStandard::*;
+            let expected: &[Kind] = match ctx.standard {
+                VHDL2008 | VHDL1993 => &[
+                    Type, Subtype, Component, Impure, Pure, Function, Procedure, Package, For,
+                    File, Shared, Constant, Signal, Variable, Attribute, Use, Alias,
+                ],
+                VHDL2019 => &[
+                    Type, Subtype, Component, Impure, Pure, Function, Procedure, Package, For,
+                    File, Shared, Constant, Signal, Variable, Attribute, Use, Alias, View,
+                ],
+            };
+            ctx.diagnostics.push(token.kinds_error(expected));
+            ctx.stream.skip_until(is_recover_token)?;
         }
     }
-
-    Ok(declarations)
+    Ok(())
 }
 
 #[cfg(test)]
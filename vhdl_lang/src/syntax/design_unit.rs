@@ -4,7 +4,7 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::tokens::{HasTokenSpan, Kind::*, TokenSpan};
+use super::tokens::{HasTokenSpan, Kind::*, Token, TokenSpan};
 use vhdl_lang::syntax::parser::ParsingContext;
 
 use super::common::check_end_identifier_mismatch;
@@ -15,7 +15,9 @@ use super::configuration::parse_configuration_declaration;
 use super::context::{
     parse_context, parse_library_clause, parse_use_clause, DeclarationOrReference,
 };
-use super::declarative_part::{parse_declarative_part, parse_package_instantiation};
+use super::declarative_part::{
+    parse_declarative_item, parse_declarative_part, parse_package_instantiation,
+};
 use crate::ast::*;
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
@@ -86,6 +88,7 @@ pub fn parse_architecture_body(ctx: &mut ParsingContext<'_>) -> ParseResult<Arch
         decl,
         end_token,
         statements,
+        is_protected: false,
     })
 }
 
@@ -134,9 +137,87 @@ pub fn parse_package_body(ctx: &mut ParsingContext<'_>) -> ParseResult<PackageBo
         end_ident_pos: check_end_identifier_mismatch(ctx, &ident, end_ident),
         end_token,
         ident: ident.into(),
+        is_protected: false,
+    })
+}
+
+/// PSL 7.2 Verification units
+/// vunit name [(entity_name[(architecture_name)])] { {vunit_item} }
+pub fn parse_verification_unit(ctx: &mut ParsingContext<'_>) -> ParseResult<VerificationUnit> {
+    let start_token = ctx.stream.expect_kind(Vunit)?;
+    let ident = WithDecl::new(ctx.stream.expect_ident()?);
+
+    let binding = if ctx.stream.skip_if_kind(LeftPar) {
+        let entity_name = WithRef::new(ctx.stream.expect_ident()?);
+        let architecture_name = if ctx.stream.skip_if_kind(LeftPar) {
+            let architecture_name = WithRef::new(ctx.stream.expect_ident()?);
+            ctx.stream.expect_kind(RightPar)?;
+            Some(architecture_name)
+        } else {
+            None
+        };
+        ctx.stream.expect_kind(RightPar)?;
+        Some(VerificationUnitBinding {
+            entity_name,
+            architecture_name,
+        })
+    } else {
+        None
+    };
+
+    ctx.stream.expect_kind(LeftBrace)?;
+    let mut decl = Vec::new();
+    let mut names = Vec::new();
+    loop {
+        match ctx.stream.peek_expect()?.kind {
+            RightBrace => break,
+            Type | Subtype | Component | Impure | Pure | Function | Procedure | File | Shared
+            | Constant | Signal | Variable | Attribute | Use | Alias => {
+                parse_declarative_item(ctx, &mut decl)?
+            }
+            _ => skip_psl_item(ctx, &mut names)?,
+        }
+    }
+    let end_token = ctx.stream.expect_kind(RightBrace)?;
+
+    Ok(VerificationUnit {
+        span: TokenSpan::new(start_token, end_token),
+        context_clause: ContextClause::default(),
+        ident,
+        binding,
+        decl,
+        names,
     })
 }
 
+/// Skip a PSL directive or declaration of a verification unit until the terminating semicolon,
+/// collecting the identifiers that may denote VHDL declarations.
+/// Identifiers that are selected from a prefix or that designate an attribute are not collected.
+fn skip_psl_item(ctx: &mut ParsingContext<'_>, names: &mut Vec<WithRef<Ident>>) -> ParseResult<()> {
+    let mut depth = 0_usize;
+    let mut previous = None;
+    loop {
+        let token_id = ctx.stream.get_current_token_id();
+        let token = ctx.stream.peek_expect()?;
+        match token.kind {
+            LeftPar | LeftSquare | LeftBrace => depth += 1,
+            RightPar | RightSquare if depth > 0 => depth -= 1,
+            RightBrace if depth == 0 => return Ok(()),
+            RightBrace => depth -= 1,
+            SemiColon if depth == 0 => {
+                ctx.stream.skip();
+                return Ok(());
+            }
+            Identifier if !matches!(previous, Some(Dot | Tick)) => {
+                names.push(WithRef::new(token.to_identifier_value(token_id)?));
+            }
+            _ => {}
+        }
+        previous = Some(token.kind);
+        ctx.stream.skip();
+    }
+}
+
 fn take_context_clause(context_clause: &mut ContextClause) -> ContextClause {
     std::mem::take(context_clause)
 }
@@ -151,11 +232,124 @@ fn context_item_message(context_item: &ContextItem, message: impl AsRef<str>) ->
     format!("{} {}", prefix, message.as_ref())
 }
 
+/// The visible header of an architecture or package body whose text is partly encrypted, i.e.,
+/// `architecture arch of ent is` or `package body pkg is`.
+/// The declarations and statements of the unit are unknown.
+fn parse_protected_unit_header(ctx: &mut ParsingContext<'_>) -> ParseResult<AnySecondaryUnit> {
+    if ctx.stream.next_kinds_are(&[Package, Body]) {
+        let start_token = ctx.stream.expect_kind(Package)?;
+        ctx.stream.expect_kind(Body)?;
+        let ident = WithDecl::new(ctx.stream.expect_ident()?);
+        let is_token = ctx.stream.expect_kind(Is)?;
+        Ok(AnySecondaryUnit::PackageBody(PackageBody {
+            span: TokenSpan::new(start_token, is_token),
+            context_clause: ContextClause::default(),
+            ident,
+            decl: Vec::new(),
+            end_token: is_token,
+            end_ident_pos: None,
+            is_protected: true,
+        }))
+    } else {
+        let start_token = ctx.stream.expect_kind(Architecture)?;
+        let ident = WithDecl::new(ctx.stream.expect_ident()?);
+        ctx.stream.expect_kind(Of)?;
+        let entity_name = ctx.stream.expect_ident()?;
+        let is_token = ctx.stream.expect_kind(Is)?;
+        Ok(AnySecondaryUnit::Architecture(ArchitectureBody {
+            span: TokenSpan::new(start_token, is_token),
+            context_clause: ContextClause::default(),
+            ident,
+            entity_name: entity_name.into_ref(),
+            begin_token: is_token,
+            decl: Vec::new(),
+            statements: Vec::new(),
+            end_token: is_token,
+            end_ident_pos: None,
+            is_protected: true,
+        }))
+    }
+}
+
+/// Report an error of the design unit or context item that starts at the token with index `start`.
+/// Parsing continues with the next design unit such that later design units of the file
+/// remain analyzable; the tokens and context clause of a failed design unit are discarded.
+/// A design unit that contains a protected envelope cannot be parsed since part of its text is encrypted.
+/// Such errors are not reported, instead parsing continues with the next design unit after the envelope.
+/// Architectures and package bodies with a visible header are kept without declarations and statements
+/// such that their names are known.
+fn push_unit_error(
+    ctx: &mut ParsingContext<'_>,
+    start: usize,
+    diagnostic: Diagnostic,
+    context_clause: &mut ContextClause,
+    design_units: &mut Vec<(Vec<Token>, AnyDesignUnit)>,
+) {
+    let error_state = ctx.stream.state();
+    let envelope = ctx
+        .stream
+        .protected_envelope_after(start)
+        .filter(|envelope| *envelope <= error_state);
+    ctx.stream.set_state(start);
+    let is_context_item = ctx.stream.next_kind_is(Library) || ctx.stream.next_kind_is(Use);
+    let header = match envelope {
+        Some(envelope) => {
+            let header = parse_protected_unit_header(ctx).ok();
+            ctx.stream.set_state(envelope.max(ctx.stream.state()));
+            header
+        }
+        None => {
+            ctx.diagnostics.push(diagnostic);
+            ctx.stream.set_state(error_state.max(start + 1));
+            None
+        }
+    };
+
+    let mut is_end = false;
+    while let Some(token) = ctx.stream.peek() {
+        if !is_end
+            && matches!(
+                token.kind,
+                Library | Context | Entity | Architecture | Configuration | Package | Vunit
+            )
+        {
+            break;
+        }
+        is_end = token.kind == End;
+        ctx.stream.skip();
+    }
+
+    let end_token = ctx.stream.get_last_token_id();
+    match header {
+        Some(mut unit) => {
+            match unit {
+                AnySecondaryUnit::Architecture(ref mut architecture) => {
+                    architecture.span = architecture.span.end_with(end_token);
+                    architecture.context_clause = take_context_clause(context_clause);
+                }
+                AnySecondaryUnit::PackageBody(ref mut package_body) => {
+                    package_body.span = package_body.span.end_with(end_token);
+                    package_body.context_clause = take_context_clause(context_clause);
+                }
+            }
+            let tokens = ctx.stream.slice_tokens();
+            design_units.push((tokens, AnyDesignUnit::Secondary(unit)));
+        }
+        None if is_context_item => {}
+        None => {
+            // The tokens and context clause of the design unit are discarded
+            let _ = ctx.stream.slice_tokens();
+            context_clause.clear();
+        }
+    }
+}
+
 pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile> {
     let mut context_clause = vec![];
     let mut design_units = vec![];
 
     while let Some(token) = ctx.stream.peek() {
+        let start = ctx.stream.state();
         try_init_token_kind!(
             token,
             Library => {
@@ -163,7 +357,7 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
                     Ok(library) => {
                         context_clause.push(ContextItem::Library(library));
                     },
-                    Err(diagnostic) => ctx.diagnostics.push(diagnostic),
+                    Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
                 }
             },
             Use => {
@@ -171,7 +365,7 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
                     Ok(use_clause) => {
                         context_clause.push(ContextItem::Use(use_clause.item));
                     },
-                    Err(diagnostic) => ctx.diagnostics.push(diagnostic),
+                    Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
                 }
             },
             Context => match parse_context(ctx) {
@@ -194,7 +388,7 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
                 Ok(DeclarationOrReference::Reference(context_ref)) => {
                     context_clause.push(ContextItem::Context(context_ref));
                 }
-                Err(diagnostic) => ctx.diagnostics.push(diagnostic),
+                Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
             },
             Entity => match parse_entity_declaration(ctx) {
                 Ok(mut entity) => {
@@ -202,7 +396,7 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
                     entity.context_clause = take_context_clause(&mut context_clause);
                     design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Entity(entity))));
                 }
-                Err(diagnostic) => ctx.diagnostics.push(diagnostic),
+                Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
             },
 
             Architecture => match parse_architecture_body(ctx) {
@@ -211,7 +405,7 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
                     architecture.context_clause = take_context_clause(&mut context_clause);
                     design_units.push((tokens, AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(architecture))));
                 }
-                Err(diagnostic) => ctx.diagnostics.push(diagnostic),
+                Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
             },
 
             Configuration => match parse_configuration_declaration(ctx) {
@@ -220,7 +414,15 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
                     configuration.context_clause = take_context_clause(&mut context_clause);
                     design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Configuration(configuration))));
                 }
-                Err(diagnostic) => ctx.diagnostics.push(diagnostic),
+                Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
+            },
+            Vunit => match parse_verification_unit(ctx) {
+                Ok(mut unit) => {
+                    let tokens = ctx.stream.slice_tokens();
+                    unit.context_clause = take_context_clause(&mut context_clause);
+                    design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::VerificationUnit(unit))));
+                }
+                Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
             },
             Package => {
                 if ctx.stream.next_kinds_are(&[Package, Body]) {
@@ -230,7 +432,7 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
                             package_body.context_clause = take_context_clause(&mut context_clause);
                             design_units.push((tokens, AnyDesignUnit::Secondary(AnySecondaryUnit::PackageBody(package_body))));
                         }
-                        Err(diagnostic) => ctx.diagnostics.push(diagnostic),
+                        Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
                     };
                 } else if ctx.stream.next_kinds_are(&[Package, Identifier, Is, New]) {
                     match parse_package_instantiation(ctx) {
@@ -239,7 +441,7 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
                             inst.context_clause = take_context_clause(&mut context_clause);
                             design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::PackageInstance(inst))));
                         },
-                        Err(diagnostic) => ctx.diagnostics.push(diagnostic),
+                        Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
                     }
                 } else {
                     match parse_package_declaration(ctx) {
@@ -248,7 +450,7 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
                             package.context_clause = take_context_clause(&mut context_clause);
                             design_units.push((tokens, AnyDesignUnit::Primary(AnyPrimaryUnit::Package(package))));
                         }
-                        Err(diagnostic) => ctx.diagnostics.push(diagnostic),
+                        Err(diagnostic) => push_unit_error(ctx, start, diagnostic, &mut context_clause, &mut design_units),
                     };
                 }
             }
@@ -263,7 +465,10 @@ pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile
         );
     }
 
-    Ok(DesignFile { design_units })
+    Ok(DesignFile {
+        design_units,
+        standard: ctx.standard,
+    })
 }
 
 #[cfg(test)]
@@ -275,6 +480,7 @@ mod tests {
     use crate::data::Diagnostic;
     use crate::syntax::test::{check_diagnostics, check_no_diagnostics, Code};
     use crate::syntax::{HasTokenSpan, TokenAccess};
+    use crate::VHDLStandard;
     use pretty_assertions::assert_eq;
 
     fn parse_str(code: &str) -> (Code, DesignFile, Vec<Diagnostic>) {
@@ -662,9 +868,70 @@ end;
             statements: vec![],
             end_token,
             end_ident_pos,
+            is_protected: false,
         }))
     }
 
+    #[test]
+    fn parse_verification_unit() {
+        let (code, design_file) = parse_ok(
+            "
+library ieee;
+vunit props (ent(rtl)) {
+  signal tmp : bit;
+  default clock is rising_edge(clk);
+  assert always {req; ack} |=> rec.valid'stable;
+}
+",
+        );
+        assert_eq!(
+            design_file.design_units,
+            [(
+                code.tokenize(),
+                AnyDesignUnit::Primary(AnyPrimaryUnit::VerificationUnit(VerificationUnit {
+                    span: code.between("vunit", ";\n}").token_span(),
+                    context_clause: vec![ContextItem::Library(
+                        code.s1("library ieee;").library_clause()
+                    )],
+                    ident: WithDecl::new(code.s1("props").ident()),
+                    binding: Some(VerificationUnitBinding {
+                        entity_name: WithRef::new(code.s1("ent").ident()),
+                        architecture_name: Some(WithRef::new(code.s1("rtl").ident())),
+                    }),
+                    decl: code.s1("signal tmp : bit;").declarative_part(),
+                    names: ["clock", "rising_edge", "clk", "always", "req", "ack", "rec"]
+                        .into_iter()
+                        .map(|name| WithRef::new(code.s1(name).ident()))
+                        .collect(),
+                }))
+            )]
+        );
+    }
+
+    #[test]
+    fn parse_verification_unit_without_binding() {
+        let (code, design_file) = parse_ok(
+            "
+vunit props {
+}
+",
+        );
+        assert_eq!(
+            design_file.design_units,
+            [(
+                code.tokenize(),
+                AnyDesignUnit::Primary(AnyPrimaryUnit::VerificationUnit(VerificationUnit {
+                    span: code.token_span(),
+                    context_clause: ContextClause::default(),
+                    ident: WithDecl::new(code.s1("props").ident()),
+                    binding: None,
+                    decl: Vec::new(),
+                    names: Vec::new(),
+                }))
+            )]
+        );
+    }
+
     #[test]
     fn parse_architecture_body() {
         let (code, design_file) = parse_ok(
@@ -852,7 +1119,8 @@ end entity;
                         end_ident_pos: None,
                         end_token: code.s1("end").token()
                     }))
-                )]
+                )],
+                standard: VHDLStandard::default(),
             }
         );
     }
@@ -890,7 +1158,8 @@ context lib.ctx;
         assert_eq!(
             design_file,
             DesignFile {
-                design_units: vec![]
+                design_units: vec![],
+                standard: VHDLStandard::default(),
             }
         );
     }
@@ -979,4 +1248,104 @@ end entity y;
         assert_eq!(tok.kind, Context);
         assert_eq!(tok.pos, code.s1("context").pos());
     }
+
+    #[test]
+    fn skips_protected_envelopes() {
+        let (_, design_file) = parse_ok(
+            "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity ent is
+end entity;
+
+library ieee;
+use ieee.std_logic_1164.all;
+
+architecture rtl of ent is
+`protect begin_protected
+`protect version = 1
+`protect encrypt_agent = \"vendor\"
+`protect key_block
+g5Xr+Jq4/k1ZtT0==;;'#(
+`protect data_method = \"aes128-cbc\"
+`protect data_block
+eW91IGNhbid0IHJlYWQgdGhpcw==]]
+`protect end_protected
+end architecture;
+
+package pkg is
+end package;
+",
+        );
+        let names = design_file
+            .design_units
+            .iter()
+            .map(|(_, unit)| unit.ident().item.name_utf8())
+            .collect_vec();
+        assert_eq!(names, vec!["ent", "rtl", "pkg"]);
+    }
+
+    #[test]
+    fn missing_end_if_is_closed_by_end_process() {
+        let (code, design_file, diagnostics) = parse_str(
+            "\
+architecture rtl of ent is
+begin
+    process
+    begin
+        if true then
+            null;
+    end process;
+end architecture;
+
+package pkg is
+end package;
+",
+        );
+        check_diagnostics(
+            diagnostics,
+            vec![Diagnostic::syntax_error(
+                code.s1("end process"),
+                "Expected 'end if' before 'end process'",
+            )],
+        );
+        let names = design_file
+            .design_units
+            .iter()
+            .map(|(_, unit)| unit.ident().item.name_utf8())
+            .collect_vec();
+        assert_eq!(names, vec!["rtl", "pkg"]);
+    }
+
+    #[test]
+    fn design_units_after_syntax_errors_are_parsed() {
+        let (code, design_file, diagnostics) = parse_str(
+            "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity is
+end entity;
+
+architecture rtl of ent is
+begin
+end architecture;
+",
+        );
+        check_diagnostics(
+            diagnostics,
+            vec![Diagnostic::syntax_error(
+                code.s1("is"),
+                "Expected '{identifier}'",
+            )],
+        );
+        let [(_, AnyDesignUnit::Secondary(AnySecondaryUnit::Architecture(architecture)))] =
+            design_file.design_units.as_slice()
+        else {
+            panic!("Expected a single architecture");
+        };
+        assert_eq!(architecture.ident.tree.item.name_utf8(), "rtl");
+        assert!(architecture.context_clause.is_empty());
+    }
 }
@@ -1135,6 +1135,7 @@ function foo() return bit;
                         items: vec![],
                         span: code.s1("()").token_span()
                     }),
+                    return_identifier: None,
                     return_type: code.s1("bit").type_mark(),
                     span: code.s1("function foo() return bit").token_span()
                 })
@@ -9,7 +9,9 @@ use crate::ast::DesignFile;
 use crate::data::*;
 use crate::standard::VHDLStandard;
 use crate::syntax::design_unit::parse_design_file;
+use crate::syntax::Kind::SemiColon;
 use crate::{Token, TokenId};
+use fnv::FnvHashMap;
 use std::io;
 use std::sync::Arc;
 use vhdl_lang::TokenAccess;
@@ -17,6 +19,8 @@ use vhdl_lang::TokenAccess;
 pub struct VHDLParser {
     pub symbols: Arc<Symbols>,
     pub standard: VHDLStandard,
+    /// The values of user-defined conditional analysis identifiers, keyed by their upper case name
+    pub conditional_identifiers: FnvHashMap<String, String>,
 }
 
 pub(crate) struct ParsingContext<'a> {
@@ -46,9 +50,20 @@ impl VHDLParser {
         VHDLParser {
             symbols: Arc::new(Symbols::from_standard(vhdl_standard)),
             standard: vhdl_standard,
+            conditional_identifiers: FnvHashMap::default(),
         }
     }
 
+    /// Evaluate conditional analysis directives with the values of `identifiers`
+    /// in addition to the standard identifiers such as `TOOL_TYPE`
+    pub fn with_conditional_identifiers(
+        mut self,
+        identifiers: FnvHashMap<String, String>,
+    ) -> VHDLParser {
+        self.conditional_identifiers = identifiers;
+        self
+    }
+
     pub fn symbol(&self, name: &Latin1String) -> Symbol {
         self.symbols.symtab().insert(name)
     }
@@ -57,15 +72,28 @@ impl VHDLParser {
         &self,
         source: &Source,
         diagnostics: &mut dyn DiagnosticHandler,
+    ) -> DesignFile {
+        self.parse_design_source_with_standard(source, self.standard, diagnostics)
+    }
+
+    /// Parse a source with another standard than the one of the parser,
+    /// such as the standard of the library that the source belongs to
+    pub fn parse_design_source_with_standard(
+        &self,
+        source: &Source,
+        standard: VHDLStandard,
+        diagnostics: &mut dyn DiagnosticHandler,
     ) -> DesignFile {
         let contents = source.contents();
-        let tokenizer = Tokenizer::new(&self.symbols, source, ContentReader::new(&contents));
+        let tokenizer = Tokenizer::new(&self.symbols, source, ContentReader::new(&contents))
+            .with_standard(standard)
+            .with_conditional_identifiers(&self.conditional_identifiers);
         let stream = TokenStream::new(tokenizer, diagnostics);
 
         let mut ctx = ParsingContext {
             stream: &stream,
             diagnostics,
-            standard: self.standard,
+            standard,
         };
 
         match parse_design_file(&mut ctx) {
@@ -77,13 +105,75 @@ impl VHDLParser {
         }
     }
 
+    /// Parse the text of a source from `start` up to `end`, or up to the end of the source,
+    /// as a sequence of complete design units.
+    /// This is used to parse only the changed part of a source again.
+    /// Returns `None` when the text cannot be parsed on its own, that is when a token or comment
+    /// crosses `end`, when the last design unit does not end before `end`,
+    /// or when the source contains tool directives that may affect the text outside of the range.
+    pub fn parse_design_source_range(
+        &self,
+        source: &Source,
+        standard: VHDLStandard,
+        start: Position,
+        end: Option<Position>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<DesignFile> {
+        let contents = source.contents();
+        if (0..contents.num_lines())
+            .filter_map(|lineno| contents.get_line(lineno))
+            .any(|line| line.contains('`'))
+        {
+            return None;
+        }
+        let mut reader = ContentReader::new(&contents);
+        reader.seek_pos(start);
+        let tokenizer = Tokenizer::new(&self.symbols, source, reader).with_standard(standard);
+        let mut range_diagnostics = Vec::new();
+        let stream = TokenStream::new_until(tokenizer, end, &mut range_diagnostics)?;
+
+        let mut ctx = ParsingContext {
+            stream: &stream,
+            diagnostics: &mut range_diagnostics,
+            standard,
+        };
+        let design_file = parse_design_file(&mut ctx).ok()?;
+
+        // Parsing the complete source could only continue after `end`
+        // when the last token does not end the last design unit
+        if let Some(last_token) = stream.last_token() {
+            let last_unit_pos = design_file
+                .design_units
+                .last()
+                .and_then(|(tokens, _)| tokens.last())
+                .map(|token| &token.pos);
+            if last_token.kind != SemiColon || last_unit_pos != Some(&last_token.pos) {
+                return None;
+            }
+        }
+
+        for diagnostic in range_diagnostics {
+            diagnostics.push(diagnostic);
+        }
+        Some(design_file)
+    }
+
     pub fn parse_design_file(
         &self,
         file_name: &Path,
         diagnostics: &mut dyn DiagnosticHandler,
+    ) -> ParserResult {
+        self.parse_design_file_with_standard(file_name, self.standard, diagnostics)
+    }
+
+    pub fn parse_design_file_with_standard(
+        &self,
+        file_name: &Path,
+        standard: VHDLStandard,
+        diagnostics: &mut dyn DiagnosticHandler,
     ) -> ParserResult {
         let source = Source::from_latin1_file(file_name)?;
-        let design_file = self.parse_design_source(&source, diagnostics);
+        let design_file = self.parse_design_source_with_standard(&source, standard, diagnostics);
         Ok((source, design_file))
     }
 }
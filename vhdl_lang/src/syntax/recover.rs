@@ -4,10 +4,11 @@
 //
 // Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
 /// Module for robust parsing
+use crate::syntax::common::ParseResult;
 use crate::syntax::parser::ParsingContext;
-use crate::syntax::Kind::{Colon, SemiColon};
-use crate::syntax::{kinds_error, kinds_str};
-use crate::TokenId;
+use crate::syntax::Kind::{self, *};
+use crate::syntax::{kind_str, kinds_error, kinds_str};
+use crate::{Diagnostic, TokenId};
 
 /// Special handling when expecting a semicolon.
 /// When the next token is
@@ -43,6 +44,54 @@ pub fn expect_semicolon(ctx: &mut ParsingContext<'_>) -> Option<TokenId> {
     }
 }
 
+/// Keywords that follow `end` at the end of a construct
+const END_KINDS: &[Kind] = &[
+    If,
+    Case,
+    Loop,
+    Process,
+    Block,
+    Generate,
+    Function,
+    Procedure,
+    Architecture,
+    Entity,
+    Package,
+    Component,
+    Record,
+    Units,
+    Protected,
+    Configuration,
+    Context,
+    Vunit,
+];
+
+/// Special handling when expecting `kind` after the `end` of a construct that ends with `end kind`.
+/// When the `end` closes another construct instead, i.e., `end process` where `end if` is expected,
+/// the end of the construct is assumed to be missing.
+/// An error is reported and the `end` is not consumed such that it closes the enclosing construct.
+/// Returns whether the construct is closed by `end kind`.
+pub fn expect_end_kind(ctx: &mut ParsingContext<'_>, kind: Kind) -> ParseResult<bool> {
+    let token = ctx.stream.peek_expect()?;
+    if token.kind != kind && END_KINDS.contains(&token.kind) {
+        let other = token.kind;
+        let other_pos = token.pos.clone();
+        ctx.stream.back();
+        let end_pos = ctx.stream.peek_expect()?.pos.clone();
+        ctx.diagnostics.push(Diagnostic::syntax_error(
+            end_pos.combine(&other_pos),
+            format!(
+                "Expected 'end {}' before 'end {}'",
+                kind_str(kind),
+                kind_str(other)
+            ),
+        ));
+        return Ok(false);
+    }
+    ctx.stream.expect_kind(kind)?;
+    Ok(true)
+}
+
 /// Expect the next token to be a SemiColon, or return the last token.
 /// The behavior is the same as [expect_semicolon].
 #[must_use]
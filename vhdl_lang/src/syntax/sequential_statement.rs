@@ -11,13 +11,13 @@ use super::expression::{parse_choices, parse_expression};
 use super::names::parse_name;
 use super::range::parse_discrete_range;
 use super::tokens::Kind::*;
-use super::waveform::{parse_delay_mechanism, parse_waveform};
+use super::waveform::{parse_delay_mechanism, parse_sequential_waveform, parse_waveform};
 use crate::ast::token_range::WithTokenSpan;
 use crate::ast::*;
 use crate::data::*;
 use crate::syntax::common::check_label_identifier_mismatch;
 use crate::syntax::kinds_error;
-use crate::syntax::recover::{expect_semicolon, expect_semicolon_or_last};
+use crate::syntax::recover::{expect_end_kind, expect_semicolon, expect_semicolon_or_last};
 use crate::syntax::separated_list::parse_name_list;
 use crate::HasTokenSpan;
 use vhdl_lang::syntax::parser::ParsingContext;
@@ -99,7 +99,7 @@ fn parse_if_statement(
     ctx.stream.expect_kind(If)?;
     let mut conditionals = Vec::new();
     let mut else_branch = None;
-    loop {
+    let is_closed = loop {
         let condition = parse_expression(ctx)?;
         ctx.stream.expect_kind(Then)?;
         let statements = parse_labeled_sequential_statements(ctx)?;
@@ -126,23 +126,26 @@ fn parse_if_statement(
                     ctx.stream,
                     end_token,
                     End => {
-                        ctx.stream.expect_kind(If)?;
+                        let is_closed = expect_end_kind(ctx, If)?;
                         else_branch = Some((statements, token_id));
-                        break;
+                        break is_closed;
                     }
                 );
             },
             End => {
-                ctx.stream.expect_kind(If)?;
+                let is_closed = expect_end_kind(ctx, If)?;
                 conditionals.push(conditional);
-                break;
+                break is_closed;
             }
         );
-    }
+    };
 
-    let end_label_pos =
-        check_label_identifier_mismatch(ctx, label, ctx.stream.pop_optional_ident());
-    expect_semicolon(ctx);
+    let mut end_label_pos = None;
+    if is_closed {
+        end_label_pos =
+            check_label_identifier_mismatch(ctx, label, ctx.stream.pop_optional_ident());
+        expect_semicolon(ctx);
+    }
     Ok(IfStatement {
         conds: Conditionals {
             conditionals,
@@ -185,13 +188,15 @@ fn parse_case_statement(
                 continue;
             },
             End => {
-                ctx.stream.expect_kind(Case)?;
-                if is_matching {
-                    ctx.stream.expect_kind(Que)?;
+                let mut end_label_pos = None;
+                if expect_end_kind(ctx, Case)? {
+                    if is_matching {
+                        ctx.stream.expect_kind(Que)?;
+                    }
+                    end_label_pos = check_label_identifier_mismatch(ctx, label, ctx.stream.pop_optional_ident());
+                    expect_semicolon(ctx);
                 }
-                let end_label_pos = check_label_identifier_mismatch(ctx, label, ctx.stream.pop_optional_ident());
                 alternatives.push(alternative);
-                expect_semicolon(ctx);
                 return Ok(CaseStatement {
                     is_matching,
                     expression,
@@ -235,9 +240,11 @@ fn parse_loop_statement(
         end_token,
         end_token_id,
         End => {
-            ctx.stream.expect_kind(Loop)?;
-            let end_label_pos = check_label_identifier_mismatch(ctx, label, ctx.stream.pop_optional_ident());
-            expect_semicolon(ctx);
+            let mut end_label_pos = None;
+            if expect_end_kind(ctx, Loop)? {
+                end_label_pos = check_label_identifier_mismatch(ctx, label, ctx.stream.pop_optional_ident());
+                expect_semicolon(ctx);
+            }
             Ok(LoopStatement {
                 iteration_scheme,
                 loop_token,
@@ -484,7 +491,7 @@ fn parse_assignment_or_procedure_call(
                     SequentialStatement::SignalAssignment(SignalAssignment {
                         target,
                         delay_mechanism,
-                        rhs: parse_signal_assignment_right_hand(ctx)?
+                        rhs: parse_assignment_right_hand(ctx, parse_sequential_waveform)?
                     })
                 }
             }
@@ -542,7 +549,7 @@ fn parse_selected_assignment(ctx: &mut ParsingContext<'_>) -> ParseResult<Sequen
                 Ok(SequentialStatement::SignalAssignment(SignalAssignment {
                     target,
                     delay_mechanism: parse_delay_mechanism(ctx)?,
-                    rhs: AssignmentRightHand::Selected(parse_selection(ctx, expression, parse_waveform)?)
+                    rhs: AssignmentRightHand::Selected(parse_selection(ctx, expression, parse_sequential_waveform)?)
                 }))
             }
         }
@@ -18,6 +18,7 @@ use crate::syntax::interface_declaration::parse_generic_interface_list;
 use crate::syntax::names::parse_name;
 use crate::syntax::recover::expect_semicolon_or_last;
 use vhdl_lang::syntax::parser::ParsingContext;
+use vhdl_lang::VHDLStandard::VHDL2019;
 
 pub fn parse_signature(ctx: &mut ParsingContext<'_>) -> ParseResult<WithTokenSpan<Signature>> {
     let left_square = ctx.stream.expect_kind(LeftSquare)?;
@@ -180,6 +181,14 @@ pub fn parse_subprogram_specification(
 
     if is_function {
         ctx.stream.expect_kind(Return)?;
+        let return_identifier =
+            if ctx.standard >= VHDL2019 && ctx.stream.next_kinds_are(&[Identifier, Of]) {
+                let ident = ctx.stream.expect_ident()?;
+                ctx.stream.expect_kind(Of)?;
+                Some(ident.into())
+            } else {
+                None
+            };
         let return_type = parse_type_mark(ctx)?;
         let end_token = ctx.stream.get_last_token_id();
         Ok(SubprogramSpecification::Function(FunctionSpecification {
@@ -187,6 +196,7 @@ pub fn parse_subprogram_specification(
             designator: designator.into(),
             header,
             parameter_list,
+            return_identifier,
             return_type,
             span: TokenSpan::new(start_token, end_token),
         }))
@@ -344,6 +354,7 @@ function foo return lib.foo.natural;
                         .into(),
                     header: None,
                     parameter_list: None,
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark(),
                     span: code.between("function", ".natural").token_span(),
                 })
@@ -371,6 +382,7 @@ function \"+\" return lib.foo.natural;
                     .into(),
                     header: None,
                     parameter_list: None,
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark(),
                     span: code.between("function", ".natural").token_span(),
                 })
@@ -398,6 +410,7 @@ impure function foo return lib.foo.natural;
                         .into(),
                     header: None,
                     parameter_list: None,
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark(),
                     span: code.between("impure", ".natural").token_span(),
                 })
@@ -424,6 +437,7 @@ pure function foo return lib.foo.natural;
                         .into(),
                     header: None,
                     parameter_list: None,
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark(),
                     span: code.between("pure", ".natural").token_span(),
                 })
@@ -484,6 +498,7 @@ function foo(foo : natural) return lib.foo.natural;
                         items: vec![code.s1("foo : natural").parameter()],
                         span: code.between("(", ")").token_span()
                     }),
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark(),
                     span: code.between("function", ".natural").token_span(),
                 })
@@ -515,6 +530,7 @@ function foo parameter (foo : natural) return lib.foo.natural;
                         items: vec![code.s1("foo : natural").parameter()],
                         span: code.between("parameter (", ")").token_span()
                     }),
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark(),
                     span: code.between("function", ".natural").token_span(),
                 })
@@ -553,6 +569,7 @@ function foo generic (abc_def: natural) parameter (foo : natural) return lib.foo
                         items: vec![code.s1("foo : natural").parameter()],
                         span: code.s1("parameter (foo : natural)").token_span()
                     }),
+                    return_identifier: None,
                     return_type: code.s1("lib.foo.natural").type_mark(),
                     span: code.between("function", ".natural").token_span(),
                 })
@@ -801,6 +818,35 @@ end function \"+\";
         )
     }
 
+    #[test]
+    pub fn parses_function_specification_with_return_identifier() {
+        let code = Code::with_standard(
+            "\
+function foo return ret of natural;
+",
+            VHDL2019,
+        );
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_subprogram_declaration),
+            SubprogramDeclaration {
+                span: code.token_span(),
+                specification: SubprogramSpecification::Function(FunctionSpecification {
+                    pure: true,
+                    designator: code
+                        .s1("foo")
+                        .ident()
+                        .map_into(SubprogramDesignator::Identifier)
+                        .into(),
+                    header: None,
+                    parameter_list: None,
+                    return_identifier: Some(code.s1("ret of").s1("ret").decl_ident()),
+                    return_type: code.s1("natural").type_mark(),
+                    span: code.between("function", "natural").token_span(),
+                })
+            }
+        );
+    }
+
     #[test]
     pub fn parse_function_spec_with_header_no_aspect() {
         let code = Code::new(
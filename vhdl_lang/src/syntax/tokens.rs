@@ -6,6 +6,7 @@
 
 #[macro_use]
 mod tokenizer;
+mod conditional_analysis;
 /// Contains constant keywords for different versions of VHDL.
 mod keywords;
 mod tokenstream;
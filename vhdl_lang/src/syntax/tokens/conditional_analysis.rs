@@ -0,0 +1,331 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Conditional analysis directives of VHDL-2019 (LRM 24.2)
+
+use super::tokenizer::Kind::*;
+use super::tokenizer::*;
+use crate::data::*;
+use crate::standard::VHDLStandard;
+use fnv::FnvHashMap;
+
+/// The value of a standard conditional analysis identifier.
+/// The tool type is `SIMULATION` such that models and testbenches are analyzed
+/// the way they are simulated.
+fn identifier_value(name: &str, standard: VHDLStandard) -> Option<String> {
+    let value = match name.to_ascii_uppercase().as_str() {
+        "VHDL_VERSION" => standard.as_ref(),
+        "TOOL_TYPE" => "SIMULATION",
+        "TOOL_VENDOR" => "VHDL-LS",
+        "TOOL_NAME" => "vhdl_lang",
+        "TOOL_EDITION" => "",
+        "TOOL_VERSION" => env!("CARGO_PKG_VERSION"),
+        _ => return None,
+    };
+    Some(value.to_owned())
+}
+
+fn identifier_name(token: &Token) -> Option<String> {
+    match &token.value {
+        Value::Identifier(symbol) if token.kind == Identifier => Some(symbol.name_utf8()),
+        _ => None,
+    }
+}
+
+/// Whether `token`, which follows a grave accent, starts a conditional analysis directive
+pub(super) fn is_conditional_directive(token: &Token) -> bool {
+    match token.kind {
+        If | Elsif | Else | End => true,
+        _ => identifier_name(token).is_some_and(|name| {
+            name.eq_ignore_ascii_case("warning") || name.eq_ignore_ascii_case("error")
+        }),
+    }
+}
+
+/// A `` `if `` directive and its alternatives
+struct Branch {
+    /// The position of the `` `if `` directive
+    pos: SrcPos,
+    /// Whether the text of the current alternative is analyzed
+    is_active: bool,
+    /// Whether the text of one of the alternatives is analyzed
+    is_taken: bool,
+    has_else: bool,
+}
+
+/// The state of nested conditional analysis directives while tokenizing a design file
+#[derive(Default)]
+pub(super) struct ConditionalAnalysis {
+    branches: Vec<Branch>,
+}
+
+impl ConditionalAnalysis {
+    /// Whether the text at the current position is analyzed
+    pub fn is_active(&self) -> bool {
+        self.branches.iter().all(|branch| branch.is_active)
+    }
+
+    /// Handle the conditional analysis directive that starts with `directive`,
+    /// the token following the grave accent.
+    /// Returns the token after an `` `end `` directive that is not part of the directive.
+    pub fn directive(
+        &mut self,
+        grave_accent: &Token,
+        directive: Token,
+        tokenizer: &mut Tokenizer<'_>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<Token> {
+        let pos = grave_accent.pos.combine(&directive.pos);
+        match directive.kind {
+            If => {
+                let is_active = self.is_active();
+                let condition = self.condition(&pos, tokenizer, is_active, diagnostics);
+                self.branches.push(Branch {
+                    pos,
+                    is_active: condition,
+                    is_taken: condition,
+                    has_else: false,
+                });
+            }
+            Elsif => {
+                let is_active = self
+                    .branches
+                    .iter()
+                    .rev()
+                    .skip(1)
+                    .all(|branch| branch.is_active);
+                let Some(branch) = self.branches.last() else {
+                    diagnostics.push(Diagnostic::syntax_error(&pos, "`elsif without `if"));
+                    let _ = self.condition(&pos, tokenizer, false, diagnostics);
+                    return None;
+                };
+                if branch.has_else {
+                    diagnostics.push(Diagnostic::syntax_error(&pos, "`elsif after `else"));
+                }
+                let is_taken = branch.is_taken;
+                let condition =
+                    self.condition(&pos, tokenizer, is_active && !is_taken, diagnostics);
+                let branch = self.branches.last_mut().unwrap();
+                branch.is_active = !is_taken && condition;
+                branch.is_taken |= condition;
+            }
+            Else => {
+                let Some(branch) = self.branches.last_mut() else {
+                    diagnostics.push(Diagnostic::syntax_error(&pos, "`else without `if"));
+                    return None;
+                };
+                if branch.has_else {
+                    diagnostics.push(Diagnostic::syntax_error(&pos, "Duplicate `else"));
+                }
+                branch.is_active = !branch.is_taken;
+                branch.is_taken = true;
+                branch.has_else = true;
+            }
+            End => {
+                if self.branches.pop().is_none() {
+                    diagnostics.push(Diagnostic::syntax_error(&pos, "`end without `if"));
+                }
+                // The `if` after `end is optional
+                return match tokenizer.pop() {
+                    Ok(Some(token)) if token.kind == If => None,
+                    Ok(token) => token,
+                    Err(err) => {
+                        diagnostics.push(err);
+                        None
+                    }
+                };
+            }
+            _ => {
+                let code = if identifier_name(&directive)
+                    .is_some_and(|name| name.eq_ignore_ascii_case("warning"))
+                {
+                    ErrorCode::WarningDirective
+                } else {
+                    ErrorCode::ErrorDirective
+                };
+                match tokenizer.pop() {
+                    Ok(Some(token)) if token.kind == StringLiteral => {
+                        if self.is_active() {
+                            if let Value::String(message) = &token.value {
+                                diagnostics.push(Diagnostic::new(
+                                    pos.combine(&token.pos),
+                                    message.to_string(),
+                                    code,
+                                ));
+                            }
+                        }
+                    }
+                    Ok(token) => {
+                        if self.is_active() {
+                            diagnostics.push(Diagnostic::syntax_error(
+                                token.map(|token| token.pos).unwrap_or(pos),
+                                "Expected string literal",
+                            ));
+                        }
+                    }
+                    Err(err) => diagnostics.push(err),
+                }
+            }
+        }
+        None
+    }
+
+    /// Report `` `if `` directives without `` `end `` at the end of the file
+    pub fn finish(self, diagnostics: &mut dyn DiagnosticHandler) {
+        for branch in self.branches {
+            diagnostics.push(Diagnostic::syntax_error(branch.pos, "`if without `end"));
+        }
+    }
+
+    /// Read and evaluate the condition of an `` `if `` or `` `elsif `` directive up to `then`.
+    /// Errors are only reported if `is_active`, otherwise the condition is false.
+    fn condition(
+        &self,
+        pos: &SrcPos,
+        tokenizer: &mut Tokenizer<'_>,
+        is_active: bool,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> bool {
+        let mut tokens = Vec::new();
+        let then_pos = loop {
+            match tokenizer.pop() {
+                Ok(Some(token)) if token.kind == Then => break token.pos,
+                Ok(Some(token)) => tokens.push(token),
+                Ok(None) => {
+                    if is_active {
+                        diagnostics.push(Diagnostic::syntax_error(pos, "Expected 'then'"));
+                    }
+                    return false;
+                }
+                Err(err) => {
+                    if is_active {
+                        diagnostics.push(err);
+                    }
+                }
+            }
+        };
+        if !is_active {
+            return false;
+        }
+        let mut condition = Condition {
+            tokens: &tokens,
+            idx: 0,
+            standard: tokenizer.standard(),
+            identifiers: tokenizer.conditional_identifiers(),
+            end_pos: &then_pos,
+        };
+        let result = condition
+            .expression()
+            .and_then(|value| match tokens.get(condition.idx) {
+                Some(token) => Err(Diagnostic::syntax_error(token, "Expected 'then'")),
+                None => Ok(value),
+            });
+        match result {
+            Ok(value) => value,
+            Err(err) => {
+                diagnostics.push(err);
+                false
+            }
+        }
+    }
+}
+
+/// A conditional analysis expression (LRM 24.2.2)
+struct Condition<'t> {
+    tokens: &'t [Token],
+    idx: usize,
+    standard: VHDLStandard,
+    /// User-defined identifiers, which take precedence over the standard identifiers
+    identifiers: Option<&'t FnvHashMap<String, String>>,
+    /// The position of `then`, used when tokens are missing
+    end_pos: &'t SrcPos,
+}
+
+impl<'t> Condition<'t> {
+    fn pop(&mut self) -> Option<&'t Token> {
+        let token = self.tokens.get(self.idx)?;
+        self.idx += 1;
+        Some(token)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<&'t Token, Diagnostic> {
+        self.pop()
+            .ok_or_else(|| Diagnostic::syntax_error(self.end_pos, format!("Expected {expected}")))
+    }
+
+    fn expression(&mut self) -> Result<bool, Diagnostic> {
+        let mut value = self.relation()?;
+        while let Some(token) = self.tokens.get(self.idx) {
+            let kind = token.kind;
+            if !matches!(kind, And | Or | Xor | Xnor | Nand | Nor) {
+                break;
+            }
+            self.idx += 1;
+            let rhs = self.relation()?;
+            value = match kind {
+                And => value && rhs,
+                Or => value || rhs,
+                Xor => value != rhs,
+                Xnor => value == rhs,
+                Nand => !(value && rhs),
+                _ => !(value || rhs),
+            };
+        }
+        Ok(value)
+    }
+
+    fn relation(&mut self) -> Result<bool, Diagnostic> {
+        let token = self.expect("conditional analysis identifier")?;
+        match token.kind {
+            Not => Ok(!self.relation()?),
+            LeftPar => {
+                let value = self.expression()?;
+                let token = self.expect("')'")?;
+                if token.kind != RightPar {
+                    return Err(Diagnostic::syntax_error(token, "Expected ')'"));
+                }
+                Ok(value)
+            }
+            Identifier => {
+                let name = identifier_name(token).unwrap_or_default();
+                let user_value = self
+                    .identifiers
+                    .and_then(|identifiers| identifiers.get(&name.to_ascii_uppercase()))
+                    .cloned();
+                let Some(value) = user_value.or_else(|| identifier_value(&name, self.standard))
+                else {
+                    return Err(Diagnostic::syntax_error(
+                        token,
+                        format!("Unknown conditional analysis identifier '{name}'"),
+                    ));
+                };
+                let operator = self.expect("relational operator")?;
+                let literal = self.expect("string literal")?;
+                let Value::String(literal) = &literal.value else {
+                    return Err(Diagnostic::syntax_error(literal, "Expected string literal"));
+                };
+                let (value, literal) = (value.as_str(), literal.to_string());
+                let literal = literal.as_str();
+                match operator.kind {
+                    EQ => Ok(value == literal),
+                    NE => Ok(value != literal),
+                    LT => Ok(value < literal),
+                    LTE => Ok(value <= literal),
+                    GT => Ok(value > literal),
+                    GTE => Ok(value >= literal),
+                    _ => Err(Diagnostic::syntax_error(
+                        operator,
+                        "Expected relational operator",
+                    )),
+                }
+            }
+            _ => Err(Diagnostic::syntax_error(
+                token,
+                "Expected conditional analysis identifier",
+            )),
+        }
+    }
+}
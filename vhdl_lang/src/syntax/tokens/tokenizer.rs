@@ -205,6 +205,10 @@ pub enum Kind {
     LeftSquare,
     #[strum(serialize = "]")]
     RightSquare,
+    #[strum(serialize = "{")]
+    LeftBrace,
+    #[strum(serialize = "}")]
+    RightBrace,
     #[strum(serialize = ";")]
     SemiColon,
     #[strum(serialize = ":")]
@@ -434,7 +438,7 @@ pub struct Token {
 /// A TokenId represents a unique value that is used to access a token.
 /// A token ID cannot be created directly by the user. Instead, the value must be taken
 /// from the AST.
-#[derive(PartialEq, Eq, Debug, Clone, Copy, Ord, PartialOrd)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Ord, PartialOrd, Hash)]
 pub struct TokenId(usize);
 
 /// The TokenId represents an index into an array of tokens.
@@ -446,6 +450,10 @@ impl TokenId {
         TokenId(idx)
     }
 
+    pub(crate) fn idx(&self) -> usize {
+        self.0
+    }
+
     pub fn pos<'a>(&'a self, ctx: &'a dyn TokenAccess) -> &SrcPos {
         ctx.get_pos(*self)
     }
@@ -544,12 +552,17 @@ pub trait HasTokenSpan {
     fn span(&self) -> TokenSpan {
         TokenSpan::new(self.get_start_token(), self.get_end_token())
     }
+
+    /// The comments that document this element, see [TokenSpan::documentation]
+    fn documentation<'a>(&self, ctx: &'a dyn TokenAccess) -> Vec<&'a Comment> {
+        self.span().documentation(ctx)
+    }
 }
 
 /// Holds token information about an AST element.
 /// Since the different pieces may be gathered in different locations,
 /// the fields are gated behind accessor functions which also check some invariants every time they are called.
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
 pub struct TokenSpan {
     pub start_token: TokenId,
     pub end_token: TokenId,
@@ -606,6 +619,46 @@ impl TokenSpan {
         ctx.get_span(self.start_token, self.end_token)
     }
 
+    /// The comments that document the tokens of the span.
+    /// These are the block of comments that ends on the line before the first token
+    /// and the comment after the last token, or after a separator that follows the last token,
+    /// on the same line.
+    pub fn documentation<'a>(&self, ctx: &'a dyn TokenAccess) -> Vec<&'a Comment> {
+        let mut documentation = Vec::new();
+        let Some(start_token) = ctx.get_token(self.start_token) else {
+            return documentation;
+        };
+        if let Some(comments) = &start_token.comments {
+            let mut line = start_token.pos.start().line;
+            let num_documenting = comments
+                .leading
+                .iter()
+                .rev()
+                .take_while(|comment| {
+                    let is_documenting = comment.range.end.line + 1 >= line;
+                    line = comment.range.start.line;
+                    is_documenting
+                })
+                .count();
+            documentation.extend(&comments.leading[comments.leading.len() - num_documenting..]);
+        }
+
+        let trailing_comment = |id: TokenId| {
+            ctx.get_token(id)
+                .and_then(|token| token.comments.as_ref())
+                .and_then(|comments| comments.trailing.as_ref())
+        };
+        let separator = ctx
+            .get_token(self.end_token + 1)
+            .filter(|token| matches!(token.kind, SemiColon | Comma));
+        if let Some(comment) = trailing_comment(self.end_token) {
+            documentation.push(comment);
+        } else if let Some(comment) = separator.and_then(|_| trailing_comment(self.end_token + 1)) {
+            documentation.push(comment);
+        }
+        documentation
+    }
+
     pub(crate) fn combine(&self, other: impl Into<TokenSpan>) -> TokenSpan {
         let other = other.into();
         debug_assert!(self.start_token <= other.end_token);
@@ -758,6 +811,16 @@ pub struct Comment {
     pub multi_line: bool,
 }
 
+impl Display for Comment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.multi_line {
+            write!(f, "/*{}*/", self.value)
+        } else {
+            write!(f, "--{}", self.value.trim_end())
+        }
+    }
+}
+
 use crate::standard::VHDLStandard;
 use std::convert::AsRef;
 use std::fmt::{Debug, Display, Formatter};
@@ -1474,6 +1537,7 @@ fn parse_basic_identifier_or_keyword(
     buffer: &mut Latin1String,
     reader: &mut ContentReader<'_>,
     symbols: &Symbols,
+    standard: VHDLStandard,
 ) -> Result<(Kind, Value), TokenError> {
     buffer.bytes.clear();
     while let Some(b) = reader.peek()? {
@@ -1488,7 +1552,7 @@ fn parse_basic_identifier_or_keyword(
         }
     }
 
-    Ok(symbols.insert_or_keyword(buffer))
+    Ok(symbols.insert_or_keyword(buffer, standard))
 }
 
 /// Assumes leading ' has already been consumed
@@ -1620,8 +1684,11 @@ fn get_trailing_comment(reader: &mut ContentReader<'_>) -> Result<Option<Comment
 /// Static tokenizer data
 pub struct Symbols {
     symtab: SymbolTable,
-    keywords: Vec<Kind>,
-    attributes: FnvHashMap<Symbol, AttributeDesignator>,
+    /// The keywords of all standards, which are the first symbols of the table,
+    /// together with the standards that they are reserved in
+    keywords: Vec<(Kind, Vec<VHDLStandard>)>,
+    attributes: FnvHashMap<VHDLStandard, FnvHashMap<Symbol, AttributeDesignator>>,
+    standard: VHDLStandard,
 }
 
 impl Symbols {
@@ -1629,39 +1696,57 @@ impl Symbols {
         &self.symtab
     }
 
-    fn insert_or_keyword(&self, name: &Latin1String) -> (Kind, Value) {
+    /// The standard that determines the keywords and attributes unless the tokenizer
+    /// is created with another standard
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard
+    }
+
+    fn insert_or_keyword(&self, name: &Latin1String, standard: VHDLStandard) -> (Kind, Value) {
         let symbol = self.symtab.insert(name);
-        if let Some(kind) = self.keywords.get(symbol.id) {
-            (*kind, Value::None)
-        } else {
-            (Identifier, Value::Identifier(symbol))
+        match self.keywords.get(symbol.id) {
+            Some((kind, standards)) if standards.contains(&standard) => (*kind, Value::None),
+            _ => (Identifier, Value::Identifier(symbol)),
         }
     }
 
+    /// Create the symbols of the keywords and attributes of all standards,
+    /// where `version` is the default standard of tokenizers
     pub fn from_standard(version: VHDLStandard) -> Symbols {
         let symtab = SymbolTable::default();
-        let kw = version.keywords();
-        let mut keywords = Vec::with_capacity(kw.len());
+        let mut keywords: Vec<(Kind, Vec<VHDLStandard>)> = Vec::new();
 
         let mut latin1 = Latin1String::empty();
-        for kind in kw {
-            latin1.bytes.clear();
-            latin1.bytes.extend_from_slice(kind.as_str().as_bytes());
-            let symbol = symtab.insert(&latin1);
-            assert_eq!(symbol.id, keywords.len());
-            keywords.push(*kind);
+        for standard in VHDLStandard::all() {
+            for kind in standard.keywords() {
+                latin1.bytes.clear();
+                latin1.bytes.extend_from_slice(kind.as_str().as_bytes());
+                let symbol = symtab.insert(&latin1);
+                if let Some((_, standards)) = keywords.get_mut(symbol.id) {
+                    standards.push(standard);
+                } else {
+                    assert_eq!(symbol.id, keywords.len());
+                    keywords.push((*kind, vec![standard]));
+                }
+            }
         }
 
-        let attributes = version
-            .builtin_attributes()
-            .iter()
-            .map(|attr| (symtab.insert_utf8(format!("{attr}").as_str()), attr.clone()))
+        let attributes = VHDLStandard::all()
+            .map(|standard| {
+                let attributes = standard
+                    .builtin_attributes()
+                    .iter()
+                    .map(|attr| (symtab.insert_utf8(format!("{attr}").as_str()), attr.clone()))
+                    .collect();
+                (standard, attributes)
+            })
             .collect();
 
         Symbols {
             symtab,
             keywords,
             attributes,
+            standard: version,
         }
     }
 }
@@ -1674,11 +1759,14 @@ impl std::default::Default for Symbols {
 
 pub struct Tokenizer<'a> {
     symbols: &'a Symbols,
+    standard: VHDLStandard,
     buffer: Latin1String,
     state: TokenState,
     pub source: &'a Source,
     reader: ContentReader<'a>,
     final_comments: Option<Vec<Comment>>,
+    /// The values of user-defined conditional analysis identifiers
+    conditional_identifiers: Option<&'a FnvHashMap<String, String>>,
 }
 
 impl<'a> Tokenizer<'a> {
@@ -1689,17 +1777,43 @@ impl<'a> Tokenizer<'a> {
     ) -> Tokenizer<'a> {
         Tokenizer {
             symbols,
+            standard: symbols.standard(),
             state: TokenState::new(reader.state()),
             buffer: Latin1String::empty(),
             source,
             reader,
             final_comments: None,
+            conditional_identifiers: None,
         }
     }
 
+    /// Tokenize with the keywords and attributes of `standard`
+    /// rather than those of the default standard of the symbols
+    pub fn with_standard(mut self, standard: VHDLStandard) -> Tokenizer<'a> {
+        self.standard = standard;
+        self
+    }
+
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard
+    }
+
+    /// Evaluate conditional analysis directives with the values of `identifiers`,
+    /// that are keyed by their upper case name, in addition to the standard identifiers
+    pub fn with_conditional_identifiers(
+        mut self,
+        identifiers: &'a FnvHashMap<String, String>,
+    ) -> Tokenizer<'a> {
+        self.conditional_identifiers = Some(identifiers);
+        self
+    }
+
+    pub fn conditional_identifiers(&self) -> Option<&'a FnvHashMap<String, String>> {
+        self.conditional_identifiers
+    }
+
     pub fn attribute(&self, sym: Symbol) -> AttributeDesignator {
-        self.symbols
-            .attributes
+        self.symbols.attributes[&self.standard]
             .get(&sym)
             .cloned()
             .unwrap_or_else(|| AttributeDesignator::Ident(WithRef::new(sym)))
@@ -1739,6 +1853,7 @@ impl<'a> Tokenizer<'a> {
                         &mut self.buffer,
                         &mut self.reader,
                         self.symbols,
+                        self.standard,
                     )?
                 }
             }
@@ -1916,6 +2031,14 @@ impl<'a> Tokenizer<'a> {
                 self.reader.skip();
                 (RightSquare, Value::None)
             }
+            b'{' => {
+                self.reader.skip();
+                (LeftBrace, Value::None)
+            }
+            b'}' => {
+                self.reader.skip();
+                (RightBrace, Value::None)
+            }
             b'\\' => {
                 self.reader.skip();
                 // LRM 15.4.3 Extended identifers
@@ -1984,11 +2107,34 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
-    #[allow(dead_code)]
     pub fn get_final_comments(&self) -> Option<Vec<Comment>> {
         self.final_comments.clone()
     }
 
+    /// Skip the encrypted text of a protected envelope (LRM 24.1) up to and including
+    /// the `` `protect end_protected `` directive or the end of the file.
+    pub fn skip_protected_envelope(&mut self) -> DiagnosticResult<()> {
+        loop {
+            let line = read_until_newline(&mut self.buffer, &mut self.reader)
+                .and_then(|_| Ok(self.reader.pop()?));
+            let is_eof = match line {
+                Ok(byte) => byte.is_none(),
+                Err(err) => {
+                    self.state.start = self.reader.state();
+                    return Err(Diagnostic::syntax_error(
+                        self.source.pos(err.range.start, err.range.end),
+                        err.message,
+                    ));
+                }
+            };
+            let line = self.buffer.to_string().to_ascii_lowercase();
+            let mut words = line.split_whitespace();
+            if words.next() == Some("`protect") && words.next() == Some("end_protected") || is_eof {
+                return Ok(());
+            }
+        }
+    }
+
     pub fn text_until_newline(&mut self) -> DiagnosticResult<Token> {
         let start_pos = self.reader.pos();
         if let Err(err) = read_until_newline(&mut self.buffer, &mut self.reader) {
@@ -2743,6 +2889,11 @@ my_other_ident",
         assert_eq!(kinds_tokenize("[]"), vec![LeftSquare, RightSquare]);
     }
 
+    #[test]
+    fn tokenize_braces() {
+        assert_eq!(kinds_tokenize("{}"), vec![LeftBrace, RightBrace]);
+    }
+
     #[test]
     fn tokenize_ignores_comments() {
         assert_eq!(
@@ -7,76 +7,204 @@
 use std::cell::Cell;
 use vhdl_lang::syntax::parser::ParsingContext;
 
+use super::conditional_analysis::{is_conditional_directive, ConditionalAnalysis};
 use super::tokenizer::Kind::*;
 use super::tokenizer::*;
 use crate::ast::token_range::WithToken;
 use crate::ast::{AttributeDesignator, Ident, RangeAttribute, TypeAttribute};
+use crate::data::error_codes::ErrorCode;
 use crate::data::{DiagnosticHandler, DiagnosticResult};
-use crate::{Diagnostic, SrcPos};
+use crate::standard::VHDLStandard;
+use crate::{Diagnostic, Position, Range, SrcPos};
 
 pub struct TokenStream<'a> {
     tokenizer: Tokenizer<'a>,
     idx: Cell<usize>,
     tokens: Vec<Token>,
+    /// The index of the token that follows each protected envelope
+    protected_envelopes: Vec<usize>,
     // This is the offset that a token's ID should be adapted
     // when getting it via `TokenStream::get_current_token_id()`
     // It is updated in the `slice_tokens` method
     token_offset: Cell<usize>,
 }
 
+/// Report the tokens that are skipped by conditional analysis directives,
+/// such that editors can gray out the text
+fn push_inactive(inactive: Option<SrcPos>, diagnostics: &mut dyn DiagnosticHandler) {
+    if let Some(pos) = inactive {
+        diagnostics.push(Diagnostic::new(
+            pos,
+            "Text is not analyzed due to conditional analysis directives",
+            ErrorCode::InactiveCode,
+        ));
+    }
+}
+
 impl<'a> TokenStream<'a> {
     /// Special handling for a tool directive of the form
     /// ```vhdl
     /// `identifier { any chars until newline }
     /// ```
     /// This needs special handling as the text that follows the identifier is arbitrary.
+    /// Since VHDL-2019, conditional analysis directives determine which text is analyzed.
+    /// The encrypted text of a protected envelope that starts with `` `protect begin_protected ``
+    /// is skipped, in which case `is_protected` is set.
+    /// Returns a token that follows the directive but is not part of it.
     fn handle_tool_directive(
         grave_accent: Token,
         tokenizer: &mut Tokenizer<'_>,
+        conditional: &mut ConditionalAnalysis,
+        is_protected: &mut bool,
         diagnostics: &mut dyn DiagnosticHandler,
-    ) {
+    ) -> Option<Token> {
         let start_pos = grave_accent.pos.clone();
-        match tokenizer.pop() {
+        let next = tokenizer.pop();
+        if let Ok(Some(tok)) = &next {
+            if tokenizer.standard() >= VHDLStandard::VHDL2019 && is_conditional_directive(tok) {
+                let tok = tok.clone();
+                return conditional.directive(&grave_accent, tok, tokenizer, diagnostics);
+            }
+        }
+        // Directives within text that is not analyzed are ignored
+        let mut ignored: Vec<Diagnostic> = Vec::new();
+        let diagnostics: &mut dyn DiagnosticHandler = if conditional.is_active() {
+            diagnostics
+        } else {
+            &mut ignored
+        };
+        let directive = match next {
             Ok(Some(tok)) => {
                 if tok.kind != Identifier {
                     diagnostics.push(Diagnostic::syntax_error(tok, "Expecting identifier"));
                     let _ = tokenizer.text_until_newline(); // skip potentially invalid tokens
-                    return;
+                    return None;
                 }
+                Some(tok)
+            }
+            Err(err) => {
+                diagnostics.push(err);
+                None
             }
-            Err(err) => diagnostics.push(err),
             Ok(None) => {
                 diagnostics.push(Diagnostic::syntax_error(start_pos, "Expecting identifier"));
-                return;
+                return None;
+            }
+        };
+        let text = match tokenizer.text_until_newline() {
+            Ok(text) => text,
+            Err(err) => {
+                diagnostics.push(err);
+                return None;
+            }
+        };
+        let is_protect = directive.is_some_and(|directive| match directive.value {
+            Value::Identifier(symbol) => symbol.name_utf8().eq_ignore_ascii_case("protect"),
+            _ => false,
+        });
+        let is_begin_protected = match text.value {
+            Value::Text(text) => text
+                .to_string()
+                .split_whitespace()
+                .next()
+                .is_some_and(|word| word.eq_ignore_ascii_case("begin_protected")),
+            _ => false,
+        };
+        if is_protect && is_begin_protected {
+            *is_protected = true;
+            // Envelopes that are truncated by the end of the file are tolerated
+            if let Err(err) = tokenizer.skip_protected_envelope() {
+                diagnostics.push(err);
             }
         }
-        match tokenizer.text_until_newline() {
-            Ok(_) => {}
-            Err(err) => diagnostics.push(err),
-        }
+        None
     }
 
     pub fn new(
-        mut tokenizer: Tokenizer<'a>,
+        tokenizer: Tokenizer<'a>,
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> TokenStream<'a> {
+        // Without an end, tokens never cross it
+        TokenStream::new_until(tokenizer, None, diagnostics).unwrap()
+    }
+
+    /// Create a token stream of the tokens that start before `end`, or of all tokens without an end.
+    /// Returns `None` when a token, comment or error crosses `end`
+    /// such that the tokens cannot be separated at `end`.
+    pub fn new_until(
+        mut tokenizer: Tokenizer<'a>,
+        end: Option<Position>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<TokenStream<'a>> {
+        let is_before_end = |range: Range| -> Option<bool> {
+            match end {
+                Some(end) if range.start >= end => Some(false),
+                Some(end) if range.end > end => None,
+                _ => Some(true),
+            }
+        };
         let mut tokens = Vec::new();
+        let mut protected_envelopes = Vec::new();
+        let mut conditional = ConditionalAnalysis::default();
+        // The tokens that are skipped since the text is not analyzed
+        let mut inactive: Option<SrcPos> = None;
+        let mut pending = None;
         loop {
-            match tokenizer.pop() {
+            let next = match pending.take() {
+                Some(token) => Ok(Some(token)),
+                None => tokenizer.pop(),
+            };
+            match next {
                 Ok(Some(token)) if token.kind == GraveAccent => {
-                    TokenStream::handle_tool_directive(token, &mut tokenizer, diagnostics)
+                    let mut is_protected = false;
+                    pending = TokenStream::handle_tool_directive(
+                        token,
+                        &mut tokenizer,
+                        &mut conditional,
+                        &mut is_protected,
+                        diagnostics,
+                    );
+                    if is_protected && conditional.is_active() {
+                        protected_envelopes.push(tokens.len());
+                    }
+                    if conditional.is_active() {
+                        push_inactive(inactive.take(), diagnostics);
+                    }
+                }
+                Ok(Some(token)) => {
+                    // The range of a token includes its comments
+                    if !is_before_end(token.full_range())? {
+                        break;
+                    }
+                    if conditional.is_active() {
+                        tokens.push(token)
+                    } else {
+                        inactive = Some(match inactive {
+                            Some(pos) => pos.combine(&token.pos),
+                            None => token.pos,
+                        });
+                    }
                 }
-                Ok(Some(token)) => tokens.push(token),
                 Ok(None) => break,
-                Err(err) => diagnostics.push(err),
+                Err(err) => {
+                    if !is_before_end(err.pos.range())? {
+                        break;
+                    }
+                    if conditional.is_active() {
+                        diagnostics.push(err)
+                    }
+                }
             }
         }
-        TokenStream {
+        push_inactive(inactive, diagnostics);
+        conditional.finish(diagnostics);
+        Some(TokenStream {
             tokenizer,
             idx: Cell::new(0),
             tokens,
+            protected_envelopes,
             token_offset: Cell::new(0),
-        }
+        })
     }
 
     pub fn state(&self) -> usize {
@@ -284,6 +412,18 @@ impl<'a> TokenStream<'a> {
     ///                current position (EOF)
     /// ```
     /// After calling `slice_tokens` again, the returned vec is `[tok x]`
+    /// The index of the token after the first protected envelope that follows the token at `idx`
+    pub fn protected_envelope_after(&self, idx: usize) -> Option<usize> {
+        self.protected_envelopes
+            .iter()
+            .copied()
+            .find(|envelope| *envelope > idx)
+    }
+
+    pub fn last_token(&self) -> Option<&Token> {
+        self.tokens.last()
+    }
+
     pub fn slice_tokens(&self) -> Vec<Token> {
         let vec = Vec::from(&self.tokens[self.token_offset.get()..self.state()]);
         self.token_offset.replace(self.state());
@@ -335,8 +475,9 @@ impl<T> Recover<T> for DiagnosticResult<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::{ContentReader, Diagnostic, NoDiagnostics};
+    use crate::data::{ContentReader, Diagnostic, ErrorCode, NoDiagnostics};
     use crate::syntax::test::Code;
+    use fnv::FnvHashMap;
     use itertools::Itertools;
 
     macro_rules! new_stream {
@@ -522,6 +663,170 @@ mod tests {
         )
     }
 
+    /// The names of the identifiers of the stream
+    fn identifiers(stream: &TokenStream<'_>) -> Vec<String> {
+        let mut names = Vec::new();
+        while let Some(token) = stream.peek() {
+            if let Value::Identifier(symbol) = &token.value {
+                names.push(symbol.name_utf8());
+            }
+            stream.skip();
+        }
+        names
+    }
+
+    #[test]
+    fn conditional_analysis_directives_select_the_analyzed_text() {
+        let code = Code::with_standard(
+            "\
+`if VHDL_VERSION >= \"2019\" and not (TOOL_TYPE = \"SYNTHESIS\") then
+a
+`if TOOL_NAME /= \"vhdl_lang\" then
+b
+`elsif TOOL_VENDOR = \"VHDL-LS\" then
+c
+`else
+d
+`end if
+`elsif TOOL_TYPE = \"SIMULATION\" then
+e
+`else
+f
+`end
+g
+",
+            VHDLStandard::VHDL2019,
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, stream, diagnostics);
+        let inactive = |pos: SrcPos| {
+            Diagnostic::new(
+                pos,
+                "Text is not analyzed due to conditional analysis directives",
+                ErrorCode::InactiveCode,
+            )
+        };
+        assert_eq!(
+            diagnostics,
+            vec![
+                inactive(code.s1("\nb\n").s1("b").pos()),
+                inactive(code.s1("\nd\n").s1("d").pos()),
+                inactive(code.s1("e\n`else\nf").pos()),
+            ]
+        );
+        assert_eq!(identifiers(&stream), vec!["a", "c", "g"]);
+    }
+
+    #[test]
+    fn conditional_analysis_identifiers_can_be_user_defined() {
+        let code = Code::with_standard(
+            "\
+`if TOOL_TYPE = \"SYNTHESIS\" and my_option = \"1\" then
+a
+`end if
+",
+            VHDLStandard::VHDL2019,
+        );
+        let identifiers_map = FnvHashMap::from_iter([
+            ("TOOL_TYPE".to_owned(), "SYNTHESIS".to_owned()),
+            ("MY_OPTION".to_owned(), "1".to_owned()),
+        ]);
+        let source = code.source();
+        let contents = source.contents();
+        let tokenizer = Tokenizer::new(&code.symbols, source, ContentReader::new(&contents))
+            .with_conditional_identifiers(&identifiers_map);
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        let stream = TokenStream::new(tokenizer, &mut diagnostics);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(identifiers(&stream), vec!["a"]);
+    }
+
+    #[test]
+    fn conditional_analysis_directive_errors() {
+        let code = Code::with_standard(
+            "\
+`warning \"check this\"
+`if UNKNOWN = \"1\" then
+a
+`else
+`error \"not supported\"
+`protect begin
+`end if
+`if TOOL_TYPE = \"SYNTHESIS\" then
+`error \"not analyzed\"
+`else
+",
+            VHDLStandard::VHDL2019,
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, stream, diagnostics);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(
+                    code.s1("`warning \"check this\""),
+                    "check this",
+                    ErrorCode::WarningDirective
+                ),
+                Diagnostic::syntax_error(
+                    code.s1("UNKNOWN"),
+                    "Unknown conditional analysis identifier 'UNKNOWN'"
+                ),
+                Diagnostic::new(
+                    code.s1("\na\n").s1("a"),
+                    "Text is not analyzed due to conditional analysis directives",
+                    ErrorCode::InactiveCode
+                ),
+                Diagnostic::new(
+                    code.s1("`error \"not supported\""),
+                    "not supported",
+                    ErrorCode::ErrorDirective
+                ),
+                Diagnostic::syntax_error(code.s("`if", 2), "`if without `end"),
+            ]
+        );
+        assert_eq!(identifiers(&stream), Vec::<String>::new());
+    }
+
+    #[test]
+    fn conditional_analysis_directives_are_tool_directives_before_vhdl_2019() {
+        let code = Code::new("`if");
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, _stream, diagnostics);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::syntax_error(
+                code.s1("if"),
+                "Expecting identifier"
+            )]
+        )
+    }
+
+    #[test]
+    fn protected_envelopes_are_skipped() {
+        let code = Code::new(
+            "\
+begin
+`protect begin_protected
+`protect data_block
+eW91IGNhbid0IHJlYWQgdGhpcw==#'
+`PROTECT END_PROTECTED
+end
+`protect begin_protected
+`protect data_block
+eW91IGNhbid0IHJlYWQgdGhpcw==",
+        );
+        new_stream!(code, stream);
+        assert_eq!(stream.peek_kind(), Some(Begin));
+        stream.skip();
+        assert_eq!(stream.peek_kind(), Some(End));
+        stream.skip();
+        assert_eq!(stream.peek_kind(), None);
+        assert_eq!(stream.protected_envelope_after(0), Some(1));
+        assert_eq!(stream.protected_envelope_after(1), Some(2));
+        assert_eq!(stream.protected_envelope_after(2), None);
+    }
+
     #[test]
     fn pop_tokens() {
         let code = Code::new(
@@ -6,8 +6,10 @@
 
 use crate::ast::token_range::WithTokenSpan;
 use crate::ast::{DelayMechanism, Waveform, WaveformElement};
+use crate::data::Diagnostic;
 use crate::syntax::parser::ParsingContext;
-use vhdl_lang::TokenSpan;
+use crate::VHDLStandard::VHDL2008;
+use vhdl_lang::{TokenAccess, TokenSpan};
 
 use super::common::{parse_optional, ParseResult};
 use super::expression::parse_expression;
@@ -68,10 +70,26 @@ pub fn parse_waveform(ctx: &mut ParsingContext<'_>) -> ParseResult<Waveform> {
     Ok(Waveform::Elements(elems))
 }
 
+/// LRM 10.5 Signal assignment statement
+/// Before VHDL-2008, `unaffected` is only allowed in concurrent signal assignments.
+pub fn parse_sequential_waveform(ctx: &mut ParsingContext<'_>) -> ParseResult<Waveform> {
+    let waveform = parse_waveform(ctx)?;
+    if let Waveform::Unaffected(token) = waveform {
+        if ctx.standard < VHDL2008 {
+            ctx.diagnostics.push(Diagnostic::syntax_error(
+                ctx.stream.index(token),
+                "'unaffected' is only allowed in concurrent signal assignments before VHDL-2008",
+            ));
+        }
+    }
+    Ok(waveform)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::syntax::test::Code;
+    use crate::syntax::test::{check_diagnostics, Code};
+    use crate::VHDLStandard::VHDL1993;
 
     #[test]
     fn test_transport_delay_mechanism() {
@@ -161,4 +179,24 @@ mod tests {
             Waveform::Unaffected(code.token())
         );
     }
+
+    #[test]
+    fn sequential_unaffected_waveform_requires_vhdl2008() {
+        let code = Code::with_standard("unaffected", VHDL1993);
+        let (waveform, diagnostics) = code.with_stream_diagnostics(parse_sequential_waveform);
+        assert_eq!(waveform, Waveform::Unaffected(code.token()));
+        check_diagnostics(
+            diagnostics,
+            vec![Diagnostic::syntax_error(
+                code.pos(),
+                "'unaffected' is only allowed in concurrent signal assignments before VHDL-2008",
+            )],
+        );
+
+        let code = Code::new("unaffected");
+        assert_eq!(
+            code.with_stream_no_diagnostics(parse_sequential_waveform),
+            Waveform::Unaffected(code.token())
+        );
+    }
 }
@@ -0,0 +1,368 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Export of all named declarations of a project as a tags file,
+//! so that editors without language server support can navigate the design.
+//!
+//! Both the extended format of universal-ctags (used by Vim)
+//! and the etags format (used by Emacs) are supported.
+
+use crate::analysis::DesignRoot;
+use crate::ast::search::{FoundDeclaration, SearchState, Searcher};
+use crate::ast::{Designator, ObjectClass};
+use crate::named_entity::{
+    AnyEntKind, Concurrent, Design, EntRef, HasEntityId, ObjectInterface, Overloaded, Type,
+};
+use crate::syntax::TokenAccess;
+use crate::SrcPos;
+use fnv::FnvHashSet;
+use itertools::Itertools;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use strum::{EnumString, IntoStaticStr};
+
+/// The format of a tags file.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum TagsFormat {
+    /// The extended format of universal-ctags
+    Ctags,
+    /// The format of Emacs' etags
+    Etags,
+}
+
+impl TagsFormat {
+    /// The conventional name of the tags file
+    pub fn default_file_name(&self) -> &'static str {
+        match self {
+            TagsFormat::Ctags => "tags",
+            TagsFormat::Etags => "TAGS",
+        }
+    }
+}
+
+/// A named declaration.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Tag {
+    pub name: String,
+    /// The kind of declaration as named by universal-ctags, i.e. `entity` or `signal`
+    pub kind: &'static str,
+    pub pos: SrcPos,
+    /// The kind and name of the enclosing declaration, such as the entity of a port
+    pub scope: Option<(&'static str, String)>,
+}
+
+/// All declarations with a name, excluding loop parameters and implicit declarations.
+pub(crate) fn list_tags(root: &DesignRoot) -> Vec<Tag> {
+    let mut searcher = TagSearcher {
+        root,
+        tags: Vec::new(),
+        visited: FnvHashSet::default(),
+    };
+    let _ = root.search(&mut searcher);
+    searcher.tags
+}
+
+struct TagSearcher<'a> {
+    root: &'a DesignRoot,
+    tags: Vec<Tag>,
+    visited: FnvHashSet<SrcPos>,
+}
+
+impl Searcher for TagSearcher<'_> {
+    fn search_decl(&mut self, _ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        let Some(ent) = decl.ent_id().map(|id| self.root.get_ent(id)) else {
+            return SearchState::NotFinished;
+        };
+        let (Some(name), Some(kind), Some(pos)) = (tag_name(ent), tag_kind(ent), ent.decl_pos())
+        else {
+            return SearchState::NotFinished;
+        };
+        // Files that are part of several libraries are searched more than once
+        if self.visited.insert(pos.clone()) {
+            let scope = ent
+                .parent
+                .and_then(|parent| Some((tag_kind(parent)?, tag_name(parent)?)));
+            self.tags.push(Tag {
+                name,
+                kind,
+                pos: pos.clone(),
+                scope,
+            });
+        }
+        SearchState::NotFinished
+    }
+}
+
+fn tag_name(ent: EntRef<'_>) -> Option<String> {
+    match ent.designator() {
+        Designator::Identifier(name) => Some(name.name_utf8()),
+        _ => None,
+    }
+}
+
+fn tag_kind(ent: EntRef<'_>) -> Option<&'static str> {
+    let kind = match ent.kind() {
+        AnyEntKind::Design(design) => match design {
+            Design::Entity(..) => "entity",
+            Design::Architecture(..) => "architecture",
+            Design::Configuration => "configuration",
+            Design::Package(..)
+            | Design::PackageBody(..)
+            | Design::UninstPackage(..)
+            | Design::PackageInstance(..)
+            | Design::InterfacePackageInstance(..) => "package",
+            Design::Context(..) => "context",
+            Design::VerificationUnit(..) => "vunit",
+        },
+        AnyEntKind::Component(_) => "component",
+        AnyEntKind::Type(typ) => match typ {
+            Type::Subtype(_) => "subtype",
+            Type::Record(_) => "record",
+            Type::Alias(_) => "alias",
+            _ => "type",
+        },
+        AnyEntKind::Object(object) => match object.iface {
+            Some(ObjectInterface::Generic) => "generic",
+            Some(ObjectInterface::Port(_)) => "port",
+            Some(ObjectInterface::Parameter(_)) => "parameter",
+            None => match object.class {
+                ObjectClass::Signal => "signal",
+                ObjectClass::Constant => "constant",
+                ObjectClass::Variable | ObjectClass::SharedVariable => "variable",
+            },
+        },
+        AnyEntKind::DeferredConstant(_) => "constant",
+        AnyEntKind::File(_) => "file",
+        AnyEntKind::InterfaceFile(_) => "parameter",
+        AnyEntKind::Overloaded(overloaded) => match overloaded {
+            Overloaded::SubprogramDecl(_) | Overloaded::UninstSubprogramDecl(..) => "prototype",
+            Overloaded::Subprogram(signature) | Overloaded::UninstSubprogram(signature, _) => {
+                if signature.return_type().is_some() {
+                    "function"
+                } else {
+                    "procedure"
+                }
+            }
+            Overloaded::InterfaceSubprogram(_) => "generic",
+            Overloaded::EnumLiteral(_) => "literal",
+            Overloaded::Alias(_) => "alias",
+        },
+        AnyEntKind::ObjectAlias { .. }
+        | AnyEntKind::ExternalAlias { .. }
+        | AnyEntKind::Alias(_) => "alias",
+        AnyEntKind::ElementDeclaration(_) => "field",
+        AnyEntKind::PhysicalLiteral(_) => "unit",
+        AnyEntKind::Attribute(_) => "attribute",
+        AnyEntKind::View(_) => "view",
+        AnyEntKind::Concurrent(Some(Concurrent::Process)) => "process",
+        AnyEntKind::Concurrent(Some(Concurrent::Instance)) => "instance",
+        AnyEntKind::Concurrent(_) | AnyEntKind::Sequential(_) => "label",
+        AnyEntKind::LoopParameter(_) | AnyEntKind::Library => return None,
+    };
+    Some(kind)
+}
+
+/// Write `tags` in the extended format of universal-ctags, sorted by name.
+/// File names are relative to `base_dir`, which should be the directory of the tags file.
+pub fn write_ctags(tags: &[Tag], base_dir: &Path, output: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        output,
+        "!_TAG_FILE_FORMAT\t2\t/extended format; --format=1 will not append ;\" to lines/"
+    )?;
+    writeln!(
+        output,
+        "!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/"
+    )?;
+    writeln!(output, "!_TAG_PROGRAM_NAME\tvhdl_lang\t//")?;
+    writeln!(
+        output,
+        "!_TAG_PROGRAM_VERSION\t{}\t//",
+        env!("CARGO_PKG_VERSION")
+    )?;
+
+    let lines = tags
+        .iter()
+        .map(|tag| {
+            let line = tag.pos.range.start.line + 1;
+            let mut entry = format!(
+                "{}\t{}\t{line};\"\t{}\tline:{line}",
+                tag.name,
+                relative_file_name(&tag.pos, base_dir),
+                tag.kind
+            );
+            if let Some((kind, name)) = &tag.scope {
+                entry.push_str(&format!("\t{kind}:{name}"));
+            }
+            entry
+        })
+        .sorted();
+    for line in lines {
+        writeln!(output, "{line}")?;
+    }
+    Ok(())
+}
+
+/// Write `tags` in the etags format, grouped by file.
+/// File names are relative to `base_dir`, which should be the directory of the tags file.
+pub fn write_etags(tags: &[Tag], base_dir: &Path, output: &mut dyn Write) -> io::Result<()> {
+    let by_file = tags
+        .iter()
+        .into_group_map_by(|tag| relative_file_name(&tag.pos, base_dir));
+    for (file_name, tags) in by_file.into_iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
+        let contents = tags[0].pos.source.contents();
+        // Character offsets of the start of each line
+        let line_offsets: Vec<usize> = (0..contents.num_lines())
+            .scan(0, |offset, lineno| {
+                let start = *offset;
+                *offset += contents
+                    .get_line(lineno)
+                    .map_or(0, |line| line.chars().count());
+                Some(start)
+            })
+            .collect();
+
+        let mut section = String::new();
+        for tag in tags.iter().sorted_by_key(|tag| tag.pos.range.start) {
+            let range = tag.pos.range;
+            let line = contents
+                .get_line(range.start.line as usize)
+                .unwrap_or_default();
+            let pattern: String = line.chars().take(range.end.character as usize).collect();
+            section.push_str(&format!(
+                "{pattern}\x7f{}\x01{},{}\n",
+                tag.name,
+                range.start.line + 1,
+                line_offsets
+                    .get(range.start.line as usize)
+                    .copied()
+                    .unwrap_or_default()
+            ));
+        }
+        write!(output, "\x0c\n{file_name},{}\n{section}", section.len())?;
+    }
+    Ok(())
+}
+
+fn relative_file_name(pos: &SrcPos, base_dir: &Path) -> String {
+    let file_name = pos.source.file_name();
+    let absolute = |path: &Path| std::path::absolute(path).unwrap_or_else(|_| path.to_owned());
+    let file_name = absolute(file_name);
+    file_name
+        .strip_prefix(absolute(base_dir))
+        .unwrap_or(&file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::tests::LibraryBuilder;
+    use crate::syntax::test::check_no_diagnostics;
+
+    const CODE: &str = "
+entity ent is
+  generic (width : natural := 8);
+  port (clk : in bit);
+end entity;
+
+architecture rtl of ent is
+  signal count : natural;
+  function inc(value : natural) return natural is
+  begin
+    return value + 1;
+  end function;
+begin
+  main: process (clk)
+  begin
+    for i in 0 to 1 loop
+    end loop;
+  end process;
+end architecture;
+";
+
+    fn tags_of(code: &str) -> Vec<Tag> {
+        let mut builder = LibraryBuilder::new();
+        let code = builder.code("libname", code);
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+        list_tags(&root)
+            .into_iter()
+            .filter(|tag| &tag.pos.source == code.source())
+            .collect()
+    }
+
+    #[test]
+    fn lists_named_declarations_with_kinds_and_scopes() {
+        let tags = tags_of(CODE);
+        let summary: Vec<_> = tags
+            .iter()
+            .map(|tag| {
+                (
+                    tag.name.as_str(),
+                    tag.kind,
+                    tag.scope
+                        .as_ref()
+                        .map(|(kind, name)| (*kind, name.as_str())),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ent", "entity", None),
+                ("width", "generic", Some(("entity", "ent"))),
+                ("clk", "port", Some(("entity", "ent"))),
+                ("rtl", "architecture", Some(("entity", "ent"))),
+                ("count", "signal", Some(("architecture", "rtl"))),
+                ("inc", "function", Some(("architecture", "rtl"))),
+                ("value", "parameter", Some(("function", "inc"))),
+                ("main", "process", Some(("architecture", "rtl"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_sorted_ctags() {
+        let tags = tags_of(CODE);
+        let mut output = Vec::new();
+        write_ctags(&tags, Path::new(""), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output
+            .lines()
+            .filter(|line| !line.starts_with("!_"))
+            .collect();
+        let file_name = relative_file_name(&tags[0].pos, Path::new(""));
+        assert_eq!(lines.len(), tags.len());
+        assert_eq!(
+            lines[0],
+            format!("clk\t{file_name}\t4;\"\tport\tline:4\tentity:ent")
+        );
+        assert_eq!(lines[2], format!("ent\t{file_name}\t2;\"\tentity\tline:2"));
+        assert!(output.starts_with("!_TAG_FILE_FORMAT\t2\t"));
+    }
+
+    #[test]
+    fn writes_etags_sections() {
+        let tags = tags_of(CODE);
+        let mut output = Vec::new();
+        write_etags(&tags, Path::new(""), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let file_name = relative_file_name(&tags[0].pos, Path::new(""));
+
+        let (header, section) = output
+            .strip_prefix('\x0c')
+            .and_then(|rest| rest.strip_prefix('\n'))
+            .and_then(|rest| rest.split_once('\n'))
+            .unwrap();
+        assert_eq!(header, format!("{file_name},{}", section.len()));
+        let entries: Vec<&str> = section.lines().collect();
+        assert_eq!(entries[0], "entity ent\x7fent\x012,1");
+        assert_eq!(entries[2], "  port (clk\x7fclk\x014,49");
+    }
+}
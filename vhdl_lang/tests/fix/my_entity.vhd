@@ -0,0 +1,11 @@
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+ENTITY my_entity IS
+  port (a : in std_logic);
+end entity;
+
+architecture rtl of my_entity is
+begin
+end architecture;
@@ -58,3 +58,385 @@ fn unused_function_gets_detected() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn stdin_replaces_the_contents_of_a_project_file() -> Result<(), Box<dyn Error>> {
+    let analyze = |contents: &str| -> Result<assert_cmd::Command, Box<dyn Error>> {
+        let mut cmd = assert_cmd::Command::cargo_bin("vhdl_lang")?;
+        cmd.arg("--config")
+            .arg("tests/unused_declarations/vhdl_ls.toml")
+            .arg("--libraries")
+            .arg("../vhdl_libraries/vhdl_ls.toml")
+            .arg("--stdin")
+            .arg("--filename")
+            .arg("tests/unused_declarations/my_entity.vhd")
+            .write_stdin(contents.to_owned());
+        Ok(cmd)
+    };
+
+    let saved = std::fs::read_to_string("tests/unused_declarations/my_entity.vhd")?;
+    analyze(&saved.replace("bar <= foo;", "bar <= foo;\n    baz <= 'Z';"))?
+        .assert()
+        .success();
+    analyze("entity my_ent is\nend entity;\nfoo")?
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("my_entity.vhd:3"));
+
+    Ok(())
+}
+
+#[test]
+fn fix_dry_run_prints_diff() -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+
+    cmd.arg("--config")
+        .arg("tests/fix/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml")
+        .arg("fix")
+        .arg("--rules")
+        .arg("keyword_case,unused_use,end_label")
+        .arg("--dry-run");
+    cmd.assert().success().stdout(
+        predicate::str::contains("-use ieee.numeric_std.all;")
+            .and(predicate::str::contains("+entity my_entity is"))
+            .and(predicate::str::contains("+end architecture rtl;"))
+            .and(predicate::str::contains("std_logic_1164").not())
+            .and(predicate::str::contains(
+                "Found 5 fixes in 1 files (keyword_case: 2, unused_use: 1, end_label: 2)",
+            )),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn dump_ast_prints_json() -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+
+    cmd.arg("--dump-ast")
+        .arg("tests/unused_declarations/my_entity.vhd");
+    cmd.assert().success().stdout(
+        predicate::str::starts_with("{\n  \"standard\": \"VHDL2008\",")
+            .and(predicate::str::contains("\"Entity\": {")),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn diagnostics_can_be_written_as_junit() -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+
+    cmd.arg("--config")
+        .arg("tests/unused_declarations/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml")
+        .arg("--output-format")
+        .arg("junit");
+    cmd.assert().failure().stdout(
+        predicate::str::starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
+            .and(predicate::str::contains(
+                "<testsuite name=\"tests/unused_declarations/my_entity.vhd\" tests=\"1\" failures=\"1\">",
+            ))
+            .and(predicate::str::contains("<failure message=\"Unused declaration of port &apos;baz&apos; : inout\" type=\"error\">")),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn baseline_suppresses_recorded_diagnostics() -> Result<(), Box<dyn Error>> {
+    let baseline =
+        std::env::temp_dir().join(format!("vhdl_lang_baseline_{}.json", std::process::id()));
+    let analyze = |option: &str| -> Result<Command, Box<dyn Error>> {
+        let mut cmd = Command::cargo_bin("vhdl_lang")?;
+        cmd.arg("--config")
+            .arg("tests/unused_declarations/vhdl_ls.toml")
+            .arg("--libraries")
+            .arg("../vhdl_libraries/vhdl_ls.toml")
+            .arg(option)
+            .arg(&baseline);
+        Ok(cmd)
+    };
+
+    analyze("--write-baseline")?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Wrote 1 diagnostics to the baseline",
+        ));
+    let contents = std::fs::read_to_string(&baseline)?;
+    let result = analyze("--baseline")?.assert();
+    std::fs::remove_file(&baseline)?;
+    result
+        .success()
+        .stdout(predicate::str::contains("Unused declaration").not());
+    assert!(contents.contains("\"line_text\": \"baz : inout std_logic\""));
+
+    Ok(())
+}
+
+#[test]
+fn only_diagnostics_of_changed_files_are_reported() -> Result<(), Box<dyn Error>> {
+    let analyze = |revision: &str| -> Result<Command, Box<dyn Error>> {
+        let mut cmd = Command::cargo_bin("vhdl_lang")?;
+        cmd.arg("--config")
+            .arg("tests/unused_declarations/vhdl_ls.toml")
+            .arg("--libraries")
+            .arg("../vhdl_libraries/vhdl_ls.toml")
+            .arg("--changed-since")
+            .arg(revision);
+        Ok(cmd)
+    };
+
+    // The files of the project are committed and unchanged
+    analyze("HEAD")?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unused declaration").not());
+    analyze("no-such-revision")?
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Could not find the changes since no-such-revision",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn diagnostics_can_be_written_as_sarif() -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+
+    cmd.arg("--config")
+        .arg("tests/unused_declarations/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml")
+        .arg("--output-format")
+        .arg("sarif");
+    cmd.assert().failure().stdout(
+        predicate::str::starts_with("{\n  \"$schema\": ")
+            .and(predicate::str::contains("\"ruleId\": \"unused\""))
+            .and(predicate::str::contains(
+                "\"uri\": \"tests/unused_declarations/my_entity.vhd\"",
+            )),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn warnings_fail_the_analysis_when_requested() -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+    cmd.arg("--config")
+        .arg("tests/fix/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("warning: Unused declaration"));
+
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+    cmd.arg("--config")
+        .arg("tests/fix/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml")
+        .arg("--fail-on")
+        .arg("warning");
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn max_diagnostics_limits_the_output() -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+
+    cmd.arg("--config")
+        .arg("tests/unused_declarations/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml")
+        .arg("--max-diagnostics")
+        .arg("0");
+    cmd.assert().failure().stdout(
+        predicate::str::contains("Found 1 diagnostics, 1 are not shown")
+            .and(predicate::str::contains("Unused declaration").not()),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn dependency_graph_is_written_as_dot() -> Result<(), Box<dyn Error>> {
+    let output =
+        std::env::temp_dir().join(format!("vhdl_lang_dependencies_{}.dot", std::process::id()));
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+
+    cmd.arg("--config")
+        .arg("tests/unused_declarations/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml")
+        .arg("dependencies")
+        .arg("--output")
+        .arg(&output);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Wrote 2 dependencies between 3 design units",
+    ));
+    let dot = std::fs::read_to_string(&output)?;
+    std::fs::remove_file(&output)?;
+    assert!(dot.starts_with("digraph dependencies {\n"));
+    assert!(dot.contains("\"my_library.my_ent\" -> \"ieee.std_logic_1164\" [label=\"use\"];"));
+
+    Ok(())
+}
+
+#[test]
+fn compile_order_is_written_as_text() -> Result<(), Box<dyn Error>> {
+    let output = std::env::temp_dir().join(format!(
+        "vhdl_lang_compile_order_{}.txt",
+        std::process::id()
+    ));
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+
+    cmd.arg("--config")
+        .arg("tests/unused_declarations/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml")
+        .arg("compile-order")
+        .arg("--output")
+        .arg(&output);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Wrote the compile order of 1 files",
+    ));
+    let order = std::fs::read_to_string(&output)?;
+    std::fs::remove_file(&output)?;
+    assert!(order.starts_with("my_library "));
+    assert!(order.ends_with("my_entity.vhd\n"));
+
+    Ok(())
+}
+
+#[test]
+fn index_writes_lsif() -> Result<(), Box<dyn Error>> {
+    let output = std::env::temp_dir().join(format!("vhdl_lang_index_{}.lsif", std::process::id()));
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+
+    cmd.arg("--config")
+        .arg("tests/unused_declarations/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml")
+        .arg("index")
+        .arg("--output")
+        .arg(&output);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("vertices and edges"));
+    let index = std::fs::read_to_string(&output)?;
+    std::fs::remove_file(&output)?;
+    assert!(index.starts_with("{\"id\":1,\"type\":\"vertex\",\"label\":\"metaData\""));
+    assert!(index.contains("my_entity.vhd\",\"languageId\":\"vhdl\"}"));
+    assert!(index.contains("\"label\":\"textDocument/references\""));
+
+    Ok(())
+}
+
+#[test]
+fn tree_prints_the_hierarchy_below_a_top_level() -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+
+    cmd.arg("--config")
+        .arg("tests/unused_declarations/vhdl_ls.toml")
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml")
+        .arg("tree")
+        .arg("--top")
+        .arg("work.my_ent");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("my_library.my_ent(arch)\n"));
+
+    Ok(())
+}
+
+#[test]
+fn format_checks_and_writes_files() -> Result<(), Box<dyn Error>> {
+    let directory = std::env::temp_dir().join(format!("vhdl_lang_format_{}", std::process::id()));
+    std::fs::create_dir_all(&directory)?;
+    let config = directory.join("vhdl_ls.toml");
+    std::fs::write(&config, "[libraries]\n\n[format]\nindent_width = 2\n")?;
+    let file = directory.join("my_entity.vhd");
+    std::fs::write(
+        &file,
+        "ENTITY my_entity IS\nport (a : in bit);\nend entity;\n",
+    )?;
+
+    let format = |check: bool| -> Result<Command, Box<dyn Error>> {
+        let mut cmd = Command::cargo_bin("vhdl_lang")?;
+        cmd.arg("--config").arg(&config).arg("format");
+        if check {
+            cmd.arg("--check");
+        }
+        cmd.arg(&directory);
+        Ok(cmd)
+    };
+    format(true)?
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "1 of 1 files would be reformatted",
+        ));
+    format(false)?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Formatted 1 of 1 files"));
+    let formatted = std::fs::read_to_string(&file)?;
+    format(true)?.assert().success();
+    std::fs::remove_dir_all(&directory)?;
+
+    assert_eq!(
+        formatted,
+        "entity my_entity is\n  port (\n    a: in bit\n  );\nend entity;\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn init_writes_a_configuration_that_can_be_analyzed() -> Result<(), Box<dyn Error>> {
+    let directory = std::env::temp_dir().join(format!("vhdl_lang_init_{}", std::process::id()));
+    std::fs::create_dir_all(directory.join("pkgs"))?;
+    std::fs::create_dir_all(directory.join("rtl"))?;
+    std::fs::write(
+        directory.join("pkgs/my_pkg.vhd"),
+        "package my_pkg is\nend package;\n",
+    )?;
+    std::fs::write(
+        directory.join("rtl/my_entity.vhd"),
+        "library pkgs;\nuse pkgs.my_pkg.all;\nentity my_entity is\nend entity;\n",
+    )?;
+
+    let init = || -> Result<Command, Box<dyn Error>> {
+        let mut cmd = Command::cargo_bin("vhdl_lang")?;
+        cmd.arg("init").arg(&directory);
+        Ok(cmd)
+    };
+    init()?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote 2 libraries"));
+    init()?
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("use --force to overwrite it"));
+
+    let mut cmd = Command::cargo_bin("vhdl_lang")?;
+    cmd.arg("--config")
+        .arg(directory.join("vhdl_ls.toml"))
+        .arg("--libraries")
+        .arg("../vhdl_libraries/vhdl_ls.toml");
+    let result = cmd.assert();
+    std::fs::remove_dir_all(&directory)?;
+    result.success();
+    Ok(())
+}
@@ -6,6 +6,7 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
 
+mod to_json_derive;
 mod token_span_attribute;
 mod token_span_derive;
 
@@ -14,6 +15,11 @@ pub fn impl_token_span_trait(input: TokenStream) -> TokenStream {
     token_span_derive::add_token_span_impl(input)
 }
 
+#[proc_macro_derive(ToJson)]
+pub fn impl_to_json_trait(input: TokenStream) -> TokenStream {
+    to_json_derive::add_to_json_impl(input)
+}
+
 #[proc_macro_attribute]
 pub fn with_token_span(args: TokenStream, input: TokenStream) -> TokenStream {
     token_span_attribute::add_token_span_fields(args, input)
@@ -0,0 +1,118 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use proc_macro::TokenStream;
+
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Fields};
+
+/// Structs become objects with one member per field,
+/// unit variants of enums become strings and all other variants become an object
+/// with the name of the variant as the only member.
+pub fn add_to_json_impl(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    for param in input.generics.type_params_mut() {
+        param
+            .bounds
+            .push(parse_quote!(::vhdl_lang::ast::json::ToJson));
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, value) = fields_to_json(&data.fields);
+            quote! {
+                let #name #pattern = self;
+                #value
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                let key = variant_name.to_string();
+                let (pattern, value) = fields_to_json(&variant.fields);
+                if variant.fields.is_empty() {
+                    quote! {
+                        #name::#variant_name #pattern => ::vhdl_lang::ast::json::Json::String(#key.to_owned()),
+                    }
+                } else {
+                    quote! {
+                        #name::#variant_name #pattern => ::vhdl_lang::ast::json::Json::Object(
+                            vec![(#key.to_owned(), #value)]
+                        ),
+                    }
+                }
+            });
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return syn::Error::new(
+                input.span(),
+                "The ToJson macro can only be applied to struct and enums!",
+            )
+            .into_compile_error()
+            .into()
+        }
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::vhdl_lang::ast::json::ToJson for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn to_json(&self, ctx: &dyn ::vhdl_lang::TokenAccess) -> ::vhdl_lang::ast::json::Json {
+                #body
+            }
+        }
+    }
+    .into()
+}
+
+/// Returns a pattern binding all fields and the expression converting them to JSON.
+/// A single unnamed field is converted to the JSON of the field itself.
+fn fields_to_json(fields: &Fields) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    match fields {
+        Fields::Named(fields) => {
+            let idents: Vec<_> = fields
+                .named
+                .iter()
+                .map(|field| field.ident.clone().unwrap())
+                .collect();
+            let keys = idents.iter().map(|ident| ident.to_string());
+            (
+                quote! { { #(#idents),* } },
+                quote! {
+                    ::vhdl_lang::ast::json::Json::Object(vec![
+                        #( (#keys.to_owned(), ::vhdl_lang::ast::json::ToJson::to_json(#idents, ctx)), )*
+                    ])
+                },
+            )
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => (
+            quote! { (inner) },
+            quote! { ::vhdl_lang::ast::json::ToJson::to_json(inner, ctx) },
+        ),
+        Fields::Unnamed(fields) => {
+            let idents: Vec<_> = (0..fields.unnamed.len())
+                .map(|i| format_ident!("field{}", i))
+                .collect();
+            (
+                quote! { ( #(#idents),* ) },
+                quote! {
+                    ::vhdl_lang::ast::json::Json::Array(vec![
+                        #( ::vhdl_lang::ast::json::ToJson::to_json(#idents, ctx), )*
+                    ])
+                },
+            )
+        }
+        Fields::Unit => (quote! {}, quote! { ::vhdl_lang::ast::json::Json::Null }),
+    }
+}
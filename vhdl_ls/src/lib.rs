@@ -8,6 +8,7 @@
 #[macro_use]
 extern crate log;
 
+pub mod logging;
 mod rpc_channel;
 mod stdio_server;
 mod vhdl_server;
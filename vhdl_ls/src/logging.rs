@@ -0,0 +1,457 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Logging of the language server to stderr or to a rotated log file.
+//!
+//! The logger is installed once at startup and can be reconfigured at runtime,
+//! i.e. when the client sends new settings, without restarting the server.
+
+use log::{LevelFilter, Log, Metadata, Record};
+use serde_json::{json, Value};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogSettings {
+    /// Levels in the `RUST_LOG` syntax, i.e. `warn,vhdl_lang::analysis=debug`
+    pub filter: String,
+    /// Write to this file instead of stderr
+    pub file: Option<PathBuf>,
+    /// Write one JSON object per line instead of plain text
+    pub json: bool,
+    /// The log file is rotated when it grows larger than this
+    pub max_file_size: u64,
+    /// The number of rotated log files to keep next to the log file
+    pub max_files: usize,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        LogSettings {
+            filter: std::env::var("RUST_LOG").unwrap_or_else(|_| "error".to_owned()),
+            file: None,
+            json: false,
+            max_file_size: 10 * 1024 * 1024,
+            max_files: 3,
+        }
+    }
+}
+
+impl LogSettings {
+    /// Returns a copy of these settings with the keys of a `logging` settings object applied
+    pub fn with_options(&self, options: &Value) -> Result<LogSettings, String> {
+        let Value::Object(options) = options else {
+            return Err("logging must be an object".to_owned());
+        };
+        let mut settings = self.clone();
+        for (key, value) in options {
+            match (key.as_str(), value) {
+                ("level", Value::String(filter)) => settings.filter.clone_from(filter),
+                ("file", Value::String(file)) if file.is_empty() => settings.file = None,
+                ("file", Value::String(file)) => settings.file = Some(PathBuf::from(file)),
+                ("file", Value::Null) => settings.file = None,
+                ("json", Value::Bool(json)) => settings.json = *json,
+                ("maxFileSize", Value::Number(size)) if size.as_u64().is_some() => {
+                    settings.max_file_size = size.as_u64().unwrap()
+                }
+                ("maxFiles", Value::Number(files)) if files.as_u64().is_some() => {
+                    settings.max_files = files.as_u64().unwrap() as usize
+                }
+                ("level" | "file" | "json" | "maxFileSize" | "maxFiles", _) => {
+                    return Err(format!("Illegal value {value} for logging.{key}"))
+                }
+                _ => return Err(format!("Unknown logging setting {key}")),
+            }
+        }
+        LevelFilters::parse(&settings.filter)?;
+        Ok(settings)
+    }
+}
+
+/// The level of each module given by the most specific matching directive
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct LevelFilters {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl LevelFilters {
+    fn parse(spec: &str) -> Result<LevelFilters, String> {
+        let mut filters = LevelFilters {
+            default: LevelFilter::Error,
+            modules: Vec::new(),
+        };
+        for directive in spec.split(',').map(str::trim) {
+            if directive.is_empty() {
+                continue;
+            }
+            let parse_level = |level: &str| {
+                level
+                    .trim()
+                    .parse::<LevelFilter>()
+                    .map_err(|_| format!("Illegal log level '{level}'"))
+            };
+            match directive.split_once('=') {
+                Some((module, level)) => filters
+                    .modules
+                    .push((module.trim().to_owned(), parse_level(level)?)),
+                // A lone module name enables all levels of that module, as with env_logger
+                None => match parse_level(directive) {
+                    Ok(level) => filters.default = level,
+                    Err(_) => filters
+                        .modules
+                        .push((directive.to_owned(), LevelFilter::Trace)),
+                },
+            }
+        }
+        // Longer module paths are more specific and take precedence
+        filters
+            .modules
+            .sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        Ok(filters)
+    }
+
+    fn level_of(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .find(|(module, _)| {
+                target
+                    .strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, std::cmp::max)
+    }
+}
+
+/// A log file that is moved to `<file>.1`, `<file>.2`, ... when it grows too large
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    fn open(path: &Path, max_size: u64, max_files: usize) -> io::Result<RotatingFile> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(RotatingFile {
+            path: path.to_owned(),
+            file,
+            size,
+            max_size,
+            max_files,
+        })
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        PathBuf::from(path)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files == 0 {
+            self.file.set_len(0)?;
+        } else {
+            for index in (1..self.max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    std::fs::rename(from, self.rotated_path(index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated_path(1))?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+enum Sink {
+    Stderr,
+    File(RotatingFile),
+}
+
+struct LoggerState {
+    filters: LevelFilters,
+    json: bool,
+    sink: Sink,
+}
+
+impl LoggerState {
+    fn new(settings: &LogSettings) -> Result<LoggerState, String> {
+        let sink = match settings.file {
+            Some(ref path) => Sink::File(
+                RotatingFile::open(path, settings.max_file_size, settings.max_files).map_err(
+                    |err| format!("Could not open log file {}: {err}", path.to_string_lossy()),
+                )?,
+            ),
+            None => Sink::Stderr,
+        };
+        Ok(LoggerState {
+            filters: LevelFilters::parse(&settings.filter)?,
+            json: settings.json,
+            sink,
+        })
+    }
+}
+
+struct Logger {
+    state: Mutex<LoggerState>,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let state = self.state.lock().unwrap();
+        metadata.level() <= state.filters.level_of(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        let mut state = self.state.lock().unwrap();
+        if record.level() > state.filters.level_of(record.target()) {
+            return;
+        }
+        let line = format_record(record, SystemTime::now(), state.json);
+        // There is nowhere to report failures of the logger itself
+        let _ = match state.sink {
+            Sink::Stderr => io::stderr().write_all(line.as_bytes()),
+            Sink::File(ref mut file) => file.write_line(&line),
+        };
+    }
+
+    fn flush(&self) {
+        let mut state = self.state.lock().unwrap();
+        let _ = match state.sink {
+            Sink::Stderr => io::stderr().flush(),
+            Sink::File(ref mut file) => file.file.flush(),
+        };
+    }
+}
+
+/// Install the logger of the language server.
+///
+/// When the settings are invalid, i.e. the log file cannot be opened,
+/// the logger falls back to stderr and the error is returned.
+pub fn init(settings: &LogSettings) -> Result<(), String> {
+    let (state, result) = match LoggerState::new(settings) {
+        Ok(state) => (state, Ok(())),
+        Err(err) => {
+            let fallback = LoggerState::new(&LogSettings {
+                file: None,
+                ..settings.clone()
+            })
+            .or_else(|_| {
+                LoggerState::new(&LogSettings {
+                    filter: "error".to_owned(),
+                    file: None,
+                    ..settings.clone()
+                })
+            })?;
+            (fallback, Err(err))
+        }
+    };
+    let max_level = state.filters.max_level();
+    let logger = LOGGER.get_or_init(|| Logger {
+        state: Mutex::new(state),
+    });
+    log::set_logger(logger).map_err(|err| err.to_string())?;
+    log::set_max_level(max_level);
+    result
+}
+
+/// Apply new settings to the logger installed by [`init`].
+/// The previous settings are kept when the new ones are invalid.
+pub fn reconfigure(settings: &LogSettings) -> Result<(), String> {
+    let Some(logger) = LOGGER.get() else {
+        return Ok(());
+    };
+    let state = LoggerState::new(settings)?;
+    log::set_max_level(state.filters.max_level());
+    *logger.state.lock().unwrap() = state;
+    Ok(())
+}
+
+fn format_record(record: &Record, time: SystemTime, json: bool) -> String {
+    let timestamp = format_timestamp(time);
+    if json {
+        let object = json!({
+            "timestamp": timestamp,
+            "level": record.level().as_str(),
+            "target": record.target(),
+            "message": record.args().to_string(),
+        });
+        format!("{object}\n")
+    } else {
+        format!(
+            "[{timestamp} {:<5} {}] {}\n",
+            record.level(),
+            record.target(),
+            record.args()
+        )
+    }
+}
+
+/// Format a time as an RFC 3339 timestamp in UTC with millisecond precision
+fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+
+    // Convert days since the epoch to a civil date
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn most_specific_module_level_applies() {
+        let filters =
+            LevelFilters::parse("warn, vhdl_lang=info,vhdl_lang::analysis=trace").unwrap();
+        assert_eq!(filters.level_of("vhdl_ls::stdio_server"), LevelFilter::Warn);
+        assert_eq!(filters.level_of("vhdl_lang::project"), LevelFilter::Info);
+        assert_eq!(
+            filters.level_of("vhdl_lang::analysis::names"),
+            LevelFilter::Trace
+        );
+        assert_eq!(filters.level_of("vhdl_language"), LevelFilter::Warn);
+        assert_eq!(filters.max_level(), LevelFilter::Trace);
+
+        assert_eq!(
+            LevelFilters::parse("vhdl_ls").unwrap().level_of("vhdl_ls"),
+            LevelFilter::Trace
+        );
+        assert_eq!(
+            LevelFilters::parse("vhdl_ls=loud"),
+            Err("Illegal log level 'loud'".to_owned())
+        );
+    }
+
+    #[test]
+    fn applies_logging_options() {
+        let settings = LogSettings {
+            filter: "error".to_owned(),
+            ..LogSettings::default()
+        };
+        let settings = settings
+            .with_options(&json!({
+                "level": "info,vhdl_ls=debug",
+                "file": "/tmp/vhdl_ls.log",
+                "json": true,
+                "maxFiles": 5,
+            }))
+            .unwrap();
+        assert_eq!(settings.filter, "info,vhdl_ls=debug");
+        assert_eq!(settings.file, Some(PathBuf::from("/tmp/vhdl_ls.log")));
+        assert!(settings.json);
+        assert_eq!(settings.max_files, 5);
+
+        assert_eq!(
+            settings.with_options(&json!({"file": ""})).unwrap().file,
+            None
+        );
+        assert_eq!(
+            settings.with_options(&json!({"json": "yes"})),
+            Err("Illegal value \"yes\" for logging.json".to_owned())
+        );
+        assert_eq!(
+            settings.with_options(&json!({"colors": true})),
+            Err("Unknown logging setting colors".to_owned())
+        );
+    }
+
+    #[test]
+    fn formats_records_as_text_and_json() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_709_294_400_123);
+        let args = format_args!("Analyzed {} files", 3);
+        let record = Record::builder()
+            .args(args)
+            .level(log::Level::Info)
+            .target("vhdl_ls::vhdl_server")
+            .build();
+        assert_eq!(
+            format_record(&record, time, false),
+            "[2024-03-01T12:00:00.123Z INFO  vhdl_ls::vhdl_server] Analyzed 3 files\n"
+        );
+        let object: Value = serde_json::from_str(&format_record(&record, time, true)).unwrap();
+        assert_eq!(
+            object,
+            json!({
+                "timestamp": "2024-03-01T12:00:00.123Z",
+                "level": "INFO",
+                "target": "vhdl_ls::vhdl_server",
+                "message": "Analyzed 3 files",
+            })
+        );
+    }
+
+    #[test]
+    fn rotates_log_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("vhdl_ls.log");
+        let mut file = RotatingFile::open(&path, 10, 2).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write_line(line).unwrap();
+        }
+        let read = |path: &Path| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(&path), "fourth\n");
+        assert_eq!(read(&file.rotated_path(1)), "third\n");
+        assert_eq!(read(&file.rotated_path(2)), "second\n");
+        assert!(!file.rotated_path(3).exists());
+    }
+}
@@ -5,6 +5,8 @@
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
 use clap::Parser;
+use std::path::PathBuf;
+use vhdl_ls::logging::{self, LogSettings};
 use vhdl_ls::VHDLServerSettings;
 
 #[derive(Parser)]
@@ -21,17 +23,41 @@ struct Args {
 
     #[arg(long, default_value_t = false)]
     is_vscode: bool,
+
+    /// Log levels in the `RUST_LOG` syntax, i.e. `warn,vhdl_lang::analysis=debug`.
+    /// Defaults to the `RUST_LOG` environment variable
+    #[arg(long)]
+    log_level: Option<String>,
+
+    /// Write the log to this file instead of stderr. The file is rotated when it grows too large
+    #[arg(long)]
+    log_file: Option<PathBuf>,
+
+    /// Write one JSON object per log message
+    #[arg(long, default_value_t = false)]
+    log_json: bool,
 }
 
 fn main() {
     let args = Args::parse();
 
-    env_logger::init();
+    let defaults = LogSettings::default();
+    let log_settings = LogSettings {
+        filter: args.log_level.unwrap_or(defaults.filter),
+        file: args.log_file,
+        json: args.log_json,
+        ..defaults
+    };
+    if let Err(err) = logging::init(&log_settings) {
+        eprintln!("{err}");
+    }
     log::info!("Starting language server");
-    vhdl_ls::start(VHDLServerSettings {
+    let exit_code = vhdl_ls::start(VHDLServerSettings {
         no_lint: args.no_lint,
         silent: args.silent,
         is_vscode: args.is_vscode,
+        logging: log_settings,
         ..Default::default()
     });
+    std::process::exit(exit_code);
 }
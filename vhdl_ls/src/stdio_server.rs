@@ -12,30 +12,56 @@ use lsp_server::{Connection, ExtractError, Request, RequestId};
 use lsp_types::{notification, request, InitializeParams};
 use serde_json::Value;
 
+use std::collections::VecDeque;
 use std::{cell::RefCell, rc::Rc};
 
 use crate::rpc_channel::{RpcChannel, SharedRpcChannel};
-use crate::vhdl_server::VHDLServer;
 use crate::vhdl_server::VHDLServerSettings;
+use crate::vhdl_server::{
+    DesignHierarchy, Explain, FindInstances, FindReferences, GotoDrivers, GotoLoads,
+    ListDesignUnits, SettingsSchema, Stats, VHDLServer,
+};
 
 /// Set up the IO channel for `stdio` and start the VHDL language server.
-pub fn start(settings: VHDLServerSettings) {
+///
+/// Returns when the client sends the `exit` notification or closes the connection,
+/// after all pending messages have been written.
+/// The process should then exit with the returned exit code.
+pub fn start(settings: VHDLServerSettings) -> i32 {
     let (connection, io_threads) = Connection::stdio();
     let connection_rpc = Rc::new(ConnectionRpcChannel::new(connection));
     let rpc = SharedRpcChannel::new(connection_rpc.clone());
     let mut server = VHDLServer::new_settings(rpc, settings);
     connection_rpc.handle_initialization(&mut server);
-    connection_rpc.main_event_loop(server);
+    let exit_code = connection_rpc.main_event_loop(server);
 
-    io_threads.join().unwrap();
+    // The writer thread finishes once all senders of the connection are dropped
+    drop(connection_rpc);
+    if let Err(err) = io_threads.join() {
+        error!("Failed to close the connection: {err}");
+    }
+    log::logger().flush();
+    exit_code
 }
 
+/// Requests that may take long in large projects and are abandoned
+/// when the client cancels them before they are handled.
+const CANCELLABLE_REQUESTS: &[&str] = &[
+    <request::Completion as request::Request>::METHOD,
+    <request::References as request::Request>::METHOD,
+    <request::WorkspaceSymbolRequest as request::Request>::METHOD,
+    <request::DocumentSymbolRequest as request::Request>::METHOD,
+    <FindReferences as request::Request>::METHOD,
+];
+
 /// Wrapper for Connection implementing RpcChannel + Clone
 /// and keeping track of outgoing request IDs.
 #[derive(Clone)]
 struct ConnectionRpcChannel {
     connection: Rc<Connection>,
     next_outgoing_request_id: Rc<RefCell<i32>>,
+    /// Messages that have been received ahead of time to look for cancellations
+    pending: Rc<RefCell<VecDeque<lsp_server::Message>>>,
 }
 
 impl RpcChannel for ConnectionRpcChannel {
@@ -61,6 +87,7 @@ impl ConnectionRpcChannel {
         Self {
             connection: Rc::new(connection),
             next_outgoing_request_id: Rc::new(RefCell::new(0)),
+            pending: Rc::new(RefCell::new(VecDeque::new())),
         }
     }
 
@@ -81,24 +108,36 @@ impl ConnectionRpcChannel {
     }
 
     /// Main event loop handling incoming messages from the client.
-    fn main_event_loop(&self, mut server: VHDLServer) {
+    /// Returns the exit code once the client sends the `exit` notification.
+    fn main_event_loop(&self, mut server: VHDLServer) -> i32 {
         info!("Language server initialized, waiting for messages ...");
-        while let Ok(message) = self.connection.receiver.recv() {
+        while let Some(message) = self.next_message() {
             trace!("Received message: {:?}", message);
             match message {
                 lsp_server::Message::Request(request) => {
-                    match self.connection.handle_shutdown(&request) {
-                        Ok(shutdown) => {
-                            if shutdown {
-                                server.shutdown_server();
-                            } else {
-                                self.handle_request(&mut server, request)
-                            }
-                        }
-                        Err(err) => panic!("{err:?}"),
+                    if CANCELLABLE_REQUESTS.contains(&request.method.as_str())
+                        && self.is_cancelled(&request.id)
+                    {
+                        self.send_cancelled(request.id);
+                    } else if request.method == <request::Shutdown as request::Request>::METHOD {
+                        server.shutdown_server();
+                        self.send_response(lsp_server::Response::new_ok(request.id, ()));
+                    } else if server.is_shut_down() {
+                        self.send_response(lsp_server::Response::new_err(
+                            request.id,
+                            lsp_server::ErrorCode::InvalidRequest as i32,
+                            "The server is shutting down".to_owned(),
+                        ));
+                    } else {
+                        self.handle_request(&mut server, request)
                     }
                 }
                 lsp_server::Message::Notification(notification) => {
+                    if notification.method
+                        == <notification::Exit as notification::Notification>::METHOD
+                    {
+                        return server.exit_notification();
+                    }
                     self.handle_notification(&mut server, notification);
                 }
                 lsp_server::Message::Response(response) => {
@@ -106,6 +145,67 @@ impl ConnectionRpcChannel {
                 }
             };
         }
+        // The client closed the connection without exiting
+        server.exit_notification()
+    }
+
+    /// The next message to handle, either received ahead of time or from the connection.
+    fn next_message(&self) -> Option<lsp_server::Message> {
+        let pending = self.pending.borrow_mut().pop_front();
+        pending.or_else(|| self.connection.receiver.recv().ok())
+    }
+
+    /// Receive all messages that are available without blocking and
+    /// return true if the client has cancelled the request with `id`.
+    /// Cancelled requests that have been received but not handled yet are answered
+    /// right away, cancellations of requests that have already been handled are dropped.
+    fn is_cancelled(&self, id: &RequestId) -> bool {
+        let mut is_cancelled = false;
+        while let Ok(message) = self.connection.receiver.try_recv() {
+            let cancelled_id = match &message {
+                lsp_server::Message::Notification(notification)
+                    if notification.method
+                        == <notification::Cancel as notification::Notification>::METHOD =>
+                {
+                    serde_json::from_value::<lsp_types::CancelParams>(notification.params.clone())
+                        .ok()
+                        .map(|params| match params.id {
+                            lsp_types::NumberOrString::Number(id) => RequestId::from(id),
+                            lsp_types::NumberOrString::String(id) => RequestId::from(id),
+                        })
+                }
+                _ => None,
+            };
+            let Some(cancelled_id) = cancelled_id else {
+                self.pending.borrow_mut().push_back(message);
+                continue;
+            };
+            if &cancelled_id == id {
+                is_cancelled = true;
+                continue;
+            }
+            let mut pending = self.pending.borrow_mut();
+            let position = pending.iter().position(|message| {
+                matches!(message, lsp_server::Message::Request(request)
+                    if request.id == cancelled_id
+                        && CANCELLABLE_REQUESTS.contains(&request.method.as_str()))
+            });
+            if let Some(position) = position {
+                pending.remove(position);
+                drop(pending);
+                self.send_cancelled(cancelled_id);
+            }
+        }
+        is_cancelled
+    }
+
+    fn send_cancelled(&self, id: RequestId) {
+        debug!("Request {id} was cancelled");
+        self.send_response(lsp_server::Response::new_err(
+            id,
+            lsp_server::ErrorCode::RequestCanceled as i32,
+            "The request was cancelled".to_owned(),
+        ));
     }
 
     /// Send responses (to requests sent by the client) back to the client.
@@ -160,16 +260,30 @@ impl ConnectionRpcChannel {
         };
         let request = match extract::<request::Rename>(request) {
             Ok((id, params)) => {
-                let result = server.rename(&params);
-                self.send_response(lsp_server::Response::new_ok(id, result));
+                let response = match server.rename(&params) {
+                    Ok(result) => lsp_server::Response::new_ok(id, result),
+                    Err(err) => lsp_server::Response::new_err(
+                        id,
+                        lsp_server::ErrorCode::RequestFailed as i32,
+                        err.to_string(),
+                    ),
+                };
+                self.send_response(response);
                 return;
             }
             Err(request) => request,
         };
         let request = match extract::<request::PrepareRenameRequest>(request) {
             Ok((id, params)) => {
-                let result = server.prepare_rename(&params);
-                self.send_response(lsp_server::Response::new_ok(id, result));
+                let response = match server.prepare_rename(&params) {
+                    Ok(result) => lsp_server::Response::new_ok(id, result),
+                    Err(err) => lsp_server::Response::new_err(
+                        id,
+                        lsp_server::ErrorCode::RequestFailed as i32,
+                        err.to_string(),
+                    ),
+                };
+                self.send_response(response);
                 return;
             }
             Err(request) => request,
@@ -198,6 +312,22 @@ impl ConnectionRpcChannel {
             }
             Err(request) => request,
         };
+        let request = match extract::<request::CodeActionRequest>(request) {
+            Ok((id, params)) => {
+                let result = server.code_action(&params);
+                self.send_response(lsp_server::Response::new_ok(id, result));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<request::LinkedEditingRange>(request) {
+            Ok((id, params)) => {
+                let result = server.linked_editing_range(&params.text_document_position_params);
+                self.send_response(lsp_server::Response::new_ok(id, result));
+                return;
+            }
+            Err(request) => request,
+        };
         let request = match extract::<request::HoverRequest>(request) {
             Ok((id, params)) => {
                 let result = server.text_document_hover(&params.text_document_position_params);
@@ -230,6 +360,78 @@ impl ConnectionRpcChannel {
             }
             Err(request) => request,
         };
+        let request = match extract::<FindInstances>(request) {
+            Ok((id, params)) => {
+                let res = server.find_instances(&params);
+                self.send_response(lsp_server::Response::new_ok(id, res));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<ListDesignUnits>(request) {
+            Ok((id, _params)) => {
+                let res = server.list_design_units();
+                self.send_response(lsp_server::Response::new_ok(id, res));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<GotoDrivers>(request) {
+            Ok((id, params)) => {
+                let res = server.goto_drivers(&params);
+                self.send_response(lsp_server::Response::new_ok(id, res));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<GotoLoads>(request) {
+            Ok((id, params)) => {
+                let res = server.goto_loads(&params);
+                self.send_response(lsp_server::Response::new_ok(id, res));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<FindReferences>(request) {
+            Ok((id, params)) => {
+                let res = server.find_references(&params);
+                self.send_response(lsp_server::Response::new_ok(id, res));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<DesignHierarchy>(request) {
+            Ok((id, params)) => {
+                let res = server.design_hierarchy(&params);
+                self.send_response(lsp_server::Response::new_ok(id, res));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<Explain>(request) {
+            Ok((id, params)) => {
+                let res = server.explain(&params);
+                self.send_response(lsp_server::Response::new_ok(id, res));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<SettingsSchema>(request) {
+            Ok((id, ())) => {
+                let res = server.settings_schema();
+                self.send_response(lsp_server::Response::new_ok(id, res));
+                return;
+            }
+            Err(request) => request,
+        };
+        let request = match extract::<Stats>(request) {
+            Ok((id, ())) => {
+                let res = server.stats();
+                self.send_response(lsp_server::Response::new_ok(id, res));
+                return;
+            }
+            Err(request) => request,
+        };
 
         debug!("Unhandled request: {:?}", request);
         self.send_response(lsp_server::Response::new_err(
@@ -272,9 +474,14 @@ impl ConnectionRpcChannel {
             Ok(params) => return server.workspace_did_change_watched_files(&params),
             Err(notification) => notification,
         };
-        // exit
-        let notification = match extract::<notification::Exit>(notification) {
-            Ok(_params) => return server.exit_notification(),
+        // workspace/didChangeWorkspaceFolders
+        let notification = match extract::<notification::DidChangeWorkspaceFolders>(notification) {
+            Ok(params) => return server.workspace_did_change_workspace_folders(&params),
+            Err(notification) => notification,
+        };
+        // workspace/didChangeConfiguration
+        let notification = match extract::<notification::DidChangeConfiguration>(notification) {
+            Ok(params) => return server.workspace_did_change_configuration(&params),
             Err(notification) => notification,
         };
 
@@ -290,3 +497,72 @@ impl ConnectionRpcChannel {
         // outgoing requests do not require confirmation by the client.
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VHDLServerSettings;
+
+    #[test]
+    fn responds_to_cancelled_request_without_handling_it() {
+        let (connection, client) = Connection::memory();
+        let connection_rpc = Rc::new(ConnectionRpcChannel::new(connection));
+        let server = VHDLServer::new_settings(
+            SharedRpcChannel::new(connection_rpc.clone()),
+            VHDLServerSettings::default(),
+        );
+
+        client
+            .sender
+            .send(
+                Request::new(
+                    RequestId::from(1),
+                    <request::Completion as request::Request>::METHOD.to_owned(),
+                    serde_json::json!({
+                        "textDocument": {"uri": "file:///ent.vhd"},
+                        "position": {"line": 0, "character": 0},
+                    }),
+                )
+                .into(),
+            )
+            .unwrap();
+        client
+            .sender
+            .send(
+                lsp_server::Notification::new(
+                    <notification::Cancel as notification::Notification>::METHOD.to_owned(),
+                    lsp_types::CancelParams {
+                        id: lsp_types::NumberOrString::Number(1),
+                    },
+                )
+                .into(),
+            )
+            .unwrap();
+        client
+            .sender
+            .send(
+                lsp_server::Notification::new(
+                    <notification::Exit as notification::Notification>::METHOD.to_owned(),
+                    (),
+                )
+                .into(),
+            )
+            .unwrap();
+        connection_rpc.main_event_loop(server);
+
+        let response = client
+            .receiver
+            .try_iter()
+            .find_map(|message| match message {
+                lsp_server::Message::Response(response) => Some(response),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(response.id, RequestId::from(1));
+        assert!(response.result.is_none());
+        assert_eq!(
+            response.error.unwrap().code,
+            lsp_server::ErrorCode::RequestCanceled as i32
+        );
+    }
+}
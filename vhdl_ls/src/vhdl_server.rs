@@ -4,26 +4,48 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
+mod code_action;
 mod completion;
+mod config_file;
+mod design_hierarchy;
+mod design_units;
 mod diagnostics;
+mod drivers;
+mod explain;
+mod instances;
 mod lifecycle;
+mod references;
 mod rename;
+mod settings;
+mod settings_schema;
+mod symbol_cache;
 mod text_document;
 mod workspace;
 
+pub use self::design_hierarchy::DesignHierarchy;
+pub use self::design_units::ListDesignUnits;
+pub use self::drivers::{GotoDrivers, GotoLoads};
+pub use self::explain::Explain;
+pub use self::instances::FindInstances;
+pub use self::references::FindReferences;
+pub use self::settings_schema::SettingsSchema;
+pub use self::symbol_cache::Stats;
+
 use lsp_types::*;
 
 use fnv::FnvHashMap;
 use vhdl_lang::ast::ObjectClass;
 
+use self::symbol_cache::{DocumentSymbolCache, ServerStats};
+use crate::logging::LogSettings;
 use crate::rpc_channel::SharedRpcChannel;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use std::io;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use vhdl_lang::{
-    AnyEntKind, Concurrent, Config, EntHierarchy, EntRef, Message, MessageHandler, Object,
-    Overloaded, Project, SeverityMap, SrcPos, Token, Type, VHDLStandard,
+    AnyEntKind, Concurrent, Config, EntHierarchy, EntRef, ErrorCode, Message, MessageHandler,
+    Object, Overloaded, Project, Severity, SeverityMap, Source, SrcPos, Token, Type, VHDLStandard,
 };
 
 /// Defines how the language server handles files
@@ -46,6 +68,22 @@ impl NonProjectFileHandling {
             _ => return None,
         })
     }
+
+    pub fn as_str(&self) -> &'static str {
+        use NonProjectFileHandling::*;
+        match self {
+            Ignore => "ignore",
+            Analyze => "analyze",
+        }
+    }
+
+    pub fn all() -> impl Iterator<Item = NonProjectFileHandling> {
+        [
+            NonProjectFileHandling::Ignore,
+            NonProjectFileHandling::Analyze,
+        ]
+        .into_iter()
+    }
 }
 
 #[derive(Default, Clone)]
@@ -54,6 +92,18 @@ pub struct VHDLServerSettings {
     pub silent: bool,
     pub is_vscode: bool,
     pub non_project_file_handling: NonProjectFileHandling,
+    pub logging: LogSettings,
+    /// Overrides the standard of the configuration files
+    pub standard: Option<VHDLStandard>,
+    /// Overrides severities of the configuration files
+    pub severity_overrides: FnvHashMap<ErrorCode, Option<Severity>>,
+}
+
+/// A workspace folder of the client and the location of its configuration file
+struct WorkspaceFolderConfig {
+    name: String,
+    path: PathBuf,
+    config_file: PathBuf,
 }
 
 pub struct VHDLServer {
@@ -63,8 +113,11 @@ pub struct VHDLServer {
     use_external_config: bool,
     project: Project,
     diagnostic_cache: FnvHashMap<Url, Vec<vhdl_lang::Diagnostic>>,
+    document_symbol_cache: DocumentSymbolCache,
+    /// Open `vhdl_ls.toml` documents, which are not part of the project
+    config_documents: FnvHashMap<Url, Source>,
     init_params: Option<InitializeParams>,
-    config_file: Option<PathBuf>,
+    workspace_folders: Vec<WorkspaceFolderConfig>,
     severity_map: SeverityMap,
     string_matcher: SkimMatcherV2,
 }
@@ -77,8 +130,10 @@ impl VHDLServer {
             use_external_config: true,
             project: Project::new(VHDLStandard::default()),
             diagnostic_cache: FnvHashMap::default(),
+            document_symbol_cache: DocumentSymbolCache::default(),
+            config_documents: FnvHashMap::default(),
             init_params: None,
-            config_file: None,
+            workspace_folders: Vec::new(),
             severity_map: SeverityMap::default(),
             string_matcher: SkimMatcherV2::default().use_cache(true).ignore_case(),
         }
@@ -92,128 +147,160 @@ impl VHDLServer {
             use_external_config,
             project: Project::new(VHDLStandard::default()),
             diagnostic_cache: Default::default(),
+            document_symbol_cache: Default::default(),
+            config_documents: Default::default(),
             init_params: None,
-            config_file: None,
+            workspace_folders: Vec::new(),
             severity_map: SeverityMap::default(),
             string_matcher: SkimMatcherV2::default(),
         }
     }
 
-    /// Load the workspace root configuration file
-    fn load_root_uri_config(&self) -> io::Result<Config> {
-        let config_file = self.config_file.as_ref().ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                "Workspace root configuration file not set",
-            )
-        })?;
-        let config = Config::read_file_path(config_file)?;
+    /// Load the configuration file of a workspace folder
+    fn load_workspace_folder_config(&self, folder: &WorkspaceFolderConfig) -> io::Result<Config> {
+        let config = Config::read_file_path(&folder.config_file)?;
 
         // Log which file was loaded
         self.message(Message::log(format!(
             "Loaded workspace root configuration file: {}",
-            config_file.to_str().unwrap()
+            folder.config_file.to_string_lossy()
         )));
 
         Ok(config)
     }
 
-    /// Load the configuration or use a default configuration if unsuccessful
+    /// Load the configuration or use a default configuration if unsuccessful.
+    /// The standard of the editor settings takes precedence over the configuration files.
+    /// The configurations of all workspace folders are merged in the order of the folders,
+    /// see [Config::merge].
     /// Log info/error messages to the client
     fn load_config(&self) -> Config {
         let mut config = Config::default();
 
+        let mut folder_configs = Vec::new();
+        let mut missing = Vec::new();
+        for folder in self.workspace_folders.iter() {
+            match self.load_workspace_folder_config(folder) {
+                Ok(folder_config) => {
+                    folder_configs.push(folder_config);
+                }
+                Err(err) if matches!(err.kind(), ErrorKind::NotFound) => {
+                    missing.push((folder, err));
+                }
+                Err(err) => {
+                    self.message(Message::error(format!("Error loading vhdl_ls.toml: {err}")));
+                }
+            }
+        }
+
         if self.use_external_config {
-            config.load_external_config(&mut self.message_filter(), None);
+            // The standard libraries of the first workspace folder that configures them
+            // replace the standard libraries shipped with the server
+            let standard_libraries = folder_configs.iter().find_map(|folder_config| {
+                folder_config
+                    .standard_libraries()
+                    .map(|path| path.to_string_lossy().into_owned())
+            });
+            config.load_external_config(&mut self.message_filter(), standard_libraries);
+        }
+        let mut folder_configs = folder_configs.into_iter();
+        if let Some(mut folders_config) = folder_configs.next() {
+            for folder_config in folder_configs {
+                folders_config.merge(&folder_config);
+            }
+            config.append(&folders_config, &mut self.message_filter());
         }
 
-        match self.load_root_uri_config() {
-            Ok(root_config) => {
-                config.append(&root_config, &mut self.message_filter());
+        if missing.len() == self.workspace_folders.len() {
+            // Only a problem when no folder defines the library mapping
+            for (_, err) in missing {
+                self.message(Message::error(format!(
+                    "Library mapping is unknown due to missing vhdl_ls.toml config file in the workspace root path: {err}"
+                )));
+                self.message(Message::warning(
+                    "Without library mapping semantic analysis might be incorrect",
+                ));
             }
-            Err(ref err) => {
-                if matches!(err.kind(), ErrorKind::NotFound) {
-                    self.message(Message::error(format!(
-                        "Library mapping is unknown due to missing vhdl_ls.toml config file in the workspace root path: {err}"
-                    )));
-                    self.message(Message::warning(
-                        "Without library mapping semantic analysis might be incorrect",
-                    ));
-                } else {
-                    self.message(Message::error(format!("Error loading vhdl_ls.toml: {err}")));
-                }
+        } else {
+            for (folder, _) in missing {
+                self.message(Message::log(format!(
+                    "No vhdl_ls.toml config file in workspace folder {}",
+                    folder.name
+                )));
             }
-        };
+        }
 
+        if let Some(standard) = self.settings.standard {
+            config.set_standard(standard);
+        }
         config
     }
 
-    /// Extract path of workspace root configuration file from InitializeParams
-    fn root_uri_config_file(&self, params: &InitializeParams) -> Option<PathBuf> {
+    /// Extract the workspace folders from InitializeParams,
+    /// using the root uri for clients that do not support multiple workspace folders
+    fn initial_workspace_folders(&self, params: &InitializeParams) -> Vec<WorkspaceFolderConfig> {
+        if let Some(folders) = params.workspace_folders.as_ref().filter(|f| !f.is_empty()) {
+            return folders
+                .iter()
+                .filter_map(|folder| self.workspace_folder_config(folder))
+                .collect();
+        }
+
         #[allow(deprecated)]
         match params.root_uri.clone() {
-            Some(root_uri) => root_uri
-                .to_file_path()
-                .map(|root_path| root_path.join(".vscode").join("vhdl_ls.toml"))
-                .map_err(|_| {
-                    self.message(Message::error(format!(
-                        "{} {} {:?} ",
-                        "Cannot load workspace:",
-                        "initializeParams.rootUri is not a valid file path:",
-                        root_uri,
-                    )))
+            Some(root_uri) => {
+                let name = root_uri
+                    .path_segments()
+                    .and_then(|mut segments| segments.next_back())
+                    .unwrap_or_default()
+                    .to_owned();
+                self.workspace_folder_config(&WorkspaceFolder {
+                    uri: root_uri,
+                    name,
                 })
-                .ok(),
+                .into_iter()
+                .collect()
+            }
             None => {
                 self.message(Message::error(
                     "Cannot load workspace: Initialize request is missing rootUri parameter.",
                 ));
+                Vec::new()
+            }
+        }
+    }
+
+    fn workspace_folder_config(&self, folder: &WorkspaceFolder) -> Option<WorkspaceFolderConfig> {
+        match folder.uri.to_file_path() {
+            Ok(path) => Some(WorkspaceFolderConfig {
+                name: folder.name.clone(),
+                config_file: path.join(".vscode").join("vhdl_ls.toml"),
+                path,
+            }),
+            Err(_) => {
+                self.message(Message::error(format!(
+                    "{} {} {:?} ",
+                    "Cannot load workspace:",
+                    "workspace folder is not a valid file path:",
+                    folder.uri,
+                )));
                 None
             }
         }
-        // if self.settings.is_vscode {
-        //     match params.root_uri.clone() {
-        //         Some(root_uri) => root_uri
-        //             .to_file_path()
-        //             .map(|root_path| root_path.join(".vscode").join("vhdl_ls.toml"))
-        //             .map_err(|_| {
-        //                 self.message(Message::error(format!(
-        //                     "{} {} {:?} ",
-        //                     "Cannot load workspace:",
-        //                     "initializeParams.rootUri is not a valid file path:",
-        //                     root_uri,
-        //                 )))
-        //             })
-        //             .ok(),
-        //         None => {
-        //             self.message(Message::error(
-        //                 "Cannot load workspace: Initialize request is missing rootUri parameter.",
-        //             ));
-        //             None
-        //         }
-        //     }
-        // } else {
-        //     match params.root_uri.clone() {
-        //         Some(root_uri) => root_uri
-        //             .to_file_path()
-        //             .map(|root_path| root_path.join("vhdl_ls.toml"))
-        //             .map_err(|_| {
-        //                 self.message(Message::error(format!(
-        //                     "{} {} {:?} ",
-        //                     "Cannot load workspace:",
-        //                     "initializeParams.rootUri is not a valid file path:",
-        //                     root_uri,
-        //                 )))
-        //             })
-        //             .ok(),
-        //         None => {
-        //             self.message(Message::error(
-        //                 "Cannot load workspace: Initialize request is missing rootUri parameter.",
-        //             ));
-        //             None
-        //         }
-        //     }
-        // }
+    }
+
+    /// The innermost workspace folder containing a file
+    fn owning_workspace_folder(&self, file_name: &Path) -> Option<&WorkspaceFolderConfig> {
+        self.workspace_folders
+            .iter()
+            .filter(|folder| file_name.starts_with(&folder.path))
+            .max_by_key(|folder| folder.path.components().count())
+    }
+
+    fn is_workspace_config_file(&self, file_name: &Path) -> bool {
+        self.workspace_folders
+            .iter()
+            .any(|folder| folder.config_file == file_name)
     }
 
     fn client_supports_related_information(&self) -> bool {
@@ -274,7 +361,29 @@ impl VHDLServer {
         try_fun().unwrap_or(false)
     }
 
-    pub fn document_symbol(&self, params: &DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+    pub fn document_symbol(
+        &mut self,
+        params: &DocumentSymbolParams,
+    ) -> Option<DocumentSymbolResponse> {
+        let uri = &params.text_document.uri;
+        if let Some(symbols) = self.document_symbol_cache.get(uri) {
+            return Some(symbols);
+        }
+        let symbols = self.compute_document_symbol(params)?;
+        self.document_symbol_cache.insert(uri, symbols.clone());
+        Some(symbols)
+    }
+
+    pub fn stats(&self) -> ServerStats {
+        ServerStats {
+            document_symbol_cache: self.document_symbol_cache.stats(),
+        }
+    }
+
+    fn compute_document_symbol(
+        &self,
+        params: &DocumentSymbolParams,
+    ) -> Option<DocumentSymbolResponse> {
         let source = self
             .project
             .get_source(&uri_to_file_name(&params.text_document.uri))?;
@@ -507,6 +616,7 @@ fn to_symbol_kind(kind: &AnyEntKind) -> SymbolKind {
     match kind {
         AnyEntKind::ExternalAlias { class, .. } => object_class_kind(ObjectClass::from(*class)),
         AnyEntKind::ObjectAlias { base_object, .. } => object_kind(base_object.object()),
+        AnyEntKind::Alias(ent) => to_symbol_kind(ent.kind()),
         AnyEntKind::Object(o) => object_kind(o),
         AnyEntKind::LoopParameter(_) => SymbolKind::CONSTANT,
         AnyEntKind::PhysicalLiteral(_) => SymbolKind::CONSTANT,
@@ -533,6 +643,7 @@ fn to_symbol_kind(kind: &AnyEntKind) -> SymbolKind {
             vhdl_lang::Design::PackageInstance(_) => SymbolKind::PACKAGE,
             vhdl_lang::Design::InterfacePackageInstance(_) => SymbolKind::PACKAGE,
             vhdl_lang::Design::Context(_) => SymbolKind::NAMESPACE,
+            vhdl_lang::Design::VerificationUnit(..) => SymbolKind::MODULE,
         },
     }
 }
@@ -543,6 +654,8 @@ mod tests {
 
     use super::*;
     use crate::rpc_channel::test_support::*;
+    use crate::vhdl_server::explain::code_description;
+    use vhdl_lang::ErrorCode;
 
     pub(crate) fn initialize_server(server: &mut VHDLServer, root_uri: Url) {
         let capabilities = ClientCapabilities::default();
@@ -675,6 +788,7 @@ end entity ent2;
                     },
                 },
                 code: Some(NumberOrString::String("syntax_error".to_owned())),
+                code_description: code_description(ErrorCode::SyntaxError),
                 severity: Some(DiagnosticSeverity::ERROR),
                 source: Some("vhdl ls".to_owned()),
                 message: "End identifier mismatch, expected ent".to_owned(),
@@ -771,6 +885,7 @@ lib.files = [
                     },
                 },
                 code: Some(NumberOrString::String("unresolved".to_owned())),
+                code_description: code_description(ErrorCode::Unresolved),
                 severity: Some(DiagnosticSeverity::ERROR),
                 source: Some("vhdl ls".to_owned()),
                 message: "No primary unit \'ent2\' within library \'lib\'".to_owned(),
@@ -950,6 +1065,222 @@ lib.files = [
         server.initialized_notification();
     }
 
+    #[test]
+    fn loads_config_of_each_workspace_folder() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir1, folder1) = temp_root_uri();
+        let (_tempdir2, folder2) = temp_root_uri();
+        let file1 = write_file(&folder1, "file1.vhd", "entity ent1 is end entity;");
+        let file2 = write_file(&folder2, "file2.vhd", "entity ent2 is end entity;");
+        for folder in [&folder1, &folder2] {
+            std::fs::create_dir(folder.to_file_path().unwrap().join(".vscode")).unwrap();
+        }
+        let config1 = write_file(
+            &folder1,
+            ".vscode/vhdl_ls.toml",
+            format!(
+                "[libraries]\nlib1.files = ['{}']",
+                uri_to_file_name(&file1).to_string_lossy()
+            ),
+        );
+        let config2 = write_file(
+            &folder2,
+            ".vscode/vhdl_ls.toml",
+            format!(
+                "[libraries]\nlib2.files = ['{}']",
+                uri_to_file_name(&file2).to_string_lossy()
+            ),
+        );
+
+        expect_loaded_config_messages(&mock, &config1);
+        expect_loaded_config_messages(&mock, &config2);
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            root_uri: Some(folder1.clone()),
+            workspace_folders: Some(vec![
+                WorkspaceFolder {
+                    uri: folder1.clone(),
+                    name: "folder1".to_owned(),
+                },
+                WorkspaceFolder {
+                    uri: folder2.clone(),
+                    name: "folder2".to_owned(),
+                },
+            ]),
+            ..Default::default()
+        };
+        server.initialize_request(initialize_params);
+        let file1 = uri_to_file_name(&file1);
+        let file2 = uri_to_file_name(&file2);
+        assert!(server.project.get_source(&file1).is_some());
+        assert!(server.project.get_source(&file2).is_some());
+        assert_eq!(
+            server
+                .owning_workspace_folder(&file2)
+                .map(|folder| folder.name.as_str()),
+            Some("folder2")
+        );
+
+        mock.expect_message_contains("Workspace folders have changed");
+        expect_loaded_config_messages(&mock, &config1);
+        server.workspace_did_change_workspace_folders(&DidChangeWorkspaceFoldersParams {
+            event: WorkspaceFoldersChangeEvent {
+                added: vec![],
+                removed: vec![WorkspaceFolder {
+                    uri: folder2,
+                    name: "folder2".to_owned(),
+                }],
+            },
+        });
+        let library_names = |server: &VHDLServer, file_name: &Path| {
+            let source = server.project.get_source(file_name).unwrap();
+            server
+                .project
+                .library_mapping_of(&source)
+                .iter()
+                .map(|name| name.name_utf8())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(library_names(&server, &file1), vec!["lib1"]);
+        assert!(library_names(&server, &file2).is_empty());
+        assert!(server.owning_workspace_folder(&file2).is_none());
+    }
+
+    #[test]
+    fn merges_the_configs_of_workspace_folders_as_peers() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir1, folder1) = temp_root_uri();
+        let (_tempdir2, folder2) = temp_root_uri();
+        let file1 = write_file(&folder1, "file1.vhd", "entity ent1 is end entity;");
+        let file2 = write_file(&folder2, "file2.vhd", "entity ent2 is end entity;");
+        for folder in [&folder1, &folder2] {
+            std::fs::create_dir(folder.to_file_path().unwrap().join(".vscode")).unwrap();
+        }
+        let config1 = write_file(
+            &folder1,
+            ".vscode/vhdl_ls.toml",
+            format!(
+                "top = ['lib.ent1']\n[libraries]\nlib.files = ['{}']\n[lint]\nunused = 'error'",
+                uri_to_file_name(&file1).to_string_lossy()
+            ),
+        );
+        let config2 = write_file(
+            &folder2,
+            ".vscode/vhdl_ls.toml",
+            format!(
+                "[libraries]\nlib.files = ['{}']",
+                uri_to_file_name(&file2).to_string_lossy()
+            ),
+        );
+
+        expect_loaded_config_messages(&mock, &config1);
+        expect_loaded_config_messages(&mock, &config2);
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            root_uri: Some(folder1.clone()),
+            workspace_folders: Some(vec![
+                WorkspaceFolder {
+                    uri: folder1,
+                    name: "folder1".to_owned(),
+                },
+                WorkspaceFolder {
+                    uri: folder2,
+                    name: "folder2".to_owned(),
+                },
+            ]),
+            ..Default::default()
+        };
+        server.initialize_request(initialize_params);
+
+        for file_name in [uri_to_file_name(&file1), uri_to_file_name(&file2)] {
+            let source = server.project.get_source(&file_name).unwrap();
+            assert_eq!(
+                server
+                    .project
+                    .library_mapping_of(&source)
+                    .iter()
+                    .map(|name| name.name_utf8())
+                    .collect::<Vec<_>>(),
+                vec!["lib"]
+            );
+        }
+        let config = server.project.config();
+        assert_eq!(
+            config.severities()[ErrorCode::Unused],
+            Some(Severity::Error)
+        );
+        assert_eq!(config.top_levels().len(), 1);
+    }
+
+    #[test]
+    fn shutdown_unregisters_capabilities() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+
+        expect_missing_config_messages(&mock);
+        mock.expect_request(
+            "client/registerCapability",
+            RegistrationParams {
+                registrations: vec![Registration {
+                    id: "workspace/didChangeWatchedFiles".to_owned(),
+                    method: "workspace/didChangeWatchedFiles".to_owned(),
+                    register_options: serde_json::to_value(
+                        DidChangeWatchedFilesRegistrationOptions {
+                            watchers: vec![FileSystemWatcher {
+                                glob_pattern: GlobPattern::String("**/vhdl_ls.toml".to_owned()),
+                                kind: None,
+                            }],
+                        },
+                    )
+                    .ok(),
+                }],
+            },
+        );
+        mock.expect_request(
+            "client/unregisterCapability",
+            UnregistrationParams {
+                unregisterations: vec![Unregistration {
+                    id: "workspace/didChangeWatchedFiles".to_owned(),
+                    method: "workspace/didChangeWatchedFiles".to_owned(),
+                }],
+            },
+        );
+
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            root_uri: Some(root_uri),
+            capabilities: ClientCapabilities {
+                workspace: Some(WorkspaceClientCapabilities {
+                    did_change_watched_files: Some(DidChangeWatchedFilesClientCapabilities {
+                        dynamic_registration: Some(true),
+                        relative_pattern_support: Some(false),
+                    }),
+                    ..WorkspaceClientCapabilities::default()
+                }),
+                ..ClientCapabilities::default()
+            },
+            ..Default::default()
+        };
+        server.initialize_request(initialize_params);
+        server.initialized_notification();
+        assert!(!server.is_shut_down());
+
+        server.shutdown_server();
+        assert!(server.is_shut_down());
+        // A repeated shutdown request does not unregister again
+        server.shutdown_server();
+        assert_eq!(server.exit_notification(), 0);
+    }
+
+    #[test]
+    fn exit_without_shutdown_is_an_error() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        expect_missing_config_messages(&mock);
+        initialize_server(&mut server, root_uri);
+        assert_eq!(server.exit_notification(), 1);
+    }
+
     #[test]
     fn update_config_file() {
         let (mock, mut server) = setup_server();
@@ -996,6 +1327,7 @@ lib.files = [
                     },
                 },
                 code: Some(NumberOrString::String("unresolved".to_owned())),
+                code_description: code_description(ErrorCode::Unresolved),
                 severity: Some(DiagnosticSeverity::ERROR),
                 source: Some("vhdl ls".to_owned()),
                 message: "No primary unit \'ent\' within library \'lib\'".to_owned(),
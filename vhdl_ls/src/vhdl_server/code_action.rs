@@ -0,0 +1,151 @@
+use crate::vhdl_server::{from_lsp_range, to_lsp_range, uri_to_file_name, VHDLServer};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionResponse,
+    NumberOrString, TextEdit, WorkspaceEdit,
+};
+use std::collections::HashMap;
+use vhdl_lang::{ErrorCode, FixRule};
+
+/// The rules whose fixes are offered as quick fixes for the diagnostics they resolve
+const QUICK_FIX_RULES: [FixRule; 1] = [FixRule::SuperfluousSensitivity];
+
+impl VHDLServer {
+    /// Quick fixes for the diagnostics within the requested range
+    pub fn code_action(&mut self, params: &CodeActionParams) -> Option<CodeActionResponse> {
+        let source = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))?;
+        let range = from_lsp_range(params.range);
+        let actions = self
+            .project
+            .list_fixes(&QUICK_FIX_RULES)
+            .into_iter()
+            .filter(|fix| {
+                fix.source == source
+                    && fix
+                        .edits
+                        .iter()
+                        .any(|edit| edit.range.start <= range.end && range.start <= edit.range.end)
+            })
+            .map(|fix| {
+                let edits = fix
+                    .edits
+                    .into_iter()
+                    .map(|edit| TextEdit::new(to_lsp_range(edit.range), edit.new_text))
+                    .collect();
+                let diagnostics = params
+                    .context
+                    .diagnostics
+                    .iter()
+                    .filter(|diagnostic| {
+                        fix_diagnostic_code(fix.rule).is_some_and(|code| {
+                            diagnostic.code
+                                == Some(NumberOrString::String(code.as_str().to_owned()))
+                        })
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title: fix.title,
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: (!diagnostics.is_empty()).then_some(diagnostics),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(HashMap::from([(params.text_document.uri.clone(), edits)])),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        Some(actions)
+    }
+}
+
+/// The code of the diagnostic that a fix of `rule` resolves
+fn fix_diagnostic_code(rule: FixRule) -> Option<ErrorCode> {
+    match rule {
+        FixRule::SuperfluousSensitivity => Some(ErrorCode::SuperfluousSensitivity),
+        FixRule::KeywordCase | FixRule::UnusedUse | FixRule::EndLabel => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vhdl_server::tests::{
+        expect_loaded_config_messages, setup_server, temp_root_uri, write_file,
+    };
+    use lsp_types::{
+        CodeActionContext, CodeActionOrCommand, CodeActionParams, InitializeParams, Position,
+        Range, TextDocumentIdentifier, TextEdit,
+    };
+    use regex::Regex;
+
+    #[test]
+    fn offers_quick_fix_removing_superfluous_sensitivity() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(
+            &root_uri,
+            "ent.vhd",
+            "\
+entity ent is
+  port (a, b : in bit; q : out bit);
+end entity;
+architecture rtl of ent is
+begin
+  process (a, b)
+  begin
+    q <= a;
+  end process;
+end architecture;
+",
+        );
+        std::fs::create_dir(root_uri.to_file_path().unwrap().join(".vscode")).unwrap();
+        let config_uri = write_file(
+            &root_uri,
+            ".vscode/vhdl_ls.toml",
+            format!(
+                "[libraries]\nstd.files = ['{}/../vhdl_libraries/std/*.vhd']\nlib.files = ['{}']",
+                env!("CARGO_MANIFEST_DIR"),
+                file_uri.to_file_path().unwrap().to_string_lossy()
+            ),
+        );
+        expect_loaded_config_messages(&mock, &config_uri);
+        mock.expect_notification_contains_regex(
+            "textDocument/publishDiagnostics",
+            Regex::new("Signal 'b' is in the sensitivity list but never read by the process")
+                .unwrap(),
+        );
+        #[allow(deprecated)]
+        server.initialize_request(InitializeParams {
+            root_uri: Some(root_uri),
+            ..Default::default()
+        });
+        server.initialized_notification();
+
+        let params = |line: u32, character: u32| CodeActionParams {
+            text_document: TextDocumentIdentifier::new(file_uri.clone()),
+            range: Range::new(
+                Position::new(line, character),
+                Position::new(line, character),
+            ),
+            context: CodeActionContext::default(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let actions = server.code_action(&params(5, 14)).unwrap();
+        assert_eq!(actions.len(), 1);
+        let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            panic!("Expected a code action");
+        };
+        assert_eq!(action.title, "Remove signal from sensitivity list");
+        assert_eq!(
+            action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&file_uri],
+            vec![TextEdit::new(
+                Range::new(Position::new(5, 12), Position::new(5, 15)),
+                "".to_owned()
+            )]
+        );
+        assert_eq!(server.code_action(&params(7, 4)).unwrap(), vec![]);
+    }
+}
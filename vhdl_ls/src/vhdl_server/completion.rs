@@ -1,7 +1,8 @@
-use crate::vhdl_server::{from_lsp_pos, uri_to_file_name, VHDLServer};
+use crate::vhdl_server::config_file::is_config_file;
+use crate::vhdl_server::{from_lsp_pos, to_lsp_range, uri_to_file_name, VHDLServer};
 use lsp_types::{
-    CompletionItem, CompletionItemKind, CompletionList, CompletionParams, Documentation,
-    InsertTextFormat, MarkupContent, MarkupKind,
+    CompletionItem, CompletionItemKind, CompletionItemLabelDetails, CompletionList,
+    CompletionParams, Documentation, InsertTextFormat, MarkupContent, MarkupKind, TextEdit,
 };
 use vhdl_lang::ast::{Designator, ObjectClass};
 use vhdl_lang::{kind_str, AnyEntKind, Design, EntRef, InterfaceEnt, Overloaded};
@@ -25,6 +26,8 @@ impl VHDLServer {
                 if self.client_supports_snippets() {
                     item.insert_text_format = Some(InsertTextFormat::SNIPPET);
                     item.insert_text = Some(format!("{} => $1,", item.insert_text.unwrap()));
+                } else {
+                    item.insert_text = Some(format!("{} => ", item.insert_text.unwrap()));
                 }
                 item
             }
@@ -119,17 +122,45 @@ impl VHDLServer {
             }
             vhdl_lang::CompletionItem::Attribute(attribute) => CompletionItem {
                 label: format!("{attribute}"),
-                detail: Some(format!("{attribute}")),
+                detail: Some(
+                    attribute
+                        .documentation()
+                        .map(|doc| doc.to_string())
+                        .unwrap_or_else(|| format!("{attribute}")),
+                ),
                 insert_text: Some(format!("{attribute}")),
                 kind: Some(CompletionItemKind::REFERENCE),
                 ..Default::default()
             },
+            vhdl_lang::CompletionItem::AutoImport(ent, edits) => {
+                let mut item = entity_to_completion_item(ent);
+                item.label_details = Some(CompletionItemLabelDetails {
+                    detail: None,
+                    description: edits
+                        .first()
+                        .map(|edit| edit.new_text.trim().replace('\n', " ")),
+                });
+                item.additional_text_edits = Some(
+                    edits
+                        .into_iter()
+                        .map(|edit| TextEdit {
+                            range: to_lsp_range(edit.range),
+                            new_text: edit.new_text,
+                        })
+                        .collect(),
+                );
+                item
+            }
         }
     }
 
     /// Called when the client requests a completion.
     /// This function looks in the source code to find suitable options and then returns them
     pub fn request_completion(&mut self, params: &CompletionParams) -> CompletionList {
+        let uri = &params.text_document_position.text_document.uri;
+        if is_config_file(uri) {
+            return self.config_document_completion(uri, params.text_document_position.position);
+        }
         let binding = uri_to_file_name(&params.text_document_position.text_document.uri);
         let file = binding.as_path();
         // 1) get source position, and source file
@@ -151,7 +182,16 @@ impl VHDLServer {
             .project
             .list_completion_options(&source, cursor)
             .into_iter()
-            .map(|item| self.completion_item_to_lsp_item(item))
+            .enumerate()
+            .map(|(idx, item)| {
+                let is_formal = matches!(item, vhdl_lang::CompletionItem::Formal(_));
+                let mut item = self.completion_item_to_lsp_item(item);
+                // Formals are listed in declaration order
+                if is_formal {
+                    item.sort_text = Some(format!("{idx:04}"));
+                }
+                item
+            })
             .collect();
 
         CompletionList {
@@ -195,6 +235,7 @@ fn entity_kind_to_completion_kind(kind: &AnyEntKind) -> CompletionItemKind {
         AnyEntKind::ExternalAlias { .. } | AnyEntKind::ObjectAlias { .. } => {
             CompletionItemKind::FIELD
         }
+        AnyEntKind::Alias(ent) => entity_kind_to_completion_kind(ent.kind()),
         AnyEntKind::File(_) | AnyEntKind::InterfaceFile(_) => CompletionItemKind::FILE,
         AnyEntKind::Component(_) => CompletionItemKind::MODULE,
         AnyEntKind::Attribute(_) => CompletionItemKind::REFERENCE,
@@ -0,0 +1,624 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Editing support for the `vhdl_ls.toml` configuration file itself.
+//! Completions and diagnostics are derived from the schema of the configuration file.
+
+use super::settings_schema::config_file_schema;
+use crate::vhdl_server::{from_lsp_range, uri_to_file_name, VHDLServer};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionList, CompletionTextEdit, Diagnostic,
+    DiagnosticSeverity, Documentation, Position, PublishDiagnosticsParams, Range,
+    TextDocumentContentChangeEvent, TextEdit, Url,
+};
+use serde_json::Value;
+use std::path::Path;
+use toml_edit::{ImDocument, TableLike};
+use vhdl_lang::Source;
+
+const CONFIG_FILE_NAME: &str = "vhdl_ls.toml";
+
+pub(super) fn is_config_file(uri: &Url) -> bool {
+    uri_to_file_name(uri)
+        .file_name()
+        .is_some_and(|name| name == CONFIG_FILE_NAME)
+}
+
+impl VHDLServer {
+    pub(super) fn config_document_opened(&mut self, uri: &Url, text: &str) {
+        self.config_documents
+            .insert(uri.clone(), Source::inline(&uri_to_file_name(uri), text));
+        self.publish_config_document_diagnostics(uri);
+    }
+
+    pub(super) fn config_document_changed(
+        &mut self,
+        uri: &Url,
+        changes: &[TextDocumentContentChangeEvent],
+    ) {
+        let Some(source) = self.config_documents.get(uri) else {
+            return;
+        };
+        for change in changes {
+            source.change(change.range.map(from_lsp_range).as_ref(), &change.text);
+        }
+        self.publish_config_document_diagnostics(uri);
+    }
+
+    pub(super) fn config_document_completion(
+        &self,
+        uri: &Url,
+        position: Position,
+    ) -> CompletionList {
+        let items = match (
+            self.config_document_text(uri),
+            uri_to_file_name(uri).parent(),
+        ) {
+            (Some(text), Some(dir)) => config_file_completions(&text, position, dir),
+            _ => Vec::new(),
+        };
+        CompletionList {
+            items,
+            is_incomplete: false,
+        }
+    }
+
+    fn publish_config_document_diagnostics(&mut self, uri: &Url) {
+        if self.settings.no_lint {
+            return;
+        }
+        let Some(text) = self.config_document_text(uri) else {
+            return;
+        };
+        self.rpc.send_notification(
+            "textDocument/publishDiagnostics",
+            PublishDiagnosticsParams {
+                uri: uri.clone(),
+                diagnostics: config_file_diagnostics(&text),
+                version: None,
+            },
+        );
+    }
+
+    fn config_document_text(&self, uri: &Url) -> Option<String> {
+        let contents = self.config_documents.get(uri)?.contents();
+        Some(
+            (0..contents.num_lines())
+                .filter_map(|lineno| contents.get_line(lineno))
+                .collect(),
+        )
+    }
+}
+
+/// Syntax errors and keys that are not part of the configuration file schema.
+fn config_file_diagnostics(text: &str) -> Vec<Diagnostic> {
+    let document = match ImDocument::parse(text) {
+        Ok(document) => document,
+        Err(err) => {
+            return vec![diagnostic(
+                text,
+                err.span(),
+                err.message().trim(),
+                DiagnosticSeverity::ERROR,
+            )]
+        }
+    };
+    let mut diagnostics = Vec::new();
+    check_unknown_keys(
+        text,
+        document.as_table(),
+        &config_file_schema(),
+        &mut diagnostics,
+    );
+    diagnostics
+}
+
+fn check_unknown_keys(
+    text: &str,
+    table: &dyn TableLike,
+    schema: &Value,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (name, item) in table.iter() {
+        match property_schema(schema, name) {
+            Some(schema) => {
+                if let Some(table) = item.as_table_like() {
+                    check_unknown_keys(text, table, schema, diagnostics);
+                }
+            }
+            None => {
+                let span = table
+                    .get_key_value(name)
+                    .and_then(|(key, _)| key.span())
+                    .or_else(|| item.span());
+                diagnostics.push(diagnostic(
+                    text,
+                    span,
+                    &format!("Unknown key '{name}'"),
+                    DiagnosticSeverity::WARNING,
+                ));
+            }
+        }
+    }
+}
+
+fn diagnostic(
+    text: &str,
+    span: Option<std::ops::Range<usize>>,
+    message: &str,
+    severity: DiagnosticSeverity,
+) -> Diagnostic {
+    let span = span.unwrap_or_default();
+    Diagnostic {
+        range: Range::new(
+            offset_to_position(text, span.start),
+            offset_to_position(text, span.end),
+        ),
+        severity: Some(severity),
+        source: Some("vhdl ls".to_owned()),
+        message: message.to_owned(),
+        ..Default::default()
+    }
+}
+
+/// The schema of the property `name` of an object schema
+fn property_schema<'a>(schema: &'a Value, name: &str) -> Option<&'a Value> {
+    schema["properties"].get(name).or_else(|| {
+        schema
+            .get("additionalProperties")
+            .filter(|schema| schema.is_object())
+    })
+}
+
+/// The schema at a path of keys such as `["libraries", "lib", "files"]`
+fn path_schema<'a>(schema: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(schema, |schema, name| property_schema(schema, name))
+}
+
+fn config_file_completions(text: &str, position: Position, dir: &Path) -> Vec<CompletionItem> {
+    let schema = config_file_schema();
+    let before = &text[..position_to_offset(text, position)];
+    let line = &before[before.rfind('\n').map_or(0, |idx| idx + 1)..];
+
+    if let Some(header) = line.trim_start().strip_prefix('[') {
+        if header.contains('.') {
+            return Vec::new();
+        }
+        return key_completions(&schema, &[], text)
+            .into_iter()
+            .filter(|item| item.kind == Some(CompletionItemKind::MODULE))
+            .collect();
+    }
+
+    let mut path = current_section(before);
+    match current_statement(before).split_once('=') {
+        None => {
+            let mut keys = dotted_key(line.trim_start());
+            // The last key is being typed
+            keys.pop();
+            path.extend(keys);
+            match path_schema(&schema, &path) {
+                Some(schema) => key_completions(schema, &path, text),
+                None => Vec::new(),
+            }
+        }
+        Some((key, value)) => {
+            path.extend(dotted_key(key));
+            match path_schema(&schema, &path) {
+                Some(schema) => value_completions(schema, value, position, dir),
+                None => Vec::new(),
+            }
+        }
+    }
+}
+
+/// The keys of the most recent table header before the cursor
+fn current_section(before: &str) -> Vec<String> {
+    before
+        .split('\n')
+        .rev()
+        .skip(1)
+        .find_map(|line| {
+            let header = line.trim().strip_prefix('[')?;
+            Some(dotted_key(header.split(']').next().unwrap_or_default()))
+        })
+        .unwrap_or_default()
+}
+
+/// The text from the beginning of the key-value pair at the cursor,
+/// which may span multiple lines for arrays.
+fn current_statement(before: &str) -> &str {
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    let mut start = line_start;
+    for line in before[..line_start].split_inclusive('\n').rev() {
+        start -= line.len();
+        let line = line.split('#').next().unwrap_or_default();
+        if line.contains('=') {
+            // Only arrays that are still open continue on the following lines
+            let statement = &before[start..];
+            if statement.matches('[').count() > statement.matches(']').count() {
+                return statement;
+            }
+            break;
+        }
+        if line.trim_start().starts_with('[') {
+            break;
+        }
+    }
+    &before[line_start..]
+}
+
+fn dotted_key(key: &str) -> Vec<String> {
+    key.split('.')
+        .map(|key| key.trim().trim_matches(['"', '\'']).to_owned())
+        .collect()
+}
+
+fn key_completions(schema: &Value, path: &[String], text: &str) -> Vec<CompletionItem> {
+    let mut items: Vec<CompletionItem> = schema["properties"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, property)| {
+            let kind = if property["type"] == "object" {
+                CompletionItemKind::MODULE
+            } else {
+                CompletionItemKind::PROPERTY
+            };
+            CompletionItem {
+                label: name.clone(),
+                kind: Some(kind),
+                detail: property["description"].as_str().map(str::to_owned),
+                documentation: property["markdownDescription"]
+                    .as_str()
+                    .map(|doc| Documentation::String(doc.to_owned())),
+                ..Default::default()
+            }
+        })
+        .collect();
+    if path == ["libraries"] {
+        items.extend(library_names(text).into_iter().map(|name| CompletionItem {
+            label: name,
+            kind: Some(CompletionItemKind::MODULE),
+            detail: Some("library".to_owned()),
+            ..Default::default()
+        }));
+    }
+    items
+}
+
+/// The names of all libraries in the configuration file.
+/// Lines are scanned individually since the file is usually incomplete while editing.
+fn library_names(text: &str) -> Vec<String> {
+    let mut section = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        let keys = if let Some(header) = line.strip_prefix('[') {
+            section = dotted_key(header.split(']').next().unwrap_or_default());
+            section.clone()
+        } else if let Some((key, _)) = line.split_once('=') {
+            section.iter().cloned().chain(dotted_key(key)).collect()
+        } else {
+            continue;
+        };
+        if let [libraries, name, _, ..] | [libraries, name] = keys.as_slice() {
+            if libraries == "libraries" && !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
+
+fn value_completions(
+    schema: &Value,
+    value: &str,
+    position: Position,
+    dir: &Path,
+) -> Vec<CompletionItem> {
+    let string_prefix = open_string(value);
+    if schema["type"] == "array" {
+        return match string_prefix {
+            Some(prefix) => path_completions(dir, prefix, position),
+            None => Vec::new(),
+        };
+    }
+
+    let values = match schema.get("enum") {
+        Some(Value::Array(values)) => values.clone(),
+        _ if schema["type"] == "boolean" => vec![Value::from(true), Value::from(false)],
+        _ => Vec::new(),
+    };
+    values
+        .into_iter()
+        .map(|value| {
+            let (label, insert_text) = match value {
+                Value::String(value) if string_prefix.is_none() => {
+                    (value.clone(), format!("\"{value}\""))
+                }
+                Value::String(value) => (value.clone(), value),
+                value => (value.to_string(), value.to_string()),
+            };
+            CompletionItem {
+                label,
+                kind: Some(CompletionItemKind::VALUE),
+                insert_text: Some(insert_text),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// The contents of the string that is open at the end of `value`, if any
+fn open_string(value: &str) -> Option<&str> {
+    let mut quote = None;
+    let mut start = 0;
+    for (idx, chr) in value.char_indices() {
+        match quote {
+            None if chr == '"' || chr == '\'' => {
+                quote = Some(chr);
+                start = idx + 1;
+            }
+            Some(open) if chr == open => quote = None,
+            _ => {}
+        }
+    }
+    quote.map(|_| &value[start..])
+}
+
+/// Suggests directories, VHDL files and glob patterns relative to the configuration file
+/// that complete `prefix`, which ends at `position`.
+fn path_completions(dir: &Path, prefix: &str, position: Position) -> Vec<CompletionItem> {
+    let parent = match prefix.rsplit_once('/') {
+        Some((parent, _)) => format!("{parent}/"),
+        None => String::new(),
+    };
+    let Ok(entries) = std::fs::read_dir(dir.join(&parent)) else {
+        return Vec::new();
+    };
+
+    let mut paths = Vec::new();
+    let mut has_vhdl_files = false;
+    let mut has_directories = false;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        if entry.path().is_dir() {
+            has_directories = true;
+            paths.push((format!("{parent}{name}/"), CompletionItemKind::FOLDER));
+        } else if is_vhdl_file(&name) {
+            has_vhdl_files = true;
+            paths.push((format!("{parent}{name}"), CompletionItemKind::FILE));
+        }
+    }
+    paths.sort_by(|(a, _), (b, _)| a.cmp(b));
+    if has_vhdl_files {
+        paths.push((format!("{parent}*.vhd"), CompletionItemKind::FILE));
+    }
+    if has_directories {
+        paths.push((format!("{parent}**/*.vhd"), CompletionItemKind::FILE));
+    }
+
+    let start = Position::new(
+        position.line,
+        position
+            .character
+            .saturating_sub(prefix.encode_utf16().count() as u32),
+    );
+    paths
+        .into_iter()
+        .map(|(path, kind)| CompletionItem {
+            label: path.clone(),
+            kind: Some(kind),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(
+                Range::new(start, position),
+                path,
+            ))),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn is_vhdl_file(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("vhd") || ext.eq_ignore_ascii_case("vhdl"))
+}
+
+fn offset_to_position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].encode_utf16().count() as u32,
+    )
+}
+
+fn position_to_offset(text: &str, position: Position) -> usize {
+    let mut offset = 0;
+    for (lineno, line) in text.split_inclusive('\n').enumerate() {
+        if lineno == position.line as usize {
+            let mut character = 0;
+            for (idx, chr) in line.char_indices() {
+                if character >= position.character as usize || chr == '\n' {
+                    return offset + idx;
+                }
+                character += chr.len_utf16();
+            }
+            return offset + line.len();
+        }
+        offset += line.len();
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Completion labels at the end of `text`
+    fn labels_at_end(text: &str, dir: &Path) -> Vec<String> {
+        let position = offset_to_position(text, text.len());
+        config_file_completions(text, position, dir)
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    }
+
+    #[test]
+    fn reports_unknown_keys() {
+        let text = "\
+standard = \"2008\"
+unknown = 1
+
+[libraries]
+lib.files = ['a.vhd']
+lib.is_synthesizable = true
+lib.typo = true
+
+[conditional_analysis]
+target = 'sim'
+
+[lint]
+unused = false
+not_a_code = 'error'
+";
+        let diagnostics = config_file_diagnostics(text);
+        let messages: Vec<(u32, &str)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.range.start.line, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (1, "Unknown key 'unknown'"),
+                (6, "Unknown key 'typo'"),
+                (13, "Unknown key 'not_a_code'"),
+            ]
+        );
+        assert_eq!(
+            diagnostics[1].range,
+            Range::new(Position::new(6, 4), Position::new(6, 8))
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let diagnostics = config_file_diagnostics("[libraries\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+    }
+
+    #[test]
+    fn completes_keys_and_values() {
+        let dir = Path::new("");
+        assert_eq!(
+            labels_at_end("", dir),
+            vec![
+                "banned",
+                "conditional_analysis",
+                "format",
+                "include",
+                "libraries",
+                "lint",
+                "standard",
+                "standard_libraries",
+                "top"
+            ]
+        );
+        assert_eq!(
+            labels_at_end("[", dir),
+            vec![
+                "banned",
+                "conditional_analysis",
+                "format",
+                "libraries",
+                "lint"
+            ]
+        );
+        assert_eq!(
+            labels_at_end("[format]\n", dir),
+            vec!["indent_width", "use_tabs"]
+        );
+        assert_eq!(
+            labels_at_end("[banned]\n", dir),
+            vec!["constructs", "packages"]
+        );
+        assert_eq!(
+            labels_at_end("standard = ", dir),
+            vec!["1993", "2008", "2019"]
+        );
+        assert_eq!(
+            labels_at_end("[libraries]\nlib.files = []\n", dir),
+            vec!["lib"]
+        );
+        assert_eq!(
+            labels_at_end("[libraries]\nlib.files = []\nlib.", dir),
+            vec![
+                "filelists",
+                "files",
+                "ignores_initial_values",
+                "is_synthesizable",
+                "is_testbench",
+                "is_third_party",
+                "standard"
+            ]
+        );
+        assert_eq!(
+            labels_at_end("[libraries.lib]\nis_third_party = ", dir),
+            vec!["true", "false"]
+        );
+        assert_eq!(
+            labels_at_end("[lint]\nunused = ", dir),
+            vec!["hint", "info", "warning", "error", "ignore", "true", "false"]
+        );
+        assert!(labels_at_end("[lint]\n", dir).contains(&"unused".to_owned()));
+    }
+
+    #[test]
+    fn completes_paths_relative_to_config_file() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path();
+        fs::create_dir(dir.join("src")).unwrap();
+        fs::write(dir.join("src").join("top.vhd"), "").unwrap();
+        fs::write(dir.join("src").join("notes.txt"), "").unwrap();
+        fs::create_dir(dir.join("src").join("sub")).unwrap();
+
+        assert_eq!(
+            labels_at_end("[libraries]\nlib.files = [\n  'src/", dir),
+            vec!["src/sub/", "src/top.vhd", "src/*.vhd", "src/**/*.vhd"]
+        );
+
+        let text = "[libraries]\nlib.files = ['src/t";
+        let position = offset_to_position(text, text.len());
+        let items = config_file_completions(text, position, dir);
+        assert_eq!(
+            items[1].text_edit,
+            Some(CompletionTextEdit::Edit(TextEdit::new(
+                Range::new(Position::new(1, 14), position),
+                "src/top.vhd".to_owned()
+            )))
+        );
+    }
+
+    #[test]
+    fn converts_between_offsets_and_positions() {
+        let text = "ab\ncd\n";
+        for offset in 0..text.len() {
+            assert_eq!(
+                position_to_offset(text, offset_to_position(text, offset)),
+                offset
+            );
+        }
+        assert_eq!(offset_to_position(text, 4), Position::new(1, 1));
+    }
+}
@@ -0,0 +1,214 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::vhdl_server::{srcpos_to_location, VHDLServer};
+use lsp_types::request::Request;
+use lsp_types::Location;
+use serde::{Deserialize, Serialize};
+use vhdl_lang::{HierarchyNode, HierarchyNodeKind};
+
+/// Custom request that returns the instantiation tree below a top-level entity,
+/// i.e. to show the design hierarchy in a tree view.
+/// Returns `null` if the entity does not exist.
+pub enum DesignHierarchy {}
+
+impl Request for DesignHierarchy {
+    type Params = DesignHierarchyParams;
+    type Result = Option<DesignHierarchyNode>;
+    const METHOD: &'static str = "vhdl/designHierarchy";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DesignHierarchyParams {
+    pub library: String,
+    pub entity: String,
+    /// The architecture of the top-level entity.
+    /// Defaults to the last architecture in source order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub architecture: Option<String>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DesignHierarchyNode {
+    /// The label of the statement, absent for the top-level entity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub kind: DesignHierarchyNodeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub library: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub architecture: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub generics: Vec<GenericValue>,
+    /// The location of the label or of the name of the top-level entity
+    pub location: Location,
+    pub children: Vec<DesignHierarchyNode>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DesignHierarchyNodeKind {
+    Instance,
+    Block,
+    Generate,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenericValue {
+    pub name: String,
+    /// The value of the generic, absent if it is not static
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl From<HierarchyNode> for DesignHierarchyNode {
+    fn from(node: HierarchyNode) -> Self {
+        let mut result = DesignHierarchyNode {
+            label: node.label,
+            kind: DesignHierarchyNodeKind::Instance,
+            library: None,
+            entity: None,
+            architecture: None,
+            generics: Vec::new(),
+            location: srcpos_to_location(&node.pos),
+            children: node.children.into_iter().map(Into::into).collect(),
+        };
+        match node.kind {
+            HierarchyNodeKind::Instance(info) => {
+                result.library = Some(info.library);
+                result.entity = Some(info.entity);
+                result.architecture = info.architecture;
+                result.generics = info
+                    .generics
+                    .into_iter()
+                    .map(|(name, value)| GenericValue { name, value })
+                    .collect();
+            }
+            HierarchyNodeKind::Block => result.kind = DesignHierarchyNodeKind::Block,
+            HierarchyNodeKind::Generate => result.kind = DesignHierarchyNodeKind::Generate,
+        }
+        result
+    }
+}
+
+impl VHDLServer {
+    pub fn design_hierarchy(
+        &mut self,
+        params: &DesignHierarchyParams,
+    ) -> Option<DesignHierarchyNode> {
+        self.project
+            .design_hierarchy(
+                &params.library,
+                &params.entity,
+                params.architecture.as_deref(),
+            )
+            .map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vhdl_server::tests::{
+        expect_loaded_config_messages, setup_server, temp_root_uri, write_file,
+    };
+    use lsp_types::InitializeParams;
+    use serde_json::json;
+
+    #[test]
+    fn returns_hierarchy_as_json() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(
+            &root_uri,
+            "top.vhd",
+            "\
+entity leaf is
+  generic (width : natural := 1);
+end entity;
+architecture rtl of leaf is
+begin
+end architecture;
+entity top is
+end entity;
+architecture rtl of top is
+begin
+  u_leaf: entity work.leaf generic map (width => 8);
+end architecture;
+",
+        );
+        std::fs::create_dir(root_uri.to_file_path().unwrap().join(".vscode")).unwrap();
+        let config_uri = write_file(
+            &root_uri,
+            ".vscode/vhdl_ls.toml",
+            format!(
+                "[libraries]\nstd.files = ['{}/../vhdl_libraries/std/*.vhd']\nlib.files = ['{}']",
+                env!("CARGO_MANIFEST_DIR"),
+                file_uri.to_file_path().unwrap().to_string_lossy()
+            ),
+        );
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        #[allow(deprecated)]
+        server.initialize_request(InitializeParams {
+            root_uri: Some(root_uri),
+            ..Default::default()
+        });
+        mock.expect_notification_contains(
+            "textDocument/publishDiagnostics",
+            "Unused declaration of generic 'width'",
+        );
+        server.initialized_notification();
+
+        let location = |line: u32, start: u32, end: u32| {
+            json!({
+                "uri": file_uri,
+                "range": {
+                    "start": {"line": line, "character": start},
+                    "end": {"line": line, "character": end},
+                },
+            })
+        };
+        let hierarchy = server.design_hierarchy(&DesignHierarchyParams {
+            library: "lib".to_owned(),
+            entity: "top".to_owned(),
+            architecture: None,
+        });
+        assert_eq!(
+            serde_json::to_value(hierarchy).unwrap(),
+            json!({
+                "kind": "instance",
+                "library": "lib",
+                "entity": "top",
+                "architecture": "rtl",
+                "location": location(6, 7, 10),
+                "children": [{
+                    "label": "u_leaf",
+                    "kind": "instance",
+                    "library": "lib",
+                    "entity": "leaf",
+                    "architecture": "rtl",
+                    "generics": [{"name": "width", "value": "8"}],
+                    "location": location(10, 2, 8),
+                    "children": [],
+                }],
+            })
+        );
+        assert_eq!(
+            server.design_hierarchy(&DesignHierarchyParams {
+                library: "lib".to_owned(),
+                entity: "missing".to_owned(),
+                architecture: None,
+            }),
+            None
+        );
+    }
+}
@@ -0,0 +1,168 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::vhdl_server::{srcpos_to_location, VHDLServer};
+use lsp_types::request::Request;
+use lsp_types::Location;
+use serde::{Deserialize, Serialize};
+use vhdl_lang::ast::{AnyKind, PrimaryKind, SecondaryKind};
+
+/// Custom request that lists all libraries and their design units,
+/// i.e. to show the project in a tree view.
+pub enum ListDesignUnits {}
+
+impl Request for ListDesignUnits {
+    type Params = ();
+    type Result = Vec<Library>;
+    const METHOD: &'static str = "vhdl/listDesignUnits";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Library {
+    pub name: String,
+    /// Primary units sorted by name, each followed by its secondary units
+    pub units: Vec<DesignUnit>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DesignUnit {
+    pub name: String,
+    pub kind: DesignUnitKind,
+    /// The primary unit of a secondary unit, i.e. the entity of an architecture
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_unit: Option<String>,
+    /// The location of the name of the unit
+    pub location: Location,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DesignUnitKind {
+    Entity,
+    Architecture,
+    Package,
+    PackageBody,
+    PackageInstance,
+    Configuration,
+    Context,
+    VerificationUnit,
+}
+
+impl From<AnyKind> for DesignUnitKind {
+    fn from(kind: AnyKind) -> Self {
+        match kind {
+            AnyKind::Primary(PrimaryKind::Entity) => DesignUnitKind::Entity,
+            AnyKind::Primary(PrimaryKind::Package) => DesignUnitKind::Package,
+            AnyKind::Primary(PrimaryKind::PackageInstance) => DesignUnitKind::PackageInstance,
+            AnyKind::Primary(PrimaryKind::Configuration) => DesignUnitKind::Configuration,
+            AnyKind::Primary(PrimaryKind::Context) => DesignUnitKind::Context,
+            AnyKind::Primary(PrimaryKind::VerificationUnit) => DesignUnitKind::VerificationUnit,
+            AnyKind::Secondary(SecondaryKind::Architecture) => DesignUnitKind::Architecture,
+            AnyKind::Secondary(SecondaryKind::PackageBody) => DesignUnitKind::PackageBody,
+        }
+    }
+}
+
+impl VHDLServer {
+    pub fn list_design_units(&mut self) -> Vec<Library> {
+        self.project
+            .list_design_units()
+            .into_iter()
+            .map(|library| Library {
+                name: library.name,
+                units: library
+                    .units
+                    .into_iter()
+                    .map(|unit| DesignUnit {
+                        name: unit.name,
+                        kind: unit.kind.into(),
+                        primary_unit: unit.primary_name,
+                        location: srcpos_to_location(&unit.pos),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vhdl_server::tests::{
+        expect_loaded_config_messages, setup_server, temp_root_uri, write_file,
+    };
+    use lsp_types::InitializeParams;
+    use serde_json::json;
+
+    #[test]
+    fn lists_libraries_with_units_as_json() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(
+            &root_uri,
+            "ent.vhd",
+            "\
+entity ent is
+end entity;
+architecture rtl of ent is
+begin
+end architecture;
+",
+        );
+        std::fs::create_dir(root_uri.to_file_path().unwrap().join(".vscode")).unwrap();
+        let config_uri = write_file(
+            &root_uri,
+            ".vscode/vhdl_ls.toml",
+            format!(
+                "[libraries]\nlib.files = ['{}']",
+                file_uri.to_file_path().unwrap().to_string_lossy()
+            ),
+        );
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        #[allow(deprecated)]
+        server.initialize_request(InitializeParams {
+            root_uri: Some(root_uri),
+            ..Default::default()
+        });
+        server.initialized_notification();
+
+        let libraries = server.list_design_units();
+        let library = libraries.iter().find(|lib| lib.name == "lib").unwrap();
+        assert_eq!(
+            serde_json::to_value(library).unwrap(),
+            json!({
+                "name": "lib",
+                "units": [
+                    {
+                        "name": "ent",
+                        "kind": "entity",
+                        "location": {
+                            "uri": file_uri,
+                            "range": {
+                                "start": {"line": 0, "character": 7},
+                                "end": {"line": 0, "character": 10},
+                            },
+                        },
+                    },
+                    {
+                        "name": "rtl",
+                        "kind": "architecture",
+                        "primaryUnit": "ent",
+                        "location": {
+                            "uri": file_uri,
+                            "range": {
+                                "start": {"line": 2, "character": 13},
+                                "end": {"line": 2, "character": 16},
+                            },
+                        },
+                    },
+                ],
+            })
+        );
+    }
+}
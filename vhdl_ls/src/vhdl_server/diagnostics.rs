@@ -1,11 +1,12 @@
+use crate::vhdl_server::explain::code_description;
 use crate::vhdl_server::{file_name_to_uri, to_lsp_range, VHDLServer};
 use fnv::FnvHashMap;
 use lsp_types::{
-    DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString,
+    DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag, Location, NumberOrString,
     PublishDiagnosticsParams, Url,
 };
 use std::collections::hash_map::Entry;
-use vhdl_lang::{Diagnostic, Severity, SeverityMap};
+use vhdl_lang::{Diagnostic, ErrorCode, Severity, SeverityMap};
 
 impl VHDLServer {
     pub fn publish_diagnostics(&mut self) {
@@ -133,10 +134,22 @@ fn to_lsp_diagnostic(
         None
     };
 
+    // Lets the editor fade out code that is never executed, elaborated or analyzed
+    let tags = if matches!(
+        diagnostic.code,
+        ErrorCode::UnreachableCode | ErrorCode::InactiveCode
+    ) {
+        Some(vec![DiagnosticTag::UNNECESSARY])
+    } else {
+        None
+    };
+
     Some(lsp_types::Diagnostic {
         range: to_lsp_range(diagnostic.pos.range()),
         severity: Some(severity),
+        tags,
         code: Some(NumberOrString::String(format!("{}", diagnostic.code))),
+        code_description: code_description(diagnostic.code),
         source: Some("vhdl ls".to_owned()),
         message: diagnostic.message,
         related_information,
@@ -146,6 +159,8 @@ fn to_lsp_diagnostic(
 
 #[cfg(test)]
 pub mod tests {
+    use super::to_lsp_diagnostic;
+    use crate::vhdl_server::explain::code_description;
     use crate::vhdl_server::tests::{
         expect_loaded_config_messages, initialize_server, setup_server, temp_root_uri,
         write_config, write_file,
@@ -156,6 +171,7 @@ pub mod tests {
         VersionedTextDocumentIdentifier,
     };
     use regex::Regex;
+    use vhdl_lang::ErrorCode;
 
     #[test]
     fn only_send_diagnostics_once() {
@@ -198,6 +214,7 @@ lib.files = [
                     Position::new(0, "architecture rtl of ent3".len() as u32),
                 ),
                 code: Some(NumberOrString::String("unresolved".to_owned())),
+                code_description: code_description(ErrorCode::Unresolved),
                 severity: Some(DiagnosticSeverity::ERROR),
                 source: Some("vhdl ls".to_owned()),
                 message: "No primary unit \'ent3\' within library \'lib\'".to_owned(),
@@ -234,4 +251,30 @@ lib.files = [
             }],
         })
     }
+
+    #[test]
+    fn unreachable_code_is_tagged_as_unnecessary() {
+        let source =
+            vhdl_lang::Source::inline(std::path::Path::new("file.vhd"), "wait;\nreport \"x\";");
+        let pos = source.pos(
+            vhdl_lang::Position::new(1, 0),
+            vhdl_lang::Position::new(1, 11),
+        );
+        for (code, tags) in [
+            (
+                ErrorCode::UnreachableCode,
+                Some(vec![lsp_types::DiagnosticTag::UNNECESSARY]),
+            ),
+            (
+                ErrorCode::InactiveCode,
+                Some(vec![lsp_types::DiagnosticTag::UNNECESSARY]),
+            ),
+            (ErrorCode::Unresolved, None),
+        ] {
+            let diagnostic = vhdl_lang::Diagnostic::new(pos.clone(), "message", code);
+            let lsp_diagnostic =
+                to_lsp_diagnostic(diagnostic, &vhdl_lang::SeverityMap::default()).unwrap();
+            assert_eq!(lsp_diagnostic.tags, tags);
+        }
+    }
 }
@@ -0,0 +1,175 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::vhdl_server::{from_lsp_pos, srcpos_to_location, uri_to_file_name, VHDLServer};
+use lsp_types::request::Request;
+use lsp_types::{Location, TextDocumentPositionParams};
+use serde::{Deserialize, Serialize};
+use vhdl_lang::{AccessLevel, SignalAccess};
+
+/// Custom request that lists the places that drive the signal or port at the given position.
+pub enum GotoDrivers {}
+
+impl Request for GotoDrivers {
+    type Params = TextDocumentPositionParams;
+    type Result = Vec<Access>;
+    const METHOD: &'static str = "vhdl/gotoDrivers";
+}
+
+/// Custom request that lists the places that read the signal or port at the given position.
+pub enum GotoLoads {}
+
+impl Request for GotoLoads {
+    type Params = TextDocumentPositionParams;
+    type Result = Vec<Access>;
+    const METHOD: &'static str = "vhdl/gotoLoads";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Access {
+    /// The location of the name of the driven or read signal
+    pub location: Location,
+    /// The name of the signal or port, which is a port of another entity
+    /// when the signal is accessed through a port map
+    pub name: String,
+    pub level: Level,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Level {
+    /// Within the architecture of the signal
+    Local,
+    /// Within an instantiated entity, one level down
+    Instance,
+    /// Within an architecture that instantiates the entity of the port, one level up
+    Parent,
+}
+
+impl From<SignalAccess> for Access {
+    fn from(access: SignalAccess) -> Self {
+        Access {
+            location: srcpos_to_location(&access.pos),
+            name: access.name,
+            level: match access.level {
+                AccessLevel::Local => Level::Local,
+                AccessLevel::Instance => Level::Instance,
+                AccessLevel::Parent => Level::Parent,
+            },
+        }
+    }
+}
+
+impl VHDLServer {
+    pub fn goto_drivers(&mut self, params: &TextDocumentPositionParams) -> Vec<Access> {
+        let Some(source) = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))
+        else {
+            return Vec::new();
+        };
+        self.project
+            .find_drivers(&source, from_lsp_pos(params.position))
+            .into_iter()
+            .map(Access::from)
+            .collect()
+    }
+
+    pub fn goto_loads(&mut self, params: &TextDocumentPositionParams) -> Vec<Access> {
+        let Some(source) = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))
+        else {
+            return Vec::new();
+        };
+        self.project
+            .find_loads(&source, from_lsp_pos(params.position))
+            .into_iter()
+            .map(Access::from)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vhdl_server::tests::{
+        expect_loaded_config_messages, setup_server, temp_root_uri, write_file,
+    };
+    use lsp_types::{InitializeParams, TextDocumentIdentifier};
+    use serde_json::json;
+
+    #[test]
+    fn lists_drivers_and_loads_as_json() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(
+            &root_uri,
+            "top.vhd",
+            "\
+entity child is
+  port (a : in bit; y : out bit);
+end entity;
+architecture rtl of child is
+  signal b : bit;
+begin
+  b <= a; y <= b;
+end architecture;
+entity top is
+end entity;
+architecture rtl of top is
+  signal s : bit;
+begin
+  s <= '1';
+  inst: entity work.child port map (a => s);
+end architecture;
+",
+        );
+        std::fs::create_dir(root_uri.to_file_path().unwrap().join(".vscode")).unwrap();
+        let config_uri = write_file(
+            &root_uri,
+            ".vscode/vhdl_ls.toml",
+            format!(
+                "[libraries]\nstd.files = ['{}/../vhdl_libraries/std/*.vhd']\nlib.files = ['{}']",
+                env!("CARGO_MANIFEST_DIR"),
+                file_uri.to_file_path().unwrap().to_string_lossy()
+            ),
+        );
+        expect_loaded_config_messages(&mock, &config_uri);
+        #[allow(deprecated)]
+        server.initialize_request(InitializeParams {
+            root_uri: Some(root_uri),
+            ..Default::default()
+        });
+        server.initialized_notification();
+
+        let params = TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier::new(file_uri.clone()),
+            position: lsp_types::Position::new(11, 9),
+        };
+        let location = |line: u32, start: u32, end: u32| {
+            json!({
+                "uri": file_uri,
+                "range": {
+                    "start": {"line": line, "character": start},
+                    "end": {"line": line, "character": end},
+                },
+            })
+        };
+        assert_eq!(
+            serde_json::to_value(server.goto_drivers(&params)).unwrap(),
+            json!([{"location": location(13, 2, 3), "name": "s", "level": "local"}])
+        );
+        assert_eq!(
+            serde_json::to_value(server.goto_loads(&params)).unwrap(),
+            json!([
+                {"location": location(14, 41, 42), "name": "s", "level": "local"},
+                {"location": location(6, 7, 8), "name": "a", "level": "instance"},
+            ])
+        );
+    }
+}
@@ -0,0 +1,85 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::vhdl_server::VHDLServer;
+use lsp_types::request::Request;
+use lsp_types::{CodeDescription, Url};
+use serde::{Deserialize, Serialize};
+use vhdl_lang::ErrorCode;
+
+/// Custom request that returns the extended explanation of a diagnostic code.
+pub enum Explain {}
+
+impl Request for Explain {
+    type Params = ExplainParams;
+    type Result = Option<Explanation>;
+    const METHOD: &'static str = "vhdl/explain";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainParams {
+    /// The code of a diagnostic, i.e. `unused`
+    pub code: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Explanation {
+    pub code: String,
+    /// The explanation of the code as Markdown
+    pub explanation: String,
+    /// A link to the online documentation of the code
+    pub href: Url,
+}
+
+/// The link to the online documentation of a diagnostic code
+/// that is sent as part of every diagnostic.
+pub(super) fn code_description(code: ErrorCode) -> Option<CodeDescription> {
+    Some(CodeDescription {
+        href: Url::parse(&code.documentation_url()).ok()?,
+    })
+}
+
+impl VHDLServer {
+    pub fn explain(&self, params: &ExplainParams) -> Option<Explanation> {
+        let code = ErrorCode::try_from(params.code.as_str()).ok()?;
+        Some(Explanation {
+            code: code.to_string(),
+            explanation: code.explanation().to_owned(),
+            href: code_description(code)?.href,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vhdl_server::tests::setup_server;
+
+    #[test]
+    fn explains_known_codes() {
+        let (_mock, server) = setup_server();
+        let explanation = server
+            .explain(&ExplainParams {
+                code: "void_return".to_owned(),
+            })
+            .unwrap();
+        assert_eq!(explanation.code, "void_return");
+        assert_eq!(explanation.explanation, ErrorCode::VoidReturn.explanation());
+        assert_eq!(
+            explanation.href.as_str(),
+            ErrorCode::VoidReturn.documentation_url()
+        );
+
+        assert_eq!(
+            server.explain(&ExplainParams {
+                code: "not_a_code".to_owned()
+            }),
+            None
+        );
+    }
+}
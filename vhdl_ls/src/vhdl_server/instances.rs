@@ -0,0 +1,50 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::vhdl_server::{from_lsp_pos, srcpos_to_location, uri_to_file_name, VHDLServer};
+use lsp_types::request::Request;
+use lsp_types::{Location, TextDocumentPositionParams};
+use serde::{Deserialize, Serialize};
+
+/// Custom request that lists every instance of the entity at the given position
+/// within the elaborated design hierarchy.
+pub enum FindInstances {}
+
+impl Request for FindInstances {
+    type Params = TextDocumentPositionParams;
+    type Result = Vec<Instance>;
+    const METHOD: &'static str = "vhdl/findInstances";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Instance {
+    /// The hierarchical path of the instance, i.e. `top.u_mid.u_leaf`
+    pub path: String,
+    /// The location of the instantiation label
+    /// or `None` if the entity is a top-level entity
+    pub location: Option<Location>,
+}
+
+impl VHDLServer {
+    pub fn find_instances(&mut self, params: &TextDocumentPositionParams) -> Vec<Instance> {
+        let Some(source) = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))
+        else {
+            return Vec::new();
+        };
+
+        self.project
+            .find_instances(&source, from_lsp_pos(params.position))
+            .into_iter()
+            .map(|instance| Instance {
+                path: instance.to_string(),
+                location: instance.pos.as_ref().map(srcpos_to_location),
+            })
+            .collect()
+    }
+}
@@ -1,24 +1,8 @@
-use crate::vhdl_server::{NonProjectFileHandling, VHDLServer};
+use crate::vhdl_server::VHDLServer;
 use lsp_types::*;
-use serde_json::Value;
-use vhdl_lang::{Message, Project};
+use vhdl_lang::Project;
 
 impl VHDLServer {
-    fn apply_initial_options(&mut self, options: &Value) {
-        let Some(non_project_file_handling) = options.get("nonProjectFiles") else {
-            return;
-        };
-        match non_project_file_handling {
-            Value::String(handling) => match NonProjectFileHandling::from_string(handling) {
-                None => self.message(Message::error(format!(
-                    "Illegal setting {handling} for nonProjectFiles setting"
-                ))),
-                Some(handling) => self.settings.non_project_file_handling = handling,
-            },
-            _ => self.message(Message::error("nonProjectFiles must be a string")),
-        }
-    }
-
     /// Register capabilities on the client side:
     /// - watch workspace config file for changes
     fn register_capabilities(&mut self) {
@@ -46,14 +30,16 @@ impl VHDLServer {
     }
 
     pub fn initialize_request(&mut self, init_params: InitializeParams) -> InitializeResult {
-        self.config_file = self.root_uri_config_file(&init_params);
+        // Editor settings take precedence over the configuration files
+        if let Some(options) = &init_params.initialization_options {
+            self.apply_settings(options);
+        }
+        self.workspace_folders = self.initial_workspace_folders(&init_params);
         let config = self.load_config();
-        self.severity_map = *config.severities();
+        self.severity_map = self.severity_map_of(&config);
         self.project = Project::from_config(config, &mut self.message_filter());
+        self.document_symbol_cache.clear();
         self.project.enable_unused_declaration_detection();
-        if let Some(options) = &init_params.initialization_options {
-            self.apply_initial_options(options)
-        }
         self.init_params = Some(init_params);
         let trigger_chars: Vec<String> = r"'.".chars().map(|ch| ch.to_string()).collect();
 
@@ -73,6 +59,8 @@ impl VHDLServer {
             workspace_symbol_provider: Some(OneOf::Left(true)),
             document_symbol_provider: Some(OneOf::Left(true)),
             document_highlight_provider: Some(OneOf::Left(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+            linked_editing_range_provider: Some(LinkedEditingRangeServerCapabilities::Simple(true)),
             completion_provider: Some(CompletionOptions {
                 resolve_provider: Some(true),
                 trigger_characters: Some(trigger_chars),
@@ -81,6 +69,13 @@ impl VHDLServer {
                 }),
                 ..Default::default()
             }),
+            workspace: Some(WorkspaceServerCapabilities {
+                workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                    supported: Some(true),
+                    change_notifications: Some(OneOf::Left(true)),
+                }),
+                file_operations: None,
+            }),
             ..Default::default()
         };
 
@@ -90,14 +85,40 @@ impl VHDLServer {
         }
     }
 
+    /// Undo the registrations of [`Self::register_capabilities`]
+    fn unregister_capabilities(&mut self) {
+        if self.client_supports_did_change_watched_files() {
+            let params = UnregistrationParams {
+                unregisterations: vec![Unregistration {
+                    id: "workspace/didChangeWatchedFiles".to_owned(),
+                    method: "workspace/didChangeWatchedFiles".to_owned(),
+                }],
+            };
+            self.rpc.send_request("client/unregisterCapability", params);
+        }
+    }
+
+    /// Prepare for the `exit` notification.
+    /// The server only answers requests with an error after this.
     pub fn shutdown_server(&mut self) {
+        if self.is_shut_down() {
+            return;
+        }
+        self.unregister_capabilities();
+        log::logger().flush();
         self.init_params = None;
     }
 
-    pub fn exit_notification(&mut self) {
-        match self.init_params {
-            Some(_) => ::std::process::exit(1),
-            None => ::std::process::exit(0),
-        }
+    pub fn is_shut_down(&self) -> bool {
+        self.init_params.is_none()
+    }
+
+    /// Returns the exit code of the server process,
+    /// which signals an error unless the server was shut down before
+    pub fn exit_notification(&mut self) -> i32 {
+        let exit_code = if self.is_shut_down() { 0 } else { 1 };
+        info!("Exiting language server with code {exit_code}");
+        log::logger().flush();
+        exit_code
     }
 }
@@ -0,0 +1,182 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::vhdl_server::{from_lsp_pos, srcpos_to_location, uri_to_file_name, VHDLServer};
+use lsp_types::request::Request;
+use lsp_types::{Location, TextDocumentPositionParams};
+use serde::{Deserialize, Serialize};
+use vhdl_lang::SrcPos;
+
+/// Custom request that finds the references of the item at the given position
+/// together with how it is referenced, optionally only references of the given kinds.
+pub enum FindReferences {}
+
+impl Request for FindReferences {
+    type Params = FindReferencesParams;
+    type Result = Vec<Reference>;
+    const METHOD: &'static str = "vhdl/findReferences";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReferencesParams {
+    #[serde(flatten)]
+    pub text_document_position: TextDocumentPositionParams,
+    /// Only return references of these kinds, all references if absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kinds: Option<Vec<ReferenceKind>>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reference {
+    pub location: Location,
+    pub kind: ReferenceKind,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReferenceKind {
+    Declaration,
+    Write,
+    Instantiation,
+    Read,
+}
+
+impl From<vhdl_lang::ReferenceKind> for ReferenceKind {
+    fn from(kind: vhdl_lang::ReferenceKind) -> Self {
+        match kind {
+            vhdl_lang::ReferenceKind::Declaration => ReferenceKind::Declaration,
+            vhdl_lang::ReferenceKind::Write => ReferenceKind::Write,
+            vhdl_lang::ReferenceKind::Instantiation => ReferenceKind::Instantiation,
+            vhdl_lang::ReferenceKind::Read => ReferenceKind::Read,
+        }
+    }
+}
+
+impl VHDLServer {
+    /// The references of the item at the position together with how it is referenced
+    pub(super) fn references_with_kind(
+        &mut self,
+        params: &TextDocumentPositionParams,
+    ) -> Vec<(SrcPos, ReferenceKind)> {
+        let Some(source) = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))
+        else {
+            return Vec::new();
+        };
+        let Some(ent) = self
+            .project
+            .find_declaration(&source, from_lsp_pos(params.position))
+        else {
+            return Vec::new();
+        };
+        self.project
+            .find_all_references_with_kind(ent)
+            .into_iter()
+            .map(|(pos, kind)| (pos, kind.into()))
+            .collect()
+    }
+
+    pub fn find_references(&mut self, params: &FindReferencesParams) -> Vec<Reference> {
+        self.references_with_kind(&params.text_document_position)
+            .into_iter()
+            .filter(|(_, kind)| {
+                params
+                    .kinds
+                    .as_ref()
+                    .is_none_or(|kinds| kinds.contains(kind))
+            })
+            .map(|(pos, kind)| Reference {
+                location: srcpos_to_location(&pos),
+                kind,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vhdl_server::tests::{
+        expect_loaded_config_messages, setup_server, temp_root_uri, write_file,
+    };
+    use lsp_types::{InitializeParams, TextDocumentIdentifier};
+    use serde_json::json;
+
+    #[test]
+    fn finds_references_of_given_kinds() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(
+            &root_uri,
+            "ent.vhd",
+            "\
+entity ent is
+end entity;
+architecture a of ent is
+  signal sig : bit;
+begin
+  sig <= not sig;
+end architecture;
+",
+        );
+        std::fs::create_dir(root_uri.to_file_path().unwrap().join(".vscode")).unwrap();
+        let config_uri = write_file(
+            &root_uri,
+            ".vscode/vhdl_ls.toml",
+            format!(
+                "[libraries]\nstd.files = ['{}/../vhdl_libraries/std/*.vhd']\nlib.files = ['{}']",
+                env!("CARGO_MANIFEST_DIR"),
+                file_uri.to_file_path().unwrap().to_string_lossy()
+            ),
+        );
+        expect_loaded_config_messages(&mock, &config_uri);
+        #[allow(deprecated)]
+        server.initialize_request(InitializeParams {
+            root_uri: Some(root_uri),
+            ..Default::default()
+        });
+        server.initialized_notification();
+
+        let references = server.find_references(
+            &serde_json::from_value(json!({
+                "textDocument": {"uri": file_uri},
+                "position": {"line": 5, "character": 2},
+                "kinds": ["write", "declaration"],
+            }))
+            .unwrap(),
+        );
+        let range = |line: u32, character: u32| lsp_types::Range {
+            start: lsp_types::Position::new(line, character),
+            end: lsp_types::Position::new(line, character + 3),
+        };
+        assert_eq!(
+            references,
+            vec![
+                Reference {
+                    location: Location::new(file_uri.clone(), range(3, 9)),
+                    kind: ReferenceKind::Declaration,
+                },
+                Reference {
+                    location: Location::new(file_uri.clone(), range(5, 2)),
+                    kind: ReferenceKind::Write,
+                },
+            ]
+        );
+
+        let all = server.find_references(&FindReferencesParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier::new(file_uri),
+                position: lsp_types::Position::new(5, 2),
+            },
+            kinds: None,
+        });
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[2].kind, ReferenceKind::Read);
+    }
+}
@@ -5,44 +5,47 @@ use lsp_types::{
     PrepareRenameResponse, RenameParams, TextDocumentPositionParams, TextEdit, Url, WorkspaceEdit,
 };
 use std::collections::HashMap;
-use vhdl_lang::ast::Designator;
+use vhdl_lang::RenameError;
 
 impl VHDLServer {
+    /// The range of the identifier to rename.
+    /// Fails with the reason if the item at the cursor cannot be renamed,
+    /// i.e. a keyword or an item declared in a third party library.
     pub fn prepare_rename(
         &mut self,
         params: &TextDocumentPositionParams,
-    ) -> Option<PrepareRenameResponse> {
-        let source = self
+    ) -> Result<Option<PrepareRenameResponse>, RenameError> {
+        let Some(source) = self
             .project
-            .get_source(&uri_to_file_name(&params.text_document.uri))?;
+            .get_source(&uri_to_file_name(&params.text_document.uri))
+        else {
+            return Ok(None);
+        };
 
-        let (pos, ent) = self
+        let target = self
             .project
-            .item_at_cursor(&source, from_lsp_pos(params.position))?;
-
-        if let Designator::Identifier(_) = ent.designator() {
-            Some(PrepareRenameResponse::Range(to_lsp_range(pos.range)))
-        } else {
-            // It does not make sense to rename operator symbols and character literals
-            // Also they have different representations that would not be handled consistently
-            // Such as function "+"(arg1, arg2 : integer) but used as foo + bar
-            None
-        }
+            .rename_target(&source, from_lsp_pos(params.position))?;
+        Ok(target.map(|(pos, _)| PrepareRenameResponse::Range(to_lsp_range(pos.range))))
     }
 
-    pub fn rename(&mut self, params: &RenameParams) -> Option<WorkspaceEdit> {
-        let source = self.project.get_source(&uri_to_file_name(
+    pub fn rename(&mut self, params: &RenameParams) -> Result<Option<WorkspaceEdit>, RenameError> {
+        let Some(source) = self.project.get_source(&uri_to_file_name(
             &params.text_document_position.text_document.uri,
-        ))?;
+        )) else {
+            return Ok(None);
+        };
 
-        let ent = self.project.find_declaration(
+        let Some((_, ent)) = self.project.rename_target(
             &source,
             from_lsp_pos(params.text_document_position.position),
-        )?;
+        )?
+        else {
+            return Ok(None);
+        };
 
         let mut changes: HashMap<Url, Vec<TextEdit>> = Default::default();
 
-        for srcpos in self.project.find_all_references(ent) {
+        for srcpos in self.project.rename_positions(ent) {
             let loc = srcpos_to_location(&srcpos);
             changes.entry(loc.uri).or_default().push(TextEdit {
                 range: loc.range,
@@ -50,9 +53,9 @@ impl VHDLServer {
             });
         }
 
-        Some(WorkspaceEdit {
+        Ok(Some(WorkspaceEdit {
             changes: Some(changes),
             ..Default::default()
-        })
+        }))
     }
 }
@@ -0,0 +1,258 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::logging;
+use crate::vhdl_server::{NonProjectFileHandling, VHDLServer};
+use fnv::FnvHashMap;
+use serde_json::Value;
+use vhdl_lang::{Config, ErrorCode, Message, Severity, SeverityMap, VHDLStandard};
+
+/// What has to be updated after applying new settings
+#[derive(Default, Debug, PartialEq, Eq)]
+pub(super) struct SettingsChanges {
+    /// The project must be reloaded with a different configuration
+    pub config: bool,
+    /// Diagnostics must be published again with different severities
+    pub severities: bool,
+    /// Diagnostics were enabled or disabled
+    pub lint: bool,
+}
+
+impl VHDLServer {
+    /// Apply editor settings, passed either as initialization options
+    /// or using `workspace/didChangeConfiguration`.
+    /// Keys that are not given keep their current value.
+    pub(super) fn apply_settings(&mut self, settings: &Value) -> SettingsChanges {
+        let mut changes = SettingsChanges::default();
+        if let Some(logging) = settings.get("logging") {
+            self.apply_logging_options(logging);
+        }
+        if let Some(non_project_file_handling) = settings.get("nonProjectFiles") {
+            self.apply_non_project_file_handling(non_project_file_handling);
+        }
+        if let Some(no_lint) = settings.get("noLint") {
+            match no_lint {
+                Value::Bool(no_lint) => {
+                    changes.lint = self.settings.no_lint != *no_lint;
+                    self.settings.no_lint = *no_lint;
+                }
+                _ => self.message(Message::error("noLint must be a boolean")),
+            }
+        }
+        if let Some(standard) = settings.get("standard") {
+            match parse_standard(standard) {
+                Ok(standard) => {
+                    changes.config = self.settings.standard != standard;
+                    self.settings.standard = standard;
+                }
+                Err(err) => self.message(Message::error(err)),
+            }
+        }
+        if let Some(lint) = settings.get("lint") {
+            match parse_severity_overrides(lint) {
+                Ok(overrides) => {
+                    changes.severities = self.settings.severity_overrides != overrides;
+                    self.settings.severity_overrides = overrides;
+                }
+                Err(err) => self.message(Message::error(err)),
+            }
+        }
+        changes
+    }
+
+    fn apply_non_project_file_handling(&mut self, non_project_file_handling: &Value) {
+        match non_project_file_handling {
+            Value::String(handling) => match NonProjectFileHandling::from_string(handling) {
+                None => self.message(Message::error(format!(
+                    "Illegal setting {handling} for nonProjectFiles setting"
+                ))),
+                Some(handling) => self.settings.non_project_file_handling = handling,
+            },
+            _ => self.message(Message::error("nonProjectFiles must be a string")),
+        }
+    }
+
+    /// Apply the `logging` settings on top of those passed on the command line
+    fn apply_logging_options(&mut self, options: &Value) {
+        let result = self
+            .settings
+            .logging
+            .with_options(options)
+            .and_then(|settings| {
+                logging::reconfigure(&settings)?;
+                self.settings.logging = settings;
+                Ok(())
+            });
+        if let Err(err) = result {
+            self.message(Message::error(err));
+        }
+    }
+
+    /// The severities of the configuration files with the overrides of the editor settings
+    pub(super) fn severity_map_of(&self, config: &Config) -> SeverityMap {
+        let mut severity_map = *config.severities();
+        for (code, severity) in self.settings.severity_overrides.iter() {
+            severity_map[*code] = *severity;
+        }
+        severity_map
+    }
+
+    /// Publish all diagnostics again, i.e. when they are shown with different severities
+    /// or linting has been disabled
+    pub(super) fn republish_diagnostics(&mut self) {
+        if self.settings.no_lint {
+            let uris: Vec<_> = self.diagnostic_cache.drain().map(|(uri, _)| uri).collect();
+            for uri in uris {
+                self.rpc.send_notification(
+                    "textDocument/publishDiagnostics",
+                    lsp_types::PublishDiagnosticsParams {
+                        uri,
+                        diagnostics: Vec::new(),
+                        version: None,
+                    },
+                );
+            }
+        } else {
+            // Forget what the client has seen so that all files are sent again
+            self.diagnostic_cache
+                .values_mut()
+                .for_each(|diagnostics| diagnostics.clear());
+            self.publish_diagnostics();
+        }
+    }
+}
+
+/// A standard revision, where `null` uses the standard of the configuration files
+fn parse_standard(value: &Value) -> Result<Option<VHDLStandard>, String> {
+    match value {
+        Value::Null => Ok(None),
+        Value::String(standard) => VHDLStandard::try_from(standard.as_str())
+            .map(Some)
+            .map_err(|_| format!("Unsupported standard '{standard}'")),
+        _ => Err("standard must be a string".to_owned()),
+    }
+}
+
+/// Severities by error code, in the same format as the `lint` table of `vhdl_ls.toml`
+fn parse_severity_overrides(
+    value: &Value,
+) -> Result<FnvHashMap<ErrorCode, Option<Severity>>, String> {
+    let Value::Object(lint) = value else {
+        return Err("lint must be an object".to_owned());
+    };
+    let mut overrides = FnvHashMap::default();
+    for (name, severity) in lint {
+        let error_code = ErrorCode::try_from(name.as_str())
+            .map_err(|_| format!("'{name}' is not a valid error code"))?;
+        let severity = match severity {
+            Value::String(severity) if severity == "ignore" => None,
+            Value::String(severity) => Some(
+                Severity::try_from(severity.as_str())
+                    .map_err(|_| format!("'{severity}' is not a valid severity level"))?,
+            ),
+            Value::Bool(true) => SeverityMap::default()[error_code],
+            Value::Bool(false) => None,
+            _ => return Err("severity must be a string or boolean".to_owned()),
+        };
+        overrides.insert(error_code, severity);
+    }
+    Ok(overrides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vhdl_server::tests::{initialize_server, setup_server, temp_root_uri};
+    use lsp_types::{DidChangeConfigurationParams, DidOpenTextDocumentParams, TextDocumentItem};
+    use serde_json::json;
+
+    #[test]
+    fn parses_severity_overrides() {
+        let overrides = parse_severity_overrides(&json!({
+            "unused": "error",
+            "unnecessary_work_library": false,
+            "type_mismatch": true,
+            "superfluous_sensitivity": "ignore",
+        }))
+        .unwrap();
+        assert_eq!(overrides[&ErrorCode::Unused], Some(Severity::Error));
+        assert_eq!(overrides[&ErrorCode::UnnecessaryWorkLibrary], None);
+        assert_eq!(overrides[&ErrorCode::TypeMismatch], Some(Severity::Error));
+        assert_eq!(overrides[&ErrorCode::SuperfluousSensitivity], None);
+        assert_eq!(
+            parse_severity_overrides(&json!({"unused": "loud"})),
+            Err("'loud' is not a valid severity level".to_owned())
+        );
+        assert_eq!(
+            parse_standard(&json!("1993")),
+            Ok(Some(VHDLStandard::VHDL1993))
+        );
+        assert_eq!(parse_standard(&json!(null)), Ok(None));
+    }
+
+    fn change_settings(server: &mut VHDLServer, settings: Value) {
+        server.workspace_did_change_configuration(&DidChangeConfigurationParams { settings });
+    }
+
+    #[test]
+    fn changes_severities_and_lint_at_runtime() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        mock.expect_error_contains("Library mapping is unknown");
+        mock.expect_warning_contains("Without library mapping");
+        initialize_server(&mut server, root_uri.clone());
+
+        let file_uri = root_uri.join("ent.vhd").unwrap();
+        mock.expect_warning_contains("is not part of the project");
+        let work_library_diagnostic = |severity: i32| {
+            json!({
+                "uri": file_uri,
+                "diagnostics": [{
+                    "code": "unnecessary_work_library",
+                    "codeDescription": {
+                        "href": "https://docs.rs/vhdl_lang/latest/vhdl_lang/enum.ErrorCode.html#variant.UnnecessaryWorkLibrary"
+                    },
+                    "message": "Library clause not necessary for current working library",
+                    "range": {
+                        "start": {"line": 0, "character": 8},
+                        "end": {"line": 0, "character": 12},
+                    },
+                    "severity": severity,
+                    "source": "vhdl ls",
+                }],
+            })
+        };
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            work_library_diagnostic(2),
+        );
+        server.text_document_did_open_notification(&DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file_uri.clone(),
+                language_id: "vhdl".to_owned(),
+                version: 0,
+                text: "library work;\nentity ent is\nend entity;".to_owned(),
+            },
+        });
+
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            work_library_diagnostic(1),
+        );
+        change_settings(
+            &mut server,
+            json!({"vhdl_ls": {"lint": {"unnecessary_work_library": "error"}}}),
+        );
+
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            json!({"uri": file_uri, "diagnostics": []}),
+        );
+        change_settings(&mut server, json!({"noLint": true}));
+        // Unchanged settings do not publish anything
+        change_settings(&mut server, json!({"noLint": true}));
+    }
+}
@@ -0,0 +1,374 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use crate::logging::LogSettings;
+use crate::vhdl_server::{NonProjectFileHandling, VHDLServer};
+use lsp_types::request::Request;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use vhdl_lang::{
+    BannedConstruct, ErrorCode, FormattingOptions, Severity, SeverityMap, VHDLStandard,
+};
+
+/// Custom request that returns JSON schemas of all settings the server understands,
+/// so that editor extensions can generate settings UIs and validate the configuration.
+pub enum SettingsSchema {}
+
+impl Request for SettingsSchema {
+    type Params = ();
+    type Result = SettingsSchemas;
+    const METHOD: &'static str = "vhdl/settingsSchema";
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsSchemas {
+    /// Schema of the `vhdl_ls.toml` project configuration file
+    pub config_file: Value,
+    /// Schema of the initialization options sent by the client
+    pub initialization_options: Value,
+}
+
+impl VHDLServer {
+    pub fn settings_schema(&self) -> SettingsSchemas {
+        SettingsSchemas {
+            config_file: config_file_schema(),
+            initialization_options: initialization_options_schema(),
+        }
+    }
+}
+
+pub(super) fn config_file_schema() -> Value {
+    let standards: Vec<String> = VHDLStandard::all()
+        .map(|standard| standard.as_ref().to_owned())
+        .collect();
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "vhdl_ls.toml",
+        "type": "object",
+        "properties": {
+            "include": {
+                "description": "Configuration files relative to this file that are merged \
+                                into this configuration, which takes precedence over them",
+                "type": "array",
+                "items": { "type": "string" },
+            },
+            "standard": {
+                "description": "The VHDL standard that all files are analyzed with",
+                "type": "string",
+                "enum": standards,
+                "default": VHDLStandard::default().as_ref(),
+            },
+            "standard_libraries": {
+                "description": "The configuration file of the standard libraries such as `ieee`, \
+                                relative to this file, replacing the libraries shipped with vhdl_ls",
+                "type": "string",
+            },
+            "top": {
+                "description": "The entities at the top of the design hierarchy, \
+                                written as `library.entity` or `library.entity(architecture)`",
+                "type": "array",
+                "items": {
+                    "type": "string",
+                    "pattern": "^\\s*\\w+\\s*\\.\\s*\\w+\\s*(\\(\\s*\\w+\\s*\\))?\\s*$",
+                },
+            },
+            "libraries": {
+                "description": "The libraries of the project by name",
+                "type": "object",
+                "additionalProperties": library_schema(),
+            },
+            "lint": lint_schema(),
+            "banned": banned_schema(),
+            "format": format_schema(),
+            "conditional_analysis": {
+                "description": "The values of user-defined identifiers of conditional analysis \
+                                directives such as `` `if TARGET = \"sim\" then ``",
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+                "propertyNames": { "not": { "pattern": "^(?i:vhdl_version)$" } },
+            },
+        },
+        "required": ["libraries"],
+        "additionalProperties": false,
+    })
+}
+
+fn library_schema() -> Value {
+    let standards: Vec<String> = VHDLStandard::all()
+        .map(|standard| standard.as_ref().to_owned())
+        .collect();
+    json!({
+        "type": "object",
+        "properties": {
+            "files": {
+                "description": "The files of the library as paths or glob patterns \
+                                relative to the configuration file. \
+                                Environment variables such as $HOME are substituted",
+                "type": "array",
+                "items": {
+                    "anyOf": [
+                        { "type": "string" },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "path": { "type": "string" },
+                                "standard": {
+                                    "description": "The VHDL standard that the files are \
+                                                    analyzed with, overriding the library",
+                                    "type": "string",
+                                    "enum": standards,
+                                },
+                            },
+                            "required": ["path"],
+                            "additionalProperties": false,
+                        },
+                    ],
+                },
+            },
+            "standard": {
+                "description": "The VHDL standard that the library is analyzed with, \
+                                overriding the standard of the project",
+                "type": "string",
+                "enum": standards,
+            },
+            "filelists": {
+                "description": "Files relative to the configuration file that list \
+                                one file of the library per line, such as `.f` files",
+                "type": "array",
+                "items": { "type": "string" },
+            },
+            "is_third_party": {
+                "description": "Suppress the diagnostics of this library, such as vendor \
+                                or IP libraries that are only used for navigation",
+                "type": "boolean",
+                "default": false,
+            },
+            "is_synthesizable": {
+                "description": "Warn about constructs that are ignored by synthesis",
+                "type": "boolean",
+                "default": false,
+            },
+            "ignores_initial_values": {
+                "description": "The synthesis target of the library ignores initial values \
+                                of signals, as is common for ASIC technologies",
+                "type": "boolean",
+                "default": false,
+            },
+            "is_testbench": {
+                "description": "Warn about signals that are read before they are initialized",
+                "type": "boolean",
+                "default": false,
+            },
+        },
+        "anyOf": [{ "required": ["files"] }, { "required": ["filelists"] }],
+        "additionalProperties": false,
+    })
+}
+
+/// The style of files formatted by the format command of `vhdl_lang`
+fn format_schema() -> Value {
+    let defaults = FormattingOptions::default();
+    json!({
+        "description": "The style of formatted files",
+        "type": "object",
+        "properties": {
+            "indent_width": {
+                "description": "The number of spaces per indentation level",
+                "type": "integer",
+                "minimum": 0,
+                "default": defaults.indent_width,
+            },
+            "use_tabs": {
+                "description": "Indent using one tab per indentation level instead of spaces",
+                "type": "boolean",
+                "default": defaults.use_tabs,
+            },
+        },
+        "additionalProperties": false,
+    })
+}
+
+fn banned_schema() -> Value {
+    let constructs: Vec<&str> = BannedConstruct::all()
+        .map(|construct| construct.as_str())
+        .collect();
+    json!({
+        "description": "Constructs and packages that are reported when used by the project",
+        "type": "object",
+        "properties": {
+            "constructs": {
+                "description": "Language constructs that are not allowed",
+                "type": "array",
+                "items": { "type": "string", "enum": constructs },
+            },
+            "packages": {
+                "description": "Packages of the form `library.package` that must not be used",
+                "type": "array",
+                "items": { "type": "string", "pattern": "^[^.]+\\.[^.]+$" },
+            },
+        },
+        "additionalProperties": false,
+    })
+}
+
+/// One property per error code that either sets the severity
+/// or disables the diagnostic altogether.
+fn lint_schema() -> Value {
+    let defaults = SeverityMap::default();
+    let severities: Vec<Value> = Severity::all()
+        .map(|severity| Value::from(severity.as_str()))
+        .chain([Value::from("ignore"), Value::from(true), Value::from(false)])
+        .collect();
+    let rules: Map<String, Value> = ErrorCode::all()
+        .map(|code| {
+            let default = match defaults[code] {
+                Some(severity) => Value::from(severity.as_str()),
+                None => Value::from(false),
+            };
+            let rule = json!({
+                "description": summary(code.explanation()),
+                "markdownDescription": code.explanation(),
+                "enum": severities,
+                "default": default,
+            });
+            (code.to_string(), rule)
+        })
+        .collect();
+    json!({
+        "description": "The severity of diagnostics by error code. \
+                        `ignore` or `false` disables a diagnostic, `true` uses the default severity",
+        "type": "object",
+        "properties": rules,
+        "additionalProperties": false,
+    })
+}
+
+fn initialization_options_schema() -> Value {
+    let standards: Vec<Value> = VHDLStandard::all()
+        .map(|standard| Value::from(standard.as_ref()))
+        .chain([Value::Null])
+        .collect();
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "vhdl_ls initialization options",
+        "description": "Also accepted by workspace/didChangeConfiguration, \
+                        either at the top level or in a `vhdl_ls` section",
+        "type": "object",
+        "properties": {
+            "noLint": {
+                "description": "Do not publish any diagnostics",
+                "type": "boolean",
+                "default": false,
+            },
+            "standard": {
+                "description": "Overrides the standard of vhdl_ls.toml",
+                "type": ["string", "null"],
+                "enum": standards,
+            },
+            "lint": lint_schema(),
+            "nonProjectFiles": {
+                "description": "How files that are not part of any library in vhdl_ls.toml are handled",
+                "type": "string",
+                "enum": NonProjectFileHandling::all()
+                    .map(|handling| handling.as_str())
+                    .collect::<Vec<_>>(),
+                "default": NonProjectFileHandling::default().as_str(),
+            },
+            "logging": logging_schema(),
+        },
+    })
+}
+
+/// The `logging` settings, which are also accepted by `workspace/didChangeConfiguration`
+fn logging_schema() -> Value {
+    let defaults = LogSettings {
+        filter: "error".to_owned(),
+        ..LogSettings::default()
+    };
+    json!({
+        "description": "Logging of the language server, to capture logs without restarting the server",
+        "type": "object",
+        "properties": {
+            "level": {
+                "description": "Log levels in the `RUST_LOG` syntax, \
+                                i.e. `warn,vhdl_lang::analysis=debug`. \
+                                Defaults to the `RUST_LOG` environment variable",
+                "type": "string",
+                "default": defaults.filter,
+            },
+            "file": {
+                "description": "Write the log to this file instead of stderr. \
+                                An empty string writes to stderr",
+                "type": "string",
+            },
+            "json": {
+                "description": "Write one JSON object per log message",
+                "type": "boolean",
+                "default": defaults.json,
+            },
+            "maxFileSize": {
+                "description": "The log file is rotated when it grows larger than this number of bytes",
+                "type": "integer",
+                "minimum": 1,
+                "default": defaults.max_file_size,
+            },
+            "maxFiles": {
+                "description": "The number of rotated log files to keep",
+                "type": "integer",
+                "minimum": 0,
+                "default": defaults.max_files,
+            },
+        },
+        "additionalProperties": false,
+    })
+}
+
+/// The first paragraph of an explanation
+fn summary(explanation: &str) -> String {
+    explanation
+        .split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_rules_have_defaults_and_descriptions() {
+        let schema = config_file_schema();
+        let rules = schema["properties"]["lint"]["properties"]
+            .as_object()
+            .unwrap();
+        assert_eq!(rules.len(), ErrorCode::all().count());
+
+        let unused = &rules["unused"];
+        assert_eq!(unused["default"], json!("warning"));
+        assert_eq!(
+            unused["description"],
+            json!(summary(ErrorCode::Unused.explanation()))
+        );
+        assert_eq!(
+            unused["enum"],
+            json!(["hint", "info", "warning", "error", "ignore", true, false])
+        );
+        assert_eq!(
+            schema["properties"]["standard"]["enum"],
+            json!(["1993", "2008", "2019"])
+        );
+    }
+
+    #[test]
+    fn summary_is_first_paragraph() {
+        assert_eq!(
+            summary("A first\nparagraph.\n\n# Example"),
+            "A first paragraph."
+        );
+    }
+}
@@ -0,0 +1,115 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use fnv::FnvHashMap;
+use lsp_types::request::Request;
+use lsp_types::{DocumentSymbolResponse, Url};
+use serde::{Deserialize, Serialize};
+
+/// Number of lookups that could and could not be served from a cache.
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy, Deserialize, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Custom request that returns statistics about the internal state of the server.
+pub enum Stats {}
+
+impl Request for Stats {
+    type Params = ();
+    type Result = ServerStats;
+    const METHOD: &'static str = "vhdl/stats";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStats {
+    pub document_symbol_cache: CacheStats,
+}
+
+/// Caches the document symbols of each file for the buffer version they were computed for,
+/// so that repeated outline requests do not walk the AST again.
+#[derive(Default)]
+pub(super) struct DocumentSymbolCache {
+    /// The buffer version of each file that was last parsed
+    versions: FnvHashMap<Url, i32>,
+    entries: FnvHashMap<Url, (Option<i32>, DocumentSymbolResponse)>,
+    stats: CacheStats,
+}
+
+impl DocumentSymbolCache {
+    /// The file was parsed again, possibly with a new buffer version.
+    pub fn reparsed(&mut self, uri: &Url, version: Option<i32>) {
+        match version {
+            Some(version) => self.versions.insert(uri.clone(), version),
+            None => self.versions.remove(uri),
+        };
+        self.entries.remove(uri);
+    }
+
+    /// All files were parsed again, for example after the configuration changed.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn get(&mut self, uri: &Url) -> Option<DocumentSymbolResponse> {
+        let version = self.versions.get(uri).copied();
+        match self.entries.get(uri) {
+            Some((cached_version, symbols)) if *cached_version == version => {
+                self.stats.hits += 1;
+                Some(symbols.clone())
+            }
+            _ => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, uri: &Url, symbols: DocumentSymbolResponse) {
+        let version = self.versions.get(uri).copied();
+        self.entries.insert(uri.clone(), (version, symbols));
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols() -> DocumentSymbolResponse {
+        DocumentSymbolResponse::Flat(Vec::new())
+    }
+
+    #[test]
+    fn serves_symbols_until_reparsed() {
+        let uri = Url::parse("file:///ent.vhd").unwrap();
+        let mut cache = DocumentSymbolCache::default();
+        cache.reparsed(&uri, Some(1));
+
+        assert_eq!(cache.get(&uri), None);
+        cache.insert(&uri, symbols());
+        assert_eq!(cache.get(&uri), Some(symbols()));
+        assert_eq!(cache.get(&uri), Some(symbols()));
+
+        cache.reparsed(&uri, Some(2));
+        assert_eq!(cache.get(&uri), None);
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 2 });
+    }
+
+    #[test]
+    fn clear_invalidates_all_files() {
+        let uri = Url::parse("file:///ent.vhd").unwrap();
+        let mut cache = DocumentSymbolCache::default();
+        cache.insert(&uri, symbols());
+        cache.clear();
+        assert_eq!(cache.get(&uri), None);
+    }
+}
@@ -1,17 +1,27 @@
+use crate::vhdl_server::config_file::is_config_file;
+use crate::vhdl_server::references::ReferenceKind;
 use crate::vhdl_server::{
     from_lsp_pos, from_lsp_range, srcpos_to_location, to_lsp_range, uri_to_file_name,
     NonProjectFileHandling, VHDLServer,
 };
 use lsp_types::{
     DidChangeTextDocumentParams, DidOpenTextDocumentParams, DocumentHighlight,
-    DocumentHighlightKind, GotoDefinitionResponse, Hover, HoverContents, Location, MarkupContent,
-    MarkupKind, ReferenceParams, TextDocumentItem, TextDocumentPositionParams,
+    DocumentHighlightKind, GotoDefinitionResponse, Hover, HoverContents, LinkedEditingRanges,
+    Location, MarkupContent, MarkupKind, ReferenceParams, TextDocumentItem,
+    TextDocumentPositionParams, VersionedTextDocumentIdentifier,
 };
 use vhdl_lang::{Message, Source};
 
 impl VHDLServer {
     pub fn text_document_did_open_notification(&mut self, params: &DidOpenTextDocumentParams) {
-        let TextDocumentItem { uri, text, .. } = &params.text_document;
+        let TextDocumentItem {
+            uri, text, version, ..
+        } = &params.text_document;
+        if is_config_file(uri) {
+            self.config_document_opened(uri, text);
+            return;
+        }
+        self.document_symbol_cache.reparsed(uri, Some(*version));
         let file_name = uri_to_file_name(uri);
         if let Some(source) = self.project.get_source(&file_name) {
             source.change(None, text);
@@ -21,8 +31,16 @@ impl VHDLServer {
             match self.settings.non_project_file_handling {
                 NonProjectFileHandling::Ignore => {}
                 NonProjectFileHandling::Analyze => {
+                    let hint = match self.owning_workspace_folder(&file_name) {
+                        Some(folder) => format!(
+                            ", add it to {} of workspace folder {}",
+                            folder.config_file.to_string_lossy(),
+                            folder.name
+                        ),
+                        None => String::new(),
+                    };
                     self.message(Message::warning(format!(
-                        "Opening file {} that is not part of the project",
+                        "Opening file {} that is not part of the project{hint}",
                         file_name.to_string_lossy()
                     )));
                     self.project
@@ -34,13 +52,19 @@ impl VHDLServer {
     }
 
     pub fn text_document_did_change_notification(&mut self, params: &DidChangeTextDocumentParams) {
-        let file_name = uri_to_file_name(&params.text_document.uri);
+        let VersionedTextDocumentIdentifier { uri, version } = &params.text_document;
+        if self.config_documents.contains_key(uri) {
+            self.config_document_changed(uri, &params.content_changes);
+            return;
+        }
+        self.document_symbol_cache.reparsed(uri, Some(*version));
+        let file_name = uri_to_file_name(uri);
         if let Some(source) = self.project.get_source(&file_name) {
             for content_change in params.content_changes.iter() {
                 let range = content_change.range.map(from_lsp_range);
-                source.change(range.as_ref(), &content_change.text);
+                self.project
+                    .change_source(&source, range.as_ref(), &content_change.text);
             }
-            self.project.update_source(&source);
             self.publish_diagnostics();
         } else if self.settings.non_project_file_handling != NonProjectFileHandling::Ignore {
             self.message(Message::error(format!(
@@ -58,10 +82,10 @@ impl VHDLServer {
             .project
             .get_source(&uri_to_file_name(&params.text_document.uri))?;
 
-        let ent = self
+        let pos = self
             .project
-            .find_declaration(&source, from_lsp_pos(params.position))?;
-        Some(srcpos_to_location(ent.decl_pos()?))
+            .find_declaration_pos(&source, from_lsp_pos(params.position))?;
+        Some(srcpos_to_location(&pos))
     }
 
     pub fn text_document_definition(
@@ -101,43 +125,42 @@ impl VHDLServer {
         let source = self
             .project
             .get_source(&uri_to_file_name(&params.text_document.uri))?;
-        let ent = self
-            .project
-            .find_declaration(&source, from_lsp_pos(params.position))?;
-
-        let value = self.project.format_declaration(ent)?;
+        let cursor = from_lsp_pos(params.position);
+        let mut physical_literal = self.project.format_physical_literal(&source, cursor);
+        let (mut value, interface_table) = match self.project.find_declaration(&source, cursor) {
+            Some(ent) => (
+                self.project.format_declaration(ent)?,
+                self.project.format_interface_table(ent),
+            ),
+            None => match self.project.format_ambiguous_operator(&source, cursor) {
+                Some(value) => (value, None),
+                None => (physical_literal.take()?, None),
+            },
+        };
+        if let Some(physical_literal) = physical_literal {
+            value = format!("{value}\n{physical_literal}");
+        }
+        let mut value = format!("```vhdl\n{value}\n```");
+        if let Some(interface_table) = interface_table {
+            value = format!("{value}\n\n{interface_table}");
+        }
 
         Some(Hover {
             contents: HoverContents::Markup(MarkupContent {
                 kind: MarkupKind::Markdown,
-                value: format!("```vhdl\n{value}\n```"),
+                value,
             }),
             range: None,
         })
     }
 
     pub fn text_document_references(&mut self, params: &ReferenceParams) -> Vec<Location> {
-        let ent = self
-            .project
-            .get_source(&uri_to_file_name(
-                &params.text_document_position.text_document.uri,
-            ))
-            .and_then(|source| {
-                self.project.find_declaration(
-                    &source,
-                    from_lsp_pos(params.text_document_position.position),
-                )
-            });
-
-        if let Some(ent) = ent {
-            self.project
-                .find_all_references(ent)
-                .iter()
-                .map(srcpos_to_location)
-                .collect()
-        } else {
-            Vec::new()
-        }
+        let include_declaration = params.context.include_declaration;
+        self.references_with_kind(&params.text_document_position)
+            .into_iter()
+            .filter(|(_, kind)| include_declaration || *kind != ReferenceKind::Declaration)
+            .map(|(pos, _)| srcpos_to_location(&pos))
+            .collect()
     }
 
     pub fn document_highlight(
@@ -152,15 +175,39 @@ impl VHDLServer {
             .project
             .find_declaration(&source, from_lsp_pos(params.position))?;
 
+        let writes = self.project.find_all_writes_in_source(&source, ent);
         Some(
             self.project
                 .find_all_references_in_source(&source, ent)
                 .iter()
                 .map(|pos| DocumentHighlight {
                     range: to_lsp_range(pos.range()),
-                    kind: Some(DocumentHighlightKind::TEXT),
+                    kind: Some(if writes.contains(pos) {
+                        DocumentHighlightKind::WRITE
+                    } else {
+                        DocumentHighlightKind::READ
+                    }),
                 })
                 .collect(),
         )
     }
+
+    pub fn linked_editing_range(
+        &mut self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<LinkedEditingRanges> {
+        let source = self
+            .project
+            .get_source(&uri_to_file_name(&params.text_document.uri))?;
+        let names = self
+            .project
+            .find_linked_names(&source, from_lsp_pos(params.position));
+        if names.is_empty() {
+            return None;
+        }
+        Some(LinkedEditingRanges {
+            ranges: names.iter().map(|pos| to_lsp_range(pos.range())).collect(),
+            word_pattern: None,
+        })
+    }
 }
@@ -1,7 +1,8 @@
 use crate::vhdl_server::{srcpos_to_location, to_symbol_kind, uri_to_file_name, VHDLServer};
 use fuzzy_matcher::FuzzyMatcher;
 use lsp_types::{
-    DidChangeWatchedFilesParams, OneOf, WorkspaceSymbol, WorkspaceSymbolParams,
+    DidChangeConfigurationParams, DidChangeWatchedFilesParams, DidChangeWorkspaceFoldersParams,
+    OneOf, WorkspaceFoldersChangeEvent, WorkspaceSymbol, WorkspaceSymbolParams,
     WorkspaceSymbolResponse,
 };
 use std::cmp::Ordering;
@@ -11,23 +12,64 @@ use vhdl_lang::{EntRef, Message};
 
 impl VHDLServer {
     pub fn workspace_did_change_watched_files(&mut self, params: &DidChangeWatchedFilesParams) {
-        if let Some(config_file) = &self.config_file {
-            let config_file_has_changed = params
-                .changes
-                .iter()
-                .any(|change| uri_to_file_name(&change.uri).as_path() == config_file);
-            if config_file_has_changed {
-                self.message(Message::log(
-                    "Configuration file has changed, reloading project...",
-                ));
-                let config = self.load_config();
-                self.severity_map = *config.severities();
+        let config_file_has_changed = params
+            .changes
+            .iter()
+            .any(|change| self.is_workspace_config_file(&uri_to_file_name(&change.uri)));
+        if config_file_has_changed {
+            self.message(Message::log(
+                "Configuration file has changed, reloading project...",
+            ));
+            self.reload_config();
+        }
+    }
 
-                self.project
-                    .update_config(config, &mut self.message_filter());
-                self.publish_diagnostics();
+    pub fn workspace_did_change_workspace_folders(
+        &mut self,
+        params: &DidChangeWorkspaceFoldersParams,
+    ) {
+        let WorkspaceFoldersChangeEvent { added, removed } = &params.event;
+        for folder in removed {
+            let path = uri_to_file_name(&folder.uri);
+            self.workspace_folders
+                .retain(|workspace_folder| workspace_folder.path != path);
+        }
+        for folder in added {
+            if let Some(folder) = self.workspace_folder_config(folder) {
+                self.workspace_folders.push(folder);
             }
         }
+        self.message(Message::log(
+            "Workspace folders have changed, reloading project...",
+        ));
+        self.reload_config();
+    }
+
+    /// Load the configuration of all workspace folders again and update the project
+    fn reload_config(&mut self) {
+        let config = self.load_config();
+        self.severity_map = self.severity_map_of(&config);
+
+        self.project
+            .update_config(config, &mut self.message_filter());
+        self.document_symbol_cache.clear();
+        self.publish_diagnostics();
+    }
+
+    /// Apply settings changed by the client,
+    /// either at the top level or in a `vhdl_ls` section
+    pub fn workspace_did_change_configuration(&mut self, params: &DidChangeConfigurationParams) {
+        let settings = params.settings.get("vhdl_ls").unwrap_or(&params.settings);
+        let changes = self.apply_settings(settings);
+        if changes.config {
+            self.message(Message::log("Settings have changed, reloading project..."));
+            self.reload_config();
+        } else if changes.severities {
+            self.severity_map = self.severity_map_of(self.project.config());
+            self.republish_diagnostics();
+        } else if changes.lint {
+            self.republish_diagnostics();
+        }
     }
 
     pub fn workspace_symbol(